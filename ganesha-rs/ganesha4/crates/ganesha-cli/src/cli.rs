@@ -50,6 +50,10 @@ pub struct Cli {
     #[arg(short = 'C', long)]
     pub directory: Option<String>,
 
+    /// Index past session transcripts locally so /recall can search them
+    #[arg(long, env = "GANESHA_RECALL")]
+    pub recall: bool,
+
     #[command(subcommand)]
     pub command: Option<Commands>,
 }
@@ -108,6 +112,9 @@ pub enum Commands {
         action: VoiceAction,
     },
 
+    /// Check that providers, MCP servers, audio and policy are ready
+    Doctor,
+
     /// Generate shell completions
     Completions {
         /// Shell to generate completions for
@@ -149,6 +156,12 @@ pub enum McpAction {
         /// Server ID from registry
         server_id: String,
     },
+    /// Search the registry of well-known MCP servers
+    Search {
+        /// Name, package or keyword (omit to list all)
+        #[arg(default_value = "")]
+        query: String,
+    },
 }
 
 #[derive(Subcommand, Debug)]
@@ -0,0 +1,147 @@
+//! # Doctor Command
+//!
+//! Environment readiness preflight: providers, vision, MCP, audio, policy.
+
+use async_trait::async_trait;
+use colored::Colorize;
+use ganesha_core::config::CoreConfig;
+use ganesha_core::doctor::{
+    CheckResult, CheckStatus, Doctor, DoctorCheck, McpCheck, PolicyCheck, ProviderCheck,
+};
+use ganesha_mcp::McpConfig;
+use ganesha_providers::ProviderManager;
+use ganesha_voice::VoiceManager;
+use std::sync::Arc;
+
+/// Audio devices and local voice models
+struct AudioCheck;
+
+#[async_trait]
+impl DoctorCheck for AudioCheck {
+    fn group(&self) -> &str {
+        "audio"
+    }
+
+    async fn run(&self) -> Vec<CheckResult> {
+        let group = self.group();
+        let diag = match tokio::task::spawn_blocking(VoiceManager::diagnose).await {
+            Ok(diag) => diag,
+            Err(e) => {
+                return vec![CheckResult::fail(group, "audio", format!("diagnostics crashed: {}", e))];
+            }
+        };
+
+        let devices = |name: &str, found: &Result<Vec<String>, String>| match found {
+            Ok(list) if !list.is_empty() => {
+                CheckResult::pass(group, name, format!("{} device(s), e.g. {}", list.len(), list[0]))
+            }
+            Ok(_) => CheckResult::warn(group, name, "no devices found")
+                .with_remediation("Connect a device or check your audio server (PulseAudio/PipeWire)"),
+            Err(e) => CheckResult::warn(group, name, format!("could not list devices: {}", e))
+                .with_remediation("Voice mode needs a working audio backend (ALSA/CoreAudio/WASAPI)"),
+        };
+
+        let mut results = vec![
+            devices("microphone", &diag.input_devices),
+            devices("speaker", &diag.output_devices),
+        ];
+
+        results.push(if diag.setup.ready_for_local_voice {
+            CheckResult::pass(group, "local voice", "whisper and piper ready")
+        } else {
+            let mut missing = Vec::new();
+            if !diag.setup.whisper_model_installed {
+                missing.push("whisper model");
+            }
+            if !diag.setup.piper_installed {
+                missing.push("piper");
+            }
+            if !diag.setup.piper_voice_installed {
+                missing.push("piper voice");
+            }
+            CheckResult::warn(group, "local voice", format!("missing {}", missing.join(", ")))
+                .with_remediation("Run `ganesha voice setup`")
+        });
+
+        results
+    }
+}
+
+/// Load MCP server configuration from the default locations
+async fn load_mcp_config() -> McpConfig {
+    let mut config = McpConfig::default();
+    for path in McpConfig::default_paths() {
+        if let Ok(loaded) = McpConfig::load(&path).await {
+            config.merge(loaded);
+        }
+    }
+    config
+}
+
+/// Run the doctor command
+pub async fn run() -> anyhow::Result<()> {
+    println!("{}", "Ganesha Doctor".bright_cyan().bold());
+    println!("{}\n", "Checking your environment...".dimmed());
+
+    let provider_manager = Arc::new(ProviderManager::new());
+    let discovery_error = provider_manager.auto_discover().await.err();
+
+    let (core_config, config_error) = match CoreConfig::load() {
+        Ok(config) => (config, None),
+        Err(e) => (CoreConfig::default(), Some(e)),
+    };
+
+    let mut report = Doctor::new()
+        .with_check(ProviderCheck::new(provider_manager))
+        .with_check(McpCheck::new(load_mcp_config().await))
+        .with_check(AudioCheck)
+        .with_check(PolicyCheck::new(core_config))
+        .run()
+        .await;
+
+    if let Some(e) = discovery_error {
+        report.results.push(
+            CheckResult::warn("providers", "discovery", e.to_string())
+                .with_remediation("Check provider settings and environment variables"),
+        );
+    }
+    if let Some(e) = config_error {
+        report.results.push(
+            CheckResult::fail("policy", "config file", e.to_string())
+                .with_remediation("Fix or remove the broken config file; defaults were used for this check"),
+        );
+    }
+
+    for group in report.groups() {
+        println!("{}", group.to_uppercase().bold());
+        for result in report.results.iter().filter(|r| r.group == group) {
+            let icon = match result.status {
+                CheckStatus::Pass => "✓".green(),
+                CheckStatus::Warn => "⚠".yellow(),
+                CheckStatus::Fail => "✗".red(),
+            };
+            println!("  {} {}: {}", icon, result.name.bright_white(), result.detail);
+            if let Some(ref hint) = result.remediation {
+                println!("      {} {}", "→".dimmed(), hint.dimmed());
+            }
+        }
+        println!();
+    }
+
+    let summary = format!(
+        "{} passed, {} warning(s), {} failed",
+        report.count(CheckStatus::Pass),
+        report.count(CheckStatus::Warn),
+        report.count(CheckStatus::Fail)
+    );
+    match report.overall() {
+        CheckStatus::Pass => println!("{} {}", "✓".green(), summary),
+        CheckStatus::Warn => println!("{} {}", "⚠".yellow(), summary),
+        CheckStatus::Fail => println!("{} {}", "✗".red(), summary),
+    }
+
+    if report.has_failures() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
@@ -94,6 +94,33 @@ pub async fn run(action: McpAction) -> anyhow::Result<()> {
                 }
             }
         }
+
+        McpAction::Search { query } => {
+            let registry = ganesha_mcp::ServerRegistry::with_builtin();
+            let results = registry.search(&query);
+            if results.is_empty() {
+                println!("No known MCP servers match '{}'", query);
+                return Ok(());
+            }
+
+            println!("{}", "Known MCP Servers".bright_cyan().bold());
+            println!();
+            for (id, entry) in results {
+                println!(
+                    "  {} {} {}",
+                    id.bright_green(),
+                    format!("({})", entry.name).dimmed(),
+                    format!("[{}]", entry.transport).dimmed()
+                );
+                println!("    {}", entry.description);
+                println!("    {}", entry.install_command.bright_cyan());
+            }
+            println!();
+            println!(
+                "Use {} for setup details",
+                "ganesha mcp install <id>".bright_green()
+            );
+        }
     }
 
     Ok(())
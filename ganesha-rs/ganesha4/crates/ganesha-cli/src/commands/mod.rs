@@ -3,6 +3,7 @@
 //! Subcommand implementations for the Ganesha CLI.
 
 pub mod chat;
+pub mod doctor;
 pub mod init;
 pub mod config;
 pub mod mcp;
@@ -6,17 +6,21 @@
 use crate::cli::{ChatMode, Cli};
 use crate::setup::{self, ProvidersConfig, ProviderType};
 use colored::Colorize;
+use ganesha_core::memory::{Role, SearchableMemory, SemanticSearchConfig, TranscriptIndex, LOCAL_EMBEDDING_MODEL};
 use ganesha_mcp::{McpManager, config::presets as mcp_presets, Tool as McpTool};
 use ganesha_providers::{GenerateOptions, LocalProvider, LocalProviderType, Message, ProviderManager, ProviderPriority};
 use rustyline::error::ReadlineError;
 use rustyline::{Config, Editor, history::FileHistory};
 use std::fs::{self, File, OpenOptions};
 use std::io::{BufRead, IsTerminal, Write};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use regex::Regex;
 use tracing::{debug, info, warn};
 
+/// A session log read back for recall: start time and (role, text) turns
+pub type Transcript = (chrono::DateTime<chrono::Utc>, Vec<(Role, String)>);
+
 /// Session logger that writes text logs to ~/.ganesha/sessions/
 pub struct SessionLogger {
     /// Path to the current session log file
@@ -286,6 +290,57 @@ impl SessionLogger {
         let sessions = self.list_sessions()?;
         Ok(sessions.iter().map(|(_, size, _)| size).sum())
     }
+
+    /// Read the user and assistant turns back out of a session log,
+    /// along with the session start time from its header
+    pub fn read_transcript(path: &Path) -> anyhow::Result<Transcript> {
+        let content = fs::read_to_string(path)?;
+        let header = Regex::new(r"^\[\d{2}:\d{2}:\d{2}\] (USER|GANESHA|COMMAND \[\w+\]):$")?;
+
+        let mut started = None;
+        let mut turns: Vec<(Role, String)> = Vec::new();
+        let mut current: Option<(Role, String)> = None;
+
+        for line in content.lines() {
+            if let Some(date) = line
+                .strip_prefix("=== Ganesha Session: ")
+                .and_then(|rest| rest.strip_suffix(" ==="))
+            {
+                started = chrono::NaiveDateTime::parse_from_str(date, "%Y-%m-%d %H:%M:%S")
+                    .ok()
+                    .and_then(|dt| dt.and_local_timezone(chrono::Local).single())
+                    .map(|dt| dt.with_timezone(&chrono::Utc));
+                continue;
+            }
+            if line.starts_with("=== Session ended") {
+                break;
+            }
+
+            if let Some(caps) = header.captures(line) {
+                turns.extend(current.take().filter(|(_, text)| !text.trim().is_empty()));
+                current = match &caps[1] {
+                    "USER" => Some((Role::User, String::new())),
+                    "GANESHA" => Some((Role::Assistant, String::new())),
+                    // Command output is noise for recall
+                    _ => None,
+                };
+                continue;
+            }
+
+            if let Some((_, ref mut text)) = current {
+                text.push_str(line);
+                text.push('\n');
+            }
+        }
+        turns.extend(current.filter(|(_, text)| !text.trim().is_empty()));
+
+        let turns = turns.into_iter().map(|(role, text)| (role, text.trim().to_string())).collect();
+        let started = match started {
+            Some(date) => date,
+            None => fs::metadata(path)?.modified()?.into(),
+        };
+        Ok((started, turns))
+    }
 }
 
 /// Parse and detect multiple choice options from AI response
@@ -2005,8 +2060,12 @@ pub struct ReplState {
     pub mcp_manager: Arc<McpManager>,
     /// Cached MCP tools (refreshed on connect/disconnect)
     pub mcp_tools: Vec<(String, McpTool)>,
+    /// Applies edits to the MCP config files while running
+    pub mcp_watcher: Option<ganesha_mcp::ConfigWatcher>,
     /// Last model used (from provider response)
     pub last_model: Option<String>,
+    /// Whether past session logs are indexed for /recall
+    pub recall_enabled: bool,
 }
 
 impl ReplState {
@@ -2034,7 +2093,9 @@ impl ReplState {
             is_first_message: true,
             mcp_manager: Arc::new(McpManager::new()),
             mcp_tools: Vec::new(),
+            mcp_watcher: None,
             last_model: None,
+            recall_enabled: cli.recall,
         }
     }
 
@@ -2055,6 +2116,30 @@ impl ReplState {
             self.mcp_manager.add_server_config("puppeteer", preset).await;
         }
 
+        // Surface crashes and reconnects of MCP server processes
+        let mut events = self.mcp_manager.subscribe_events();
+        tokio::spawn(async move {
+            use ganesha_mcp::ConnectionEvent;
+            loop {
+                match events.recv().await {
+                    Ok(event @ (ConnectionEvent::Disconnected { .. }
+                    | ConnectionEvent::Reconnecting { .. })) => {
+                        println!("{} {}", "⟳".yellow(), event.to_string().dimmed());
+                    }
+                    Ok(event @ (ConnectionEvent::ReconnectFailed { .. }
+                    | ConnectionEvent::ConfigReloadFailed { .. })) => {
+                        println!("{} {}", "✗".red(), event.to_string().red());
+                    }
+                    Ok(event @ ConnectionEvent::ConfigReloaded(_)) => {
+                        println!("{} {}", "⟳".bright_blue(), event.to_string().dimmed());
+                    }
+                    Ok(ConnectionEvent::Connected { .. }) => {}
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => {}
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+                }
+            }
+        });
+
         // Auto-connect to configured servers
         if let Err(e) = self.mcp_manager.auto_connect().await {
             // Only warn if there were configured servers that failed
@@ -2067,6 +2152,11 @@ impl ReplState {
         // Refresh tool cache
         self.refresh_mcp_tools().await;
 
+        match self.mcp_manager.watch_default_config() {
+            Ok(watcher) => self.mcp_watcher = Some(watcher),
+            Err(e) => debug!("Not watching MCP config: {}", e),
+        }
+
         Ok(())
     }
 
@@ -2142,7 +2232,7 @@ impl ReplState {
             }
             ChatMode::Help => {
                 "You are Ganesha's help system. Explain Ganesha's features, commands, and capabilities. \
-                Available commands: /help, /mode, /model, /clear, /undo, /diff, /git, /commit, /add, /drop, /ls, /mcp, /session, /provider, /exit"
+                Available commands: /help, /mode, /model, /clear, /undo, /diff, /git, /commit, /add, /drop, /ls, /mcp, /session, /recall, /provider, /exit"
             }
         };
 
@@ -2230,6 +2320,12 @@ const SLASH_COMMANDS: &[SlashCommand] = &[
         description: "Session management",
         handler: cmd_session,
     },
+    SlashCommand {
+        name: "recall",
+        aliases: &[],
+        description: "Search past sessions (requires --recall)",
+        handler: cmd_recall,
+    },
     SlashCommand {
         name: "provider",
         aliases: &["p"],
@@ -3226,6 +3322,79 @@ fn cmd_session(args: &str, state: &mut ReplState) -> anyhow::Result<()> {
     Ok(())
 }
 
+fn cmd_recall(args: &str, state: &mut ReplState) -> anyhow::Result<()> {
+    let query = args.trim();
+
+    if !state.recall_enabled {
+        println!("Transcript recall is off.");
+        println!(
+            "Start Ganesha with {} (or set {}) to index past sessions on this machine.",
+            "--recall".bright_green(),
+            "GANESHA_RECALL=1".bright_green()
+        );
+        return Ok(());
+    }
+    if query.is_empty() {
+        println!("Usage: /recall <query>");
+        return Ok(());
+    }
+
+    let config = SemanticSearchConfig {
+        embeddings_enabled: true,
+        embedding_model: Some(LOCAL_EMBEDDING_MODEL.to_string()),
+        max_results: 5,
+        ..Default::default()
+    };
+    let index_path = state.session_logger.sessions_dir.join("recall_index.json");
+    let mut index = TranscriptIndex::open(&index_path, config)?;
+
+    // Index incrementally: only turns appended since the last /recall are embedded
+    let mut session_ids = Vec::new();
+    let mut added = 0;
+    for (path, _, _) in state.session_logger.list_sessions()? {
+        let Some(id) = path.file_stem().and_then(|s| s.to_str()).map(str::to_string) else {
+            continue;
+        };
+        match SessionLogger::read_transcript(&path) {
+            Ok((started, turns)) => added += index.index_session(&id, started, &turns),
+            Err(e) => debug!("Skipping transcript {:?}: {}", path, e),
+        }
+        session_ids.push(id);
+    }
+    index.retain_sessions(&session_ids);
+    index.save()?;
+    if added > 0 {
+        debug!("Indexed {} new turns for recall", added);
+    }
+
+    let results = index.search(query, 5);
+    if results.is_empty() {
+        println!("No past sessions mention that");
+        return Ok(());
+    }
+
+    println!("\n{}\n", "Recalled from past sessions".bright_cyan().bold());
+    for result in results {
+        let turn = result.item;
+        let who = match turn.role {
+            Role::User => "You",
+            _ => "Ganesha",
+        };
+        let preview: String = turn.content.chars().take(300).collect();
+        let ellipsis = if turn.content.chars().count() > 300 { "..." } else { "" };
+        println!(
+            "  {} {} {}",
+            turn.session_date.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M").to_string().dimmed(),
+            turn.session_id.bright_white(),
+            format!("({:.2})", result.score).dimmed()
+        );
+        println!("    {}: {}{}", who.bright_green(), preview, ellipsis);
+        println!();
+    }
+
+    Ok(())
+}
+
 fn cmd_provider(args: &str, _state: &mut ReplState) -> anyhow::Result<()> {
     use setup::{ProvidersConfig, ProviderType, run_setup_wizard};
 
@@ -9,6 +9,7 @@
 //! - Managing session-scoped and persistent consent rules
 //! - Integrating with risk levels to auto-approve safe operations
 //! - Providing a consistent consent flow across the application
+//! - Keeping an append-only audit trail of every decision
 //!
 //! ## Example
 //!
@@ -26,9 +27,10 @@
 //! }
 //! ```
 
-use crate::risk::{OperationRisk, RiskLevel};
+use crate::risk::{ImpactEstimate, OperationRisk, RiskLevel};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
+use std::io::Write;
 use std::path::PathBuf;
 use std::time::{Duration, Instant};
 use thiserror::Error;
@@ -70,7 +72,7 @@ impl Default for ConsentLevel {
 }
 
 /// Decision from the consent system
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ConsentDecision {
     /// Operation is approved
     Approved,
@@ -225,6 +227,20 @@ impl ConsentRequest {
         self
     }
 
+    /// Apply an impact estimate: escalates the risk (see
+    /// [`ImpactEstimate::escalate`]) and records the touched files
+    pub fn with_impact(mut self, impact: &ImpactEstimate) -> Self {
+        let risk = impact.escalate(self.risk);
+        self.affected_files.extend(impact.files_touched.iter().cloned());
+        if impact.touches_outside_working_dir() {
+            self.context.insert(
+                "outside_working_dir".to_string(),
+                impact.outside_paths.iter().map(|p| p.display().to_string()).collect::<Vec<_>>().join(", "),
+            );
+        }
+        self.with_risk(risk)
+    }
+
     /// Add affected files
     pub fn with_files(mut self, files: impl IntoIterator<Item = impl Into<PathBuf>>) -> Self {
         self.affected_files.extend(files.into_iter().map(Into::into));
@@ -325,6 +341,12 @@ impl ConsentRule {
         self
     }
 
+    /// Time left before this rule expires (`None` if it never does)
+    pub fn remaining_ttl(&self) -> Option<Duration> {
+        self.expires_at
+            .map(|expires_at| (expires_at - chrono::Utc::now()).to_std().unwrap_or(Duration::ZERO))
+    }
+
     /// Check if this rule matches a consent request
     pub fn matches(&self, request: &ConsentRequest) -> bool {
         // Check expiration
@@ -467,6 +489,8 @@ pub enum RememberScope {
     Project,
     /// Globally (all projects)
     Global,
+    /// For a limited time, e.g. "approve shell commands for 10 minutes"
+    Duration(Duration),
 }
 
 impl Default for RememberScope {
@@ -475,6 +499,60 @@ impl Default for RememberScope {
     }
 }
 
+/// Longest description or command kept in an audit entry, in characters
+const AUDIT_TEXT_LIMIT: usize = 500;
+
+/// Shorten `text` to `AUDIT_TEXT_LIMIT` characters, marking the cut
+fn truncate_for_audit(text: &str) -> String {
+    if text.chars().count() <= AUDIT_TEXT_LIMIT {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(AUDIT_TEXT_LIMIT).collect();
+    format!("{}... [truncated, {} chars total]", kept, text.chars().count())
+}
+
+/// One consent decision, as recorded in the audit trail
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConsentAuditEntry {
+    /// The request, with long descriptions and commands truncated
+    pub request: ConsentRequest,
+    /// What was decided
+    pub decision: ConsentDecision,
+    /// The rule that produced the decision, if any
+    pub matched_rule: Option<ConsentRule>,
+    /// The user's answer, for decisions made at a prompt
+    #[serde(default)]
+    pub response: Option<ConsentResponse>,
+    /// When the decision was made
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+impl ConsentAuditEntry {
+    fn new(request: &ConsentRequest, decision: ConsentDecision, matched_rule: Option<ConsentRule>) -> Self {
+        let mut request = request.clone();
+        request.description = truncate_for_audit(&request.description);
+        request.command = request.command.as_deref().map(truncate_for_audit);
+        Self {
+            request,
+            decision,
+            matched_rule,
+            response: None,
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
+    fn for_response(request: &ConsentRequest, response: &ConsentResponse) -> Self {
+        let decision = match response.decision {
+            ConsentLevel::Auto => ConsentDecision::Approved,
+            ConsentLevel::Deny => ConsentDecision::Denied,
+            ConsentLevel::Confirm => ConsentDecision::NeedsPrompt,
+        };
+        let mut entry = Self::new(request, decision, None);
+        entry.response = Some(response.clone());
+        entry
+    }
+}
+
 /// Manages consent requests and rules
 pub struct ConsentManager {
     /// Current risk level setting
@@ -489,6 +567,10 @@ pub struct ConsentManager {
     approved_batches: HashSet<String>,
     /// Timeout for remembering recent consents
     consent_memory_timeout: Duration,
+    /// Every decision made, in order (never cleared)
+    decision_log: Vec<ConsentAuditEntry>,
+    /// JSONL file decisions are appended to, if enabled
+    audit_sink: Option<PathBuf>,
 }
 
 impl ConsentManager {
@@ -501,9 +583,22 @@ impl ConsentManager {
             denied_operations: HashSet::new(),
             approved_batches: HashSet::new(),
             consent_memory_timeout: Duration::from_secs(300), // 5 minutes
+            decision_log: Vec::new(),
+            audit_sink: None,
         }
     }
 
+    /// Also append each decision to a JSONL file as it is made
+    pub fn with_audit_log(mut self, path: impl Into<PathBuf>) -> Self {
+        self.audit_sink = Some(path.into());
+        self
+    }
+
+    /// All decisions made so far, oldest first
+    pub fn decision_log(&self) -> &[ConsentAuditEntry] {
+        &self.decision_log
+    }
+
     /// Set the risk level
     pub fn set_risk_level(&mut self, level: RiskLevel) {
         self.risk_level = level;
@@ -521,6 +616,16 @@ impl ConsentManager {
         self.rules.push(rule);
     }
 
+    /// Rules currently in effect, with the time each has left
+    /// (`None` for rules that do not expire)
+    pub fn active_rules(&self) -> Vec<(&ConsentRule, Option<Duration>)> {
+        self.rules
+            .iter()
+            .map(|rule| (rule, rule.remaining_ttl()))
+            .filter(|(_, ttl)| *ttl != Some(Duration::ZERO))
+            .collect()
+    }
+
     /// Remove expired rules
     pub fn cleanup_expired_rules(&mut self) {
         let now = chrono::Utc::now();
@@ -545,6 +650,29 @@ impl ConsentManager {
 
     /// Request consent for an operation
     pub fn request_consent(&mut self, request: &ConsentRequest) -> Result<ConsentDecision> {
+        let (decision, matched_rule) = self.decide(request);
+        self.audit(ConsentAuditEntry::new(request, decision, matched_rule));
+        Ok(decision)
+    }
+
+    /// Record a decision in the log and the JSONL sink
+    fn audit(&mut self, entry: ConsentAuditEntry) {
+        if let Some(ref path) = self.audit_sink {
+            let written = serde_json::to_string(&entry)
+                .map_err(std::io::Error::from)
+                .and_then(|line| {
+                    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+                    writeln!(file, "{}", line)
+                });
+            if let Err(e) = written {
+                warn!("Failed to write consent audit log {:?}: {}", path, e);
+            }
+        }
+        self.decision_log.push(entry);
+    }
+
+    /// Decide on a request, returning the rule that decided it (if any)
+    fn decide(&mut self, request: &ConsentRequest) -> (ConsentDecision, Option<ConsentRule>) {
         debug!(
             "Consent request: {} (risk: {:?})",
             request.description, request.risk
@@ -552,35 +680,36 @@ impl ConsentManager {
 
         // Clean up stale entries
         self.cleanup_recent_consents();
+        self.cleanup_expired_rules();
 
         // Check if this was recently denied
         if self.denied_operations.contains(&request.id) {
-            return Ok(ConsentDecision::Denied);
+            return (ConsentDecision::Denied, None);
         }
 
         // Check if this batch is already approved
         if let Some(ref batch_id) = request.batch_id {
             if self.approved_batches.contains(batch_id) {
-                return Ok(ConsentDecision::Approved);
+                return (ConsentDecision::Approved, None);
             }
         }
 
         // Check if we recently approved something similar
         let consent_key = self.make_consent_key(request);
         if self.recent_consents.contains_key(&consent_key) {
-            return Ok(ConsentDecision::Approved);
+            return (ConsentDecision::Approved, None);
         }
 
         // Check risk level auto-approval
         if self.auto_approved_by_risk(request.risk) {
             debug!("Auto-approved by risk level");
-            return Ok(ConsentDecision::Approved);
+            return (ConsentDecision::Approved, None);
         }
 
         // Check if operation is even allowed
         if !self.allowed_by_risk(request.risk) {
             warn!("Operation denied by risk level");
-            return Ok(ConsentDecision::Denied);
+            return (ConsentDecision::Denied, None);
         }
 
         // Check consent rules
@@ -588,15 +717,15 @@ impl ConsentManager {
             if rule.matches(request) {
                 debug!("Matched rule: {}", rule.name);
                 match rule.action {
-                    ConsentLevel::Auto => return Ok(ConsentDecision::Approved),
-                    ConsentLevel::Deny => return Ok(ConsentDecision::Denied),
+                    ConsentLevel::Auto => return (ConsentDecision::Approved, Some(rule.clone())),
+                    ConsentLevel::Deny => return (ConsentDecision::Denied, Some(rule.clone())),
                     ConsentLevel::Confirm => {} // Continue to prompt
                 }
             }
         }
 
         // Need user prompt
-        Ok(ConsentDecision::NeedsPrompt)
+        (ConsentDecision::NeedsPrompt, None)
     }
 
     /// Record a consent response
@@ -626,6 +755,15 @@ impl ConsentManager {
                                 rule
                             };
 
+                            self.rules.push(rule);
+                        }
+                        RememberScope::Duration(duration) => {
+                            let rule = ConsentRule::new(format!("Auto-approved for {:?}: {}", duration, request.description))
+                                .for_category(request.category.clone())
+                                .up_to_risk(request.risk)
+                                .with_action(ConsentLevel::Auto)
+                                .expires_in(duration);
+
                             self.rules.push(rule);
                         }
                     }
@@ -643,6 +781,7 @@ impl ConsentManager {
                 // No-op, user will be prompted again
             }
         }
+        self.audit(ConsentAuditEntry::for_response(request, response));
     }
 
     /// Create a key for consent memory
@@ -675,7 +814,7 @@ impl ConsentManager {
         self.approved_batches.remove(batch_id);
     }
 
-    /// Clear all session-scoped consents
+    /// Clear all session-scoped consents (the decision log is kept)
     pub fn clear_session(&mut self) {
         self.recent_consents.clear();
         self.denied_operations.clear();
@@ -887,6 +1026,132 @@ mod tests {
         // Note: This depends on the consent key generation
     }
 
+    #[test]
+    fn test_time_boxed_consent() {
+        let mut manager = ConsentManager::new(RiskLevel::Normal);
+
+        let request = ConsentRequest::shell_command("echo hello");
+        assert_eq!(
+            manager.request_consent(&request).unwrap(),
+            ConsentDecision::NeedsPrompt
+        );
+
+        let response = ConsentResponse::approve(&request.id)
+            .remember(RememberScope::Duration(Duration::from_secs(600)));
+        manager.record_response(&request, &response);
+
+        let later = ConsentRequest::shell_command("echo again");
+        assert_eq!(
+            manager.request_consent(&later).unwrap(),
+            ConsentDecision::Approved
+        );
+
+        let active = manager.active_rules();
+        assert_eq!(active.len(), 1);
+        let ttl = active[0].1.unwrap();
+        assert!(ttl > Duration::from_secs(590) && ttl <= Duration::from_secs(600));
+    }
+
+    #[test]
+    fn test_expired_consent_is_pruned() {
+        let mut manager = ConsentManager::new(RiskLevel::Normal);
+
+        let request = ConsentRequest::shell_command("echo hello");
+        let response = ConsentResponse::approve(&request.id)
+            .remember(RememberScope::Duration(Duration::ZERO));
+        manager.record_response(&request, &response);
+        assert!(manager.active_rules().is_empty());
+
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(
+            manager.request_consent(&request).unwrap(),
+            ConsentDecision::NeedsPrompt
+        );
+        assert!(manager.rules.is_empty());
+    }
+
+    #[test]
+    fn test_impact_escalates_outside_working_dir() {
+        let command = "cp settings.json /etc/app/settings.json";
+        let impact = crate::risk::estimate_command_impact(command, std::path::Path::new("/work"));
+        let request = ConsentRequest::shell_command(command).with_impact(&impact);
+
+        assert_eq!(request.risk, OperationRisk::High);
+        assert_eq!(request.suggested_level, ConsentLevel::Confirm);
+        assert_eq!(request.context.get("outside_working_dir").unwrap(), "/etc/app/settings.json");
+        assert!(request.affected_files.contains(&PathBuf::from("/work/settings.json")));
+    }
+
+    #[test]
+    fn test_decision_log() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("consent.jsonl");
+        let mut manager = ConsentManager::new(RiskLevel::Normal).with_audit_log(&path);
+        manager.add_rule(ConsentRuleBuilder::auto_approve_git());
+
+        let status = ConsentRequest::new("git status", OperationRisk::Low)
+            .with_category(OperationCategory::Git);
+        manager.request_consent(&status).unwrap();
+
+        let long = ConsentRequest::shell_command(format!("echo {}", "x".repeat(2000)));
+        manager.request_consent(&long).unwrap();
+
+        manager.set_risk_level(RiskLevel::Trusted);
+        let write = ConsentRequest::new("Write file", OperationRisk::Medium)
+            .with_category(OperationCategory::FileWrite);
+        manager.request_consent(&write).unwrap();
+        manager.clear_session();
+
+        let log = manager.decision_log();
+        assert_eq!(log.len(), 3);
+        assert_eq!(log[0].decision, ConsentDecision::Approved);
+        assert_eq!(log[0].matched_rule.as_ref().unwrap().name, "Auto-approve git operations");
+        assert_eq!(log[1].decision, ConsentDecision::NeedsPrompt);
+        assert!(log[1].matched_rule.is_none());
+        let command = log[1].request.command.as_deref().unwrap();
+        assert!(command.starts_with("echo xxx"));
+        assert!(command.ends_with("[truncated, 2005 chars total]"));
+        assert_eq!(log[1].request.description, truncate_for_audit(&long.description));
+        assert!(log[2].matched_rule.is_none());
+
+        let lines: Vec<ConsentAuditEntry> = std::fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[2].request.id, write.id);
+    }
+
+    #[test]
+    fn test_responses_are_audited() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let path = temp_dir.path().join("consent.jsonl");
+        let mut manager = ConsentManager::new(RiskLevel::Normal).with_audit_log(&path);
+
+        let push = ConsentRequest::shell_command("git push --force");
+        let rm = ConsentRequest::shell_command("rm -rf build");
+        manager.request_consent(&push).unwrap();
+        manager.record_response(&push, &ConsentResponse::approve(&push.id));
+        manager.record_response(&rm, &ConsentResponse::deny(&rm.id));
+
+        let log = manager.decision_log();
+        assert_eq!(log.len(), 3);
+        assert!(log[0].response.is_none());
+        assert_eq!(log[1].decision, ConsentDecision::Approved);
+        assert_eq!(log[1].response.as_ref().unwrap().request_id, push.id);
+        assert_eq!(log[2].decision, ConsentDecision::Denied);
+        assert_eq!(log[2].request.id, rm.id);
+
+        let lines: Vec<ConsentAuditEntry> = std::fs::read_to_string(&path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[2].decision, ConsentDecision::Denied);
+    }
+
     #[test]
     fn test_clear_session() {
         let mut manager = ConsentManager::new(RiskLevel::Normal);
@@ -0,0 +1,532 @@
+//! # Environment Doctor
+//!
+//! Preflight checks that tell a user what is (and isn't) set up before they
+//! hit a confusing failure mid-task.
+//!
+//! ## Overview
+//!
+//! Each subsystem contributes a [`DoctorCheck`] that returns one or more
+//! [`CheckResult`]s with a pass/warn/fail status and, when something is
+//! wrong, a remediation hint. [`Doctor`] runs the checks and collects them
+//! into a [`DoctorReport`].
+//!
+//! Built-in checks cover provider connectivity, vision capability, MCP
+//! server availability and the access policy. Front-ends add their own
+//! (e.g. audio devices) by implementing [`DoctorCheck`].
+//!
+//! ## Example
+//!
+//! ```ignore
+//! let report = Doctor::new()
+//!     .with_check(ProviderCheck::new(provider_manager))
+//!     .with_check(McpCheck::new(mcp_config))
+//!     .with_check(PolicyCheck::new(CoreConfig::load()?))
+//!     .run()
+//!     .await;
+//!
+//! if report.has_failures() {
+//!     for result in report.failures() {
+//!         println!("{}: {}", result.name, result.detail);
+//!     }
+//! }
+//! ```
+
+use crate::config::CoreConfig;
+use crate::risk::RiskLevel;
+use async_trait::async_trait;
+use ganesha_mcp::config::TransportConfig;
+use ganesha_providers::ProviderManager;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Outcome of a single check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    /// Working as expected
+    Pass,
+    /// Usable, but something is missing or risky
+    Warn,
+    /// Broken; the related feature will not work
+    Fail,
+}
+
+/// Result of one check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckResult {
+    /// Check group (e.g. "providers", "mcp")
+    pub group: String,
+    /// What was checked
+    pub name: String,
+    /// Status
+    pub status: CheckStatus,
+    /// What was found
+    pub detail: String,
+    /// How to fix it, if not passing
+    pub remediation: Option<String>,
+}
+
+impl CheckResult {
+    /// A passing result
+    pub fn pass(group: impl Into<String>, name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            group: group.into(),
+            name: name.into(),
+            status: CheckStatus::Pass,
+            detail: detail.into(),
+            remediation: None,
+        }
+    }
+
+    /// A warning
+    pub fn warn(group: impl Into<String>, name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            status: CheckStatus::Warn,
+            ..Self::pass(group, name, detail)
+        }
+    }
+
+    /// A failure
+    pub fn fail(group: impl Into<String>, name: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            status: CheckStatus::Fail,
+            ..Self::pass(group, name, detail)
+        }
+    }
+
+    /// Attach a remediation hint
+    pub fn with_remediation(mut self, hint: impl Into<String>) -> Self {
+        self.remediation = Some(hint.into());
+        self
+    }
+}
+
+/// A subsystem check run by [`Doctor`]
+#[async_trait]
+pub trait DoctorCheck: Send + Sync {
+    /// Group name shown in the report
+    fn group(&self) -> &str;
+
+    /// Run the check
+    async fn run(&self) -> Vec<CheckResult>;
+}
+
+/// Aggregated results of a doctor run
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DoctorReport {
+    /// All results in the order checks ran
+    pub results: Vec<CheckResult>,
+}
+
+impl DoctorReport {
+    /// Results with the given status
+    pub fn with_status(&self, status: CheckStatus) -> impl Iterator<Item = &CheckResult> {
+        self.results.iter().filter(move |r| r.status == status)
+    }
+
+    /// Failed results
+    pub fn failures(&self) -> impl Iterator<Item = &CheckResult> {
+        self.with_status(CheckStatus::Fail)
+    }
+
+    /// Warnings
+    pub fn warnings(&self) -> impl Iterator<Item = &CheckResult> {
+        self.with_status(CheckStatus::Warn)
+    }
+
+    /// Number of results with the given status
+    pub fn count(&self, status: CheckStatus) -> usize {
+        self.with_status(status).count()
+    }
+
+    /// Whether any check failed
+    pub fn has_failures(&self) -> bool {
+        self.failures().next().is_some()
+    }
+
+    /// Worst status in the report (Pass for an empty report)
+    pub fn overall(&self) -> CheckStatus {
+        self.results.iter().map(|r| r.status).max().unwrap_or(CheckStatus::Pass)
+    }
+
+    /// Group names in the order they first appear
+    pub fn groups(&self) -> Vec<&str> {
+        let mut groups: Vec<&str> = Vec::new();
+        for result in &self.results {
+            if !groups.contains(&result.group.as_str()) {
+                groups.push(&result.group);
+            }
+        }
+        groups
+    }
+}
+
+/// Runs a set of checks and aggregates the results
+#[derive(Default)]
+pub struct Doctor {
+    checks: Vec<Box<dyn DoctorCheck>>,
+}
+
+impl Doctor {
+    /// Create a doctor with no checks
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a check
+    pub fn with_check(mut self, check: impl DoctorCheck + 'static) -> Self {
+        self.checks.push(Box::new(check));
+        self
+    }
+
+    /// Run every check in order
+    pub async fn run(&self) -> DoctorReport {
+        let mut report = DoctorReport::default();
+        for check in &self.checks {
+            let results = check.run().await;
+            if results.is_empty() {
+                report.results.push(CheckResult::warn(
+                    check.group(),
+                    check.group(),
+                    "check reported no results",
+                ));
+            }
+            report.results.extend(results);
+        }
+        report
+    }
+}
+
+// ============================================================================
+// Built-in checks
+// ============================================================================
+
+/// Provider connectivity and vision capability
+pub struct ProviderCheck {
+    manager: Arc<ProviderManager>,
+}
+
+impl ProviderCheck {
+    /// Check the providers registered with `manager`
+    pub fn new(manager: Arc<ProviderManager>) -> Self {
+        Self { manager }
+    }
+}
+
+#[async_trait]
+impl DoctorCheck for ProviderCheck {
+    fn group(&self) -> &str {
+        "providers"
+    }
+
+    async fn run(&self) -> Vec<CheckResult> {
+        let group = self.group();
+        let health = self.manager.health_check().await;
+
+        if health.is_empty() {
+            return vec![CheckResult::fail(group, "providers", "no LLM providers configured")
+                .with_remediation(
+                    "Set ANTHROPIC_API_KEY, OPENAI_API_KEY or OPENROUTER_API_KEY, \
+                     or start a local server (Ollama, LM Studio)",
+                )];
+        }
+
+        let mut results = Vec::new();
+        for provider in &health {
+            let result = if !provider.available {
+                CheckResult::warn(group, &provider.name, "not reachable")
+                    .with_remediation("Check that the server is running and the API key is valid")
+            } else if let Some(ref error) = provider.error {
+                CheckResult::warn(group, &provider.name, format!("reachable, but listing models failed: {}", error))
+            } else {
+                CheckResult::pass(group, &provider.name, format!("reachable, {} model(s)", provider.models.len()))
+            };
+            results.push(result);
+        }
+
+        if !health.iter().any(|p| p.available) {
+            results.push(
+                CheckResult::fail(group, "connectivity", "none of the configured providers is reachable")
+                    .with_remediation("Start a local server or check your network and API keys"),
+            );
+        }
+
+        let vision: Vec<String> = health
+            .iter()
+            .filter(|p| p.available)
+            .flat_map(|p| p.vision_models().map(|m| m.id.clone()))
+            .collect();
+        results.push(if vision.is_empty() {
+            CheckResult::warn(group, "vision", "no vision-capable model available")
+                .with_remediation("Load a vision model (e.g. llava, qwen2-vl) or configure a cloud provider")
+        } else {
+            CheckResult::pass(group, "vision", format!("{} vision model(s), e.g. {}", vision.len(), vision[0]))
+        });
+
+        results
+    }
+}
+
+/// MCP server availability
+pub struct McpCheck {
+    config: ganesha_mcp::McpConfig,
+}
+
+impl McpCheck {
+    /// Check the servers in `config`
+    pub fn new(config: ganesha_mcp::McpConfig) -> Self {
+        Self { config }
+    }
+
+    /// Where Playwright keeps its downloaded browsers
+    fn playwright_browsers_dir() -> Option<PathBuf> {
+        if let Ok(path) = std::env::var("PLAYWRIGHT_BROWSERS_PATH") {
+            return Some(PathBuf::from(path));
+        }
+        if cfg!(target_os = "macos") {
+            dirs::home_dir().map(|h| h.join("Library/Caches/ms-playwright"))
+        } else {
+            dirs::cache_dir().map(|c| c.join("ms-playwright"))
+        }
+    }
+}
+
+#[async_trait]
+impl DoctorCheck for McpCheck {
+    fn group(&self) -> &str {
+        "mcp"
+    }
+
+    async fn run(&self) -> Vec<CheckResult> {
+        let group = self.group();
+        let mut results = Vec::new();
+
+        // Presets (puppeteer, playwright, github, ...) are launched through npx
+        results.push(match find_in_path("npx") {
+            Some(path) => CheckResult::pass(group, "npx", format!("found at {}", path.display())),
+            None => CheckResult::warn(group, "npx", "not found; MCP presets cannot start")
+                .with_remediation("Install Node.js (https://nodejs.org), which provides npx"),
+        });
+
+        let mut servers: Vec<_> = self.config.servers.iter().filter(|(_, s)| s.enabled).collect();
+        servers.sort_by(|a, b| a.0.cmp(b.0));
+
+        let mut uses_playwright = false;
+        for (id, server) in servers {
+            if let TransportConfig::Stdio { command, args, .. } = &server.transport {
+                uses_playwright |= id.contains("playwright") || args.iter().any(|a| a.contains("playwright"));
+
+                if find_in_path(command).is_none() && !Path::new(command).exists() {
+                    results.push(
+                        CheckResult::fail(group, id.as_str(), format!("command `{}` not found", command))
+                            .with_remediation(format!("Install `{}` or fix the server command in mcp.toml", command)),
+                    );
+                    continue;
+                }
+            }
+
+            let missing = server.missing_env_vars();
+            results.push(if missing.is_empty() {
+                CheckResult::pass(group, id.as_str(), "ready")
+            } else {
+                CheckResult::warn(group, id.as_str(), format!("missing environment: {}", missing.join(", ")))
+                    .with_remediation(format!("Export {} before starting Ganesha", missing.join(", ")))
+            });
+        }
+
+        if uses_playwright {
+            let installed = Self::playwright_browsers_dir()
+                .map(|dir| dir.is_dir())
+                .unwrap_or(false);
+            results.push(if installed {
+                CheckResult::pass(group, "playwright browsers", "installed")
+            } else {
+                CheckResult::warn(group, "playwright browsers", "not installed")
+                    .with_remediation("Run `npx playwright install chromium`")
+            });
+        }
+
+        results
+    }
+}
+
+/// Access policy sanity
+pub struct PolicyCheck {
+    config: CoreConfig,
+}
+
+impl PolicyCheck {
+    /// Check the given configuration
+    pub fn new(config: CoreConfig) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait]
+impl DoctorCheck for PolicyCheck {
+    fn group(&self) -> &str {
+        "policy"
+    }
+
+    async fn run(&self) -> Vec<CheckResult> {
+        let group = self.group();
+        let mut results = Vec::new();
+
+        results.push(match self.config.validate() {
+            Ok(()) => CheckResult::pass(group, "configuration", "valid"),
+            Err(e) => CheckResult::fail(group, "configuration", e.to_string())
+                .with_remediation("Fix the value in ~/.config/ganesha/config.toml or .ganesha/config.toml"),
+        });
+
+        let risk = self.config.risk_level;
+        results.push(match risk {
+            RiskLevel::Yolo => CheckResult::warn(group, "risk level", "yolo: every operation is auto-approved")
+                .with_remediation("Use `--risk normal` unless you are in a disposable environment"),
+            _ => CheckResult::pass(group, "risk level", risk.to_string()),
+        });
+
+        if self.config.execution.dry_run {
+            results.push(
+                CheckResult::warn(group, "dry run", "enabled: commands will not actually run")
+                    .with_remediation("Set execution.dry_run = false to execute commands"),
+            );
+        }
+
+        results
+    }
+}
+
+/// Locate an executable on PATH
+pub fn find_in_path(command: &str) -> Option<PathBuf> {
+    let path_var = std::env::var_os("PATH")?;
+    let extensions: Vec<String> = if cfg!(windows) {
+        std::env::var("PATHEXT")
+            .unwrap_or_else(|_| ".EXE;.CMD;.BAT".to_string())
+            .split(';')
+            .map(|e| e.to_lowercase())
+            .chain(std::iter::once(String::new()))
+            .collect()
+    } else {
+        vec![String::new()]
+    };
+
+    std::env::split_paths(&path_var).find_map(|dir| {
+        extensions
+            .iter()
+            .map(|ext| dir.join(format!("{}{}", command, ext)))
+            .find(|candidate| candidate.is_file())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ganesha_providers::{
+        GenerateOptions, LlmProvider, Message, ModelInfo, ModelTier, ProviderPriority, Response,
+    };
+
+    struct StaticCheck {
+        group: &'static str,
+        results: Vec<CheckResult>,
+    }
+
+    #[async_trait]
+    impl DoctorCheck for StaticCheck {
+        fn group(&self) -> &str {
+            self.group
+        }
+
+        async fn run(&self) -> Vec<CheckResult> {
+            self.results.clone()
+        }
+    }
+
+    struct OfflineProvider;
+
+    #[async_trait]
+    impl LlmProvider for OfflineProvider {
+        fn name(&self) -> &str {
+            "offline"
+        }
+
+        async fn is_available(&self) -> bool {
+            false
+        }
+
+        fn default_model(&self) -> &str {
+            "none"
+        }
+
+        fn model_tier(&self, _model: &str) -> ModelTier {
+            ModelTier::Unknown
+        }
+
+        async fn list_models(&self) -> ganesha_providers::Result<Vec<ModelInfo>> {
+            Ok(Vec::new())
+        }
+
+        async fn chat(
+            &self,
+            _messages: &[Message],
+            _options: &GenerateOptions,
+        ) -> ganesha_providers::Result<Response> {
+            Err(ganesha_providers::ProviderError::Unavailable("offline".into()))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_doctor_aggregates_subsystem_results() {
+        let manager = Arc::new(ProviderManager::new());
+        manager.register(OfflineProvider, ProviderPriority::Primary).await;
+
+        let mut config = CoreConfig::default();
+        config.risk_level = RiskLevel::Yolo;
+
+        let report = Doctor::new()
+            .with_check(ProviderCheck::new(manager))
+            .with_check(StaticCheck {
+                group: "audio",
+                results: vec![CheckResult::pass("audio", "microphone", "default input")],
+            })
+            .with_check(PolicyCheck::new(config))
+            .with_check(StaticCheck { group: "clipboard", results: Vec::new() })
+            .run()
+            .await;
+
+        assert_eq!(report.groups(), vec!["providers", "audio", "policy", "clipboard"]);
+        assert!(report.has_failures());
+        assert_eq!(report.overall(), CheckStatus::Fail);
+
+        let failure = report.failures().next().unwrap();
+        assert_eq!(failure.name, "connectivity");
+        assert!(failure.remediation.is_some());
+
+        assert!(report.warnings().any(|r| r.name == "offline"));
+        assert!(report.warnings().any(|r| r.name == "vision"));
+        assert!(report.warnings().any(|r| r.name == "risk level"));
+        assert!(report.warnings().any(|r| r.group == "clipboard"));
+        assert_eq!(report.count(CheckStatus::Pass), 2);
+    }
+
+    #[tokio::test]
+    async fn test_mcp_check_flags_missing_server_command() {
+        let mut config = ganesha_mcp::McpConfig::default();
+        config.servers.insert(
+            "custom".to_string(),
+            ganesha_mcp::ServerConfig::stdio("custom", "ganesha-definitely-not-installed"),
+        );
+
+        let results = McpCheck::new(config).run().await;
+        let custom = results.iter().find(|r| r.name == "custom").unwrap();
+        assert_eq!(custom.status, CheckStatus::Fail);
+        assert!(custom.detail.contains("ganesha-definitely-not-installed"));
+    }
+
+    #[test]
+    fn test_empty_report_is_healthy() {
+        let report = DoctorReport::default();
+        assert_eq!(report.overall(), CheckStatus::Pass);
+        assert!(!report.has_failures());
+    }
+}
@@ -24,10 +24,12 @@
 //! }
 //! ```
 
-use crate::planner::{ActionType, PlanStep, RollbackStrategy, StepId};
+use crate::planner::{ActionType, PlanStep, RollbackStrategy, StepId, TaskPlan};
+use crate::risk::OperationRisk;
 use async_trait::async_trait;
+use futures::stream::{FuturesUnordered, StreamExt};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use std::time::{Duration, Instant};
@@ -140,6 +142,9 @@ pub struct ExecutionResult {
     pub exit_code: Option<i32>,
     /// Rollback point ID (if created)
     pub rollback_point: Option<String>,
+    /// Commands that would have run (dry run only)
+    #[serde(default)]
+    pub planned_commands: Vec<String>,
     /// Additional metadata
     pub metadata: HashMap<String, serde_json::Value>,
 }
@@ -156,6 +161,7 @@ impl ExecutionResult {
             duration,
             exit_code: None,
             rollback_point: None,
+            planned_commands: Vec::new(),
             metadata: HashMap::new(),
         }
     }
@@ -171,6 +177,7 @@ impl ExecutionResult {
             duration,
             exit_code: None,
             rollback_point: None,
+            planned_commands: Vec::new(),
             metadata: HashMap::new(),
         }
     }
@@ -205,6 +212,12 @@ impl ExecutionResult {
         self
     }
 
+    /// Record a command that was not run because of dry run
+    pub fn with_planned_command(mut self, command: impl Into<String>) -> Self {
+        self.planned_commands.push(command.into());
+        self
+    }
+
     /// Add metadata
     pub fn with_metadata(mut self, key: impl Into<String>, value: impl Serialize) -> Self {
         if let Ok(json_value) = serde_json::to_value(value) {
@@ -229,10 +242,34 @@ pub struct ExecutionContext {
     pub rollback_dir: Option<PathBuf>,
     /// Dry run mode (don't actually make changes)
     pub dry_run: bool,
+    /// In dry run, still run shell commands classified as read-only
+    pub dry_run_read_probes: bool,
     /// Maximum file size to read (bytes)
     pub max_file_size: usize,
 }
 
+/// Shell syntax that lets a read-only first word chain into, background,
+/// redirect to or spawn something else
+const PROBE_BLOCKERS: &[&str] = &[";", "&", "|", "\n", "\r", ">", "`", "$(", "<("];
+
+/// `find` actions that delete, write files or run commands
+const FIND_ACTIONS: &[&str] = &["-delete", "-exec", "-ok", "-fprint", "-fls"];
+
+/// Whether a dry run may really run `command`: read-only commands only,
+/// with no chaining, backgrounding, redirects, substitutions or `find`
+/// actions
+fn is_read_probe(command: &str) -> bool {
+    if PROBE_BLOCKERS.iter().any(|b| command.contains(b)) {
+        return false;
+    }
+    let segments = crate::risk::split_commands(command);
+    !segments.is_empty()
+        && segments.iter().all(|words| {
+            !words.iter().any(|w| FIND_ACTIONS.iter().any(|a| w.starts_with(a)))
+                && OperationRisk::classify_command(&words.join(" ")) == OperationRisk::ReadOnly
+        })
+}
+
 impl Default for ExecutionContext {
     fn default() -> Self {
         Self {
@@ -242,6 +279,7 @@ impl Default for ExecutionContext {
             enable_rollback: true,
             rollback_dir: None,
             dry_run: false,
+            dry_run_read_probes: false,
             max_file_size: 10 * 1024 * 1024, // 10 MB
         }
     }
@@ -274,6 +312,17 @@ impl ExecutionContext {
         self
     }
 
+    /// Let read-only shell commands (ls, cat, grep...) run during a dry run
+    pub fn allow_read_probes(mut self) -> Self {
+        self.dry_run_read_probes = true;
+        self
+    }
+
+    /// Whether `command` must be skipped rather than run
+    pub fn skips_command(&self, command: &str) -> bool {
+        self.dry_run && !(self.dry_run_read_probes && is_read_probe(command))
+    }
+
     /// Disable rollback
     pub fn no_rollback(mut self) -> Self {
         self.enable_rollback = false;
@@ -337,6 +386,82 @@ impl StandardExecutor {
         self
     }
 
+    /// Execute a plan, running steps whose dependencies have completed
+    /// concurrently (at most `max_parallelism` at a time).
+    ///
+    /// When a step fails, every step that depends on it, directly or not, is
+    /// skipped and reported as failed with `"skipped": true` metadata. Results
+    /// are returned in plan order. Fails before running anything if the plan
+    /// has a dependency cycle or a dependency on a missing step.
+    pub async fn execute_plan_parallel(
+        &self,
+        plan: &TaskPlan,
+        context: &ExecutionContext,
+        max_parallelism: usize,
+    ) -> Result<Vec<ExecutionResult>> {
+        plan.validate()
+            .map_err(|e| ExecutorError::ExecutionFailed(e.to_string()))?;
+        let order = plan
+            .execution_order()
+            .map_err(|e| ExecutorError::ExecutionFailed(e.to_string()))?;
+
+        let max_parallelism = max_parallelism.max(1);
+        let mut pending: Vec<&PlanStep> = order.iter().filter_map(|id| plan.get_step(*id)).collect();
+        let mut succeeded: HashSet<StepId> = HashSet::new();
+        let mut failed: HashSet<StepId> = HashSet::new();
+        let mut results: HashMap<StepId, ExecutionResult> = HashMap::new();
+        let mut running = FuturesUnordered::new();
+
+        loop {
+            // Pending steps are in dependency order, so skips cascade in one pass
+            let mut i = 0;
+            while i < pending.len() {
+                let step = pending[i];
+                if let Some(dep) = step.dependencies.iter().find(|d| failed.contains(*d)) {
+                    info!("Skipping step {}: dependency {} failed", step.id, dep);
+                    failed.insert(step.id);
+                    results.insert(
+                        step.id,
+                        ExecutionResult::failure(
+                            step.id,
+                            format!("Skipped: dependency {} failed", dep),
+                            Duration::ZERO,
+                        )
+                        .with_metadata("skipped", true),
+                    );
+                    pending.remove(i);
+                } else if running.len() < max_parallelism
+                    && step.dependencies.iter().all(|d| succeeded.contains(d))
+                {
+                    running.push(async move { (step.id, self.execute_step(step, context).await) });
+                    pending.remove(i);
+                } else {
+                    i += 1;
+                }
+            }
+
+            let Some((id, result)) = running.next().await else {
+                break;
+            };
+            let result = result.unwrap_or_else(|e| {
+                error!("Execution error for step {}: {}", id, e);
+                ExecutionResult::failure(id, e.to_string(), Duration::ZERO)
+            });
+            if result.success {
+                succeeded.insert(id);
+            } else {
+                failed.insert(id);
+            }
+            results.insert(id, result);
+        }
+
+        Ok(plan
+            .steps()
+            .iter()
+            .filter_map(|step| results.remove(&step.id))
+            .collect())
+    }
+
     /// Read a file
     async fn read_file(&self, path: &Path, context: &ExecutionContext) -> Result<String> {
         let full_path = if path.is_absolute() {
@@ -457,8 +582,13 @@ impl StandardExecutor {
 
         debug!("Executing command: {} in {:?}", command, context.working_directory);
 
-        if context.dry_run {
-            return Ok((format!("[DRY RUN] Would execute: {}", command), 0));
+        if context.skips_command(command) {
+            let kind = if OperationRisk::classify_command(command) == OperationRisk::ReadOnly {
+                "read"
+            } else {
+                "write"
+            };
+            return Ok((format!("[DRY RUN] Would execute ({}): {}", kind, command), 0));
         }
 
         // Use platform-appropriate shell
@@ -516,7 +646,7 @@ impl StandardExecutor {
         step: &PlanStep,
         context: &ExecutionContext,
     ) -> Result<Option<String>> {
-        if !context.enable_rollback {
+        if !context.enable_rollback || context.dry_run {
             return Ok(None);
         }
 
@@ -700,11 +830,7 @@ impl Executor for StandardExecutor {
             }
 
             ActionType::ShellCommand => {
-                let command = step
-                    .context
-                    .get("command")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("");
+                let command = step.command().unwrap_or_default();
 
                 let timeout = step
                     .context
@@ -733,12 +859,7 @@ impl Executor for StandardExecutor {
             }
 
             ActionType::RunTests => {
-                // Default test command
-                let test_command = step
-                    .context
-                    .get("test_command")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("cargo test");
+                let test_command = step.command().unwrap_or_default();
 
                 match self.execute_command(test_command, context, None).await {
                     Ok((output, exit_code)) => {
@@ -761,12 +882,7 @@ impl Executor for StandardExecutor {
             }
 
             ActionType::Build => {
-                // Default build command
-                let build_command = step
-                    .context
-                    .get("build_command")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("cargo build");
+                let build_command = step.command().unwrap_or_default();
 
                 match self.execute_command(build_command, context, None).await {
                     Ok((output, exit_code)) => {
@@ -789,11 +905,7 @@ impl Executor for StandardExecutor {
             }
 
             ActionType::GitOperation => {
-                let git_command = step
-                    .context
-                    .get("git_command")
-                    .and_then(|v| v.as_str())
-                    .unwrap_or("git status");
+                let git_command = step.command().unwrap_or_default();
 
                 match self.execute_command(git_command, context, None).await {
                     Ok((output, exit_code)) => {
@@ -819,6 +931,13 @@ impl Executor for StandardExecutor {
             }
         };
 
+        // Record commands that a dry run skipped
+        if let Some(command) = step.command() {
+            if context.skips_command(command) {
+                result = result.with_planned_command(command);
+            }
+        }
+
         // Add rollback point if created
         if let Some(ref point) = rollback_point {
             result = result.with_rollback_point(point);
@@ -997,6 +1116,185 @@ mod tests {
         assert!(!file_path.exists()); // File should not be created in dry run
     }
 
+    #[tokio::test]
+    async fn test_dry_run_leaves_filesystem_untouched() {
+        let temp_dir = TempDir::new().unwrap();
+        let existing = temp_dir.path().join("existing.txt");
+        std::fs::write(&existing, "keep me").unwrap();
+
+        let executor = StandardExecutor::new();
+        let context = ExecutionContext::new(temp_dir.path()).dry_run();
+
+        let steps = vec![
+            PlanStep::new("Write", ActionType::WriteFile)
+                .with_target(temp_dir.path().join("new.txt"))
+                .with_context("content", "new"),
+            PlanStep::new("Edit", ActionType::EditFile)
+                .with_target(&existing)
+                .with_context("old_text", "keep")
+                .with_context("new_text", "lose"),
+            PlanStep::new("Make dir", ActionType::CreateDirectory)
+                .with_target(temp_dir.path().join("subdir")),
+            PlanStep::new("Touch", ActionType::ShellCommand)
+                .with_context("command", "touch touched.txt"),
+            PlanStep::new("Delete", ActionType::DeleteFile).with_target(&existing),
+        ];
+
+        let results = execute_plan_steps(&executor, &steps, &context).await;
+        assert_eq!(results.len(), 5);
+        assert!(results.iter().all(|r| r.success));
+
+        let change_types: Vec<_> = results
+            .iter()
+            .flat_map(|r| r.changes.iter().map(|c| c.change_type.clone()))
+            .collect();
+        assert_eq!(
+            change_types,
+            vec![
+                FileChangeType::Created,
+                FileChangeType::Modified,
+                FileChangeType::Created,
+                FileChangeType::Deleted,
+            ]
+        );
+        assert_eq!(results[3].planned_commands, vec!["touch touched.txt"]);
+
+        // Nothing on disk changed
+        let entries: Vec<_> = std::fs::read_dir(temp_dir.path()).unwrap().collect();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(std::fs::read_to_string(&existing).unwrap(), "keep me");
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_read_probes() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("probe.txt"), "").unwrap();
+
+        let executor = StandardExecutor::new();
+        let context = ExecutionContext::new(temp_dir.path())
+            .dry_run()
+            .allow_read_probes();
+
+        let probe = PlanStep::new("List", ActionType::ShellCommand).with_context("command", "ls");
+        let result = executor.execute_step(&probe, &context).await.unwrap();
+        assert!(result.output.unwrap().contains("probe.txt"));
+        assert!(result.planned_commands.is_empty());
+
+        let write = PlanStep::new("Remove", ActionType::ShellCommand)
+            .with_context("command", "rm probe.txt");
+        let result = executor.execute_step(&write, &context).await.unwrap();
+        assert!(result.output.unwrap().contains("Would execute (write)"));
+        assert_eq!(result.planned_commands, vec!["rm probe.txt"]);
+        assert!(temp_dir.path().join("probe.txt").exists());
+
+        // A read-only first word doesn't make the rest of the command safe
+        for command in [
+            "ls && rm probe.txt",
+            "cat probe.txt > copy.txt",
+            "find . -delete",
+            "cat probe.txt & rm probe.txt",
+            "ls\nrm probe.txt",
+            "ls\r\nrm probe.txt",
+            "find . -fprint copy.txt",
+            "find . -fprintf copy.txt %p",
+            "find . -fls copy.txt",
+            "find . -ok rm {} ;",
+            "find . -okdir rm {} +",
+            "find . -execdir rm {} +",
+            "cat <(rm probe.txt)",
+        ] {
+            assert!(context.skips_command(command), "{}", command);
+            let step = PlanStep::new("Probe", ActionType::ShellCommand).with_context("command", command);
+            let result = executor.execute_step(&step, &context).await.unwrap();
+            assert_eq!(result.planned_commands, vec![command]);
+        }
+        assert!(temp_dir.path().join("probe.txt").exists());
+        assert!(!temp_dir.path().join("copy.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn test_parallel_independent_steps() {
+        let temp_dir = TempDir::new().unwrap();
+        let executor = StandardExecutor::new();
+        let context = ExecutionContext::new(temp_dir.path());
+
+        let mut plan = TaskPlan::new("Run tests in three crates");
+        for name in ["a", "b", "c"] {
+            plan.add_step(
+                PlanStep::new(format!("Test {}", name), ActionType::ShellCommand)
+                    .with_context("command", format!("sleep 0.5 && echo {}", name)),
+            );
+        }
+        let first = plan.steps()[0].id;
+        plan.add_step(
+            PlanStep::new("Summarize", ActionType::ShellCommand)
+                .with_context("command", "echo done")
+                .depends_on(first),
+        );
+
+        let start = Instant::now();
+        let results = executor.execute_plan_parallel(&plan, &context, 3).await.unwrap();
+        assert!(start.elapsed() < Duration::from_millis(1400));
+        assert_eq!(results.len(), 4);
+        assert!(results.iter().all(|r| r.success));
+        assert_eq!(results[3].output.as_deref(), Some("done\n"));
+    }
+
+    #[tokio::test]
+    async fn test_parallel_failure_skips_dependents() {
+        let temp_dir = TempDir::new().unwrap();
+        let executor = StandardExecutor::new();
+        let context = ExecutionContext::new(temp_dir.path());
+
+        let failing = PlanStep::new("Fail", ActionType::ShellCommand).with_context("command", "exit 1");
+        let child = PlanStep::new("Child", ActionType::ShellCommand)
+            .with_context("command", "touch child.txt")
+            .depends_on(failing.id);
+        let grandchild = PlanStep::new("Grandchild", ActionType::ShellCommand)
+            .with_context("command", "touch grandchild.txt")
+            .depends_on(child.id);
+        let independent = PlanStep::new("Independent", ActionType::ShellCommand)
+            .with_context("command", "echo ok");
+
+        let mut plan = TaskPlan::new("Partial failure");
+        for step in [failing, child, grandchild, independent] {
+            plan.add_step(step);
+        }
+
+        let results = executor.execute_plan_parallel(&plan, &context, 2).await.unwrap();
+        let skipped: Vec<bool> = results
+            .iter()
+            .map(|r| r.metadata.get("skipped") == Some(&serde_json::Value::Bool(true)))
+            .collect();
+        assert_eq!(skipped, vec![false, true, true, false]);
+        assert!(!results[0].success);
+        assert!(results[3].success);
+        assert!(!temp_dir.path().join("child.txt").exists());
+        assert!(!temp_dir.path().join("grandchild.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn test_parallel_rejects_cycles() {
+        let temp_dir = TempDir::new().unwrap();
+        let executor = StandardExecutor::new();
+        let context = ExecutionContext::new(temp_dir.path());
+
+        let mut first = PlanStep::new("First", ActionType::ShellCommand)
+            .with_context("command", "touch first.txt");
+        let second = PlanStep::new("Second", ActionType::ShellCommand)
+            .with_context("command", "touch second.txt")
+            .depends_on(first.id);
+        first.dependencies.push(second.id);
+
+        let mut plan = TaskPlan::new("Cyclic");
+        plan.add_step(first);
+        plan.add_step(second);
+
+        let err = executor.execute_plan_parallel(&plan, &context, 2).await.unwrap_err();
+        assert!(matches!(err, ExecutorError::ExecutionFailed(_)));
+        assert!(std::fs::read_dir(temp_dir.path()).unwrap().next().is_none());
+    }
+
     #[tokio::test]
     async fn test_rollback() {
         let temp_dir = TempDir::new().unwrap();
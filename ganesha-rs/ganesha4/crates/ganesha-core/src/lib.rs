@@ -15,6 +15,7 @@
 //! - **Sandbox**: Isolated execution environments
 //! - **Memory**: Conversation and knowledge memory
 //! - **MiniMe**: Subagent management for parallel tasks
+//! - **Doctor**: Environment readiness checks
 //!
 //! ## Architecture
 //!
@@ -67,6 +68,7 @@
 // Core modules - all public for complete access
 pub mod config;
 pub mod consent;
+pub mod doctor;
 pub mod executor;
 pub mod memory;
 pub mod minime;
@@ -142,7 +144,7 @@ pub type Result<T> = std::result::Result<T, CoreError>;
 // ============================================================================
 // Risk exports
 // ============================================================================
-pub use risk::{OperationRisk, RiskLevel};
+pub use risk::{OperationRisk, RiskLevel, ImpactEstimate, estimate_impact, estimate_command_impact};
 
 // ============================================================================
 // Memory system exports
@@ -150,7 +152,7 @@ pub use risk::{OperationRisk, RiskLevel};
 pub use memory::{
     // Conversation Memory
     Role, Message as MemoryMessage, Conversation, ConversationMetadata, ConversationSummary,
-    SummarizationRequest,
+    SummarizationRequest, key_entities,
 
     // Knowledge Graph
     Entity, EntityType, Relationship, RelationType,
@@ -164,6 +166,11 @@ pub use memory::{
 
     // Semantic Search
     SearchResult, SearchableMemory, SemanticSearchConfig, Embedding, TextSearch,
+    Embedder, EmbeddingIndex,
+
+    // Transcript Recall
+    LocalEmbedder, TranscriptIndex, TranscriptTurn,
+    LOCAL_EMBEDDING_MODEL, LOCAL_EMBEDDING_DIMENSION,
 
     // Unified Memory System
     MemorySystem, UnifiedSearchResults,
@@ -180,11 +187,12 @@ pub use minime::{
     SubAgent, AgentId, AgentStatus, AgentHandle, AgentResult, TokenUsage,
     // Manager
     SubAgentManager, MAX_CONCURRENT_AGENTS, DEFAULT_TIMEOUT_SECS,
+    BudgetConfig, BUDGET_WARNING_THRESHOLD,
     // Progress
     ProgressUpdate, ProgressType,
     // Task distribution
     WorkItem, OutputType, TaskSplitter, ResultAggregator,
-    LlmTaskSplitter, SimpleAggregator, LlmAggregator,
+    LlmTaskSplitter, RuleBasedSplitter, SimpleAggregator, LlmAggregator,
     // Model selection
     ModelSelector,
     // Agent types
@@ -199,6 +207,7 @@ pub use minime::{
 pub use sandbox::{
     Sandbox, SandboxConfig, SandboxMode, SandboxManager,
     ExecutedCommand, ApplyResult, SandboxError,
+    ManifestEntry, SANDBOX_MANIFEST_FILE,
 };
 
 // ============================================================================
@@ -206,15 +215,16 @@ pub use sandbox::{
 // ============================================================================
 pub use rollback::{
     Checkpoint as RollbackCheckpoint, FileBackup, RollbackManager, RollbackResult,
-    AutoCheckpoint, RollbackError,
+    AutoCheckpoint, CommandRecord, RollbackError,
 };
 
 // ============================================================================
 // Planner exports
 // ============================================================================
 pub use planner::{
-    ActionType, PlanBuilder, PlanStep, Planner, PlannerError, PlanningContext,
-    RollbackStrategy, SimplePlanner, StepId, TaskPlan,
+    ActionType, PlanBuilder, PlanDiff, PlanStep, Planner, PlannerError, PlanningContext,
+    RollbackStrategy, SimplePlanner, StepChange, StepId, TaskPlan,
+    ToolCallingPlanner, EMIT_PLAN_TOOL,
 };
 
 // ============================================================================
@@ -231,13 +241,14 @@ pub use executor::{
 pub use verifier::{
     CheckType, IssueSeverity, StandardVerifier, VerificationContext,
     VerificationIssue, VerificationResult, VerificationStatus, Verifier, VerifierError,
+    parse_cargo_diagnostics,
 };
 
 // ============================================================================
 // Consent exports
 // ============================================================================
 pub use consent::{
-    ConsentDecision, ConsentError, ConsentLevel, ConsentManager, ConsentRequest,
+    ConsentAuditEntry, ConsentDecision, ConsentError, ConsentLevel, ConsentManager, ConsentRequest,
     ConsentResponse, ConsentRule, ConsentRuleBuilder, OperationCategory, RememberScope,
 };
 
@@ -245,10 +256,18 @@ pub use consent::{
 // Session exports
 // ============================================================================
 pub use session::{
-    Checkpoint, Message, MessageRole, Session, SessionError,
+    Checkpoint, Message, MessageRole, ResumePoint, Session, SessionError,
     SessionManager, SessionStatus, SessionSummary, ToolCall,
 };
 
+// ============================================================================
+// Doctor exports
+// ============================================================================
+pub use doctor::{
+    CheckResult, CheckStatus, Doctor, DoctorCheck, DoctorReport,
+    McpCheck, PolicyCheck, ProviderCheck,
+};
+
 // ============================================================================
 // Config exports
 // ============================================================================
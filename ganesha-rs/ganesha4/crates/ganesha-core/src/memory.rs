@@ -6,6 +6,7 @@
 //! - File context memory for tracking code structure
 //! - Session persistence with SQLite backend
 //! - Semantic search preparation for future embeddings
+//! - Opt-in local transcript index for cross-session recall
 //!
 //! ## Architecture
 //!
@@ -30,6 +31,7 @@
 //! └─────────────────────────────────────────────────────────────────┘
 //! ```
 
+use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use rusqlite::{params, Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
@@ -73,6 +75,12 @@ pub enum MemoryError {
 
     #[error("Lock poisoned")]
     LockPoisoned,
+
+    #[error("Embedding dimension mismatch: expected {expected}, got {actual}")]
+    EmbeddingDimension { expected: usize, actual: usize },
+
+    #[error("Embedding error: {0}")]
+    Embedding(String),
 }
 
 pub type Result<T> = std::result::Result<T, MemoryError>;
@@ -300,6 +308,10 @@ pub struct Conversation {
     pub messages: Vec<Message>,
     /// Summaries of older conversation segments
     pub summaries: Vec<ConversationSummary>,
+    /// Rolling summary covering every message summarized so far
+    /// (see `summarize_incremental`)
+    #[serde(default)]
+    pub running_summary: Option<ConversationSummary>,
     /// System prompt (kept separate for easy updates)
     pub system_prompt: Option<String>,
 }
@@ -312,6 +324,7 @@ impl Conversation {
             metadata: ConversationMetadata::default(),
             messages: Vec::new(),
             summaries: Vec::new(),
+            running_summary: None,
             system_prompt: None,
         }
     }
@@ -438,6 +451,87 @@ impl Conversation {
         self.metadata.total_tokens = self.total_tokens();
     }
 
+    /// Prepare a rolling-summary update covering only messages added since
+    /// the last one. The prompt includes the existing summary and the key
+    /// entities it mentions so the merge keeps them.
+    pub fn prepare_incremental_summarization(&self) -> Option<SummarizationRequest> {
+        let summarized: std::collections::HashSet<Uuid> = self
+            .running_summary
+            .as_ref()
+            .map(|s| s.message_ids.iter().copied().collect())
+            .unwrap_or_default();
+        let new_messages: Vec<&Message> = self
+            .messages
+            .iter()
+            .filter(|m| !summarized.contains(&m.id))
+            .collect();
+        if new_messages.is_empty() {
+            return None;
+        }
+
+        let transcript = new_messages
+            .iter()
+            .map(|m| format!("{}: {}", m.role, m.content))
+            .collect::<Vec<_>>()
+            .join("\n\n");
+
+        let content = match self.running_summary {
+            Some(ref previous) => {
+                let entities = key_entities(&previous.summary);
+                let mut prompt = format!("Existing summary:\n{}\n\n", previous.summary);
+                if !entities.is_empty() {
+                    prompt.push_str(&format!(
+                        "Key entities already captured (keep all of them): {}\n\n",
+                        entities.join(", ")
+                    ));
+                }
+                prompt.push_str(&format!(
+                    "New messages:\n{}\n\nUpdate the existing summary to also cover the new messages.",
+                    transcript
+                ));
+                prompt
+            }
+            None => transcript,
+        };
+
+        Some(SummarizationRequest {
+            conversation_id: self.id,
+            content,
+            message_ids: new_messages.iter().map(|m| m.id).collect(),
+            original_tokens: new_messages.iter().map(|m| m.tokens).sum(),
+            keep_recent: 0,
+        })
+    }
+
+    /// Replace the rolling summary with a merged one. Messages are kept.
+    pub fn apply_incremental_summarization(&mut self, summary_text: String, request: SummarizationRequest) {
+        let (mut message_ids, original_tokens) = match self.running_summary.take() {
+            Some(previous) => (previous.message_ids, previous.original_tokens),
+            None => (Vec::new(), 0),
+        };
+        message_ids.extend(request.message_ids);
+
+        self.running_summary = Some(ConversationSummary::new(
+            summary_text,
+            message_ids,
+            original_tokens + request.original_tokens,
+        ));
+    }
+
+    /// Bring the rolling summary up to date, sending only new messages (plus
+    /// the current summary) to `summarize`, which should call the LLM.
+    pub async fn summarize_incremental<F, Fut>(&mut self, summarize: F) -> Result<Option<&ConversationSummary>>
+    where
+        F: FnOnce(String) -> Fut,
+        Fut: std::future::Future<Output = Result<String>>,
+    {
+        if let Some(request) = self.prepare_incremental_summarization() {
+            let summary_text = summarize(request.content.clone()).await?;
+            self.apply_incremental_summarization(summary_text, request);
+        }
+        Ok(self.running_summary.as_ref())
+    }
+
     /// Get recent messages (for display or processing)
     pub fn recent_messages(&self, count: usize) -> &[Message] {
         let start = self.messages.len().saturating_sub(count);
@@ -519,6 +613,27 @@ impl Default for Conversation {
     }
 }
 
+/// Names worth preserving across summaries: paths, file names,
+/// `snake_case` / `CamelCase` identifiers and `module::paths`
+pub fn key_entities(text: &str) -> Vec<String> {
+    let mut seen = std::collections::HashSet::new();
+    text.split(|c: char| c.is_whitespace() || matches!(c, ',' | ';' | '(' | ')' | '[' | ']' | '"' | '\'' | '`'))
+        .map(|word| word.trim_end_matches(['.', ':', '!', '?']))
+        .filter(|word| {
+            let has_extension = word
+                .rsplit_once('.')
+                .map(|(stem, ext)| !stem.is_empty() && !ext.is_empty() && ext.chars().all(|c| c.is_ascii_alphanumeric()))
+                .unwrap_or(false);
+            let camel_case = word.starts_with(|c: char| c.is_uppercase())
+                && word.chars().skip(1).any(|c| c.is_uppercase() || c.is_ascii_digit());
+            word.len() > 2
+                && (word.contains('/') || word.contains("::") || word.contains('_') || has_extension || camel_case)
+        })
+        .filter(|word| seen.insert(word.to_string()))
+        .map(String::from)
+        .collect()
+}
+
 /// Request to summarize part of a conversation
 #[derive(Debug, Clone)]
 pub struct SummarizationRequest {
@@ -1255,6 +1370,107 @@ impl KnowledgeGraph {
         })
     }
 
+    /// Every entity and relationship, oldest first
+    fn snapshot(&self) -> Result<KnowledgeGraphExport> {
+        let mut entities = self.query_entities(&EntityQuery::new())?;
+        entities.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+        let mut relationships = self.query_relationships(&RelationshipQuery::new())?;
+        relationships.sort_by(|a, b| a.created_at.cmp(&b.created_at).then(a.id.cmp(&b.id)));
+        Ok(KnowledgeGraphExport { entities, relationships })
+    }
+
+    /// Export the whole graph as JSON (see [`Self::import_json`])
+    pub fn export_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(&self.snapshot()?)?)
+    }
+
+    /// Replace the graph's contents with a JSON export.
+    ///
+    /// Runs as one transaction: if any record fails to import, the graph
+    /// keeps its previous contents.
+    pub fn import_json(&mut self, json: &str) -> Result<()> {
+        let export: KnowledgeGraphExport = serde_json::from_str(json)?;
+        let entity_cache = self.entity_cache.clone();
+        let relationship_cache = self.relationship_cache.clone();
+
+        self.conn.execute_batch("BEGIN IMMEDIATE")?;
+        let result = self
+            .replace_with(export)
+            .and_then(|()| Ok(self.conn.execute_batch("COMMIT")?));
+        if result.is_err() {
+            let _ = self.conn.execute_batch("ROLLBACK");
+            self.entity_cache = entity_cache;
+            self.relationship_cache = relationship_cache;
+        }
+        result
+    }
+
+    fn replace_with(&mut self, export: KnowledgeGraphExport) -> Result<()> {
+        self.clear()?;
+        for entity in export.entities {
+            self.add_entity(entity)?;
+        }
+        for relationship in export.relationships {
+            self.add_relationship(relationship)?;
+        }
+        Ok(())
+    }
+
+    /// Export the graph as GraphML for tools like Gephi or yEd.
+    ///
+    /// Nodes carry `name` and `entity_type`, edges carry `relation_type`;
+    /// properties are included as JSON strings.
+    pub fn export_graphml(&self) -> Result<String> {
+        let export = self.snapshot()?;
+        let mut out = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+            "  <key id=\"name\" for=\"node\" attr.name=\"name\" attr.type=\"string\"/>\n",
+            "  <key id=\"entity_type\" for=\"node\" attr.name=\"entity_type\" attr.type=\"string\"/>\n",
+            "  <key id=\"node_properties\" for=\"node\" attr.name=\"properties\" attr.type=\"string\"/>\n",
+            "  <key id=\"relation_type\" for=\"edge\" attr.name=\"relation_type\" attr.type=\"string\"/>\n",
+            "  <key id=\"edge_properties\" for=\"edge\" attr.name=\"properties\" attr.type=\"string\"/>\n",
+            "  <graph id=\"knowledge_graph\" edgedefault=\"directed\">\n",
+        ));
+
+        for entity in &export.entities {
+            out.push_str(&format!("    <node id=\"{}\">\n", entity.id));
+            out.push_str(&format!("      <data key=\"name\">{}</data>\n", xml_escape(&entity.name)));
+            out.push_str(&format!(
+                "      <data key=\"entity_type\">{}</data>\n",
+                xml_escape(&entity.entity_type.to_string())
+            ));
+            if !entity.properties.is_empty() {
+                out.push_str(&format!(
+                    "      <data key=\"node_properties\">{}</data>\n",
+                    xml_escape(&serde_json::to_string(&entity.properties)?)
+                ));
+            }
+            out.push_str("    </node>\n");
+        }
+
+        for rel in &export.relationships {
+            out.push_str(&format!(
+                "    <edge id=\"{}\" source=\"{}\" target=\"{}\">\n",
+                rel.id, rel.from_id, rel.to_id
+            ));
+            out.push_str(&format!(
+                "      <data key=\"relation_type\">{}</data>\n",
+                xml_escape(&rel.relation_type.to_string())
+            ));
+            if !rel.properties.is_empty() {
+                out.push_str(&format!(
+                    "      <data key=\"edge_properties\">{}</data>\n",
+                    xml_escape(&serde_json::to_string(&rel.properties)?)
+                ));
+            }
+            out.push_str("    </edge>\n");
+        }
+
+        out.push_str("  </graph>\n</graphml>\n");
+        Ok(out)
+    }
+
     /// Parse entity type from string
     fn parse_entity_type(s: &str) -> EntityType {
         match s {
@@ -1287,6 +1503,21 @@ impl KnowledgeGraph {
     }
 }
 
+/// Serialized form of a whole knowledge graph
+#[derive(Debug, Serialize, Deserialize)]
+struct KnowledgeGraphExport {
+    entities: Vec<Entity>,
+    relationships: Vec<Relationship>,
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
 /// Statistics about the knowledge graph
 #[derive(Debug, Clone)]
 pub struct KnowledgeGraphStats {
@@ -2022,7 +2253,7 @@ pub struct SessionMetadata {
 }
 
 // ============================================================================
-// Semantic Search (preparation for embeddings)
+// Semantic Search
 // ============================================================================
 
 /// A searchable item with relevance scoring
@@ -2068,6 +2299,26 @@ pub trait SearchableMemory {
         filter: &dyn Fn(&Self::Item) -> bool,
         limit: usize,
     ) -> Vec<SearchResult<Self::Item>>;
+
+    /// Search by cosine similarity to an already computed embedding.
+    ///
+    /// Scores are the similarity; equal scores keep insertion order.
+    /// Stores without embeddings return nothing.
+    fn search_semantic(&self, _query_embedding: &Embedding, _top_k: usize) -> Vec<SearchResult<Self::Item>> {
+        Vec::new()
+    }
+}
+
+/// Source of embeddings, e.g. a provider's embeddings endpoint.
+///
+/// [`LocalEmbedder`] implements this without any network access.
+#[async_trait]
+pub trait Embedder: Send + Sync {
+    /// Model name recorded on produced embeddings
+    fn model(&self) -> &str;
+
+    /// Embed a piece of text
+    async fn embed_text(&self, text: &str) -> Result<Embedding>;
 }
 
 /// Configuration for semantic search
@@ -2097,7 +2348,7 @@ impl Default for SemanticSearchConfig {
     }
 }
 
-/// Embedding vector (placeholder for future implementation)
+/// Embedding vector
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Embedding {
     /// Vector values
@@ -2219,6 +2470,132 @@ impl Default for TextSearch {
     }
 }
 
+/// Index of items by embedding, searched by cosine similarity.
+///
+/// Nothing is indexed unless [`SemanticSearchConfig::embeddings_enabled`] is
+/// set. Embeddings must match `embedding_dimension`; results below
+/// `similarity_threshold` are dropped.
+pub struct EmbeddingIndex<T> {
+    config: SemanticSearchConfig,
+    embedder: Option<Arc<dyn Embedder>>,
+    entries: Vec<(T, Embedding)>,
+}
+
+impl<T: Clone> EmbeddingIndex<T> {
+    /// Create an empty index
+    pub fn new(config: SemanticSearchConfig) -> Self {
+        Self {
+            config,
+            embedder: None,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Use `embedder` for [`Self::insert_text`] and [`Self::search_text`]
+    pub fn with_embedder(mut self, embedder: Arc<dyn Embedder>) -> Self {
+        self.embedder = Some(embedder);
+        self
+    }
+
+    /// Whether indexing is enabled
+    pub fn is_enabled(&self) -> bool {
+        self.config.embeddings_enabled
+    }
+
+    /// Add an item with a precomputed embedding.
+    ///
+    /// Returns whether the item was indexed (false when disabled).
+    pub fn insert(&mut self, item: T, embedding: Embedding) -> Result<bool> {
+        if !self.is_enabled() {
+            return Ok(false);
+        }
+        self.check_dimension(&embedding)?;
+        self.entries.push((item, embedding));
+        Ok(true)
+    }
+
+    /// Embed `text` with the configured embedder and add the item
+    pub async fn insert_text(&mut self, item: T, text: &str) -> Result<bool> {
+        if !self.is_enabled() {
+            return Ok(false);
+        }
+        let embedding = self.embedder()?.embed_text(text).await?;
+        self.insert(item, embedding)
+    }
+
+    /// Embed `query` with the configured embedder and search
+    pub async fn search_text(&self, query: &str, top_k: usize) -> Result<Vec<SearchResult<T>>> {
+        if !self.is_enabled() || self.entries.is_empty() {
+            return Ok(Vec::new());
+        }
+        let embedding = self.embedder()?.embed_text(query).await?;
+        self.check_dimension(&embedding)?;
+        Ok(self.search_semantic(&embedding, top_k))
+    }
+
+    /// Number of indexed items
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the index is empty
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Remove all items
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    fn embedder(&self) -> Result<&Arc<dyn Embedder>> {
+        self.embedder
+            .as_ref()
+            .ok_or_else(|| MemoryError::Embedding("no embedder configured".to_string()))
+    }
+
+    fn check_dimension(&self, embedding: &Embedding) -> Result<()> {
+        if embedding.values.len() != self.config.embedding_dimension {
+            return Err(MemoryError::EmbeddingDimension {
+                expected: self.config.embedding_dimension,
+                actual: embedding.values.len(),
+            });
+        }
+        Ok(())
+    }
+}
+
+impl<T: Clone> SearchableMemory for EmbeddingIndex<T> {
+    type Item = T;
+
+    /// Text queries need an async embedder; use [`EmbeddingIndex::search_text`]
+    fn search(&self, _query: &str, _limit: usize) -> Vec<SearchResult<Self::Item>> {
+        Vec::new()
+    }
+
+    fn search_filtered(
+        &self,
+        _query: &str,
+        _filter: &dyn Fn(&Self::Item) -> bool,
+        _limit: usize,
+    ) -> Vec<SearchResult<Self::Item>> {
+        Vec::new()
+    }
+
+    fn search_semantic(&self, query_embedding: &Embedding, top_k: usize) -> Vec<SearchResult<Self::Item>> {
+        let mut results: Vec<SearchResult<T>> = self.entries
+            .iter()
+            .map(|(item, embedding)| SearchResult::new(item.clone(), query_embedding.cosine_similarity(embedding)))
+            .filter(|r| r.score >= self.config.similarity_threshold)
+            .collect();
+
+        // Stable sort: equal scores stay in insertion order
+        results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        results.truncate(top_k.min(self.config.max_results));
+        results
+    }
+}
+
 // Implement SearchableMemory for Conversation
 impl SearchableMemory for Conversation {
     type Item = Message;
@@ -2270,6 +2647,313 @@ impl SearchableMemory for Conversation {
     }
 }
 
+// ============================================================================
+// Transcript Recall
+// ============================================================================
+
+/// Model name recorded on embeddings produced by [`LocalEmbedder`]
+pub const LOCAL_EMBEDDING_MODEL: &str = "local-hash";
+
+/// Dimension of [`LocalEmbedder`] vectors
+pub const LOCAL_EMBEDDING_DIMENSION: usize = 256;
+
+/// Embedder that runs entirely on this machine.
+///
+/// Words and adjacent word pairs are hashed into a fixed-size signed vector
+/// (the "hashing trick"), so no model download or network call is needed.
+/// The hash is FNV-1a, which is stable across builds, so persisted vectors
+/// stay comparable after an upgrade.
+#[derive(Debug, Clone)]
+pub struct LocalEmbedder {
+    dimension: usize,
+}
+
+impl LocalEmbedder {
+    /// Create an embedder producing vectors of the given dimension
+    pub fn new(dimension: usize) -> Self {
+        Self { dimension: dimension.max(1) }
+    }
+
+    /// Embed a piece of text
+    pub fn embed(&self, text: &str) -> Embedding {
+        let mut values = vec![0.0f32; self.dimension];
+        let words = Self::tokenize(text);
+
+        for word in &words {
+            self.add_feature(&mut values, word, 1.0);
+        }
+        for pair in words.windows(2) {
+            self.add_feature(&mut values, &format!("{} {}", pair[0], pair[1]), 0.5);
+        }
+
+        let norm = values.iter().map(|v| v * v).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            values.iter_mut().for_each(|v| *v /= norm);
+        }
+
+        Embedding::new(values, LOCAL_EMBEDDING_MODEL, text)
+    }
+
+    fn add_feature(&self, values: &mut [f32], feature: &str, weight: f32) {
+        let hash = fnv1a(feature.as_bytes());
+        let bucket = (hash % self.dimension as u64) as usize;
+        let sign = if (hash >> 63) & 1 == 0 { 1.0 } else { -1.0 };
+        values[bucket] += sign * weight;
+    }
+
+    /// Lowercased alphanumeric words, ignoring single characters
+    fn tokenize(text: &str) -> Vec<String> {
+        text.split(|c: char| !c.is_alphanumeric())
+            .filter(|w| w.chars().count() > 1)
+            .map(|w| w.to_lowercase())
+            .collect()
+    }
+}
+
+#[async_trait]
+impl Embedder for LocalEmbedder {
+    fn model(&self) -> &str {
+        LOCAL_EMBEDDING_MODEL
+    }
+
+    async fn embed_text(&self, text: &str) -> Result<Embedding> {
+        Ok(self.embed(text))
+    }
+}
+
+impl Default for LocalEmbedder {
+    fn default() -> Self {
+        Self::new(LOCAL_EMBEDDING_DIMENSION)
+    }
+}
+
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+/// A single turn from a saved session transcript
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptTurn {
+    /// Session the turn belongs to
+    pub session_id: String,
+    /// When the session started
+    pub session_date: DateTime<Utc>,
+    /// Position of the turn within the session
+    pub index: usize,
+    /// Who spoke
+    pub role: Role,
+    /// Turn text
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexedTurn {
+    turn: TranscriptTurn,
+    vector: Vec<f32>,
+}
+
+/// Embedding index over past session transcripts for cross-session recall.
+///
+/// Indexing is opt-in: nothing is embedded unless
+/// [`SemanticSearchConfig::embeddings_enabled`] is set. Sessions are indexed
+/// incrementally, so re-indexing a transcript only embeds turns appended
+/// since the last pass.
+#[derive(Debug)]
+pub struct TranscriptIndex {
+    config: SemanticSearchConfig,
+    embedder: LocalEmbedder,
+    path: Option<PathBuf>,
+    turns: Vec<IndexedTurn>,
+    /// Number of turns indexed per session
+    indexed: HashMap<String, usize>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct TranscriptIndexFile {
+    model: String,
+    dimension: usize,
+    turns: Vec<IndexedTurn>,
+}
+
+impl TranscriptIndex {
+    /// Create an empty, in-memory index
+    pub fn new(config: SemanticSearchConfig) -> Self {
+        Self {
+            config,
+            embedder: LocalEmbedder::default(),
+            path: None,
+            turns: Vec::new(),
+            indexed: HashMap::new(),
+        }
+    }
+
+    /// Open an index persisted at `path`, starting empty if it does not exist
+    /// or was written by a different embedder
+    pub fn open(path: impl Into<PathBuf>, config: SemanticSearchConfig) -> Result<Self> {
+        let path = path.into();
+        let mut index = Self::new(config);
+
+        if path.exists() {
+            let content = std::fs::read_to_string(&path)?;
+            let file: TranscriptIndexFile = serde_json::from_str(&content)?;
+            if file.model == LOCAL_EMBEDDING_MODEL && file.dimension == LOCAL_EMBEDDING_DIMENSION {
+                for entry in file.turns {
+                    *index.indexed.entry(entry.turn.session_id.clone()).or_insert(0) += 1;
+                    index.turns.push(entry);
+                }
+            }
+        }
+
+        index.path = Some(path);
+        Ok(index)
+    }
+
+    /// Persist the index (no-op for in-memory indexes)
+    pub fn save(&self) -> Result<()> {
+        let Some(ref path) = self.path else {
+            return Ok(());
+        };
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let file = TranscriptIndexFile {
+            model: LOCAL_EMBEDDING_MODEL.to_string(),
+            dimension: LOCAL_EMBEDDING_DIMENSION,
+            turns: self.turns.clone(),
+        };
+        std::fs::write(path, serde_json::to_string(&file)?)?;
+        Ok(())
+    }
+
+    /// Whether indexing is enabled
+    pub fn is_enabled(&self) -> bool {
+        self.config.embeddings_enabled
+    }
+
+    /// Index a session transcript, embedding only turns not seen before.
+    ///
+    /// Returns the number of newly indexed turns. A transcript shorter than
+    /// what was indexed (e.g. rewritten) is re-indexed from scratch.
+    pub fn index_session(
+        &mut self,
+        session_id: &str,
+        session_date: DateTime<Utc>,
+        turns: &[(Role, String)],
+    ) -> usize {
+        if !self.is_enabled() {
+            return 0;
+        }
+
+        let mut already = self.indexed.get(session_id).copied().unwrap_or(0);
+        if turns.len() < already {
+            self.remove_session(session_id);
+            already = 0;
+        }
+
+        for (index, (role, content)) in turns.iter().enumerate().skip(already) {
+            let vector = self.embedder.embed(content).values;
+            self.turns.push(IndexedTurn {
+                turn: TranscriptTurn {
+                    session_id: session_id.to_string(),
+                    session_date,
+                    index,
+                    role: *role,
+                    content: content.clone(),
+                },
+                vector,
+            });
+        }
+
+        self.indexed.insert(session_id.to_string(), turns.len());
+        turns.len() - already
+    }
+
+    /// Drop a session from the index
+    pub fn remove_session(&mut self, session_id: &str) {
+        self.turns.retain(|t| t.turn.session_id != session_id);
+        self.indexed.remove(session_id);
+    }
+
+    /// Drop every session not in `session_ids` (e.g. deleted transcripts)
+    pub fn retain_sessions(&mut self, session_ids: &[String]) {
+        self.turns.retain(|t| session_ids.contains(&t.turn.session_id));
+        self.indexed.retain(|id, _| session_ids.contains(id));
+    }
+
+    /// Number of indexed turns
+    pub fn len(&self) -> usize {
+        self.turns.len()
+    }
+
+    /// Whether the index is empty
+    pub fn is_empty(&self) -> bool {
+        self.turns.is_empty()
+    }
+
+    /// Number of indexed sessions
+    pub fn session_count(&self) -> usize {
+        self.indexed.len()
+    }
+
+    fn similarity(query: &[f32], vector: &[f32]) -> f64 {
+        if query.len() != vector.len() {
+            return 0.0;
+        }
+        // Both sides are unit vectors, so the dot product is the cosine
+        query.iter().zip(vector).map(|(a, b)| (*a as f64) * (*b as f64)).sum()
+    }
+}
+
+impl SearchableMemory for TranscriptIndex {
+    type Item = TranscriptTurn;
+
+    fn search(&self, query: &str, limit: usize) -> Vec<SearchResult<Self::Item>> {
+        self.search_filtered(query, &|_| true, limit)
+    }
+
+    fn search_filtered(
+        &self,
+        query: &str,
+        filter: &dyn Fn(&Self::Item) -> bool,
+        limit: usize,
+    ) -> Vec<SearchResult<Self::Item>> {
+        let query_vector = self.embedder.embed(query).values;
+
+        let mut results: Vec<SearchResult<TranscriptTurn>> = self.turns
+            .iter()
+            .filter(|t| filter(&t.turn))
+            .map(|t| SearchResult {
+                item: t.turn.clone(),
+                score: Self::similarity(&query_vector, &t.vector),
+                matches: TextSearch::find_matches(query, &t.turn.content),
+            })
+            .filter(|r| r.score > 0.0)
+            .collect();
+
+        results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        results.truncate(limit.min(self.config.max_results));
+        results
+    }
+
+    fn search_semantic(&self, query_embedding: &Embedding, top_k: usize) -> Vec<SearchResult<Self::Item>> {
+        let mut results: Vec<SearchResult<TranscriptTurn>> = self.turns
+            .iter()
+            .map(|t| SearchResult::new(t.turn.clone(), Self::similarity(&query_embedding.values, &t.vector)))
+            .filter(|r| r.score > 0.0)
+            .collect();
+
+        results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+        results.truncate(top_k.min(self.config.max_results));
+        results
+    }
+}
+
 // ============================================================================
 // Unified Memory System
 // ============================================================================
@@ -2534,6 +3218,66 @@ mod tests {
         assert_eq!(connected[0].0.name, "main");
     }
 
+    #[test]
+    fn test_knowledge_graph_export_round_trip() {
+        let mut kg = KnowledgeGraph::in_memory().unwrap();
+        let file_id = kg.add_entity(Entity::new("src/<main>.rs", EntityType::File).with_property("lines", 42)).unwrap();
+        let func_id = kg.add_entity(Entity::new("main", EntityType::Function)).unwrap();
+        let svc_id = kg.add_entity(Entity::new("billing", EntityType::Custom("service".to_string()))).unwrap();
+        kg.add_relationship(Relationship::new(file_id, func_id, RelationType::Contains)).unwrap();
+        kg.add_relationship(Relationship::new(func_id, svc_id, RelationType::Custom("invokes".to_string()))).unwrap();
+
+        let graphml = kg.export_graphml().unwrap();
+        assert!(graphml.contains("attr.name=\"entity_type\""));
+        assert!(graphml.contains("<data key=\"entity_type\">function</data>"));
+        assert!(graphml.contains("<data key=\"name\">src/&lt;main&gt;.rs</data>"));
+        assert!(graphml.contains(&format!("source=\"{}\" target=\"{}\"", file_id, func_id)));
+
+        let json = kg.export_json().unwrap();
+        let mut restored = KnowledgeGraph::in_memory().unwrap();
+        restored.import_json(&json).unwrap();
+
+        let (before, after) = (kg.stats().unwrap(), restored.stats().unwrap());
+        assert_eq!(after.entity_count, before.entity_count);
+        assert_eq!(after.relationship_count, before.relationship_count);
+
+        let svc = restored.get_entity(svc_id).unwrap().unwrap();
+        assert_eq!(svc.entity_type, EntityType::Custom("service".to_string()));
+        assert_eq!(restored.get_entity(file_id).unwrap().unwrap().get_property_as::<i64>("lines"), Some(42));
+        let rels = restored.query_relationships(&RelationshipQuery::new().from_entity(func_id)).unwrap();
+        assert_eq!(rels[0].relation_type, RelationType::Custom("invokes".to_string()));
+    }
+
+    #[test]
+    fn test_knowledge_graph_failed_import_keeps_graph() {
+        let mut kg = KnowledgeGraph::in_memory().unwrap();
+        let file_id = kg.add_entity(Entity::new("main.rs", EntityType::File)).unwrap();
+        let func_id = kg.add_entity(Entity::new("main", EntityType::Function)).unwrap();
+        kg.add_relationship(Relationship::new(file_id, func_id, RelationType::Contains)).unwrap();
+
+        // The second relationship points at an entity the export doesn't have
+        let mut other = KnowledgeGraph::in_memory().unwrap();
+        let a = other.add_entity(Entity::new("a", EntityType::Concept)).unwrap();
+        let b = other.add_entity(Entity::new("b", EntityType::Concept)).unwrap();
+        other.add_relationship(Relationship::new(a, b, RelationType::RelatedTo)).unwrap();
+        let mut export: serde_json::Value = serde_json::from_str(&other.export_json().unwrap()).unwrap();
+        let mut dangling = export["relationships"][0].clone();
+        dangling["id"] = serde_json::json!(Uuid::new_v4());
+        dangling["to_id"] = serde_json::json!(Uuid::new_v4());
+        export["relationships"].as_array_mut().unwrap().push(dangling);
+
+        assert!(matches!(
+            kg.import_json(&export.to_string()),
+            Err(MemoryError::EntityNotFound(_))
+        ));
+
+        let stats = kg.stats().unwrap();
+        assert_eq!((stats.entity_count, stats.relationship_count), (2, 1));
+        assert_eq!((stats.cached_entities, stats.cached_relationships), (2, 1));
+        assert!(kg.get_entity(a).unwrap().is_none());
+        assert_eq!(kg.find_connected(file_id).unwrap()[0].0.name, "main");
+    }
+
     #[test]
     fn test_file_context_memory() {
         let mut fcm = FileContextMemory::new(100);
@@ -2564,6 +3308,96 @@ mod tests {
         assert!((emb1.cosine_similarity(&emb3) - 0.0).abs() < 0.001);
     }
 
+    #[tokio::test]
+    async fn test_semantic_search_ranks_by_similarity() {
+        let config = SemanticSearchConfig {
+            embeddings_enabled: true,
+            embedding_dimension: 3,
+            similarity_threshold: 0.0,
+            ..Default::default()
+        };
+        let mut index = EmbeddingIndex::new(config);
+        let vector = |v: [f32; 3]| Embedding::new(v.to_vec(), "test", "");
+
+        index.insert("first", vector([1.0, 1.0, 0.0])).unwrap();
+        index.insert("exact", vector([1.0, 0.0, 0.0])).unwrap();
+        index.insert("second", vector([2.0, 2.0, 0.0])).unwrap();
+        index.insert("orthogonal", vector([0.0, 0.0, 1.0])).unwrap();
+        assert!(matches!(
+            index.insert("short", Embedding::new(vec![1.0], "test", "")),
+            Err(MemoryError::EmbeddingDimension { expected: 3, actual: 1 })
+        ));
+
+        let results = index.search_semantic(&vector([1.0, 0.0, 0.0]), 4);
+        let order: Vec<&str> = results.iter().map(|r| r.item).collect();
+        // "first" and "second" tie; insertion order decides
+        assert_eq!(order, vec!["exact", "first", "second", "orthogonal"]);
+        assert!((results[0].score - 1.0).abs() < 1e-9);
+        assert!((results[1].score - std::f64::consts::FRAC_1_SQRT_2).abs() < 1e-6);
+        assert_eq!(index.search_semantic(&vector([1.0, 0.0, 0.0]), 1).len(), 1);
+
+        // Pluggable embedder
+        let config = SemanticSearchConfig {
+            embeddings_enabled: true,
+            embedding_dimension: LOCAL_EMBEDDING_DIMENSION,
+            similarity_threshold: 0.1,
+            ..Default::default()
+        };
+        let mut index = EmbeddingIndex::new(config).with_embedder(Arc::new(LocalEmbedder::default()));
+        index.insert_text(1, "configure the postgres connection pool").await.unwrap();
+        index.insert_text(2, "render the settings page in react").await.unwrap();
+        let results = index.search_text("postgres connection pool size", 5).await.unwrap();
+        assert_eq!(results[0].item, 1);
+
+        let mut disabled = EmbeddingIndex::new(SemanticSearchConfig::default());
+        assert!(!disabled.insert("x", vector([1.0, 0.0, 0.0])).unwrap());
+        assert!(disabled.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_incremental_summary_matches_one_shot_entities() {
+        // Stand-in summarizer: keeps the key entities it is shown
+        async fn summarize(content: String) -> Result<String> {
+            Ok(key_entities(&content).join(", "))
+        }
+
+        let messages: Vec<String> = (0..100)
+            .map(|i| format!("Message {}: updated src/module_{}.rs and the Handler{} type", i, i % 10, i % 7))
+            .collect();
+
+        let mut incremental = Conversation::new();
+        let mut fed = 0;
+        for chunk in messages.chunks(25) {
+            for text in chunk {
+                incremental.add_user_message(text.as_str());
+            }
+            let request = incremental.prepare_incremental_summarization().unwrap();
+            fed += request.message_ids.len();
+            let summary = summarize(request.content.clone()).await.unwrap();
+            incremental.apply_incremental_summarization(summary, request);
+        }
+        assert_eq!(fed, 100);
+        assert!(incremental.summarize_incremental(summarize).await.unwrap().is_some());
+        assert!(incremental.prepare_incremental_summarization().is_none());
+
+        let mut one_shot = Conversation::new();
+        for text in &messages {
+            one_shot.add_user_message(text.as_str());
+        }
+        let request = one_shot.prepare_summarization(0).unwrap();
+        let one_shot_summary = summarize(request.content).await.unwrap();
+
+        let running = incremental.running_summary.as_ref().unwrap();
+        assert_eq!(running.message_ids.len(), 100);
+        let mut rolling: Vec<String> = key_entities(&running.summary);
+        let mut full: Vec<String> = key_entities(&one_shot_summary);
+        rolling.sort();
+        full.sort();
+        assert_eq!(rolling, full);
+        assert!(full.contains(&"src/module_3.rs".to_string()));
+        assert!(full.contains(&"Handler6".to_string()));
+    }
+
     #[test]
     fn test_conversation_to_markdown() {
         let mut conv = Conversation::new();
@@ -2591,4 +3425,76 @@ mod tests {
         let named = kg.query_entities(&EntityQuery::new().with_name_pattern("file1")).unwrap();
         assert_eq!(named.len(), 1);
     }
+
+    fn recall_config() -> SemanticSearchConfig {
+        SemanticSearchConfig {
+            embeddings_enabled: true,
+            embedding_model: Some(LOCAL_EMBEDDING_MODEL.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn turns(lines: &[(Role, &str)]) -> Vec<(Role, String)> {
+        lines.iter().map(|(r, c)| (*r, c.to_string())).collect()
+    }
+
+    #[test]
+    fn test_transcript_index_recalls_relevant_session() {
+        let mut index = TranscriptIndex::new(recall_config());
+        let monday = Utc::now() - chrono::Duration::days(3);
+        let today = Utc::now();
+
+        index.index_session("deploy", monday, &turns(&[
+            (Role::User, "How do I deploy the nginx reverse proxy with docker compose?"),
+            (Role::Assistant, "Add an nginx service to docker-compose.yml and mount the proxy config."),
+        ]));
+        index.index_session("parser", today, &turns(&[
+            (Role::User, "The JSON parser panics on trailing commas"),
+            (Role::Assistant, "Make the tokenizer skip a comma before a closing brace."),
+        ]));
+
+        let results = index.search("nginx docker proxy setup", 5);
+        assert!(!results.is_empty());
+        assert_eq!(results[0].item.session_id, "deploy");
+        assert_eq!(results[0].item.session_date, monday);
+        let best_other = results.iter()
+            .filter(|r| r.item.session_id != "deploy")
+            .map(|r| r.score)
+            .fold(0.0, f64::max);
+        assert!(results.iter()
+            .filter(|r| r.item.session_id == "deploy")
+            .all(|r| r.score > best_other));
+
+        let results = index.search("parser trailing comma", 5);
+        assert_eq!(results[0].item.session_id, "parser");
+    }
+
+    #[test]
+    fn test_transcript_index_is_incremental_and_persistent() {
+        let temp = tempfile::tempdir().unwrap();
+        let path = temp.path().join("recall.json");
+        let date = Utc::now();
+        let mut lines = turns(&[(Role::User, "first question about lifetimes")]);
+
+        let mut index = TranscriptIndex::open(&path, recall_config()).unwrap();
+        assert_eq!(index.index_session("s1", date, &lines), 1);
+        assert_eq!(index.index_session("s1", date, &lines), 0);
+
+        lines.push((Role::Assistant, "borrowed references cannot outlive owners".to_string()));
+        assert_eq!(index.index_session("s1", date, &lines), 1);
+        index.save().unwrap();
+
+        let mut reopened = TranscriptIndex::open(&path, recall_config()).unwrap();
+        assert_eq!(reopened.len(), 2);
+        assert_eq!(reopened.index_session("s1", date, &lines), 0);
+        assert_eq!(reopened.search("lifetimes", 1)[0].item.index, 0);
+    }
+
+    #[test]
+    fn test_transcript_index_disabled_by_default() {
+        let mut index = TranscriptIndex::new(SemanticSearchConfig::default());
+        let added = index.index_session("s1", Utc::now(), &turns(&[(Role::User, "hello there")]));
+        assert_eq!(added, 0);
+        assert!(index.is_empty());
+    }
 }
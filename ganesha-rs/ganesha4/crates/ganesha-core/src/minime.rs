@@ -23,7 +23,7 @@
 //! - Pool of up to 10 concurrent subagents
 //! - Model selection based on task complexity
 //! - Automatic escalation on failure
-//! - Cost and token tracking
+//! - Cost and token tracking, with optional per-manager budgets
 //! - Progress updates via channels
 //! - Task splitting and result aggregation
 
@@ -33,10 +33,10 @@ use chrono::{DateTime, Utc};
 use ganesha_providers::{GenerateOptions, Message, ModelTier, ProviderManager, Usage};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::Arc;
-use std::time::Duration;
-use tokio::sync::{mpsc, oneshot, Mutex, RwLock, Semaphore};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, oneshot, watch, Mutex, RwLock, Semaphore};
 use tokio::time::timeout;
 use tracing::{debug, error, info, warn};
 use uuid::Uuid;
@@ -290,6 +290,10 @@ pub struct ProgressUpdate {
     pub message: String,
     /// Timestamp
     pub timestamp: DateTime<Utc>,
+    /// Token usage: the agent's on completion or failure, the manager's
+    /// cumulative usage on budget warnings
+    #[serde(default)]
+    pub token_usage: Option<TokenUsage>,
 }
 
 /// Type of progress update
@@ -309,6 +313,8 @@ pub enum ProgressType {
     Cancelled,
     /// Agent is escalating to a larger model
     Escalating,
+    /// The manager's budget is at least 80% consumed
+    BudgetWarning,
 }
 
 // ============================================================================
@@ -321,6 +327,164 @@ pub const MAX_CONCURRENT_AGENTS: usize = 10;
 /// Default timeout for agent tasks
 pub const DEFAULT_TIMEOUT_SECS: u64 = 300; // 5 minutes
 
+/// Fraction of a budget at which a [`ProgressType::BudgetWarning`] is sent
+pub const BUDGET_WARNING_THRESHOLD: f64 = 0.8;
+
+/// Limits shared by every subagent of one manager
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BudgetConfig {
+    /// Total tokens all agents may use together
+    pub max_total_tokens: u64,
+    /// Wall-clock time from the first spawn until agents are cancelled
+    pub max_wall_clock: Duration,
+}
+
+/// Budget consumption shared between the manager and its agent tasks
+struct BudgetTracker {
+    config: BudgetConfig,
+    /// Set on the first spawn
+    started: OnceLock<Instant>,
+    usage: std::sync::Mutex<TokenUsage>,
+    warned: AtomicBool,
+    exhausted: watch::Sender<bool>,
+}
+
+impl BudgetTracker {
+    fn new(config: BudgetConfig) -> Self {
+        Self {
+            config,
+            started: OnceLock::new(),
+            usage: std::sync::Mutex::new(TokenUsage::default()),
+            warned: AtomicBool::new(false),
+            exhausted: watch::channel(false).0,
+        }
+    }
+
+    fn start(&self) -> Instant {
+        *self.started.get_or_init(Instant::now)
+    }
+
+    fn usage(&self) -> TokenUsage {
+        self.usage.lock().map(|u| u.clone()).unwrap_or_default()
+    }
+
+    /// Fraction consumed: the larger of tokens and wall clock
+    fn consumption(&self) -> f64 {
+        let tokens = if self.config.max_total_tokens == 0 {
+            1.0
+        } else {
+            self.usage().total_tokens as f64 / self.config.max_total_tokens as f64
+        };
+        let elapsed = match self.started.get() {
+            Some(started) if !self.config.max_wall_clock.is_zero() => {
+                started.elapsed().as_secs_f64() / self.config.max_wall_clock.as_secs_f64()
+            }
+            Some(_) => 1.0,
+            None => 0.0,
+        };
+        tokens.max(elapsed)
+    }
+
+    fn is_exhausted(&self) -> bool {
+        *self.exhausted.borrow() || self.consumption() >= 1.0
+    }
+
+    /// Error for spawns refused because the budget is spent
+    fn exhausted_error(&self) -> CoreError {
+        let usage = self.usage();
+        CoreError::MiniMeError(format!(
+            "Budget exhausted: {}/{} tokens, {:.1}s/{:.1}s wall clock",
+            usage.total_tokens,
+            self.config.max_total_tokens,
+            self.started.get().map(|s| s.elapsed().as_secs_f64()).unwrap_or(0.0),
+            self.config.max_wall_clock.as_secs_f64()
+        ))
+    }
+
+    /// Add usage from one response
+    fn record(&self, usage: &Usage) {
+        if let Ok(mut total) = self.usage.lock() {
+            total.add(usage);
+        }
+    }
+
+    /// Cancel running agents if the budget is spent. Called once an agent
+    /// has finished so the response that tripped the budget is kept.
+    fn signal_if_exhausted(&self) {
+        if self.consumption() >= 1.0 {
+            self.exhausted.send_replace(true);
+        }
+    }
+
+    /// Send a warning the first time consumption reaches the threshold
+    async fn maybe_warn(&self, agent: &SubAgent, progress_tx: &mpsc::Sender<ProgressUpdate>) {
+        let consumption = self.consumption();
+        if consumption < BUDGET_WARNING_THRESHOLD || self.warned.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let usage = self.usage();
+        warn!("Subagent budget {:.0}% consumed", consumption.min(1.0) * 100.0);
+        let _ = progress_tx
+            .send(ProgressUpdate {
+                agent_id: agent.id,
+                agent_name: agent.name.clone(),
+                update_type: ProgressType::BudgetWarning,
+                message: format!(
+                    "Budget {:.0}% consumed ({}/{} tokens, {:.1}s/{:.1}s wall clock)",
+                    consumption.min(1.0) * 100.0,
+                    usage.total_tokens,
+                    self.config.max_total_tokens,
+                    self.start().elapsed().as_secs_f64(),
+                    self.config.max_wall_clock.as_secs_f64()
+                ),
+                timestamp: Utc::now(),
+                token_usage: Some(usage),
+            })
+            .await;
+    }
+
+    /// Resolve once the token budget is spent or the wall clock runs out.
+    /// Warns on the way when the wall clock passes the threshold, since no
+    /// response may arrive to trigger the check in time. A wall clock too
+    /// long to represent (e.g. `Duration::MAX`) never runs out.
+    async fn wait_exhausted(&self, agent: &SubAgent, progress_tx: &mpsc::Sender<ProgressUpdate>) {
+        let start = self.start();
+        let warn_after = self.config.max_wall_clock.mul_f64(BUDGET_WARNING_THRESHOLD);
+        let warn_at = start.checked_add(warn_after);
+        let deadline = start.checked_add(self.config.max_wall_clock);
+        let mut exhausted = self.exhausted.subscribe();
+        tokio::select! {
+            _ = exhausted.wait_for(|spent| *spent) => return,
+            _ = sleep_until(warn_at) => {}
+        }
+        self.maybe_warn(agent, progress_tx).await;
+        tokio::select! {
+            _ = exhausted.wait_for(|spent| *spent) => {}
+            _ = sleep_until(deadline) => {}
+        }
+    }
+}
+
+/// Sleep until `at`; never resolves for `None`
+async fn sleep_until(at: Option<Instant>) {
+    match at {
+        Some(at) => tokio::time::sleep_until(tokio::time::Instant::from_std(at)).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Resolve when `budget` is exhausted; never resolves without a budget
+async fn budget_exhausted(
+    budget: Option<&BudgetTracker>,
+    agent: &SubAgent,
+    progress_tx: &mpsc::Sender<ProgressUpdate>,
+) {
+    match budget {
+        Some(budget) => budget.wait_exhausted(agent, progress_tx).await,
+        None => std::future::pending().await,
+    }
+}
+
 /// Manages a pool of subagents for parallel task execution
 pub struct SubAgentManager {
     /// Provider manager for LLM access
@@ -341,6 +505,8 @@ pub struct SubAgentManager {
     default_timeout: Duration,
     /// Model escalation enabled
     escalation_enabled: bool,
+    /// Optional token and wall-clock budget
+    budget: Option<Arc<BudgetTracker>>,
 }
 
 impl SubAgentManager {
@@ -357,6 +523,7 @@ impl SubAgentManager {
             total_tokens: Arc::new(AtomicU64::new(0)),
             default_timeout: Duration::from_secs(DEFAULT_TIMEOUT_SECS),
             escalation_enabled: true,
+            budget: None,
         }
     }
 
@@ -372,6 +539,20 @@ impl SubAgentManager {
         self
     }
 
+    /// Limit tokens and wall-clock time across all agents.
+    ///
+    /// Once the budget is spent, new spawns fail and running agents are
+    /// cancelled.
+    pub fn with_budget(mut self, budget: BudgetConfig) -> Self {
+        self.budget = Some(Arc::new(BudgetTracker::new(budget)));
+        self
+    }
+
+    /// Cumulative token usage counted against the budget
+    pub fn budget_usage(&self) -> Option<TokenUsage> {
+        self.budget.as_ref().map(|b| b.usage())
+    }
+
     /// Take ownership of the progress receiver
     pub async fn take_progress_receiver(&self) -> Option<mpsc::Receiver<ProgressUpdate>> {
         self.progress_rx.lock().await.take()
@@ -397,6 +578,13 @@ impl SubAgentManager {
         let model = model.into();
         let context = context.into();
 
+        if let Some(ref budget) = self.budget {
+            budget.start();
+            if budget.is_exhausted() {
+                return Err(budget.exhausted_error());
+            }
+        }
+
         // Create the agent
         let agent_name = format!("agent-{}", &Uuid::new_v4().to_string()[..8]);
         let mut agent = SubAgent::new(&agent_name, &task, &model).with_context(&context);
@@ -422,6 +610,7 @@ impl SubAgentManager {
         let escalation_enabled = self.escalation_enabled;
         let total_cost = self.total_cost_cents.clone();
         let total_tokens = self.total_tokens.clone();
+        let budget = self.budget.clone();
 
         // Spawn the agent task
         tokio::spawn(async move {
@@ -445,6 +634,7 @@ impl SubAgentManager {
                     update_type: ProgressType::Started,
                     message: format!("Starting task: {}", task),
                     timestamp: Utc::now(),
+                    token_usage: None,
                 })
                 .await;
 
@@ -461,6 +651,7 @@ impl SubAgentManager {
                         update_type: ProgressType::Cancelled,
                         message: "Agent cancelled".to_string(),
                         timestamp: Utc::now(),
+                        token_usage: None,
                     }).await;
 
                     AgentResult {
@@ -473,11 +664,32 @@ impl SubAgentManager {
                         duration_ms: 0,
                     }
                 }
+                _ = budget_exhausted(budget.as_deref(), &agent, &progress_tx) => {
+                    let _ = progress_tx.send(ProgressUpdate {
+                        agent_id,
+                        agent_name: agent.name.clone(),
+                        update_type: ProgressType::Cancelled,
+                        message: "Agent cancelled: budget exhausted".to_string(),
+                        timestamp: Utc::now(),
+                        token_usage: None,
+                    }).await;
+
+                    AgentResult {
+                        agent_id,
+                        success: false,
+                        output: None,
+                        error: Some("Budget exhausted".to_string()),
+                        token_usage: TokenUsage::default(),
+                        cost_cents: 0.0,
+                        duration_ms: 0,
+                    }
+                }
                 result = timeout(timeout_duration, execute_agent_task(
                     &provider_manager,
                     &agent,
                     &progress_tx,
                     escalation_enabled,
+                    budget.as_deref(),
                 )) => {
                     match result {
                         Ok(r) => r,
@@ -488,6 +700,7 @@ impl SubAgentManager {
                                 update_type: ProgressType::Failed,
                                 message: "Agent timed out".to_string(),
                                 timestamp: Utc::now(),
+                                token_usage: None,
                             }).await;
 
                             AgentResult {
@@ -504,6 +717,10 @@ impl SubAgentManager {
                 }
             };
 
+            if let Some(ref budget) = budget {
+                budget.signal_if_exhausted();
+            }
+
             // Update totals
             total_cost.fetch_add((result.cost_cents * 100.0) as u64, Ordering::Relaxed);
             total_tokens.fetch_add(result.token_usage.total_tokens as u64, Ordering::Relaxed);
@@ -596,6 +813,7 @@ async fn execute_agent_task(
     agent: &SubAgent,
     progress_tx: &mpsc::Sender<ProgressUpdate>,
     escalation_enabled: bool,
+    budget: Option<&BudgetTracker>,
 ) -> AgentResult {
     let start = std::time::Instant::now();
     let mut token_usage = TokenUsage::default();
@@ -639,6 +857,7 @@ async fn execute_agent_task(
                     attempts, current_model
                 ),
                 timestamp: Utc::now(),
+                token_usage: None,
             })
             .await;
 
@@ -649,6 +868,12 @@ async fn execute_agent_task(
                 if let Some(usage) = &response.usage {
                     token_usage.add(usage);
                     cost_cents += calculate_cost(&current_model, usage);
+                    if let Some(budget) = budget {
+                        budget.record(usage);
+                    }
+                }
+                if let Some(budget) = budget {
+                    budget.maybe_warn(agent, progress_tx).await;
                 }
 
                 let _ = progress_tx
@@ -658,6 +883,7 @@ async fn execute_agent_task(
                         update_type: ProgressType::Completed,
                         message: "Task completed successfully".to_string(),
                         timestamp: Utc::now(),
+                        token_usage: Some(token_usage.clone()),
                     })
                     .await;
 
@@ -685,6 +911,7 @@ async fn execute_agent_task(
                             update_type: ProgressType::Failed,
                             message: format!("Failed after {} attempts: {}", attempts, e),
                             timestamp: Utc::now(),
+                            token_usage: Some(token_usage.clone()),
                         })
                         .await;
 
@@ -712,6 +939,7 @@ async fn execute_agent_task(
                                     current_model, better_model
                                 ),
                                 timestamp: Utc::now(),
+                                token_usage: None,
                             })
                             .await;
                         current_model = better_model;
@@ -964,6 +1192,142 @@ impl TaskSplitter for LlmTaskSplitter {
     }
 }
 
+/// Deterministic task splitter that needs no LLM call.
+///
+/// Tries, in order:
+/// 1. An explicit delimiter (see [`RuleBasedSplitter::with_delimiter`])
+/// 2. A numbered list (`1.` or `1)` at the start of a line)
+/// 3. One item per file path mentioned in the task
+///
+/// Anything else becomes a single work item. Ids are derived from the rule
+/// and position (`file-1`, `item-2`, ...), so the same task always yields
+/// the same ids.
+#[derive(Debug, Clone, Default)]
+pub struct RuleBasedSplitter {
+    delimiter: Option<String>,
+}
+
+impl RuleBasedSplitter {
+    /// Create a splitter using the numbered-list and file rules
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Also split on an explicit delimiter, e.g. `"---"` or `";"`
+    pub fn with_delimiter(mut self, delimiter: impl Into<String>) -> Self {
+        self.delimiter = Some(delimiter.into());
+        self
+    }
+
+    /// Split synchronously (the [`TaskSplitter`] impl delegates here)
+    pub fn split_task(&self, task: &str, context: &str) -> Vec<WorkItem> {
+        let item = |rule: &str, index: usize, description: String, context: &str| WorkItem {
+            id: format!("{}-{}", rule, index + 1),
+            ..WorkItem::new(description).with_context(context)
+        };
+
+        if let Some(ref delimiter) = self.delimiter {
+            let parts: Vec<&str> = task
+                .split(delimiter.as_str())
+                .map(str::trim)
+                .filter(|p| !p.is_empty())
+                .collect();
+            if parts.len() > 1 {
+                return parts
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, part)| item("part", i, part.to_string(), context))
+                    .collect();
+            }
+        }
+
+        let (preamble, entries) = Self::numbered_list(task);
+        if entries.len() > 1 {
+            let context = match preamble.trim() {
+                "" => context.to_string(),
+                preamble if context.is_empty() => preamble.to_string(),
+                preamble => format!("{}\n\n{}", preamble, context),
+            };
+            return entries
+                .into_iter()
+                .enumerate()
+                .map(|(i, entry)| item("item", i, entry, &context))
+                .collect();
+        }
+
+        let files = Self::file_mentions(task);
+        if files.len() > 1 {
+            return files
+                .into_iter()
+                .enumerate()
+                .map(|(i, file)| {
+                    let description = format!("{} (only in {})", task.trim(), file);
+                    item("file", i, description, context).with_output_type(OutputType::Code)
+                })
+                .collect();
+        }
+
+        vec![item("task", 0, task.trim().to_string(), context)]
+    }
+
+    /// Lines before the list, and the text of each numbered entry
+    /// (continuation lines are folded into the preceding entry)
+    fn numbered_list(task: &str) -> (String, Vec<String>) {
+        let mut preamble = Vec::new();
+        let mut entries: Vec<String> = Vec::new();
+
+        for line in task.lines() {
+            let trimmed = line.trim();
+            let digits = trimmed.chars().take_while(|c| c.is_ascii_digit()).count();
+            let rest = &trimmed[digits..];
+            if digits > 0 && (rest.starts_with(". ") || rest.starts_with(") ")) {
+                entries.push(rest[2..].trim().to_string());
+            } else if let Some(last) = entries.last_mut() {
+                if !trimmed.is_empty() {
+                    last.push(' ');
+                    last.push_str(trimmed);
+                }
+            } else {
+                preamble.push(line);
+            }
+        }
+
+        (preamble.join("\n"), entries)
+    }
+
+    /// File paths mentioned in the task, in order of first mention
+    fn file_mentions(task: &str) -> Vec<String> {
+        let mut files: Vec<String> = Vec::new();
+        for word in task.split(|c: char| c.is_whitespace() || matches!(c, ',' | ';' | '(' | ')' | '`' | '"' | '\'')) {
+            let word = word.trim_end_matches(['.', ':', '!', '?']);
+            let is_file = word
+                .rsplit_once('.')
+                .map(|(stem, ext)| {
+                    !stem.is_empty()
+                        && (1..=5).contains(&ext.len())
+                        && ext.chars().all(|c| c.is_ascii_alphanumeric())
+                        && ext.chars().any(|c| c.is_ascii_alphabetic())
+                })
+                .unwrap_or(false);
+            if is_file && !files.iter().any(|f| f == word) {
+                files.push(word.to_string());
+            }
+        }
+        files
+    }
+}
+
+#[async_trait]
+impl TaskSplitter for RuleBasedSplitter {
+    async fn split(&self, task: &str, context: &str) -> Result<Vec<WorkItem>> {
+        Ok(self.split_task(task, context))
+    }
+
+    fn name(&self) -> &str {
+        "rule-based-splitter"
+    }
+}
+
 /// Default result aggregator that concatenates results
 pub struct SimpleAggregator;
 
@@ -1405,6 +1769,7 @@ impl TaskOrchestrator {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ganesha_providers::{MockProvider, MockReply, ProviderPriority};
 
     #[test]
     fn test_agent_id_creation() {
@@ -1475,6 +1840,7 @@ mod tests {
             prompt_tokens: 100,
             completion_tokens: 50,
             total_tokens: 150,
+            ..Default::default()
         };
         usage.add(&provider_usage);
         assert_eq!(usage.prompt_tokens, 100);
@@ -1505,6 +1871,7 @@ mod tests {
             prompt_tokens: 1000,
             completion_tokens: 500,
             total_tokens: 1500,
+            ..Default::default()
         };
 
         // Haiku should be cheap
@@ -1513,4 +1880,188 @@ mod tests {
 
         assert!(haiku_cost < opus_cost);
     }
+
+    async fn metered_manager(tokens: u32, delay: Duration, budget: BudgetConfig) -> SubAgentManager {
+        let providers = Arc::new(ProviderManager::new());
+        // Answers after `delay`, using `tokens` per call
+        let metered = MockProvider::new()
+            .with_name("metered")
+            .with_fallback(MockReply::after(delay, "done"))
+            .with_usage(Usage {
+                prompt_tokens: tokens / 2,
+                completion_tokens: tokens / 2,
+                total_tokens: tokens,
+                ..Default::default()
+            });
+        providers.register(metered, ProviderPriority::Primary).await;
+        SubAgentManager::new(providers).with_escalation(false).with_budget(budget)
+    }
+
+    #[tokio::test]
+    async fn test_token_budget_refuses_spawn_and_warns() {
+        let manager = metered_manager(
+            400,
+            Duration::ZERO,
+            BudgetConfig { max_total_tokens: 1000, max_wall_clock: Duration::from_secs(60) },
+        )
+        .await;
+        let mut progress = manager.take_progress_receiver().await.unwrap();
+
+        for _ in 0..3 {
+            let handle = manager.spawn_agent("task", "metered-model").await.unwrap();
+            assert!(handle.wait().await.success);
+        }
+        assert_eq!(manager.budget_usage().unwrap().total_tokens, 1200);
+
+        let err = manager.spawn_agent("task", "metered-model").await.unwrap_err();
+        assert!(matches!(err, CoreError::MiniMeError(ref msg) if msg.contains("Budget exhausted")));
+
+        let mut warnings = Vec::new();
+        while let Ok(update) = progress.try_recv() {
+            if update.update_type == ProgressType::BudgetWarning {
+                warnings.push(update);
+            }
+        }
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].token_usage.as_ref().unwrap().total_tokens, 800);
+    }
+
+    #[tokio::test]
+    async fn test_wall_clock_budget_cancels_running_agents() {
+        let manager = metered_manager(
+            10,
+            Duration::from_secs(30),
+            BudgetConfig { max_total_tokens: 1_000_000, max_wall_clock: Duration::from_millis(50) },
+        )
+        .await;
+
+        let mut progress = manager.take_progress_receiver().await.unwrap();
+
+        let handle = manager.spawn_agent("slow task", "metered-model").await.unwrap();
+        let result = tokio::time::timeout(Duration::from_secs(5), handle.wait()).await.unwrap();
+        assert!(!result.success);
+        assert_eq!(result.error.as_deref(), Some("Budget exhausted"));
+        assert!(manager.spawn_agent("late task", "metered-model").await.is_err());
+
+        // The warning comes from the clock alone; no response ever arrived
+        let mut warnings = Vec::new();
+        while let Ok(update) = progress.try_recv() {
+            if update.update_type == ProgressType::BudgetWarning {
+                warnings.push(update);
+            }
+        }
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].token_usage.as_ref().unwrap().total_tokens, 0);
+        assert!(warnings[0].message.contains("wall clock"));
+    }
+
+    #[tokio::test]
+    async fn test_unlimited_wall_clock_budget() {
+        let manager = metered_manager(
+            10,
+            Duration::ZERO,
+            BudgetConfig { max_total_tokens: 1_000_000, max_wall_clock: Duration::MAX },
+        )
+        .await;
+
+        let handle = manager.spawn_agent("task", "metered-model").await.unwrap();
+        let result = tokio::time::timeout(Duration::from_secs(5), handle.wait()).await.unwrap();
+        assert!(result.success);
+        assert!(manager.spawn_agent("another task", "metered-model").await.is_ok());
+    }
+
+    struct FixedSplitter(Vec<WorkItem>);
+
+    #[async_trait]
+    impl TaskSplitter for FixedSplitter {
+        async fn split(&self, _task: &str, _context: &str) -> Result<Vec<WorkItem>> {
+            Ok(self.0.clone())
+        }
+
+        fn name(&self) -> &str {
+            "fixed"
+        }
+    }
+
+    #[tokio::test]
+    async fn test_aggregator_runs_on_partial_results() {
+        let manager = metered_manager(
+            400,
+            Duration::ZERO,
+            BudgetConfig { max_total_tokens: 1000, max_wall_clock: Duration::from_secs(60) },
+        )
+        .await;
+
+        // A chain, so each step starts only after the previous one used its tokens
+        let mut items = vec![WorkItem::new("step 1")];
+        for i in 2..=4 {
+            let previous = items.last().unwrap().id.clone();
+            items.push(WorkItem::new(format!("step {}", i)).depends_on(previous));
+        }
+
+        let orchestrator = TaskOrchestrator::new(
+            Arc::new(manager),
+            Arc::new(FixedSplitter(items)),
+            Arc::new(SimpleAggregator),
+        );
+        let output = orchestrator.execute("task", "").await.unwrap();
+
+        assert_eq!(output.matches("done").count(), 3);
+        assert!(output.contains("## step 4\nFailed: Mini-Me error: Budget exhausted"));
+    }
+
+    #[test]
+    fn test_rule_based_splitter() {
+        let splitter = RuleBasedSplitter::new();
+        let task = "Add license headers to src/main.rs, src/lib.rs and src/util.rs.";
+
+        let items = splitter.split_task(task, "MIT");
+        assert_eq!(items.len(), 3);
+        let ids: Vec<&str> = items.iter().map(|i| i.id.as_str()).collect();
+        assert_eq!(ids, vec!["file-1", "file-2", "file-3"]);
+        assert!(items[1].description.ends_with("(only in src/lib.rs)"));
+        assert_eq!(items[0].context, "MIT");
+        let again: Vec<String> = splitter.split_task(task, "MIT").into_iter().map(|i| i.id).collect();
+        assert_eq!(again, ids);
+
+        let items = splitter.split_task("Release checklist:\n1. Bump version\n2) Update\n   changelog\n3. Tag", "");
+        let descriptions: Vec<&str> = items.iter().map(|i| i.description.as_str()).collect();
+        assert_eq!(descriptions, vec!["Bump version", "Update changelog", "Tag"]);
+        assert_eq!(items[0].id, "item-1");
+        assert_eq!(items[0].context, "Release checklist:");
+
+        let items = RuleBasedSplitter::new().with_delimiter("---").split_task("lint --- test --- build", "");
+        assert_eq!(items.iter().map(|i| i.id.as_str()).collect::<Vec<_>>(), vec!["part-1", "part-2", "part-3"]);
+
+        let items = splitter.split_task("Explain the design", "");
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].id, "task-1");
+    }
+
+    #[tokio::test]
+    async fn test_orchestrator_merges_in_input_order() {
+        let providers = Arc::new(ProviderManager::new());
+        // The first file finishes last
+        providers
+            .register(
+                MockProvider::new()
+                    .on("only in a.rs", MockReply::after(Duration::from_millis(60), "only in a.rs"))
+                    .on("only in b.rs", MockReply::after(Duration::from_millis(30), "only in b.rs"))
+                    .on("only in c.rs", "only in c.rs"),
+                ProviderPriority::Primary,
+            )
+            .await;
+
+        let orchestrator = TaskOrchestrator::new(
+            Arc::new(SubAgentManager::new(providers).with_escalation(false)),
+            Arc::new(RuleBasedSplitter::new()),
+            Arc::new(SimpleAggregator),
+        );
+        let output = orchestrator.execute("Format a.rs, b.rs and c.rs", "").await.unwrap();
+
+        let a = output.find("only in a.rs\n").unwrap();
+        let b = output.find("only in b.rs\n").unwrap();
+        let c = output.find("only in c.rs\n").unwrap();
+        assert!(a < b && b < c, "{}", output);
+    }
 }
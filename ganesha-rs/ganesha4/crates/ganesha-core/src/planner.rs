@@ -23,9 +23,11 @@
 
 use crate::risk::OperationRisk;
 use async_trait::async_trait;
+use ganesha_providers::{GenerateOptions, LlmProvider, Message, ToolDefinition};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
+use std::sync::Arc;
 use thiserror::Error;
 use uuid::Uuid;
 
@@ -123,7 +125,7 @@ impl ActionType {
 }
 
 /// A single step in a task plan
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PlanStep {
     /// Unique identifier for this step
     pub id: StepId,
@@ -190,6 +192,18 @@ impl PlanStep {
         self
     }
 
+    /// Shell command this step would run, if its action runs one
+    pub fn command(&self) -> Option<&str> {
+        let (key, default) = match self.action_type {
+            ActionType::ShellCommand => ("command", ""),
+            ActionType::RunTests => ("test_command", "cargo test"),
+            ActionType::Build => ("build_command", "cargo build"),
+            ActionType::GitOperation => ("git_command", "git status"),
+            _ => return None,
+        };
+        Some(self.context.get(key).and_then(|v| v.as_str()).unwrap_or(default))
+    }
+
     /// Set the risk level
     pub fn with_risk(mut self, risk: OperationRisk) -> Self {
         self.risk = risk;
@@ -237,7 +251,7 @@ impl PlanStep {
 }
 
 /// Strategy for rolling back a step
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub enum RollbackStrategy {
     /// Automatically determine rollback based on action type
     #[default]
@@ -425,6 +439,59 @@ impl TaskPlan {
         }
         self
     }
+
+    /// Compare this plan with a newer one, matching steps by ID
+    pub fn diff(&self, other: &TaskPlan) -> PlanDiff {
+        let old: HashMap<StepId, &PlanStep> = self.steps.iter().map(|s| (s.id, s)).collect();
+        let new: HashMap<StepId, &PlanStep> = other.steps.iter().map(|s| (s.id, s)).collect();
+
+        let mut diff = PlanDiff::default();
+        for step in &other.steps {
+            match old.get(&step.id) {
+                None => diff.added.push(step.clone()),
+                Some(before) if *before != step => diff.changed.push(StepChange {
+                    id: step.id,
+                    before: (*before).clone(),
+                    after: step.clone(),
+                }),
+                Some(_) => {}
+            }
+        }
+        diff.removed = self
+            .steps
+            .iter()
+            .filter(|s| !new.contains_key(&s.id))
+            .cloned()
+            .collect();
+
+        diff
+    }
+}
+
+/// A step that exists in both plans but differs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StepChange {
+    pub id: StepId,
+    pub before: PlanStep,
+    pub after: PlanStep,
+}
+
+/// Differences between two versions of a plan (see `TaskPlan::diff`)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PlanDiff {
+    /// Steps only in the new plan, in its order
+    pub added: Vec<PlanStep>,
+    /// Steps only in the old plan, in its order
+    pub removed: Vec<PlanStep>,
+    /// Steps with the same ID whose contents changed
+    pub changed: Vec<StepChange>,
+}
+
+impl PlanDiff {
+    /// True if the plans have the same steps
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
 }
 
 /// Context for planning operations
@@ -676,6 +743,230 @@ impl Planner for SimplePlanner {
     }
 }
 
+/// Name of the tool `ToolCallingPlanner` asks the model to call
+pub const EMIT_PLAN_TOOL: &str = "emit_plan";
+
+/// Planner that has the model return its plan as a structured tool call.
+///
+/// The provider is offered a single `emit_plan` tool whose arguments map
+/// directly onto `PlanStep`s, so nothing is scraped out of free text.
+/// Providers without tool support, or replies without an `emit_plan` call,
+/// fall back to `SimplePlanner`.
+pub struct ToolCallingPlanner {
+    provider: Arc<dyn LlmProvider>,
+    fallback: SimplePlanner,
+    max_steps: usize,
+}
+
+impl ToolCallingPlanner {
+    /// Create a planner backed by the given provider
+    pub fn new(provider: Arc<dyn LlmProvider>) -> Self {
+        Self {
+            provider,
+            fallback: SimplePlanner::new(),
+            max_steps: 50,
+        }
+    }
+
+    /// Set maximum steps
+    pub fn with_max_steps(mut self, max: usize) -> Self {
+        self.max_steps = max;
+        self.fallback = self.fallback.with_max_steps(max);
+        self
+    }
+
+    /// The `emit_plan` tool definition
+    pub fn emit_plan_tool() -> ToolDefinition {
+        ToolDefinition {
+            name: EMIT_PLAN_TOOL.to_string(),
+            description: "Submit the step-by-step plan for the task".to_string(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "steps": {
+                        "type": "array",
+                        "items": {
+                            "type": "object",
+                            "properties": {
+                                "description": { "type": "string" },
+                                "action": {
+                                    "type": "string",
+                                    "enum": [
+                                        "read_file", "write_file", "edit_file", "delete_file",
+                                        "create_directory", "shell_command", "run_tests", "build",
+                                        "git_operation", "search", "analyze", "generate", "user_input"
+                                    ]
+                                },
+                                "target_files": { "type": "array", "items": { "type": "string" } },
+                                "command": {
+                                    "type": "string",
+                                    "description": "Command to run for shell_command, run_tests, build and git_operation steps"
+                                },
+                                "depends_on": {
+                                    "type": "array",
+                                    "items": { "type": "integer" },
+                                    "description": "Zero-based indices of earlier steps this step needs"
+                                },
+                                "rollback_commands": {
+                                    "type": "array",
+                                    "items": { "type": "string" },
+                                    "description": "Commands that undo this step, if any"
+                                },
+                                "optional": { "type": "boolean" }
+                            },
+                            "required": ["description", "action"]
+                        }
+                    }
+                },
+                "required": ["steps"]
+            }),
+        }
+    }
+
+    /// Build a plan from `emit_plan` arguments
+    pub fn plan_from_arguments(&self, task: &str, arguments: &serde_json::Value) -> Result<TaskPlan> {
+        let raw: EmitPlanArgs = serde_json::from_value(arguments.clone())
+            .map_err(|e| PlannerError::ParseError(format!("Invalid {} arguments: {}", EMIT_PLAN_TOOL, e)))?;
+
+        let mut plan = TaskPlan::new(task);
+        let mut ids = Vec::with_capacity(raw.steps.len());
+
+        for (index, raw_step) in raw.steps.into_iter().take(self.max_steps).enumerate() {
+            let action_type = action_from_name(&raw_step.action);
+            let mut step = PlanStep::new(raw_step.description, action_type.clone())
+                .with_targets(raw_step.target_files);
+
+            for dep in raw_step.depends_on {
+                let dep_id = ids.get(dep).copied().filter(|_| dep < index).ok_or_else(|| {
+                    PlannerError::InvalidStepRef(format!(
+                        "Step {} depends on step {}, which does not precede it",
+                        index, dep
+                    ))
+                })?;
+                step = step.depends_on(dep_id);
+            }
+
+            if let Some(command) = raw_step.command {
+                let key = match action_type {
+                    ActionType::RunTests => "test_command",
+                    ActionType::Build => "build_command",
+                    ActionType::GitOperation => "git_command",
+                    _ => "command",
+                };
+                step = step.with_context(key, command);
+            }
+            if !raw_step.rollback_commands.is_empty() {
+                step = step.with_rollback(RollbackStrategy::Custom(raw_step.rollback_commands));
+            }
+            if raw_step.optional {
+                step = step.optional();
+            }
+
+            ids.push(step.id);
+            plan.add_step(step);
+        }
+
+        plan.validate()?;
+        Ok(plan)
+    }
+
+    fn prompt(task: &str, context: &PlanningContext) -> String {
+        let mut prompt = format!("Task: {}\n", task);
+        if let Some(ref wd) = context.working_directory {
+            prompt.push_str(&format!("Working directory: {}\n", wd.display()));
+        }
+        if let Some(ref project_type) = context.project_type {
+            prompt.push_str(&format!("Project type: {}\n", project_type));
+        }
+        if !context.project_files.is_empty() {
+            prompt.push_str("Project files:\n");
+            for file in &context.project_files {
+                prompt.push_str(&format!("- {}\n", file.display()));
+            }
+        }
+        for (key, value) in &context.preferences {
+            prompt.push_str(&format!("Preference {}: {}\n", key, value));
+        }
+        prompt
+    }
+}
+
+#[async_trait]
+impl Planner for ToolCallingPlanner {
+    async fn plan(&self, task: &str, context: &PlanningContext) -> Result<TaskPlan> {
+        let Some(tools) = self.provider.as_tool_provider() else {
+            tracing::debug!("{} has no tool support, using SimplePlanner", self.provider.name());
+            return self.fallback.plan(task, context).await;
+        };
+
+        let mut messages = vec![Message::system(format!(
+            "You are a planning assistant. Break the task into small, ordered steps and \
+             submit them by calling the {} tool exactly once.",
+            EMIT_PLAN_TOOL
+        ))];
+        messages.extend(context.conversation_history.iter().map(Message::user));
+        messages.push(Message::user(Self::prompt(task, context)));
+
+        let response = tools
+            .chat_with_tools(&messages, &[Self::emit_plan_tool()], &GenerateOptions::default())
+            .await
+            .map_err(|e| PlannerError::ProviderError(e.to_string()))?;
+
+        let Some(call) = response.tool_calls.iter().find(|c| c.name == EMIT_PLAN_TOOL) else {
+            tracing::warn!("Model did not call {}, using SimplePlanner", EMIT_PLAN_TOOL);
+            return self.fallback.plan(task, context).await;
+        };
+
+        let mut plan = self.plan_from_arguments(task, &call.arguments)?;
+        if let Some(ref wd) = context.working_directory {
+            plan = plan.with_metadata("working_directory", wd.display().to_string());
+        }
+        Ok(plan)
+    }
+}
+
+/// Arguments of the `emit_plan` tool
+#[derive(Debug, Deserialize)]
+struct EmitPlanArgs {
+    steps: Vec<EmitPlanStep>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EmitPlanStep {
+    description: String,
+    action: String,
+    #[serde(default)]
+    target_files: Vec<PathBuf>,
+    #[serde(default)]
+    command: Option<String>,
+    #[serde(default)]
+    depends_on: Vec<usize>,
+    #[serde(default)]
+    rollback_commands: Vec<String>,
+    #[serde(default)]
+    optional: bool,
+}
+
+/// Map an `emit_plan` action name to an `ActionType`
+fn action_from_name(name: &str) -> ActionType {
+    match name {
+        "read_file" => ActionType::ReadFile,
+        "write_file" => ActionType::WriteFile,
+        "edit_file" => ActionType::EditFile,
+        "delete_file" => ActionType::DeleteFile,
+        "create_directory" => ActionType::CreateDirectory,
+        "shell_command" => ActionType::ShellCommand,
+        "run_tests" => ActionType::RunTests,
+        "build" => ActionType::Build,
+        "git_operation" => ActionType::GitOperation,
+        "search" => ActionType::Search,
+        "analyze" => ActionType::Analyze,
+        "generate" => ActionType::Generate,
+        "user_input" => ActionType::UserInput,
+        other => ActionType::Custom(other.to_string()),
+    }
+}
+
 /// Builder for creating plans manually
 pub struct PlanBuilder {
     plan: TaskPlan,
@@ -799,6 +1090,37 @@ mod tests {
         assert!(plan.validate().is_err());
     }
 
+    #[test]
+    fn test_plan_diff() {
+        let mut old = TaskPlan::new("Test task");
+        let kept = PlanStep::new("Read file", ActionType::ReadFile);
+        let edited = PlanStep::new("Write file", ActionType::WriteFile);
+        let dropped = PlanStep::new("Analyze", ActionType::Analyze);
+        old.add_step(kept.clone());
+        old.add_step(edited.clone());
+        old.add_step(dropped.clone());
+
+        assert!(old.diff(&old.clone()).is_empty());
+
+        let mut new = TaskPlan::new("Test task");
+        new.add_step(kept);
+        let mut rewritten = edited.clone();
+        rewritten.description = "Write file carefully".to_string();
+        rewritten = rewritten.with_risk(OperationRisk::High);
+        new.add_step(rewritten.clone());
+        let added = PlanStep::new("Run tests", ActionType::RunTests);
+        new.add_step(added.clone());
+
+        let diff = old.diff(&new);
+        assert!(!diff.is_empty());
+        assert_eq!(diff.added, vec![added]);
+        assert_eq!(diff.removed, vec![dropped]);
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].id, edited.id);
+        assert_eq!(diff.changed[0].before, edited);
+        assert_eq!(diff.changed[0].after, rewritten);
+    }
+
     #[tokio::test]
     async fn test_simple_planner() {
         let planner = SimplePlanner::new();
@@ -822,4 +1144,100 @@ mod tests {
         assert_eq!(plan.len(), 2);
         assert!(plan.metadata.contains_key("author"));
     }
+
+    /// Provider that answers every request with a canned `emit_plan` call
+    struct CannedToolProvider {
+        tools: bool,
+    }
+
+    #[async_trait]
+    impl LlmProvider for CannedToolProvider {
+        fn name(&self) -> &str {
+            "canned"
+        }
+
+        async fn is_available(&self) -> bool {
+            true
+        }
+
+        fn default_model(&self) -> &str {
+            "canned"
+        }
+
+        fn model_tier(&self, _model: &str) -> ganesha_providers::ModelTier {
+            ganesha_providers::ModelTier::Unknown
+        }
+
+        async fn list_models(&self) -> ganesha_providers::Result<Vec<ganesha_providers::ModelInfo>> {
+            Ok(Vec::new())
+        }
+
+        async fn chat(
+            &self,
+            _messages: &[Message],
+            _options: &GenerateOptions,
+        ) -> ganesha_providers::Result<ganesha_providers::Response> {
+            Err(ganesha_providers::ProviderError::Unavailable("text chat unused".into()))
+        }
+
+        fn as_tool_provider(&self) -> Option<&dyn ganesha_providers::ToolProvider> {
+            if self.tools {
+                Some(self)
+            } else {
+                None
+            }
+        }
+    }
+
+    #[async_trait]
+    impl ganesha_providers::ToolProvider for CannedToolProvider {
+        async fn chat_with_tools(
+            &self,
+            _messages: &[Message],
+            tools: &[ToolDefinition],
+            _options: &GenerateOptions,
+        ) -> ganesha_providers::Result<ganesha_providers::ToolResponse> {
+            assert_eq!(tools[0].name, EMIT_PLAN_TOOL);
+            Ok(ganesha_providers::ToolResponse {
+                content: None,
+                tool_calls: vec![ganesha_providers::ToolCall {
+                    id: "call-1".into(),
+                    name: EMIT_PLAN_TOOL.into(),
+                    arguments: serde_json::json!({
+                        "steps": [
+                            { "description": "Read the config", "action": "read_file",
+                              "target_files": ["config.toml"] },
+                            { "description": "Create the output dir", "action": "shell_command",
+                              "command": "mkdir out", "depends_on": [0],
+                              "rollback_commands": ["rmdir out"] },
+                            { "description": "Run the tests", "action": "run_tests",
+                              "command": "cargo test -p core", "depends_on": [1] }
+                        ]
+                    }),
+                }],
+                finish_reason: Some("tool_calls".into()),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tool_calling_planner() {
+        let planner = ToolCallingPlanner::new(Arc::new(CannedToolProvider { tools: true }));
+        let plan = planner.plan("Set up output", &PlanningContext::new()).await.unwrap();
+
+        let steps = plan.steps();
+        assert_eq!(steps.len(), 3);
+        assert_eq!(steps[0].action_type, ActionType::ReadFile);
+        assert_eq!(steps[0].target_files, vec![PathBuf::from("config.toml")]);
+        assert_eq!(steps[1].command(), Some("mkdir out"));
+        assert_eq!(steps[1].dependencies, vec![steps[0].id]);
+        assert_eq!(steps[1].rollback_strategy, RollbackStrategy::Custom(vec!["rmdir out".into()]));
+        assert_eq!(steps[2].command(), Some("cargo test -p core"));
+        assert_eq!(plan.execution_order().unwrap(), vec![steps[0].id, steps[1].id, steps[2].id]);
+
+        // Without tool support the keyword-based SimplePlanner takes over
+        let planner = ToolCallingPlanner::new(Arc::new(CannedToolProvider { tools: false }));
+        let plan = planner.plan("Set up output", &PlanningContext::new()).await.unwrap();
+        assert_eq!(plan.steps()[0].action_type, ActionType::Analyze);
+    }
 }
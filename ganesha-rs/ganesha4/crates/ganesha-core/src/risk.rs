@@ -8,9 +8,15 @@
 //! - **Normal**: Asks before risky operations (default)
 //! - **Trusted**: Auto-approves routine tasks
 //! - **Yolo**: Auto-approves everything
+//!
+//! [`estimate_impact`] adds a "blast radius" for planned steps: how many
+//! files they touch, whether that can be undone, and whether anything
+//! outside the working directory is involved.
 
+use crate::planner::{ActionType, PlanStep, RollbackStrategy};
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::path::{Component, Path, PathBuf};
 
 /// Risk level for operations
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -198,6 +204,277 @@ impl OperationRisk {
     }
 }
 
+/// Estimated blast radius of a planned step
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImpactEstimate {
+    /// Files the step would modify, create or delete
+    pub files_touched: Vec<PathBuf>,
+    /// Whether the changes can be undone (by rollback or by nature)
+    pub reversible: bool,
+    /// Paths referenced outside the working directory
+    pub outside_paths: Vec<PathBuf>,
+}
+
+impl ImpactEstimate {
+    /// Number of files the step would modify, create or delete
+    pub fn files_touched_count(&self) -> usize {
+        self.files_touched.len()
+    }
+
+    /// Whether the step references anything outside the working directory
+    pub fn touches_outside_working_dir(&self) -> bool {
+        !self.outside_paths.is_empty()
+    }
+
+    /// Raise `risk` to account for the impact: anything outside the working
+    /// directory is at least High, irreversible changes at least Medium
+    pub fn escalate(&self, risk: OperationRisk) -> OperationRisk {
+        let mut risk = risk;
+        if self.touches_outside_working_dir() {
+            risk = risk.max(OperationRisk::High);
+        }
+        if !self.reversible {
+            risk = risk.max(OperationRisk::Medium);
+        }
+        risk
+    }
+
+    fn touch(&mut self, path: PathBuf) {
+        if !self.files_touched.contains(&path) {
+            self.files_touched.push(path);
+        }
+    }
+
+    fn reference(&mut self, path: PathBuf, working_dir: &Path) {
+        if !is_inside(&path, working_dir) && !self.outside_paths.contains(&path) {
+            self.outside_paths.push(path);
+        }
+    }
+}
+
+/// Estimate a step's impact from its action type, target files and command,
+/// without executing anything. Relative paths resolve against `working_dir`.
+pub fn estimate_impact(step: &PlanStep, working_dir: &Path) -> ImpactEstimate {
+    let mut impact = ImpactEstimate {
+        reversible: true,
+        ..Default::default()
+    };
+
+    let writes = matches!(
+        step.action_type,
+        ActionType::WriteFile | ActionType::EditFile | ActionType::DeleteFile | ActionType::CreateDirectory
+    );
+    for target in &step.target_files {
+        let path = resolve(target.to_string_lossy().as_ref(), working_dir);
+        impact.reference(path.clone(), working_dir);
+        if writes {
+            impact.touch(path);
+        }
+    }
+
+    match step.action_type {
+        ActionType::DeleteFile => impact.reversible = false,
+        ActionType::WriteFile | ActionType::EditFile => {
+            impact.reversible = step.rollback_strategy != RollbackStrategy::None;
+        }
+        _ => {}
+    }
+
+    if let Some(command) = step.command() {
+        let command_impact = estimate_command_impact(command, working_dir);
+        for path in command_impact.files_touched {
+            impact.touch(path);
+        }
+        for path in command_impact.outside_paths {
+            impact.reference(path, working_dir);
+        }
+        impact.reversible &= command_impact.reversible
+            || matches!(step.rollback_strategy, RollbackStrategy::Custom(_));
+    }
+
+    impact
+}
+
+/// Commands whose path arguments are all modified
+const WRITING_COMMANDS: &[&str] = &[
+    "rm", "rmdir", "unlink", "shred", "truncate", "mv", "cp", "touch", "mkdir", "chmod", "chown", "tee", "ln",
+];
+
+/// Commands whose effects cannot be undone
+const DESTRUCTIVE_COMMANDS: &[&str] = &["rm", "rmdir", "unlink", "shred", "truncate", "dd", "mkfs"];
+
+/// Estimate a shell command's impact by parsing it for writing commands
+/// (`rm`, `mv`, ...), redirects and absolute paths
+pub fn estimate_command_impact(command: &str, working_dir: &Path) -> ImpactEstimate {
+    let mut impact = ImpactEstimate {
+        reversible: true,
+        ..Default::default()
+    };
+
+    for segment in split_commands(command) {
+        let mut words = segment.iter().map(String::as_str).peekable();
+        // Skip privilege wrappers and environment assignments
+        while let Some(word) = words.peek() {
+            if matches!(*word, "sudo" | "doas" | "env" | "nohup" | "time") || is_assignment(word) {
+                words.next();
+            } else {
+                break;
+            }
+        }
+        let Some(program) = words.next() else {
+            continue;
+        };
+        let program = Path::new(program)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or(program);
+
+        if DESTRUCTIVE_COMMANDS.contains(&program) {
+            impact.reversible = false;
+        }
+        if program == "git" && is_destructive_git(&segment) {
+            impact.reversible = false;
+        }
+
+        let args: Vec<&str> = words.collect();
+        let mut i = 0;
+        while i < args.len() {
+            let arg = args[i];
+            i += 1;
+
+            // Redirects: `> file`, `>> file`, `2> file`, `>file`
+            let redirect = arg.trim_start_matches(|c: char| c.is_ascii_digit() || c == '&');
+            if let Some(target) = redirect.strip_prefix(">>").or_else(|| redirect.strip_prefix('>')) {
+                let overwrite = !redirect.starts_with(">>");
+                let target = if target.is_empty() {
+                    i += 1;
+                    args.get(i - 1).copied().unwrap_or("")
+                } else {
+                    target
+                };
+                if !target.is_empty() && !target.starts_with('&') && !is_device(target) {
+                    let path = resolve(target, working_dir);
+                    impact.reference(path.clone(), working_dir);
+                    impact.touch(path);
+                    if overwrite {
+                        impact.reversible = false;
+                    }
+                }
+                continue;
+            }
+            if arg.starts_with('<') || arg.starts_with('-') {
+                continue;
+            }
+
+            if WRITING_COMMANDS.contains(&program) {
+                let path = resolve(arg, working_dir);
+                impact.reference(path.clone(), working_dir);
+                impact.touch(path);
+            } else if looks_like_path(arg) && !is_device(arg) {
+                impact.reference(resolve(arg, working_dir), working_dir);
+            }
+        }
+    }
+
+    impact
+}
+
+/// Split a command line into simple commands on `;`, `&&`, `||`, `|` and
+/// newlines, and each into words (honoring single and double quotes)
+pub(crate) fn split_commands(command: &str) -> Vec<Vec<String>> {
+    let mut commands = Vec::new();
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut quote: Option<char> = None;
+    let mut chars = command.chars().peekable();
+
+    let flush_word = |word: &mut String, words: &mut Vec<String>| {
+        if !word.is_empty() {
+            words.push(std::mem::take(word));
+        }
+    };
+
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => word.push(c),
+            (None, '\'') | (None, '"') => quote = Some(c),
+            (None, '\n') => {
+                flush_word(&mut word, &mut words);
+                if !words.is_empty() {
+                    commands.push(std::mem::take(&mut words));
+                }
+            }
+            (None, c) if c.is_whitespace() => flush_word(&mut word, &mut words),
+            (None, ';') | (None, '|') | (None, '&') if c != '&' || chars.peek() == Some(&'&') => {
+                if matches!(chars.peek(), Some('|') | Some('&')) {
+                    chars.next();
+                }
+                flush_word(&mut word, &mut words);
+                if !words.is_empty() {
+                    commands.push(std::mem::take(&mut words));
+                }
+            }
+            (None, c) => word.push(c),
+        }
+    }
+    flush_word(&mut word, &mut words);
+    if !words.is_empty() {
+        commands.push(words);
+    }
+    commands
+}
+
+fn is_assignment(word: &str) -> bool {
+    word.split_once('=')
+        .map(|(name, _)| !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(false)
+}
+
+fn is_destructive_git(words: &[String]) -> bool {
+    let has = |flag: &str| words.iter().any(|w| w == flag);
+    (has("reset") && has("--hard"))
+        || has("clean")
+        || (has("push") && (has("--force") || has("-f")))
+        || (has("checkout") && has("--"))
+}
+
+fn is_device(path: &str) -> bool {
+    matches!(path, "/dev/null" | "/dev/stdout" | "/dev/stderr")
+}
+
+fn looks_like_path(arg: &str) -> bool {
+    arg.starts_with('/') || arg.starts_with('~') || arg == ".." || arg.starts_with("../")
+}
+
+/// Resolve a path lexically (no filesystem access) against `working_dir`
+fn resolve(path: &str, working_dir: &Path) -> PathBuf {
+    let path = match path.strip_prefix('~') {
+        Some(rest) => match dirs::home_dir() {
+            Some(home) => home.join(rest.trim_start_matches('/')),
+            None => PathBuf::from(path),
+        },
+        None => PathBuf::from(path),
+    };
+    let joined = if path.is_absolute() { path } else { working_dir.join(path) };
+
+    let mut normalized = PathBuf::new();
+    for component in joined.components() {
+        match component {
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            Component::CurDir => {}
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+fn is_inside(path: &Path, working_dir: &Path) -> bool {
+    path.starts_with(resolve(&working_dir.to_string_lossy(), Path::new("")))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -233,4 +510,53 @@ mod tests {
 
         assert!(RiskLevel::Yolo.allows(OperationRisk::Critical));
     }
+
+    #[test]
+    fn test_command_impact() {
+        let cwd = Path::new("/home/dev/project");
+
+        let impact = estimate_command_impact("rm -f build/a.o build/b.o && echo done > log.txt", cwd);
+        assert_eq!(impact.files_touched_count(), 3);
+        assert!(impact.files_touched.contains(&PathBuf::from("/home/dev/project/log.txt")));
+        assert!(!impact.reversible);
+        assert!(!impact.touches_outside_working_dir());
+
+        let impact = estimate_command_impact("echo 'x > y' >> notes.md 2>/dev/null", cwd);
+        assert_eq!(impact.files_touched, vec![PathBuf::from("/home/dev/project/notes.md")]);
+        assert!(impact.reversible);
+
+        let impact = estimate_command_impact("sudo cp config.toml /etc/app/config.toml", cwd);
+        assert_eq!(impact.outside_paths, vec![PathBuf::from("/etc/app/config.toml")]);
+        assert_eq!(impact.escalate(OperationRisk::Medium), OperationRisk::High);
+
+        let impact = estimate_command_impact("cat ../other/secrets.txt | grep key", cwd);
+        assert_eq!(impact.files_touched_count(), 0);
+        assert_eq!(impact.outside_paths, vec![PathBuf::from("/home/dev/other/secrets.txt")]);
+
+        let impact = estimate_command_impact("cargo test --workspace", cwd);
+        assert_eq!(impact, ImpactEstimate { reversible: true, ..Default::default() });
+    }
+
+    #[test]
+    fn test_step_impact() {
+        let cwd = Path::new("/repo");
+
+        let step = PlanStep::new("Delete old files", ActionType::DeleteFile)
+            .with_targets(["old.rs", "/repo/src/../legacy.rs"]);
+        let impact = estimate_impact(&step, cwd);
+        assert_eq!(impact.files_touched, vec![PathBuf::from("/repo/old.rs"), PathBuf::from("/repo/legacy.rs")]);
+        assert!(!impact.reversible);
+
+        let step = PlanStep::new("Edit", ActionType::EditFile).with_target("src/lib.rs");
+        assert!(estimate_impact(&step, cwd).reversible);
+
+        let step = PlanStep::new("Read", ActionType::ReadFile).with_target("/var/log/syslog");
+        let impact = estimate_impact(&step, cwd);
+        assert_eq!(impact.files_touched_count(), 0);
+        assert!(impact.touches_outside_working_dir());
+
+        let mut step = PlanStep::new("Clean", ActionType::ShellCommand);
+        step.context.insert("command".to_string(), "git clean -fdx".into());
+        assert!(!estimate_impact(&step, cwd).reversible);
+    }
 }
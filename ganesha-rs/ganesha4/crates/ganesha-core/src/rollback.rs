@@ -1,13 +1,17 @@
 //! Rollback system for undoing changes
 //!
 //! Provides checkpointing and rollback capabilities to safely
-//! undo changes made by Ganesha.
+//! undo changes made by Ganesha. Checkpoints hold file backups and a log
+//! of shell commands run since; rollback replays the commands' inverses
+//! newest first, then restores files.
 
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::planner::{PlanStep, RollbackStrategy};
+
 /// Rollback-specific errors
 #[derive(Error, Debug)]
 pub enum RollbackError {
@@ -20,6 +24,9 @@ pub enum RollbackError {
     #[error("Checkpoint not found: {0}")]
     NotFound(String),
 
+    #[error("Cannot roll back irreversible commands: {}", .0.join(", "))]
+    IrreversibleCommands(Vec<String>),
+
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
 
@@ -48,6 +55,33 @@ pub struct Checkpoint {
     pub parent_id: Option<String>,
     /// Metadata
     pub metadata: HashMap<String, String>,
+    /// Shell commands executed after this checkpoint, oldest first
+    #[serde(default)]
+    pub commands: Vec<CommandRecord>,
+}
+
+/// A shell command executed after a checkpoint, with its inverse
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandRecord {
+    /// The command that was executed
+    pub command: String,
+    /// Commands that undo it, run in order (empty if irreversible)
+    pub inverse: Vec<String>,
+    /// How many of `inverse` have already run, so a rollback retried after
+    /// a failure picks up where it stopped
+    #[serde(default)]
+    pub inverted: usize,
+    /// Directory the command ran in
+    pub working_dir: PathBuf,
+    /// When the command was executed
+    pub executed_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl CommandRecord {
+    /// Whether the command can be undone
+    pub fn is_reversible(&self) -> bool {
+        !self.inverse.is_empty()
+    }
 }
 
 /// Backup of a single file
@@ -75,6 +109,7 @@ impl Checkpoint {
             working_dir,
             parent_id: None,
             metadata: HashMap::new(),
+            commands: Vec::new(),
         }
     }
 
@@ -140,6 +175,11 @@ impl Checkpoint {
     pub fn add_metadata(&mut self, key: &str, value: &str) {
         self.metadata.insert(key.to_string(), value.to_string());
     }
+
+    /// Get number of commands logged
+    pub fn command_count(&self) -> usize {
+        self.commands.len()
+    }
 }
 
 /// Manages checkpoints and rollbacks
@@ -256,10 +296,47 @@ impl RollbackManager {
             checkpoint_id: checkpoint_id.to_string(),
             files_restored: Vec::new(),
             files_deleted: Vec::new(),
+            commands_reverted: Vec::new(),
             git_reset: false,
             success: true,
         };
 
+        // Commands logged on this checkpoint and every later one
+        let mut later: Vec<&Checkpoint> = self.checkpoints.values()
+            .filter(|c| c.created_at >= checkpoint.created_at)
+            .collect();
+        later.sort_by_key(|c| c.created_at);
+        let later_ids: Vec<String> = later.iter().map(|c| c.id.clone()).collect();
+
+        // Refuse before touching anything if some command can't be undone
+        let irreversible: Vec<String> = later.iter()
+            .flat_map(|c| c.commands.iter())
+            .filter(|c| !c.is_reversible())
+            .map(|c| c.command.clone())
+            .collect();
+        if !irreversible.is_empty() {
+            return Err(RollbackError::IrreversibleCommands(irreversible));
+        }
+
+        // Replay inverses, newest command first. Progress is saved after
+        // every inverse, so if one fails a retry skips what already ran.
+        for id in later_ids.iter().rev() {
+            while let Some(record) = self.checkpoints[id].commands.last().cloned() {
+                for inverse in &record.inverse[record.inverted.min(record.inverse.len())..] {
+                    run_inverse(inverse, &record.working_dir).await?;
+                    self.update_commands(id, |commands| {
+                        if let Some(last) = commands.last_mut() {
+                            last.inverted += 1;
+                        }
+                    }).await?;
+                }
+                self.update_commands(id, |commands| {
+                    commands.pop();
+                }).await?;
+                result.commands_reverted.push(record.command);
+            }
+        }
+
         // Restore files
         for backup in &checkpoint.files {
             let full_path = self.working_dir.join(&backup.path);
@@ -288,10 +365,11 @@ impl RollbackManager {
         }
 
         tracing::info!(
-            "Rolled back to checkpoint {}: {} files restored, {} files deleted",
+            "Rolled back to checkpoint {}: {} files restored, {} files deleted, {} commands reverted",
             checkpoint_id,
             result.files_restored.len(),
-            result.files_deleted.len()
+            result.files_deleted.len(),
+            result.commands_reverted.len()
         );
 
         Ok(result)
@@ -332,6 +410,47 @@ impl RollbackManager {
         self.rollback(&target_id).await
     }
 
+    /// Log an executed shell command against the most recent checkpoint
+    pub async fn record_command(&mut self, command: &str, inverse: Vec<String>) -> Result<()> {
+        let id = self.most_recent_checkpoint()
+            .map(|c| c.id.clone())
+            .ok_or_else(|| RollbackError::NotFound("No checkpoint to record command against".to_string()))?;
+        self.record_command_at(&id, command, inverse).await
+    }
+
+    /// Log an executed plan step, taking its inverse from a custom rollback strategy
+    pub async fn record_step(&mut self, step: &PlanStep) -> Result<()> {
+        let Some(command) = step.command() else {
+            return Ok(());
+        };
+        let inverse = match &step.rollback_strategy {
+            RollbackStrategy::Custom(commands) => commands.clone(),
+            _ => Vec::new(),
+        };
+        self.record_command(command, inverse).await
+    }
+
+    /// Log an executed shell command against a specific checkpoint
+    async fn record_command_at(&mut self, checkpoint_id: &str, command: &str, inverse: Vec<String>) -> Result<()> {
+        let record = CommandRecord {
+            command: command.to_string(),
+            inverse,
+            inverted: 0,
+            working_dir: self.working_dir.clone(),
+            executed_at: chrono::Utc::now(),
+        };
+        self.update_commands(checkpoint_id, |commands| commands.push(record)).await
+    }
+
+    /// Change a checkpoint's command log and save it
+    async fn update_commands(&mut self, checkpoint_id: &str, update: impl FnOnce(&mut Vec<CommandRecord>)) -> Result<()> {
+        let checkpoint = self.checkpoints.get_mut(checkpoint_id)
+            .ok_or_else(|| RollbackError::NotFound(checkpoint_id.to_string()))?;
+        update(&mut checkpoint.commands);
+        let checkpoint = checkpoint.clone();
+        self.save_checkpoint(&checkpoint).await
+    }
+
     /// Get a checkpoint by ID
     pub fn get_checkpoint(&self, id: &str) -> Option<&Checkpoint> {
         self.checkpoints.get(id)
@@ -440,6 +559,8 @@ pub struct RollbackResult {
     pub checkpoint_id: String,
     pub files_restored: Vec<PathBuf>,
     pub files_deleted: Vec<PathBuf>,
+    #[serde(default)]
+    pub commands_reverted: Vec<String>,
     pub git_reset: bool,
     pub success: bool,
 }
@@ -474,11 +595,21 @@ impl<'a> AutoCheckpoint<'a> {
         })
     }
 
+    /// Log a shell command run under this checkpoint
+    pub async fn record_command(&mut self, command: &str, inverse: Vec<String>) -> Result<()> {
+        self.manager.record_command_at(&self.checkpoint_id, command, inverse).await
+    }
+
     /// Commit the changes (don't rollback on drop)
     pub fn commit(mut self) {
         self.committed = true;
     }
 
+    /// Undo the logged commands and restore the backed-up files
+    pub async fn rollback(self) -> Result<RollbackResult> {
+        self.manager.rollback(&self.checkpoint_id).await
+    }
+
     /// Get the checkpoint ID
     pub fn checkpoint_id(&self) -> &str {
         &self.checkpoint_id
@@ -487,3 +618,114 @@ impl<'a> AutoCheckpoint<'a> {
 
 // Note: Can't implement async Drop, so manual cleanup is needed
 // Users should call commit() on success
+
+/// Run one inverse command through the shell
+async fn run_inverse(command: &str, working_dir: &Path) -> Result<()> {
+    let output = tokio::process::Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .current_dir(working_dir)
+        .output()
+        .await?;
+
+    if !output.status.success() {
+        return Err(RollbackError::RollbackFailed(format!(
+            "`{}` exited with {}: {}",
+            command,
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    async fn manager(temp_dir: &TempDir) -> RollbackManager {
+        let mut manager = RollbackManager::with_storage(
+            temp_dir.path().to_path_buf(),
+            temp_dir.path().join(".checkpoints"),
+        );
+        manager.initialize().await.unwrap();
+        manager
+    }
+
+    #[tokio::test]
+    async fn test_rollback_reverts_commands() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut manager = manager(&temp_dir).await;
+        let created = temp_dir.path().join("created.txt");
+
+        let mut auto = AutoCheckpoint::new(&mut manager, "before touch", &[]).await.unwrap();
+        let status = tokio::process::Command::new("sh")
+            .args(["-c", "touch created.txt"])
+            .current_dir(temp_dir.path())
+            .status()
+            .await
+            .unwrap();
+        assert!(status.success());
+        auto.record_command("touch created.txt", vec!["rm created.txt".to_string()])
+            .await
+            .unwrap();
+        assert!(created.exists());
+
+        let result = auto.rollback().await.unwrap();
+        assert!(!created.exists());
+        assert_eq!(result.commands_reverted, vec!["touch created.txt".to_string()]);
+
+        // The log is consumed, so a second rollback has nothing to replay
+        let id = result.checkpoint_id;
+        assert_eq!(manager.get_checkpoint(&id).unwrap().command_count(), 0);
+        assert!(manager.rollback(&id).await.unwrap().commands_reverted.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_rollback_refuses_irreversible_commands() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut manager = manager(&temp_dir).await;
+
+        let id = manager.create_checkpoint("start").await.unwrap();
+        manager.record_command("mkdir out", vec!["rmdir out".to_string()]).await.unwrap();
+        manager.record_command("curl -X POST https://example.com", Vec::new()).await.unwrap();
+
+        match manager.rollback(&id).await {
+            Err(RollbackError::IrreversibleCommands(commands)) => {
+                assert_eq!(commands, vec!["curl -X POST https://example.com".to_string()]);
+            }
+            other => panic!("expected IrreversibleCommands, got {:?}", other),
+        }
+        // Nothing was replayed
+        assert_eq!(manager.get_checkpoint(&id).unwrap().command_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_retried_rollback_skips_undone_steps() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut manager = manager(&temp_dir).await;
+        let log = temp_dir.path().join("undo.log");
+
+        let id = manager.create_checkpoint("start").await.unwrap();
+        manager.record_command("step 1", vec!["echo undo-1 >> undo.log".to_string()]).await.unwrap();
+        manager.record_command("step 2", vec!["test -e ready".to_string()]).await.unwrap();
+        manager.record_command(
+            "step 3",
+            vec!["echo undo-3a >> undo.log".to_string(), "echo undo-3b >> undo.log".to_string()],
+        ).await.unwrap();
+
+        // Step 3 is undone, then undoing step 2 fails
+        assert!(matches!(manager.rollback(&id).await, Err(RollbackError::RollbackFailed(_))));
+        assert_eq!(std::fs::read_to_string(&log).unwrap(), "undo-3a\nundo-3b\n");
+        assert_eq!(manager.get_checkpoint(&id).unwrap().command_count(), 2);
+
+        // The retry carries on from step 2 without undoing step 3 again,
+        // also after a reload from disk
+        std::fs::write(temp_dir.path().join("ready"), "").unwrap();
+        let mut manager = self::manager(&temp_dir).await;
+        let result = manager.rollback(&id).await.unwrap();
+        assert_eq!(result.commands_reverted, vec!["step 2".to_string(), "step 1".to_string()]);
+        assert_eq!(std::fs::read_to_string(&log).unwrap(), "undo-3a\nundo-3b\nundo-1\n");
+    }
+}
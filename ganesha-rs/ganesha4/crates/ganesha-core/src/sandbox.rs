@@ -6,8 +6,10 @@
 
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::process::Stdio;
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
+use tokio::io::AsyncWriteExt;
 
 /// Sandbox-specific errors
 #[derive(Error, Debug)]
@@ -29,6 +31,9 @@ pub enum SandboxError {
 
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
+
+    #[error("Manifest error: {0}")]
+    ManifestError(#[from] serde_json::Error),
 }
 
 pub type Result<T> = std::result::Result<T, SandboxError>;
@@ -388,7 +393,11 @@ impl Sandbox {
         Ok(diffs.join("\n"))
     }
 
-    /// Apply sandbox changes to the original project
+    /// Apply sandbox changes to the original project.
+    ///
+    /// Worktree sandboxes are applied as a patch, all or nothing: if it does
+    /// not apply cleanly the project is left untouched and the result lists
+    /// the conflicting paths.
     pub async fn apply_changes(&self) -> Result<ApplyResult> {
         if self.mode == SandboxMode::DryRun {
             return Ok(ApplyResult {
@@ -396,14 +405,20 @@ impl Sandbox {
                 files_created: Vec::new(),
                 files_deleted: Vec::new(),
                 success: true,
+                conflicts: Vec::new(),
             });
         }
 
+        if uses_worktree(self.mode) {
+            return self.apply_worktree().await;
+        }
+
         let mut result = ApplyResult {
             files_modified: Vec::new(),
             files_created: Vec::new(),
             files_deleted: Vec::new(),
             success: true,
+            conflicts: Vec::new(),
         };
 
         for relative_path in &self.modified_files {
@@ -431,30 +446,68 @@ impl Sandbox {
         Ok(result)
     }
 
+    /// Apply everything changed in the worktree (tracked or not) as one patch
+    async fn apply_worktree(&self) -> Result<ApplyResult> {
+        let mut result = ApplyResult {
+            files_modified: Vec::new(),
+            files_created: Vec::new(),
+            files_deleted: Vec::new(),
+            success: true,
+            conflicts: Vec::new(),
+        };
+
+        git(&self.sandbox_root, &["add", "-A"], None).await?;
+        let status = git(
+            &self.sandbox_root,
+            &["diff", "--cached", "--name-status", "--no-renames", "HEAD"],
+            None,
+        ).await?;
+
+        for line in String::from_utf8_lossy(&status.stdout).lines() {
+            let Some((kind, path)) = line.split_once('\t') else {
+                continue;
+            };
+            let path = PathBuf::from(path);
+            match kind {
+                "A" => result.files_created.push(path),
+                "D" => result.files_deleted.push(path),
+                _ => result.files_modified.push(path),
+            }
+        }
+
+        if result.files_created.is_empty() && result.files_modified.is_empty() && result.files_deleted.is_empty() {
+            return Ok(result);
+        }
+
+        let patch = git(
+            &self.sandbox_root,
+            &["diff", "--cached", "--binary", "--no-renames", "HEAD"],
+            None,
+        ).await?.stdout;
+
+        // Check first so a conflicting patch never half-applies
+        let check = git_unchecked(&self.original_root, &["apply", "--check", "-"], Some(&patch)).await?;
+        if !check.status.success() {
+            result.success = false;
+            result.conflicts = conflicting_paths(&String::from_utf8_lossy(&check.stderr));
+            tracing::warn!("Sandbox {} conflicts with the project: {:?}", self.id, result.conflicts);
+            return Ok(result);
+        }
+
+        git(&self.original_root, &["apply", "-"], Some(&patch)).await?;
+
+        tracing::info!(
+            "Applied {} changes from sandbox",
+            result.files_modified.len() + result.files_created.len() + result.files_deleted.len()
+        );
+        Ok(result)
+    }
+
     /// Discard sandbox without applying changes
     pub async fn discard(mut self) -> Result<()> {
         self.active = false;
 
-        match self.mode {
-            SandboxMode::GitWorktree => {
-                use tokio::process::Command;
-
-                // Remove the worktree
-                let _ = Command::new("git")
-                    .current_dir(&self.original_root)
-                    .args(["worktree", "remove", "--force"])
-                    .arg(&self.sandbox_root)
-                    .output()
-                    .await;
-            }
-            SandboxMode::FullIsolation | SandboxMode::Overlay => {
-                // Remove sandbox directory
-                let _ = tokio::fs::remove_dir_all(&self.sandbox_root).await;
-            }
-            SandboxMode::DryRun => {
-                // Nothing to clean up
-            }
-        }
+        remove_sandbox_dir(self.mode, &self.original_root, &self.sandbox_root).await;
 
         tracing::info!("Sandbox discarded: {}", self.id);
         Ok(())
@@ -478,12 +531,76 @@ pub struct ApplyResult {
     pub files_created: Vec<PathBuf>,
     pub files_deleted: Vec<PathBuf>,
     pub success: bool,
+    /// Paths that kept the changes from applying cleanly
+    #[serde(default)]
+    pub conflicts: Vec<PathBuf>,
 }
 
-/// Manages multiple sandboxes
+/// File (in the sandbox base directory) recording sandboxes on disk
+pub const SANDBOX_MANIFEST_FILE: &str = "ganesha-sandboxes.json";
+
+/// A sandbox recorded in the on-disk manifest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub id: String,
+    pub mode: SandboxMode,
+    pub original_root: PathBuf,
+    pub sandbox_root: PathBuf,
+    /// Process that created the sandbox
+    pub pid: u32,
+    /// Manager instance that created the sandbox
+    pub owner: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Apply `update` to the manifest at `path` under an exclusive lock,
+/// replacing the file atomically if it changed anything
+fn update_manifest_file<F>(path: &Path, update: F) -> Result<()>
+where
+    F: FnOnce(&mut Vec<ManifestEntry>) -> bool,
+{
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let lock = std::fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(path.with_extension("json.lock"))?;
+    lock.lock()?;
+
+    let mut entries: Vec<ManifestEntry> = match std::fs::read_to_string(path) {
+        Ok(content) => serde_json::from_str(&content)?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+        Err(e) => return Err(e.into()),
+    };
+    if !update(&mut entries) {
+        return Ok(());
+    }
+
+    let temp = path.with_extension(format!("json.{}.tmp", std::process::id()));
+    {
+        let mut file = std::fs::File::create(&temp)?;
+        std::io::Write::write_all(&mut file, serde_json::to_string_pretty(&entries)?.as_bytes())?;
+        file.sync_all()?;
+    }
+    if let Err(e) = std::fs::rename(&temp, path) {
+        let _ = std::fs::remove_file(&temp);
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+/// Manages multiple sandboxes.
+///
+/// Sandboxes are recorded in a manifest in the base directory so that ones
+/// left behind by a crashed process can be removed with
+/// [`SandboxManager::cleanup_orphans`].
 pub struct SandboxManager {
     config: SandboxConfig,
     sandboxes: HashMap<String, Sandbox>,
+    /// Identifies this manager's entries in the manifest
+    owner: String,
 }
 
 impl SandboxManager {
@@ -492,13 +609,75 @@ impl SandboxManager {
         Self {
             config,
             sandboxes: HashMap::new(),
+            owner: uuid::Uuid::new_v4().to_string(),
+        }
+    }
+
+    /// Path of the sandbox manifest
+    pub fn manifest_path(&self) -> PathBuf {
+        self.config.base_dir.clone()
+            .unwrap_or_else(std::env::temp_dir)
+            .join(SANDBOX_MANIFEST_FILE)
+    }
+
+    /// Read the manifest (empty if it does not exist)
+    pub async fn read_manifest(&self) -> Result<Vec<ManifestEntry>> {
+        match tokio::fs::read_to_string(self.manifest_path()).await {
+            Ok(content) => Ok(serde_json::from_str(&content)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(e.into()),
         }
     }
 
+    /// Read-modify-write the manifest. `update` returns whether it changed
+    /// anything.
+    ///
+    /// Other sessions share the manifest, so the update holds an exclusive
+    /// lock on a sidecar lock file throughout. The new manifest is written to
+    /// a temporary file and renamed over the old one, so a crash mid-write
+    /// leaves the previous version intact.
+    async fn update_manifest<F>(&self, update: F) -> Result<()>
+    where
+        F: FnOnce(&mut Vec<ManifestEntry>) -> bool + Send + 'static,
+    {
+        let path = self.manifest_path();
+        tokio::task::spawn_blocking(move || update_manifest_file(&path, update))
+            .await
+            .map_err(|e| SandboxError::FileError(format!("Manifest update panicked: {}", e)))?
+    }
+
+    async fn unregister(&self, id: &str) -> Result<()> {
+        let id = id.to_string();
+        self.update_manifest(move |entries| {
+            let before = entries.len();
+            entries.retain(|e| e.id != id);
+            entries.len() != before
+        })
+        .await
+    }
+
     /// Create a new sandbox
     pub async fn create_sandbox(&mut self, project_root: PathBuf) -> Result<String> {
         let sandbox = Sandbox::create(project_root, self.config.clone()).await?;
         let id = sandbox.id.clone();
+
+        if sandbox.mode != SandboxMode::DryRun {
+            let entry = ManifestEntry {
+                id: id.clone(),
+                mode: sandbox.mode,
+                original_root: sandbox.original_root.clone(),
+                sandbox_root: sandbox.sandbox_root.clone(),
+                pid: std::process::id(),
+                owner: self.owner.clone(),
+                created_at: sandbox.created_at,
+            };
+            self.update_manifest(move |entries| {
+                entries.push(entry);
+                true
+            })
+            .await?;
+        }
+
         self.sandboxes.insert(id.clone(), sandbox);
         Ok(id)
     }
@@ -513,12 +692,28 @@ impl SandboxManager {
         self.sandboxes.get_mut(id)
     }
 
-    /// Apply sandbox changes and remove it
+    /// Apply sandbox changes and remove it.
+    ///
+    /// If the changes conflict, the sandbox is kept so they are not lost.
     pub async fn apply_and_remove(&mut self, id: &str) -> Result<ApplyResult> {
         let sandbox = self.sandboxes.remove(id)
             .ok_or_else(|| SandboxError::NotFound(id.to_string()))?;
 
-        sandbox.apply_changes().await
+        let result = match sandbox.apply_changes().await {
+            Ok(result) => result,
+            Err(e) => {
+                self.sandboxes.insert(id.to_string(), sandbox);
+                return Err(e);
+            }
+        };
+
+        if result.success {
+            sandbox.discard().await?;
+            self.unregister(id).await?;
+        } else {
+            self.sandboxes.insert(id.to_string(), sandbox);
+        }
+        Ok(result)
     }
 
     /// Discard a sandbox
@@ -526,7 +721,45 @@ impl SandboxManager {
         let sandbox = self.sandboxes.remove(id)
             .ok_or_else(|| SandboxError::NotFound(id.to_string()))?;
 
-        sandbox.discard().await
+        sandbox.discard().await?;
+        self.unregister(id).await
+    }
+
+    /// Remove sandboxes recorded in the manifest that no live session owns:
+    /// their process has exited, or this manager no longer tracks them.
+    ///
+    /// Returns the IDs of the removed sandboxes.
+    pub async fn cleanup_orphans(&mut self) -> Result<Vec<String>> {
+        let orphans: Vec<ManifestEntry> = self
+            .read_manifest()
+            .await?
+            .into_iter()
+            .filter(|e| {
+                if e.owner == self.owner {
+                    !self.sandboxes.contains_key(&e.id)
+                } else {
+                    !process_alive(e.pid)
+                }
+            })
+            .collect();
+
+        for entry in &orphans {
+            tracing::info!("Removing orphaned sandbox {} at {:?}", entry.id, entry.sandbox_root);
+            remove_sandbox_dir(entry.mode, &entry.original_root, &entry.sandbox_root).await;
+        }
+
+        let ids: Vec<String> = orphans.into_iter().map(|e| e.id).collect();
+        if !ids.is_empty() {
+            // Only drop what was removed; other sessions may have added
+            // entries meanwhile
+            let removed = ids.clone();
+            self.update_manifest(move |entries| {
+                entries.retain(|e| !removed.contains(&e.id));
+                true
+            })
+            .await?;
+        }
+        Ok(ids)
     }
 
     /// List all active sandboxes
@@ -553,6 +786,117 @@ impl SandboxManager {
     }
 }
 
+/// Whether sandboxes in this mode are git worktrees
+fn uses_worktree(mode: SandboxMode) -> bool {
+    // Overlay falls back to a worktree on Linux
+    mode == SandboxMode::GitWorktree || (cfg!(target_os = "linux") && mode == SandboxMode::Overlay)
+}
+
+/// Remove a sandbox's files (and its worktree registration)
+async fn remove_sandbox_dir(mode: SandboxMode, original_root: &Path, sandbox_root: &Path) {
+    if mode == SandboxMode::DryRun {
+        return;
+    }
+
+    if uses_worktree(mode) {
+        let _ = tokio::process::Command::new("git")
+            .current_dir(original_root)
+            .args(["worktree", "remove", "--force"])
+            .arg(sandbox_root)
+            .output()
+            .await;
+    }
+
+    let _ = tokio::fs::remove_dir_all(sandbox_root).await;
+
+    if uses_worktree(mode) {
+        // Drop the registration if the directory was already gone
+        let _ = tokio::process::Command::new("git")
+            .current_dir(original_root)
+            .args(["worktree", "prune"])
+            .output()
+            .await;
+    }
+}
+
+/// Whether a process is still running. Assumes yes where this cannot be
+/// checked, so live sandboxes are never removed.
+fn process_alive(pid: u32) -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        Path::new("/proc").join(pid.to_string()).exists()
+    }
+
+    #[cfg(all(unix, not(target_os = "linux")))]
+    {
+        std::process::Command::new("kill")
+            .args(["-0", &pid.to_string()])
+            .stderr(Stdio::null())
+            .status()
+            .map(|s| s.success())
+            .unwrap_or(true)
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = pid;
+        true
+    }
+}
+
+/// Run git, returning its output whatever the exit status
+async fn git_unchecked(dir: &Path, args: &[&str], input: Option<&[u8]>) -> Result<std::process::Output> {
+    let mut child = tokio::process::Command::new("git")
+        .current_dir(dir)
+        .args(args)
+        .stdin(if input.is_some() { Stdio::piped() } else { Stdio::null() })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    if let (Some(input), Some(mut stdin)) = (input, child.stdin.take()) {
+        stdin.write_all(input).await?;
+    }
+
+    Ok(child.wait_with_output().await?)
+}
+
+/// Run git, failing on a non-zero exit status
+async fn git(dir: &Path, args: &[&str], input: Option<&[u8]>) -> Result<std::process::Output> {
+    let output = git_unchecked(dir, args, input).await?;
+    if !output.status.success() {
+        return Err(SandboxError::ExecutionFailed(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(output)
+}
+
+/// Paths named in `git apply --check` errors, e.g.
+/// `error: src/main.rs: patch does not apply`
+fn conflicting_paths(stderr: &str) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = Vec::new();
+    for line in stderr.lines() {
+        let Some(rest) = line.strip_prefix("error: ") else {
+            continue;
+        };
+        if let Some(location) = rest.strip_prefix("patch failed: ") {
+            // "path:line"
+            let path = location.rsplit_once(':').map(|(p, _)| p).unwrap_or(location);
+            if !paths.iter().any(|p| p == Path::new(path)) {
+                paths.push(PathBuf::from(path));
+            }
+        } else if let Some((path, _)) = rest.split_once(": ") {
+            if !paths.iter().any(|p| p == Path::new(path)) {
+                paths.push(PathBuf::from(path));
+            }
+        }
+    }
+    paths
+}
+
 /// Helper function to copy directory recursively
 async fn copy_dir_recursive(
     src: &Path,
@@ -613,3 +957,145 @@ fn simple_diff(original: &str, modified: &str) -> String {
 
     diff
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn run_git(dir: &Path, args: &[&str]) -> String {
+        let output = std::process::Command::new("git")
+            .current_dir(dir)
+            .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {:?}: {}", args, String::from_utf8_lossy(&output.stderr));
+        String::from_utf8_lossy(&output.stdout).to_string()
+    }
+
+    /// A repository with one committed file, plus a base dir for sandboxes
+    fn setup() -> (TempDir, TempDir, SandboxManager) {
+        let repo = TempDir::new().unwrap();
+        run_git(repo.path(), &["init", "-q"]);
+        std::fs::write(repo.path().join("notes.txt"), "one\ntwo\nthree\n").unwrap();
+        run_git(repo.path(), &["add", "."]);
+        run_git(repo.path(), &["commit", "-q", "-m", "init"]);
+
+        let base = TempDir::new().unwrap();
+        let manager = SandboxManager::new(SandboxConfig {
+            base_dir: Some(base.path().to_path_buf()),
+            ..Default::default()
+        });
+        (repo, base, manager)
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_orphans_removes_dead_entries() {
+        let (repo, base, mut manager) = setup();
+        let live_id = manager.create_sandbox(repo.path().to_path_buf()).await.unwrap();
+
+        // A worktree left behind by a process that no longer exists
+        let orphan_root = base.path().join("ganesha-sandbox-orphan");
+        run_git(repo.path(), &["worktree", "add", "-q", "-d", orphan_root.to_str().unwrap(), "HEAD"]);
+        let orphan = ManifestEntry {
+            id: "orphan".to_string(),
+            mode: SandboxMode::GitWorktree,
+            original_root: repo.path().to_path_buf(),
+            sandbox_root: orphan_root.clone(),
+            pid: u32::MAX,
+            owner: "crashed-session".to_string(),
+            created_at: chrono::Utc::now(),
+        };
+        manager
+            .update_manifest(move |entries| {
+                entries.push(orphan);
+                true
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(manager.cleanup_orphans().await.unwrap(), vec!["orphan".to_string()]);
+        assert!(!orphan_root.exists());
+        assert!(!run_git(repo.path(), &["worktree", "list"]).contains("ganesha-sandbox-orphan"));
+
+        let remaining = manager.read_manifest().await.unwrap();
+        assert_eq!(remaining.len(), 1);
+        assert_eq!(remaining[0].id, live_id);
+        assert!(manager.get(&live_id).unwrap().path().exists());
+
+        manager.discard(&live_id).await.unwrap();
+        assert!(manager.read_manifest().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_managers_keep_every_manifest_entry() {
+        let base = TempDir::new().unwrap();
+        let projects: Vec<TempDir> = (0..8).map(|_| TempDir::new().unwrap()).collect();
+        let config = SandboxConfig {
+            mode: SandboxMode::FullIsolation,
+            base_dir: Some(base.path().to_path_buf()),
+            ..Default::default()
+        };
+
+        let tasks: Vec<_> = projects
+            .iter()
+            .map(|project| {
+                let root = project.path().to_path_buf();
+                let config = config.clone();
+                tokio::spawn(async move {
+                    let mut manager = SandboxManager::new(config);
+                    manager.create_sandbox(root).await.unwrap()
+                })
+            })
+            .collect();
+        let mut created = Vec::new();
+        for task in tasks {
+            created.push(task.await.unwrap());
+        }
+
+        let manager = SandboxManager::new(config);
+        let mut recorded: Vec<String> = manager.read_manifest().await.unwrap().into_iter().map(|e| e.id).collect();
+        recorded.sort();
+        created.sort();
+        assert_eq!(recorded, created);
+
+        // Only the manifest and its lock file are left next to the sandboxes
+        let leftovers: Vec<_> = std::fs::read_dir(base.path())
+            .unwrap()
+            .filter_map(|e| e.ok())
+            .map(|e| e.file_name().to_string_lossy().into_owned())
+            .filter(|name| name.starts_with("ganesha-sandboxes"))
+            .collect();
+        assert_eq!(leftovers.len(), 2, "{:?}", leftovers);
+    }
+
+    #[tokio::test]
+    async fn test_worktree_apply_reports_conflicts() {
+        let (repo, _base, mut manager) = setup();
+        let id = manager.create_sandbox(repo.path().to_path_buf()).await.unwrap();
+        let sandbox = manager.get_mut(&id).unwrap();
+        sandbox.write_file(Path::new("notes.txt"), "one\nTWO\nthree\n").await.unwrap();
+        sandbox.write_file(Path::new("new.txt"), "fresh\n").await.unwrap();
+
+        // The project changed the same line meanwhile
+        std::fs::write(repo.path().join("notes.txt"), "one\n2\nthree\n").unwrap();
+
+        let result = manager.apply_and_remove(&id).await.unwrap();
+        assert!(!result.success);
+        assert_eq!(result.conflicts, vec![PathBuf::from("notes.txt")]);
+        assert_eq!(std::fs::read_to_string(repo.path().join("notes.txt")).unwrap(), "one\n2\nthree\n");
+        assert!(!repo.path().join("new.txt").exists());
+        assert!(manager.get(&id).is_some());
+
+        // Once the project is back in line the changes apply cleanly
+        std::fs::write(repo.path().join("notes.txt"), "one\ntwo\nthree\n").unwrap();
+        let result = manager.apply_and_remove(&id).await.unwrap();
+        assert!(result.success);
+        assert_eq!(result.files_modified, vec![PathBuf::from("notes.txt")]);
+        assert_eq!(result.files_created, vec![PathBuf::from("new.txt")]);
+        assert_eq!(std::fs::read_to_string(repo.path().join("notes.txt")).unwrap(), "one\nTWO\nthree\n");
+        assert!(manager.get(&id).is_none());
+        assert!(manager.read_manifest().await.unwrap().is_empty());
+    }
+}
@@ -28,8 +28,12 @@
 //!
 //! // Save session
 //! manager.save_session(&session)?;
+//!
+//! // After a crash, resume from a checkpoint (restoring files too)
+//! let resume = manager.resume_from_checkpoint(&id, &checkpoint_id, Some(&mut rollback)).await?;
 //! ```
 
+use crate::rollback::{RollbackError, RollbackManager, RollbackResult};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -58,6 +62,9 @@ pub enum SessionError {
 
     #[error("Session storage error: {0}")]
     StorageError(String),
+
+    #[error("Rollback error: {0}")]
+    RollbackError(#[from] RollbackError),
 }
 
 pub type Result<T> = std::result::Result<T, SessionError>;
@@ -200,6 +207,12 @@ pub struct Checkpoint {
     pub working_directory: PathBuf,
     /// Additional state to restore
     pub state: HashMap<String, serde_json::Value>,
+    /// Index of the first plan step not yet executed
+    #[serde(default)]
+    pub next_step_index: usize,
+    /// `RollbackManager` checkpoint holding the file state at this point
+    #[serde(default)]
+    pub rollback_checkpoint_id: Option<String>,
 }
 
 impl Checkpoint {
@@ -212,9 +225,23 @@ impl Checkpoint {
             timestamp: Utc::now(),
             working_directory,
             state: HashMap::new(),
+            next_step_index: 0,
+            rollback_checkpoint_id: None,
         }
     }
 
+    /// Set the index of the next plan step to execute
+    pub fn with_next_step(mut self, index: usize) -> Self {
+        self.next_step_index = index;
+        self
+    }
+
+    /// Link the rollback checkpoint that captures file state
+    pub fn with_rollback_checkpoint(mut self, id: impl Into<String>) -> Self {
+        self.rollback_checkpoint_id = Some(id.into());
+        self
+    }
+
     /// Add state to the checkpoint
     pub fn with_state(mut self, key: impl Into<String>, value: impl Serialize) -> Self {
         if let Ok(json_value) = serde_json::to_value(value) {
@@ -351,6 +378,12 @@ impl Session {
         self.checkpoints.last().unwrap()
     }
 
+    /// Add a prepared checkpoint (e.g. with plan progress or file state)
+    pub fn add_checkpoint(&mut self, checkpoint: Checkpoint) -> &Checkpoint {
+        self.checkpoints.push(checkpoint);
+        self.checkpoints.last().unwrap()
+    }
+
     /// Get all checkpoints
     pub fn checkpoints(&self) -> &[Checkpoint] {
         &self.checkpoints
@@ -577,6 +610,11 @@ impl SessionManager {
         Ok(self.sessions.get(id).unwrap())
     }
 
+    /// List the checkpoints a session can be resumed from, oldest first
+    pub fn list_checkpoints(&mut self, session_id: &str) -> Result<&[Checkpoint]> {
+        Ok(self.load_session(session_id)?.checkpoints())
+    }
+
     /// Delete a session
     pub fn delete_session(&mut self, id: &str) -> Result<()> {
         self.sessions.remove(id);
@@ -725,6 +763,77 @@ impl SessionManager {
         self.sessions.insert(id.to_string(), session);
         Ok(())
     }
+
+    /// Resume a session from one of its checkpoints, e.g. after a crash.
+    ///
+    /// Messages after the checkpoint are dropped and the session becomes the
+    /// active one. If the checkpoint is linked to a rollback checkpoint and a
+    /// `RollbackManager` is given, files are first put back into the state
+    /// they had at the checkpoint - undoing later writes, or re-applying
+    /// content that a later rollback removed. Files are restored before the
+    /// session is touched, so a failed restore leaves the session as it was.
+    pub async fn resume_from_checkpoint(
+        &mut self,
+        session_id: &str,
+        checkpoint_id: &str,
+        rollback: Option<&mut RollbackManager>,
+    ) -> Result<ResumePoint> {
+        self.load_session_async(session_id).await?;
+        let checkpoint = self
+            .sessions
+            .get(session_id)
+            .and_then(|s| s.get_checkpoint(checkpoint_id))
+            .cloned()
+            .ok_or_else(|| SessionError::CheckpointNotFound(checkpoint_id.to_string()))?;
+
+        let files = match (&checkpoint.rollback_checkpoint_id, rollback) {
+            (Some(id), Some(manager)) => Some(manager.rollback(id).await?),
+            (Some(id), None) => {
+                warn!(
+                    "Checkpoint {} has file state ({}) but no rollback manager was given; files left as they are",
+                    checkpoint_id, id
+                );
+                None
+            }
+            (None, _) => None,
+        };
+
+        let session = self.sessions.get_mut(session_id).unwrap();
+        session.restore_to_checkpoint(checkpoint_id)?;
+        session.set_status(SessionStatus::Active);
+        let session = session.clone();
+        self.save_session_async(&session).await?;
+        self.active_session_id = Some(session_id.to_string());
+
+        info!(
+            "Resumed session {} from checkpoint {} at step {}",
+            session_id, checkpoint_id, checkpoint.next_step_index
+        );
+
+        Ok(ResumePoint {
+            session_id: session_id.to_string(),
+            checkpoint_id: checkpoint.id,
+            message_count: session.message_count(),
+            next_step_index: checkpoint.next_step_index,
+            state: checkpoint.state,
+            files,
+        })
+    }
+}
+
+/// Where a resumed session picks up (see `SessionManager::resume_from_checkpoint`)
+#[derive(Debug, Clone)]
+pub struct ResumePoint {
+    pub session_id: String,
+    pub checkpoint_id: String,
+    /// Messages kept in the conversation
+    pub message_count: usize,
+    /// Index of the first plan step to execute
+    pub next_step_index: usize,
+    /// Working state saved with the checkpoint
+    pub state: HashMap<String, serde_json::Value>,
+    /// Files restored to the checkpoint's state, if any
+    pub files: Option<RollbackResult>,
 }
 
 #[cfg(test)]
@@ -818,6 +927,59 @@ mod tests {
         assert_eq!(summary.checkpoint_count, 0);
     }
 
+    #[tokio::test]
+    async fn test_resume_from_checkpoint() {
+        let temp_dir = TempDir::new().unwrap();
+        let work_dir = temp_dir.path().join("project");
+        std::fs::create_dir_all(&work_dir).unwrap();
+        let file = work_dir.join("main.rs");
+        std::fs::write(&file, "fn main() {}").unwrap();
+
+        let mut rollback =
+            RollbackManager::with_storage(work_dir.clone(), temp_dir.path().join("checkpoints"));
+        rollback.initialize().await.unwrap();
+        let rollback_id = rollback
+            .create_checkpoint_for_files("before step 2", &[PathBuf::from("main.rs")])
+            .await
+            .unwrap();
+
+        let mut manager = SessionManager::new(temp_dir.path().join("sessions")).unwrap();
+        let session = manager.create_session(&work_dir).unwrap();
+        let session_id = session.id.clone();
+        session.add_message(Message::user("Refactor main"));
+        session.add_message(Message::assistant("Step 1 done"));
+        let checkpoint = Checkpoint::new("After step 1", 2, work_dir.clone())
+            .with_next_step(1)
+            .with_state("phase", "editing")
+            .with_rollback_checkpoint(&rollback_id);
+        let checkpoint_id = session.add_checkpoint(checkpoint).id.clone();
+        session.add_message(Message::assistant("Step 2 half done"));
+        let session = session.clone();
+        manager.save_session(&session).unwrap();
+        std::fs::write(&file, "fn main() { broken").unwrap();
+
+        // Simulate a restart
+        let mut manager = SessionManager::new(temp_dir.path().join("sessions")).unwrap();
+        assert_eq!(manager.list_checkpoints(&session_id).unwrap().len(), 1);
+
+        let resume = manager
+            .resume_from_checkpoint(&session_id, &checkpoint_id, Some(&mut rollback))
+            .await
+            .unwrap();
+
+        assert_eq!(resume.next_step_index, 1);
+        assert_eq!(resume.message_count, 2);
+        assert_eq!(resume.state["phase"], "editing");
+        assert_eq!(resume.files.unwrap().files_restored, vec![PathBuf::from("main.rs")]);
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "fn main() {}");
+        assert_eq!(manager.active_session().unwrap().message_count(), 2);
+
+        assert!(matches!(
+            manager.resume_from_checkpoint(&session_id, "missing", None).await,
+            Err(SessionError::CheckpointNotFound(_))
+        ));
+    }
+
     #[test]
     fn test_list_sessions() {
         let temp_dir = TempDir::new().unwrap();
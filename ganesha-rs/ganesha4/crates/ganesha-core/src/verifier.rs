@@ -90,6 +90,8 @@ pub enum CheckType {
     FileExists,
     /// Build verification
     Build,
+    /// `cargo check` diagnostics for Rust projects
+    CargoCheck,
     /// Custom check
     Custom(String),
 }
@@ -294,6 +296,10 @@ pub struct VerificationContext {
     pub build_command: Option<String>,
     /// Lint command to use
     pub lint_command: Option<String>,
+    /// Package to limit `cargo check` to (`-p`)
+    pub cargo_package: Option<String>,
+    /// Whether warnings fail verification
+    pub fail_on_warnings: bool,
 }
 
 impl Default for VerificationContext {
@@ -311,6 +317,8 @@ impl Default for VerificationContext {
             test_command: None,
             build_command: None,
             lint_command: None,
+            cargo_package: None,
+            fail_on_warnings: false,
         }
     }
 }
@@ -362,6 +370,18 @@ impl VerificationContext {
         self
     }
 
+    /// Limit `cargo check` to one package
+    pub fn with_cargo_package(mut self, package: impl Into<String>) -> Self {
+        self.cargo_package = Some(package.into());
+        self
+    }
+
+    /// Fail verification on warnings, not just errors
+    pub fn with_fail_on_warnings(mut self, enabled: bool) -> Self {
+        self.fail_on_warnings = enabled;
+        self
+    }
+
     /// Disable tests
     pub fn no_tests(mut self) -> Self {
         self.run_tests = false;
@@ -800,6 +820,53 @@ impl Verifier for StandardVerifier {
             }
         }
 
+        // Run cargo check
+        if context.enabled_checks.contains(&CheckType::CargoCheck) {
+            if self.detect_project_type(&context.working_directory).as_deref() == Some("rust") {
+                result.record_check(CheckType::CargoCheck);
+
+                let mut cmd_parts: Vec<String> = vec![
+                    "cargo".to_string(),
+                    "check".to_string(),
+                    "--message-format=json".to_string(),
+                ];
+                if let Some(ref package) = context.cargo_package {
+                    cmd_parts.push("-p".to_string());
+                    cmd_parts.push(package.clone());
+                }
+
+                debug!("Running {}", cmd_parts.join(" "));
+                match self
+                    .run_command(&cmd_parts, &context.working_directory, context.timeout)
+                    .await
+                {
+                    Ok((success, output)) => {
+                        let issues = parse_cargo_diagnostics(&output);
+                        if !success && !issues.iter().any(|i| i.is_error()) {
+                            // Failed before compiling, e.g. a broken manifest
+                            result.add_issue(
+                                VerificationIssue::new(
+                                    CheckType::CargoCheck,
+                                    IssueSeverity::Error,
+                                    "cargo check failed",
+                                )
+                                .with_raw_output(output),
+                            );
+                        }
+                        for issue in issues {
+                            result.add_issue(issue);
+                        }
+                    }
+                    Err(e) => {
+                        warn!("cargo check failed to run: {}", e);
+                        result.skip_check(CheckType::CargoCheck, &e.to_string());
+                    }
+                }
+            } else {
+                result.skip_check(CheckType::CargoCheck, "Not a Rust project");
+            }
+        }
+
         // Run tests
         if context.run_tests && context.enabled_checks.contains(&CheckType::UnitTests) {
             let test_issues = self.run_tests(context).await?;
@@ -809,6 +876,10 @@ impl Verifier for StandardVerifier {
             }
         }
 
+        if context.fail_on_warnings && result.warning_count() > 0 {
+            result.status = VerificationStatus::Failed;
+        }
+
         result.duration = start.elapsed();
         Ok(result)
     }
@@ -1002,6 +1073,81 @@ pub async fn verify_file_matches(path: &Path, expected: &str) -> bool {
     }
 }
 
+/// Parse `cargo check --message-format=json` output into issues.
+///
+/// rustc levels map to severities: `error` to Error, an internal compiler
+/// error to Critical, `warning` to Warning, `note`/`help` to Info. Lines
+/// that are not compiler messages (including plain stderr) are ignored, as
+/// are the "aborting due to" and "N warnings emitted" summaries.
+pub fn parse_cargo_diagnostics(output: &str) -> Vec<VerificationIssue> {
+    let mut issues = Vec::new();
+
+    for line in output.lines() {
+        let Ok(value) = serde_json::from_str::<serde_json::Value>(line.trim()) else {
+            continue;
+        };
+        if value["reason"] != "compiler-message" {
+            continue;
+        }
+        let message = &value["message"];
+        let text = message["message"].as_str().unwrap_or_default();
+        let spans = message["spans"].as_array().map(Vec::as_slice).unwrap_or_default();
+
+        let severity = match message["level"].as_str().unwrap_or_default() {
+            "error: internal compiler error" => IssueSeverity::Critical,
+            "error" => IssueSeverity::Error,
+            "warning" => IssueSeverity::Warning,
+            "note" | "help" => IssueSeverity::Info,
+            _ => continue,
+        };
+        if spans.is_empty()
+            && (text.starts_with("aborting due to")
+                || (severity == IssueSeverity::Warning && text.contains("warning") && text.contains("emitted")))
+        {
+            continue;
+        }
+
+        let text = match message["code"]["code"].as_str() {
+            Some(code) => format!("{}: {}", code, text),
+            None => text.to_string(),
+        };
+        let mut issue = VerificationIssue::new(CheckType::CargoCheck, severity, text);
+
+        let primary = spans
+            .iter()
+            .find(|s| s["is_primary"].as_bool().unwrap_or(false))
+            .or_else(|| spans.first());
+        if let Some(span) = primary {
+            if let Some(file) = span["file_name"].as_str() {
+                issue = issue.with_file(file);
+            }
+            if let Some(line) = span["line_start"].as_u64() {
+                issue = issue.with_location(line as usize, span["column_start"].as_u64().map(|c| c as usize));
+            }
+        }
+
+        let children = message["children"].as_array().map(Vec::as_slice).unwrap_or_default();
+        if let Some(help) = children.iter().find(|c| c["level"] == "help") {
+            let replacement = help["spans"]
+                .as_array()
+                .and_then(|spans| spans.iter().find_map(|s| s["suggested_replacement"].as_str()));
+            let help_text = help["message"].as_str().unwrap_or_default();
+            issue = issue.with_suggestion(match replacement {
+                Some(replacement) => format!("{}: `{}`", help_text, replacement),
+                None => help_text.to_string(),
+            });
+        }
+
+        if let Some(rendered) = message["rendered"].as_str() {
+            issue = issue.with_raw_output(rendered);
+        }
+
+        issues.push(issue);
+    }
+
+    issues
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1080,4 +1226,57 @@ mod tests {
         let temp_dir = TempDir::new().unwrap();
         assert!(verifier.detect_project_type(temp_dir.path()).is_none());
     }
+
+    #[test]
+    fn test_parse_cargo_diagnostics() {
+        let output = [
+            r#"{"reason":"compiler-artifact","package_id":"demo 0.1.0"}"#,
+            r#"{"reason":"compiler-message","message":{"message":"unused variable: `x`","code":{"code":"unused_variables"},"level":"warning","spans":[{"file_name":"src/lib.rs","line_start":2,"column_start":9,"is_primary":true}],"children":[{"message":"if this is intentional, prefix it with an underscore","level":"help","spans":[{"suggested_replacement":"_x"}]}],"rendered":"warning: unused variable: `x`"}}"#,
+            r#"{"reason":"compiler-message","message":{"message":"mismatched types","code":{"code":"E0308"},"level":"error","spans":[{"file_name":"src/other.rs","line_start":1,"column_start":1,"is_primary":false},{"file_name":"src/lib.rs","line_start":5,"column_start":13,"is_primary":true}],"children":[],"rendered":"error[E0308]: mismatched types"}}"#,
+            r#"{"reason":"compiler-message","message":{"message":"aborting due to 1 previous error","code":null,"level":"error","spans":[],"children":[],"rendered":"error: aborting due to 1 previous error"}}"#,
+            "error: could not compile `demo` (lib) due to 1 previous error",
+        ]
+        .join("\n");
+
+        let issues = parse_cargo_diagnostics(&output);
+        assert_eq!(issues.len(), 2);
+
+        assert_eq!(issues[0].severity, IssueSeverity::Warning);
+        assert_eq!(issues[0].check_type, CheckType::CargoCheck);
+        assert_eq!(issues[0].file, Some(PathBuf::from("src/lib.rs")));
+        assert_eq!((issues[0].line, issues[0].column), (Some(2), Some(9)));
+        assert!(issues[0].suggestion.as_deref().unwrap().contains("`_x`"));
+
+        assert_eq!(issues[1].severity, IssueSeverity::Error);
+        assert_eq!(issues[1].message, "E0308: mismatched types");
+        assert_eq!((issues[1].line, issues[1].column), (Some(5), Some(13)));
+    }
+
+    #[tokio::test]
+    async fn test_cargo_check_warnings_only() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(
+            temp_dir.path().join("Cargo.toml"),
+            "[package]\nname = \"warn-only\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[workspace]\n",
+        )
+        .unwrap();
+        std::fs::create_dir(temp_dir.path().join("src")).unwrap();
+        std::fs::write(temp_dir.path().join("src/lib.rs"), "fn unused() {}\n").unwrap();
+
+        let verifier = StandardVerifier::new();
+        let execution = ExecutionResult::success(StepId::new(), Duration::from_millis(1));
+        let mut context = VerificationContext::new(temp_dir.path())
+            .no_tests()
+            .with_cargo_package("warn-only");
+        context.enabled_checks = vec![CheckType::CargoCheck];
+
+        let result = verifier.verify(&execution, &context).await.unwrap();
+        assert_eq!(result.status, VerificationStatus::PassedWithWarnings);
+        assert!(result.warning_count() > 0);
+        assert_eq!(result.error_count(), 0);
+
+        let strict = context.with_fail_on_warnings(true);
+        let result = verifier.verify(&execution, &strict).await.unwrap();
+        assert!(!result.passed());
+    }
 }
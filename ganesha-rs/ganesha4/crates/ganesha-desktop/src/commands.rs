@@ -1,6 +1,22 @@
 //! IPC commands for Tauri frontend communication
+//!
+//! Live status is pushed to the webview as `FrontendEvent`s. Any thread can
+//! emit through an `EventEmitter`, including the synchronous hotkey handler;
+//! emitted events are queued on a channel and the main loop fans them out
+//! with `EventHub::dispatch_pending`. Each subscriber names the event kinds it
+//! wants in an `EventFilter` and gets its own channel, so an event only
+//! reaches subscribers that asked for it. The frontend sends
+//! `Command::SubscribeEvents` once and then listens on `FRONTEND_EVENT`.
 
+use crate::state::AppState;
 use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+
+/// Tauri event name that all `FrontendEvent`s are emitted on
+pub const FRONTEND_EVENT: &str = "ganesha://event";
+
+/// Events buffered per subscriber before new ones are dropped for it
+const EVENT_BUFFER: usize = 256;
 
 /// Commands that can be invoked from the frontend
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -50,6 +66,11 @@ pub enum Command {
     DisconnectMcp { server: String },
     /// List MCP servers
     ListMcpServers,
+    /// Start receiving the `FrontendEvent`s matching `filter` on `FRONTEND_EVENT`
+    SubscribeEvents {
+        #[serde(default)]
+        filter: EventFilter,
+    },
 }
 
 /// Responses sent back to the frontend
@@ -78,6 +99,8 @@ pub enum Response {
     CommandOutput { stdout: String, stderr: String, exit_code: i32 },
     /// MCP server status
     McpStatus { servers: Vec<McpServerInfo> },
+    /// Event subscription opened, with the state to render until the first event
+    Subscribed { event: String, state: AppState },
 }
 
 /// Events emitted to the frontend
@@ -112,6 +135,281 @@ pub enum Event {
     SessionChanged { session_id: String },
 }
 
+/// Live status pushed to the frontend
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "payload")]
+pub enum FrontendEvent {
+    /// Full state snapshot
+    StateChanged { state: AppState },
+    /// Voice listening started or stopped
+    Listening { active: bool },
+    /// Assistant started or stopped speaking
+    Speaking { active: bool },
+    /// A request started or finished executing
+    Executing { active: bool },
+    /// Border overlay shown or hidden
+    Border { visible: bool },
+    /// Automation paused or resumed
+    Paused { paused: bool },
+    /// Backend connection changed
+    Connection { connected: bool },
+    /// Progress of the current task
+    TaskProgress(TaskProgress),
+    /// Event from the voice system
+    Voice(VoiceEvent),
+}
+
+impl FrontendEvent {
+    /// Which kind of event this is, for filtering
+    pub fn kind(&self) -> EventKind {
+        match self {
+            FrontendEvent::StateChanged { .. } => EventKind::State,
+            FrontendEvent::Listening { .. } => EventKind::Listening,
+            FrontendEvent::Speaking { .. } => EventKind::Speaking,
+            FrontendEvent::Executing { .. } => EventKind::Executing,
+            FrontendEvent::Border { .. } => EventKind::Border,
+            FrontendEvent::Paused { .. } => EventKind::Paused,
+            FrontendEvent::Connection { .. } => EventKind::Connection,
+            FrontendEvent::TaskProgress(_) => EventKind::TaskProgress,
+            FrontendEvent::Voice(_) => EventKind::Voice,
+        }
+    }
+
+    /// Events describing what changed between two states
+    pub fn diff(old: &AppState, new: &AppState) -> Vec<FrontendEvent> {
+        let mut events = Vec::new();
+        if old.is_listening != new.is_listening {
+            events.push(FrontendEvent::Listening { active: new.is_listening });
+        }
+        if old.is_processing != new.is_processing {
+            events.push(FrontendEvent::Executing { active: new.is_processing });
+        }
+        if old.border_visible != new.border_visible {
+            events.push(FrontendEvent::Border { visible: new.border_visible });
+        }
+        if old.paused != new.paused {
+            events.push(FrontendEvent::Paused { paused: new.paused });
+        }
+        if old.is_connected != new.is_connected {
+            events.push(FrontendEvent::Connection { connected: new.is_connected });
+        }
+        events
+    }
+}
+
+impl From<VoiceEvent> for FrontendEvent {
+    fn from(event: VoiceEvent) -> Self {
+        match event {
+            VoiceEvent::AssistantStartedSpeaking => FrontendEvent::Speaking { active: true },
+            VoiceEvent::AssistantFinishedSpeaking | VoiceEvent::UserInterrupted => {
+                FrontendEvent::Speaking { active: false }
+            }
+            other => FrontendEvent::Voice(other),
+        }
+    }
+}
+
+/// Kinds of `FrontendEvent`, one per variant
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EventKind {
+    /// Full state snapshots
+    State,
+    Listening,
+    Speaking,
+    Executing,
+    Border,
+    Paused,
+    Connection,
+    TaskProgress,
+    Voice,
+}
+
+/// The event kinds a subscriber wants. The default accepts everything.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventFilter {
+    /// Accepted kinds; `None` accepts all
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kinds: Option<Vec<EventKind>>,
+}
+
+impl EventFilter {
+    /// Accept every event
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    /// Accept only the given kinds
+    pub fn only(kinds: impl IntoIterator<Item = EventKind>) -> Self {
+        Self {
+            kinds: Some(kinds.into_iter().collect()),
+        }
+    }
+
+    /// Whether events of `kind` pass the filter
+    pub fn accepts(&self, kind: EventKind) -> bool {
+        self.kinds.as_ref().is_none_or(|kinds| kinds.contains(&kind))
+    }
+}
+
+/// Progress of the task currently being executed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaskProgress {
+    pub task: String,
+    pub step: usize,
+    pub total: usize,
+    pub message: Option<String>,
+}
+
+/// Voice system events, mirroring `ganesha_voice::VoiceEvent` so the desktop
+/// crate does not pull in the audio stack
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum VoiceEvent {
+    /// Voice system initialized, with the speech-to-text backend in use (if any)
+    Initialized { stt_backend: Option<String> },
+    /// Listening started
+    ListeningStarted,
+    /// Listening stopped
+    ListeningStopped,
+    /// Voice activity detected
+    VoiceActivityDetected,
+    /// User finished speaking
+    UserFinishedSpeaking { text: String },
+    /// Assistant started speaking
+    AssistantStartedSpeaking,
+    /// Assistant finished speaking
+    AssistantFinishedSpeaking,
+    /// User interrupted
+    UserInterrupted,
+    /// Audio level update
+    AudioLevel { level: f32 },
+    /// Error occurred
+    Error { message: String },
+}
+
+/// Cheap, cloneable handle for emitting events from any thread
+#[derive(Debug, Clone)]
+pub struct EventEmitter {
+    sender: mpsc::UnboundedSender<FrontendEvent>,
+}
+
+impl EventEmitter {
+    /// Queue an event for the frontend. Never blocks, so it is safe to call
+    /// from hotkey handlers and other non-async code.
+    pub fn emit(&self, event: impl Into<FrontendEvent>) {
+        // The receiver only goes away on shutdown
+        let _ = self.sender.send(event.into());
+    }
+}
+
+/// A subscriber's filter and the channel its events go to
+struct Subscriber {
+    filter: EventFilter,
+    sender: mpsc::Sender<FrontendEvent>,
+}
+
+/// Collects events from all emitters and fans them out to subscribers
+pub struct EventHub {
+    sender: mpsc::UnboundedSender<FrontendEvent>,
+    receiver: mpsc::UnboundedReceiver<FrontendEvent>,
+    subscribers: Vec<Subscriber>,
+}
+
+impl EventHub {
+    /// Create a new event hub
+    pub fn new() -> Self {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        Self {
+            sender,
+            receiver,
+            subscribers: Vec::new(),
+        }
+    }
+
+    /// Handle for emitting events
+    pub fn emitter(&self) -> EventEmitter {
+        EventEmitter {
+            sender: self.sender.clone(),
+        }
+    }
+
+    /// Receive the events matching `filter` dispatched from now on. If the
+    /// filter accepts state snapshots, the new subscriber (and only it)
+    /// starts with a snapshot of `state`.
+    pub fn subscribe(
+        &mut self,
+        filter: EventFilter,
+        state: &AppState,
+    ) -> mpsc::Receiver<FrontendEvent> {
+        let (sender, receiver) = mpsc::channel(EVENT_BUFFER);
+        if filter.accepts(EventKind::State) {
+            // The channel is new and empty, so this cannot fail
+            let _ = sender.try_send(FrontendEvent::StateChanged {
+                state: state.clone(),
+            });
+        }
+        self.subscribers.push(Subscriber { filter, sender });
+        receiver
+    }
+
+    /// Number of active subscribers
+    pub fn subscriber_count(&self) -> usize {
+        self.subscribers
+            .iter()
+            .filter(|subscriber| !subscriber.sender.is_closed())
+            .count()
+    }
+
+    /// Fan queued events out to the subscribers whose filter accepts them.
+    /// Call from the main loop. Returns how many were dispatched.
+    pub fn dispatch_pending(&mut self) -> usize {
+        let mut dispatched = 0;
+        while let Ok(event) = self.receiver.try_recv() {
+            let kind = event.kind();
+            // No subscribers yet is not an error; the event is dropped
+            self.subscribers.retain(|subscriber| {
+                if !subscriber.filter.accepts(kind) {
+                    return !subscriber.sender.is_closed();
+                }
+                match subscriber.sender.try_send(event.clone()) {
+                    Ok(()) => true,
+                    Err(mpsc::error::TrySendError::Full(_)) => {
+                        tracing::warn!("Frontend event subscriber full, dropped {:?} event", kind);
+                        true
+                    }
+                    Err(mpsc::error::TrySendError::Closed(_)) => false,
+                }
+            });
+            dispatched += 1;
+        }
+        dispatched
+    }
+}
+
+impl Default for EventHub {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Forward a subscription to the webview until the hub shuts down.
+///
+/// In Tauri:
+/// ```ignore
+/// let rx = app.subscribe_events(EventFilter::all());
+/// tauri::async_runtime::spawn(forward_events(rx, move |event| {
+///     let _ = handle.emit(FRONTEND_EVENT, event);
+/// }));
+/// ```
+pub async fn forward_events<F>(mut receiver: mpsc::Receiver<FrontendEvent>, mut sink: F)
+where
+    F: FnMut(&FrontendEvent),
+{
+    while let Some(event) = receiver.recv().await {
+        sink(&event);
+    }
+}
+
 /// Model information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ModelInfo {
@@ -177,7 +475,7 @@ impl CommandHandler for DefaultCommandHandler {
             Command::GetState => {
                 // Return current state
                 Response::State {
-                    state: crate::state::AppState::new(),
+                    state: AppState::new(),
                 }
             }
             Command::SendMessage { content } => {
@@ -186,6 +484,13 @@ impl CommandHandler for DefaultCommandHandler {
                     data: Some(serde_json::json!({"status": "processing", "message": content})),
                 }
             }
+            Command::SubscribeEvents { filter: _ } => {
+                // In Tauri: forward_events(app.subscribe_events(filter), |e| app.emit(FRONTEND_EVENT, e))
+                Response::Subscribed {
+                    event: FRONTEND_EVENT.to_string(),
+                    state: AppState::new(),
+                }
+            }
             Command::Cancel => {
                 // Cancel current operation
                 Response::Success { data: None }
@@ -206,3 +511,70 @@ impl Default for DefaultCommandHandler {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_emit_from_sync_thread_reaches_subscribers() {
+        let mut hub = EventHub::new();
+        let state = AppState::new();
+        let mut all = hub.subscribe(EventFilter::all(), &state);
+        let mut speaking = hub.subscribe(EventFilter::only([EventKind::Speaking]), &state);
+
+        let emitter = hub.emitter();
+        std::thread::spawn(move || {
+            emitter.emit(FrontendEvent::Listening { active: true });
+            emitter.emit(VoiceEvent::AssistantStartedSpeaking);
+        })
+        .join()
+        .unwrap();
+
+        assert_eq!(hub.dispatch_pending(), 2);
+        assert_eq!(all.try_recv().unwrap(), FrontendEvent::StateChanged { state });
+        assert_eq!(all.try_recv().unwrap(), FrontendEvent::Listening { active: true });
+        assert_eq!(all.try_recv().unwrap(), FrontendEvent::Speaking { active: true });
+        assert_eq!(speaking.try_recv().unwrap(), FrontendEvent::Speaking { active: true });
+        assert!(speaking.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_snapshot_goes_only_to_new_subscriber() {
+        let mut hub = EventHub::new();
+        let mut state = AppState::new();
+        let mut first = hub.subscribe(EventFilter::all(), &state);
+        assert!(matches!(first.try_recv(), Ok(FrontendEvent::StateChanged { .. })));
+
+        state.paused = true;
+        let mut second = hub.subscribe(EventFilter::all(), &state);
+        hub.dispatch_pending();
+        assert!(first.try_recv().is_err());
+        assert_eq!(second.try_recv().unwrap(), FrontendEvent::StateChanged { state });
+
+        drop(first);
+        hub.emitter().emit(FrontendEvent::Paused { paused: false });
+        hub.dispatch_pending();
+        assert_eq!(hub.subscriber_count(), 1);
+    }
+
+    #[test]
+    fn test_diff_and_serialization() {
+        let old = AppState::new();
+        let mut new = old.clone();
+        new.border_visible = true;
+        new.paused = true;
+
+        let events = FrontendEvent::diff(&old, &new);
+        assert_eq!(
+            events,
+            vec![
+                FrontendEvent::Border { visible: true },
+                FrontendEvent::Paused { paused: true },
+            ]
+        );
+
+        let json = serde_json::to_value(&events[0]).unwrap();
+        assert_eq!(json, serde_json::json!({"type": "Border", "payload": {"visible": true}}));
+    }
+}
@@ -38,6 +38,12 @@ pub struct WindowConfig {
     pub width: u32,
     /// Window height
     pub height: u32,
+    /// Last window x position (None = centered)
+    #[serde(default)]
+    pub x: Option<i32>,
+    /// Last window y position (None = centered)
+    #[serde(default)]
+    pub y: Option<i32>,
     /// Start minimized to tray
     pub start_minimized: bool,
     /// Always on top
@@ -52,6 +58,8 @@ impl Default for WindowConfig {
             glass_effect: true,
             width: 800,
             height: 600,
+            x: None,
+            y: None,
             start_minimized: false,
             always_on_top: false,
             title: "Ganesha - Obstacle Remover".to_string(),
@@ -164,6 +172,9 @@ impl DesktopConfig {
     pub fn save(&self, path: &std::path::Path) -> crate::Result<()> {
         let content = toml::to_string_pretty(self)
             .map_err(|e| crate::DesktopError::ConfigError(e.to_string()))?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
         std::fs::write(path, content)?;
         Ok(())
     }
@@ -1,67 +1,250 @@
 //! Global hotkey management
+//!
+//! Registration goes through a `HotkeyBackend` so that a combo already owned
+//! by another application is reported as `HotkeyError::Conflict` (with a
+//! suggested alternative) instead of leaving a dead key.
 
 use crate::{config::DesktopConfig, Result};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use thiserror::Error;
+
+/// Identifies a registered hotkey
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct HotkeyId(u32);
+
+impl std::fmt::Display for HotkeyId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+/// Hotkey registration errors
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum HotkeyError {
+    #[error(
+        "{combo} is already used by {owner}{}",
+        .suggestion.as_ref().map(|s| format!(" (try {})", s)).unwrap_or_default()
+    )]
+    Conflict {
+        combo: String,
+        /// "another application" or the Ganesha hotkey holding it
+        owner: String,
+        /// A free combo on the same key, if one was found
+        suggestion: Option<String>,
+    },
+
+    #[error("Invalid key combination: {0}")]
+    InvalidCombo(String),
+
+    #[error("No hotkey registered with id {0}")]
+    NotFound(HotkeyId),
+
+    #[error("Platform hotkey error: {0}")]
+    Platform(String),
+}
+
+/// OS-level global hotkey registration
+pub trait HotkeyBackend: Send + Sync {
+    /// Grab the combo. Returns `HotkeyError::Conflict` if another
+    /// application already owns it.
+    fn register(&self, binding: &HotkeyBinding) -> std::result::Result<(), HotkeyError>;
+
+    /// Release the combo.
+    fn unregister(&self, binding: &HotkeyBinding);
+}
+
+/// Backend that accepts every combo, used until a platform backend is
+/// supplied with `HotkeyManager::with_backend`
+pub struct NoopBackend;
+
+impl HotkeyBackend for NoopBackend {
+    fn register(&self, _binding: &HotkeyBinding) -> std::result::Result<(), HotkeyError> {
+        // Platform implementations:
+        // - Windows: RegisterHotKey / global-hotkey crate
+        // - macOS: CGEventTapCreate
+        // - Linux: X11 XGrabKey or libxkbcommon
+        Ok(())
+    }
+
+    fn unregister(&self, _binding: &HotkeyBinding) {}
+}
 
 /// Manages global hotkeys
 pub struct HotkeyManager {
-    hotkeys: HashMap<String, HotkeyBinding>,
+    /// Configured hotkey names
+    names: HashMap<String, HotkeyId>,
+    bindings: BTreeMap<HotkeyId, HotkeyBinding>,
+    next_id: u32,
+    backend: Box<dyn HotkeyBackend>,
+    /// Configured hotkeys that could not be registered
+    failed: Vec<(String, HotkeyError)>,
     listening: bool,
 }
 
 impl HotkeyManager {
     /// Create a new hotkey manager
     pub fn new(config: &DesktopConfig) -> Result<Self> {
-        let mut hotkeys = HashMap::new();
+        Self::with_backend(config, Box::new(NoopBackend))
+    }
+
+    /// Create a hotkey manager registering through `backend`.
+    ///
+    /// Configured hotkeys that cannot be registered are logged and listed by
+    /// `failed_registrations` rather than failing startup.
+    pub fn with_backend(config: &DesktopConfig, backend: Box<dyn HotkeyBackend>) -> Result<Self> {
+        let mut manager = Self {
+            names: HashMap::new(),
+            bindings: BTreeMap::new(),
+            next_id: 0,
+            backend,
+            failed: Vec::new(),
+            listening: false,
+        };
 
         // Register configured hotkeys
-        hotkeys.insert(
-            "push_to_talk".to_string(),
-            HotkeyBinding::new(&config.hotkeys.push_to_talk, HotkeyAction::PushToTalk)?,
-        );
-        hotkeys.insert(
-            "toggle_window".to_string(),
-            HotkeyBinding::new(&config.hotkeys.toggle_window, HotkeyAction::ToggleWindow)?,
-        );
-        hotkeys.insert(
-            "emergency_stop".to_string(),
-            HotkeyBinding::new(&config.hotkeys.emergency_stop, HotkeyAction::EmergencyStop)?,
-        );
-        hotkeys.insert(
-            "toggle_voice".to_string(),
-            HotkeyBinding::new(&config.hotkeys.toggle_voice, HotkeyAction::ToggleVoice)?,
-        );
+        let configured = [
+            ("push_to_talk", &config.hotkeys.push_to_talk, HotkeyAction::PushToTalk),
+            ("toggle_window", &config.hotkeys.toggle_window, HotkeyAction::ToggleWindow),
+            ("emergency_stop", &config.hotkeys.emergency_stop, HotkeyAction::EmergencyStop),
+            ("toggle_voice", &config.hotkeys.toggle_voice, HotkeyAction::ToggleVoice),
+        ];
+        for (name, combo, action) in configured {
+            match manager.try_register(combo, action) {
+                Ok(id) => {
+                    manager.names.insert(name.to_string(), id);
+                }
+                Err(e) => {
+                    tracing::warn!("Hotkey {} not registered: {}", name, e);
+                    manager.failed.push((name.to_string(), e));
+                }
+            }
+        }
 
-        Ok(Self {
-            hotkeys,
-            listening: false,
-        })
+        Ok(manager)
     }
 
-    /// Start listening for hotkeys
-    pub async fn start_listening(&mut self) -> Result<()> {
-        self.listening = true;
+    /// Register a global hotkey.
+    ///
+    /// Fails with `HotkeyError::Conflict` if the combo is already bound here
+    /// or owned by another application; the error carries a free
+    /// alternative when one exists.
+    pub fn register(&mut self, key_combo: &str, action: HotkeyAction) -> Result<HotkeyId> {
+        Ok(self.try_register(key_combo, action)?)
+    }
 
-        // In actual implementation:
-        // - Use platform-specific hotkey registration
-        // - Windows: RegisterHotKey / global-hotkey crate
-        // - macOS: CGEventTapCreate
-        // - Linux: X11 XGrabKey or libxkbcommon
+    fn try_register(
+        &mut self,
+        key_combo: &str,
+        action: HotkeyAction,
+    ) -> std::result::Result<HotkeyId, HotkeyError> {
+        let binding = HotkeyBinding {
+            key_combo: key_combo.to_string(),
+            modifiers: parse_modifiers(key_combo),
+            key: parse_key(key_combo),
+            action,
+        };
+        if matches!(binding.key, Key::Unknown(_)) {
+            return Err(HotkeyError::InvalidCombo(key_combo.to_string()));
+        }
+
+        if let Some(owner) = self.owner_of(&binding) {
+            return Err(self.conflict(&binding, owner));
+        }
+        if let Err(e) = self.backend.register(&binding) {
+            return Err(match e {
+                HotkeyError::Conflict { owner, .. } => self.conflict(&binding, owner),
+                other => other,
+            });
+        }
+
+        let id = HotkeyId(self.next_id);
+        self.next_id += 1;
+        tracing::info!("Registered hotkey {}: {} -> {:?}", id, binding.key_combo, binding.action);
+        self.bindings.insert(id, binding);
+        Ok(id)
+    }
+
+    /// Release a registered hotkey
+    pub fn unregister(&mut self, id: HotkeyId) -> Result<()> {
+        let binding = self.bindings.remove(&id).ok_or(HotkeyError::NotFound(id))?;
+        self.backend.unregister(&binding);
+        self.names.retain(|_, named| *named != id);
+        tracing::info!("Unregistered hotkey {}: {}", id, binding.key_combo);
+        Ok(())
+    }
+
+    /// All registered hotkeys, in registration order
+    pub fn list_registered(&self) -> Vec<(HotkeyId, &HotkeyBinding)> {
+        self.bindings.iter().map(|(id, binding)| (*id, binding)).collect()
+    }
 
-        for (_name, binding) in &self.hotkeys {
-            tracing::info!("Registered hotkey: {} -> {:?}", binding.key_combo, binding.action);
+    /// Configured hotkeys that could not be registered, e.g. for the
+    /// settings UI to warn about
+    pub fn failed_registrations(&self) -> &[(String, HotkeyError)] {
+        &self.failed
+    }
+
+    /// Who already holds this combo, if anyone in this app does
+    fn owner_of(&self, binding: &HotkeyBinding) -> Option<String> {
+        self.bindings
+            .iter()
+            .find(|(_, existing)| existing.modifiers == binding.modifiers && existing.key == binding.key)
+            .map(|(id, _)| {
+                match self.names.iter().find(|(_, named)| *named == id) {
+                    Some((name, _)) => format!("hotkey \"{}\"", name),
+                    None => format!("hotkey {}", id),
+                }
+            })
+    }
+
+    /// Build a conflict error, probing for a free combo on the same key
+    fn conflict(&self, binding: &HotkeyBinding, owner: String) -> HotkeyError {
+        let extra = [
+            Modifiers { shift: true, ..Default::default() },
+            Modifiers { alt: true, ..Default::default() },
+            Modifiers { shift: true, alt: true, ..Default::default() },
+            Modifiers { meta: true, ..Default::default() },
+        ];
+        let suggestion = extra.iter().find_map(|extra| {
+            let modifiers = Modifiers {
+                ctrl: binding.modifiers.ctrl || extra.ctrl,
+                alt: binding.modifiers.alt || extra.alt,
+                shift: binding.modifiers.shift || extra.shift,
+                meta: binding.modifiers.meta || extra.meta,
+            };
+            if modifiers == binding.modifiers {
+                return None;
+            }
+            let candidate = HotkeyBinding {
+                key_combo: format_combo(modifiers, &binding.key),
+                modifiers,
+                key: binding.key.clone(),
+                action: binding.action.clone(),
+            };
+            if self.owner_of(&candidate).is_some() || self.backend.register(&candidate).is_err() {
+                return None;
+            }
+            self.backend.unregister(&candidate);
+            Some(candidate.key_combo)
+        });
+
+        HotkeyError::Conflict {
+            combo: binding.key_combo.clone(),
+            owner,
+            suggestion,
         }
+    }
 
-        tracing::info!("Hotkey listener started");
+    /// Start listening for hotkeys
+    pub async fn start_listening(&mut self) -> Result<()> {
+        self.listening = true;
+        tracing::info!("Hotkey listener started ({} hotkeys)", self.bindings.len());
         Ok(())
     }
 
     /// Stop listening for hotkeys
     pub fn stop_listening(&mut self) -> Result<()> {
         self.listening = false;
-
-        // Unregister all hotkeys
-
         tracing::info!("Hotkey listener stopped");
         Ok(())
     }
@@ -71,29 +254,46 @@ impl HotkeyManager {
         self.listening
     }
 
-    /// Update a hotkey binding
+    /// Update a configured hotkey binding. If the new combo cannot be
+    /// registered the old one stays in place.
     pub fn rebind(&mut self, name: &str, key_combo: &str) -> Result<()> {
-        if let Some(binding) = self.hotkeys.get_mut(name) {
-            binding.key_combo = key_combo.to_string();
-            binding.modifiers = parse_modifiers(key_combo);
-            binding.key = parse_key(key_combo);
-
-            tracing::info!("Hotkey {} rebound to {}", name, key_combo);
+        let Some(&old_id) = self.names.get(name) else {
+            return Ok(());
+        };
+        let old = self.bindings.remove(&old_id).ok_or(HotkeyError::NotFound(old_id))?;
+        self.backend.unregister(&old);
+
+        match self.register(key_combo, old.action.clone()) {
+            Ok(id) => {
+                self.names.insert(name.to_string(), id);
+                tracing::info!("Hotkey {} rebound to {}", name, key_combo);
+                Ok(())
+            }
+            Err(e) => {
+                if let Err(restore) = self.backend.register(&old) {
+                    tracing::warn!("Could not restore hotkey {}: {}", name, restore);
+                }
+                self.bindings.insert(old_id, old);
+                Err(e)
+            }
         }
-        Ok(())
     }
 
-    /// Get all registered hotkeys
+    /// Get all configured hotkeys
     pub fn list_hotkeys(&self) -> Vec<(&str, &str)> {
-        self.hotkeys
+        self.names
             .iter()
-            .map(|(name, binding)| (name.as_str(), binding.key_combo.as_str()))
+            .filter_map(|(name, id)| {
+                self.bindings
+                    .get(id)
+                    .map(|binding| (name.as_str(), binding.key_combo.as_str()))
+            })
             .collect()
     }
 
     /// Handle a hotkey press event
     pub fn handle_keypress(&self, modifiers: Modifiers, key: Key) -> Option<HotkeyAction> {
-        for binding in self.hotkeys.values() {
+        for binding in self.bindings.values() {
             if binding.modifiers == modifiers && binding.key == key {
                 return Some(binding.action.clone());
             }
@@ -204,6 +404,38 @@ pub enum HotkeyAction {
     Custom(String),
 }
 
+/// Format modifiers and key as a combo string, e.g. "Ctrl+Shift+Space"
+fn format_combo(modifiers: Modifiers, key: &Key) -> String {
+    let mut parts: Vec<String> = Vec::new();
+    if modifiers.ctrl {
+        parts.push("Ctrl".to_string());
+    }
+    if modifiers.alt {
+        parts.push("Alt".to_string());
+    }
+    if modifiers.shift {
+        parts.push("Shift".to_string());
+    }
+    if modifiers.meta {
+        parts.push("Super".to_string());
+    }
+    parts.push(match key {
+        Key::Letter(c) => c.to_uppercase().to_string(),
+        Key::Function(n) => format!("F{}", n),
+        Key::Space => "Space".to_string(),
+        Key::Escape => "Escape".to_string(),
+        Key::Enter => "Enter".to_string(),
+        Key::Tab => "Tab".to_string(),
+        Key::Backspace => "Backspace".to_string(),
+        Key::Up => "Up".to_string(),
+        Key::Down => "Down".to_string(),
+        Key::Left => "Left".to_string(),
+        Key::Right => "Right".to_string(),
+        Key::Unknown(name) => name.clone(),
+    });
+    parts.join("+")
+}
+
 /// Parse modifiers from key combo string
 fn parse_modifiers(combo: &str) -> Modifiers {
     let lower = combo.to_lowercase();
@@ -265,4 +497,74 @@ mod tests {
         assert_eq!(parse_key("Escape"), Key::Escape);
         assert_eq!(parse_key("F1"), Key::Function(1));
     }
+
+    /// Backend where some combos belong to other applications
+    struct TakenBackend {
+        taken: Vec<(Modifiers, Key)>,
+    }
+
+    impl HotkeyBackend for TakenBackend {
+        fn register(&self, binding: &HotkeyBinding) -> std::result::Result<(), HotkeyError> {
+            if self.taken.contains(&(binding.modifiers, binding.key.clone())) {
+                return Err(HotkeyError::Conflict {
+                    combo: binding.key_combo.clone(),
+                    owner: "another application".to_string(),
+                    suggestion: None,
+                });
+            }
+            Ok(())
+        }
+
+        fn unregister(&self, _binding: &HotkeyBinding) {}
+    }
+
+    #[test]
+    fn test_register_reports_conflict_with_suggestion() {
+        let backend = TakenBackend {
+            taken: vec![
+                (Modifiers::ctrl(), Key::Space),
+                (Modifiers::ctrl_shift(), Key::Space),
+            ],
+        };
+        let mut manager =
+            HotkeyManager::with_backend(&DesktopConfig::default(), Box::new(backend)).unwrap();
+
+        // Push-to-talk (Ctrl+Space) is owned elsewhere: not silently dropped
+        assert_eq!(manager.failed_registrations().len(), 1);
+        assert_eq!(manager.failed_registrations()[0].0, "push_to_talk");
+        assert_eq!(manager.list_registered().len(), 3);
+
+        match manager.register("Ctrl+Space", HotkeyAction::PushToTalk) {
+            Err(crate::DesktopError::HotkeyError(HotkeyError::Conflict {
+                owner, suggestion, ..
+            })) => {
+                assert_eq!(owner, "another application");
+                assert_eq!(suggestion.as_deref(), Some("Ctrl+Alt+Space"));
+            }
+            other => panic!("expected conflict, got {:?}", other.map(|_| ())),
+        }
+
+        // Clashing with our own hotkey names it
+        match manager.register("ctrl+shift+g", HotkeyAction::Custom("x".to_string())) {
+            Err(crate::DesktopError::HotkeyError(HotkeyError::Conflict { owner, .. })) => {
+                assert_eq!(owner, "hotkey \"toggle_window\"");
+            }
+            other => panic!("expected conflict, got {:?}", other.map(|_| ())),
+        }
+
+        let id = manager
+            .register("Ctrl+Alt+Space", HotkeyAction::PushToTalk)
+            .unwrap();
+        assert_eq!(
+            manager.handle_keypress(
+                Modifiers { ctrl: true, alt: true, ..Default::default() },
+                Key::Space
+            )
+            .map(|a| matches!(a, HotkeyAction::PushToTalk)),
+            Some(true)
+        );
+        manager.unregister(id).unwrap();
+        assert!(manager.unregister(id).is_err());
+        assert_eq!(manager.list_registered().len(), 3);
+    }
 }
@@ -38,11 +38,12 @@ pub mod state;
 pub mod commands;
 
 pub use config::DesktopConfig;
-pub use window::WindowManager;
-pub use tray::TrayManager;
+pub use window::{MonitorBounds, WindowEvent, WindowManager};
+pub use tray::{TrayAction, TrayCommand, TrayHandle, TrayManager};
 pub use border::BorderOverlay;
-pub use hotkey::HotkeyManager;
+pub use hotkey::{HotkeyBackend, HotkeyError, HotkeyId, HotkeyManager};
 pub use state::AppState;
+pub use commands::{EventEmitter, EventFilter, EventHub, EventKind, FrontendEvent, TaskProgress};
 
 use thiserror::Error;
 
@@ -55,7 +56,7 @@ pub enum DesktopError {
     TrayError(String),
 
     #[error("Hotkey error: {0}")]
-    HotkeyError(String),
+    HotkeyError(#[from] hotkey::HotkeyError),
 
     #[error("Configuration error: {0}")]
     ConfigError(String),
@@ -78,6 +79,8 @@ pub struct DesktopApp {
     tray_manager: TrayManager,
     border_overlay: BorderOverlay,
     hotkey_manager: HotkeyManager,
+    events: EventHub,
+    config_path: Option<std::path::PathBuf>,
 }
 
 impl DesktopApp {
@@ -89,6 +92,8 @@ impl DesktopApp {
             tray_manager: TrayManager::new(&config)?,
             border_overlay: BorderOverlay::new(&config)?,
             hotkey_manager: HotkeyManager::new(&config)?,
+            events: EventHub::new(),
+            config_path: DesktopConfig::default_path(),
             config,
         })
     }
@@ -106,14 +111,69 @@ impl DesktopApp {
         Ok(())
     }
 
+    /// Use a different config file for persisting settings such as the
+    /// window position, or `None` to keep them in memory only
+    pub fn with_config_path(mut self, path: Option<std::path::PathBuf>) -> Self {
+        self.config_path = path;
+        self
+    }
+
+    /// Record the connected monitors, primary first, as reported by Tauri's
+    /// `primary_monitor()` and `available_monitors()`. Call at startup and
+    /// whenever the monitor layout changes.
+    pub fn set_monitors(&mut self, monitors: Vec<MonitorBounds>) {
+        self.window_manager.set_monitors(monitors);
+    }
+
     /// Show the main window
     pub fn show_window(&mut self) -> Result<()> {
-        self.window_manager.show()
+        self.window_manager.show()?;
+        self.state.window_visible = true;
+        // Showing may have clamped an off-screen position
+        self.persist_window_geometry()
+    }
+
+    /// Handle a window event from the window system, remembering the new
+    /// position or size
+    pub fn on_window_event(&mut self, event: &WindowEvent) -> Result<()> {
+        if self.window_manager.handle_event(event) {
+            self.persist_window_geometry()?;
+        }
+        Ok(())
+    }
+
+    /// Center the window at its default size and forget the saved position
+    pub fn reset_window_position(&mut self) -> Result<()> {
+        self.window_manager.reset_window_position()?;
+        self.persist_window_geometry()
+    }
+
+    /// Save the window geometry to the config file. Skipped until the
+    /// monitors are known, so an unverified position is never written back.
+    fn persist_window_geometry(&mut self) -> Result<()> {
+        if !self.window_manager.monitors_known() {
+            return Ok(());
+        }
+        let before = self.config.window.clone();
+        self.window_manager.save_geometry(&mut self.config.window);
+        let unchanged = (before.x, before.y, before.width, before.height)
+            == (
+                self.config.window.x,
+                self.config.window.y,
+                self.config.window.width,
+                self.config.window.height,
+            );
+        match &self.config_path {
+            Some(path) if !unchanged => self.config.save(path),
+            _ => Ok(()),
+        }
     }
 
     /// Hide the main window
     pub fn hide_window(&mut self) -> Result<()> {
-        self.window_manager.hide()
+        self.window_manager.hide()?;
+        self.state.window_visible = false;
+        Ok(())
     }
 
     /// Toggle window visibility
@@ -129,6 +189,7 @@ impl DesktopApp {
     pub fn show_border(&mut self) -> Result<()> {
         self.border_overlay.show()?;
         self.state.border_visible = true;
+        self.events.emitter().emit(FrontendEvent::Border { visible: true });
         Ok(())
     }
 
@@ -136,21 +197,117 @@ impl DesktopApp {
     pub fn hide_border(&mut self) -> Result<()> {
         self.border_overlay.hide()?;
         self.state.border_visible = false;
+        self.events.emitter().emit(FrontendEvent::Border { visible: false });
         Ok(())
     }
 
     /// Handle push-to-talk activation
     pub fn on_push_to_talk_start(&mut self) {
         self.state.is_listening = true;
+        self.events.emitter().emit(FrontendEvent::Listening { active: true });
         // Notify voice system to start recording
     }
 
     /// Handle push-to-talk release
     pub fn on_push_to_talk_end(&mut self) {
         self.state.is_listening = false;
+        self.events.emitter().emit(FrontendEvent::Listening { active: false });
         // Notify voice system to stop recording and process
     }
 
+    /// Mark a request as executing or finished
+    pub fn set_processing(&mut self, processing: bool) {
+        self.state.set_processing(processing);
+        self.events.emitter().emit(FrontendEvent::Executing { active: processing });
+    }
+
+    /// Report progress of the current task to the frontend
+    pub fn report_progress(&self, progress: TaskProgress) {
+        self.events.emitter().emit(FrontendEvent::TaskProgress(progress));
+    }
+
+    /// Handle for emitting frontend events from other threads, e.g. the
+    /// hotkey handler or the voice event bridge
+    pub fn event_emitter(&self) -> EventEmitter {
+        self.events.emitter()
+    }
+
+    /// Subscribe to the frontend events accepted by `filter`. If it accepts
+    /// state snapshots, the current state is sent first so the new subscriber
+    /// does not have to wait for the next change.
+    pub fn subscribe_events(
+        &mut self,
+        filter: EventFilter,
+    ) -> tokio::sync::mpsc::Receiver<FrontendEvent> {
+        self.events.subscribe(filter, &self.state)
+    }
+
+    /// Fan queued frontend events out to subscribers. Call from the main
+    /// thread's event loop. Returns how many were dispatched.
+    pub fn process_events(&mut self) -> usize {
+        self.events.dispatch_pending()
+    }
+
+    /// Apply a tray menu click, run the registered tray callbacks, and
+    /// rebuild the menu. Call on the main thread.
+    pub fn handle_tray_action(&mut self, action: &TrayAction) -> Result<()> {
+        let before = self.state.clone();
+        match action {
+            TrayAction::ShowWindow => self.show_window()?,
+            TrayAction::HideWindow => self.hide_window()?,
+            TrayAction::TogglePause => self.state.paused = !self.state.paused,
+            TrayAction::ToggleBorder => {
+                if self.state.border_visible {
+                    self.hide_border()?
+                } else {
+                    self.show_border()?
+                }
+            }
+            TrayAction::SetRiskLevel(level) => self.state.risk_level = level.clone(),
+            TrayAction::SetPersonality(personality) => {
+                self.state.personality = personality.clone()
+            }
+            // Left to the frontend via tray callbacks
+            TrayAction::ToggleVoice
+            | TrayAction::ToggleAlwaysOnTop
+            | TrayAction::OpenSettings
+            | TrayAction::ShowAbout
+            | TrayAction::Quit => {}
+        }
+
+        self.tray_manager.run_callbacks(action, &mut self.state);
+        self.tray_manager.rebuild_menu(&self.state);
+
+        // Border changes were already emitted by show_border/hide_border
+        let emitter = self.events.emitter();
+        FrontendEvent::diff(&before, &self.state)
+            .into_iter()
+            .filter(|event| !matches!(event, FrontendEvent::Border { .. }))
+            .for_each(|event| emitter.emit(event));
+        Ok(())
+    }
+
+    /// Apply commands queued through `TrayHandle`s. Call from the main
+    /// thread's event loop. Returns how many were processed.
+    pub fn process_tray_commands(&mut self) -> Result<usize> {
+        let mut processed = 0;
+        while let Some(command) = self.tray_manager.next_command() {
+            match command {
+                TrayCommand::Rebuild => {
+                    self.tray_manager.rebuild_menu(&self.state);
+                }
+                TrayCommand::Action(action) => self.handle_tray_action(&action)?,
+            }
+            processed += 1;
+        }
+        Ok(processed)
+    }
+
+    /// Tray manager, e.g. to register click callbacks or get a `TrayHandle`
+    pub fn tray_manager_mut(&mut self) -> &mut TrayManager {
+        &mut self.tray_manager
+    }
+
     /// Get current application state
     pub fn state(&self) -> &AppState {
         &self.state
@@ -161,3 +318,29 @@ impl DesktopApp {
         &mut self.state
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_window_geometry_waits_for_monitors() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("desktop.toml");
+        let mut config = DesktopConfig::default();
+        config.window.x = Some(2500);
+        config.window.y = Some(100);
+        let mut app = DesktopApp::new(config).unwrap().with_config_path(Some(path.clone()));
+
+        // Unknown monitors: shown where saved, nothing written
+        app.show_window().unwrap();
+        app.on_window_event(&WindowEvent::Moved { x: 2600, y: 100 }).unwrap();
+        assert!(!path.exists());
+
+        // On the second monitor: kept there and saved
+        app.set_monitors(vec![MonitorBounds::default(), MonitorBounds::new(1920, 0, 2560, 1440)]);
+        app.show_window().unwrap();
+        let saved = DesktopConfig::load(&path).unwrap();
+        assert_eq!((saved.window.x, saved.window.y), (Some(2600), Some(100)));
+    }
+}
@@ -5,12 +5,15 @@ use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 /// Application runtime state
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AppState {
     /// Window visibility
     pub window_visible: bool,
     /// Border overlay visibility
     pub border_visible: bool,
+    /// Automation paused from the tray
+    #[serde(default)]
+    pub paused: bool,
     /// Voice listening active
     pub is_listening: bool,
     /// Currently processing a request
@@ -71,6 +74,7 @@ impl Default for AppState {
         Self {
             window_visible: true,
             border_visible: false,
+            paused: false,
             is_listening: false,
             is_processing: false,
             is_connected: false,
@@ -85,7 +89,7 @@ impl Default for AppState {
 }
 
 /// Token usage tracking
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct TokenUsage {
     /// Total tokens used
     pub total: usize,
@@ -1,21 +1,73 @@
 //! System tray integration
+//!
+//! The tray menu is regenerated from `AppState` whenever it changes. Tauri
+//! only allows the tray menu to be rebuilt on the main thread, so other
+//! threads send a `TrayCommand` through a `TrayHandle` and the main loop
+//! applies it with `DesktopApp::process_tray_commands`.
 
-use crate::{config::DesktopConfig, Result};
+use crate::{config::DesktopConfig, state::AppState, Result};
+use tokio::sync::mpsc;
+
+/// Risk levels offered in the tray menu
+pub const RISK_LEVELS: [&str; 4] = ["Safe", "Normal", "Trusted", "Yolo"];
+
+/// Personalities offered in the tray menu
+pub const PERSONALITIES: [&str; 4] = ["Professional", "Friendly", "Mentor", "Pirate"];
+
+/// Callback run when a tray menu item is clicked
+pub type TrayCallback = Box<dyn Fn(&TrayAction, &mut AppState) + Send + Sync>;
+
+/// Requests sent to the main thread through a `TrayHandle`
+#[derive(Debug, Clone)]
+pub enum TrayCommand {
+    /// Regenerate the menu from the current state
+    Rebuild,
+    /// A menu item was clicked
+    Action(TrayAction),
+}
+
+/// Thread-safe handle for requesting tray updates from background tasks
+#[derive(Debug, Clone)]
+pub struct TrayHandle {
+    sender: mpsc::UnboundedSender<TrayCommand>,
+}
+
+impl TrayHandle {
+    /// Ask the main thread to rebuild the menu
+    pub fn request_rebuild(&self) {
+        // The receiver only goes away on shutdown
+        let _ = self.sender.send(TrayCommand::Rebuild);
+    }
+
+    /// Forward a menu click to the main thread
+    pub fn dispatch(&self, action: TrayAction) {
+        let _ = self.sender.send(TrayCommand::Action(action));
+    }
+}
 
 /// Manages the system tray icon and menu
 pub struct TrayManager {
     enabled: bool,
     minimize_to_tray: bool,
     show_notifications: bool,
+    menu: TrayMenu,
+    callbacks: Vec<TrayCallback>,
+    sender: mpsc::UnboundedSender<TrayCommand>,
+    receiver: mpsc::UnboundedReceiver<TrayCommand>,
 }
 
 impl TrayManager {
     /// Create a new tray manager
     pub fn new(config: &DesktopConfig) -> Result<Self> {
+        let (sender, receiver) = mpsc::unbounded_channel();
         Ok(Self {
             enabled: config.tray.enabled,
             minimize_to_tray: config.tray.minimize_to_tray,
             show_notifications: config.tray.show_notifications,
+            menu: Self::build_menu(&AppState::default()),
+            callbacks: Vec::new(),
+            sender,
+            receiver,
         })
     }
 
@@ -35,32 +87,48 @@ impl TrayManager {
         Ok(())
     }
 
-    /// Build the tray menu
-    #[allow(dead_code)]
-    fn build_menu(&self) -> TrayMenu {
+    /// Build the tray menu for the given state
+    fn build_menu(state: &AppState) -> TrayMenu {
+        let window_item = if state.window_visible {
+            TrayMenuItem::item("Hide Window", TrayAction::HideWindow)
+        } else {
+            TrayMenuItem::item("Show Window", TrayAction::ShowWindow)
+        };
+        let pause_label = if state.paused { "Resume Ganesha" } else { "Pause Ganesha" };
+
         TrayMenu {
             items: vec![
-                TrayMenuItem::item("Show Window", TrayAction::ShowWindow),
+                window_item,
+                TrayMenuItem::item(pause_label, TrayAction::TogglePause),
                 TrayMenuItem::separator(),
                 TrayMenuItem::submenu(
                     "Risk Level",
-                    vec![
-                        TrayMenuItem::radio("Safe", TrayAction::SetRiskLevel("Safe".to_string()), false),
-                        TrayMenuItem::radio("Normal", TrayAction::SetRiskLevel("Normal".to_string()), true),
-                        TrayMenuItem::radio("Trusted", TrayAction::SetRiskLevel("Trusted".to_string()), false),
-                        TrayMenuItem::radio("Yolo", TrayAction::SetRiskLevel("Yolo".to_string()), false),
-                    ],
+                    RISK_LEVELS
+                        .iter()
+                        .map(|level| {
+                            TrayMenuItem::radio(
+                                level,
+                                TrayAction::SetRiskLevel(level.to_string()),
+                                state.risk_level == *level,
+                            )
+                        })
+                        .collect(),
                 ),
                 TrayMenuItem::submenu(
                     "Personality",
-                    vec![
-                        TrayMenuItem::radio("Professional", TrayAction::SetPersonality("Professional".to_string()), true),
-                        TrayMenuItem::radio("Friendly", TrayAction::SetPersonality("Friendly".to_string()), false),
-                        TrayMenuItem::radio("Mentor", TrayAction::SetPersonality("Mentor".to_string()), false),
-                        TrayMenuItem::radio("Pirate", TrayAction::SetPersonality("Pirate".to_string()), false),
-                    ],
+                    PERSONALITIES
+                        .iter()
+                        .map(|personality| {
+                            TrayMenuItem::radio(
+                                personality,
+                                TrayAction::SetPersonality(personality.to_string()),
+                                state.personality == *personality,
+                            )
+                        })
+                        .collect(),
                 ),
                 TrayMenuItem::separator(),
+                TrayMenuItem::check("Show Border", TrayAction::ToggleBorder, state.border_visible),
                 TrayMenuItem::check("Voice Mode", TrayAction::ToggleVoice, false),
                 TrayMenuItem::check("Always on Top", TrayAction::ToggleAlwaysOnTop, false),
                 TrayMenuItem::separator(),
@@ -72,6 +140,47 @@ impl TrayManager {
         }
     }
 
+    /// Regenerate the menu from the current state. Must run on the main
+    /// thread; elsewhere use `TrayHandle::request_rebuild`.
+    pub fn rebuild_menu(&mut self, state: &AppState) -> &TrayMenu {
+        self.menu = Self::build_menu(state);
+        // In Tauri: tray.set_menu(Some(menu))
+        tracing::debug!("Tray menu rebuilt");
+        &self.menu
+    }
+
+    /// The current menu
+    pub fn menu(&self) -> &TrayMenu {
+        &self.menu
+    }
+
+    /// Register a callback run after each menu click is applied
+    pub fn on_action<F>(&mut self, callback: F)
+    where
+        F: Fn(&TrayAction, &mut AppState) + Send + Sync + 'static,
+    {
+        self.callbacks.push(Box::new(callback));
+    }
+
+    /// Run the registered click callbacks
+    pub(crate) fn run_callbacks(&self, action: &TrayAction, state: &mut AppState) {
+        for callback in &self.callbacks {
+            callback(action, state);
+        }
+    }
+
+    /// Handle for sending commands from other threads
+    pub fn handle(&self) -> TrayHandle {
+        TrayHandle {
+            sender: self.sender.clone(),
+        }
+    }
+
+    /// Take the next pending command, if any
+    pub(crate) fn next_command(&mut self) -> Option<TrayCommand> {
+        self.receiver.try_recv().ok()
+    }
+
     /// Update the tray icon
     pub fn set_icon(&mut self, icon: TrayIcon) -> Result<()> {
         // In Tauri: tray.set_icon(icon)
@@ -191,10 +300,12 @@ impl TrayMenuItem {
 }
 
 /// Actions triggered by tray menu
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum TrayAction {
     ShowWindow,
     HideWindow,
+    TogglePause,
+    ToggleBorder,
     ToggleVoice,
     ToggleAlwaysOnTop,
     SetRiskLevel(String),
@@ -203,3 +314,70 @@ pub enum TrayAction {
     ShowAbout,
     Quit,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn find<'a>(items: &'a [TrayMenuItem], wanted: &TrayAction) -> Option<&'a TrayMenuItem> {
+        items.iter().find_map(|item| match item {
+            TrayMenuItem::Item { action, .. }
+            | TrayMenuItem::Check { action, .. }
+            | TrayMenuItem::Radio { action, .. }
+                if action == wanted =>
+            {
+                Some(item)
+            }
+            TrayMenuItem::Submenu { items, .. } => find(items, wanted),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn test_rebuild_menu_reflects_state() {
+        let mut tray = TrayManager::new(&DesktopConfig::default()).unwrap();
+        let mut state = AppState::new();
+        state.paused = true;
+        state.border_visible = true;
+        state.personality = "Pirate".to_string();
+
+        let menu = tray.rebuild_menu(&state);
+
+        assert!(matches!(
+            find(&menu.items, &TrayAction::TogglePause),
+            Some(TrayMenuItem::Item { label, .. }) if label == "Resume Ganesha"
+        ));
+        assert!(matches!(
+            find(&menu.items, &TrayAction::ToggleBorder),
+            Some(TrayMenuItem::Check { checked: true, .. })
+        ));
+        assert!(matches!(
+            find(&menu.items, &TrayAction::SetPersonality("Pirate".to_string())),
+            Some(TrayMenuItem::Radio { selected: true, .. })
+        ));
+        assert!(matches!(
+            find(&menu.items, &TrayAction::SetPersonality("Professional".to_string())),
+            Some(TrayMenuItem::Radio { selected: false, .. })
+        ));
+    }
+
+    #[test]
+    fn test_handle_queues_commands_for_main_thread() {
+        let mut tray = TrayManager::new(&DesktopConfig::default()).unwrap();
+        let handle = tray.handle();
+
+        std::thread::spawn(move || {
+            handle.request_rebuild();
+            handle.dispatch(TrayAction::TogglePause);
+        })
+        .join()
+        .unwrap();
+
+        assert!(matches!(tray.next_command(), Some(TrayCommand::Rebuild)));
+        assert!(matches!(
+            tray.next_command(),
+            Some(TrayCommand::Action(TrayAction::TogglePause))
+        ));
+        assert!(tray.next_command().is_none());
+    }
+}
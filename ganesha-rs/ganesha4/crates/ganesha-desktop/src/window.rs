@@ -1,12 +1,63 @@
 //! Window management for the desktop app
+//!
+//! The window's position and size are remembered in `WindowConfig` so it
+//! reopens where it was left. A saved position that no longer lands on any
+//! monitor (e.g. the monitor was disconnected) is clamped back onto the
+//! primary monitor when the window is shown. Until the window system has
+//! reported the connected monitors, the saved position is used as is.
 
-use crate::{config::DesktopConfig, Result};
+use crate::{
+    config::{DesktopConfig, WindowConfig},
+    Result,
+};
+
+/// How much of the window must overlap a monitor to count as reachable
+const MIN_VISIBLE_WIDTH: i64 = 64;
+const MIN_VISIBLE_HEIGHT: i64 = 32;
+
+/// Screen area of a monitor in desktop coordinates
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MonitorBounds {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl MonitorBounds {
+    /// Create monitor bounds
+    pub fn new(x: i32, y: i32, width: u32, height: u32) -> Self {
+        Self { x, y, width, height }
+    }
+
+    /// Whether enough of a window at (x, y) is on this monitor to grab it.
+    /// The top edge must be on screen so the title bar stays reachable.
+    fn shows(&self, x: i32, y: i32, width: u32, height: u32) -> bool {
+        let (left, top) = (self.x as i64, self.y as i64);
+        let (right, bottom) = (left + self.width as i64, top + self.height as i64);
+        let (x, y) = (x as i64, y as i64);
+
+        let overlap_w = (x + width as i64).min(right) - x.max(left);
+        let overlap_h = (y + height as i64).min(bottom) - y.max(top);
+        y >= top && y < bottom && overlap_w >= MIN_VISIBLE_WIDTH && overlap_h >= MIN_VISIBLE_HEIGHT
+    }
+}
+
+impl Default for MonitorBounds {
+    fn default() -> Self {
+        Self::new(0, 0, 1920, 1080)
+    }
+}
 
 /// Window manager handles the main application window
 pub struct WindowManager {
     config: WindowManagerConfig,
     visible: bool,
     focused: bool,
+    /// Top-left corner, None while centered
+    position: Option<(i32, i32)>,
+    /// Connected monitors, primary first; empty until reported
+    monitors: Vec<MonitorBounds>,
 }
 
 #[derive(Debug, Clone)]
@@ -34,6 +85,8 @@ impl WindowManager {
             },
             visible: !config.window.start_minimized,
             focused: false,
+            position: config.window.x.zip(config.window.y),
+            monitors: Vec::new(),
         })
     }
 
@@ -44,6 +97,11 @@ impl WindowManager {
         // - Apply glass effect if enabled
         // - Set always_on_top if configured
         // - Register window event handlers
+        // - Load the monitors:
+        //   self.set_monitors(window.available_monitors()?.iter().map(|m| {
+        //       MonitorBounds::new(m.position().x, m.position().y, m.size().width, m.size().height)
+        //   }).collect())
+        //   with window.primary_monitor() moved to the front
 
         tracing::info!(
             "Window initialized: {}x{}, glass={}",
@@ -55,10 +113,17 @@ impl WindowManager {
         Ok(())
     }
 
-    /// Show the window
+    /// Show the window at its remembered position
     pub fn show(&mut self) -> Result<()> {
         self.visible = true;
-        // In Tauri: window.show()
+        self.clamp_to_monitors();
+        // In Tauri:
+        // match self.position {
+        //     Some((x, y)) => window.set_position(PhysicalPosition::new(x, y)),
+        //     None => window.center(),
+        // }
+        // window.set_size(PhysicalSize::new(width, height))
+        // window.show()
         tracing::debug!("Window shown");
         Ok(())
     }
@@ -144,6 +209,103 @@ impl WindowManager {
     pub fn dimensions(&self) -> (u32, u32) {
         (self.config.width, self.config.height)
     }
+
+    /// Get window position, None while centered
+    pub fn position(&self) -> Option<(i32, i32)> {
+        self.position
+    }
+
+    /// Update the connected monitors, primary first.
+    /// In Tauri: window.primary_monitor() followed by window.available_monitors()
+    pub fn set_monitors(&mut self, monitors: Vec<MonitorBounds>) {
+        if !monitors.is_empty() {
+            self.monitors = monitors;
+        }
+    }
+
+    /// Whether the window system has reported the connected monitors
+    pub fn monitors_known(&self) -> bool {
+        !self.monitors.is_empty()
+    }
+
+    /// Track a move or resize reported by the window system.
+    /// Returns whether the geometry changed and should be persisted.
+    pub fn handle_event(&mut self, event: &WindowEvent) -> bool {
+        match *event {
+            WindowEvent::Moved { x, y } if self.position != Some((x, y)) => {
+                self.position = Some((x, y));
+                true
+            }
+            WindowEvent::Resized { width, height }
+                if (width, height) != (self.config.width, self.config.height) =>
+            {
+                self.config.width = width;
+                self.config.height = height;
+                true
+            }
+            WindowEvent::Shown => {
+                self.visible = true;
+                false
+            }
+            WindowEvent::Hidden => {
+                self.visible = false;
+                false
+            }
+            WindowEvent::Focused => {
+                self.focused = true;
+                false
+            }
+            WindowEvent::Blurred => {
+                self.focused = false;
+                false
+            }
+            _ => false,
+        }
+    }
+
+    /// Write the current position and size into the window config
+    pub fn save_geometry(&self, config: &mut WindowConfig) {
+        config.x = self.position.map(|(x, _)| x);
+        config.y = self.position.map(|(_, y)| y);
+        config.width = self.config.width;
+        config.height = self.config.height;
+    }
+
+    /// Forget the remembered position and size and center the window
+    pub fn reset_window_position(&mut self) -> Result<()> {
+        let defaults = WindowConfig::default();
+        self.position = None;
+        self.config.width = defaults.width;
+        self.config.height = defaults.height;
+        // In Tauri: window.set_size(...) + window.center()
+        tracing::info!("Window position reset");
+        Ok(())
+    }
+
+    /// Pull a window that is no longer on any monitor back onto the primary
+    /// one. Without known monitors there is nothing to check against.
+    fn clamp_to_monitors(&mut self) {
+        let (Some((x, y)), Some(&primary)) = (self.position, self.monitors.first()) else {
+            return;
+        };
+        let (width, height) = (self.config.width, self.config.height);
+        if self.monitors.iter().any(|m| m.shows(x, y, width, height)) {
+            return;
+        }
+
+        self.config.width = width.min(primary.width);
+        self.config.height = height.min(primary.height);
+        let max_x = primary.x + (primary.width - self.config.width) as i32;
+        let max_y = primary.y + (primary.height - self.config.height) as i32;
+        let clamped = (x.clamp(primary.x, max_x), y.clamp(primary.y, max_y));
+
+        tracing::info!(
+            "Saved window position {:?} is off-screen, moved to {:?}",
+            (x, y),
+            clamped
+        );
+        self.position = Some(clamped);
+    }
 }
 
 /// Window events that can be emitted
@@ -164,3 +326,70 @@ pub enum WindowEvent {
     /// Close was requested
     CloseRequested,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_at(x: i32, y: i32) -> DesktopConfig {
+        let mut config = DesktopConfig::default();
+        config.window.x = Some(x);
+        config.window.y = Some(y);
+        config
+    }
+
+    #[test]
+    fn test_geometry_round_trips_through_config() {
+        let mut manager = WindowManager::new(&DesktopConfig::default()).unwrap();
+        assert!(manager.handle_event(&WindowEvent::Moved { x: 300, y: 200 }));
+        assert!(manager.handle_event(&WindowEvent::Resized { width: 640, height: 480 }));
+        assert!(!manager.handle_event(&WindowEvent::Moved { x: 300, y: 200 }));
+
+        let mut config = DesktopConfig::default();
+        manager.save_geometry(&mut config.window);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("desktop.toml");
+        config.save(&path).unwrap();
+        let loaded = DesktopConfig::load(&path).unwrap();
+
+        let mut restored = WindowManager::new(&loaded).unwrap();
+        restored.set_monitors(vec![MonitorBounds::default()]);
+        restored.show().unwrap();
+        assert_eq!(restored.position(), Some((300, 200)));
+        assert_eq!(restored.dimensions(), (640, 480));
+    }
+
+    #[test]
+    fn test_offscreen_position_is_clamped_to_primary() {
+        // Saved on a second monitor to the right that is now gone
+        let mut manager = WindowManager::new(&config_at(2500, 100)).unwrap();
+        manager.set_monitors(vec![MonitorBounds::default()]);
+        manager.show().unwrap();
+        assert_eq!(manager.position(), Some((1120, 100)));
+
+        // Monitors not reported yet: nothing to judge against
+        let mut manager = WindowManager::new(&config_at(2500, 100)).unwrap();
+        assert!(!manager.monitors_known());
+        manager.show().unwrap();
+        assert_eq!(manager.position(), Some((2500, 100)));
+
+        // Still connected: left alone
+        let mut manager = WindowManager::new(&config_at(2500, 100)).unwrap();
+        manager.set_monitors(vec![
+            MonitorBounds::default(),
+            MonitorBounds::new(1920, 0, 2560, 1440),
+        ]);
+        manager.show().unwrap();
+        assert_eq!(manager.position(), Some((2500, 100)));
+
+        // Title bar above the top edge
+        let mut manager = WindowManager::new(&config_at(100, -50)).unwrap();
+        manager.set_monitors(vec![MonitorBounds::default()]);
+        manager.show().unwrap();
+        assert_eq!(manager.position(), Some((100, 0)));
+
+        manager.reset_window_position().unwrap();
+        assert_eq!(manager.position(), None);
+        assert_eq!(manager.dimensions(), (800, 600));
+    }
+}
@@ -0,0 +1,234 @@
+//! # Tool Result Cache
+//!
+//! Opt-in cache for idempotent tool calls (documentation fetches, library
+//! lookups, ...). Entries are keyed by server, tool and the canonical form of
+//! the arguments, so `{"a":1,"b":2}` and `{"b":2,"a":1}` share an entry.
+
+use crate::types::ToolCallResponse;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Tool name fragments that mark side effects; such tools are never cached
+/// even if configured as cacheable
+const NEVER_CACHEABLE: &[&str] = &[
+    "navigate", "goto", "go_back", "go_forward", "reload", "click", "fill", "submit", "upload",
+];
+
+/// Whether a tool can never be cached because it changes state
+/// (navigation, form input and submission)
+pub fn is_never_cacheable(tool_name: &str) -> bool {
+    let name = tool_name.to_lowercase();
+    NEVER_CACHEABLE.iter().any(|fragment| name.contains(fragment))
+}
+
+/// Serialize JSON with object keys sorted at every level
+pub fn canonicalize(value: &Value) -> String {
+    fn write(value: &Value, out: &mut String) {
+        match value {
+            Value::Object(map) => {
+                let mut keys: Vec<&String> = map.keys().collect();
+                keys.sort();
+                out.push('{');
+                for (i, key) in keys.into_iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    out.push_str(&Value::String(key.clone()).to_string());
+                    out.push(':');
+                    write(&map[key], out);
+                }
+                out.push('}');
+            }
+            Value::Array(items) => {
+                out.push('[');
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        out.push(',');
+                    }
+                    write(item, out);
+                }
+                out.push(']');
+            }
+            other => out.push_str(&other.to_string()),
+        }
+    }
+
+    let mut out = String::new();
+    write(value, &mut out);
+    out
+}
+
+/// Cache hit/miss counters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Calls answered from the cache
+    pub hits: u64,
+    /// Cacheable calls that went to the server
+    pub misses: u64,
+    /// Entries currently stored (including expired ones not yet evicted)
+    pub entries: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct CacheKey {
+    server: String,
+    tool: String,
+    arguments: String,
+}
+
+struct CachedResponse {
+    stored_at: Instant,
+    response: ToolCallResponse,
+}
+
+/// TTL cache of tool call responses
+pub struct ToolCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<CacheKey, CachedResponse>>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl ToolCache {
+    /// Create a cache whose entries expire after `ttl`
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    fn key(server: &str, tool: &str, arguments: &Value) -> CacheKey {
+        CacheKey {
+            server: server.to_string(),
+            tool: tool.to_string(),
+            arguments: canonicalize(arguments),
+        }
+    }
+
+    /// Look up a fresh response, counting the hit or miss
+    pub fn get(&self, server: &str, tool: &str, arguments: &Value) -> Option<ToolCallResponse> {
+        let key = Self::key(server, tool, arguments);
+        let mut entries = self.entries.lock().unwrap();
+
+        match entries.get(&key) {
+            Some(cached) if cached.stored_at.elapsed() < self.ttl => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                Some(cached.response.clone())
+            }
+            stale => {
+                if stale.is_some() {
+                    entries.remove(&key);
+                }
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+
+    /// Store a response. Errors and still-running results are not cached.
+    ///
+    /// Expired entries are swept first, so keys that are never looked up
+    /// again do not pile up.
+    pub fn insert(&self, server: &str, tool: &str, arguments: &Value, response: &ToolCallResponse) {
+        if response.error.is_some() || response.is_running {
+            return;
+        }
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, cached| cached.stored_at.elapsed() < self.ttl);
+        entries.insert(
+            Self::key(server, tool, arguments),
+            CachedResponse {
+                stored_at: Instant::now(),
+                response: response.clone(),
+            },
+        );
+    }
+
+    /// Drop all entries (counters are kept)
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+
+    /// Current hit/miss counts
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: self.entries.lock().unwrap().len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn response(text: &str) -> ToolCallResponse {
+        ToolCallResponse {
+            id: "1".to_string(),
+            content: Some(vec![crate::types::ContentBlock::Text {
+                text: text.to_string(),
+            }]),
+            error: None,
+            is_running: false,
+        }
+    }
+
+    #[test]
+    fn test_canonicalize_sorts_nested_keys() {
+        let a = json!({"b": {"y": 1, "x": [{"d": 1, "c": 2}]}, "a": "s"});
+        let b = json!({"a": "s", "b": {"x": [{"c": 2, "d": 1}], "y": 1}});
+        assert_eq!(canonicalize(&a), canonicalize(&b));
+        assert_eq!(canonicalize(&a), r#"{"a":"s","b":{"x":[{"c":2,"d":1}],"y":1}}"#);
+    }
+
+    #[test]
+    fn test_hits_misses_and_expiry() {
+        let cache = ToolCache::new(Duration::from_secs(60));
+        let args = json!({"library": "tokio", "topic": "sync"});
+
+        assert!(cache.get("context7", "get-docs", &args).is_none());
+        cache.insert("context7", "get-docs", &args, &response("docs"));
+        let reordered = json!({"topic": "sync", "library": "tokio"});
+        assert!(cache.get("context7", "get-docs", &reordered).is_some());
+        assert!(cache.get("other", "get-docs", &args).is_none());
+        assert_eq!(
+            cache.stats(),
+            CacheStats {
+                hits: 1,
+                misses: 2,
+                entries: 1
+            }
+        );
+
+        let expired = ToolCache::new(Duration::ZERO);
+        expired.insert("context7", "get-docs", &args, &response("docs"));
+        assert!(expired.get("context7", "get-docs", &args).is_none());
+        assert_eq!(expired.stats().entries, 0);
+    }
+
+    #[test]
+    fn test_insert_sweeps_expired_entries() {
+        let cache = ToolCache::new(Duration::ZERO);
+        for library in ["tokio", "serde", "axum"] {
+            cache.insert("context7", "get-docs", &json!({ "library": library }), &response("docs"));
+        }
+        // Only the newest entry survives; the others were never looked up again
+        assert_eq!(cache.stats().entries, 1);
+    }
+
+    #[test]
+    fn test_side_effect_tools_are_never_cacheable() {
+        assert!(is_never_cacheable("puppeteer_navigate"));
+        assert!(is_never_cacheable("browser_click"));
+        assert!(is_never_cacheable("fillForm"));
+        assert!(!is_never_cacheable("get-library-docs"));
+    }
+}
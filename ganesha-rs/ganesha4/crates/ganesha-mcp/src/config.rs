@@ -5,13 +5,14 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 use tokio::fs;
 
 use crate::McpProtocolError;
 use crate::types::Result;
 
 /// MCP configuration
-#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct McpConfig {
     /// Server configurations by ID
     #[serde(default)]
@@ -81,7 +82,7 @@ impl McpConfig {
 }
 
 /// Configuration for a single MCP server
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ServerConfig {
     /// Display name
     pub name: String,
@@ -117,9 +118,23 @@ pub struct ServerConfig {
     #[serde(default)]
     pub required_env: Vec<String>,
 
+    /// Reject tool calls whose arguments do not match the tool's schema.
+    /// When false, mismatches are only logged (for servers with loose schemas).
+    #[serde(default = "default_true")]
+    pub strict_schema: bool,
+
+    /// Idempotent tools whose results may be cached (when the manager's
+    /// tool cache is enabled)
+    #[serde(default)]
+    pub cacheable_tools: Vec<String>,
+
     /// Description of what this server provides
     #[serde(skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
+
+    /// Reconnection policy when a stdio server process dies
+    #[serde(default)]
+    pub reconnect: ReconnectConfig,
 }
 
 fn default_true() -> bool {
@@ -130,8 +145,49 @@ fn default_timeout() -> u64 {
     30
 }
 
+/// Reconnection policy for stdio servers whose process exited
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReconnectConfig {
+    /// Re-spawn the server automatically
+    pub enabled: bool,
+    /// Attempts before giving up
+    pub max_retries: u32,
+    /// Delay before the first attempt in milliseconds (doubles each attempt)
+    pub initial_backoff_ms: u64,
+    /// Upper bound on the delay between attempts in milliseconds
+    pub max_backoff_ms: u64,
+    /// Tool calls made during an outage wait for the reconnect instead of
+    /// failing immediately
+    pub wait_for_reconnect: bool,
+    /// How long a waiting tool call waits in seconds
+    pub wait_timeout: u64,
+}
+
+impl Default for ReconnectConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            max_retries: 5,
+            initial_backoff_ms: 500,
+            max_backoff_ms: 30_000,
+            wait_for_reconnect: true,
+            wait_timeout: 30,
+        }
+    }
+}
+
+impl ReconnectConfig {
+    /// Delay before the given attempt (starting at 1)
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 1u64.checked_shl(attempt.saturating_sub(1)).unwrap_or(u64::MAX);
+        let delay = self.initial_backoff_ms.saturating_mul(factor);
+        Duration::from_millis(delay.min(self.max_backoff_ms))
+    }
+}
+
 /// Transport configuration
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "lowercase")]
 pub enum TransportConfig {
     /// Stdio (local process)
@@ -186,7 +242,10 @@ impl ServerConfig {
             exclude_tools: None,
             timeout: 30,
             required_env: Vec::new(),
+            strict_schema: true,
+            cacheable_tools: Vec::new(),
             description: None,
+            reconnect: ReconnectConfig::default(),
         }
     }
 
@@ -205,7 +264,10 @@ impl ServerConfig {
             exclude_tools: None,
             timeout: 30,
             required_env: Vec::new(),
+            strict_schema: true,
+            cacheable_tools: Vec::new(),
             description: None,
+            reconnect: ReconnectConfig::default(),
         }
     }
 
@@ -263,7 +325,10 @@ pub mod presets {
             exclude_tools: None,
             timeout: 30,
             required_env: Vec::new(),
+            strict_schema: true,
+            cacheable_tools: Vec::new(),
             description: Some("Access to local filesystem".to_string()),
+            reconnect: ReconnectConfig::default(),
         }
     }
 
@@ -287,7 +352,10 @@ pub mod presets {
             exclude_tools: None,
             timeout: 30,
             required_env: vec!["GITHUB_PERSONAL_ACCESS_TOKEN".to_string()],
+            strict_schema: true,
+            cacheable_tools: Vec::new(),
             description: Some("GitHub repository access".to_string()),
+            reconnect: ReconnectConfig::default(),
         }
     }
 
@@ -311,7 +379,10 @@ pub mod presets {
             exclude_tools: None,
             timeout: 30,
             required_env: vec!["BRAVE_API_KEY".to_string()],
+            strict_schema: true,
+            cacheable_tools: Vec::new(),
             description: Some("Web search via Brave".to_string()),
+            reconnect: ReconnectConfig::default(),
         }
     }
 
@@ -342,7 +413,10 @@ pub mod presets {
             exclude_tools: None,
             timeout: 120, // Browser operations can take time
             required_env: Vec::new(),
+            strict_schema: true,
+            cacheable_tools: Vec::new(),
             description: Some("Browser automation via Puppeteer".to_string()),
+            reconnect: ReconnectConfig::default(),
         }
     }
 
@@ -366,7 +440,10 @@ pub mod presets {
             exclude_tools: None,
             timeout: 120,
             required_env: Vec::new(),
+            strict_schema: true,
+            cacheable_tools: Vec::new(),
             description: Some("Browser automation".to_string()),
+            reconnect: ReconnectConfig::default(),
         }
     }
 
@@ -390,7 +467,10 @@ pub mod presets {
             exclude_tools: None,
             timeout: 30,
             required_env: Vec::new(),
+            strict_schema: true,
+            cacheable_tools: Vec::new(),
             description: Some("Persistent knowledge storage".to_string()),
+            reconnect: ReconnectConfig::default(),
         }
     }
 }
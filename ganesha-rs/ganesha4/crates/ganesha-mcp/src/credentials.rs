@@ -0,0 +1,177 @@
+//! # Credentials
+//!
+//! Server configs refer to secrets either through `required_env` or with
+//! `${NAME}` placeholders in the command, arguments, environment, URL or
+//! headers. The manager resolves them from the process environment first and
+//! asks its `CredentialProvider` for anything still missing, so a GUI can
+//! plug in an interactive prompt while the CLI reads env vars and the
+//! system keyring.
+
+use crate::config::{ServerConfig, TransportConfig};
+use std::collections::HashMap;
+use tracing::debug;
+
+/// Keyring service that MCP secrets are stored under
+pub const KEYRING_SERVICE: &str = "ganesha-mcp";
+
+/// Source of secrets for MCP servers
+pub trait CredentialProvider: Send + Sync {
+    /// Look up the secret `key` for `server`
+    fn get_secret(&self, server: &str, key: &str) -> Option<String>;
+}
+
+/// Reads environment variables, then the system keyring
+/// (service `ganesha-mcp`, user `<server>/<key>` or just `<key>`)
+#[derive(Debug, Clone, Default)]
+pub struct DefaultCredentialProvider;
+
+impl CredentialProvider for DefaultCredentialProvider {
+    fn get_secret(&self, server: &str, key: &str) -> Option<String> {
+        if let Ok(value) = std::env::var(key) {
+            return Some(value);
+        }
+
+        [format!("{}/{}", server, key), key.to_string()]
+            .iter()
+            .find_map(|user| {
+                keyring::Entry::new(KEYRING_SERVICE, user)
+                    .and_then(|entry| entry.get_password())
+                    .map_err(|e| debug!("No keyring entry {}: {}", user, e))
+                    .ok()
+            })
+    }
+}
+
+/// Adapts the older prompt-style `CredentialHandler`
+pub(crate) struct HandlerProvider(pub(crate) Box<dyn crate::manager::CredentialHandler>);
+
+impl CredentialProvider for HandlerProvider {
+    fn get_secret(&self, _server: &str, key: &str) -> Option<String> {
+        self.0.request_credential(key, "", None)
+    }
+}
+
+/// Expand `${NAME}` placeholders, recording names `lookup` cannot resolve
+fn substitute(
+    value: &str,
+    lookup: &mut impl FnMut(&str) -> Option<String>,
+    missing: &mut Vec<String>,
+) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(start) = rest.find("${") {
+        let Some(len) = rest[start + 2..].find('}') else {
+            break;
+        };
+        let name = &rest[start + 2..start + 2 + len];
+        out.push_str(&rest[..start]);
+        match lookup(name) {
+            Some(secret) => out.push_str(&secret),
+            None => {
+                if !missing.iter().any(|m| m == name) {
+                    missing.push(name.to_string());
+                }
+            }
+        }
+        rest = &rest[start + 3 + len..];
+    }
+
+    out.push_str(rest);
+    out
+}
+
+/// Resolve a server config's credentials.
+///
+/// Placeholders are replaced with their secrets, and `required_env` entries
+/// that are not set in the environment are passed to the server process as
+/// environment variables. Returns the names that could not be resolved.
+pub(crate) fn resolve(
+    config: &ServerConfig,
+    mut lookup: impl FnMut(&str) -> Option<String>,
+) -> std::result::Result<ServerConfig, Vec<String>> {
+    let mut missing = Vec::new();
+    let mut resolved = config.clone();
+    let mut sub = |value: &mut String, missing: &mut Vec<String>| {
+        *value = substitute(value, &mut lookup, missing);
+    };
+
+    let mut extra_env = HashMap::new();
+    match &mut resolved.transport {
+        TransportConfig::Stdio {
+            command, args, env, ..
+        } => {
+            sub(command, &mut missing);
+            args.iter_mut().for_each(|arg| sub(arg, &mut missing));
+            env.values_mut().for_each(|value| sub(value, &mut missing));
+        }
+        TransportConfig::Sse { url, auth } => {
+            sub(url, &mut missing);
+            if let Some(auth) = auth {
+                sub(auth, &mut missing);
+            }
+        }
+        TransportConfig::Http { url, headers } => {
+            sub(url, &mut missing);
+            headers.values_mut().for_each(|value| sub(value, &mut missing));
+        }
+    }
+
+    for key in &config.required_env {
+        if std::env::var(key).is_ok() {
+            continue;
+        }
+        match lookup(key) {
+            Some(secret) => {
+                extra_env.insert(key.clone(), secret);
+            }
+            None if !missing.contains(key) => missing.push(key.clone()),
+            None => {}
+        }
+    }
+    if let TransportConfig::Stdio { env, .. } = &mut resolved.transport {
+        for (key, secret) in extra_env {
+            env.entry(key).or_insert(secret);
+        }
+    }
+
+    if missing.is_empty() {
+        Ok(resolved)
+    } else {
+        Err(missing)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_placeholders_and_required_env() {
+        let mut config = ServerConfig::sse("Remote", "https://example.com/${GANESHA_TEST_REGION}/mcp");
+        if let TransportConfig::Sse { auth, .. } = &mut config.transport {
+            *auth = Some("Bearer ${GANESHA_TEST_TOKEN}".to_string());
+        }
+
+        let secrets: HashMap<&str, &str> =
+            [("GANESHA_TEST_REGION", "eu"), ("GANESHA_TEST_TOKEN", "s3cret")].into();
+        let resolved = resolve(&config, |key| secrets.get(key).map(|s| s.to_string())).unwrap();
+        let TransportConfig::Sse { url, auth } = resolved.transport else {
+            unreachable!()
+        };
+        assert_eq!(url, "https://example.com/eu/mcp");
+        assert_eq!(auth.as_deref(), Some("Bearer s3cret"));
+
+        let mut stdio = ServerConfig::stdio("GitHub", "github-mcp");
+        stdio.required_env = vec!["GANESHA_TEST_UNSET_TOKEN".to_string()];
+        assert_eq!(
+            resolve(&stdio, |_| None).unwrap_err(),
+            vec!["GANESHA_TEST_UNSET_TOKEN".to_string()]
+        );
+        let resolved = resolve(&stdio, |_| Some("tok".to_string())).unwrap();
+        let TransportConfig::Stdio { env, .. } = resolved.transport else {
+            unreachable!()
+        };
+        assert_eq!(env["GANESHA_TEST_UNSET_TOKEN"], "tok");
+    }
+}
@@ -8,10 +8,12 @@
 //! tools and data sources. This crate provides:
 //!
 //! - Server discovery and management
-//! - Hot-loading of servers (add/remove without restart)
+//! - Hot-loading of servers (add/remove without restart, or by editing
+//!   the config files while `McpManager::watch_config` is running)
 //! - Multiple transport support (stdio, SSE, HTTP)
 //! - Automatic credential prompting
 //! - Tool/resource/prompt exposure to the LLM
+//! - Opt-in caching of idempotent tool results
 //!
 //! ## Transport Types
 //!
@@ -32,17 +34,25 @@ pub mod server;
 pub mod manager;
 pub mod config;
 pub mod registry;
+pub mod cache;
+pub mod credentials;
+pub mod watch;
 
 pub use types::{
     Tool, ToolSchema, Resource, Prompt, PromptArgument,
     ToolCallRequest, ToolCallResponse,
     McpError, Result,
 };
-pub use transport::{Transport, StdioTransport, SseTransport, HttpTransport};
+pub use transport::{
+    collect_response, ChunkStream, Transport, StdioTransport, SseTransport, HttpTransport,
+};
 pub use server::{McpServer, ServerStatus, ServerCapabilities};
-pub use manager::McpManager;
-pub use config::{McpConfig, ServerConfig};
-pub use registry::ServerRegistry;
+pub use manager::{ConfigDiff, ConnectionEvent, CredentialHandler, McpManager};
+pub use watch::ConfigWatcher;
+pub use credentials::{CredentialProvider, DefaultCredentialProvider};
+pub use config::{McpConfig, ReconnectConfig, ServerConfig};
+pub use registry::{RegistryEntry, RegistryTransport, ServerRegistry};
+pub use cache::{CacheStats, ToolCache};
 
 use thiserror::Error;
 
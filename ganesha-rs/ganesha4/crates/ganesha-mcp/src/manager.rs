@@ -1,29 +1,172 @@
 //! # MCP Manager
 //!
 //! Manages multiple MCP servers with hot-loading support.
+//!
+//! When a stdio server process dies, the manager re-spawns it in the
+//! background with exponential backoff (see `ReconnectConfig`). Tool calls
+//! made during the outage either wait for the reconnect or fail fast with
+//! `ServerNotConnected`. Progress is reported as `ConnectionEvent`s.
 
-use crate::config::{McpConfig, ServerConfig, TransportConfig};
+use crate::cache::{self, CacheStats, ToolCache};
+use crate::credentials::{self, CredentialProvider, DefaultCredentialProvider, HandlerProvider};
+use crate::config::{McpConfig, ReconnectConfig, ServerConfig, TransportConfig};
 use crate::server::{McpServer, ServerStatus};
-use crate::transport::{HttpTransport, SseTransport, StdioTransport, Transport};
+use crate::transport::{ChunkStream, HttpTransport, SseTransport, StdioTransport, Transport};
 use crate::types::{Result, Tool, ToolCallRequest, ToolCallResponse};
 use crate::McpProtocolError;
 use std::collections::HashMap;
-use std::path::Path;
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{broadcast, watch, RwLock};
+use tokio::task::JoinHandle;
 use tracing::{debug, info, warn};
 
+/// Connection events buffered for slow subscribers
+const EVENT_BUFFER: usize = 64;
+
+/// Connection state changes, e.g. for showing "reconnecting to playwright..."
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectionEvent {
+    /// Server connected (or reconnected)
+    Connected { server: String },
+    /// Server process exited
+    Disconnected { server: String },
+    /// About to re-spawn the server after `delay`
+    Reconnecting {
+        server: String,
+        attempt: u32,
+        max_retries: u32,
+        delay: Duration,
+    },
+    /// All reconnect attempts failed
+    ReconnectFailed { server: String, error: String },
+    /// Config files changed and were applied
+    ConfigReloaded(ConfigDiff),
+    /// Config files changed but could not be loaded; nothing was applied
+    ConfigReloadFailed { error: String },
+}
+
+impl fmt::Display for ConnectionEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConnectionEvent::Connected { server } => write!(f, "connected to {}", server),
+            ConnectionEvent::Disconnected { server } => write!(f, "lost connection to {}", server),
+            ConnectionEvent::Reconnecting {
+                server,
+                attempt,
+                max_retries,
+                ..
+            } => write!(
+                f,
+                "reconnecting to {}... (attempt {}/{})",
+                server, attempt, max_retries
+            ),
+            ConnectionEvent::ReconnectFailed { server, error } => {
+                write!(f, "gave up reconnecting to {}: {}", server, error)
+            }
+            ConnectionEvent::ConfigReloaded(diff) => write!(f, "MCP config reloaded: {}", diff),
+            ConnectionEvent::ConfigReloadFailed { error } => {
+                write!(f, "failed to reload MCP config: {}", error)
+            }
+        }
+    }
+}
+
+/// Servers that differ between two configs
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConfigDiff {
+    /// Newly configured servers
+    pub added: Vec<String>,
+    /// Servers no longer configured
+    pub removed: Vec<String>,
+    /// Servers whose config changed
+    pub changed: Vec<String>,
+}
+
+impl ConfigDiff {
+    /// Compare two configs server by server
+    pub fn between(old: &McpConfig, new: &McpConfig) -> Self {
+        let mut diff = ConfigDiff::default();
+        for (id, config) in &new.servers {
+            match old.servers.get(id) {
+                None => diff.added.push(id.clone()),
+                Some(previous) if previous != config => diff.changed.push(id.clone()),
+                Some(_) => {}
+            }
+        }
+        diff.removed = old
+            .servers
+            .keys()
+            .filter(|id| !new.servers.contains_key(*id))
+            .cloned()
+            .collect();
+
+        diff.added.sort();
+        diff.removed.sort();
+        diff.changed.sort();
+        diff
+    }
+
+    /// Whether nothing changed
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl fmt::Display for ConfigDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let parts: Vec<String> = [
+            ("added", &self.added),
+            ("removed", &self.removed),
+            ("changed", &self.changed),
+        ]
+        .iter()
+        .filter(|(_, ids)| !ids.is_empty())
+        .map(|(label, ids)| format!("{} {}", label, ids.join(", ")))
+        .collect();
+
+        if parts.is_empty() {
+            write!(f, "no changes")
+        } else {
+            write!(f, "{}", parts.join("; "))
+        }
+    }
+}
+
+/// A background reconnect in progress (or finished)
+struct ReconnectTask {
+    /// None while running, then whether the server came back
+    outcome: watch::Receiver<Option<bool>>,
+    handle: JoinHandle<()>,
+}
+
 /// Manages multiple MCP servers
 pub struct McpManager {
     /// Connected servers by ID
-    servers: RwLock<HashMap<String, Arc<McpServer>>>,
+    servers: Arc<RwLock<HashMap<String, Arc<McpServer>>>>,
     /// Configuration
     config: RwLock<McpConfig>,
-    /// Callback for requesting credentials
-    credential_handler: Option<Box<dyn CredentialHandler>>,
+    /// What the config files contained at the last (re)load, so a reload
+    /// leaves servers added at runtime alone
+    file_config: RwLock<McpConfig>,
+    /// Source of secrets missing from the environment
+    credential_provider: Box<dyn CredentialProvider>,
+    /// Configs with credentials filled in, for reconnecting
+    resolved_configs: RwLock<HashMap<String, ServerConfig>>,
+    /// Reconnects by server ID
+    reconnects: Mutex<HashMap<String, ReconnectTask>>,
+    /// Connection event broadcaster
+    events: broadcast::Sender<ConnectionEvent>,
+    /// Tool result cache, if enabled
+    cache: Option<ToolCache>,
+    /// Runtime cacheability overrides by "server:tool"
+    cache_overrides: Mutex<HashMap<String, bool>>,
 }
 
-/// Handler for requesting credentials from the user
+/// Handler for requesting credentials from the user.
+/// Prefer `CredentialProvider`, which also receives the server ID.
 pub trait CredentialHandler: Send + Sync {
     /// Request a credential value
     fn request_credential(
@@ -38,24 +181,80 @@ impl McpManager {
     /// Create a new MCP manager
     pub fn new() -> Self {
         Self {
-            servers: RwLock::new(HashMap::new()),
+            servers: Arc::new(RwLock::new(HashMap::new())),
             config: RwLock::new(McpConfig::default()),
-            credential_handler: None,
+            file_config: RwLock::new(McpConfig::default()),
+            credential_provider: Box::new(DefaultCredentialProvider),
+            resolved_configs: RwLock::new(HashMap::new()),
+            reconnects: Mutex::new(HashMap::new()),
+            events: broadcast::channel(EVENT_BUFFER).0,
+            cache: None,
+            cache_overrides: Mutex::new(HashMap::new()),
         }
     }
 
     /// Set a credential handler for prompting users
     pub fn with_credential_handler(mut self, handler: impl CredentialHandler + 'static) -> Self {
-        self.credential_handler = Some(Box::new(handler));
+        self.credential_provider = Box::new(HandlerProvider(Box::new(handler)));
+        self
+    }
+
+    /// Set where missing credentials come from (default: env vars, then the
+    /// system keyring)
+    pub fn with_credential_provider(mut self, provider: impl CredentialProvider + 'static) -> Self {
+        self.credential_provider = Box::new(provider);
+        self
+    }
+
+    /// Enable caching of results from cacheable tools for `ttl`
+    pub fn with_tool_cache(mut self, ttl: Duration) -> Self {
+        self.cache = Some(ToolCache::new(ttl));
         self
     }
 
+    /// Mark a tool ("server:tool") as cacheable or not, overriding the
+    /// server config. Navigation and form tools are never cached.
+    pub fn set_tool_cacheable(&self, tool_id: &str, cacheable: bool) {
+        self.cache_overrides
+            .lock()
+            .unwrap()
+            .insert(tool_id.to_string(), cacheable);
+    }
+
+    /// Whether results of a tool may be served from the cache
+    pub async fn is_tool_cacheable(&self, server_id: &str, tool_name: &str) -> bool {
+        if cache::is_never_cacheable(tool_name) {
+            return false;
+        }
+        let tool_id = format!("{}:{}", server_id, tool_name);
+        if let Some(&cacheable) = self.cache_overrides.lock().unwrap().get(&tool_id) {
+            return cacheable;
+        }
+        self.config
+            .read()
+            .await
+            .servers
+            .get(server_id)
+            .is_some_and(|c| c.cacheable_tools.iter().any(|t| t == tool_name))
+    }
+
+    /// Drop all cached tool results
+    pub fn clear_cache(&self) {
+        if let Some(cache) = &self.cache {
+            cache.clear();
+        }
+    }
+
+    /// Tool cache hit/miss counts (all zero when caching is disabled)
+    pub fn cache_stats(&self) -> CacheStats {
+        self.cache.as_ref().map(ToolCache::stats).unwrap_or_default()
+    }
+
     /// Load configuration from files
     pub async fn load_config(&self) -> Result<()> {
-        let paths = McpConfig::default_paths();
         let mut merged_config = McpConfig::default();
 
-        for path in paths {
+        for path in McpConfig::default_paths() {
             if path.exists() {
                 info!("Loading MCP config from {:?}", path);
                 match McpConfig::load(&path).await {
@@ -65,10 +264,75 @@ impl McpManager {
             }
         }
 
+        *self.file_config.write().await = merged_config.clone();
         *self.config.write().await = merged_config;
         Ok(())
     }
 
+    /// Re-read config files (later paths override earlier ones, as in
+    /// `load_config`) and apply the difference: new servers are added and
+    /// auto-connected, removed ones disconnected, and connected servers
+    /// whose config changed are reconnected. Unchanged servers are left
+    /// running. If any file fails to parse nothing is applied.
+    pub async fn reload_config(&self, paths: &[PathBuf]) -> Result<ConfigDiff> {
+        let mut new_files = McpConfig::default();
+        for path in paths {
+            match McpConfig::load(path).await {
+                Ok(config) => new_files.merge(config),
+                Err(e) => {
+                    let error = format!("{}: {}", path.display(), e);
+                    let _ = self.events.send(ConnectionEvent::ConfigReloadFailed {
+                        error: error.clone(),
+                    });
+                    return Err(McpProtocolError::ConfigError(error));
+                }
+            }
+        }
+
+        let old_files =
+            std::mem::replace(&mut *self.file_config.write().await, new_files.clone());
+        let diff = ConfigDiff::between(&old_files, &new_files);
+        if diff.is_empty() {
+            return Ok(diff);
+        }
+        info!("MCP config changed: {}", diff);
+
+        for id in &diff.removed {
+            if let Err(e) = self.disconnect(id).await {
+                warn!("Error disconnecting from {}: {}", id, e);
+            }
+            self.config.write().await.servers.remove(id);
+        }
+
+        for id in diff.added.iter().chain(&diff.changed) {
+            let server_config = new_files.servers[id].clone();
+            let was_connected =
+                self.servers.read().await.contains_key(id) || self.is_reconnecting(id);
+            if was_connected {
+                if let Err(e) = self.disconnect(id).await {
+                    warn!("Error disconnecting from {}: {}", id, e);
+                }
+            }
+
+            let connect = server_config.enabled && (was_connected || server_config.auto_connect);
+            self.config
+                .write()
+                .await
+                .servers
+                .insert(id.clone(), server_config);
+            if connect {
+                if let Err(e) = self.connect(id).await {
+                    warn!("Failed to connect to {} after config change: {}", id, e);
+                }
+            }
+        }
+
+        let _ = self
+            .events
+            .send(ConnectionEvent::ConfigReloaded(diff.clone()));
+        Ok(diff)
+    }
+
     /// Load configuration from a specific path
     pub async fn load_config_from(&self, path: &Path) -> Result<()> {
         let config = McpConfig::load(path).await?;
@@ -130,80 +394,73 @@ impl McpManager {
             )));
         }
 
-        // Check for missing environment variables
-        let missing = server_config.missing_env_vars();
-        if !missing.is_empty() {
-            // Try to get credentials via handler
-            if let Some(handler) = &self.credential_handler {
-                for var in &missing {
-                    if let Some(value) = handler.request_credential(var, "", None) {
-                        std::env::set_var(var, value);
-                    }
-                }
-            }
-
-            // Check again
-            let still_missing = server_config.missing_env_vars();
-            if !still_missing.is_empty() {
-                return Err(McpProtocolError::AuthRequired(format!(
-                    "Missing environment variables: {}",
-                    still_missing.join(", ")
-                )));
-            }
-        }
-
         let server_config = server_config.clone();
         drop(config);
 
         info!("Connecting to MCP server: {}", id);
-
-        // Create transport based on config
-        let transport: Arc<dyn Transport> = match &server_config.transport {
-            TransportConfig::Stdio {
-                command,
-                args,
-                env,
-                cwd,
-            } => {
-                let transport = StdioTransport::spawn(
-                    command,
-                    args,
-                    env,
-                    cwd.as_deref(),
-                )
-                .await?;
-                Arc::new(transport)
-            }
-            TransportConfig::Sse { url, auth } => {
-                let transport = if let Some(auth) = auth {
-                    SseTransport::with_auth(url, auth)
-                } else {
-                    SseTransport::new(url)
-                };
-                Arc::new(transport)
-            }
-            TransportConfig::Http { url, headers: _ } => {
-                Arc::new(HttpTransport::new(url))
+        let server = match self.open_with_credentials(id, &server_config, false).await {
+            Err(McpProtocolError::AuthRequired(reason)) => {
+                debug!("{} needs credentials ({}), asking the credential provider", id, reason);
+                self.open_with_credentials(id, &server_config, true).await?
             }
+            other => other?,
         };
 
-        // Create and initialize server
-        let mut server = McpServer::new(id, &server_config.name, transport);
-        server.set_trusted(server_config.trusted);
-        server.initialize().await?;
-
         // Store server
         self.servers
             .write()
             .await
             .insert(id.to_string(), Arc::new(server));
 
+        let _ = self.events.send(ConnectionEvent::Connected {
+            server: id.to_string(),
+        });
         info!("Connected to MCP server: {}", id);
         Ok(())
     }
 
+    /// Resolve the config's credentials and open the server. The environment
+    /// is checked first; the credential provider is only asked on the retry
+    /// after `AuthRequired`.
+    async fn open_with_credentials(
+        &self,
+        id: &str,
+        config: &ServerConfig,
+        ask_provider: bool,
+    ) -> Result<McpServer> {
+        let provider = &self.credential_provider;
+        let resolved = credentials::resolve(config, |key| {
+            let from_provider = || provider.get_secret(id, key);
+            if ask_provider {
+                from_provider().or_else(|| std::env::var(key).ok())
+            } else {
+                std::env::var(key).ok()
+            }
+        })
+        .map_err(|missing| {
+            McpProtocolError::AuthRequired(format!("Missing credentials: {}", missing.join(", ")))
+        })?;
+
+        let server = open_server(id, &resolved).await?;
+        self.resolved_configs
+            .write()
+            .await
+            .insert(id.to_string(), resolved);
+        Ok(server)
+    }
+
+    /// Config to re-spawn a server with, credentials included
+    async fn reconnect_config(&self, id: &str) -> Option<ServerConfig> {
+        if let Some(resolved) = self.resolved_configs.read().await.get(id) {
+            return Some(resolved.clone());
+        }
+        self.config.read().await.servers.get(id).cloned()
+    }
+
     /// Disconnect from a server
     pub async fn disconnect(&self, id: &str) -> Result<()> {
+        self.cancel_reconnect(id);
+        self.resolved_configs.write().await.remove(id);
         if let Some(server) = self.servers.write().await.remove(id) {
             server.disconnect().await?;
             info!("Disconnected from MCP server: {}", id);
@@ -213,6 +470,9 @@ impl McpManager {
 
     /// Disconnect from all servers
     pub async fn disconnect_all(&self) -> Result<()> {
+        for (_, task) in self.reconnects.lock().unwrap().drain() {
+            task.handle.abort();
+        }
         let servers: Vec<_> = self.servers.write().await.drain().collect();
         for (id, server) in servers {
             if let Err(e) = server.disconnect().await {
@@ -271,6 +531,51 @@ impl McpManager {
 
     /// Call a tool (format: "server_id:tool_name")
     pub async fn call_tool(&self, tool_id: &str, arguments: serde_json::Value) -> Result<ToolCallResponse> {
+        let (server_id, tool_name, server, request) = self.prepare_call(tool_id, arguments).await?;
+        let cache = match &self.cache {
+            Some(cache) if self.is_tool_cacheable(server_id, tool_name).await => Some(cache),
+            _ => None,
+        };
+        if let Some(mut cached) =
+            cache.and_then(|c| c.get(server_id, tool_name, &request.arguments))
+        {
+            debug!("Cache hit for {}", tool_id);
+            cached.id = request.id;
+            return Ok(cached);
+        }
+
+        let response = match server.call_tool(request.clone()).await {
+            Ok(response) => response,
+            Err(e) => return Err(self.call_failed(&server, server_id, tool_name, e).await),
+        };
+
+        if let Some(cache) = cache {
+            cache.insert(server_id, tool_name, &request.arguments, &response);
+        }
+        Ok(response)
+    }
+
+    /// Call a tool and receive the raw JSON-RPC response body as it arrives,
+    /// for tools with large outputs. Arguments are validated as for
+    /// `call_tool`; streamed calls neither read nor fill the tool cache.
+    pub async fn call_tool_streaming(
+        &self,
+        tool_id: &str,
+        arguments: serde_json::Value,
+    ) -> Result<ChunkStream> {
+        let (server_id, tool_name, server, request) = self.prepare_call(tool_id, arguments).await?;
+        match server.call_tool_streaming(request).await {
+            Ok(chunks) => Ok(chunks),
+            Err(e) => Err(self.call_failed(&server, server_id, tool_name, e).await),
+        }
+    }
+
+    /// Resolve "server_id:tool_name" to a live server and a validated request
+    async fn prepare_call<'a>(
+        &self,
+        tool_id: &'a str,
+        arguments: serde_json::Value,
+    ) -> Result<(&'a str, &'a str, Arc<McpServer>, ToolCallRequest)> {
         let parts: Vec<&str> = tool_id.splitn(2, ':').collect();
         if parts.len() != 2 {
             return Err(McpProtocolError::InvalidRequest(format!(
@@ -282,21 +587,223 @@ impl McpManager {
         let server_id = parts[0];
         let tool_name = parts[1];
 
-        let servers = self.servers.read().await;
-        let server = servers.get(server_id).ok_or_else(|| {
-            McpProtocolError::ServerNotConnected(format!("Server '{}' not connected", server_id))
-        })?;
+        let server = self.live_server(server_id).await?;
 
         // Check if tool exists
-        if !server.has_tool(tool_name).await {
+        let Some(tool) = server.tools().await.into_iter().find(|t| t.name == tool_name) else {
             return Err(McpProtocolError::ToolNotFound(format!(
                 "Tool '{}' not found on server '{}'",
                 tool_name, server_id
             )));
-        }
+        };
 
         let request = ToolCallRequest::new(tool_name, arguments);
-        server.call_tool(request).await
+        self.validate_arguments(server_id, &tool, &request).await?;
+        Ok((server_id, tool_name, server, request))
+    }
+
+    /// The error to report for a failed call. If the process died mid-call
+    /// the server may already have acted on the request, so it is not
+    /// resent: reconnect so the next call finds a live server instead.
+    async fn call_failed(
+        &self,
+        server: &McpServer,
+        server_id: &str,
+        tool_name: &str,
+        e: McpProtocolError,
+    ) -> McpProtocolError {
+        if server.is_alive() {
+            return e;
+        }
+        debug!("Tool call on {} failed with dead transport: {}", server_id, e);
+        if let Err(reconnect) = self.recover(server_id).await {
+            debug!("Reconnect after failed call to {} failed: {}", server_id, reconnect);
+        }
+        McpProtocolError::TransportError(format!(
+            "Server '{}' exited during the call to '{}', which was not retried: {}",
+            server_id, tool_name, e
+        ))
+    }
+
+    /// Check a call's arguments against the tool schema before sending it,
+    /// so a misnamed or mistyped argument gets a clear error instead of a
+    /// cryptic one from the server
+    async fn validate_arguments(
+        &self,
+        server_id: &str,
+        tool: &Tool,
+        request: &ToolCallRequest,
+    ) -> Result<()> {
+        let Err(problems) = tool.input_schema.validate(&request.arguments) else {
+            return Ok(());
+        };
+        let message = format!(
+            "Invalid arguments for tool '{}:{}': {}",
+            server_id,
+            tool.name,
+            problems.join("; ")
+        );
+
+        let strict = self
+            .config
+            .read()
+            .await
+            .servers
+            .get(server_id)
+            .is_none_or(|c| c.strict_schema);
+        if strict {
+            Err(McpProtocolError::InvalidRequest(message))
+        } else {
+            warn!("{}", message);
+            Ok(())
+        }
+    }
+
+    /// Subscribe to connection events (connects, crashes, reconnect attempts)
+    pub fn subscribe_events(&self) -> broadcast::Receiver<ConnectionEvent> {
+        self.events.subscribe()
+    }
+
+    /// Look for stdio servers whose process exited and start reconnecting
+    /// them. Tool calls do this on their own; call this periodically to
+    /// recover idle servers too.
+    pub async fn check_connections(&self) {
+        let dead: Vec<String> = self
+            .servers
+            .read()
+            .await
+            .iter()
+            .filter(|(_, server)| !server.is_alive())
+            .map(|(id, _)| id.clone())
+            .collect();
+
+        for id in dead {
+            if let Some(config) = self.reconnect_config(&id).await {
+                self.mark_dead(&id).await;
+                self.start_reconnect(&id, &config);
+            }
+        }
+    }
+
+    /// Get a usable server, going through reconnection if its process died
+    async fn live_server(&self, id: &str) -> Result<Arc<McpServer>> {
+        let server = self.servers.read().await.get(id).cloned();
+        match server {
+            Some(server) if server.is_alive() => Ok(server),
+            Some(_) => self.recover(id).await,
+            None if self.is_reconnecting(id) => self.recover(id).await,
+            None => Err(McpProtocolError::ServerNotConnected(format!(
+                "Server '{}' not connected",
+                id
+            ))),
+        }
+    }
+
+    /// Handle an outage: make sure a reconnect is running, then wait for it
+    /// or fail fast depending on the server's `ReconnectConfig`
+    async fn recover(&self, id: &str) -> Result<Arc<McpServer>> {
+        let not_connected =
+            |why: &str| McpProtocolError::ServerNotConnected(format!("Server '{}' {}", id, why));
+
+        let config = self
+            .reconnect_config(id)
+            .await
+            .ok_or_else(|| not_connected("not connected"))?;
+
+        self.mark_dead(id).await;
+        let mut outcome = self
+            .start_reconnect(id, &config)
+            .ok_or_else(|| not_connected("process exited"))?;
+
+        let policy = &config.reconnect;
+        if !policy.wait_for_reconnect {
+            return Err(not_connected("is reconnecting"));
+        }
+
+        let wait = Duration::from_secs(policy.wait_timeout);
+        let reconnected = tokio::time::timeout(wait, outcome.wait_for(Option::is_some))
+            .await
+            .map(|state| state.map(|s| *s == Some(true)).unwrap_or(false));
+        match reconnected {
+            Ok(true) => self
+                .servers
+                .read()
+                .await
+                .get(id)
+                .cloned()
+                .ok_or_else(|| not_connected("not connected")),
+            Ok(false) => Err(not_connected("could not be reconnected")),
+            Err(_) => Err(McpProtocolError::Timeout(format!(
+                "Server '{}' did not reconnect within {}s",
+                id, policy.wait_timeout
+            ))),
+        }
+    }
+
+    /// Drop a server whose transport died and report it once
+    async fn mark_dead(&self, id: &str) {
+        let mut servers = self.servers.write().await;
+        if servers.get(id).is_some_and(|server| !server.is_alive()) {
+            servers.remove(id);
+            drop(servers);
+            warn!("MCP server '{}' exited", id);
+            let _ = self.events.send(ConnectionEvent::Disconnected {
+                server: id.to_string(),
+            });
+        }
+    }
+
+    fn is_reconnecting(&self, id: &str) -> bool {
+        self.reconnects
+            .lock()
+            .unwrap()
+            .get(id)
+            .is_some_and(|task| task.outcome.borrow().is_none())
+    }
+
+    fn cancel_reconnect(&self, id: &str) {
+        if let Some(task) = self.reconnects.lock().unwrap().remove(id) {
+            task.handle.abort();
+        }
+    }
+
+    /// Start a background reconnect unless one is already running. Returns
+    /// None when the server is not eligible (non-stdio or reconnect disabled).
+    fn start_reconnect(
+        &self,
+        id: &str,
+        config: &ServerConfig,
+    ) -> Option<watch::Receiver<Option<bool>>> {
+        let mut reconnects = self.reconnects.lock().unwrap();
+        if let Some(task) = reconnects.get(id) {
+            if task.outcome.borrow().is_none() {
+                return Some(task.outcome.clone());
+            }
+        }
+
+        let policy = config.reconnect.clone();
+        let is_stdio = matches!(config.transport, TransportConfig::Stdio { .. });
+        if !policy.enabled || !is_stdio || policy.max_retries == 0 {
+            return None;
+        }
+
+        let (tx, outcome) = watch::channel(None);
+        let handle = tokio::spawn(reconnect_loop(
+            id.to_string(),
+            config.clone(),
+            policy,
+            self.servers.clone(),
+            self.events.clone(),
+            tx,
+        ));
+        reconnects.insert(
+            id.to_string(),
+            ReconnectTask {
+                outcome: outcome.clone(),
+                handle,
+            },
+        );
+        Some(outcome)
     }
 
     /// Find which server has a specific tool
@@ -323,6 +830,94 @@ impl McpManager {
     }
 }
 
+/// Create the transport for a server config and run the MCP handshake
+async fn open_server(id: &str, server_config: &ServerConfig) -> Result<McpServer> {
+    // Create transport based on config
+    let transport: Arc<dyn Transport> = match &server_config.transport {
+        TransportConfig::Stdio {
+            command,
+            args,
+            env,
+            cwd,
+        } => {
+            let transport = StdioTransport::spawn(
+                command,
+                args,
+                env,
+                cwd.as_deref(),
+            )
+            .await?;
+            Arc::new(transport)
+        }
+        TransportConfig::Sse { url, auth } => {
+            let transport = if let Some(auth) = auth {
+                SseTransport::with_auth(url, auth)
+            } else {
+                SseTransport::new(url)
+            };
+            Arc::new(transport)
+        }
+        TransportConfig::Http { url, headers: _ } => {
+            Arc::new(HttpTransport::new(url))
+        }
+    };
+
+    // Create and initialize server
+    let mut server = McpServer::new(id, &server_config.name, transport);
+    server.set_trusted(server_config.trusted);
+    server.initialize().await?;
+    Ok(server)
+}
+
+/// Re-spawn a server with exponential backoff until it comes back or the
+/// retries run out
+async fn reconnect_loop(
+    id: String,
+    config: ServerConfig,
+    policy: ReconnectConfig,
+    servers: Arc<RwLock<HashMap<String, Arc<McpServer>>>>,
+    events: broadcast::Sender<ConnectionEvent>,
+    outcome: watch::Sender<Option<bool>>,
+) {
+    let mut last_error = String::new();
+
+    for attempt in 1..=policy.max_retries {
+        let delay = policy.backoff(attempt);
+        info!(
+            "Reconnecting to MCP server '{}' in {:?} (attempt {}/{})",
+            id, delay, attempt, policy.max_retries
+        );
+        let _ = events.send(ConnectionEvent::Reconnecting {
+            server: id.clone(),
+            attempt,
+            max_retries: policy.max_retries,
+            delay,
+        });
+        tokio::time::sleep(delay).await;
+
+        match open_server(&id, &config).await {
+            Ok(server) => {
+                servers.write().await.insert(id.clone(), Arc::new(server));
+                info!("Reconnected to MCP server: {}", id);
+                let _ = events.send(ConnectionEvent::Connected { server: id });
+                let _ = outcome.send(Some(true));
+                return;
+            }
+            Err(e) => {
+                debug!("Reconnect attempt {} to {} failed: {}", attempt, id, e);
+                last_error = e.to_string();
+            }
+        }
+    }
+
+    warn!("Giving up reconnecting to MCP server '{}': {}", id, last_error);
+    let _ = events.send(ConnectionEvent::ReconnectFailed {
+        server: id,
+        error: last_error,
+    });
+    let _ = outcome.send(Some(false));
+}
+
 impl Default for McpManager {
     fn default() -> Self {
         Self::new()
@@ -332,10 +927,226 @@ impl Default for McpManager {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::transport::collect_response;
+    use crate::types::ContentBlock;
 
     #[tokio::test]
     async fn test_manager_creation() {
         let manager = McpManager::new();
         assert!(manager.list_connected().await.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_tool_cacheability() {
+        let manager = McpManager::new().with_tool_cache(Duration::from_secs(60));
+        let mut config = ServerConfig::stdio("Docs", "docs-server");
+        config.cacheable_tools = vec!["get-docs".to_string(), "navigate".to_string()];
+        manager.add_server_config("docs", config).await;
+
+        assert!(manager.is_tool_cacheable("docs", "get-docs").await);
+        assert!(!manager.is_tool_cacheable("docs", "search").await);
+        // Navigation stays uncached even when listed
+        assert!(!manager.is_tool_cacheable("docs", "navigate").await);
+
+        manager.set_tool_cacheable("docs:search", true);
+        manager.set_tool_cacheable("docs:get-docs", false);
+        assert!(manager.is_tool_cacheable("docs", "search").await);
+        assert!(!manager.is_tool_cacheable("docs", "get-docs").await);
+        assert_eq!(manager.cache_stats(), CacheStats::default());
+    }
+
+    struct Prompted(Mutex<Vec<String>>);
+
+    impl CredentialProvider for Arc<Prompted> {
+        fn get_secret(&self, server: &str, key: &str) -> Option<String> {
+            self.0.lock().unwrap().push(format!("{}/{}", server, key));
+            None
+        }
+    }
+
+    #[tokio::test]
+    async fn test_auth_required_asks_provider_once() {
+        let prompted = Arc::new(Prompted(Mutex::new(Vec::new())));
+        let manager = McpManager::new().with_credential_provider(prompted.clone());
+        let mut config = ServerConfig::stdio("GitHub", "github-mcp");
+        config.required_env = vec!["GANESHA_TEST_MISSING_TOKEN".to_string()];
+        manager.add_server_config("github", config).await;
+
+        let err = manager.connect("github").await.unwrap_err();
+        assert!(matches!(err, McpProtocolError::AuthRequired(_)));
+        assert_eq!(
+            *prompted.0.lock().unwrap(),
+            vec!["github/GANESHA_TEST_MISSING_TOKEN".to_string()]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_reload_config_applies_diff() {
+        let dir = tempfile::tempdir().unwrap();
+        let (global, project) = (dir.path().join("global.toml"), dir.path().join("project.toml"));
+        let paths = vec![global.clone(), project.clone()];
+
+        let mut config = McpConfig::default();
+        config.servers.insert("docs".into(), ServerConfig::stdio("Docs", "docs-mcp"));
+        config.servers.insert("git".into(), ServerConfig::stdio("Git", "git-mcp"));
+        config.save(&global).await.unwrap();
+
+        let manager = McpManager::new();
+        manager.add_server_config("runtime", ServerConfig::stdio("Runtime", "rt")).await;
+        let mut events = manager.subscribe_events();
+        let diff = manager.reload_config(&paths).await.unwrap();
+        assert_eq!(diff.added, vec!["docs", "git"]);
+
+        // Project config overrides docs; git is dropped from the global file
+        let mut project_config = McpConfig::default();
+        project_config
+            .servers
+            .insert("docs".into(), ServerConfig::stdio("Docs", "docs-mcp-v2"));
+        project_config.save(&project).await.unwrap();
+        config.servers.remove("git");
+        config.save(&global).await.unwrap();
+
+        let diff = manager.reload_config(&paths).await.unwrap();
+        assert_eq!(
+            diff,
+            ConfigDiff {
+                added: vec![],
+                removed: vec!["git".into()],
+                changed: vec!["docs".into()],
+            }
+        );
+        assert_eq!(diff.to_string(), "removed git; changed docs");
+
+        let mut configured: Vec<String> =
+            manager.list_configured().await.into_iter().map(|(id, _)| id).collect();
+        configured.sort();
+        assert_eq!(configured, vec!["docs", "runtime"]);
+
+        assert!(manager.reload_config(&paths).await.unwrap().is_empty());
+        std::fs::write(&project, "servers = [").unwrap();
+        assert!(manager.reload_config(&paths).await.is_err());
+        assert_eq!(manager.list_configured().await.len(), 2);
+
+        let received: Vec<_> = std::iter::from_fn(|| events.try_recv().ok()).collect();
+        assert_eq!(received.len(), 3);
+        assert!(matches!(received[2], ConnectionEvent::ConfigReloadFailed { .. }));
+    }
+
+    #[test]
+    fn test_backoff_doubles_up_to_cap() {
+        let policy = ReconnectConfig {
+            initial_backoff_ms: 100,
+            max_backoff_ms: 1_000,
+            ..ReconnectConfig::default()
+        };
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(3), Duration::from_millis(400));
+        assert_eq!(policy.backoff(5), Duration::from_millis(1_000));
+        assert_eq!(policy.backoff(100), Duration::from_millis(1_000));
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_retries() {
+        let manager = McpManager::new();
+        let mut config = ServerConfig::stdio("Broken", "/nonexistent/ganesha-mcp-server");
+        config.reconnect = ReconnectConfig {
+            max_retries: 2,
+            initial_backoff_ms: 1,
+            ..ReconnectConfig::default()
+        };
+        manager.add_server_config("broken", config.clone()).await;
+        let mut events = manager.subscribe_events();
+
+        let mut outcome = manager.start_reconnect("broken", &config).unwrap();
+        outcome.wait_for(Option::is_some).await.unwrap();
+        assert_eq!(*outcome.borrow(), Some(false));
+
+        let received: Vec<_> = std::iter::from_fn(|| events.try_recv().ok()).collect();
+        assert_eq!(received.len(), 3);
+        assert_eq!(received[0].to_string(), "reconnecting to broken... (attempt 1/2)");
+        assert!(matches!(received[2], ConnectionEvent::ReconnectFailed { .. }));
+
+        let err = manager.call_tool("broken:anything", serde_json::json!({})).await;
+        assert!(matches!(err, Err(McpProtocolError::ServerNotConnected(_))));
+    }
+
+    #[tokio::test]
+    async fn test_server_dying_mid_call_is_respawned_without_resending() {
+        // Exits on its first tools/call, then answers normally once respawned
+        let dir = tempfile::tempdir().unwrap();
+        let script = format!(
+            r#"cd '{}'
+while IFS= read -r line; do
+  case "$line" in *'"id"'*) ;; *) continue ;; esac
+  case "$line" in
+    *'"tools/list"'*) result='{{"tools":[{{"name":"bump","description":"Bump","inputSchema":{{"type":"object"}}}}]}}' ;;
+    *'"tools/call"'*)
+      echo call >> calls
+      [ -e crashed ] || {{ : > crashed; exit 1; }}
+      result='{{"content":[{{"type":"text","text":"bumped"}}]}}' ;;
+    *) result='{{}}' ;;
+  esac
+  printf '{{"jsonrpc":"2.0","id":0,"result":%s}}\n' "$result"
+done"#,
+            dir.path().display()
+        );
+        let mut config = ServerConfig::stdio("Flaky", "sh");
+        if let TransportConfig::Stdio { args, .. } = &mut config.transport {
+            *args = vec!["-c".to_string(), script];
+        }
+        config.reconnect.initial_backoff_ms = 1;
+
+        let manager = McpManager::new();
+        manager.add_server_config("flaky", config).await;
+        manager.connect("flaky").await.unwrap();
+        let calls = || std::fs::read_to_string(dir.path().join("calls")).unwrap().lines().count();
+
+        let err = manager.call_tool("flaky:bump", serde_json::json!({})).await.unwrap_err();
+        assert!(err.to_string().contains("not retried"), "{}", err);
+        assert_eq!(calls(), 1);
+        assert_eq!(manager.server_status("flaky").await, Some(ServerStatus::Connected));
+
+        let response = manager.call_tool("flaky:bump", serde_json::json!({})).await.unwrap();
+        assert!(matches!(
+            response.content.as_deref(),
+            Some([ContentBlock::Text { text }]) if text == "bumped"
+        ));
+        assert_eq!(calls(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_call_tool_streaming_assembles_response() {
+        let script = r#"while IFS= read -r line; do
+  case "$line" in *'"id"'*) ;; *) continue ;; esac
+  case "$line" in
+    *'"tools/list"'*) result='{"tools":[{"name":"dump","description":"Dump","inputSchema":{"type":"object"}}]}' ;;
+    *'"tools/call"'*) result='{"content":[{"type":"text","text":"large output"}]}' ;;
+    *) result='{}' ;;
+  esac
+  printf '{"jsonrpc":"2.0","id":0,"result":%s}\n' "$result"
+done"#;
+        let mut config = ServerConfig::stdio("Dumper", "sh");
+        if let TransportConfig::Stdio { args, .. } = &mut config.transport {
+            *args = vec!["-c".to_string(), script.to_string()];
+        }
+
+        let manager = McpManager::new();
+        manager.add_server_config("dumper", config).await;
+        manager.connect("dumper").await.unwrap();
+
+        // Stdio cannot stream, so the whole body arrives as one chunk
+        let chunks = manager
+            .call_tool_streaming("dumper:dump", serde_json::json!({}))
+            .await
+            .unwrap();
+        let response = collect_response(chunks).await.unwrap();
+        let response = ToolCallResponse::from_rpc("1".to_string(), response);
+        assert!(matches!(
+            response.content.as_deref(),
+            Some([ContentBlock::Text { text }]) if text == "large output"
+        ));
+
+        let err = manager.call_tool_streaming("dumper:missing", serde_json::json!({})).await;
+        assert!(matches!(err, Err(McpProtocolError::ToolNotFound(_))));
+    }
 }
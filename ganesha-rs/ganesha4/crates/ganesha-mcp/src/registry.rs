@@ -1,10 +1,17 @@
 //! # Server Registry
 //!
 //! Registry of known MCP servers for auto-discovery and installation.
+//!
+//! The built-in entries live in `registry.toml`, embedded at compile time.
 
+use crate::types::Result;
+use crate::McpProtocolError;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+/// Built-in registry data
+const BUILTIN_REGISTRY: &str = include_str!("registry.toml");
+
 /// Registry of known MCP servers
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerRegistry {
@@ -21,10 +28,15 @@ pub struct RegistryEntry {
     pub description: String,
     /// Category
     pub category: ServerCategory,
+    /// How the server is reached
+    #[serde(default)]
+    pub transport: RegistryTransport,
     /// Installation command
     pub install_command: String,
     /// NPM package (if applicable)
     pub npm_package: Option<String>,
+    /// Python package (if applicable)
+    pub pip_package: Option<String>,
     /// Required environment variables
     #[serde(default)]
     pub required_env: Vec<RequiredEnvVar>,
@@ -35,6 +47,29 @@ pub struct RegistryEntry {
     pub verified: bool,
 }
 
+/// Transport a registry server speaks
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RegistryTransport {
+    /// Local process over stdin/stdout
+    #[default]
+    Stdio,
+    /// Server-Sent Events
+    Sse,
+    /// Stateless HTTP
+    Http,
+}
+
+impl std::fmt::Display for RegistryTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RegistryTransport::Stdio => write!(f, "stdio"),
+            RegistryTransport::Sse => write!(f, "sse"),
+            RegistryTransport::Http => write!(f, "http"),
+        }
+    }
+}
+
 /// Required environment variable
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RequiredEnvVar {
@@ -74,164 +109,18 @@ pub enum ServerCategory {
 impl ServerRegistry {
     /// Create a new registry with built-in servers
     pub fn with_builtin() -> Self {
-        let mut servers = HashMap::new();
-
-        // Filesystem
-        servers.insert(
-            "filesystem".to_string(),
-            RegistryEntry {
-                name: "Filesystem".to_string(),
-                description: "Access to local filesystem for reading and writing files".to_string(),
-                category: ServerCategory::Filesystem,
-                install_command: "npx -y @modelcontextprotocol/server-filesystem".to_string(),
-                npm_package: Some("@modelcontextprotocol/server-filesystem".to_string()),
-                required_env: Vec::new(),
-                homepage: Some("https://github.com/modelcontextprotocol/servers".to_string()),
-                verified: true,
-            },
-        );
-
-        // GitHub
-        servers.insert(
-            "github".to_string(),
-            RegistryEntry {
-                name: "GitHub".to_string(),
-                description: "Access to GitHub repositories, issues, and PRs".to_string(),
-                category: ServerCategory::Git,
-                install_command: "npx -y @modelcontextprotocol/server-github".to_string(),
-                npm_package: Some("@modelcontextprotocol/server-github".to_string()),
-                required_env: vec![RequiredEnvVar {
-                    name: "GITHUB_PERSONAL_ACCESS_TOKEN".to_string(),
-                    description: "GitHub Personal Access Token".to_string(),
-                    obtain_url: Some("https://github.com/settings/tokens".to_string()),
-                    optional: false,
-                }],
-                homepage: Some("https://github.com/modelcontextprotocol/servers".to_string()),
-                verified: true,
-            },
-        );
-
-        // Brave Search
-        servers.insert(
-            "brave-search".to_string(),
-            RegistryEntry {
-                name: "Brave Search".to_string(),
-                description: "Web search using Brave Search API".to_string(),
-                category: ServerCategory::Web,
-                install_command: "npx -y @anthropics/mcp-server-brave-search".to_string(),
-                npm_package: Some("@anthropics/mcp-server-brave-search".to_string()),
-                required_env: vec![RequiredEnvVar {
-                    name: "BRAVE_API_KEY".to_string(),
-                    description: "Brave Search API Key".to_string(),
-                    obtain_url: Some("https://brave.com/search/api/".to_string()),
-                    optional: false,
-                }],
-                homepage: Some("https://github.com/anthropics/anthropic-mcp-servers".to_string()),
-                verified: true,
-            },
-        );
-
-        // Fetch
-        servers.insert(
-            "fetch".to_string(),
-            RegistryEntry {
-                name: "Fetch".to_string(),
-                description: "Fetch and extract content from web pages".to_string(),
-                category: ServerCategory::Web,
-                install_command: "npx -y @anthropics/mcp-server-fetch".to_string(),
-                npm_package: Some("@anthropics/mcp-server-fetch".to_string()),
-                required_env: Vec::new(),
-                homepage: Some("https://github.com/anthropics/anthropic-mcp-servers".to_string()),
-                verified: true,
-            },
-        );
-
-        // PostgreSQL
-        servers.insert(
-            "postgres".to_string(),
-            RegistryEntry {
-                name: "PostgreSQL".to_string(),
-                description: "Query PostgreSQL databases".to_string(),
-                category: ServerCategory::Database,
-                install_command: "npx -y @modelcontextprotocol/server-postgres".to_string(),
-                npm_package: Some("@modelcontextprotocol/server-postgres".to_string()),
-                required_env: vec![RequiredEnvVar {
-                    name: "POSTGRES_CONNECTION_STRING".to_string(),
-                    description: "PostgreSQL connection string".to_string(),
-                    obtain_url: None,
-                    optional: false,
-                }],
-                homepage: Some("https://github.com/modelcontextprotocol/servers".to_string()),
-                verified: true,
-            },
-        );
-
-        // SQLite
-        servers.insert(
-            "sqlite".to_string(),
-            RegistryEntry {
-                name: "SQLite".to_string(),
-                description: "Query SQLite databases".to_string(),
-                category: ServerCategory::Database,
-                install_command: "npx -y @modelcontextprotocol/server-sqlite".to_string(),
-                npm_package: Some("@modelcontextprotocol/server-sqlite".to_string()),
-                required_env: Vec::new(),
-                homepage: Some("https://github.com/modelcontextprotocol/servers".to_string()),
-                verified: true,
-            },
-        );
-
-        // Slack
-        servers.insert(
-            "slack".to_string(),
-            RegistryEntry {
-                name: "Slack".to_string(),
-                description: "Interact with Slack workspaces".to_string(),
-                category: ServerCategory::Communication,
-                install_command: "npx -y @modelcontextprotocol/server-slack".to_string(),
-                npm_package: Some("@modelcontextprotocol/server-slack".to_string()),
-                required_env: vec![RequiredEnvVar {
-                    name: "SLACK_BOT_TOKEN".to_string(),
-                    description: "Slack Bot Token".to_string(),
-                    obtain_url: Some("https://api.slack.com/apps".to_string()),
-                    optional: false,
-                }],
-                homepage: Some("https://github.com/modelcontextprotocol/servers".to_string()),
-                verified: true,
-            },
-        );
-
-        // Memory (knowledge graph)
-        servers.insert(
-            "memory".to_string(),
-            RegistryEntry {
-                name: "Memory".to_string(),
-                description: "Persistent memory using a knowledge graph".to_string(),
-                category: ServerCategory::Other,
-                install_command: "npx -y @modelcontextprotocol/server-memory".to_string(),
-                npm_package: Some("@modelcontextprotocol/server-memory".to_string()),
-                required_env: Vec::new(),
-                homepage: Some("https://github.com/modelcontextprotocol/servers".to_string()),
-                verified: true,
-            },
-        );
+        Self::from_toml(BUILTIN_REGISTRY).expect("built-in registry.toml is valid")
+    }
 
-        // Puppeteer
-        servers.insert(
-            "puppeteer".to_string(),
-            RegistryEntry {
-                name: "Puppeteer".to_string(),
-                description: "Browser automation and web scraping".to_string(),
-                category: ServerCategory::Web,
-                install_command: "npx -y @anthropics/mcp-server-puppeteer".to_string(),
-                npm_package: Some("@anthropics/mcp-server-puppeteer".to_string()),
-                required_env: Vec::new(),
-                homepage: Some("https://github.com/anthropics/anthropic-mcp-servers".to_string()),
-                verified: true,
-            },
-        );
+    /// Parse a registry from TOML (`[servers.<id>]` tables)
+    pub fn from_toml(content: &str) -> Result<Self> {
+        toml::from_str(content)
+            .map_err(|e| McpProtocolError::ConfigError(format!("Invalid registry: {}", e)))
+    }
 
-        Self { servers }
+    /// Add entries from another registry, replacing ones with the same ID
+    pub fn extend(&mut self, other: ServerRegistry) {
+        self.servers.extend(other.servers);
     }
 
     /// Get a server entry by ID
@@ -247,17 +136,40 @@ impl ServerRegistry {
             .collect()
     }
 
-    /// Search servers by name or description
+    /// Search servers by ID, name, description or package, best matches
+    /// first. An empty query lists everything.
     pub fn search(&self, query: &str) -> Vec<(&String, &RegistryEntry)> {
-        let query_lower = query.to_lowercase();
-        self.servers
+        let query_lower = query.trim().to_lowercase();
+        let rank = |id: &str, entry: &RegistryEntry| -> Option<u8> {
+            let packages = [&entry.npm_package, &entry.pip_package];
+            if id == query_lower {
+                Some(0)
+            } else if id.contains(&query_lower) || entry.name.to_lowercase().contains(&query_lower) {
+                Some(1)
+            } else if packages
+                .iter()
+                .any(|p| p.as_deref().is_some_and(|p| p.to_lowercase().contains(&query_lower)))
+            {
+                Some(2)
+            } else if entry.description.to_lowercase().contains(&query_lower) {
+                Some(3)
+            } else {
+                None
+            }
+        };
+
+        let mut results: Vec<_> = self
+            .servers
             .iter()
-            .filter(|(id, entry)| {
-                id.contains(&query_lower)
-                    || entry.name.to_lowercase().contains(&query_lower)
-                    || entry.description.to_lowercase().contains(&query_lower)
-            })
-            .collect()
+            .filter_map(|(id, entry)| rank(id, entry).map(|r| (r, id, entry)))
+            .collect();
+        results.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(b.1)));
+        results.into_iter().map(|(_, id, entry)| (id, entry)).collect()
+    }
+
+    /// Command that sets up a server (npx, pip, ...)
+    pub fn install_command(&self, id: &str) -> Option<&str> {
+        self.servers.get(id).map(|entry| entry.install_command.as_str())
     }
 
     /// List all verified servers
@@ -274,3 +186,37 @@ impl Default for ServerRegistry {
         Self::with_builtin()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_registry_parses() {
+        let registry = ServerRegistry::with_builtin();
+        for id in ["playwright", "context7", "filesystem", "fetch", "git"] {
+            assert!(registry.get(id).is_some(), "missing {}", id);
+        }
+        assert_eq!(
+            registry.install_command("git"),
+            Some("pip install mcp-server-git")
+        );
+        assert_eq!(registry.get("context7").unwrap().category, ServerCategory::DevTools);
+        assert_eq!(registry.get("github").unwrap().required_env.len(), 1);
+    }
+
+    #[test]
+    fn test_search_ranks_id_matches_first() {
+        let registry = ServerRegistry::with_builtin();
+        let ids: Vec<&str> = registry
+            .search("Git")
+            .into_iter()
+            .map(|(id, _)| id.as_str())
+            .collect();
+        assert_eq!(&ids[..2], ["git", "github"]);
+
+        let ids: Vec<&String> = registry.search("browser").into_iter().map(|(id, _)| id).collect();
+        assert_eq!(ids, ["playwright", "puppeteer"]);
+        assert_eq!(registry.search("").len(), registry.servers.len());
+    }
+}
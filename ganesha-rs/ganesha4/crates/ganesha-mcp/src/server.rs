@@ -2,10 +2,10 @@
 //!
 //! Represents a connected MCP server and its capabilities.
 
-use crate::transport::Transport;
+use crate::transport::{ChunkStream, Transport};
 use crate::types::{
     JsonRpcRequest, Prompt, Resource, Result, Tool,
-    ToolCallRequest, ToolCallResponse, ContentBlock,
+    ToolCallRequest, ToolCallResponse,
 };
 use crate::McpProtocolError;
 use serde::{Deserialize, Serialize};
@@ -189,53 +189,26 @@ impl McpServer {
 
     /// Call a tool on this server
     pub async fn call_tool(&self, request: ToolCallRequest) -> Result<ToolCallResponse> {
-        let params = serde_json::json!({
-            "name": request.name,
-            "arguments": request.arguments
-        });
+        let response = self.transport.request(Self::tool_call_rpc(&request)).await?;
 
-        let rpc_request = JsonRpcRequest::new("tools/call", Some(params));
-        let response = self.transport.request(rpc_request).await?;
-
-        if let Some(error) = response.error {
+        if let Some(error) = &response.error {
             warn!("MCP tool error: {} - {}", error.code, error.message);
-            return Ok(ToolCallResponse {
-                id: request.id,
-                content: None,
-                error: Some(crate::types::ToolError {
-                    code: error.code,
-                    message: error.message,
-                    data: error.data,
-                }),
-                is_running: false,
-            });
         }
+        Ok(ToolCallResponse::from_rpc(request.id, response))
+    }
 
-        if let Some(result) = response.result {
-            let content: Vec<ContentBlock> = result
-                .get("content")
-                .and_then(|c| serde_json::from_value(c.clone()).ok())
-                .unwrap_or_default();
-
-            let is_running = result
-                .get("isRunning")
-                .and_then(|r| r.as_bool())
-                .unwrap_or(false);
-
-            return Ok(ToolCallResponse {
-                id: request.id,
-                content: Some(content),
-                error: None,
-                is_running,
-            });
-        }
+    /// Call a tool and receive the raw JSON-RPC response body as it arrives.
+    /// Assemble it with `collect_response` and `ToolCallResponse::from_rpc`.
+    pub async fn call_tool_streaming(&self, request: ToolCallRequest) -> Result<ChunkStream> {
+        self.transport.call_streaming(Self::tool_call_rpc(&request)).await
+    }
 
-        Ok(ToolCallResponse {
-            id: request.id,
-            content: Some(vec![]),
-            error: None,
-            is_running: false,
-        })
+    fn tool_call_rpc(request: &ToolCallRequest) -> JsonRpcRequest {
+        let params = serde_json::json!({
+            "name": request.name,
+            "arguments": request.arguments
+        });
+        JsonRpcRequest::new("tools/call", Some(params))
     }
 
     /// Get a resource by URI
@@ -332,6 +305,12 @@ impl McpServer {
             .any(|t| t.name == name)
     }
 
+    /// Whether the transport is still usable (false once a stdio server
+    /// process has exited)
+    pub fn is_alive(&self) -> bool {
+        self.transport.is_connected()
+    }
+
     /// Disconnect the server
     pub async fn disconnect(&self) -> Result<()> {
         self.transport.close().await?;
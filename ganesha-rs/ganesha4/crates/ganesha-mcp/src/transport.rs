@@ -5,7 +5,11 @@
 use crate::types::{JsonRpcRequest, JsonRpcResponse, Result};
 use crate::McpProtocolError;
 use async_trait::async_trait;
+use futures::stream::{self, BoxStream, StreamExt};
 use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::process::{Child, Command};
 use tokio::sync::mpsc;
@@ -17,6 +21,15 @@ pub trait Transport: Send + Sync {
     /// Send a request and wait for response
     async fn request(&self, req: JsonRpcRequest) -> Result<JsonRpcResponse>;
 
+    /// Send a request and receive the raw JSON-RPC response body as it
+    /// arrives, for large tool outputs. Transports that cannot stream send
+    /// the whole serialized response as a single chunk.
+    async fn call_streaming(&self, req: JsonRpcRequest) -> Result<ChunkStream> {
+        let response = self.request(req).await?;
+        let body = serde_json::to_vec(&response)?;
+        Ok(stream::once(async move { Ok(body) }).boxed())
+    }
+
     /// Send a notification (no response expected)
     async fn notify(&self, method: &str, params: Option<serde_json::Value>) -> Result<()>;
 
@@ -27,12 +40,27 @@ pub trait Transport: Send + Sync {
     fn is_connected(&self) -> bool;
 }
 
+/// Raw response body chunks from `Transport::call_streaming`
+pub type ChunkStream = BoxStream<'static, Result<Vec<u8>>>;
+
+/// Assemble a streamed body into a JSON-RPC response
+pub async fn collect_response(mut chunks: ChunkStream) -> Result<JsonRpcResponse> {
+    let mut body = Vec::new();
+    while let Some(chunk) = chunks.next().await {
+        body.extend_from_slice(&chunk?);
+    }
+    serde_json::from_slice(&body).map_err(|e| {
+        McpProtocolError::InvalidResponse(format!("Failed to parse response: {}", e))
+    })
+}
+
 /// Stdio transport for local MCP servers
 pub struct StdioTransport {
     child: tokio::sync::Mutex<Option<Child>>,
     stdin_tx: mpsc::Sender<String>,
     response_rx: tokio::sync::Mutex<mpsc::Receiver<JsonRpcResponse>>,
-    connected: std::sync::atomic::AtomicBool,
+    /// Cleared when the process closes stdout, i.e. exited or crashed
+    connected: Arc<AtomicBool>,
 }
 
 impl StdioTransport {
@@ -91,8 +119,11 @@ impl StdioTransport {
             }
         });
 
+        let connected = Arc::new(AtomicBool::new(true));
+
         // Task to read from stdout
         let mut reader = BufReader::new(stdout).lines();
+        let reader_connected = connected.clone();
         tokio::spawn(async move {
             while let Ok(Some(line)) = reader.next_line().await {
                 match serde_json::from_str::<JsonRpcResponse>(&line) {
@@ -106,13 +137,14 @@ impl StdioTransport {
                     }
                 }
             }
+            reader_connected.store(false, Ordering::SeqCst);
         });
 
         Ok(Self {
             child: tokio::sync::Mutex::new(Some(child)),
             stdin_tx,
             response_rx: tokio::sync::Mutex::new(response_rx),
-            connected: std::sync::atomic::AtomicBool::new(true),
+            connected,
         })
     }
 }
@@ -132,9 +164,12 @@ impl Transport for StdioTransport {
         let mut rx = self.response_rx.lock().await;
         match tokio::time::timeout(std::time::Duration::from_secs(30), rx.recv()).await {
             Ok(Some(response)) => Ok(response),
-            Ok(None) => Err(McpProtocolError::TransportError(
-                "Connection closed".to_string(),
-            )),
+            Ok(None) => {
+                self.connected.store(false, Ordering::SeqCst);
+                Err(McpProtocolError::TransportError(
+                    "Connection closed".to_string(),
+                ))
+            }
             Err(_) => Err(McpProtocolError::Timeout("Request timeout".to_string())),
         }
     }
@@ -267,6 +302,8 @@ impl Transport for SseTransport {
 pub struct HttpTransport {
     url: String,
     client: reqwest::Client,
+    /// Longest gap allowed between streamed chunks
+    idle_timeout: Duration,
 }
 
 impl HttpTransport {
@@ -275,8 +312,17 @@ impl HttpTransport {
         Self {
             url: url.into(),
             client: reqwest::Client::new(),
+            idle_timeout: Duration::from_secs(30),
         }
     }
+
+    /// Set how long a streaming call may go without receiving data. The
+    /// timer restarts on every chunk, so long transfers are fine as long as
+    /// they keep making progress.
+    pub fn with_idle_timeout(mut self, idle_timeout: Duration) -> Self {
+        self.idle_timeout = idle_timeout;
+        self
+    }
 }
 
 #[async_trait]
@@ -304,6 +350,45 @@ impl Transport for HttpTransport {
         Ok(json_response)
     }
 
+    async fn call_streaming(&self, req: JsonRpcRequest) -> Result<ChunkStream> {
+        let idle = self.idle_timeout;
+        let send = self.client.post(&self.url).json(&req).send();
+        let response = tokio::time::timeout(idle, send)
+            .await
+            .map_err(|_| McpProtocolError::Timeout("No response headers".to_string()))?
+            .map_err(|e| McpProtocolError::TransportError(format!("HTTP error: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(McpProtocolError::TransportError(format!(
+                "HTTP {} from server",
+                response.status()
+            )));
+        }
+
+        // Each chunk gets a fresh `idle` window; the stream ends after the
+        // first error
+        let body = response.bytes_stream().boxed();
+        Ok(stream::unfold(Some(body), move |body| async move {
+            let mut body = body?;
+            match tokio::time::timeout(idle, body.next()).await {
+                Ok(Some(Ok(chunk))) => Some((Ok(chunk.to_vec()), Some(body))),
+                Ok(Some(Err(e))) => Some((
+                    Err(McpProtocolError::TransportError(format!("HTTP error: {}", e))),
+                    None,
+                )),
+                Ok(None) => None,
+                Err(_) => Some((
+                    Err(McpProtocolError::Timeout(format!(
+                        "No data received for {:?}",
+                        idle
+                    ))),
+                    None,
+                )),
+            }
+        })
+        .boxed())
+    }
+
     async fn notify(&self, method: &str, params: Option<serde_json::Value>) -> Result<()> {
         let notification = crate::types::JsonRpcNotification {
             jsonrpc: "2.0".to_string(),
@@ -330,3 +415,84 @@ impl Transport for HttpTransport {
         true // HTTP is stateless
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::AsyncReadExt;
+    use tokio::net::TcpListener;
+
+    /// Serve one request with a chunked body, pausing `gap` between chunks
+    async fn serve_chunks(chunks: Vec<&'static str>, gap: Duration) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            // Read until the JSON body has arrived
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !request.ends_with(b"}") {
+                let n = socket.read(&mut buf).await.unwrap();
+                request.extend_from_slice(&buf[..n]);
+            }
+
+            socket
+                .write_all(b"HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ntransfer-encoding: chunked\r\n\r\n")
+                .await
+                .unwrap();
+            for chunk in chunks {
+                let frame = format!("{:x}\r\n{}\r\n", chunk.len(), chunk);
+                socket.write_all(frame.as_bytes()).await.unwrap();
+                socket.flush().await.unwrap();
+                tokio::time::sleep(gap).await;
+            }
+            socket.write_all(b"0\r\n\r\n").await.unwrap();
+        });
+
+        url
+    }
+
+    #[tokio::test]
+    async fn test_http_streams_three_chunks() {
+        let chunks = vec![
+            r#"{"jsonrpc":"2.0","id":"1","#,
+            r#""result":{"content":[{"type":"text","#,
+            r#""text":"large output"}]}}"#,
+        ];
+        // Total transfer exceeds the idle timeout; each gap does not
+        let url = serve_chunks(chunks.clone(), Duration::from_millis(100)).await;
+        let transport = HttpTransport::new(url).with_idle_timeout(Duration::from_millis(250));
+
+        let request = JsonRpcRequest::new("tools/call", None);
+        let received: Vec<Vec<u8>> = transport
+            .call_streaming(request)
+            .await
+            .unwrap()
+            .map(|chunk| chunk.unwrap())
+            .collect()
+            .await;
+        assert_eq!(received.len(), 3);
+        assert_eq!(received[1], chunks[1].as_bytes());
+
+        let response = collect_response(stream::iter(received.into_iter().map(Ok)).boxed())
+            .await
+            .unwrap();
+        assert_eq!(response.result.unwrap()["content"][0]["text"], "large output");
+    }
+
+    #[tokio::test]
+    async fn test_http_stream_times_out_when_idle() {
+        let url = serve_chunks(vec![r#"{"jsonrpc":"#, r#""2.0"}"#], Duration::from_millis(300)).await;
+        let transport = HttpTransport::new(url).with_idle_timeout(Duration::from_millis(100));
+
+        let request = JsonRpcRequest::new("tools/call", None);
+        let mut chunks = transport.call_streaming(request).await.unwrap();
+        assert!(chunks.next().await.unwrap().is_ok());
+        assert!(matches!(
+            chunks.next().await,
+            Some(Err(McpProtocolError::Timeout(_)))
+        ));
+        assert!(chunks.next().await.is_none());
+    }
+}
@@ -44,6 +44,127 @@ impl Default for ToolSchema {
     }
 }
 
+impl ToolSchema {
+    /// Check call arguments against the schema's required fields and
+    /// property types. Returns one message per problem.
+    pub fn validate(&self, arguments: &serde_json::Value) -> std::result::Result<(), Vec<String>> {
+        let empty = serde_json::Map::new();
+        let args = match arguments {
+            serde_json::Value::Object(map) => map,
+            serde_json::Value::Null => &empty,
+            other if self.schema_type == "object" => {
+                return Err(vec![format!(
+                    "arguments must be an object, got {}",
+                    json_type_name(other)
+                )])
+            }
+            _ => return Ok(()),
+        };
+
+        let mut problems = Vec::new();
+        let properties = self.properties.as_ref();
+
+        for field in self.required.iter().flatten() {
+            if args.contains_key(field) {
+                continue;
+            }
+            // Unknown keys the model may have meant instead, e.g. `q` for `query`
+            let guess = args
+                .keys()
+                .filter(|key| properties.is_none_or(|props| !props.contains_key(*key)))
+                .find(|key| looks_like(key, field));
+            match guess {
+                Some(key) => problems.push(format!(
+                    "missing required field '{}' (got '{}', did you mean '{}'?)",
+                    field, key, field
+                )),
+                None => problems.push(format!("missing required field '{}'", field)),
+            }
+        }
+
+        for (key, value) in args {
+            let Some(prop) = properties.and_then(|props| props.get(key)) else {
+                continue;
+            };
+            if !matches_type(&prop.prop_type, value) {
+                problems.push(format!(
+                    "field '{}' should be {}, got {}",
+                    key,
+                    prop.prop_type,
+                    json_type_name(value)
+                ));
+            } else if let (Some(allowed), Some(s)) = (&prop.enum_values, value.as_str()) {
+                if !allowed.iter().any(|a| a == s) {
+                    problems.push(format!(
+                        "field '{}' must be one of [{}], got '{}'",
+                        key,
+                        allowed.join(", "),
+                        s
+                    ));
+                }
+            }
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
+}
+
+/// JSON Schema type name of a value
+fn json_type_name(value: &serde_json::Value) -> &'static str {
+    match value {
+        serde_json::Value::Null => "null",
+        serde_json::Value::Bool(_) => "boolean",
+        serde_json::Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        serde_json::Value::Number(_) => "number",
+        serde_json::Value::String(_) => "string",
+        serde_json::Value::Array(_) => "array",
+        serde_json::Value::Object(_) => "object",
+    }
+}
+
+/// Whether a value satisfies a JSON Schema type (unknown types pass)
+fn matches_type(expected: &str, value: &serde_json::Value) -> bool {
+    match expected {
+        "string" => value.is_string(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "number" => value.is_number(),
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+/// Whether `key` is plausibly a mistyped or abbreviated `field`
+fn looks_like(key: &str, field: &str) -> bool {
+    let (key, field) = (key.to_lowercase(), field.to_lowercase());
+    let normalize = |s: &str| s.replace(['_', '-'], "");
+    field.starts_with(&key)
+        || key.starts_with(&field)
+        || normalize(&key) == normalize(&field)
+        || edit_distance(&key, &field) <= 2
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut prev = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitute = prev + usize::from(ca != *cb);
+            prev = row[j + 1];
+            row[j + 1] = substitute.min(prev + 1).min(row[j] + 1);
+        }
+    }
+    row[b.len()]
+}
+
 /// Schema for a single property
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PropertySchema {
@@ -136,6 +257,51 @@ pub struct ToolCallResponse {
     pub is_running: bool,
 }
 
+impl ToolCallResponse {
+    /// Build the response to tool call `id` from the server's `tools/call`
+    /// reply
+    pub fn from_rpc(id: String, response: JsonRpcResponse) -> Self {
+        if let Some(error) = response.error {
+            return Self {
+                id,
+                content: None,
+                error: Some(ToolError {
+                    code: error.code,
+                    message: error.message,
+                    data: error.data,
+                }),
+                is_running: false,
+            };
+        }
+
+        let Some(result) = response.result else {
+            return Self {
+                id,
+                content: Some(vec![]),
+                error: None,
+                is_running: false,
+            };
+        };
+
+        let content: Vec<ContentBlock> = result
+            .get("content")
+            .and_then(|c| serde_json::from_value(c.clone()).ok())
+            .unwrap_or_default();
+
+        let is_running = result
+            .get("isRunning")
+            .and_then(|r| r.as_bool())
+            .unwrap_or(false);
+
+        Self {
+            id,
+            content: Some(content),
+            error: None,
+            is_running,
+        }
+    }
+}
+
 /// Content block in a tool response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -220,3 +386,48 @@ pub struct JsonRpcNotification {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub params: Option<serde_json::Value>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn search_schema() -> ToolSchema {
+        serde_json::from_value(json!({
+            "type": "object",
+            "properties": {
+                "query": {"type": "string"},
+                "limit": {"type": "integer"},
+                "engine": {"type": "string", "enum": ["web", "news"]}
+            },
+            "required": ["query"]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_validate_suggests_misnamed_field() {
+        let schema = search_schema();
+        assert!(schema.validate(&json!({"query": "rust", "limit": 5})).is_ok());
+
+        let problems = schema.validate(&json!({"q": "rust"})).unwrap_err();
+        assert_eq!(
+            problems,
+            vec!["missing required field 'query' (got 'q', did you mean 'query'?)"]
+        );
+    }
+
+    #[test]
+    fn test_validate_types_and_enums() {
+        let schema = search_schema();
+        let problems = schema
+            .validate(&json!({"query": 42, "limit": 2.5, "engine": "images"}))
+            .unwrap_err();
+        assert_eq!(problems.len(), 3);
+        assert!(problems.contains(&"field 'query' should be string, got integer".to_string()));
+        assert!(problems.contains(&"field 'limit' should be integer, got number".to_string()));
+
+        assert!(schema.validate(&json!("rust")).is_err());
+        assert!(ToolSchema::default().validate(&serde_json::Value::Null).is_ok());
+    }
+}
@@ -0,0 +1,94 @@
+//! # Config Watching
+//!
+//! Watches MCP config files and applies edits to a running `McpManager`
+//! through `McpManager::reload_config`.
+
+use crate::config::McpConfig;
+use crate::manager::McpManager;
+use crate::types::Result;
+use crate::McpProtocolError;
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+/// Editors often write a file in several steps; wait for them to settle
+const DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// Keeps watching config files until dropped
+pub struct ConfigWatcher {
+    _watcher: RecommendedWatcher,
+    task: JoinHandle<()>,
+}
+
+impl Drop for ConfigWatcher {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+impl McpManager {
+    /// Watch the default config files (global, then project)
+    pub fn watch_default_config(self: &Arc<Self>) -> Result<ConfigWatcher> {
+        self.watch_config(McpConfig::default_paths())
+    }
+
+    /// Reload the config whenever one of `paths` is created, modified or
+    /// removed. Later paths override earlier ones. Files that do not exist
+    /// yet are picked up when created, as long as their directory exists.
+    pub fn watch_config(self: &Arc<Self>, paths: Vec<PathBuf>) -> Result<ConfigWatcher> {
+        let cwd = std::env::current_dir()?;
+        let paths: Vec<PathBuf> = paths.into_iter().map(|p| cwd.join(p)).collect();
+
+        // notify calls back on its own thread; hand events to the async task
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let watched = paths.clone();
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            if let Ok(event) = res {
+                let relevant = !matches!(event.kind, EventKind::Access(_))
+                    && event.paths.iter().any(|p| watched.contains(p));
+                if relevant {
+                    let _ = tx.send(());
+                }
+            }
+        })
+        .map_err(|e| McpProtocolError::ConfigError(format!("Failed to watch config: {}", e)))?;
+
+        // Watch directories rather than files so replaced or newly created
+        // files are seen
+        for dir in paths.iter().filter_map(|p| p.parent()) {
+            if !dir.is_dir() {
+                debug!("Not watching {:?}: directory does not exist", dir);
+                continue;
+            }
+            watcher
+                .watch(dir, RecursiveMode::NonRecursive)
+                .map_err(|e| {
+                    McpProtocolError::ConfigError(format!("Failed to watch {:?}: {}", dir, e))
+                })?;
+        }
+
+        let manager = Arc::downgrade(self);
+        let task = tokio::spawn(async move {
+            while rx.recv().await.is_some() {
+                tokio::time::sleep(DEBOUNCE).await;
+                while rx.try_recv().is_ok() {}
+
+                let Some(manager) = manager.upgrade() else {
+                    break;
+                };
+                if let Err(e) = manager.reload_config(&paths).await {
+                    warn!("Failed to reload MCP config: {}", e);
+                }
+            }
+        });
+
+        Ok(ConfigWatcher {
+            _watcher: watcher,
+            task,
+        })
+    }
+}
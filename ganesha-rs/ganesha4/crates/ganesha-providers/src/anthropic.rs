@@ -4,7 +4,8 @@
 
 use crate::{
     GenerateOptions, LlmProvider, Message, MessageRole, ModelInfo, ModelTier,
-    ProviderError, Response, Result, Usage, get_model_tier,
+    ProviderConfig, ProviderError, Response, Result, ToolCall, ToolDefinition, ToolProvider,
+    ToolResponse, Usage, get_model_tier, RESPONSE_SCHEMA_NAME,
 };
 use async_trait::async_trait;
 use reqwest::Client;
@@ -30,6 +31,12 @@ impl AnthropicProvider {
         }
     }
 
+    /// Apply per-provider HTTP settings (extra headers, proxy)
+    pub fn with_config(mut self, config: &ProviderConfig) -> Result<Self> {
+        self.client = config.build_client(None)?;
+        Ok(self)
+    }
+
     /// Set the default model
     pub fn with_default_model(mut self, model: impl Into<String>) -> Self {
         self.default_model = model.into();
@@ -38,20 +45,25 @@ impl AnthropicProvider {
 
     /// Convert our messages to Anthropic format
     /// Anthropic requires system prompt separate from messages
-    fn convert_messages(&self, messages: &[Message]) -> (Option<String>, Vec<AnthropicMessage>) {
-        let mut system = None;
+    fn convert_messages(&self, messages: &[Message]) -> (Vec<ContentBlock>, Vec<AnthropicMessage>) {
+        let mut system = Vec::new();
         let mut anthropic_messages = Vec::new();
 
         for msg in messages {
+            let cache_control = CacheControl::when(msg.cacheable);
             match msg.role {
                 MessageRole::System => {
-                    system = Some(msg.content.clone());
+                    system.push(ContentBlock::Text {
+                        text: msg.content.clone(),
+                        cache_control,
+                    });
                 }
                 MessageRole::User => {
                     anthropic_messages.push(AnthropicMessage {
                         role: "user".to_string(),
                         content: vec![ContentBlock::Text {
                             text: msg.content.clone(),
+                            cache_control,
                         }],
                     });
                 }
@@ -60,6 +72,7 @@ impl AnthropicProvider {
                         role: "assistant".to_string(),
                         content: vec![ContentBlock::Text {
                             text: msg.content.clone(),
+                            cache_control,
                         }],
                     });
                 }
@@ -70,6 +83,7 @@ impl AnthropicProvider {
                         content: vec![ContentBlock::ToolResult {
                             tool_use_id: msg.tool_call_id.clone().unwrap_or_default(),
                             content: msg.content.clone(),
+                            cache_control,
                         }],
                     });
                 }
@@ -78,6 +92,75 @@ impl AnthropicProvider {
 
         (system, anthropic_messages)
     }
+
+    /// Build the Messages API request body
+    fn build_request(&self, model: &str, messages: &[Message], options: &GenerateOptions) -> AnthropicChatRequest {
+        let (mut system, anthropic_messages) = self.convert_messages(messages);
+
+        // Override system if provided in options
+        if let Some(sys) = &options.system {
+            system = vec![ContentBlock::Text {
+                text: sys.clone(),
+                cache_control: CacheControl::when(options.cache_system),
+            }];
+        }
+
+        let mut request = AnthropicChatRequest {
+            model: model.to_string(),
+            messages: anthropic_messages,
+            system,
+            max_tokens: options.max_tokens.unwrap_or(4096),
+            temperature: options.temperature,
+            stop_sequences: options.stop.clone(),
+            tools: None,
+            tool_choice: None,
+        };
+
+        // Structured output: force a single tool whose input schema is the response schema
+        if let Some(schema) = &options.response_schema {
+            request.tools = Some(vec![AnthropicTool {
+                name: RESPONSE_SCHEMA_NAME.to_string(),
+                description: "Respond with the structured result.".to_string(),
+                input_schema: schema.clone(),
+            }]);
+            request.tool_choice = Some(serde_json::json!({
+                "type": "tool",
+                "name": RESPONSE_SCHEMA_NAME,
+            }));
+        }
+
+        request
+    }
+
+    /// POST a Messages API request
+    async fn send(&self, request: &AnthropicChatRequest) -> Result<AnthropicChatResponse> {
+        let response = self
+            .client
+            .post("https://api.anthropic.com/v1/messages")
+            .header("x-api-key", &self.api_key)
+            .header("anthropic-version", ANTHROPIC_API_VERSION)
+            .header("Content-Type", "application/json")
+            .json(request)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let body = response.text().await.unwrap_or_default();
+
+            // Check for rate limiting
+            if status == 429 {
+                return Err(ProviderError::RateLimited { retry_after: None });
+            }
+
+            return Err(ProviderError::ApiError {
+                status,
+                message: body,
+            });
+        }
+
+        Ok(response.json().await?)
+    }
 }
 
 #[async_trait]
@@ -149,60 +232,35 @@ impl LlmProvider for AnthropicProvider {
 
         debug!("Anthropic chat with model: {}", model);
 
-        let (mut system, anthropic_messages) = self.convert_messages(messages);
-
-        // Override system if provided in options
-        if let Some(sys) = &options.system {
-            system = Some(sys.clone());
-        }
-
-        let request = AnthropicChatRequest {
-            model: model.clone(),
-            messages: anthropic_messages,
-            system,
-            max_tokens: options.max_tokens.unwrap_or(4096),
-            temperature: options.temperature,
-            stop_sequences: options.stop.clone(),
+        let request = self.build_request(&model, messages, options);
+        let chat_response = self.send(&request).await?;
+
+        // Extract text content from response, or the forced tool's input for structured output
+        let content = if options.response_schema.is_some() {
+            chat_response
+                .content
+                .into_iter()
+                .find_map(|block| match block {
+                    ResponseContentBlock::ToolUse { name, input, .. } if name == RESPONSE_SCHEMA_NAME => {
+                        Some(input.to_string())
+                    }
+                    _ => None,
+                })
+                .ok_or_else(|| {
+                    ProviderError::InvalidResponse("No structured response tool call in response".to_string())
+                })?
+        } else {
+            chat_response
+                .content
+                .into_iter()
+                .filter_map(|block| match block {
+                    ResponseContentBlock::Text { text } => Some(text),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+                .join("")
         };
 
-        let response = self
-            .client
-            .post("https://api.anthropic.com/v1/messages")
-            .header("x-api-key", &self.api_key)
-            .header("anthropic-version", ANTHROPIC_API_VERSION)
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            let status = response.status().as_u16();
-            let body = response.text().await.unwrap_or_default();
-
-            // Check for rate limiting
-            if status == 429 {
-                return Err(ProviderError::RateLimited { retry_after: None });
-            }
-
-            return Err(ProviderError::ApiError {
-                status,
-                message: body,
-            });
-        }
-
-        let chat_response: AnthropicChatResponse = response.json().await?;
-
-        // Extract text content from response
-        let content = chat_response
-            .content
-            .into_iter()
-            .filter_map(|block| match block {
-                ResponseContentBlock::Text { text } => Some(text),
-                _ => None,
-            })
-            .collect::<Vec<_>>()
-            .join("");
-
         Ok(Response {
             content,
             model,
@@ -212,9 +270,36 @@ impl LlmProvider for AnthropicProvider {
                 completion_tokens: chat_response.usage.output_tokens,
                 total_tokens: chat_response.usage.input_tokens
                     + chat_response.usage.output_tokens,
+                cache_read_tokens: chat_response.usage.cache_read_input_tokens,
+                cache_creation_tokens: chat_response.usage.cache_creation_input_tokens,
             }),
         })
     }
+
+    fn as_tool_provider(&self) -> Option<&dyn ToolProvider> {
+        Some(self)
+    }
+}
+
+#[async_trait]
+impl ToolProvider for AnthropicProvider {
+    async fn chat_with_tools(
+        &self,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+        options: &GenerateOptions,
+    ) -> Result<ToolResponse> {
+        let model = options.model.as_ref().unwrap_or(&self.default_model);
+        debug!("Anthropic tool call with model: {} ({} tools)", model, tools.len());
+
+        let mut request = self.build_request(model, messages, options);
+        if !tools.is_empty() {
+            request.tools = Some(tools.iter().map(AnthropicTool::from).collect());
+            request.tool_choice = None;
+        }
+
+        Ok(self.send(&request).await?.into())
+    }
 }
 
 // Anthropic API types
@@ -223,13 +308,34 @@ impl LlmProvider for AnthropicProvider {
 struct AnthropicChatRequest {
     model: String,
     messages: Vec<AnthropicMessage>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    system: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    system: Vec<ContentBlock>,
     max_tokens: u32,
     #[serde(skip_serializing_if = "Option::is_none")]
     temperature: Option<f32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     stop_sequences: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<Vec<AnthropicTool>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_choice: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize)]
+struct AnthropicTool {
+    name: String,
+    description: String,
+    input_schema: serde_json::Value,
+}
+
+impl From<&ToolDefinition> for AnthropicTool {
+    fn from(tool: &ToolDefinition) -> Self {
+        Self {
+            name: tool.name.clone(),
+            description: tool.description.clone(),
+            input_schema: tool.parameters.clone(),
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -242,9 +348,30 @@ struct AnthropicMessage {
 #[serde(tag = "type")]
 enum ContentBlock {
     #[serde(rename = "text")]
-    Text { text: String },
+    Text {
+        text: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
+    },
     #[serde(rename = "tool_result")]
-    ToolResult { tool_use_id: String, content: String },
+    ToolResult {
+        tool_use_id: String,
+        content: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
+    },
+}
+
+/// Prompt-cache breakpoint; everything up to and including the block is cached
+#[derive(Debug, Serialize)]
+struct CacheControl {
+    r#type: &'static str,
+}
+
+impl CacheControl {
+    fn when(cacheable: bool) -> Option<Self> {
+        cacheable.then_some(Self { r#type: "ephemeral" })
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -256,7 +383,6 @@ struct AnthropicChatResponse {
 
 #[derive(Debug, Deserialize)]
 #[serde(tag = "type")]
-#[allow(dead_code)]
 enum ResponseContentBlock {
     #[serde(rename = "text")]
     Text { text: String },
@@ -268,8 +394,108 @@ enum ResponseContentBlock {
     },
 }
 
+impl From<AnthropicChatResponse> for ToolResponse {
+    fn from(response: AnthropicChatResponse) -> Self {
+        let mut text = String::new();
+        let mut tool_calls = Vec::new();
+        for block in response.content {
+            match block {
+                ResponseContentBlock::Text { text: t } => text.push_str(&t),
+                ResponseContentBlock::ToolUse { id, name, input } => {
+                    tool_calls.push(ToolCall { id, name, arguments: input })
+                }
+            }
+        }
+        Self {
+            content: (!text.is_empty()).then_some(text),
+            tool_calls,
+            finish_reason: response.stop_reason,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 struct AnthropicUsage {
     input_tokens: u32,
     output_tokens: u32,
+    #[serde(default)]
+    cache_creation_input_tokens: u32,
+    #[serde(default)]
+    cache_read_input_tokens: u32,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cacheable_messages_get_cache_control() {
+        let provider = AnthropicProvider::new("key");
+        let messages = vec![
+            Message::system("huge planning prompt").cacheable(),
+            Message::system("per-turn context"),
+            Message::user("list files"),
+        ];
+
+        let request = provider.build_request("claude", &messages, &GenerateOptions::default());
+        let body = serde_json::to_value(&request).unwrap();
+
+        assert_eq!(body["system"][0]["text"], "huge planning prompt");
+        assert_eq!(body["system"][0]["cache_control"]["type"], "ephemeral");
+        assert!(body["system"][1].get("cache_control").is_none());
+        assert!(body["messages"][0]["content"][0].get("cache_control").is_none());
+
+        let options = GenerateOptions {
+            system: Some("override".into()),
+            cache_system: true,
+            ..Default::default()
+        };
+        let body = serde_json::to_value(provider.build_request("claude", &messages, &options)).unwrap();
+        assert_eq!(body["system"].as_array().unwrap().len(), 1);
+        assert_eq!(body["system"][0]["cache_control"]["type"], "ephemeral");
+    }
+
+    #[test]
+    fn test_usage_reports_cache_tokens() {
+        let response: AnthropicChatResponse = serde_json::from_str(
+            r#"{"content":[{"type":"text","text":"ok"}],"stop_reason":"end_turn",
+                "usage":{"input_tokens":12,"output_tokens":3,
+                         "cache_creation_input_tokens":0,"cache_read_input_tokens":2048}}"#,
+        )
+        .unwrap();
+        assert_eq!(response.usage.cache_read_input_tokens, 2048);
+
+        let response: AnthropicChatResponse = serde_json::from_str(
+            r#"{"content":[],"usage":{"input_tokens":1,"output_tokens":1}}"#,
+        )
+        .unwrap();
+        assert_eq!(response.usage.cache_creation_input_tokens, 0);
+    }
+
+    #[test]
+    fn test_tool_use_blocks_become_tool_calls() {
+        let tools = [ToolDefinition {
+            name: "shell".into(),
+            description: "run a command".into(),
+            parameters: serde_json::json!({"type": "object"}),
+        }];
+        let provider = AnthropicProvider::new("key");
+        let mut request = provider.build_request("claude", &[Message::user("list files")], &GenerateOptions::default());
+        request.tools = Some(tools.iter().map(AnthropicTool::from).collect());
+        let body = serde_json::to_value(&request).unwrap();
+        assert_eq!(body["tools"][0]["name"], "shell");
+        assert_eq!(body["tools"][0]["input_schema"]["type"], "object");
+
+        let response: AnthropicChatResponse = serde_json::from_str(
+            r#"{"content":[{"type":"text","text":"Listing."},
+                {"type":"tool_use","id":"toolu_1","name":"shell","input":{"command":"ls"}}],
+                "stop_reason":"tool_use","usage":{"input_tokens":10,"output_tokens":5}}"#,
+        )
+        .unwrap();
+        let response = ToolResponse::from(response);
+        assert_eq!(response.content.as_deref(), Some("Listing."));
+        assert_eq!(response.finish_reason.as_deref(), Some("tool_use"));
+        assert_eq!(response.tool_calls[0].id, "toolu_1");
+        assert_eq!(response.tool_calls[0].arguments, serde_json::json!({"command": "ls"}));
+    }
 }
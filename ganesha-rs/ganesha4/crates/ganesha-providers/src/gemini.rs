@@ -1,10 +1,17 @@
 //! # Gemini Provider
 //!
-//! Implementation for Google Gemini API using their OpenAI-compatible endpoint.
+//! Implementation for Google Gemini API. Chat goes through the native
+//! `generateContent` endpoint so system prompts can use the dedicated
+//! `system_instruction` field; model listing uses the OpenAI-compatible endpoint.
+//!
+//! Gemini takes schemas (tool parameters, `response_schema`) as an OpenAPI
+//! subset and rejects JSON Schema keywords outside it, so those are converted
+//! by `to_gemini_schema` first.
 
 use crate::{
     GenerateOptions, LlmProvider, Message, MessageRole, ModelInfo, ModelTier,
-    ProviderError, Response, Result, Usage, get_model_tier,
+    ProviderConfig, ProviderError, Response, Result, ToolCall, ToolDefinition,
+    ToolProvider, ToolResponse, Usage, get_model_tier,
 };
 use async_trait::async_trait;
 use reqwest::Client;
@@ -25,34 +32,174 @@ impl GeminiProvider {
         Self {
             client: Client::new(),
             api_key: api_key.into(),
-            base_url: "https://generativelanguage.googleapis.com/v1beta/openai".to_string(),
+            base_url: "https://generativelanguage.googleapis.com/v1beta".to_string(),
             default_model: "gemini-2.0-flash".to_string(),
         }
     }
 
+    /// Apply per-provider HTTP settings (extra headers, proxy)
+    pub fn with_config(mut self, config: &ProviderConfig) -> Result<Self> {
+        self.client = config.build_client(None)?;
+        Ok(self)
+    }
+
     /// Set the default model
     pub fn with_default_model(mut self, model: impl Into<String>) -> Self {
         self.default_model = model.into();
         self
     }
 
-    /// Convert our message format to OpenAI-compatible format
-    fn convert_messages(&self, messages: &[Message]) -> Vec<GeminiMessage> {
-        messages
-            .iter()
-            .map(|m| GeminiMessage {
-                role: match m.role {
-                    MessageRole::System => "system".to_string(),
-                    MessageRole::User => "user".to_string(),
-                    MessageRole::Assistant => "assistant".to_string(),
-                    MessageRole::Tool => "tool".to_string(),
-                },
-                content: m.content.clone(),
-            })
-            .collect()
+    /// Set the API base URL (e.g., for a proxy)
+    pub fn with_base_url(mut self, url: impl Into<String>) -> Self {
+        self.base_url = url.into();
+        self
+    }
+
+    /// Build a `generateContent` request. System messages (and `options.system`)
+    /// are joined into `system_instruction`; everything else becomes `contents`
+    /// with Gemini's `user`/`model` roles.
+    fn build_request(messages: &[Message], options: &GenerateOptions) -> GeminiGenerateRequest {
+        let mut system_parts: Vec<&str> = Vec::new();
+        if let Some(system) = &options.system {
+            system_parts.push(system);
+        }
+
+        let mut contents = Vec::new();
+        for m in messages {
+            let role = match m.role {
+                MessageRole::System => {
+                    system_parts.push(&m.content);
+                    continue;
+                }
+                MessageRole::Assistant => "model",
+                // Gemini has no tool role for plain-text results
+                MessageRole::User | MessageRole::Tool => "user",
+            };
+            contents.push(GeminiContent {
+                role: Some(role.to_string()),
+                parts: vec![GeminiPart { text: m.content.clone() }],
+            });
+        }
+
+        let system_instruction = (!system_parts.is_empty()).then(|| GeminiContent {
+            role: None,
+            parts: vec![GeminiPart { text: system_parts.join("\n\n") }],
+        });
+
+        GeminiGenerateRequest {
+            contents,
+            system_instruction,
+            generation_config: GeminiGenerationConfig {
+                temperature: options.temperature,
+                max_output_tokens: options.max_tokens,
+                stop_sequences: options.stop.clone(),
+                response_mime_type: (options.json_mode || options.response_schema.is_some())
+                    .then(|| "application/json".to_string()),
+                response_schema: options.response_schema.as_ref().map(to_gemini_schema),
+            },
+            tools: None,
+        }
+    }
+
+    /// POST a request to `generateContent` for `model`
+    async fn send(&self, model: &str, request: &GeminiGenerateRequest) -> Result<GeminiGenerateResponse> {
+        // Model ids from the models list carry a "models/" prefix
+        let url = format!(
+            "{}/models/{}:generateContent",
+            self.base_url,
+            model.trim_start_matches("models/")
+        );
+        let response = self
+            .client
+            .post(&url)
+            .header("x-goog-api-key", &self.api_key)
+            .header("Content-Type", "application/json")
+            .json(request)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let body = response.text().await.unwrap_or_default();
+
+            // Check for rate limiting
+            if status == 429 {
+                return Err(ProviderError::RateLimited { retry_after: None });
+            }
+
+            return Err(ProviderError::ApiError {
+                status,
+                message: body,
+            });
+        }
+
+        Ok(response.json().await?)
     }
 }
 
+/// JSON Schema keywords Gemini's OpenAPI-subset `Schema` accepts as-is
+const GEMINI_SCHEMA_KEYS: &[&str] = &[
+    "type", "format", "title", "description", "nullable", "enum", "required",
+    "minItems", "maxItems", "minimum", "maximum", "minLength", "maxLength",
+    "pattern", "minProperties", "maxProperties", "propertyOrdering", "example",
+];
+
+/// Convert a JSON Schema to the OpenAPI subset Gemini accepts. Unsupported
+/// keywords (`$schema`, `additionalProperties`, `default`, `$ref`, ...) are
+/// dropped; `"type": [T, "null"]` becomes `T` plus `nullable`, `const`
+/// becomes a one-value `enum` and `oneOf` becomes `anyOf`.
+fn to_gemini_schema(schema: &serde_json::Value) -> serde_json::Value {
+    use serde_json::Value;
+
+    let Some(object) = schema.as_object() else {
+        return schema.clone();
+    };
+
+    let mut converted = serde_json::Map::new();
+    for (key, value) in object {
+        match key.as_str() {
+            "properties" => {
+                let properties = value
+                    .as_object()
+                    .map(|p| p.iter().map(|(name, s)| (name.clone(), to_gemini_schema(s))).collect())
+                    .unwrap_or_default();
+                converted.insert(key.clone(), Value::Object(properties));
+            }
+            "items" => {
+                converted.insert(key.clone(), to_gemini_schema(value));
+            }
+            "anyOf" | "oneOf" => {
+                let variants = value
+                    .as_array()
+                    .map(|v| v.iter().map(to_gemini_schema).collect())
+                    .unwrap_or_default();
+                converted.insert("anyOf".to_string(), Value::Array(variants));
+            }
+            "const" => {
+                converted.insert("enum".to_string(), Value::Array(vec![value.clone()]));
+            }
+            "type" => match value.as_array() {
+                Some(types) => {
+                    if let Some(t) = types.iter().find(|t| t.as_str() != Some("null")) {
+                        converted.insert(key.clone(), t.clone());
+                    }
+                    if types.iter().any(|t| t.as_str() == Some("null")) {
+                        converted.insert("nullable".to_string(), Value::Bool(true));
+                    }
+                }
+                None => {
+                    converted.insert(key.clone(), value.clone());
+                }
+            },
+            k if GEMINI_SCHEMA_KEYS.contains(&k) => {
+                converted.entry(key.clone()).or_insert_with(|| value.clone());
+            }
+            _ => {}
+        }
+    }
+    Value::Object(converted)
+}
+
 #[async_trait]
 impl LlmProvider for GeminiProvider {
     fn name(&self) -> &str {
@@ -66,7 +213,7 @@ impl LlmProvider for GeminiProvider {
         }
 
         // Try a simple models list request
-        let url = format!("{}/models", self.base_url);
+        let url = format!("{}/openai/models", self.base_url);
         match self
             .client
             .get(&url)
@@ -88,7 +235,7 @@ impl LlmProvider for GeminiProvider {
     }
 
     async fn list_models(&self) -> Result<Vec<ModelInfo>> {
-        let url = format!("{}/models", self.base_url);
+        let url = format!("{}/openai/models", self.base_url);
         let response = self
             .client
             .get(&url)
@@ -132,115 +279,195 @@ impl LlmProvider for GeminiProvider {
 
         debug!("Gemini chat with model: {}", model);
 
-        let mut request = GeminiChatRequest {
-            model: model.clone(),
-            messages: self.convert_messages(messages),
-            temperature: options.temperature,
-            max_tokens: options.max_tokens,
-            stop: options.stop.clone(),
-        };
-
-        // Add system prompt if provided
-        if let Some(system) = &options.system {
-            request.messages.insert(
-                0,
-                GeminiMessage {
-                    role: "system".to_string(),
-                    content: system.clone(),
-                },
-            );
-        }
+        let request = Self::build_request(messages, options);
+        let chat_response = self.send(&model, &request).await?;
 
-        let url = format!("{}/chat/completions", self.base_url);
-        let response = self
-            .client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await?;
+        let candidate = chat_response
+            .candidates
+            .into_iter()
+            .next()
+            .ok_or_else(|| ProviderError::InvalidResponse("No candidates in response".to_string()))?;
 
-        if !response.status().is_success() {
-            let status = response.status().as_u16();
-            let body = response.text().await.unwrap_or_default();
+        Ok(Response {
+            content: candidate
+                .content
+                .map(|c| c.parts.into_iter().filter_map(|p| p.text).collect())
+                .unwrap_or_default(),
+            model,
+            finish_reason: candidate.finish_reason,
+            usage: chat_response.usage_metadata.map(|u| Usage {
+                prompt_tokens: u.prompt_token_count,
+                completion_tokens: u.candidates_token_count,
+                total_tokens: u.total_token_count,
+                ..Default::default()
+            }),
+        })
+    }
 
-            // Check for rate limiting
-            if status == 429 {
-                return Err(ProviderError::RateLimited { retry_after: None });
-            }
+    fn as_tool_provider(&self) -> Option<&dyn ToolProvider> {
+        Some(self)
+    }
+}
 
-            return Err(ProviderError::ApiError {
-                status,
-                message: body,
-            });
+#[async_trait]
+impl ToolProvider for GeminiProvider {
+    async fn chat_with_tools(
+        &self,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+        options: &GenerateOptions,
+    ) -> Result<ToolResponse> {
+        let model = options.model.as_ref().unwrap_or(&self.default_model);
+        debug!("Gemini tool call with model: {} ({} tools)", model, tools.len());
+
+        let mut request = Self::build_request(messages, options);
+        if !tools.is_empty() {
+            request.tools = Some(vec![GeminiTools {
+                function_declarations: tools.iter().map(GeminiFunctionDeclaration::from).collect(),
+            }]);
         }
 
-        let chat_response: GeminiChatResponse = response.json().await?;
-
-        let choice = chat_response
-            .choices
+        let candidate = self
+            .send(model, &request)
+            .await?
+            .candidates
             .into_iter()
             .next()
-            .ok_or_else(|| ProviderError::InvalidResponse("No choices in response".to_string()))?;
+            .ok_or_else(|| ProviderError::InvalidResponse("No candidates in response".to_string()))?;
 
-        Ok(Response {
-            content: choice.message.content.unwrap_or_default(),
-            model,
-            finish_reason: choice.finish_reason,
-            usage: chat_response.usage.map(|u| Usage {
-                prompt_tokens: u.prompt_tokens,
-                completion_tokens: u.completion_tokens,
-                total_tokens: u.total_tokens,
-            }),
+        let mut content = String::new();
+        let mut tool_calls = Vec::new();
+        for part in candidate.content.map(|c| c.parts).unwrap_or_default() {
+            if let Some(text) = part.text {
+                content.push_str(&text);
+            }
+            if let Some(call) = part.function_call {
+                // Gemini doesn't always id its calls; number them instead
+                tool_calls.push(ToolCall {
+                    id: call.id.unwrap_or_else(|| format!("call_{}", tool_calls.len())),
+                    name: call.name,
+                    arguments: call.args,
+                });
+            }
+        }
+
+        Ok(ToolResponse {
+            content: (!content.is_empty()).then_some(content),
+            tool_calls,
+            finish_reason: candidate.finish_reason,
         })
     }
 }
 
-// Gemini API types (OpenAI-compatible)
+// Gemini generateContent API types
 
 #[derive(Debug, Serialize)]
-struct GeminiChatRequest {
-    model: String,
-    messages: Vec<GeminiMessage>,
+struct GeminiGenerateRequest {
+    contents: Vec<GeminiContent>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    temperature: Option<f32>,
+    system_instruction: Option<GeminiContent>,
+    generation_config: GeminiGenerationConfig,
     #[serde(skip_serializing_if = "Option::is_none")]
-    max_tokens: Option<u32>,
+    tools: Option<Vec<GeminiTools>>,
+}
+
+#[derive(Debug, Serialize)]
+struct GeminiTools {
+    function_declarations: Vec<GeminiFunctionDeclaration>,
+}
+
+#[derive(Debug, Serialize)]
+struct GeminiFunctionDeclaration {
+    name: String,
+    description: String,
+    parameters: serde_json::Value,
+}
+
+impl From<&ToolDefinition> for GeminiFunctionDeclaration {
+    fn from(tool: &ToolDefinition) -> Self {
+        Self {
+            name: tool.name.clone(),
+            description: tool.description.clone(),
+            parameters: to_gemini_schema(&tool.parameters),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct GeminiContent {
     #[serde(skip_serializing_if = "Option::is_none")]
-    stop: Option<Vec<String>>,
+    role: Option<String>,
+    parts: Vec<GeminiPart>,
 }
 
 #[derive(Debug, Serialize)]
-struct GeminiMessage {
-    role: String,
-    content: String,
+struct GeminiPart {
+    text: String,
+}
+
+#[derive(Debug, Serialize)]
+struct GeminiGenerationConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    max_output_tokens: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stop_sequences: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_mime_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_schema: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Deserialize)]
-struct GeminiChatResponse {
-    choices: Vec<GeminiChoice>,
-    usage: Option<GeminiUsage>,
+#[serde(rename_all = "camelCase")]
+struct GeminiGenerateResponse {
+    #[serde(default)]
+    candidates: Vec<GeminiCandidate>,
+    usage_metadata: Option<GeminiUsage>,
 }
 
 #[derive(Debug, Deserialize)]
-struct GeminiChoice {
-    message: GeminiResponseMessage,
+#[serde(rename_all = "camelCase")]
+struct GeminiCandidate {
+    content: Option<GeminiResponseContent>,
     finish_reason: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
-struct GeminiResponseMessage {
-    content: Option<String>,
+struct GeminiResponseContent {
+    #[serde(default)]
+    parts: Vec<GeminiResponsePart>,
 }
 
 #[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GeminiResponsePart {
+    text: Option<String>,
+    function_call: Option<GeminiFunctionCall>,
+}
+
+#[derive(Debug, Deserialize)]
+struct GeminiFunctionCall {
+    id: Option<String>,
+    name: String,
+    #[serde(default)]
+    args: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
 struct GeminiUsage {
-    prompt_tokens: u32,
-    completion_tokens: u32,
-    total_tokens: u32,
+    #[serde(default)]
+    prompt_token_count: u32,
+    #[serde(default)]
+    candidates_token_count: u32,
+    #[serde(default)]
+    total_token_count: u32,
 }
 
+// OpenAI-compatible models list
+
 #[derive(Debug, Deserialize)]
 struct GeminiModelsResponse {
     data: Vec<GeminiModel>,
@@ -250,3 +477,141 @@ struct GeminiModelsResponse {
 struct GeminiModel {
     id: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Matcher;
+
+    #[test]
+    fn test_system_messages_go_to_system_instruction() {
+        let messages = vec![
+            Message::system("You are terse."),
+            Message::user("hi"),
+            Message::assistant("hello"),
+            Message::system("Answer in French."),
+            Message::user("how are you?"),
+        ];
+        let options = GenerateOptions::default();
+
+        let body = serde_json::to_value(GeminiProvider::build_request(&messages, &options)).unwrap();
+
+        assert_eq!(
+            body["system_instruction"]["parts"][0]["text"],
+            "You are terse.\n\nAnswer in French."
+        );
+        let contents = body["contents"].as_array().unwrap();
+        assert_eq!(contents.len(), 3);
+        let roles: Vec<_> = contents.iter().map(|c| c["role"].as_str().unwrap()).collect();
+        assert_eq!(roles, vec!["user", "model", "user"]);
+        assert!(!body["contents"].to_string().contains("You are terse."));
+        assert!(!body["contents"].to_string().contains("system"));
+    }
+
+    #[tokio::test]
+    async fn test_chat_uses_generate_content() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/models/gemini-2.0-flash:generateContent")
+            .match_header("x-goog-api-key", "key")
+            .match_body(Matcher::PartialJsonString(
+                r#"{"system_instruction":{"parts":[{"text":"Be brief."}]}}"#.into(),
+            ))
+            .with_body(
+                r#"{"candidates":[{"content":{"role":"model","parts":[{"text":"Hi"},{"text":"!"}]},"finishReason":"STOP"}],
+                    "usageMetadata":{"promptTokenCount":5,"candidatesTokenCount":2,"totalTokenCount":7}}"#,
+            )
+            .create_async()
+            .await;
+
+        let provider = GeminiProvider::new("key").with_base_url(server.url());
+        let options = GenerateOptions {
+            system: Some("Be brief.".into()),
+            ..Default::default()
+        };
+        let response = provider.chat(&[Message::user("hello")], &options).await.unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(response.content, "Hi!");
+        assert_eq!(response.finish_reason.as_deref(), Some("STOP"));
+        assert_eq!(response.usage.unwrap().total_tokens, 7);
+    }
+
+    #[test]
+    fn test_schemas_are_converted_to_the_openapi_subset() {
+        let schema = serde_json::json!({
+            "$schema": "http://json-schema.org/draft-07/schema#",
+            "type": "object",
+            "additionalProperties": false,
+            "properties": {
+                "path": {"type": "string", "default": ".", "description": "Where"},
+                "mode": {"const": "fast"},
+                "limit": {"type": ["integer", "null"]},
+                "tags": {"type": "array", "items": {"type": "string", "default": "x"}}
+            },
+            "required": ["path"]
+        });
+
+        assert_eq!(
+            to_gemini_schema(&schema),
+            serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "path": {"type": "string", "description": "Where"},
+                    "mode": {"enum": ["fast"]},
+                    "limit": {"type": "integer", "nullable": true},
+                    "tags": {"type": "array", "items": {"type": "string"}}
+                },
+                "required": ["path"]
+            })
+        );
+
+        let options = GenerateOptions {
+            response_schema: Some(schema),
+            ..Default::default()
+        };
+        let body = serde_json::to_value(GeminiProvider::build_request(&[], &options)).unwrap();
+        let sent = body["generation_config"]["response_schema"].to_string();
+        assert!(!sent.contains("$schema") && !sent.contains("additionalProperties"));
+    }
+
+    #[tokio::test]
+    async fn test_chat_with_tools_sends_function_declarations() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/models/gemini-2.0-flash:generateContent")
+            .match_body(Matcher::PartialJsonString(
+                r#"{"tools":[{"function_declarations":[{"name":"read_file",
+                    "parameters":{"type":"object","properties":{"path":{"type":"string"}}}}]}]}"#
+                    .into(),
+            ))
+            .with_body(
+                r#"{"candidates":[{"content":{"role":"model","parts":[
+                    {"functionCall":{"name":"read_file","args":{"path":"a.txt"}}}]},"finishReason":"STOP"}]}"#,
+            )
+            .create_async()
+            .await;
+
+        let provider = GeminiProvider::new("key").with_base_url(server.url());
+        let tool = ToolDefinition {
+            name: "read_file".into(),
+            description: "Read a file".into(),
+            parameters: serde_json::json!({
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {"path": {"type": "string", "default": "."}}
+            }),
+        };
+        let response = provider
+            .chat_with_tools(&[Message::user("read a.txt")], &[tool], &GenerateOptions::default())
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(response.content, None);
+        assert_eq!(response.tool_calls.len(), 1);
+        assert_eq!(response.tool_calls[0].id, "call_0");
+        assert_eq!(response.tool_calls[0].name, "read_file");
+        assert_eq!(response.tool_calls[0].arguments, serde_json::json!({"path": "a.txt"}));
+    }
+}
@@ -35,6 +35,9 @@ pub mod local;
 pub mod manager;
 pub mod tiers;
 pub mod message;
+pub mod logging;
+pub mod schema;
+pub mod mock;
 
 pub use traits::{
     LlmProvider, StreamingProvider, ToolProvider,
@@ -46,9 +49,15 @@ pub use anthropic::AnthropicProvider;
 pub use gemini::GeminiProvider;
 pub use openrouter::OpenRouterProvider;
 pub use local::{LocalProvider, LocalProviderType};
-pub use manager::{ProviderManager, ProviderPriority, ProviderConfig};
+pub use manager::{
+    ProviderManager, ProviderPriority, ProviderConfig, ProviderHealth,
+    CircuitState, ProviderHealthReport, DEFAULT_PROVIDER_COOLDOWN, MAX_PROVIDER_COOLDOWN,
+};
 pub use tiers::{ModelTier, ModelInfo, get_model_tier};
 pub use message::{Message, MessageRole};
+pub use logging::{LoggingProvider, LoggingConfig, DEFAULT_MAX_BODY_CHARS, redact_secrets};
+pub use schema::{validate_response, RESPONSE_SCHEMA_NAME};
+pub use mock::{MockProvider, MockReply, MockMatcher, MockCall};
 
 use thiserror::Error;
 
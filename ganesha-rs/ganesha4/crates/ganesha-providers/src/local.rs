@@ -9,7 +9,8 @@
 
 use crate::{
     GenerateOptions, LlmProvider, Message, MessageRole, ModelInfo, ModelTier,
-    ProviderError, Response, Result, Usage, get_model_tier,
+    ProviderConfig, ProviderError, Response, Result, Usage, get_model_tier,
+    validate_response,
 };
 use async_trait::async_trait;
 use reqwest::Client;
@@ -17,6 +18,9 @@ use serde::{Deserialize, Serialize};
 use tracing::{debug, info};
 use std::time::Duration;
 
+/// Timeout for local inference requests (local models can be slow)
+const LOCAL_TIMEOUT: Duration = Duration::from_secs(300);
+
 /// Type of local provider
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum LocalProviderType {
@@ -97,7 +101,7 @@ impl LocalProvider {
     pub fn new(provider_type: LocalProviderType) -> Self {
         Self {
             client: Client::builder()
-                .timeout(Duration::from_secs(300)) // 5 min timeout for local inference
+                .timeout(LOCAL_TIMEOUT)
                 .build()
                 .unwrap(),
             base_url: provider_type.default_base_url(),
@@ -112,6 +116,12 @@ impl LocalProvider {
         self
     }
 
+    /// Apply per-provider HTTP settings (extra headers, proxy)
+    pub fn with_config(mut self, config: &ProviderConfig) -> Result<Self> {
+        self.client = config.build_client(Some(LOCAL_TIMEOUT))?;
+        Ok(self)
+    }
+
     /// Set the default model
     pub fn with_default_model(mut self, model: impl Into<String>) -> Self {
         self.default_model = Some(model.into());
@@ -197,6 +207,8 @@ impl LocalProvider {
             model: model.clone(),
             messages: self.convert_messages(messages),
             stream: false,
+            // Ollama's JSON mode; schemas are checked after the fact
+            format: (options.json_mode || options.response_schema.is_some()).then(|| "json".to_string()),
             options: OllamaOptions {
                 temperature: options.temperature,
                 num_predict: options.max_tokens.map(|t| t as i32),
@@ -216,7 +228,18 @@ impl LocalProvider {
             });
         }
 
-        let chat_response: OllamaChatResponse = response.json().await?;
+        let body = response.text().await?;
+        let chat_response: OllamaChatResponse = serde_json::from_str(&body).map_err(|e| {
+            ProviderError::InvalidResponse(format!(
+                "unexpected Ollama response ({}): {}",
+                e,
+                body_snippet(&body)
+            ))
+        })?;
+
+        if let Some(schema) = &options.response_schema {
+            validate_response(&chat_response.message.content, schema)?;
+        }
 
         Ok(Response {
             content: chat_response.message.content,
@@ -227,6 +250,7 @@ impl LocalProvider {
                 completion_tokens: chat_response.eval_count.unwrap_or(0),
                 total_tokens: chat_response.prompt_eval_count.unwrap_or(0)
                     + chat_response.eval_count.unwrap_or(0),
+                ..Default::default()
             }),
         })
     }
@@ -262,6 +286,9 @@ impl LocalProvider {
             temperature: options.temperature,
             max_tokens: options.max_tokens,
             stop: options.stop.clone(),
+            // Local servers vary in json_schema support, so ask for JSON and validate here
+            response_format: (options.json_mode || options.response_schema.is_some())
+                .then(|| serde_json::json!({"type": "json_object"})),
         };
 
         let url = format!("{}/chat/completions", self.base_url);
@@ -276,27 +303,127 @@ impl LocalProvider {
             });
         }
 
-        let chat_response: OpenAiCompatResponse = response.json().await?;
-
-        let choice = chat_response
-            .choices
-            .into_iter()
-            .next()
-            .ok_or_else(|| ProviderError::InvalidResponse("No choices in response".to_string()))?;
+        let body = response.text().await?;
+        let response = parse_openai_compatible_response(&body, model)?;
+        if let Some(schema) = &options.response_schema {
+            validate_response(&response.content, schema)?;
+        }
+        Ok(response)
+    }
+}
 
-        Ok(Response {
-            content: choice.message.content.unwrap_or_default(),
-            model,
-            finish_reason: choice.finish_reason,
-            usage: chat_response.usage.map(|u| Usage {
-                prompt_tokens: u.prompt_tokens,
-                completion_tokens: u.completion_tokens,
-                total_tokens: u.total_tokens,
-            }),
+/// Maximum number of body characters quoted in an `InvalidResponse` error
+const BODY_SNIPPET_CHARS: usize = 200;
+
+/// Parse a chat completion from an OpenAI-compatible server, tolerating the
+/// off-spec shapes seen from the long tail of local servers: missing `usage`,
+/// completion-style `text` instead of `message.content`, content given as an
+/// array of parts, or no `choices` wrapper at all.
+fn parse_openai_compatible_response(body: &str, model: String) -> Result<Response> {
+    let json: serde_json::Value = serde_json::from_str(body).map_err(|e| {
+        ProviderError::InvalidResponse(format!("not valid JSON ({}): {}", e, body_snippet(body)))
+    })?;
+
+    let choice = json
+        .get("choices")
+        .and_then(|c| c.as_array())
+        .and_then(|c| c.first());
+
+    let content = choice
+        .and_then(|c| {
+            c.get("message")
+                .and_then(|m| m.get("content").or_else(|| m.get("text")))
+                .or_else(|| c.get("text"))
+                .or_else(|| c.get("delta").and_then(|d| d.get("content")))
         })
+        .or_else(|| {
+            ["content", "response", "output", "text", "generated_text"]
+                .iter()
+                .find_map(|key| json.get(*key))
+        })
+        .and_then(content_text);
+
+    let content = match content {
+        Some(content) => content,
+        // An explicit null content (e.g. a tool-only turn) is a valid empty reply
+        None if choice
+            .and_then(|c| c.get("message"))
+            .map(|m| m.get("content").is_some_and(|c| c.is_null()))
+            .unwrap_or(false) =>
+        {
+            String::new()
+        }
+        None => {
+            return Err(ProviderError::InvalidResponse(format!(
+                "no completion text found in response: {}",
+                body_snippet(body)
+            )))
+        }
+    };
+
+    let finish_reason = choice
+        .and_then(|c| c.get("finish_reason"))
+        .and_then(|f| f.as_str())
+        .map(str::to_string);
+
+    let usage = json.get("usage").filter(|u| u.is_object()).map(|u| {
+        let field = |name: &str| u.get(name).and_then(|v| v.as_u64()).unwrap_or(0) as u32;
+        let prompt_tokens = field("prompt_tokens");
+        let completion_tokens = field("completion_tokens");
+        let total_tokens = match field("total_tokens") {
+            0 => prompt_tokens + completion_tokens,
+            total => total,
+        };
+        Usage {
+            prompt_tokens,
+            completion_tokens,
+            total_tokens,
+            ..Default::default()
+        }
+    });
+
+    Ok(Response {
+        content,
+        model,
+        finish_reason,
+        usage,
+    })
+}
+
+/// Extract text from a content value given either as a string or as an
+/// array of strings / `{"type": "text", "text": ...}` parts
+fn content_text(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::String(text) => Some(text.clone()),
+        serde_json::Value::Array(parts) => {
+            let texts: Vec<&str> = parts
+                .iter()
+                .filter_map(|part| {
+                    part.as_str()
+                        .or_else(|| part.get("text").and_then(|t| t.as_str()))
+                        .or_else(|| part.get("content").and_then(|t| t.as_str()))
+                })
+                .collect();
+            if texts.is_empty() && !parts.is_empty() {
+                None
+            } else {
+                Some(texts.concat())
+            }
+        }
+        _ => None,
     }
 }
 
+/// Leading part of a response body, for error messages
+fn body_snippet(body: &str) -> String {
+    let trimmed = body.trim();
+    if trimmed.chars().count() <= BODY_SNIPPET_CHARS {
+        return trimmed.to_string();
+    }
+    let snippet: String = trimmed.chars().take(BODY_SNIPPET_CHARS).collect();
+    format!("{}...", snippet)
+}
+
 #[async_trait]
 impl LlmProvider for LocalProvider {
     fn name(&self) -> &str {
@@ -420,6 +547,8 @@ struct OllamaChatRequest {
     model: String,
     messages: Vec<LocalMessage>,
     stream: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<String>,
     options: OllamaOptions,
 }
 
@@ -467,38 +596,161 @@ struct OpenAiCompatRequest {
     max_tokens: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
     stop: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Deserialize)]
-struct OpenAiCompatResponse {
-    choices: Vec<OpenAiCompatChoice>,
-    usage: Option<OpenAiCompatUsage>,
+struct OpenAiCompatModelsResponse {
+    data: Vec<OpenAiCompatModel>,
 }
 
 #[derive(Debug, Deserialize)]
-struct OpenAiCompatChoice {
-    message: OpenAiCompatMessage,
-    finish_reason: Option<String>,
+struct OpenAiCompatModel {
+    id: String,
 }
 
-#[derive(Debug, Deserialize)]
-struct OpenAiCompatMessage {
-    content: Option<String>,
-}
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-#[derive(Debug, Deserialize)]
-struct OpenAiCompatUsage {
-    prompt_tokens: u32,
-    completion_tokens: u32,
-    total_tokens: u32,
-}
+    fn parse(body: &str) -> Result<Response> {
+        parse_openai_compatible_response(body, "test-model".to_string())
+    }
 
-#[derive(Debug, Deserialize)]
-struct OpenAiCompatModelsResponse {
-    data: Vec<OpenAiCompatModel>,
-}
+    #[test]
+    fn test_parses_spec_response() {
+        let response = parse(r#"{
+            "choices": [{"message": {"role": "assistant", "content": "hello"}, "finish_reason": "stop"}],
+            "usage": {"prompt_tokens": 3, "completion_tokens": 1, "total_tokens": 4}
+        }"#).unwrap();
 
-#[derive(Debug, Deserialize)]
-struct OpenAiCompatModel {
-    id: String,
+        assert_eq!(response.content, "hello");
+        assert_eq!(response.finish_reason.as_deref(), Some("stop"));
+        assert_eq!(response.usage.unwrap().total_tokens, 4);
+    }
+
+    #[test]
+    fn test_tolerates_missing_and_partial_usage() {
+        let response = parse(r#"{"choices": [{"message": {"content": "hi"}}]}"#).unwrap();
+        assert_eq!(response.content, "hi");
+        assert!(response.usage.is_none());
+
+        let response = parse(r#"{
+            "choices": [{"message": {"content": "hi"}}],
+            "usage": {"prompt_tokens": 5, "completion_tokens": 2}
+        }"#).unwrap();
+        assert_eq!(response.usage.unwrap().total_tokens, 7);
+    }
+
+    #[test]
+    fn test_accepts_alternate_content_fields() {
+        // Completion-style choice
+        let response = parse(r#"{"choices": [{"text": "from text", "finish_reason": "length"}]}"#).unwrap();
+        assert_eq!(response.content, "from text");
+
+        // No choices wrapper
+        let response = parse(r#"{"response": "bare response"}"#).unwrap();
+        assert_eq!(response.content, "bare response");
+
+        // Streaming-style delta in a non-streaming reply
+        let response = parse(r#"{"choices": [{"delta": {"content": "delta text"}}]}"#).unwrap();
+        assert_eq!(response.content, "delta text");
+    }
+
+    #[test]
+    fn test_accepts_array_content() {
+        let response = parse(r#"{"choices": [{"message": {"content": [
+            {"type": "text", "text": "part one, "},
+            {"type": "text", "text": "part two"}
+        ]}}]}"#).unwrap();
+        assert_eq!(response.content, "part one, part two");
+
+        let response = parse(r#"{"choices": [{"message": {"content": ["a", "b"]}}]}"#).unwrap();
+        assert_eq!(response.content, "ab");
+    }
+
+    #[test]
+    fn test_null_content_is_empty_reply() {
+        let response = parse(r#"{"choices": [{"message": {"content": null}, "finish_reason": "tool_calls"}]}"#).unwrap();
+        assert_eq!(response.content, "");
+    }
+
+    #[test]
+    fn test_unrecoverable_body_reports_snippet() {
+        let err = parse(r#"{"status": "loading model", "progress": 0.4}"#).unwrap_err();
+        match err {
+            ProviderError::InvalidResponse(msg) => {
+                assert!(msg.contains("no completion text"));
+                assert!(msg.contains("loading model"));
+            }
+            other => panic!("expected InvalidResponse, got {:?}", other),
+        }
+
+        let garbage = format!("<html>{}</html>", "x".repeat(500));
+        match parse(&garbage).unwrap_err() {
+            ProviderError::InvalidResponse(msg) => {
+                assert!(msg.contains("not valid JSON"));
+                assert!(msg.contains("<html>"));
+                assert!(msg.ends_with("..."));
+                assert!(msg.len() < 400);
+            }
+            other => panic!("expected InvalidResponse, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_chat_with_off_spec_server() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/v1/chat/completions")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"choices": [{"text": "served by a shim"}]}"#)
+            .create_async()
+            .await;
+
+        let provider = LocalProvider::new(LocalProviderType::Vllm)
+            .with_base_url(format!("{}/v1", server.url()));
+        let response = provider
+            .chat(&[Message::user("hi")], &GenerateOptions::default())
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(response.content, "served by a shim");
+        assert!(response.usage.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_response_schema_requests_json_and_validates() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/chat/completions")
+            .match_body(mockito::Matcher::PartialJsonString(
+                r#"{"response_format":{"type":"json_object"}}"#.into(),
+            ))
+            .with_body(r#"{"choices":[{"message":{"content":"{\"answer\": 42}"}}]}"#)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let provider = LocalProvider::new(LocalProviderType::LmStudio).with_base_url(server.url());
+        let mut options = GenerateOptions {
+            response_schema: Some(serde_json::json!({
+                "type": "object",
+                "properties": {"answer": {"type": "integer"}},
+                "required": ["answer"]
+            })),
+            ..Default::default()
+        };
+        let response = provider.chat(&[Message::user("?")], &options).await.unwrap();
+        assert_eq!(response.content, r#"{"answer": 42}"#);
+
+        options.response_schema = Some(serde_json::json!({"type": "object", "required": ["reason"]}));
+        let err = provider.chat(&[Message::user("?")], &options).await.unwrap_err();
+        assert!(matches!(err, ProviderError::InvalidResponse(_)), "{:?}", err);
+
+        mock.assert_async().await;
+    }
 }
@@ -0,0 +1,535 @@
+//! # Request Logging
+//!
+//! `LoggingProvider` wraps any provider and records each request
+//! (messages and options) with its response, usage or error. Records go
+//! to a tracing span and, optionally, one JSON object per line in a file.
+//!
+//! Logged text has API keys redacted and is capped at
+//! `LoggingConfig::max_body_chars`. Tool calls are logged like chats when
+//! the wrapped provider supports them. Streams are teed: every chunk reaches
+//! the caller as soon as the wrapped provider yields it, and the record is
+//! written once the stream ends.
+
+use crate::{
+    GenerateOptions, LlmProvider, Message, ModelInfo, ModelTier, ProviderError, Response,
+    Result, StreamingProvider, ToolDefinition, ToolProvider, ToolResponse,
+};
+use async_trait::async_trait;
+use futures::{Stream, StreamExt};
+use serde_json::{json, Value};
+use std::io::Write;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tracing::{debug, warn, Instrument};
+
+/// Default cap on each logged body, in characters
+pub const DEFAULT_MAX_BODY_CHARS: usize = 4096;
+
+/// Key prefixes that mark a credential in logged text
+const SECRET_PREFIXES: &[&str] = &["sk-", "AIza", "ghp_", "gsk_", "xai-", "hf_"];
+
+/// Shortest token after a prefix that is treated as a key
+const MIN_SECRET_LEN: usize = 16;
+
+/// Where and how much to log
+#[derive(Debug, Clone)]
+pub struct LoggingConfig {
+    /// Append a JSON record per request to this file
+    pub jsonl_path: Option<PathBuf>,
+    /// Longest message/response body kept, in characters
+    pub max_body_chars: usize,
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            jsonl_path: None,
+            max_body_chars: DEFAULT_MAX_BODY_CHARS,
+        }
+    }
+}
+
+impl LoggingConfig {
+    /// Also write records to a JSONL file
+    pub fn with_jsonl(mut self, path: impl Into<PathBuf>) -> Self {
+        self.jsonl_path = Some(path.into());
+        self
+    }
+
+    /// Set the per-body size cap
+    pub fn with_max_body_chars(mut self, max: usize) -> Self {
+        self.max_body_chars = max;
+        self
+    }
+}
+
+/// Shared JSONL writer; clones of a `LoggingProvider` config append to the same file
+#[derive(Clone)]
+struct RecordSink {
+    config: LoggingConfig,
+    file: Option<Arc<Mutex<std::fs::File>>>,
+}
+
+impl RecordSink {
+    fn new(config: LoggingConfig) -> Self {
+        let file = config.jsonl_path.as_ref().and_then(|path| {
+            std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .map_err(|e| warn!("Cannot open provider log {}: {}", path.display(), e))
+                .ok()
+                .map(|f| Arc::new(Mutex::new(f)))
+        });
+        Self { config, file }
+    }
+
+    fn body(&self, text: &str) -> String {
+        truncate(&redact_secrets(text), self.config.max_body_chars)
+    }
+
+    fn request(&self, messages: &[Message], options: &GenerateOptions) -> Value {
+        json!({
+            "messages": messages.iter().map(|m| json!({
+                "role": m.role,
+                "content": self.body(&m.content),
+            })).collect::<Vec<_>>(),
+            "options": {
+                "model": options.model,
+                "temperature": options.temperature,
+                "max_tokens": options.max_tokens,
+                "stop": options.stop,
+                "system": options.system.as_deref().map(|s| self.body(s)),
+                "json_mode": options.json_mode,
+                "response_schema": options.response_schema.is_some(),
+            },
+        })
+    }
+
+    fn write(&self, record: Value) {
+        debug!(target: "ganesha_providers::requests", record = %record, "provider request");
+        if let Some(file) = &self.file {
+            let mut file = file.lock().unwrap();
+            if let Err(e) = writeln!(file, "{}", record) {
+                warn!("Failed to write provider log: {}", e);
+            }
+        }
+    }
+}
+
+/// Provider decorator that logs every request and its outcome
+pub struct LoggingProvider<P> {
+    inner: P,
+    sink: RecordSink,
+}
+
+impl<P: LlmProvider> LoggingProvider<P> {
+    /// Wrap `inner`, logging as described by `config`
+    pub fn new(inner: P, config: LoggingConfig) -> Self {
+        Self {
+            inner,
+            sink: RecordSink::new(config),
+        }
+    }
+
+    /// The wrapped provider
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+
+    fn record(
+        &self,
+        kind: &str,
+        model: &str,
+        request: Value,
+        started: Instant,
+        outcome: std::result::Result<Value, &ProviderError>,
+    ) -> Value {
+        let (response, error) = match outcome {
+            Ok(response) => (response, Value::Null),
+            Err(e) => (Value::Null, json!(self.sink.body(&e.to_string()))),
+        };
+        json!({
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+            "provider": self.inner.name(),
+            "kind": kind,
+            "model": model,
+            "duration_ms": started.elapsed().as_millis() as u64,
+            "request": request,
+            "response": response,
+            "error": error,
+        })
+    }
+
+    fn model_for<'a>(&'a self, options: &'a GenerateOptions) -> &'a str {
+        options.model.as_deref().unwrap_or_else(|| self.inner.default_model())
+    }
+}
+
+#[async_trait]
+impl<P: LlmProvider> LlmProvider for LoggingProvider<P> {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn is_available(&self) -> bool {
+        self.inner.is_available().await
+    }
+
+    fn default_model(&self) -> &str {
+        self.inner.default_model()
+    }
+
+    fn model_tier(&self, model: &str) -> ModelTier {
+        self.inner.model_tier(model)
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        self.inner.list_models().await
+    }
+
+    async fn chat(&self, messages: &[Message], options: &GenerateOptions) -> Result<Response> {
+        let model = self.model_for(options).to_string();
+        let span = tracing::info_span!("llm_chat", provider = self.inner.name(), model = %model);
+        let request = self.sink.request(messages, options);
+        let started = Instant::now();
+
+        let result = self.inner.chat(messages, options).instrument(span.clone()).await;
+
+        let outcome = match &result {
+            Ok(response) => Ok(json!({
+                "content": self.sink.body(&response.content),
+                "model": response.model,
+                "finish_reason": response.finish_reason,
+                "usage": response.usage.as_ref().map(|u| json!({
+                    "prompt_tokens": u.prompt_tokens,
+                    "completion_tokens": u.completion_tokens,
+                    "total_tokens": u.total_tokens,
+                    "cache_read_tokens": u.cache_read_tokens,
+                    "cache_creation_tokens": u.cache_creation_tokens,
+                })),
+            })),
+            Err(e) => Err(e),
+        };
+        let record = self.record("chat", &model, request, started, outcome);
+        span.in_scope(|| self.sink.write(record));
+
+        result
+    }
+
+    /// Tool support follows the wrapped provider; calls still go through the log
+    fn as_tool_provider(&self) -> Option<&dyn ToolProvider> {
+        self.inner.as_tool_provider()?;
+        Some(self)
+    }
+}
+
+#[async_trait]
+impl<P: LlmProvider> ToolProvider for LoggingProvider<P> {
+    async fn chat_with_tools(
+        &self,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+        options: &GenerateOptions,
+    ) -> Result<ToolResponse> {
+        let Some(inner) = self.inner.as_tool_provider() else {
+            return Err(ProviderError::Unavailable(format!(
+                "{} does not support tool calling",
+                self.inner.name()
+            )));
+        };
+        let model = self.model_for(options).to_string();
+        let span = tracing::info_span!("llm_tools", provider = self.inner.name(), model = %model);
+        let mut request = self.sink.request(messages, options);
+        request["tools"] = json!(tools.iter().map(|t| &t.name).collect::<Vec<_>>());
+        let started = Instant::now();
+
+        let result = inner.chat_with_tools(messages, tools, options).instrument(span.clone()).await;
+
+        let outcome = match &result {
+            Ok(response) => Ok(json!({
+                "content": response.content.as_deref().map(|c| self.sink.body(c)),
+                "tool_calls": response.tool_calls.iter().map(|call| json!({
+                    "name": call.name,
+                    "arguments": self.sink.body(&call.arguments.to_string()),
+                })).collect::<Vec<_>>(),
+                "finish_reason": response.finish_reason,
+            })),
+            Err(e) => Err(e),
+        };
+        let record = self.record("tools", &model, request, started, outcome);
+        span.in_scope(|| self.sink.write(record));
+
+        result
+    }
+}
+
+#[async_trait]
+impl<P: StreamingProvider> StreamingProvider for LoggingProvider<P> {
+    async fn stream(
+        &self,
+        messages: &[Message],
+        options: &GenerateOptions,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<String>> + Send>>> {
+        let model = self.model_for(options).to_string();
+        let span = tracing::info_span!("llm_stream", provider = self.inner.name(), model = %model);
+        let request = self.sink.request(messages, options);
+        let started = Instant::now();
+
+        let mut inner = match self.inner.stream(messages, options).instrument(span.clone()).await {
+            Ok(stream) => stream,
+            Err(e) => {
+                let record = self.record("stream", &model, request, started, Err(&e));
+                span.in_scope(|| self.sink.write(record));
+                return Err(e);
+            }
+        };
+
+        // Build the record up front; only the outcome is filled in as chunks pass through
+        let record = self.record("stream", &model, request, started, Ok(Value::Null));
+        let sink = self.sink.clone();
+
+        let teed = async_stream::stream! {
+            let mut captured = String::new();
+            let mut chunks = 0usize;
+            let mut total_chars = 0usize;
+            let mut error = None;
+
+            while let Some(item) = inner.next().await {
+                match &item {
+                    Ok(chunk) => {
+                        chunks += 1;
+                        total_chars += chunk.chars().count();
+                        if captured.chars().count() <= sink.config.max_body_chars {
+                            captured.push_str(chunk);
+                        }
+                    }
+                    Err(e) => error = Some(e.to_string()),
+                }
+                yield item;
+            }
+
+            let mut record = record;
+            record["duration_ms"] = json!(started.elapsed().as_millis() as u64);
+            record["response"] = json!({
+                "content": sink.body(&captured),
+                "chunks": chunks,
+                "content_chars": total_chars,
+            });
+            record["error"] = error.map_or(Value::Null, |e| json!(sink.body(&e)));
+            span.in_scope(|| sink.write(record));
+        };
+
+        Ok(Box::pin(teed))
+    }
+}
+
+/// Replace anything that looks like an API key with `[REDACTED]`
+pub fn redact_secrets(text: &str) -> String {
+    let is_key_char = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_';
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while !rest.is_empty() {
+        let next = SECRET_PREFIXES
+            .iter()
+            .filter_map(|p| rest.find(p))
+            .chain(rest.find("Bearer "))
+            .min();
+        let Some(start) = next else {
+            out.push_str(rest);
+            break;
+        };
+
+        // Only redact at the start of a token, not inside a word like "task-"
+        let at_boundary = rest[..start].chars().next_back().is_none_or(|c| !is_key_char(c));
+        let (prefix_len, min_len) = if rest[start..].starts_with("Bearer ") {
+            ("Bearer ".len(), 1)
+        } else {
+            (0, MIN_SECRET_LEN)
+        };
+        let token_start = start + prefix_len;
+        let token_len = rest[token_start..]
+            .find(|c: char| !is_key_char(c) && c != '.')
+            .unwrap_or(rest.len() - token_start);
+
+        out.push_str(&rest[..token_start]);
+        if at_boundary && token_len >= min_len {
+            out.push_str("[REDACTED]");
+        } else {
+            out.push_str(&rest[token_start..token_start + token_len]);
+        }
+        rest = &rest[token_start + token_len..];
+        if token_len == 0 {
+            // Bare prefix with nothing after it; step past it
+            let step = rest.chars().next().map_or(0, char::len_utf8);
+            out.push_str(&rest[..step]);
+            rest = &rest[step..];
+        }
+    }
+
+    out
+}
+
+/// Cut `text` to `max` characters, noting how much was dropped
+fn truncate(text: &str, max: usize) -> String {
+    let total = text.chars().count();
+    if total <= max {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(max).collect();
+    format!("{}…[{} chars truncated]", kept, total - max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::{MockProvider, MockReply};
+    use crate::{ModelTier, ToolCall};
+    use std::time::Duration;
+    use tokio::sync::mpsc;
+
+    /// Streams whatever the test pushes into its channel
+    struct ChannelProvider {
+        receiver: Mutex<Option<mpsc::UnboundedReceiver<Result<String>>>>,
+    }
+
+    #[async_trait]
+    impl LlmProvider for ChannelProvider {
+        fn name(&self) -> &str {
+            "channel"
+        }
+
+        async fn is_available(&self) -> bool {
+            true
+        }
+
+        fn default_model(&self) -> &str {
+            "channel-model"
+        }
+
+        fn model_tier(&self, _model: &str) -> ModelTier {
+            ModelTier::Unknown
+        }
+
+        async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+            Ok(Vec::new())
+        }
+
+        async fn chat(&self, _messages: &[Message], _options: &GenerateOptions) -> Result<Response> {
+            Err(ProviderError::Unavailable("stream only".into()))
+        }
+    }
+
+    #[async_trait]
+    impl StreamingProvider for ChannelProvider {
+        async fn stream(
+            &self,
+            _messages: &[Message],
+            _options: &GenerateOptions,
+        ) -> Result<Pin<Box<dyn Stream<Item = Result<String>> + Send>>> {
+            let mut receiver = self.receiver.lock().unwrap().take().unwrap();
+            Ok(Box::pin(async_stream::stream! {
+                while let Some(item) = receiver.recv().await {
+                    yield item;
+                }
+            }))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stream_is_teed_not_buffered() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("requests.jsonl");
+
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let provider = LoggingProvider::new(
+            ChannelProvider { receiver: Mutex::new(Some(receiver)) },
+            LoggingConfig::default().with_jsonl(&path).with_max_body_chars(40),
+        );
+
+        let messages = [Message::user("my key is sk-proj-abcdefghijklmnopqrstuvwxyz, be careful")];
+        let mut stream = provider.stream(&messages, &GenerateOptions::default()).await.unwrap();
+
+        // The first chunk comes through while the provider is still producing
+        sender.send(Ok("Hello".to_string())).unwrap();
+        let first = tokio::time::timeout(Duration::from_secs(1), stream.next()).await;
+        assert_eq!(first.unwrap().unwrap().unwrap(), "Hello");
+        assert!(std::fs::read_to_string(&path).unwrap_or_default().is_empty());
+
+        sender.send(Ok(", world. ".repeat(10))).unwrap();
+        drop(sender);
+        let rest: Vec<String> = stream.map(|c| c.unwrap()).collect().await;
+        assert_eq!(rest.len(), 1);
+
+        let log = std::fs::read_to_string(&path).unwrap();
+        let record: Value = serde_json::from_str(log.lines().next().unwrap()).unwrap();
+
+        assert_eq!(record["kind"], "stream");
+        assert_eq!(record["model"], "channel-model");
+        assert_eq!(record["response"]["chunks"], 2);
+        assert_eq!(record["response"]["content_chars"], 95);
+        let content = record["response"]["content"].as_str().unwrap();
+        assert!(content.starts_with("Hello, world."));
+        assert!(content.ends_with("chars truncated]"));
+        let logged_message = record["request"]["messages"][0]["content"].as_str().unwrap();
+        assert_eq!(logged_message, "my key is [REDACTED], be careful");
+    }
+
+    #[tokio::test]
+    async fn test_tool_calls_are_logged() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("requests.jsonl");
+        let call = MockReply::ToolCalls(vec![ToolCall {
+            id: "call_1".into(),
+            name: "shell".into(),
+            arguments: json!({"command": "ls"}),
+        }]);
+        let provider = LoggingProvider::new(
+            MockProvider::queue([call]),
+            LoggingConfig::default().with_jsonl(&path),
+        );
+        let tools = [ToolDefinition {
+            name: "shell".into(),
+            description: "run a command".into(),
+            parameters: json!({"type": "object"}),
+        }];
+
+        let provider: &dyn LlmProvider = &provider;
+        let response = provider
+            .as_tool_provider()
+            .unwrap()
+            .chat_with_tools(&[Message::user("list files")], &tools, &GenerateOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(response.tool_calls[0].name, "shell");
+
+        let log = std::fs::read_to_string(&path).unwrap();
+        let record: Value = serde_json::from_str(log.lines().next().unwrap()).unwrap();
+        assert_eq!(record["kind"], "tools");
+        assert_eq!(record["request"]["tools"], json!(["shell"]));
+        assert_eq!(record["response"]["tool_calls"][0]["name"], "shell");
+        assert_eq!(record["response"]["tool_calls"][0]["arguments"], r#"{"command":"ls"}"#);
+
+        // Providers without tool support stay without it
+        let (_sender, receiver) = mpsc::unbounded_channel();
+        let plain = LoggingProvider::new(
+            ChannelProvider { receiver: Mutex::new(Some(receiver)) },
+            LoggingConfig::default(),
+        );
+        assert!(plain.as_tool_provider().is_none());
+    }
+
+    #[test]
+    fn test_redact_secrets() {
+        assert_eq!(
+            redact_secrets("Authorization: Bearer abc.def-123 ok"),
+            "Authorization: Bearer [REDACTED] ok"
+        );
+        assert_eq!(redact_secrets("AIzaSyA1234567890abcdefghij"), "[REDACTED]");
+        // Short or mid-word matches are left alone
+        assert_eq!(redact_secrets("task-sk-1 and sk-short"), "task-sk-1 and sk-short");
+    }
+}
@@ -25,6 +25,10 @@ pub struct Message {
     /// Name (for tool calls)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
+    /// Ask the provider to cache the prompt up to and including this message
+    /// (Anthropic `cache_control`). Ignored by providers without prompt caching.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cacheable: bool,
 }
 
 impl Message {
@@ -35,6 +39,7 @@ impl Message {
             content: content.into(),
             tool_call_id: None,
             name: None,
+            cacheable: false,
         }
     }
 
@@ -45,6 +50,7 @@ impl Message {
             content: content.into(),
             tool_call_id: None,
             name: None,
+            cacheable: false,
         }
     }
 
@@ -55,6 +61,7 @@ impl Message {
             content: content.into(),
             tool_call_id: None,
             name: None,
+            cacheable: false,
         }
     }
 
@@ -65,6 +72,13 @@ impl Message {
             content: content.into(),
             tool_call_id: Some(tool_call_id.into()),
             name: None,
+            cacheable: false,
         }
     }
+    /// Mark this message as a prompt-cache breakpoint. Best placed on large,
+    /// stable prefixes such as the system prompt.
+    pub fn cacheable(mut self) -> Self {
+        self.cacheable = true;
+        self
+    }
 }
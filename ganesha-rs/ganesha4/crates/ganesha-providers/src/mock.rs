@@ -0,0 +1,486 @@
+//! # Mock Provider
+//!
+//! A scripted, in-process provider for deterministic tests. Responses come
+//! either from a queue (consumed in order) or from a list of matchers over
+//! the incoming messages; every call is recorded so tests can assert on it.
+//!
+//! ```
+//! use ganesha_providers::{LlmProvider, MockProvider};
+//!
+//! # tokio_test::block_on(async {
+//! let mock = MockProvider::new()
+//!     .on("disk usage", r#"{"actions":[{"command":"df -h"}]}"#)
+//!     .on_regex(r"(?i)^hello", "hi there");
+//!
+//! assert_eq!(mock.generate("system", "Hello!").await.unwrap(), "hi there");
+//! assert_eq!(mock.call_count(), 1);
+//! # });
+//! ```
+
+use crate::{
+    GenerateOptions, LlmProvider, Message, MessageRole, ModelInfo, ModelTier,
+    ProviderError, Response, Result, StreamingProvider, ToolCall, ToolDefinition,
+    ToolProvider, ToolResponse, Usage,
+};
+use async_trait::async_trait;
+use futures::Stream;
+use regex::Regex;
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// What a scripted call returns
+#[derive(Debug, Clone)]
+pub enum MockReply {
+    /// Plain text content
+    Text(String),
+    /// Tool calls (text content is empty for `chat`)
+    ToolCalls(Vec<ToolCall>),
+    /// Fail the call with an `ApiError` carrying this message
+    Error(String),
+    /// Fail the call with this error (HTTP and serde errors are replayed
+    /// as `InvalidResponse`, since they cannot be cloned)
+    Fail(Arc<ProviderError>),
+    /// Give the inner reply after a pause
+    Delayed(Duration, Box<MockReply>),
+}
+
+impl MockReply {
+    /// `reply`, after waiting `delay`
+    pub fn after(delay: Duration, reply: impl Into<MockReply>) -> Self {
+        Self::Delayed(delay, Box::new(reply.into()))
+    }
+}
+
+impl From<ProviderError> for MockReply {
+    fn from(error: ProviderError) -> Self {
+        Self::Fail(Arc::new(error))
+    }
+}
+
+impl From<&str> for MockReply {
+    fn from(text: &str) -> Self {
+        Self::Text(text.to_string())
+    }
+}
+
+impl From<String> for MockReply {
+    fn from(text: String) -> Self {
+        Self::Text(text)
+    }
+}
+
+impl From<ToolCall> for MockReply {
+    fn from(call: ToolCall) -> Self {
+        Self::ToolCalls(vec![call])
+    }
+}
+
+/// Matches a call by the content of its messages
+#[derive(Debug, Clone)]
+pub enum MockMatcher {
+    /// Any message contains this substring
+    Contains(String),
+    /// Any message matches this regex
+    Regex(Regex),
+}
+
+impl MockMatcher {
+    fn matches(&self, messages: &[Message]) -> bool {
+        messages.iter().any(|m| match self {
+            Self::Contains(needle) => m.content.contains(needle.as_str()),
+            Self::Regex(re) => re.is_match(&m.content),
+        })
+    }
+}
+
+/// One recorded call to the mock
+#[derive(Debug, Clone)]
+pub struct MockCall {
+    /// Messages as sent, with `options.system` prepended if set
+    pub messages: Vec<Message>,
+    /// Model requested in the options, if any
+    pub model: Option<String>,
+    /// Names of the tools offered (empty for `chat`/`stream`)
+    pub tools: Vec<String>,
+}
+
+impl MockCall {
+    /// Content of the last user message
+    pub fn last_user_message(&self) -> Option<&str> {
+        self.messages
+            .iter()
+            .rev()
+            .find(|m| m.role == MessageRole::User)
+            .map(|m| m.content.as_str())
+    }
+
+    /// Whether any message contains `text`
+    pub fn contains(&self, text: &str) -> bool {
+        self.messages.iter().any(|m| m.content.contains(text))
+    }
+}
+
+enum Script {
+    Queue(VecDeque<MockReply>),
+    Map(Vec<(MockMatcher, MockReply)>),
+}
+
+/// Scripted provider implementing `LlmProvider`, `StreamingProvider` and
+/// `ToolProvider` without any network access
+pub struct MockProvider {
+    name: String,
+    script: Mutex<Script>,
+    fallback: Option<MockReply>,
+    usage: Usage,
+    calls: Mutex<Vec<MockCall>>,
+}
+
+impl MockProvider {
+    /// Map mode: replies are chosen by the first matcher that fits the
+    /// incoming messages and can be matched any number of times
+    pub fn new() -> Self {
+        Self::with_script(Script::Map(Vec::new()))
+    }
+
+    /// Queue mode: each call consumes the next reply, in order
+    pub fn queue<R: Into<MockReply>>(replies: impl IntoIterator<Item = R>) -> Self {
+        Self::with_script(Script::Queue(replies.into_iter().map(Into::into).collect()))
+    }
+
+    fn with_script(script: Script) -> Self {
+        Self {
+            name: "mock".to_string(),
+            script: Mutex::new(script),
+            fallback: None,
+            usage: Usage::default(),
+            calls: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Set the provider name
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Reply used when nothing matches (map mode) or the queue is exhausted.
+    /// Without one, those calls fail with `InvalidResponse`.
+    pub fn with_fallback(mut self, reply: impl Into<MockReply>) -> Self {
+        self.fallback = Some(reply.into());
+        self
+    }
+
+    /// Token usage reported with every `chat` reply (all zero by default)
+    pub fn with_usage(mut self, usage: Usage) -> Self {
+        self.usage = usage;
+        self
+    }
+
+    /// Map mode: reply when any message contains `needle`
+    pub fn on(self, needle: impl Into<String>, reply: impl Into<MockReply>) -> Self {
+        self.on_match(MockMatcher::Contains(needle.into()), reply)
+    }
+
+    /// Map mode: reply when any message matches `pattern`.
+    /// Panics on an invalid pattern, as befits test setup.
+    pub fn on_regex(self, pattern: &str, reply: impl Into<MockReply>) -> Self {
+        let re = Regex::new(pattern).unwrap_or_else(|e| panic!("invalid mock pattern {:?}: {}", pattern, e));
+        self.on_match(MockMatcher::Regex(re), reply)
+    }
+
+    /// Map mode: reply when `matcher` fits. Switches a queue mock to map mode.
+    pub fn on_match(self, matcher: MockMatcher, reply: impl Into<MockReply>) -> Self {
+        {
+            let mut script = self.script.lock().unwrap();
+            if let Script::Map(rules) = &mut *script {
+                rules.push((matcher, reply.into()));
+            } else {
+                *script = Script::Map(vec![(matcher, reply.into())]);
+            }
+        }
+        self
+    }
+
+    /// Queue mode: append a reply
+    pub fn push(&self, reply: impl Into<MockReply>) {
+        let mut script = self.script.lock().unwrap();
+        match &mut *script {
+            Script::Queue(queue) => queue.push_back(reply.into()),
+            Script::Map(_) => *script = Script::Queue(VecDeque::from([reply.into()])),
+        }
+    }
+
+    /// All calls so far, oldest first
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    /// Number of calls so far
+    pub fn call_count(&self) -> usize {
+        self.calls.lock().unwrap().len()
+    }
+
+    /// Queued replies not yet consumed (0 in map mode)
+    pub fn remaining(&self) -> usize {
+        match &*self.script.lock().unwrap() {
+            Script::Queue(queue) => queue.len(),
+            Script::Map(_) => 0,
+        }
+    }
+
+    /// Record the call and pick its reply, waiting out any delay
+    async fn respond(&self, messages: &[Message], options: &GenerateOptions, tools: &[ToolDefinition]) -> Result<MockReply> {
+        let mut sent = Vec::with_capacity(messages.len() + 1);
+        if let Some(system) = &options.system {
+            sent.push(Message::system(system.clone()));
+        }
+        sent.extend(messages.iter().cloned());
+
+        let reply = match &mut *self.script.lock().unwrap() {
+            Script::Queue(queue) => queue.pop_front(),
+            Script::Map(rules) => rules
+                .iter()
+                .find(|(matcher, _)| matcher.matches(&sent))
+                .map(|(_, reply)| reply.clone()),
+        };
+
+        self.calls.lock().unwrap().push(MockCall {
+            messages: sent,
+            model: options.model.clone(),
+            tools: tools.iter().map(|t| t.name.clone()).collect(),
+        });
+
+        let mut reply = reply.or_else(|| self.fallback.clone());
+        while let Some(MockReply::Delayed(delay, inner)) = reply {
+            tokio::time::sleep(delay).await;
+            reply = Some(*inner);
+        }
+
+        match reply {
+            Some(MockReply::Error(message)) => Err(ProviderError::ApiError { status: 500, message }),
+            Some(MockReply::Fail(error)) => Err(replay_error(&error)),
+            Some(reply) => Ok(reply),
+            None => Err(ProviderError::InvalidResponse(
+                "no scripted mock response for this call".to_string(),
+            )),
+        }
+    }
+
+    fn model_name(&self, options: &GenerateOptions) -> String {
+        options.model.clone().unwrap_or_else(|| "mock-model".to_string())
+    }
+}
+
+/// A fresh copy of a scripted error
+fn replay_error(error: &ProviderError) -> ProviderError {
+    match error {
+        ProviderError::ApiError { status, message } => ProviderError::ApiError {
+            status: *status,
+            message: message.clone(),
+        },
+        ProviderError::RateLimited { retry_after } => ProviderError::RateLimited {
+            retry_after: *retry_after,
+        },
+        ProviderError::InvalidResponse(m) => ProviderError::InvalidResponse(m.clone()),
+        ProviderError::ModelNotFound(m) => ProviderError::ModelNotFound(m.clone()),
+        ProviderError::AuthError(m) => ProviderError::AuthError(m.clone()),
+        ProviderError::Unavailable(m) => ProviderError::Unavailable(m.clone()),
+        ProviderError::Timeout(secs) => ProviderError::Timeout(*secs),
+        ProviderError::StreamError(m) => ProviderError::StreamError(m.clone()),
+        ProviderError::ConfigError(m) => ProviderError::ConfigError(m.clone()),
+        ProviderError::HttpError(_) | ProviderError::SerdeError(_) => {
+            ProviderError::InvalidResponse(error.to_string())
+        }
+    }
+}
+
+impl Default for MockProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl LlmProvider for MockProvider {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn is_available(&self) -> bool {
+        true
+    }
+
+    fn default_model(&self) -> &str {
+        "mock-model"
+    }
+
+    fn model_tier(&self, _model: &str) -> ModelTier {
+        ModelTier::Exceptional
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        Ok(vec![ModelInfo {
+            id: "mock-model".to_string(),
+            name: "Mock Model".to_string(),
+            provider: self.name.clone(),
+            tier: ModelTier::Exceptional,
+            context_length: None,
+            supports_vision: false,
+            supports_tools: true,
+        }])
+    }
+
+    async fn chat(&self, messages: &[Message], options: &GenerateOptions) -> Result<Response> {
+        let content = match self.respond(messages, options, &[]).await? {
+            MockReply::Text(text) => text,
+            _ => String::new(),
+        };
+        Ok(Response {
+            content,
+            model: self.model_name(options),
+            finish_reason: Some("stop".to_string()),
+            usage: Some(self.usage.clone()),
+        })
+    }
+
+    fn as_tool_provider(&self) -> Option<&dyn ToolProvider> {
+        Some(self)
+    }
+}
+
+#[async_trait]
+impl StreamingProvider for MockProvider {
+    /// Streams text replies word by word (whitespace kept with each chunk)
+    async fn stream(
+        &self,
+        messages: &[Message],
+        options: &GenerateOptions,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<String>> + Send>>> {
+        let text = match self.respond(messages, options, &[]).await? {
+            MockReply::Text(text) => text,
+            _ => String::new(),
+        };
+        let chunks: Vec<Result<String>> = text.split_inclusive(' ').map(|c| Ok(c.to_string())).collect();
+        Ok(Box::pin(futures::stream::iter(chunks)))
+    }
+}
+
+#[async_trait]
+impl ToolProvider for MockProvider {
+    async fn chat_with_tools(
+        &self,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+        options: &GenerateOptions,
+    ) -> Result<ToolResponse> {
+        Ok(match self.respond(messages, options, tools).await? {
+            MockReply::ToolCalls(tool_calls) => ToolResponse {
+                content: None,
+                tool_calls,
+                finish_reason: Some("tool_calls".to_string()),
+            },
+            MockReply::Text(text) => ToolResponse {
+                content: Some(text),
+                tool_calls: Vec::new(),
+                finish_reason: Some("stop".to_string()),
+            },
+            MockReply::Error(_) | MockReply::Fail(_) | MockReply::Delayed(..) => {
+                unreachable!("errors and delays are resolved by respond")
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    #[tokio::test]
+    async fn test_queue_mode_consumes_in_order() {
+        let mock = MockProvider::queue(["first", "second"]);
+
+        assert_eq!(mock.generate("sys", "a").await.unwrap(), "first");
+        assert_eq!(mock.generate("sys", "b").await.unwrap(), "second");
+        assert!(matches!(mock.generate("sys", "c").await, Err(ProviderError::InvalidResponse(_))));
+
+        let calls = mock.calls();
+        assert_eq!(calls.len(), 3);
+        assert_eq!(calls[1].last_user_message(), Some("b"));
+        assert_eq!(mock.remaining(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_usage_is_reported_on_every_reply() {
+        let usage = Usage { prompt_tokens: 30, completion_tokens: 12, total_tokens: 42, ..Default::default() };
+        let mock = MockProvider::new().with_fallback("ok").with_usage(usage);
+
+        for _ in 0..2 {
+            let response = mock.chat(&[Message::user("hi")], &GenerateOptions::default()).await.unwrap();
+            assert_eq!(response.usage.unwrap().total_tokens, 42);
+        }
+        assert_eq!(mock.call_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_map_mode_matches_by_prompt() {
+        let mock = MockProvider::new()
+            .on("weather", "sunny")
+            .on_regex(r"\bdelete\b", MockReply::Error("refused".into()))
+            .on("login", ProviderError::AuthError("bad key".into()))
+            .on("slow", MockReply::after(Duration::from_millis(20), "done"))
+            .with_fallback("default");
+
+        assert_eq!(mock.generate("sys", "what's the weather?").await.unwrap(), "sunny");
+        assert_eq!(mock.generate("sys", "what's the weather now?").await.unwrap(), "sunny");
+        assert!(matches!(
+            mock.generate("sys", "delete everything").await,
+            Err(ProviderError::ApiError { status: 500, .. })
+        ));
+        assert_eq!(mock.generate("sys", "anything else").await.unwrap(), "default");
+        for _ in 0..2 {
+            assert!(matches!(mock.generate("sys", "login").await, Err(ProviderError::AuthError(_))));
+        }
+        let started = std::time::Instant::now();
+        assert_eq!(mock.generate("sys", "slow please").await.unwrap(), "done");
+        assert!(started.elapsed() >= Duration::from_millis(20));
+        assert_eq!(mock.call_count(), 7);
+        assert!(mock.calls()[0].contains("sys"));
+    }
+
+    #[tokio::test]
+    async fn test_stream_and_tool_calls() {
+        let call = ToolCall {
+            id: "call_1".into(),
+            name: "shell".into(),
+            arguments: serde_json::json!({"command": "ls"}),
+        };
+        let mock = MockProvider::queue([MockReply::from("hello streaming world"), MockReply::from(call)]);
+
+        let chunks: Vec<String> = mock
+            .stream(&[Message::user("hi")], &GenerateOptions::default())
+            .await
+            .unwrap()
+            .map(|c| c.unwrap())
+            .collect()
+            .await;
+        assert_eq!(chunks, vec!["hello ", "streaming ", "world"]);
+
+        let tools = vec![ToolDefinition {
+            name: "shell".into(),
+            description: "run a command".into(),
+            parameters: serde_json::json!({"type": "object"}),
+        }];
+        let provider: &dyn LlmProvider = &mock;
+        let response = provider
+            .as_tool_provider()
+            .unwrap()
+            .chat_with_tools(&[Message::user("list")], &tools, &GenerateOptions::default())
+            .await
+            .unwrap();
+        assert_eq!(response.tool_calls[0].name, "shell");
+        assert_eq!(mock.calls()[1].tools, vec!["shell"]);
+    }
+}
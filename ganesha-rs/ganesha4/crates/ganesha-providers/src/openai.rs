@@ -4,7 +4,8 @@
 
 use crate::{
     GenerateOptions, LlmProvider, Message, MessageRole, ModelInfo, ModelTier,
-    ProviderError, Response, Result, Usage, get_model_tier,
+    ProviderConfig, ProviderError, Response, Result, ToolCall, ToolDefinition, ToolProvider,
+    ToolResponse, Usage, get_model_tier, RESPONSE_SCHEMA_NAME,
 };
 use async_trait::async_trait;
 use reqwest::Client;
@@ -40,6 +41,12 @@ impl OpenAiProvider {
         }
     }
 
+    /// Apply per-provider HTTP settings (extra headers, proxy)
+    pub fn with_config(mut self, config: &ProviderConfig) -> Result<Self> {
+        self.client = config.build_client(None)?;
+        Ok(self)
+    }
+
     /// Set the default model
     pub fn with_default_model(mut self, model: impl Into<String>) -> Self {
         self.default_model = model.into();
@@ -63,6 +70,72 @@ impl OpenAiProvider {
             })
             .collect()
     }
+
+    /// Build the chat completions request body
+    fn build_request(&self, messages: &[Message], options: &GenerateOptions) -> OpenAiChatRequest {
+        let mut request = OpenAiChatRequest {
+            model: options.model.clone().unwrap_or_else(|| self.default_model.clone()),
+            messages: self.convert_messages(messages),
+            temperature: options.temperature,
+            max_tokens: options.max_tokens,
+            stop: options.stop.clone(),
+            response_format: ResponseFormat::from_options(options),
+            tools: None,
+        };
+
+        // Add system prompt if provided
+        if let Some(system) = &options.system {
+            request.messages.insert(
+                0,
+                OpenAiMessage {
+                    role: "system".to_string(),
+                    content: system.clone(),
+                    tool_call_id: None,
+                    name: None,
+                },
+            );
+        }
+
+        request
+    }
+
+    /// POST a chat completions request and return its first choice
+    async fn send(&self, request: &OpenAiChatRequest) -> Result<(OpenAiChoice, Option<OpenAiUsage>)> {
+        let url = format!("{}/chat/completions", self.base_url);
+        let response = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .header("Content-Type", "application/json")
+            .json(request)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let body = response.text().await.unwrap_or_default();
+
+            // Check for rate limiting
+            if status == 429 {
+                return Err(ProviderError::RateLimited { retry_after: None });
+            }
+
+            return Err(ProviderError::ApiError {
+                status,
+                message: body,
+            });
+        }
+
+        let chat_response: OpenAiChatResponse = response.json().await?;
+
+        let choice = chat_response
+            .choices
+            .into_iter()
+            .next()
+            .ok_or_else(|| ProviderError::InvalidResponse("No choices in response".to_string()))?;
+
+        Ok((choice, chat_response.usage))
+    }
 }
 
 #[async_trait]
@@ -136,86 +209,68 @@ impl LlmProvider for OpenAiProvider {
     }
 
     async fn chat(&self, messages: &[Message], options: &GenerateOptions) -> Result<Response> {
-        let model = options
-            .model
-            .as_ref()
-            .unwrap_or(&self.default_model)
-            .clone();
+        let request = self.build_request(messages, options);
+        debug!("OpenAI chat with model: {}", request.model);
+        let model = request.model.clone();
 
-        debug!("OpenAI chat with model: {}", model);
-
-        let mut request = OpenAiChatRequest {
-            model: model.clone(),
-            messages: self.convert_messages(messages),
-            temperature: options.temperature,
-            max_tokens: options.max_tokens,
-            stop: options.stop.clone(),
-            response_format: if options.json_mode {
-                Some(ResponseFormat {
-                    r#type: "json_object".to_string(),
-                })
-            } else {
-                None
-            },
-        };
-
-        // Add system prompt if provided
-        if let Some(system) = &options.system {
-            request.messages.insert(
-                0,
-                OpenAiMessage {
-                    role: "system".to_string(),
-                    content: system.clone(),
-                    tool_call_id: None,
-                    name: None,
-                },
-            );
-        }
-
-        let url = format!("{}/chat/completions", self.base_url);
-        let response = self
-            .client
-            .post(&url)
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            let status = response.status().as_u16();
-            let body = response.text().await.unwrap_or_default();
-
-            // Check for rate limiting
-            if status == 429 {
-                return Err(ProviderError::RateLimited { retry_after: None });
-            }
-
-            return Err(ProviderError::ApiError {
-                status,
-                message: body,
-            });
-        }
-
-        let chat_response: OpenAiChatResponse = response.json().await?;
-
-        let choice = chat_response
-            .choices
-            .into_iter()
-            .next()
-            .ok_or_else(|| ProviderError::InvalidResponse("No choices in response".to_string()))?;
+        let (choice, usage) = self.send(&request).await?;
 
         Ok(Response {
             content: choice.message.content.unwrap_or_default(),
             model,
             finish_reason: choice.finish_reason,
-            usage: chat_response.usage.map(|u| Usage {
+            usage: usage.map(|u| Usage {
                 prompt_tokens: u.prompt_tokens,
                 completion_tokens: u.completion_tokens,
                 total_tokens: u.total_tokens,
+                ..Default::default()
             }),
         })
     }
+
+    fn as_tool_provider(&self) -> Option<&dyn ToolProvider> {
+        Some(self)
+    }
+}
+
+#[async_trait]
+impl ToolProvider for OpenAiProvider {
+    async fn chat_with_tools(
+        &self,
+        messages: &[Message],
+        tools: &[ToolDefinition],
+        options: &GenerateOptions,
+    ) -> Result<ToolResponse> {
+        let mut request = self.build_request(messages, options);
+        debug!("OpenAI tool call with model: {} ({} tools)", request.model, tools.len());
+        if !tools.is_empty() {
+            request.tools = Some(tools.iter().map(OpenAiTool::from).collect());
+        }
+
+        let (choice, _) = self.send(&request).await?;
+
+        let tool_calls = choice
+            .message
+            .tool_calls
+            .into_iter()
+            .map(|call| {
+                // Arguments arrive as a JSON string; keep malformed ones as a plain string
+                let arguments = serde_json::from_str(&call.function.arguments)
+                    .unwrap_or(serde_json::Value::String(call.function.arguments));
+                ToolCall {
+                    id: call.id,
+                    name: call.function.name,
+                    arguments,
+                }
+            })
+            .collect();
+
+        Ok(ToolResponse {
+            content: choice.message.content.filter(|c| !c.is_empty()),
+            tool_calls,
+            finish_reason: choice.finish_reason,
+        })
+    }
 }
 
 // OpenAI API types
@@ -232,11 +287,70 @@ struct OpenAiChatRequest {
     stop: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     response_format: Option<ResponseFormat>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<Vec<OpenAiTool>>,
+}
+
+#[derive(Debug, Serialize)]
+struct OpenAiTool {
+    r#type: &'static str,
+    function: OpenAiFunction,
+}
+
+#[derive(Debug, Serialize)]
+struct OpenAiFunction {
+    name: String,
+    description: String,
+    parameters: serde_json::Value,
+}
+
+impl From<&ToolDefinition> for OpenAiTool {
+    fn from(tool: &ToolDefinition) -> Self {
+        Self {
+            r#type: "function",
+            function: OpenAiFunction {
+                name: tool.name.clone(),
+                description: tool.description.clone(),
+                parameters: tool.parameters.clone(),
+            },
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
 struct ResponseFormat {
     r#type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    json_schema: Option<JsonSchemaFormat>,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonSchemaFormat {
+    name: String,
+    schema: serde_json::Value,
+    strict: bool,
+}
+
+impl ResponseFormat {
+    fn from_options(options: &GenerateOptions) -> Option<Self> {
+        if let Some(schema) = &options.response_schema {
+            Some(Self {
+                r#type: "json_schema".to_string(),
+                json_schema: Some(JsonSchemaFormat {
+                    name: RESPONSE_SCHEMA_NAME.to_string(),
+                    schema: schema.clone(),
+                    strict: options.strict_schema,
+                }),
+            })
+        } else if options.json_mode {
+            Some(Self {
+                r#type: "json_object".to_string(),
+                json_schema: None,
+            })
+        } else {
+            None
+        }
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -264,6 +378,20 @@ struct OpenAiChoice {
 #[derive(Debug, Deserialize)]
 struct OpenAiResponseMessage {
     content: Option<String>,
+    #[serde(default)]
+    tool_calls: Vec<OpenAiToolCall>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiToolCall {
+    id: String,
+    function: OpenAiFunctionCall,
+}
+
+#[derive(Debug, Deserialize)]
+struct OpenAiFunctionCall {
+    name: String,
+    arguments: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -282,3 +410,63 @@ struct OpenAiModelsResponse {
 struct OpenAiModel {
     id: String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_response_format_strict_is_opt_in() {
+        let schema = serde_json::json!({"type": "object", "properties": {"answer": {"type": "string"}}});
+        let options = GenerateOptions {
+            response_schema: Some(schema.clone()),
+            ..Default::default()
+        };
+        let format = serde_json::to_value(ResponseFormat::from_options(&options)).unwrap();
+        assert_eq!(format["type"], "json_schema");
+        assert_eq!(format["json_schema"]["schema"], schema);
+        assert_eq!(format["json_schema"]["strict"], false);
+
+        let options = GenerateOptions {
+            strict_schema: true,
+            ..options
+        };
+        let format = serde_json::to_value(ResponseFormat::from_options(&options)).unwrap();
+        assert_eq!(format["json_schema"]["strict"], true);
+    }
+
+    #[tokio::test]
+    async fn test_chat_with_tools_reads_tool_calls() {
+        let mut server = mockito::Server::new_async().await;
+        let mock = server
+            .mock("POST", "/chat/completions")
+            .match_body(mockito::Matcher::PartialJson(serde_json::json!({
+                "tools": [{"type": "function", "function": {"name": "shell"}}]
+            })))
+            .with_body(r#"{"choices":[{"message":{"role":"assistant","content":null,
+                "tool_calls":[{"id":"call_1","type":"function",
+                "function":{"name":"shell","arguments":"{\"command\":\"ls\"}"}}]},
+                "finish_reason":"tool_calls"}]}"#)
+            .create_async()
+            .await;
+
+        let provider = OpenAiProvider::with_base_url("sk-test", server.url());
+        let tools = [ToolDefinition {
+            name: "shell".into(),
+            description: "run a command".into(),
+            parameters: serde_json::json!({"type": "object"}),
+        }];
+        let response = (&provider as &dyn LlmProvider)
+            .as_tool_provider()
+            .unwrap()
+            .chat_with_tools(&[Message::user("list files")], &tools, &GenerateOptions::default())
+            .await
+            .unwrap();
+
+        mock.assert_async().await;
+        assert_eq!(response.content, None);
+        assert_eq!(response.finish_reason.as_deref(), Some("tool_calls"));
+        assert_eq!(response.tool_calls[0].id, "call_1");
+        assert_eq!(response.tool_calls[0].arguments, serde_json::json!({"command": "ls"}));
+    }
+}
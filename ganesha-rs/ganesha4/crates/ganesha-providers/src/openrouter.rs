@@ -2,25 +2,41 @@
 //!
 //! Implementation for OpenRouter aggregator API.
 //! OpenRouter provides access to many models through a single API.
+//!
+//! Instead of a fixed model, the provider can be asked for a tier
+//! (`with_tier`). It then uses the cheapest listed model in that tier,
+//! moving on to the next cheapest if OpenRouter no longer serves it.
 
 use crate::{
     GenerateOptions, LlmProvider, Message, MessageRole, ModelInfo, ModelTier,
-    ProviderError, Response, Result, Usage, get_model_tier,
+    ProviderConfig, ProviderError, Response, Result, Usage, get_model_tier,
+    validate_response,
 };
 use async_trait::async_trait;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, LazyLock, Mutex};
 use tracing::debug;
 
 const OPENROUTER_API_URL: &str = "https://openrouter.ai/api/v1";
 
+/// Model lists fetched this process, keyed by API base URL
+static MODEL_LIST_CACHE: LazyLock<tokio::sync::Mutex<HashMap<String, Arc<Vec<OpenRouterModel>>>>> =
+    LazyLock::new(|| tokio::sync::Mutex::new(HashMap::new()));
+
 /// OpenRouter provider - aggregates many LLM providers
 pub struct OpenRouterProvider {
     client: Client,
     api_key: String,
+    base_url: String,
     default_model: String,
     site_url: Option<String>,
     site_name: Option<String>,
+    /// Models of the requested tier, cheapest first (see `with_tier`)
+    tier_candidates: Vec<String>,
+    /// Tier candidates OpenRouter reported as missing
+    missing_models: Mutex<HashSet<String>>,
 }
 
 impl OpenRouterProvider {
@@ -29,10 +45,84 @@ impl OpenRouterProvider {
         Self {
             client: Client::new(),
             api_key: api_key.into(),
+            base_url: OPENROUTER_API_URL.to_string(),
             default_model: "anthropic/claude-3.5-sonnet".to_string(),
             site_url: None,
             site_name: Some("Ganesha".to_string()),
+            tier_candidates: Vec::new(),
+            missing_models: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Use a different API base URL (e.g. a gateway in front of OpenRouter)
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Pick the default model by tier instead of by name.
+    ///
+    /// Fetches the model list (once per process), keeps the models whose
+    /// `get_model_tier` matches `tier`, and uses the cheapest. When a chat
+    /// request without an explicit model gets a 404 for it, the next
+    /// cheapest candidate is tried.
+    pub async fn with_tier(mut self, tier: ModelTier) -> Result<Self> {
+        let models = self.fetch_models().await?;
+
+        let mut candidates: Vec<(f64, &OpenRouterModel)> = models
+            .iter()
+            .filter(|m| self.get_openrouter_tier(&m.id) == tier)
+            .filter_map(|m| m.price().map(|price| (price, m)))
+            .collect();
+        candidates.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        if candidates.is_empty() {
+            return Err(ProviderError::ModelNotFound(format!(
+                "no OpenRouter model in tier {:?}",
+                tier
+            )));
         }
+
+        self.tier_candidates = candidates.into_iter().map(|(_, m)| m.id.clone()).collect();
+        self.default_model = self.tier_candidates[0].clone();
+        debug!("OpenRouter tier {:?} candidates: {:?}", tier, self.tier_candidates);
+        Ok(self)
+    }
+
+    /// Raw model list, fetched once per process per base URL
+    async fn fetch_models(&self) -> Result<Arc<Vec<OpenRouterModel>>> {
+        let mut cache = MODEL_LIST_CACHE.lock().await;
+        if let Some(models) = cache.get(&self.base_url) {
+            return Ok(models.clone());
+        }
+
+        let url = format!("{}/models", self.base_url);
+        let response = self
+            .client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", self.api_key))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let body = response.text().await.unwrap_or_default();
+            return Err(ProviderError::ApiError {
+                status,
+                message: body,
+            });
+        }
+
+        let models_response: OpenRouterModelsResponse = response.json().await?;
+        let models = Arc::new(models_response.data);
+        cache.insert(self.base_url.clone(), models.clone());
+        Ok(models)
+    }
+
+    /// Apply per-provider HTTP settings (extra headers, proxy)
+    pub fn with_config(mut self, config: &ProviderConfig) -> Result<Self> {
+        self.client = config.build_client(None)?;
+        Ok(self)
     }
 
     /// Set the default model
@@ -91,7 +181,7 @@ impl LlmProvider for OpenRouterProvider {
         }
 
         // Try to list models
-        let url = format!("{}/models", OPENROUTER_API_URL);
+        let url = format!("{}/models", self.base_url);
         match self
             .client
             .get(&url)
@@ -112,34 +202,22 @@ impl LlmProvider for OpenRouterProvider {
         self.get_openrouter_tier(model)
     }
 
+    /// Models cheapest first; those without a usable price come last
     async fn list_models(&self) -> Result<Vec<ModelInfo>> {
-        let url = format!("{}/models", OPENROUTER_API_URL);
-        let response = self
-            .client
-            .get(&url)
-            .header("Authorization", format!("Bearer {}", self.api_key))
-            .send()
-            .await?;
-
-        if !response.status().is_success() {
-            let status = response.status().as_u16();
-            let body = response.text().await.unwrap_or_default();
-            return Err(ProviderError::ApiError {
-                status,
-                message: body,
-            });
-        }
-
-        let models_response: OpenRouterModelsResponse = response.json().await?;
-
-        Ok(models_response
-            .data
+        let models = self.fetch_models().await?;
+        let mut models: Vec<&OpenRouterModel> = models.iter().collect();
+        models.sort_by(|a, b| match (a.price(), b.price()) {
+            (Some(a), Some(b)) => a.total_cmp(&b),
+            (a, b) => b.is_some().cmp(&a.is_some()),
+        });
+
+        Ok(models
             .into_iter()
             .map(|m| {
                 let tier = self.get_openrouter_tier(&m.id);
                 ModelInfo {
                     id: m.id.clone(),
-                    name: m.name.unwrap_or(m.id.clone()),
+                    name: m.name.clone().unwrap_or(m.id.clone()),
                     provider: "openrouter".to_string(),
                     tier,
                     context_length: Some(m.context_length.unwrap_or(4096)),
@@ -154,12 +232,42 @@ impl LlmProvider for OpenRouterProvider {
     }
 
     async fn chat(&self, messages: &[Message], options: &GenerateOptions) -> Result<Response> {
-        let model = options
-            .model
-            .as_ref()
-            .unwrap_or(&self.default_model)
-            .clone();
+        if let Some(model) = &options.model {
+            return self.chat_with_model(model.clone(), messages, options).await;
+        }
+        if self.tier_candidates.is_empty() {
+            return self.chat_with_model(self.default_model.clone(), messages, options).await;
+        }
+
+        let mut last_error = None;
+        for model in &self.tier_candidates {
+            if self.missing_models.lock().unwrap().contains(model) {
+                continue;
+            }
+            match self.chat_with_model(model.clone(), messages, options).await {
+                Err(ProviderError::ModelNotFound(_)) => {
+                    debug!("OpenRouter model {} not found, trying next candidate", model);
+                    self.missing_models.lock().unwrap().insert(model.clone());
+                    last_error = Some(ProviderError::ModelNotFound(model.clone()));
+                }
+                result => return result,
+            }
+        }
 
+        Err(last_error.unwrap_or_else(|| {
+            ProviderError::ModelNotFound("all tier candidates are unavailable".to_string())
+        }))
+    }
+}
+
+impl OpenRouterProvider {
+    /// Send one chat request to a specific model
+    async fn chat_with_model(
+        &self,
+        model: String,
+        messages: &[Message],
+        options: &GenerateOptions,
+    ) -> Result<Response> {
         debug!("OpenRouter chat with model: {}", model);
 
         let mut request = OpenRouterChatRequest {
@@ -168,7 +276,8 @@ impl LlmProvider for OpenRouterProvider {
             temperature: options.temperature,
             max_tokens: options.max_tokens,
             stop: options.stop.clone(),
-            response_format: if options.json_mode {
+            // Not every routed model honours json_schema, so ask for JSON and check it here
+            response_format: if options.json_mode || options.response_schema.is_some() {
                 Some(ResponseFormat {
                     r#type: "json_object".to_string(),
                 })
@@ -189,7 +298,7 @@ impl LlmProvider for OpenRouterProvider {
             );
         }
 
-        let url = format!("{}/chat/completions", OPENROUTER_API_URL);
+        let url = format!("{}/chat/completions", self.base_url);
 
         let mut req_builder = self
             .client
@@ -214,6 +323,9 @@ impl LlmProvider for OpenRouterProvider {
             if status == 429 {
                 return Err(ProviderError::RateLimited { retry_after: None });
             }
+            if status == 404 {
+                return Err(ProviderError::ModelNotFound(model));
+            }
 
             return Err(ProviderError::ApiError {
                 status,
@@ -229,14 +341,20 @@ impl LlmProvider for OpenRouterProvider {
             .next()
             .ok_or_else(|| ProviderError::InvalidResponse("No choices in response".to_string()))?;
 
+        let content = choice.message.content.unwrap_or_default();
+        if let Some(schema) = &options.response_schema {
+            validate_response(&content, schema)?;
+        }
+
         Ok(Response {
-            content: choice.message.content.unwrap_or_default(),
+            content,
             model,
             finish_reason: choice.finish_reason,
             usage: chat_response.usage.map(|u| Usage {
                 prompt_tokens: u.prompt_tokens,
                 completion_tokens: u.completion_tokens,
                 total_tokens: u.total_tokens,
+                ..Default::default()
             }),
         })
     }
@@ -306,9 +424,92 @@ struct OpenRouterModel {
     name: Option<String>,
     context_length: Option<u32>,
     architecture: Option<ModelArchitecture>,
+    pricing: Option<ModelPricing>,
+}
+
+impl OpenRouterModel {
+    /// Prompt plus completion price per token, if listed.
+    /// Negative prices mark routers with variable pricing and are skipped.
+    fn price(&self) -> Option<f64> {
+        let pricing = self.pricing.as_ref()?;
+        let prompt: f64 = pricing.prompt.parse().ok()?;
+        let completion: f64 = pricing.completion.parse().ok()?;
+        (prompt >= 0.0 && completion >= 0.0).then_some(prompt + completion)
+    }
+}
+
+/// Per-token prices, as decimal strings in USD
+#[derive(Debug, Deserialize)]
+struct ModelPricing {
+    prompt: String,
+    completion: String,
 }
 
 #[derive(Debug, Deserialize)]
 struct ModelArchitecture {
     modality: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mockito::Matcher;
+
+    #[tokio::test]
+    async fn test_with_tier_picks_cheapest_and_skips_missing() {
+        let mut server = mockito::Server::new_async().await;
+        let models = server
+            .mock("GET", "/models")
+            .with_body(
+                r#"{"data":[
+                    {"id":"openai/gpt-4o","pricing":{"prompt":"0.0000025","completion":"0.00001"}},
+                    {"id":"deepseek/deepseek-v3","pricing":{"prompt":"0.0000003","completion":"0.0000009"}},
+                    {"id":"openai/gpt-4o-mini","pricing":{"prompt":"0.00000015","completion":"0.0000006"}},
+                    {"id":"openrouter/auto","pricing":{"prompt":"-1","completion":"-1"}}
+                ]}"#,
+            )
+            .expect(1)
+            .create_async()
+            .await;
+        let missing = server
+            .mock("POST", "/chat/completions")
+            .match_body(Matcher::PartialJsonString(r#"{"model":"deepseek/deepseek-v3"}"#.into()))
+            .with_status(404)
+            .with_body(r#"{"error":{"message":"No endpoints found"}}"#)
+            .expect(1)
+            .create_async()
+            .await;
+        let served = server
+            .mock("POST", "/chat/completions")
+            .match_body(Matcher::PartialJsonString(r#"{"model":"openai/gpt-4o"}"#.into()))
+            .with_body(r#"{"choices":[{"message":{"content":"hi"},"finish_reason":"stop"}]}"#)
+            .expect(2)
+            .create_async()
+            .await;
+
+        let provider = OpenRouterProvider::new("sk-or-test")
+            .with_base_url(server.url())
+            .with_tier(ModelTier::Exceptional)
+            .await
+            .unwrap();
+        assert_eq!(provider.default_model(), "deepseek/deepseek-v3");
+
+        let messages = [Message::user("hello")];
+        let response = provider.chat(&messages, &GenerateOptions::default()).await.unwrap();
+        assert_eq!(response.model, "openai/gpt-4o");
+
+        // The missing model is remembered rather than retried
+        provider.chat(&messages, &GenerateOptions::default()).await.unwrap();
+        missing.assert_async().await;
+        served.assert_async().await;
+
+        // The model list is only fetched once per process
+        let capable = OpenRouterProvider::new("sk-or-test")
+            .with_base_url(server.url())
+            .with_tier(ModelTier::Capable)
+            .await
+            .unwrap();
+        assert_eq!(capable.default_model(), "openai/gpt-4o-mini");
+        models.assert_async().await;
+    }
+}
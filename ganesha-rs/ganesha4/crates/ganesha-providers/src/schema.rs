@@ -0,0 +1,91 @@
+//! # Structured Output
+//!
+//! Helpers for `GenerateOptions::response_schema`. Providers that can
+//! constrain decoding translate the schema into their own request field;
+//! the rest ask for plain JSON and check the reply with `validate_response`.
+
+use crate::{ProviderError, Result};
+use serde_json::Value;
+
+/// Name given to the schema (or forced tool) in provider requests
+pub const RESPONSE_SCHEMA_NAME: &str = "structured_response";
+
+/// Parse `content` as JSON and check it against `schema`.
+///
+/// A surrounding markdown code fence is tolerated, since models asked for
+/// bare JSON still sometimes wrap it. Returns the parsed value, or
+/// `InvalidResponse` describing the first few violations.
+pub fn validate_response(content: &str, schema: &Value) -> Result<Value> {
+    let json_text = strip_code_fence(content);
+    let value: Value = serde_json::from_str(json_text).map_err(|e| {
+        ProviderError::InvalidResponse(format!("response is not valid JSON: {}", e))
+    })?;
+
+    let validator = jsonschema::validator_for(schema)
+        .map_err(|e| ProviderError::ConfigError(format!("invalid response schema: {}", e)))?;
+
+    let errors: Vec<String> = validator
+        .iter_errors(&value)
+        .take(5)
+        .map(|e| {
+            let path = e.instance_path.to_string();
+            if path.is_empty() {
+                e.to_string()
+            } else {
+                format!("{}: {}", path, e)
+            }
+        })
+        .collect();
+
+    if errors.is_empty() {
+        Ok(value)
+    } else {
+        Err(ProviderError::InvalidResponse(format!(
+            "response does not match schema: {}",
+            errors.join("; ")
+        )))
+    }
+}
+
+fn strip_code_fence(content: &str) -> &str {
+    let trimmed = content.trim();
+    let Some(rest) = trimmed.strip_prefix("```") else {
+        return trimmed;
+    };
+    let body = rest.split_once('\n').map_or("", |(_, body)| body);
+    body.trim_end().strip_suffix("```").unwrap_or(body).trim()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate_response() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "command": {"type": "string"},
+                "risk": {"type": "string", "enum": ["low", "high"]}
+            },
+            "required": ["command"]
+        });
+
+        let value = validate_response(r#"{"command":"ls","risk":"low"}"#, &schema).unwrap();
+        assert_eq!(value["command"], "ls");
+
+        let fenced = "```json\n{\"command\":\"pwd\"}\n```";
+        assert_eq!(validate_response(fenced, &schema).unwrap()["command"], "pwd");
+
+        let err = validate_response(r#"{"risk":"medium"}"#, &schema).unwrap_err();
+        let ProviderError::InvalidResponse(msg) = err else { panic!("expected InvalidResponse") };
+        assert!(msg.contains("command"), "{}", msg);
+        assert!(msg.contains("/risk"), "{}", msg);
+
+        assert!(matches!(
+            validate_response("not json", &schema),
+            Err(ProviderError::InvalidResponse(_))
+        ));
+    }
+}
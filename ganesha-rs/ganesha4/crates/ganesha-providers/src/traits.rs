@@ -26,6 +26,10 @@ pub struct Usage {
     pub prompt_tokens: u32,
     pub completion_tokens: u32,
     pub total_tokens: u32,
+    /// Prompt tokens served from the provider's prompt cache
+    pub cache_read_tokens: u32,
+    /// Prompt tokens written to the provider's prompt cache
+    pub cache_creation_tokens: u32,
 }
 
 /// Options for generation
@@ -43,6 +47,16 @@ pub struct GenerateOptions {
     pub system: Option<String>,
     /// Enable JSON mode
     pub json_mode: bool,
+    /// JSON schema the response must satisfy. Providers constrain decoding
+    /// natively where they can; otherwise they request JSON mode and validate
+    /// the reply, returning `InvalidResponse` on mismatch.
+    pub response_schema: Option<serde_json::Value>,
+    /// Ask for strict schema adherence where supported (OpenAI `strict`).
+    /// Strict mode rejects schemas that leave properties optional or allow
+    /// additional properties, so it is off unless requested.
+    pub strict_schema: bool,
+    /// Mark `system` as a prompt-cache breakpoint (see `Message::cacheable`)
+    pub cache_system: bool,
 }
 
 impl Default for GenerateOptions {
@@ -54,6 +68,9 @@ impl Default for GenerateOptions {
             stop: None,
             system: None,
             json_mode: false,
+            response_schema: None,
+            strict_schema: false,
+            cache_system: false,
         }
     }
 }
@@ -92,6 +109,49 @@ pub trait LlmProvider: Send + Sync {
         let response = self.chat(&messages, &GenerateOptions::default()).await?;
         Ok(response.content)
     }
+    /// This provider as a `ToolProvider`, if it supports tool calling.
+    ///
+    /// Providers that implement `ToolProvider` should override this to
+    /// return `Some(self)` so callers holding a `dyn LlmProvider` can use it.
+    fn as_tool_provider(&self) -> Option<&dyn ToolProvider> {
+        None
+    }
+}
+
+/// Shared providers, so a caller can keep a handle on one it registered
+#[async_trait]
+impl<P: LlmProvider + ?Sized> LlmProvider for std::sync::Arc<P> {
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    async fn is_available(&self) -> bool {
+        (**self).is_available().await
+    }
+
+    fn default_model(&self) -> &str {
+        (**self).default_model()
+    }
+
+    fn model_tier(&self, model: &str) -> ModelTier {
+        (**self).model_tier(model)
+    }
+
+    async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        (**self).list_models().await
+    }
+
+    async fn chat(&self, messages: &[Message], options: &GenerateOptions) -> Result<Response> {
+        (**self).chat(messages, options).await
+    }
+
+    async fn generate(&self, system: &str, user: &str) -> Result<String> {
+        (**self).generate(system, user).await
+    }
+
+    fn as_tool_provider(&self) -> Option<&dyn ToolProvider> {
+        (**self).as_tool_provider()
+    }
 }
 
 /// Streaming provider trait
@@ -7,8 +7,8 @@
 //! - Element location with bounding boxes
 //! - State detection (enabled/disabled, checked/unchecked)
 
-use crate::capture::{Region, Screenshot};
-use crate::config::{CaptureSettings, VisionConfig, VisionModel};
+use crate::capture::{Dimensions, Region, Screenshot};
+use crate::config::{CaptureSettings, ChangeDetectionSettings, VisionConfig, VisionModel};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -140,6 +140,15 @@ impl UIElement {
     pub fn is_clickable(&self) -> bool {
         self.element_type.is_interactive() && self.state.enabled && self.state.visible
     }
+
+    /// Copy of this element with its bounds mapped from the analyzed image
+    /// (`source`) onto the physical screen (`screen`).
+    pub fn to_screen_coords(&self, source: Dimensions, screen: Dimensions) -> UIElement {
+        UIElement {
+            bounds: self.bounds.scaled(source, screen),
+            ..self.clone()
+        }
+    }
 }
 
 /// Text extracted from a screenshot (OCR result).
@@ -170,9 +179,73 @@ pub struct ScreenAnalysis {
     pub raw_response: Option<String>,
     /// Analysis timestamp
     pub timestamp: i64,
+    /// Size of the image the model saw. Element and text bounds are in this
+    /// pixel space, which is smaller than the screen when the capture was
+    /// downscaled for encoding.
+    #[serde(default)]
+    pub source_dimensions: Option<Dimensions>,
+    /// Size of the captured screen in physical pixels
+    #[serde(default)]
+    pub screen_dimensions: Option<Dimensions>,
 }
 
 impl ScreenAnalysis {
+    /// Copy of this analysis with all bounds in physical screen pixels.
+    /// A no-op when either dimension is unknown.
+    pub fn to_screen_coords(&self) -> ScreenAnalysis {
+        let (Some(source), Some(screen)) = (self.source_dimensions, self.screen_dimensions) else {
+            return self.clone();
+        };
+        ScreenAnalysis {
+            elements: self
+                .elements
+                .iter()
+                .map(|e| e.to_screen_coords(source, screen))
+                .collect(),
+            text_blocks: self
+                .text_blocks
+                .iter()
+                .map(|t| ExtractedText {
+                    bounds: t.bounds.scaled(source, screen),
+                    ..t.clone()
+                })
+                .collect(),
+            source_dimensions: Some(screen),
+            ..self.clone()
+        }
+    }
+
+    /// Merge OCR results into `text_blocks`. `ocr_text` bounds are in screen
+    /// pixels and are mapped into this analysis' coordinate space.
+    ///
+    /// An OCR block covering at least half of a reported block (or vice
+    /// versa) is treated as the same text; whichever has the higher
+    /// confidence is kept. Everything else the OCR found is added.
+    pub fn merge_ocr_text(&mut self, ocr_text: Vec<ExtractedText>) {
+        let scale = match (self.screen_dimensions, self.source_dimensions) {
+            (Some(screen), Some(source)) => Some((screen, source)),
+            _ => None,
+        };
+
+        for mut block in ocr_text {
+            if let Some((screen, source)) = scale {
+                block.bounds = block.bounds.scaled(screen, source);
+            }
+
+            let same = self
+                .text_blocks
+                .iter()
+                .position(|t| overlap_fraction(&t.bounds, &block.bounds) >= 0.5);
+            match same {
+                Some(i) if self.text_blocks[i].confidence < block.confidence => {
+                    self.text_blocks[i] = block;
+                }
+                Some(_) => {}
+                None => self.text_blocks.push(block),
+            }
+        }
+    }
+
     /// Find an element by its text content.
     pub fn find_by_text(&self, text: &str) -> Option<&UIElement> {
         let text_lower = text.to_lowercase();
@@ -212,6 +285,16 @@ impl ScreenAnalysis {
     }
 }
 
+/// Intersection of two regions as a fraction of the smaller one.
+fn overlap_fraction(a: &Region, b: &Region) -> f64 {
+    let w = (a.x + a.width as i32).min(b.x + b.width as i32) - a.x.max(b.x);
+    let h = (a.y + a.height as i32).min(b.y + b.height as i32) - a.y.max(b.y);
+    if w <= 0 || h <= 0 {
+        return 0.0;
+    }
+    (w as u64 * h as u64) as f64 / a.area().min(b.area()).max(1) as f64
+}
+
 /// Detected application context.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppContext {
@@ -235,7 +318,8 @@ pub trait VisionAnalyzer: Send + Sync {
     /// Extract text from a screenshot (OCR).
     async fn extract_text(&self, screenshot: &Screenshot) -> AnalysisResult<Vec<ExtractedText>>;
 
-    /// Find a specific element by description.
+    /// Find a specific element by description. Its bounds are in screen
+    /// coordinates, ready to click.
     async fn find_element(
         &self,
         screenshot: &Screenshot,
@@ -244,6 +328,63 @@ pub trait VisionAnalyzer: Send + Sync {
 
     /// Answer a question about the screenshot.
     async fn ask(&self, screenshot: &Screenshot, question: &str) -> AnalysisResult<String>;
+
+    /// Re-analyze only what changed since `previous` was analyzed.
+    ///
+    /// Returns `previous_analysis` unchanged when the changed area is below
+    /// `settings.threshold`, and analyzes the whole of `current` when it is
+    /// above `settings.full_reanalysis_threshold`. Otherwise each changed
+    /// crop is analyzed on its own; its elements and text replace whatever
+    /// the previous analysis had in that area, and the rest is kept.
+    async fn analyze_changed(
+        &self,
+        current: &Screenshot,
+        previous: &Screenshot,
+        previous_analysis: &ScreenAnalysis,
+        settings: &ChangeDetectionSettings,
+    ) -> AnalysisResult<ScreenAnalysis> {
+        let changed = current.diff_regions_with(previous, settings.block_size);
+        let total_area = current.region.area().max(1) as f64;
+        let changed_fraction = changed.iter().map(Region::area).sum::<u64>() as f64 / total_area;
+
+        if changed.is_empty() || changed_fraction < settings.threshold as f64 {
+            return Ok(previous_analysis.clone());
+        }
+        if changed_fraction > settings.full_reanalysis_threshold as f64 {
+            return self.analyze(current, None).await;
+        }
+
+        // Crops are analyzed at their own scale, so merge in full-resolution
+        // screenshot pixels
+        let mut merged = previous_analysis.to_screen_coords();
+        merged.elements.retain(|e| !changed.iter().any(|r| r.intersects(&e.bounds)));
+        merged.text_blocks.retain(|t| !changed.iter().any(|r| r.intersects(&t.bounds)));
+
+        for region in &changed {
+            let crop = current
+                .crop(*region)
+                .map_err(|e| AnalysisError::InvalidResponse(format!("changed region: {}", e)))?;
+            let partial = self.analyze(&crop, None).await?.to_screen_coords();
+
+            // Crop-relative bounds back to full-screenshot coordinates
+            merged.elements.extend(partial.elements.into_iter().map(|mut e| {
+                e.bounds.x += region.x;
+                e.bounds.y += region.y;
+                e
+            }));
+            merged.text_blocks.extend(partial.text_blocks.into_iter().map(|mut t| {
+                t.bounds.x += region.x;
+                t.bounds.y += region.y;
+                t
+            }));
+        }
+
+        merged.raw_response = None;
+        merged.timestamp = chrono::Utc::now().timestamp_millis();
+        merged.source_dimensions = Some(current.dimensions());
+        merged.screen_dimensions = Some(current.dimensions());
+        Ok(merged)
+    }
 }
 
 /// Vision analyzer using OpenAI GPT-4 Vision.
@@ -499,6 +640,8 @@ Return the analysis as JSON with this structure:
             app_context,
             raw_response: Some(response),
             timestamp: chrono::Utc::now().timestamp_millis(),
+            source_dimensions: Some(screenshot.encoded_dimensions(&self.capture_settings)),
+            screen_dimensions: Some(screenshot.dimensions()),
         })
     }
 
@@ -611,7 +754,7 @@ Return the analysis as JSON with this structure:
         }
 
         let e = &parsed["element"];
-        Ok(Some(UIElement {
+        let element = UIElement {
             id: e["id"]
                 .as_str()
                 .unwrap_or("found_element")
@@ -632,7 +775,11 @@ Return the analysis as JSON with this structure:
             },
             confidence: e["confidence"].as_f64().unwrap_or(0.5) as f32,
             attributes: HashMap::new(),
-        }))
+        };
+        Ok(Some(element.to_screen_coords(
+            screenshot.encoded_dimensions(&self.capture_settings),
+            screenshot.dimensions(),
+        )))
     }
 
     async fn ask(&self, screenshot: &Screenshot, question: &str) -> AnalysisResult<String> {
@@ -882,6 +1029,8 @@ Return the analysis as JSON with this structure:
             app_context,
             raw_response: Some(response),
             timestamp: chrono::Utc::now().timestamp_millis(),
+            source_dimensions: Some(screenshot.encoded_dimensions(&self.capture_settings)),
+            screen_dimensions: Some(screenshot.dimensions()),
         })
     }
 
@@ -984,7 +1133,7 @@ Return the analysis as JSON with this structure:
         }
 
         let e = &parsed["element"];
-        Ok(Some(UIElement {
+        let element = UIElement {
             id: e["id"]
                 .as_str()
                 .unwrap_or("found_element")
@@ -1005,7 +1154,11 @@ Return the analysis as JSON with this structure:
             },
             confidence: e["confidence"].as_f64().unwrap_or(0.5) as f32,
             attributes: HashMap::new(),
-        }))
+        };
+        Ok(Some(element.to_screen_coords(
+            screenshot.encoded_dimensions(&self.capture_settings),
+            screenshot.dimensions(),
+        )))
     }
 
     async fn ask(&self, screenshot: &Screenshot, question: &str) -> AnalysisResult<String> {
@@ -1032,6 +1185,37 @@ Return the analysis as JSON with this structure:
     }
 }
 
+/// Lets the boxed analyzer from [`create_analyzer`] be used wherever a
+/// `VisionAnalyzer` type parameter is expected (e.g. `ActionPlanner`).
+#[async_trait]
+impl<T: VisionAnalyzer + ?Sized> VisionAnalyzer for Box<T> {
+    async fn analyze(&self, screenshot: &Screenshot, prompt: Option<&str>) -> AnalysisResult<ScreenAnalysis> {
+        (**self).analyze(screenshot, prompt).await
+    }
+
+    async fn extract_text(&self, screenshot: &Screenshot) -> AnalysisResult<Vec<ExtractedText>> {
+        (**self).extract_text(screenshot).await
+    }
+
+    async fn find_element(&self, screenshot: &Screenshot, description: &str) -> AnalysisResult<Option<UIElement>> {
+        (**self).find_element(screenshot, description).await
+    }
+
+    async fn ask(&self, screenshot: &Screenshot, question: &str) -> AnalysisResult<String> {
+        (**self).ask(screenshot, question).await
+    }
+
+    async fn analyze_changed(
+        &self,
+        current: &Screenshot,
+        previous: &Screenshot,
+        previous_analysis: &ScreenAnalysis,
+        settings: &ChangeDetectionSettings,
+    ) -> AnalysisResult<ScreenAnalysis> {
+        (**self).analyze_changed(current, previous, previous_analysis, settings).await
+    }
+}
+
 /// Create a vision analyzer based on configuration.
 pub fn create_analyzer(config: &VisionConfig) -> AnalysisResult<Box<dyn VisionAnalyzer>> {
     match config.model {
@@ -1088,9 +1272,168 @@ mod tests {
             app_context: None,
             raw_response: None,
             timestamp: 0,
+            source_dimensions: None,
+            screen_dimensions: None,
         };
 
         assert!(analysis.find_by_text("save").is_some());
         assert!(analysis.find_by_text("delete").is_none());
     }
+
+    #[test]
+    fn test_to_screen_coords_scales_to_physical_pixels() {
+        let analysis = ScreenAnalysis {
+            elements: vec![UIElement {
+                id: "btn1".to_string(),
+                element_type: ElementType::Button,
+                bounds: Region::new(900, 500, 120, 40),
+                text: Some("OK".to_string()),
+                state: ElementState::default(),
+                confidence: 0.9,
+                attributes: HashMap::new(),
+            }],
+            text_blocks: vec![],
+            description: String::new(),
+            app_context: None,
+            raw_response: None,
+            timestamp: 0,
+            source_dimensions: Some(Dimensions::new(1920, 1080)),
+            screen_dimensions: Some(Dimensions::new(3840, 2160)),
+        };
+
+        assert_eq!(analysis.elements[0].center(), (960, 520));
+        let on_screen = analysis.to_screen_coords();
+        assert_eq!(on_screen.elements[0].bounds, Region::new(1800, 1000, 240, 80));
+        assert_eq!(on_screen.elements[0].center(), (1920, 1040));
+        assert_eq!(on_screen.source_dimensions, on_screen.screen_dimensions);
+    }
+
+    #[test]
+    fn test_merge_ocr_text_dedupes_by_overlap() {
+        let text = |text: &str, bounds: Region, confidence: f32| ExtractedText {
+            text: text.to_string(),
+            bounds,
+            confidence,
+            is_word: false,
+        };
+        let mut analysis = ScreenAnalysis {
+            elements: vec![],
+            text_blocks: vec![
+                text("Save Fle", Region::new(10, 10, 50, 10), 0.4),
+                text("Cancel", Region::new(100, 10, 40, 10), 0.95),
+            ],
+            description: String::new(),
+            app_context: None,
+            raw_response: None,
+            timestamp: 0,
+            source_dimensions: Some(Dimensions::new(960, 540)),
+            screen_dimensions: Some(Dimensions::new(1920, 1080)),
+        };
+
+        // OCR runs at full resolution, so its bounds are twice as large
+        analysis.merge_ocr_text(vec![
+            text("Save File", Region::new(20, 20, 100, 20), 0.9),
+            text("Cancel", Region::new(200, 20, 80, 20), 0.8),
+            text("v1.2.3", Region::new(1800, 1040, 60, 16), 0.85),
+        ]);
+
+        let texts: Vec<_> = analysis.text_blocks.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(texts, vec!["Save File", "Cancel", "v1.2.3"]);
+        assert_eq!(analysis.text_blocks[0].bounds, Region::new(10, 10, 50, 10));
+        assert_eq!(analysis.text_blocks[1].confidence, 0.95);
+        assert_eq!(analysis.text_blocks[2].bounds, Region::new(900, 520, 30, 8));
+    }
+
+    /// Analyzer that reports one button at (1, 1) of whatever it is shown
+    struct CountingAnalyzer {
+        calls: std::sync::Mutex<Vec<(u32, u32)>>,
+    }
+
+    #[async_trait]
+    impl VisionAnalyzer for CountingAnalyzer {
+        async fn analyze(&self, screenshot: &Screenshot, _prompt: Option<&str>) -> AnalysisResult<ScreenAnalysis> {
+            self.calls.lock().unwrap().push((screenshot.width(), screenshot.height()));
+            Ok(ScreenAnalysis {
+                elements: vec![UIElement {
+                    id: "new".to_string(),
+                    element_type: ElementType::Button,
+                    bounds: Region::new(1, 1, 10, 10),
+                    text: Some("New".to_string()),
+                    state: ElementState::default(),
+                    confidence: 0.9,
+                    attributes: HashMap::new(),
+                }],
+                text_blocks: vec![],
+                description: "fresh".to_string(),
+                app_context: None,
+                raw_response: None,
+                timestamp: 0,
+                source_dimensions: None,
+                screen_dimensions: None,
+            })
+        }
+
+        async fn extract_text(&self, _screenshot: &Screenshot) -> AnalysisResult<Vec<ExtractedText>> {
+            Ok(vec![])
+        }
+
+        async fn find_element(&self, _screenshot: &Screenshot, _description: &str) -> AnalysisResult<Option<UIElement>> {
+            Ok(None)
+        }
+
+        async fn ask(&self, _screenshot: &Screenshot, _question: &str) -> AnalysisResult<String> {
+            Ok(String::new())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_analyze_changed_reuses_and_merges() {
+        use image::{DynamicImage, Rgba, RgbaImage};
+
+        let blank = RgbaImage::from_pixel(640, 640, Rgba([0, 0, 0, 255]));
+        let previous = Screenshot::new(DynamicImage::ImageRgba8(blank.clone()), Region::new(0, 0, 640, 640), "t0");
+        let element = |id: &str, bounds: Region| UIElement {
+            id: id.to_string(),
+            element_type: ElementType::Button,
+            bounds,
+            text: None,
+            state: ElementState::default(),
+            confidence: 0.9,
+            attributes: HashMap::new(),
+        };
+        let previous_analysis = ScreenAnalysis {
+            elements: vec![element("stale", Region::new(40, 40, 10, 10)), element("kept", Region::new(200, 200, 10, 10))],
+            text_blocks: vec![],
+            description: "old".to_string(),
+            app_context: None,
+            raw_response: None,
+            timestamp: 0,
+            source_dimensions: None,
+            screen_dimensions: None,
+        };
+        let analyzer = CountingAnalyzer { calls: Default::default() };
+        let settings = ChangeDetectionSettings::default();
+
+        // One changed pixel (a cursor blink) is under the threshold
+        let mut blink = blank.clone();
+        blink.put_pixel(100, 100, Rgba([255, 255, 255, 255]));
+        let current = Screenshot::new(DynamicImage::ImageRgba8(blink), previous.region, "t1");
+        let result = analyzer.analyze_changed(&current, &previous, &previous_analysis, &settings).await.unwrap();
+        assert_eq!(result.description, "old");
+        assert!(analyzer.calls.lock().unwrap().is_empty());
+
+        // A 64x64 change covering the stale element: only that crop is sent
+        let mut dialog = blank.clone();
+        for x in 32..96 {
+            for y in 32..96 {
+                dialog.put_pixel(x, y, Rgba([200, 200, 200, 255]));
+            }
+        }
+        let current = Screenshot::new(DynamicImage::ImageRgba8(dialog), previous.region, "t2");
+        let result = analyzer.analyze_changed(&current, &previous, &previous_analysis, &settings).await.unwrap();
+        assert_eq!(*analyzer.calls.lock().unwrap(), vec![(64, 64)]);
+        let ids: Vec<_> = result.elements.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, vec!["kept", "new"]);
+        assert_eq!(result.elements[1].bounds, Region::new(33, 33, 10, 10));
+    }
 }
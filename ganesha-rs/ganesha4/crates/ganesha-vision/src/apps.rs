@@ -13,6 +13,7 @@ use crate::input::{InputSimulator, KeyboardShortcut};
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::Path;
 use std::time::Duration;
 use thiserror::Error;
 
@@ -42,6 +43,9 @@ pub enum AppError {
 
     #[error("Timeout waiting for application")]
     Timeout,
+
+    #[error("Invalid action patterns: {0}")]
+    InvalidPatterns(String),
 }
 
 /// Result type for app operations.
@@ -307,6 +311,96 @@ impl AppActionLibrary {
             .iter()
             .find(|p| p.name == action_name)
     }
+
+    /// Get action patterns for a running application, by app name and then
+    /// by process name.
+    pub fn patterns_for(&self, app: &AppInfo) -> Option<&Vec<ActionPattern>> {
+        self.get_patterns(&app.name)
+            .or_else(|| self.get_patterns(&app.process_name))
+    }
+
+    /// Load patterns from a TOML file and merge them into the library.
+    ///
+    /// The file has the same shape as `export_patterns` writes: an `[[apps."<name>"]]`
+    /// table per pattern. A loaded pattern replaces an existing one of the same
+    /// name for that app; new names are appended. Returns how many patterns were
+    /// loaded. Nothing is merged if any pattern fails validation.
+    pub fn load_patterns_from(&mut self, path: impl AsRef<Path>) -> AppResult<usize> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path)
+            .map_err(|e| AppError::InvalidPatterns(format!("{}: {}", path.display(), e)))?;
+        let loaded: AppActionLibrary = toml::from_str(&content)
+            .map_err(|e| AppError::InvalidPatterns(format!("{}: {}", path.display(), e)))?;
+
+        for (app_name, patterns) in &loaded.apps {
+            for pattern in patterns {
+                pattern.validate().map_err(|e| {
+                    AppError::InvalidPatterns(format!(
+                        "{}: {} / {}: {}",
+                        path.display(),
+                        app_name,
+                        pattern.name,
+                        e
+                    ))
+                })?;
+            }
+        }
+
+        let mut count = 0;
+        for (app_name, patterns) in loaded.apps {
+            let existing = self.apps.entry(app_name).or_default();
+            for pattern in patterns {
+                match existing.iter_mut().find(|p| p.name == pattern.name) {
+                    Some(slot) => *slot = pattern,
+                    None => existing.push(pattern),
+                }
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Write an app's patterns to a TOML file, e.g. to use the built-ins as a
+    /// starting template for `load_patterns_from`.
+    pub fn export_patterns(&self, app_name: &str, path: impl AsRef<Path>) -> AppResult<()> {
+        let patterns = self
+            .get_patterns(app_name)
+            .ok_or_else(|| AppError::NotFound(app_name.to_string()))?;
+        let export = AppActionLibrary {
+            apps: HashMap::from([(app_name.to_string(), patterns.clone())]),
+        };
+        let content = toml::to_string_pretty(&export)
+            .map_err(|e| AppError::OperationFailed(e.to_string()))?;
+        std::fs::write(path.as_ref(), content)
+            .map_err(|e| AppError::OperationFailed(format!("{}: {}", path.as_ref().display(), e)))
+    }
+}
+
+impl ActionPattern {
+    /// Check that the pattern can be executed. Unknown action types are
+    /// already rejected when parsing.
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("pattern name is empty".to_string());
+        }
+        self.action.validate()
+    }
+}
+
+impl AppAction {
+    /// Check the action's arguments, recursing into sequences.
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            AppAction::Shortcut { shortcut } => KeyboardShortcut::parse(shortcut)
+                .map(|_| ())
+                .map_err(|e| e.to_string()),
+            AppAction::SelectMenu { path } if path.is_empty() => {
+                Err("menu path is empty".to_string())
+            }
+            AppAction::Sequence { actions } => actions.iter().try_for_each(AppAction::validate),
+            _ => Ok(()),
+        }
+    }
 }
 
 /// Trait for application control operations.
@@ -796,6 +890,65 @@ mod tests {
         assert_eq!(action.unwrap().name, "render");
     }
 
+    #[test]
+    fn test_export_and_load_patterns() {
+        let dir = tempfile::tempdir().unwrap();
+        let template = dir.path().join("blender.toml");
+        let library = AppActionLibrary::with_defaults();
+        library.export_patterns("Blender", &template).unwrap();
+        assert!(library.export_patterns("Unknown App", &template).is_err());
+
+        let mut roundtrip = AppActionLibrary::default();
+        let count = roundtrip.load_patterns_from(&template).unwrap();
+        assert_eq!(count, library.get_patterns("Blender").unwrap().len());
+
+        let custom = dir.path().join("custom.toml");
+        std::fs::write(
+            &custom,
+            r#"
+[[apps.Blender]]
+name = "render"
+description = "Render animation instead"
+action = { type = "shortcut", shortcut = "Ctrl+F12" }
+
+[[apps."My Editor"]]
+name = "export"
+description = "Export and confirm"
+action = { type = "sequence", actions = [
+    { type = "select_menu", path = ["File", "Export"] },
+    { type = "wait", milliseconds = 500 },
+    { type = "click_button", label = "OK" },
+] }
+"#,
+        )
+        .unwrap();
+
+        let mut library = AppActionLibrary::with_defaults();
+        let before = library.get_patterns("Blender").unwrap().len();
+        assert_eq!(library.load_patterns_from(&custom).unwrap(), 2);
+        assert_eq!(library.get_patterns("Blender").unwrap().len(), before);
+        assert_eq!(
+            library.find_action("Blender", "render").unwrap().description,
+            "Render animation instead"
+        );
+        assert!(library.find_action("My Editor", "export").is_some());
+
+        let bad = dir.path().join("bad.toml");
+        std::fs::write(
+            &bad,
+            r#"
+[[apps.Blender]]
+name = "explode"
+description = "Not a real action"
+action = { type = "self_destruct" }
+"#,
+        )
+        .unwrap();
+        let err = library.load_patterns_from(&bad).unwrap_err();
+        assert!(matches!(err, AppError::InvalidPatterns(_)));
+        assert!(err.to_string().contains("self_destruct"));
+    }
+
     #[test]
     fn test_app_state() {
         assert_ne!(AppState::Focused, AppState::Minimized);
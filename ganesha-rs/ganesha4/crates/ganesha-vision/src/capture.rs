@@ -11,6 +11,8 @@ use crate::config::{CaptureSettings, ImageFormat};
 use async_trait::async_trait;
 use image::{DynamicImage, ImageBuffer, Rgba};
 use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 use std::io::Cursor;
 use thiserror::Error;
 
@@ -79,6 +81,19 @@ impl Region {
             && y < self.y + self.height as i32
     }
 
+    /// Area in pixels.
+    pub fn area(&self) -> u64 {
+        self.width as u64 * self.height as u64
+    }
+
+    /// Check if two regions overlap.
+    pub fn intersects(&self, other: &Region) -> bool {
+        (self.x as i64) < other.x as i64 + other.width as i64
+            && (other.x as i64) < self.x as i64 + self.width as i64
+            && (self.y as i64) < other.y as i64 + other.height as i64
+            && (other.y as i64) < self.y as i64 + self.height as i64
+    }
+
     /// Get the center point of this region.
     pub fn center(&self) -> (i32, i32) {
         (
@@ -86,6 +101,43 @@ impl Region {
             self.y + (self.height / 2) as i32,
         )
     }
+
+    /// Map this region from `from` pixel space onto `to` pixel space.
+    pub fn scaled(&self, from: Dimensions, to: Dimensions) -> Region {
+        let (sx, sy) = from.scale_to(to);
+        Region::new(
+            (self.x as f64 * sx).round() as i32,
+            (self.y as f64 * sy).round() as i32,
+            (self.width as f64 * sx).round() as u32,
+            (self.height as f64 * sy).round() as u32,
+        )
+    }
+}
+
+/// Width and height of an image or screen, in pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Dimensions {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Dimensions {
+    /// Create new dimensions.
+    pub fn new(width: u32, height: u32) -> Self {
+        Self { width, height }
+    }
+
+    /// Horizontal and vertical factors that map this space onto `other`.
+    /// Empty dimensions map 1:1.
+    pub fn scale_to(&self, other: Dimensions) -> (f64, f64) {
+        if self.width == 0 || self.height == 0 {
+            return (1.0, 1.0);
+        }
+        (
+            other.width as f64 / self.width as f64,
+            other.height as f64 / self.height as f64,
+        )
+    }
 }
 
 /// Information about a display monitor.
@@ -133,6 +185,8 @@ pub struct Screenshot {
     pub timestamp: i64,
     /// Source of the capture (monitor name, window title, etc.)
     pub source: String,
+    /// Monitor this frame came from, for per-monitor captures
+    pub monitor: Option<MonitorInfo>,
 }
 
 impl Screenshot {
@@ -143,9 +197,16 @@ impl Screenshot {
             region,
             timestamp: chrono::Utc::now().timestamp_millis(),
             source: source.into(),
+            monitor: None,
         }
     }
 
+    /// Tag the screenshot with the monitor it was captured from.
+    pub fn with_monitor(mut self, monitor: MonitorInfo) -> Self {
+        self.monitor = Some(monitor);
+        self
+    }
+
     /// Get the width of the screenshot.
     pub fn width(&self) -> u32 {
         self.image.width()
@@ -156,20 +217,31 @@ impl Screenshot {
         self.image.height()
     }
 
+    /// Pixel dimensions of the captured image.
+    pub fn dimensions(&self) -> Dimensions {
+        Dimensions::new(self.image.width(), self.image.height())
+    }
+
+    /// Dimensions of the image `encode` produces, i.e. what a vision model
+    /// sees after downscaling to `settings.max_dimension`.
+    pub fn encoded_dimensions(&self, settings: &CaptureSettings) -> Dimensions {
+        let (width, height) = (self.image.width(), self.image.height());
+        if width <= settings.max_dimension && height <= settings.max_dimension {
+            return Dimensions::new(width, height);
+        }
+        let scale = settings.max_dimension as f64 / width.max(height) as f64;
+        Dimensions::new((width as f64 * scale) as u32, (height as f64 * scale) as u32)
+    }
+
     /// Encode the screenshot to bytes in the specified format.
     pub fn encode(&self, settings: &CaptureSettings) -> CaptureResult<Vec<u8>> {
         let mut buffer = Cursor::new(Vec::new());
 
         // Scale down if needed
-        let image = if self.image.width() > settings.max_dimension
-            || self.image.height() > settings.max_dimension
-        {
-            let scale = settings.max_dimension as f64
-                / self.image.width().max(self.image.height()) as f64;
-            let new_width = (self.image.width() as f64 * scale) as u32;
-            let new_height = (self.image.height() as f64 * scale) as u32;
+        let target = self.encoded_dimensions(settings);
+        let image = if target != self.dimensions() {
             self.image
-                .resize(new_width, new_height, image::imageops::FilterType::Lanczos3)
+                .resize(target.width, target.height, image::imageops::FilterType::Lanczos3)
         } else {
             self.image.clone()
         };
@@ -209,6 +281,77 @@ impl Screenshot {
         ))
     }
 
+    /// Areas that changed since `previous`, in image coordinates (as used by
+    /// `crop`), compared in 32-pixel blocks. See `diff_regions_with`.
+    pub fn diff_regions(&self, previous: &Screenshot) -> Vec<Region> {
+        self.diff_regions_with(previous, 32)
+    }
+
+    /// Areas that changed since `previous`, compared in `block_size` blocks.
+    ///
+    /// Each block is reduced to a `hash_region` of its pixels; touching changed
+    /// blocks are merged into one bounding region. A size change counts as
+    /// the whole image changing.
+    pub fn diff_regions_with(&self, previous: &Screenshot, block_size: u32) -> Vec<Region> {
+        let (width, height) = (self.image.width(), self.image.height());
+        if (width, height) != (previous.image.width(), previous.image.height()) {
+            return vec![Region::new(0, 0, width, height)];
+        }
+
+        let block = block_size.max(1);
+        let cols = width.div_ceil(block) as usize;
+        let rows = height.div_ceil(block) as usize;
+        let current = self.image.to_rgba8();
+        let before = previous.image.to_rgba8();
+
+        let mut changed = vec![false; cols * rows];
+        for row in 0..rows {
+            for col in 0..cols {
+                let (x, y) = (col as u32 * block, row as u32 * block);
+                let (w, h) = (block.min(width - x), block.min(height - y));
+                changed[row * cols + col] = hash_region(current.as_raw(), width, x, y, w, h)
+                    != hash_region(before.as_raw(), width, x, y, w, h);
+            }
+        }
+
+        // Flood-fill touching changed blocks into groups, one region per group
+        let mut regions = Vec::new();
+        let mut seen = vec![false; cols * rows];
+        for start in 0..changed.len() {
+            if !changed[start] || seen[start] {
+                continue;
+            }
+            seen[start] = true;
+            let mut stack = vec![start];
+            let (mut min_c, mut max_c, mut min_r, mut max_r) = (cols, 0, rows, 0);
+            while let Some(i) = stack.pop() {
+                let (r, c) = (i / cols, i % cols);
+                min_c = min_c.min(c);
+                max_c = max_c.max(c);
+                min_r = min_r.min(r);
+                max_r = max_r.max(r);
+                let neighbours = [
+                    (r > 0).then(|| i - cols),
+                    (r + 1 < rows).then(|| i + cols),
+                    (c > 0).then(|| i - 1),
+                    (c + 1 < cols).then(|| i + 1),
+                ];
+                for n in neighbours.into_iter().flatten() {
+                    if changed[n] && !seen[n] {
+                        seen[n] = true;
+                        stack.push(n);
+                    }
+                }
+            }
+            let x = min_c as u32 * block;
+            let y = min_r as u32 * block;
+            let right = ((max_c as u32 + 1) * block).min(width);
+            let bottom = ((max_r as u32 + 1) * block).min(height);
+            regions.push(Region::new(x as i32, y as i32, right - x, bottom - y));
+        }
+        regions
+    }
+
     /// Crop the screenshot to a specific region.
     pub fn crop(&self, region: Region) -> CaptureResult<Screenshot> {
         // Validate region is within bounds
@@ -237,6 +380,84 @@ impl Screenshot {
     }
 }
 
+/// Hash of the RGB values in a region of an RGBA buffer (alpha is ignored).
+///
+/// Every pixel is hashed, so a one-pixel change such as a text caret or a
+/// changed glyph is still detected.
+pub fn hash_region(pixels: &[u8], width: u32, x: u32, y: u32, w: u32, h: u32) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    let (width, x, w) = (width as usize, x as usize, w as usize);
+    for row in y as usize..y as usize + h as usize {
+        let start = (row * width + x) * 4;
+        for pixel in pixels[start..start + w * 4].chunks_exact(4) {
+            pixel[..3].hash(&mut hasher);
+        }
+    }
+    hasher.finish()
+}
+
+/// Smallest region containing all of `regions`, or `None` if there are none.
+pub fn bounding_region<'a>(regions: impl IntoIterator<Item = &'a Region>) -> Option<Region> {
+    let mut iter = regions.into_iter();
+    let first = iter.next()?;
+    let (mut left, mut top) = (first.x as i64, first.y as i64);
+    let (mut right, mut bottom) = (left + first.width as i64, top + first.height as i64);
+    for r in iter {
+        left = left.min(r.x as i64);
+        top = top.min(r.y as i64);
+        right = right.max(r.x as i64 + r.width as i64);
+        bottom = bottom.max(r.y as i64 + r.height as i64);
+    }
+    Some(Region::new(left as i32, top as i32, (right - left) as u32, (bottom - top) as u32))
+}
+
+/// Stitch per-monitor screenshots into one desktop screenshot.
+///
+/// Each frame is placed by its monitor's region (falling back to the
+/// screenshot's own region). Frames whose pixel size differs from that
+/// region, as on HiDPI monitors with a scale factor above 1, are resized to
+/// it, so pixel `(px, py)` of the result is screen coordinate
+/// `(region.x + px, region.y + py)` on every monitor. Gaps between monitors
+/// are left transparent. The timestamp is that of the earliest frame.
+pub fn stitch_screenshots(shots: &[Screenshot]) -> CaptureResult<Screenshot> {
+    let placements: Vec<Region> = shots
+        .iter()
+        .map(|s| s.monitor.as_ref().map_or(s.region, |m| m.region))
+        .collect();
+    let bounds = bounding_region(&placements)
+        .ok_or_else(|| CaptureError::CaptureFailed("No screenshots to stitch".to_string()))?;
+
+    let mut canvas = image::RgbaImage::new(bounds.width, bounds.height);
+    for (shot, region) in shots.iter().zip(&placements) {
+        let frame = if shot.image.width() == region.width && shot.image.height() == region.height {
+            shot.image.to_rgba8()
+        } else {
+            image::imageops::resize(
+                &shot.image.to_rgba8(),
+                region.width,
+                region.height,
+                image::imageops::FilterType::Triangle,
+            )
+        };
+        image::imageops::replace(
+            &mut canvas,
+            &frame,
+            region.x as i64 - bounds.x as i64,
+            region.y as i64 - bounds.y as i64,
+        );
+    }
+
+    let mut stitched = Screenshot::new(
+        DynamicImage::ImageRgba8(canvas),
+        bounds,
+        format!("Desktop ({} monitors)", shots.len()),
+    );
+    if let Some(earliest) = shots.iter().map(|s| s.timestamp).min() {
+        stitched.timestamp = earliest;
+    }
+    Ok(stitched)
+}
+
 /// Trait for platform-specific screen capture implementations.
 #[async_trait]
 pub trait ScreenCapture: Send + Sync {
@@ -255,6 +476,20 @@ pub trait ScreenCapture: Send + Sync {
     /// Capture a specific monitor.
     async fn capture_monitor(&self, monitor_index: u32) -> CaptureResult<Screenshot>;
 
+    /// Capture every monitor, as close to simultaneously as the platform
+    /// allows. Each screenshot is tagged with its `MonitorInfo`; pass the
+    /// result to `stitch_screenshots` for a single desktop image.
+    async fn capture_all_monitors(&self) -> CaptureResult<Vec<Screenshot>> {
+        let monitors = self.get_monitors().await?;
+        let captures =
+            futures::future::join_all(monitors.iter().map(|m| self.capture_monitor(m.index))).await;
+        monitors
+            .into_iter()
+            .zip(captures)
+            .map(|(monitor, shot)| shot.map(|s| s.with_monitor(monitor)))
+            .collect()
+    }
+
     /// Capture a specific region.
     async fn capture_region(&self, region: Region) -> CaptureResult<Screenshot>;
 
@@ -360,31 +595,41 @@ pub mod platform {
         }
 
         async fn capture_all(&self) -> CaptureResult<Screenshot> {
-            // Capture primary monitor for now
-            // TODO: Implement stitching multiple monitors
-            let monitors = xcap::Monitor::all()
-                .map_err(|e| CaptureError::CaptureFailed(e.to_string()))?;
-
-            let monitor = monitors
-                .into_iter()
-                .find(|m| m.is_primary().unwrap_or(false))
-                .ok_or_else(|| CaptureError::MonitorNotFound(0))?;
-
-            let capture = monitor
-                .capture_image()
-                .map_err(|e| CaptureError::CaptureFailed(e.to_string()))?;
-
-            let width = capture.width();
-            let height = capture.height();
-            let data = capture.into_raw();
+            let shots = self.capture_all_monitors().await?;
+            stitch_screenshots(&shots)
+        }
 
-            let image = Self::convert_image(data, width, height)?;
-            let mon_x = monitor.x().unwrap_or(0);
-            let mon_y = monitor.y().unwrap_or(0);
-            let mon_name = monitor.name().unwrap_or_else(|_| "Primary".to_string());
-            let region = Region::new(mon_x, mon_y, width, height);
+        async fn capture_all_monitors(&self) -> CaptureResult<Vec<Screenshot>> {
+            let monitors = self.get_monitors().await?;
 
-            Ok(Screenshot::new(image, region, mon_name))
+            // One blocking task per monitor so frames are grabbed together rather
+            // than back to back. Monitor handles aren't Send on every platform, so
+            // each task looks its monitor up again by index.
+            let tasks = monitors.into_iter().map(|info| {
+                tokio::task::spawn_blocking(move || -> CaptureResult<Screenshot> {
+                    let monitor = xcap::Monitor::all()
+                        .map_err(|e| CaptureError::CaptureFailed(e.to_string()))?
+                        .into_iter()
+                        .nth(info.index as usize)
+                        .ok_or(CaptureError::MonitorNotFound(info.index))?;
+
+                    let capture = monitor
+                        .capture_image()
+                        .map_err(|e| CaptureError::CaptureFailed(e.to_string()))?;
+
+                    let width = capture.width();
+                    let height = capture.height();
+                    let image = Self::convert_image(capture.into_raw(), width, height)?;
+
+                    Ok(Screenshot::new(image, info.region, info.name.clone()).with_monitor(info))
+                })
+            });
+
+            futures::future::join_all(tasks)
+                .await
+                .into_iter()
+                .map(|joined| joined.unwrap_or_else(|e| Err(CaptureError::CaptureFailed(e.to_string()))))
+                .collect()
         }
 
         async fn capture_monitor(&self, monitor_index: u32) -> CaptureResult<Screenshot> {
@@ -420,9 +665,16 @@ pub mod platform {
                 ));
             }
 
-            // Capture full screen and crop
+            // Capture full screen and crop; the desktop may start left of or above (0, 0)
             let full = self.capture_all().await?;
-            full.crop(region)
+            let mut cropped = full.crop(Region::new(
+                region.x - full.region.x,
+                region.y - full.region.y,
+                region.width,
+                region.height,
+            ))?;
+            cropped.region = region;
+            Ok(cropped)
         }
 
         async fn get_windows(&self) -> CaptureResult<Vec<WindowInfo>> {
@@ -590,6 +842,95 @@ mod tests {
         assert_eq!(region.center(), (200, 200));
     }
 
+    fn solid(width: u32, height: u32, color: [u8; 4]) -> DynamicImage {
+        DynamicImage::ImageRgba8(ImageBuffer::from_pixel(width, height, Rgba(color)))
+    }
+
+    fn monitor(index: u32, region: Region, scale_factor: f64) -> MonitorInfo {
+        MonitorInfo {
+            index,
+            name: format!("Monitor {}", index),
+            is_primary: index == 0,
+            region,
+            scale_factor,
+        }
+    }
+
+    #[test]
+    fn test_stitch_normalizes_scale_factors() {
+        // A 1x monitor at the origin and a 2x HiDPI monitor to its left
+        let left = Region::new(-100, 20, 100, 50);
+        let right = Region::new(0, 0, 200, 100);
+        let shots = vec![
+            Screenshot::new(solid(200, 100, [255, 0, 0, 255]), right, "right")
+                .with_monitor(monitor(0, right, 1.0)),
+            Screenshot::new(solid(200, 100, [0, 0, 255, 255]), left, "left")
+                .with_monitor(monitor(1, left, 2.0)),
+        ];
+
+        let stitched = stitch_screenshots(&shots).unwrap();
+        assert_eq!(stitched.region, Region::new(-100, 0, 300, 100));
+        assert_eq!((stitched.width(), stitched.height()), (300, 100));
+
+        // Screen (x, y) is pixel (x - region.x, y - region.y)
+        let at = |x: i32, y: i32| {
+            stitched
+                .image
+                .to_rgba8()
+                .get_pixel((x - stitched.region.x) as u32, (y - stitched.region.y) as u32)
+                .0
+        };
+        assert_eq!(at(-1, 20), [0, 0, 255, 255]);
+        assert_eq!(at(-100, 69), [0, 0, 255, 255]);
+        assert_eq!(at(-50, 70), [0, 0, 0, 0]); // below the short left monitor
+        assert_eq!(at(0, 0), [255, 0, 0, 255]);
+        assert_eq!(at(199, 99), [255, 0, 0, 255]);
+    }
+
+    #[test]
+    fn test_diff_regions() {
+        let before = Screenshot::new(solid(256, 128, [10, 10, 10, 255]), Region::new(0, 0, 256, 128), "before");
+        assert!(before.diff_regions(&before.clone()).is_empty());
+
+        let mut image = before.image.to_rgba8();
+        // A line across two touching blocks top-left, and a lone pixel bottom-right
+        for x in 5..60 {
+            image.put_pixel(x, 10, Rgba([255, 255, 255, 255]));
+        }
+        image.put_pixel(250, 121, Rgba([255, 0, 0, 255]));
+        let after = Screenshot::new(DynamicImage::ImageRgba8(image), before.region, "after");
+
+        let regions = after.diff_regions(&before);
+        assert_eq!(regions, vec![Region::new(0, 0, 64, 32), Region::new(224, 96, 32, 32)]);
+
+        let resized = Screenshot::new(solid(100, 100, [0, 0, 0, 255]), Region::new(0, 0, 100, 100), "resized");
+        assert_eq!(resized.diff_regions(&before), vec![Region::new(0, 0, 100, 100)]);
+    }
+
+    #[test]
+    fn test_hash_region_sees_every_pixel() {
+        let before = solid(64, 64, [10, 10, 10, 255]).to_rgba8();
+        let hash = |image: &image::RgbaImage| hash_region(image.as_raw(), 64, 0, 0, 64, 64);
+
+        for (x, y) in [(0, 0), (7, 13), (33, 51), (63, 63)] {
+            let mut after = before.clone();
+            after.put_pixel(x, y, Rgba([11, 10, 10, 255]));
+            assert_ne!(hash(&after), hash(&before), "change at ({}, {})", x, y);
+        }
+
+        // Alpha alone is not a visible change
+        let mut after = before.clone();
+        after.put_pixel(7, 13, Rgba([10, 10, 10, 0]));
+        assert_eq!(hash(&after), hash(&before));
+    }
+
+    #[test]
+    fn test_bounding_region() {
+        assert_eq!(bounding_region(&[]), None);
+        let regions = [Region::new(0, 0, 1920, 1080), Region::new(1920, -200, 1080, 1920)];
+        assert_eq!(bounding_region(&regions), Some(Region::new(0, -200, 3000, 1920)));
+    }
+
     #[test]
     fn test_region_valid() {
         assert!(Region::new(0, 0, 100, 100).is_valid());
@@ -95,6 +95,72 @@ impl Default for CaptureSettings {
     }
 }
 
+/// When a new screenshot is different enough to be worth re-analyzing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChangeDetectionSettings {
+    /// Side length in pixels of the blocks compared between frames
+    pub block_size: u32,
+    /// Fraction of the screen (0.0-1.0) that must change before anything is
+    /// re-analyzed; smaller changes such as a blinking cursor are ignored
+    pub threshold: f32,
+    /// Fraction of the screen above which the whole screenshot is re-analyzed
+    /// instead of the changed crops
+    pub full_reanalysis_threshold: f32,
+}
+
+impl Default for ChangeDetectionSettings {
+    fn default() -> Self {
+        Self {
+            block_size: 32,
+            threshold: 0.005,
+            full_reanalysis_threshold: 0.5,
+        }
+    }
+}
+
+/// Control overlay border that frames whatever is being controlled.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OverlaySettings {
+    /// Border thickness in pixels
+    pub border_width: u32,
+    /// How often to re-read the controlled window's geometry (milliseconds)
+    pub poll_interval_ms: u64,
+}
+
+impl Default for OverlaySettings {
+    fn default() -> Self {
+        Self {
+            border_width: 4,
+            poll_interval_ms: 250,
+        }
+    }
+}
+
+/// Audit log rotation (`audit.log` -> `audit.log.1` -> ...).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuditRotationSettings {
+    /// Rotate once the log reaches this size in bytes (0 = no size limit)
+    pub max_bytes: u64,
+    /// Rotate once the oldest entry in the log is this old, in seconds
+    /// (0 = no age limit)
+    pub max_age_secs: u64,
+    /// Rotated files to keep; older ones are deleted
+    pub max_files: usize,
+}
+
+impl Default for AuditRotationSettings {
+    fn default() -> Self {
+        Self {
+            max_bytes: 10 * 1024 * 1024,
+            max_age_secs: 24 * 60 * 60,
+            max_files: 5,
+        }
+    }
+}
+
 /// Safety limits for automated actions.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SafetyLimits {
@@ -289,6 +355,16 @@ pub struct VisionConfig {
     pub api_key_env: String,
     /// Capture settings
     pub capture: CaptureSettings,
+    /// Change detection for incremental re-analysis
+    #[serde(default)]
+    pub change_detection: ChangeDetectionSettings,
+    /// Run OCR over each screenshot and merge in text the model missed
+    /// (requires the `ocr` feature)
+    #[serde(default)]
+    pub ocr_fallback: bool,
+    /// Control overlay border
+    #[serde(default)]
+    pub overlay: OverlaySettings,
     /// Safety limits
     pub safety: SafetyLimits,
     /// Confirmation settings
@@ -299,6 +375,9 @@ pub struct VisionConfig {
     pub audit_logging: bool,
     /// Audit log file path
     pub audit_log_path: Option<String>,
+    /// Audit log rotation
+    #[serde(default)]
+    pub audit_rotation: AuditRotationSettings,
     /// Enable dry-run mode (no actual input simulation)
     pub dry_run: bool,
 }
@@ -311,11 +390,15 @@ impl Default for VisionConfig {
             api_endpoint: None,
             api_key_env: "OPENAI_API_KEY".to_string(),
             capture: CaptureSettings::default(),
+            change_detection: ChangeDetectionSettings::default(),
+            ocr_fallback: false,
+            overlay: OverlaySettings::default(),
             safety: SafetyLimits::default(),
             confirmations: ConfirmationSettings::default(),
             apps: AppListConfig::with_defaults(),
             audit_logging: true,
             audit_log_path: None,
+            audit_rotation: AuditRotationSettings::default(),
             dry_run: false,
         }
     }
@@ -359,6 +442,18 @@ impl VisionConfig {
         self
     }
 
+    /// Enable the OCR fallback for text the vision model misses.
+    pub fn with_ocr_fallback(mut self, enabled: bool) -> Self {
+        self.ocr_fallback = enabled;
+        self
+    }
+
+    /// Set audit log rotation.
+    pub fn with_audit_rotation(mut self, rotation: AuditRotationSettings) -> Self {
+        self.audit_rotation = rotation;
+        self
+    }
+
     /// Validate the configuration.
     pub fn validate(&self) -> Result<(), ConfigError> {
         // Check safety limits are reasonable
@@ -386,6 +481,10 @@ impl VisionConfig {
             ));
         }
 
+        if self.ocr_fallback && !cfg!(feature = "ocr") {
+            tracing::warn!("ocr_fallback is set but this build lacks the `ocr` feature");
+        }
+
         // Warn if allow all mode is enabled
         if self.apps.mode == AppListMode::AllowAll && !self.dry_run {
             tracing::warn!("Vision system configured to allow all apps without dry-run mode");
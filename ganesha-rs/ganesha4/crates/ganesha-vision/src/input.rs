@@ -4,7 +4,8 @@
 //! - Mouse movement and clicks
 //! - Keyboard input (typing and shortcuts)
 //! - Drag and drop operations
-//! - Platform-specific implementations (X11, Wayland, Windows, macOS)
+//! - Platform-specific implementations (X11, Windows, macOS via enigo; native
+//!   Wayland with the `wayland` feature)
 
 use crate::capture::Region;
 use async_trait::async_trait;
@@ -32,6 +33,9 @@ pub enum InputError {
 
     #[error("Operation cancelled")]
     Cancelled,
+
+    #[error("No input backend available: {0}")]
+    NoBackend(String),
 }
 
 /// Result type for input operations.
@@ -466,6 +470,9 @@ pub trait InputSimulator: Send + Sync {
     /// Check if input simulation is available.
     fn is_available(&self) -> bool;
 
+    /// Name of the backend in use (`wayland`, `enigo` or `mock`).
+    fn backend_name(&self) -> &'static str;
+
     /// Get the current mouse position.
     async fn mouse_position(&self) -> InputResult<(i32, i32)>;
 
@@ -568,6 +575,118 @@ pub trait InputSimulator: Send + Sync {
     }
 }
 
+/// Lets the boxed simulator from [`create_input_simulator`] be used wherever
+/// an `InputSimulator` type parameter is expected (e.g. `ActionPlanner`).
+#[async_trait]
+impl<T: InputSimulator + ?Sized> InputSimulator for Box<T> {
+    fn is_available(&self) -> bool {
+        (**self).is_available()
+    }
+
+    fn backend_name(&self) -> &'static str {
+        (**self).backend_name()
+    }
+
+    async fn mouse_position(&self) -> InputResult<(i32, i32)> {
+        (**self).mouse_position().await
+    }
+
+    async fn mouse_move(&self, x: i32, y: i32) -> InputResult<()> {
+        (**self).mouse_move(x, y).await
+    }
+
+    async fn mouse_move_smooth(&self, x: i32, y: i32, duration: Duration) -> InputResult<()> {
+        (**self).mouse_move_smooth(x, y, duration).await
+    }
+
+    async fn mouse_click(&self, action: &MouseAction) -> InputResult<()> {
+        (**self).mouse_click(action).await
+    }
+
+    async fn mouse_drag(&self, drag: &DragOperation) -> InputResult<()> {
+        (**self).mouse_drag(drag).await
+    }
+
+    async fn mouse_scroll(&self, scroll: &ScrollAction) -> InputResult<()> {
+        (**self).mouse_scroll(scroll).await
+    }
+
+    async fn type_text(&self, text: &str) -> InputResult<()> {
+        (**self).type_text(text).await
+    }
+
+    async fn key_press(&self, key: KeyInput) -> InputResult<()> {
+        (**self).key_press(key).await
+    }
+
+    async fn key_down(&self, key: KeyInput) -> InputResult<()> {
+        (**self).key_down(key).await
+    }
+
+    async fn key_up(&self, key: KeyInput) -> InputResult<()> {
+        (**self).key_up(key).await
+    }
+
+    async fn shortcut(&self, shortcut: &KeyboardShortcut) -> InputResult<()> {
+        (**self).shortcut(shortcut).await
+    }
+
+    // Forward the convenience methods too, so overrides in the boxed
+    // backend are not bypassed
+
+    async fn click(&self, x: i32, y: i32) -> InputResult<()> {
+        (**self).click(x, y).await
+    }
+
+    async fn double_click(&self, x: i32, y: i32) -> InputResult<()> {
+        (**self).double_click(x, y).await
+    }
+
+    async fn right_click(&self, x: i32, y: i32) -> InputResult<()> {
+        (**self).right_click(x, y).await
+    }
+
+    async fn press_enter(&self) -> InputResult<()> {
+        (**self).press_enter().await
+    }
+
+    async fn press_tab(&self) -> InputResult<()> {
+        (**self).press_tab().await
+    }
+
+    async fn press_escape(&self) -> InputResult<()> {
+        (**self).press_escape().await
+    }
+
+    async fn copy(&self) -> InputResult<()> {
+        (**self).copy().await
+    }
+
+    async fn paste(&self) -> InputResult<()> {
+        (**self).paste().await
+    }
+
+    async fn cut(&self) -> InputResult<()> {
+        (**self).cut().await
+    }
+
+    async fn select_all(&self) -> InputResult<()> {
+        (**self).select_all().await
+    }
+
+    async fn undo(&self) -> InputResult<()> {
+        (**self).undo().await
+    }
+
+    async fn redo(&self) -> InputResult<()> {
+        (**self).redo().await
+    }
+
+    async fn save(&self) -> InputResult<()> {
+        (**self).save().await
+    }
+}
+
 /// Platform-specific input simulation using enigo.
 #[cfg(feature = "gui-automation")]
 pub mod platform {
@@ -674,6 +793,10 @@ pub mod platform {
             true
         }
 
+        fn backend_name(&self) -> &'static str {
+            "enigo"
+        }
+
         async fn mouse_position(&self) -> InputResult<(i32, i32)> {
             let enigo = self.enigo.lock().map_err(|e| {
                 InputError::SimulationFailed(format!("Failed to lock enigo: {}", e))
@@ -974,10 +1097,779 @@ pub mod platform {
     }
 }
 
-/// Create the default input simulator for the current platform.
+/// Native Wayland input via the `wlr-virtual-pointer` and `virtual-keyboard`
+/// protocols (wlroots compositors such as Sway and Hyprland, and others that
+/// implement them).
+#[cfg(feature = "wayland")]
+pub mod wayland {
+    use super::*;
+    use std::io::Write;
+    use std::os::fd::AsFd;
+    use std::sync::Mutex;
+    use std::time::Instant;
+    use wayland_client::protocol::{wl_keyboard, wl_output, wl_pointer, wl_registry, wl_seat};
+    use wayland_client::{Connection, Dispatch, EventQueue, QueueHandle, WEnum};
+    use wayland_protocols_wlr::virtual_pointer::v1::client::{
+        zwlr_virtual_pointer_manager_v1::ZwlrVirtualPointerManagerV1,
+        zwlr_virtual_pointer_v1::ZwlrVirtualPointerV1,
+    };
+
+    use self::virtual_keyboard::{
+        zwp_virtual_keyboard_manager_v1::ZwpVirtualKeyboardManagerV1,
+        zwp_virtual_keyboard_v1::ZwpVirtualKeyboardV1,
+    };
+
+    #[allow(dead_code, non_upper_case_globals, unused_imports, missing_docs, clippy::all)]
+    mod virtual_keyboard {
+        use wayland_client;
+        use wayland_client::protocol::*;
+
+        pub mod __interfaces {
+            use wayland_client::backend as wayland_backend;
+            use wayland_client::protocol::__interfaces::*;
+            wayland_scanner::generate_interfaces!("protocols/virtual-keyboard-unstable-v1.xml");
+        }
+        use self::__interfaces::*;
+
+        wayland_scanner::generate_client_code!("protocols/virtual-keyboard-unstable-v1.xml");
+    }
+
+    /// Keymap sent to the compositor. Key codes from `evdev_keycode` and
+    /// `evdev_char` assume this US layout.
+    const KEYMAP: &str = "xkb_keymap {
+        xkb_keycodes { include \"evdev+aliases(qwerty)\" };
+        xkb_types { include \"complete\" };
+        xkb_compat { include \"complete\" };
+        xkb_symbols { include \"pc+us+inet(evdev)\" };
+    };\n";
+
+    const KEY_RELEASED: u32 = 0;
+    const KEY_PRESSED: u32 = 1;
+    const KEY_LEFTSHIFT: u32 = 42;
+
+    /// Linux evdev keycode for a special key.
+    pub fn evdev_keycode(key: Key) -> u32 {
+        match key {
+            Key::F1 => 59,
+            Key::F2 => 60,
+            Key::F3 => 61,
+            Key::F4 => 62,
+            Key::F5 => 63,
+            Key::F6 => 64,
+            Key::F7 => 65,
+            Key::F8 => 66,
+            Key::F9 => 67,
+            Key::F10 => 68,
+            Key::F11 => 87,
+            Key::F12 => 88,
+            Key::Escape => 1,
+            Key::Tab => 15,
+            Key::CapsLock => 58,
+            Key::Backspace => 14,
+            Key::Enter => 28,
+            Key::Space => 57,
+            Key::Insert => 110,
+            Key::Delete => 111,
+            Key::Home => 102,
+            Key::End => 107,
+            Key::PageUp => 104,
+            Key::PageDown => 109,
+            Key::Up => 103,
+            Key::Down => 108,
+            Key::Left => 105,
+            Key::Right => 106,
+            Key::Shift => KEY_LEFTSHIFT,
+            Key::Control => 29,
+            Key::Alt => 56,
+            Key::Meta | Key::Super => 125,
+            Key::NumLock => 69,
+            Key::Numpad0 => 82,
+            Key::Numpad1 => 79,
+            Key::Numpad2 => 80,
+            Key::Numpad3 => 81,
+            Key::Numpad4 => 75,
+            Key::Numpad5 => 76,
+            Key::Numpad6 => 77,
+            Key::Numpad7 => 71,
+            Key::Numpad8 => 72,
+            Key::Numpad9 => 73,
+            Key::NumpadAdd => 78,
+            Key::NumpadSubtract => 74,
+            Key::NumpadMultiply => 55,
+            Key::NumpadDivide => 98,
+            Key::NumpadDecimal => 83,
+            Key::NumpadEnter => 96,
+            Key::VolumeUp => 115,
+            Key::VolumeDown => 114,
+            Key::VolumeMute => 113,
+            Key::PlayPause => 164,
+            Key::Stop => 166,
+            Key::NextTrack => 163,
+            Key::PreviousTrack => 165,
+            Key::PrintScreen => 99,
+            Key::ScrollLock => 70,
+            Key::Pause => 119,
+        }
+    }
+
+    /// Linux evdev keycode for a character in the US layout, and whether
+    /// Shift must be held to produce it.
+    pub fn evdev_char(c: char) -> Option<(u32, bool)> {
+        const LETTERS: [u32; 26] = [
+            30, 48, 46, 32, 18, 33, 34, 35, 23, 36, 37, 38, 50, 49, 24, 25, 16, 19, 31, 20, 22,
+            47, 17, 45, 21, 44,
+        ];
+        let code = match c {
+            'a'..='z' => (LETTERS[(c as u8 - b'a') as usize], false),
+            'A'..='Z' => (LETTERS[(c as u8 - b'A') as usize], true),
+            '1'..='9' => ((c as u8 - b'1') as u32 + 2, false),
+            '0' => (11, false),
+            '!' => (2, true),
+            '@' => (3, true),
+            '#' => (4, true),
+            '$' => (5, true),
+            '%' => (6, true),
+            '^' => (7, true),
+            '&' => (8, true),
+            '*' => (9, true),
+            '(' => (10, true),
+            ')' => (11, true),
+            '-' => (12, false),
+            '_' => (12, true),
+            '=' => (13, false),
+            '+' => (13, true),
+            '[' => (26, false),
+            '{' => (26, true),
+            ']' => (27, false),
+            '}' => (27, true),
+            ';' => (39, false),
+            ':' => (39, true),
+            '\'' => (40, false),
+            '"' => (40, true),
+            '`' => (41, false),
+            '~' => (41, true),
+            '\\' => (43, false),
+            '|' => (43, true),
+            ',' => (51, false),
+            '<' => (51, true),
+            '.' => (52, false),
+            '>' => (52, true),
+            '/' => (53, false),
+            '?' => (53, true),
+            ' ' => (57, false),
+            '\n' => (28, false),
+            '\t' => (15, false),
+            _ => return None,
+        };
+        Some(code)
+    }
+
+    fn evdev_input(key: &KeyInput) -> InputResult<(u32, bool)> {
+        match key {
+            KeyInput::Char(c) => evdev_char(*c).ok_or_else(|| {
+                InputError::InvalidKey(format!("No Wayland keycode for {:?} in the US layout", c))
+            }),
+            KeyInput::Special(k) => Ok((evdev_keycode(*k), false)),
+        }
+    }
+
+    fn modifier_keycode(modifier: Modifier) -> u32 {
+        match modifier {
+            Modifier::Shift => evdev_keycode(Key::Shift),
+            Modifier::Control => evdev_keycode(Key::Control),
+            Modifier::Alt => evdev_keycode(Key::Alt),
+            Modifier::Meta | Modifier::Super => evdev_keycode(Key::Super),
+        }
+    }
+
+    fn button_code(button: MouseButton) -> u32 {
+        match button {
+            MouseButton::Left => 0x110,
+            MouseButton::Right => 0x111,
+            MouseButton::Middle => 0x112,
+            MouseButton::Back => 0x113,
+            MouseButton::Forward => 0x114,
+        }
+    }
+
+    fn protocol_error(e: impl std::fmt::Display) -> InputError {
+        InputError::SimulationFailed(format!("Wayland: {}", e))
+    }
+
+    /// Where one output sits and how big it is.
+    #[derive(Debug, Clone, Copy)]
+    struct OutputGeometry {
+        /// Position in the compositor's logical space
+        x: i32,
+        y: i32,
+        /// Current mode, in physical pixels
+        mode_width: i32,
+        mode_height: i32,
+        /// Scale the compositor renders the output at
+        scale: i32,
+        /// Turned a quarter turn, so the mode's width runs vertically
+        rotated: bool,
+    }
+
+    impl Default for OutputGeometry {
+        fn default() -> Self {
+            Self { x: 0, y: 0, mode_width: 0, mode_height: 0, scale: 1, rotated: false }
+        }
+    }
+
+    impl OutputGeometry {
+        /// Size in logical space, the same space as `x`/`y`.
+        fn logical_size(&self) -> (i32, i32) {
+            let scale = self.scale.max(1);
+            let (width, height) = (self.mode_width / scale, self.mode_height / scale);
+            if self.rotated {
+                (height, width)
+            } else {
+                (width, height)
+            }
+        }
+    }
+
+    /// Size of the logical area spanned by `outputs`.
+    fn logical_extent<'a>(outputs: impl Iterator<Item = &'a OutputGeometry>) -> (u32, u32) {
+        let (mut width, mut height) = (0, 0);
+        for o in outputs {
+            let (w, h) = o.logical_size();
+            width = width.max(o.x + w);
+            height = height.max(o.y + h);
+        }
+        (width.max(1) as u32, height.max(1) as u32)
+    }
+
+    /// One output and its geometry.
+    struct OutputInfo {
+        output: wl_output::WlOutput,
+        geometry: OutputGeometry,
+    }
+
+    /// Globals advertised by the compositor.
+    #[derive(Default)]
+    struct Globals {
+        seat: Option<wl_seat::WlSeat>,
+        pointer_manager: Option<ZwlrVirtualPointerManagerV1>,
+        keyboard_manager: Option<ZwpVirtualKeyboardManagerV1>,
+        outputs: Vec<OutputInfo>,
+    }
+
+    impl Globals {
+        /// Size of the logical area spanned by all outputs, used as the
+        /// extent for absolute pointer motion.
+        fn extent(&self) -> (u32, u32) {
+            logical_extent(self.outputs.iter().map(|o| &o.geometry))
+        }
+    }
+
+    impl Dispatch<wl_registry::WlRegistry, ()> for Globals {
+        fn event(
+            state: &mut Self,
+            registry: &wl_registry::WlRegistry,
+            event: wl_registry::Event,
+            _: &(),
+            _: &Connection,
+            qh: &QueueHandle<Self>,
+        ) {
+            let wl_registry::Event::Global {
+                name,
+                interface,
+                version,
+            } = event
+            else {
+                return;
+            };
+            match interface.as_str() {
+                "wl_seat" if state.seat.is_none() => {
+                    state.seat = Some(registry.bind(name, version.min(5), qh, ()));
+                }
+                "wl_output" => {
+                    let output = registry.bind(name, version.min(3), qh, ());
+                    state.outputs.push(OutputInfo {
+                        output,
+                        geometry: OutputGeometry::default(),
+                    });
+                }
+                "zwlr_virtual_pointer_manager_v1" => {
+                    state.pointer_manager = Some(registry.bind(name, version.min(2), qh, ()));
+                }
+                "zwp_virtual_keyboard_manager_v1" => {
+                    state.keyboard_manager = Some(registry.bind(name, 1, qh, ()));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    impl Dispatch<wl_output::WlOutput, ()> for Globals {
+        fn event(
+            state: &mut Self,
+            output: &wl_output::WlOutput,
+            event: wl_output::Event,
+            _: &(),
+            _: &Connection,
+            _: &QueueHandle<Self>,
+        ) {
+            let Some(info) = state.outputs.iter_mut().find(|o| &o.output == output) else {
+                return;
+            };
+            let geometry = &mut info.geometry;
+            match event {
+                wl_output::Event::Geometry { x, y, transform, .. } => {
+                    geometry.x = x;
+                    geometry.y = y;
+                    geometry.rotated = matches!(
+                        transform,
+                        WEnum::Value(
+                            wl_output::Transform::_90
+                                | wl_output::Transform::_270
+                                | wl_output::Transform::Flipped90
+                                | wl_output::Transform::Flipped270
+                        )
+                    );
+                }
+                wl_output::Event::Mode {
+                    flags: WEnum::Value(flags),
+                    width,
+                    height,
+                    ..
+                } if flags.contains(wl_output::Mode::Current) => {
+                    geometry.mode_width = width;
+                    geometry.mode_height = height;
+                }
+                wl_output::Event::Scale { factor } => {
+                    geometry.scale = factor;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    wayland_client::delegate_noop!(Globals: ignore wl_seat::WlSeat);
+    wayland_client::delegate_noop!(Globals: ZwlrVirtualPointerManagerV1);
+    wayland_client::delegate_noop!(Globals: ZwlrVirtualPointerV1);
+    wayland_client::delegate_noop!(Globals: ZwpVirtualKeyboardManagerV1);
+    wayland_client::delegate_noop!(Globals: ZwpVirtualKeyboardV1);
+
+    /// Connection state, guarded by one lock so events stay ordered.
+    struct Devices {
+        queue: EventQueue<Globals>,
+        globals: Globals,
+        pointer: ZwlrVirtualPointerV1,
+        keyboard: ZwpVirtualKeyboardV1,
+        /// Last position we moved the pointer to
+        position: (i32, i32),
+    }
+
+    impl Devices {
+        fn move_to(&mut self, time: u32, x: i32, y: i32) -> InputResult<()> {
+            let (width, height) = self.globals.extent();
+            if x < 0 || y < 0 || x as u32 > width || y as u32 > height {
+                return Err(InputError::OutOfBounds(x, y));
+            }
+            self.pointer
+                .motion_absolute(time, x as u32, y as u32, width, height);
+            self.pointer.frame();
+            self.position = (x, y);
+            Ok(())
+        }
+
+        fn button(&self, time: u32, button: MouseButton, pressed: bool) {
+            let state = if pressed {
+                wl_pointer::ButtonState::Pressed
+            } else {
+                wl_pointer::ButtonState::Released
+            };
+            self.pointer.button(time, button_code(button), state);
+            self.pointer.frame();
+        }
+
+        fn key(&self, time: u32, code: u32, pressed: bool) {
+            let state = if pressed { KEY_PRESSED } else { KEY_RELEASED };
+            self.keyboard.key(time, code, state);
+        }
+
+        /// Press and release a key, holding Shift around it if needed.
+        fn tap(&self, time: u32, (code, shift): (u32, bool)) {
+            if shift {
+                self.key(time, KEY_LEFTSHIFT, true);
+            }
+            self.key(time, code, true);
+            self.key(time, code, false);
+            if shift {
+                self.key(time, KEY_LEFTSHIFT, false);
+            }
+        }
+
+        fn modifiers(&self, time: u32, modifiers: &[Modifier], pressed: bool) {
+            if pressed {
+                for modifier in modifiers {
+                    self.key(time, modifier_keycode(*modifier), true);
+                }
+            } else {
+                for modifier in modifiers.iter().rev() {
+                    self.key(time, modifier_keycode(*modifier), false);
+                }
+            }
+        }
+    }
+
+    /// Input simulator speaking the Wayland virtual-input protocols.
+    ///
+    /// Wayland does not let clients read the global pointer position, so
+    /// `mouse_position` reports where this simulator last moved the pointer.
+    pub struct WaylandSimulator {
+        connection: Connection,
+        devices: Mutex<Devices>,
+        started: Instant,
+        delay: Duration,
+    }
+
+    impl WaylandSimulator {
+        /// Connect to the compositor named by `WAYLAND_DISPLAY` and create a
+        /// virtual pointer and keyboard on its first seat.
+        pub fn connect() -> InputResult<Self> {
+            let connection = Connection::connect_to_env().map_err(protocol_error)?;
+            let mut queue = connection.new_event_queue();
+            let qh = queue.handle();
+            connection.display().get_registry(&qh, ());
+
+            let mut globals = Globals::default();
+            // First roundtrip binds the globals, the second collects output modes
+            queue.roundtrip(&mut globals).map_err(protocol_error)?;
+            queue.roundtrip(&mut globals).map_err(protocol_error)?;
+
+            let seat = globals
+                .seat
+                .clone()
+                .ok_or_else(|| protocol_error("compositor has no seat"))?;
+            let pointer_manager = globals.pointer_manager.clone().ok_or_else(|| {
+                protocol_error("compositor does not support zwlr_virtual_pointer_manager_v1")
+            })?;
+            let keyboard_manager = globals.keyboard_manager.clone().ok_or_else(|| {
+                protocol_error("compositor does not support zwp_virtual_keyboard_manager_v1")
+            })?;
+
+            let pointer = pointer_manager.create_virtual_pointer(Some(&seat), &qh, ());
+            let keyboard = keyboard_manager.create_virtual_keyboard(&seat, &qh, ());
+
+            let mut keymap = tempfile::tempfile()
+                .map_err(|e| protocol_error(format!("keymap file: {}", e)))?;
+            keymap
+                .write_all(KEYMAP.as_bytes())
+                .and_then(|_| keymap.write_all(&[0]))
+                .map_err(|e| protocol_error(format!("keymap file: {}", e)))?;
+            keyboard.keymap(
+                wl_keyboard::KeymapFormat::XkbV1.into(),
+                keymap.as_fd(),
+                KEYMAP.len() as u32 + 1,
+            );
+
+            // Surfaces protocol errors such as an unauthorized client
+            queue.roundtrip(&mut globals).map_err(protocol_error)?;
+
+            Ok(Self {
+                connection,
+                devices: Mutex::new(Devices {
+                    queue,
+                    globals,
+                    pointer,
+                    keyboard,
+                    position: (0, 0),
+                }),
+                started: Instant::now(),
+                delay: Duration::from_millis(10),
+            })
+        }
+
+        /// Set delay between actions.
+        pub fn with_delay(mut self, delay: Duration) -> Self {
+            self.delay = delay;
+            self
+        }
+
+        /// Run `f` with the devices and a protocol timestamp, then send the
+        /// requests it queued.
+        fn with_devices<R>(
+            &self,
+            f: impl FnOnce(&mut Devices, u32) -> InputResult<R>,
+        ) -> InputResult<R> {
+            let mut devices = self.devices.lock().map_err(|e| {
+                InputError::SimulationFailed(format!("Failed to lock Wayland devices: {}", e))
+            })?;
+            let Devices { queue, globals, .. } = &mut *devices;
+            queue.dispatch_pending(globals).map_err(protocol_error)?;
+
+            let time = self.started.elapsed().as_millis() as u32;
+            let result = f(&mut devices, time)?;
+            self.connection.flush().map_err(protocol_error)?;
+            Ok(result)
+        }
+    }
+
+    #[async_trait]
+    impl InputSimulator for WaylandSimulator {
+        fn is_available(&self) -> bool {
+            true
+        }
+
+        fn backend_name(&self) -> &'static str {
+            "wayland"
+        }
+
+        async fn mouse_position(&self) -> InputResult<(i32, i32)> {
+            self.with_devices(|devices, _| Ok(devices.position))
+        }
+
+        async fn mouse_move(&self, x: i32, y: i32) -> InputResult<()> {
+            self.with_devices(|devices, time| devices.move_to(time, x, y))?;
+            tokio::time::sleep(self.delay).await;
+            Ok(())
+        }
+
+        async fn mouse_move_smooth(&self, x: i32, y: i32, duration: Duration) -> InputResult<()> {
+            let (start_x, start_y) = self.mouse_position().await?;
+            let steps = 20;
+            let step_delay = duration / steps;
+
+            for i in 1..=steps {
+                let progress = i as f64 / steps as f64;
+                // Use ease-in-out curve
+                let eased = if progress < 0.5 {
+                    2.0 * progress * progress
+                } else {
+                    1.0 - (-2.0 * progress + 2.0).powi(2) / 2.0
+                };
+
+                let current_x = start_x + ((x - start_x) as f64 * eased) as i32;
+                let current_y = start_y + ((y - start_y) as f64 * eased) as i32;
+                self.with_devices(|devices, time| devices.move_to(time, current_x, current_y))?;
+
+                tokio::time::sleep(step_delay).await;
+            }
+
+            Ok(())
+        }
+
+        async fn mouse_click(&self, action: &MouseAction) -> InputResult<()> {
+            self.with_devices(|devices, time| {
+                devices.move_to(time, action.x, action.y)?;
+                devices.modifiers(time, &action.modifiers, true);
+                match action.click_type {
+                    ClickType::Single | ClickType::Double | ClickType::Triple => {
+                        let clicks = match action.click_type {
+                            ClickType::Double => 2,
+                            ClickType::Triple => 3,
+                            _ => 1,
+                        };
+                        for _ in 0..clicks {
+                            devices.button(time, action.button, true);
+                            devices.button(time, action.button, false);
+                        }
+                    }
+                    ClickType::Press => devices.button(time, action.button, true),
+                    ClickType::Release => devices.button(time, action.button, false),
+                }
+                devices.modifiers(time, &action.modifiers, false);
+                Ok(())
+            })?;
+
+            tokio::time::sleep(self.delay).await;
+            Ok(())
+        }
+
+        async fn mouse_drag(&self, drag: &DragOperation) -> InputResult<()> {
+            self.with_devices(|devices, time| {
+                devices.modifiers(time, &drag.modifiers, true);
+                devices.move_to(time, drag.start_x, drag.start_y)?;
+                devices.button(time, drag.button, true);
+                Ok(())
+            })?;
+
+            self.mouse_move_smooth(drag.end_x, drag.end_y, drag.duration)
+                .await?;
+
+            self.with_devices(|devices, time| {
+                devices.button(time, drag.button, false);
+                devices.modifiers(time, &drag.modifiers, false);
+                Ok(())
+            })?;
+
+            tokio::time::sleep(self.delay).await;
+            Ok(())
+        }
+
+        async fn mouse_scroll(&self, scroll: &ScrollAction) -> InputResult<()> {
+            self.with_devices(|devices, time| {
+                devices.move_to(time, scroll.x, scroll.y)?;
+                // One wheel click is 15 units of scroll, as with libinput
+                for (axis, delta) in [
+                    (wl_pointer::Axis::VerticalScroll, scroll.delta_y),
+                    (wl_pointer::Axis::HorizontalScroll, scroll.delta_x),
+                ] {
+                    if delta != 0 {
+                        devices
+                            .pointer
+                            .axis_discrete(time, axis, delta as f64 * 15.0, delta);
+                    }
+                }
+                devices.pointer.frame();
+                Ok(())
+            })?;
+
+            tokio::time::sleep(self.delay).await;
+            Ok(())
+        }
+
+        async fn type_text(&self, text: &str) -> InputResult<()> {
+            // Check every character before sending any of them
+            let codes = text
+                .chars()
+                .map(|c| evdev_input(&KeyInput::Char(c)))
+                .collect::<InputResult<Vec<_>>>()?;
+
+            self.with_devices(|devices, time| {
+                for code in codes {
+                    devices.tap(time, code);
+                }
+                Ok(())
+            })?;
+
+            tokio::time::sleep(self.delay).await;
+            Ok(())
+        }
+
+        async fn key_press(&self, key: KeyInput) -> InputResult<()> {
+            let code = evdev_input(&key)?;
+            self.with_devices(|devices, time| {
+                devices.tap(time, code);
+                Ok(())
+            })?;
+
+            tokio::time::sleep(self.delay).await;
+            Ok(())
+        }
+
+        async fn key_down(&self, key: KeyInput) -> InputResult<()> {
+            let (code, _) = evdev_input(&key)?;
+            self.with_devices(|devices, time| {
+                devices.key(time, code, true);
+                Ok(())
+            })?;
+
+            tokio::time::sleep(self.delay).await;
+            Ok(())
+        }
+
+        async fn key_up(&self, key: KeyInput) -> InputResult<()> {
+            let (code, _) = evdev_input(&key)?;
+            self.with_devices(|devices, time| {
+                devices.key(time, code, false);
+                Ok(())
+            })?;
+
+            tokio::time::sleep(self.delay).await;
+            Ok(())
+        }
+
+        async fn shortcut(&self, shortcut: &KeyboardShortcut) -> InputResult<()> {
+            let (code, _) = evdev_input(&shortcut.key)?;
+            self.with_devices(|devices, time| {
+                devices.modifiers(time, &shortcut.modifiers, true);
+                devices.key(time, code, true);
+                devices.key(time, code, false);
+                devices.modifiers(time, &shortcut.modifiers, false);
+                Ok(())
+            })?;
+
+            tokio::time::sleep(self.delay).await;
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn test_evdev_char_table() {
+            assert_eq!(evdev_char('a'), Some((30, false)));
+            assert_eq!(evdev_char('Q'), Some((16, true)));
+            assert_eq!(evdev_char('1'), Some((2, false)));
+            assert_eq!(evdev_char('0'), Some((11, false)));
+            assert_eq!(evdev_char('?'), Some((53, true)));
+            assert_eq!(evdev_char('é'), None);
+            assert_eq!(evdev_keycode(Key::Enter), 28);
+            assert_eq!(evdev_keycode(Key::Super), evdev_keycode(Key::Meta));
+        }
+
+        #[test]
+        fn test_extent_is_in_logical_pixels() {
+            // A 4K laptop panel at 2x with a 1080p monitor to its right
+            let output = |x, y, mode_width, mode_height| OutputGeometry {
+                x,
+                y,
+                mode_width,
+                mode_height,
+                ..Default::default()
+            };
+            let laptop = OutputGeometry { scale: 2, ..output(0, 0, 3840, 2160) };
+            let monitor = output(1920, 0, 1920, 1080);
+            assert_eq!(logical_extent([laptop, monitor].iter()), (3840, 1080));
+
+            // A portrait monitor stacked below
+            let portrait = OutputGeometry { rotated: true, ..output(0, 1080, 1920, 1080) };
+            assert_eq!(logical_extent([laptop, portrait].iter()), (1920, 3000));
+
+            assert_eq!(logical_extent(std::iter::empty()), (1, 1));
+        }
+    }
+}
+
+/// Try the native Wayland backend. `None` outside a Wayland session.
+fn try_wayland() -> Option<InputResult<Box<dyn InputSimulator>>> {
+    std::env::var_os("WAYLAND_DISPLAY")?;
+
+    #[cfg(feature = "wayland")]
+    let result = wayland::WaylandSimulator::connect()
+        .map(|simulator| Box::new(simulator) as Box<dyn InputSimulator>);
+    #[cfg(not(feature = "wayland"))]
+    let result = Err(InputError::SimulationFailed(
+        "built without the `wayland` feature".to_string(),
+    ));
+
+    Some(result)
+}
+
+/// Create the input simulator for the current session.
+///
+/// On Wayland the native virtual-input backend is tried first; enigo is the
+/// fallback, but there it only reaches X11/XWayland windows.
 #[cfg(feature = "gui-automation")]
-pub fn create_input_simulator() -> InputResult<impl InputSimulator> {
-    platform::EnigoSimulator::new()
+pub fn create_input_simulator() -> InputResult<Box<dyn InputSimulator>> {
+    let wayland_error = match try_wayland() {
+        Some(Ok(simulator)) => return Ok(simulator),
+        Some(Err(e)) => Some(e),
+        None => None,
+    };
+
+    match platform::EnigoSimulator::new() {
+        Ok(simulator) => {
+            if let Some(e) = wayland_error {
+                tracing::warn!(
+                    "Wayland virtual input unavailable ({}); using enigo, which only reaches X11/XWayland windows",
+                    e
+                );
+            }
+            Ok(Box::new(simulator))
+        }
+        Err(e) => Err(InputError::NoBackend(match wayland_error {
+            Some(wayland) => format!("wayland: {}; enigo: {}", wayland, e),
+            None => format!("enigo: {}", e),
+        })),
+    }
 }
 
 /// Mock input simulator for testing.
@@ -994,6 +1886,10 @@ pub mod mock {
             false
         }
 
+        fn backend_name(&self) -> &'static str {
+            "mock"
+        }
+
         async fn mouse_position(&self) -> InputResult<(i32, i32)> {
             Err(InputError::NotAvailable)
         }
@@ -1046,8 +1942,15 @@ pub mod mock {
 }
 
 #[cfg(not(feature = "gui-automation"))]
-pub fn create_input_simulator() -> InputResult<impl InputSimulator> {
-    Ok(mock::MockSimulator)
+pub fn create_input_simulator() -> InputResult<Box<dyn InputSimulator>> {
+    match try_wayland() {
+        Some(Ok(simulator)) => Ok(simulator),
+        Some(Err(e)) => {
+            tracing::warn!("Wayland virtual input unavailable: {}", e);
+            Ok(Box::new(mock::MockSimulator))
+        }
+        None => Ok(Box::new(mock::MockSimulator)),
+    }
 }
 
 #[cfg(test)]
@@ -9,10 +9,13 @@
 //!
 //! - **Screen Capture**: Platform-abstracted screen capture with multi-monitor support
 //! - **Image Analysis**: Vision model integration (GPT-4V, Claude, Gemini) for UI analysis
-//! - **Input Simulation**: Mouse and keyboard input simulation across platforms
+//! - **OCR Fallback**: Optional tesseract pass for text the vision model misses (`ocr` feature)
+//! - **Input Simulation**: Mouse and keyboard input simulation across platforms, with
+//!   native Wayland virtual input (`wayland` feature)
 //! - **Application Control**: Window focus, management, and app-specific action patterns
 //! - **Action Planning**: AI-powered task planning with verification and error recovery
 //! - **Safety Controls**: Rate limiting, whitelisting, confirmation, and audit logging
+//! - **Control Overlay**: Border that follows the window being controlled
 //!
 //! ## Quick Start
 //!
@@ -64,6 +67,8 @@ pub mod apps;
 pub mod capture;
 pub mod config;
 pub mod input;
+pub mod ocr;
+pub mod overlay;
 pub mod planner;
 pub mod safety;
 
@@ -77,24 +82,27 @@ pub use apps::{
     AppState, DefaultAppController,
 };
 pub use capture::{
-    CaptureError, CaptureResult, MonitorInfo, Region, ScreenCapture, Screenshot, WindowInfo,
+    bounding_region, hash_region, stitch_screenshots, CaptureError, CaptureResult, Dimensions,
+    MonitorInfo, Region, ScreenCapture, Screenshot, WindowInfo,
 };
 pub use config::{
-    AppListConfig, AppListMode, CaptureSettings, ConfigError, ConfirmationSettings, ImageFormat,
-    KnownApp, SafetyLimits, VisionConfig, VisionModel,
+    AppListConfig, AppListMode, AuditRotationSettings, CaptureSettings, ChangeDetectionSettings, ConfigError,
+    ConfirmationSettings, ImageFormat, KnownApp, OverlaySettings, SafetyLimits, VisionConfig,
+    VisionModel,
 };
 pub use input::{
     ClickType, DragOperation, InputError, InputResult, InputSimulator, Key, KeyInput,
     KeyboardShortcut, Modifier, MouseAction, MouseButton, ScrollAction,
 };
+pub use overlay::{ControlOverlay, OverlayEvent, ScreenBorder};
 pub use planner::{
     ActionPlan, ConfirmationHandler, ConfirmationRequest, ExecutionContext, ExecutionEvent,
     ExecutionEventType, ExecutionStatus, PlanStep, PlannedAction, PlannerError, PlannerResult,
     ScrollDirection, VisionTask,
 };
 pub use safety::{
-    ActionType, AuditEntry, AuditLogger, EmergencyStopMonitor, SafetyError, SafetyGuard,
-    SafetyResult, SafetyStats,
+    ActionType, AuditEntry, AuditFilter, AuditLogger, BucketLevel, EmergencyStopMonitor, SafetyError,
+    SafetyGuard, SafetyResult, SafetyStats,
 };
 
 use std::sync::Arc;
@@ -209,7 +217,7 @@ impl VisionSystem {
 
     /// Create an input simulator instance.
     #[cfg(feature = "gui-automation")]
-    pub fn create_input(&self) -> Result<impl InputSimulator> {
+    pub fn create_input(&self) -> Result<Box<dyn InputSimulator>> {
         input::create_input_simulator().map_err(VisionError::InputError)
     }
 
@@ -284,6 +292,12 @@ impl VisionSystemBuilder {
         self
     }
 
+    /// Enable the OCR fallback.
+    pub fn ocr_fallback(mut self, enabled: bool) -> Self {
+        self.config.ocr_fallback = enabled;
+        self
+    }
+
     /// Set dry-run mode.
     pub fn dry_run(mut self, dry_run: bool) -> Self {
         self.config.dry_run = dry_run;
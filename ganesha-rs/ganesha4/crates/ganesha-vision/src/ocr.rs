@@ -0,0 +1,151 @@
+//! OCR fallback for text the vision model fails to transcribe.
+//!
+//! Local vision models often skip small UI text. When `VisionConfig::ocr_fallback`
+//! is set, the planner runs an OCR pass over the full-resolution screenshot and
+//! merges the lines it finds into `ScreenAnalysis::text_blocks`
+//! (see `ScreenAnalysis::merge_ocr_text`).
+//!
+//! OCR is done by the `tesseract` command-line tool and is only compiled in
+//! with the `ocr` feature.
+
+use crate::analysis::{AnalysisError, AnalysisResult, ExtractedText};
+use crate::capture::{Region, Screenshot};
+use std::collections::BTreeMap;
+
+/// Run OCR over the screenshot at full resolution.
+///
+/// Returns one block per recognized line, in screenshot pixel coordinates.
+#[cfg(feature = "ocr")]
+pub async fn recognize_text(screenshot: &Screenshot) -> AnalysisResult<Vec<ExtractedText>> {
+    use std::process::Stdio;
+    use tokio::io::AsyncWriteExt;
+
+    let mut png = std::io::Cursor::new(Vec::new());
+    screenshot
+        .image
+        .write_to(&mut png, image::ImageFormat::Png)
+        .map_err(|e| AnalysisError::ModelError(format!("OCR encode: {}", e)))?;
+
+    let mut child = tokio::process::Command::new("tesseract")
+        .args(["stdin", "stdout", "tsv"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| AnalysisError::ModelError(format!("failed to run tesseract: {}", e)))?;
+
+    let mut stdin = child
+        .stdin
+        .take()
+        .ok_or_else(|| AnalysisError::ModelError("tesseract stdin unavailable".to_string()))?;
+    stdin
+        .write_all(&png.into_inner())
+        .await
+        .map_err(|e| AnalysisError::ModelError(format!("tesseract stdin: {}", e)))?;
+    drop(stdin);
+
+    let output = child
+        .wait_with_output()
+        .await
+        .map_err(|e| AnalysisError::ModelError(format!("tesseract: {}", e)))?;
+    if !output.status.success() {
+        return Err(AnalysisError::ModelError(format!(
+            "tesseract exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(parse_tesseract_tsv(&String::from_utf8_lossy(&output.stdout)))
+}
+
+/// Run OCR over the screenshot at full resolution.
+///
+/// Always fails: this build has no OCR support.
+#[cfg(not(feature = "ocr"))]
+pub async fn recognize_text(_screenshot: &Screenshot) -> AnalysisResult<Vec<ExtractedText>> {
+    Err(AnalysisError::ModelError(
+        "OCR support not compiled in (enable the `ocr` feature)".to_string(),
+    ))
+}
+
+/// Parse `tesseract ... tsv` output into one block per line of text.
+///
+/// Words are grouped by block/paragraph/line number; each line's bounds are
+/// the union of its words and its confidence is their mean, scaled to 0.0-1.0.
+pub fn parse_tesseract_tsv(tsv: &str) -> Vec<ExtractedText> {
+    struct Line {
+        words: Vec<String>,
+        bounds: Region,
+        confidence_sum: f32,
+    }
+
+    // Keyed by (block, paragraph, line) so lines come out in reading order
+    let mut lines: BTreeMap<(u32, u32, u32), Line> = BTreeMap::new();
+
+    for row in tsv.lines().skip(1) {
+        let cols: Vec<&str> = row.split('\t').collect();
+        if cols.len() < 12 || cols[0] != "5" {
+            continue;
+        }
+        let text = cols[11].trim();
+        let confidence: f32 = cols[10].parse().unwrap_or(-1.0);
+        if text.is_empty() || confidence < 0.0 {
+            continue;
+        }
+        let num = |i: usize| cols[i].parse::<u32>().unwrap_or(0);
+        let bounds = Region::new(num(6) as i32, num(7) as i32, num(8), num(9));
+
+        let line = lines.entry((num(2), num(3), num(4))).or_insert_with(|| Line {
+            words: Vec::new(),
+            bounds,
+            confidence_sum: 0.0,
+        });
+        line.words.push(text.to_string());
+        line.bounds = union(line.bounds, bounds);
+        line.confidence_sum += confidence;
+    }
+
+    lines
+        .into_values()
+        .map(|line| ExtractedText {
+            confidence: line.confidence_sum / line.words.len() as f32 / 100.0,
+            text: line.words.join(" "),
+            bounds: line.bounds,
+            is_word: false,
+        })
+        .collect()
+}
+
+fn union(a: Region, b: Region) -> Region {
+    let x = a.x.min(b.x);
+    let y = a.y.min(b.y);
+    let right = (a.x + a.width as i32).max(b.x + b.width as i32);
+    let bottom = (a.y + a.height as i32).max(b.y + b.height as i32);
+    Region::new(x, y, (right - x) as u32, (bottom - y) as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tesseract_tsv_groups_lines() {
+        let tsv = "level\tpage_num\tblock_num\tpar_num\tline_num\tword_num\tleft\ttop\twidth\theight\tconf\ttext\n\
+            1\t1\t0\t0\t0\t0\t0\t0\t800\t600\t-1\t\n\
+            4\t1\t1\t1\t1\t0\t10\t20\t90\t12\t-1\t\n\
+            5\t1\t1\t1\t1\t1\t10\t20\t40\t12\t96\tSave\n\
+            5\t1\t1\t1\t1\t2\t55\t21\t45\t11\t90\tFile\n\
+            5\t1\t2\t1\t1\t1\t300\t400\t30\t10\t80\tOK\n\
+            5\t1\t2\t1\t2\t1\t300\t420\t30\t10\t70\t \n";
+
+        let lines = parse_tesseract_tsv(tsv);
+
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0].text, "Save File");
+        assert_eq!(lines[0].bounds, Region::new(10, 20, 90, 12));
+        assert!((lines[0].confidence - 0.93).abs() < 1e-6);
+        assert_eq!(lines[1].text, "OK");
+        assert!(!lines[1].is_word);
+    }
+}
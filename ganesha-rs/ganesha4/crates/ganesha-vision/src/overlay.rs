@@ -0,0 +1,325 @@
+//! Control overlay that frames the application being controlled.
+//!
+//! This module provides:
+//! - `ScreenBorder` - border geometry around a screen region
+//! - `ControlOverlay` - keeps the border on the targeted app's window as it
+//!   moves or resizes, falling back to the whole screen when nothing is targeted
+//! - `OverlayEvent` - broadcast on every reposition
+//!
+//! Drawing is left to the frontend (e.g. the desktop app); subscribe to the
+//! events and place the four edge windows from `ScreenBorder::edges`.
+
+use crate::apps::{AppController, AppResult};
+use crate::capture::Region;
+use crate::config::OverlaySettings;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, RwLock};
+
+/// A border of `width` pixels drawn just inside `frame`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScreenBorder {
+    /// Region the border surrounds
+    pub frame: Region,
+    /// Border thickness in pixels
+    pub width: u32,
+}
+
+impl ScreenBorder {
+    /// Create a border around a region.
+    pub fn new(frame: Region, width: u32) -> Self {
+        Self { frame, width }
+    }
+
+    /// Top, bottom, left and right edge rectangles.
+    pub fn edges(&self) -> [Region; 4] {
+        let Region {
+            x,
+            y,
+            width,
+            height,
+        } = self.frame;
+        let w = self.width.min(width).min(height);
+        [
+            Region::new(x, y, width, w),
+            Region::new(x, y + (height - w) as i32, width, w),
+            Region::new(x, y, w, height),
+            Region::new(x + (width - w) as i32, y, w, height),
+        ]
+    }
+}
+
+/// Emitted each time the overlay border moves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OverlayEvent {
+    /// The border now frames the targeted app's window
+    FollowingWindow {
+        app_name: String,
+        border: ScreenBorder,
+    },
+    /// No app is targeted (or its window is not visible); the border frames
+    /// the whole screen
+    FullScreen { border: ScreenBorder },
+}
+
+impl OverlayEvent {
+    /// The new border position.
+    pub fn border(&self) -> ScreenBorder {
+        match self {
+            Self::FollowingWindow { border, .. } | Self::FullScreen { border } => *border,
+        }
+    }
+}
+
+/// Overlay border that tracks the window of the app being controlled.
+pub struct ControlOverlay<A: AppController> {
+    controller: Arc<A>,
+    screen: Region,
+    settings: OverlaySettings,
+    target: RwLock<Option<String>>,
+    border: RwLock<ScreenBorder>,
+    running: AtomicBool,
+    events: broadcast::Sender<OverlayEvent>,
+}
+
+impl<A: AppController + 'static> ControlOverlay<A> {
+    /// Create an overlay for a screen of the given size. It starts out
+    /// framing the whole screen.
+    pub fn new(controller: Arc<A>, screen: Region, settings: OverlaySettings) -> Self {
+        let (events, _) = broadcast::channel(64);
+        Self {
+            controller,
+            border: RwLock::new(ScreenBorder::new(screen, settings.border_width)),
+            screen,
+            settings,
+            target: RwLock::new(None),
+            running: AtomicBool::new(false),
+            events,
+        }
+    }
+
+    /// Receive an `OverlayEvent` for every reposition.
+    pub fn subscribe(&self) -> broadcast::Receiver<OverlayEvent> {
+        self.events.subscribe()
+    }
+
+    /// Set the app to follow, or `None` for a full-screen border. Takes
+    /// effect on the next `update`.
+    pub async fn set_target(&self, app_name: Option<String>) {
+        *self.target.write().await = app_name;
+    }
+
+    /// Current border position.
+    pub async fn border(&self) -> ScreenBorder {
+        *self.border.read().await
+    }
+
+    /// Re-read the target window's geometry and move the border if needed.
+    /// Returns the event emitted, if the border moved.
+    pub async fn update(&self) -> AppResult<Option<OverlayEvent>> {
+        let target = self.target.read().await.clone();
+        let window = match &target {
+            Some(name) => self
+                .controller
+                .find_app(name)
+                .await?
+                .and_then(|app| app.window)
+                .filter(|w| w.is_visible && !w.is_minimized && w.region.is_valid()),
+            None => None,
+        };
+
+        let event = match (target, window) {
+            (Some(app_name), Some(window)) => OverlayEvent::FollowingWindow {
+                app_name,
+                border: ScreenBorder::new(window.region, self.settings.border_width),
+            },
+            _ => OverlayEvent::FullScreen {
+                border: ScreenBorder::new(self.screen, self.settings.border_width),
+            },
+        };
+
+        let mut border = self.border.write().await;
+        if *border == event.border() {
+            return Ok(None);
+        }
+        *border = event.border();
+        drop(border);
+
+        tracing::debug!("Overlay repositioned: {:?}", event);
+        // No subscribers is fine
+        let _ = self.events.send(event.clone());
+        Ok(Some(event))
+    }
+
+    /// Poll the target window every `poll_interval_ms` until `stop` is called.
+    pub fn spawn(self: Arc<Self>) -> tokio::task::JoinHandle<()> {
+        self.running.store(true, Ordering::SeqCst);
+        tokio::spawn(async move {
+            let mut interval =
+                tokio::time::interval(Duration::from_millis(self.settings.poll_interval_ms.max(1)));
+            loop {
+                interval.tick().await;
+                if !self.running.load(Ordering::SeqCst) {
+                    break;
+                }
+                if let Err(e) = self.update().await {
+                    tracing::warn!("Overlay geometry update failed: {}", e);
+                }
+            }
+        })
+    }
+
+    /// Stop the polling loop started by `spawn`.
+    pub fn stop(&self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::apps::{AppAction, AppInfo, AppState};
+    use crate::capture::WindowInfo;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    /// Controller with one app, "Blender", whose window can be moved
+    struct FakeController {
+        window: Mutex<Option<Region>>,
+    }
+
+    impl FakeController {
+        fn blender(&self) -> Option<AppInfo> {
+            let region = (*self.window.lock().unwrap())?;
+            Some(AppInfo {
+                name: "Blender".to_string(),
+                process_name: "blender".to_string(),
+                pid: 1,
+                window: Some(WindowInfo {
+                    id: 1,
+                    title: "Blender".to_string(),
+                    process_name: "blender".to_string(),
+                    pid: 1,
+                    region,
+                    is_minimized: false,
+                    is_visible: true,
+                }),
+                state: AppState::Focused,
+                known_config: None,
+            })
+        }
+    }
+
+    #[async_trait]
+    impl AppController for FakeController {
+        async fn list_running(&self) -> AppResult<Vec<AppInfo>> {
+            Ok(self.blender().into_iter().collect())
+        }
+        async fn find_app(&self, name: &str) -> AppResult<Option<AppInfo>> {
+            Ok(self.blender().filter(|app| app.name == name))
+        }
+        async fn find_by_process(&self, process_name: &str) -> AppResult<Option<AppInfo>> {
+            Ok(self.blender().filter(|app| app.process_name == process_name))
+        }
+        async fn focus(&self, _app: &AppInfo) -> AppResult<()> {
+            Ok(())
+        }
+        async fn minimize(&self, _app: &AppInfo) -> AppResult<()> {
+            Ok(())
+        }
+        async fn maximize(&self, _app: &AppInfo) -> AppResult<()> {
+            Ok(())
+        }
+        async fn restore(&self, _app: &AppInfo) -> AppResult<()> {
+            Ok(())
+        }
+        async fn move_window(&self, _app: &AppInfo, _x: i32, _y: i32) -> AppResult<()> {
+            Ok(())
+        }
+        async fn resize_window(&self, _app: &AppInfo, _width: u32, _height: u32) -> AppResult<()> {
+            Ok(())
+        }
+        async fn wait_for_ready(&self, _app: &AppInfo, _timeout: Duration) -> AppResult<()> {
+            Ok(())
+        }
+        async fn execute_action(&self, _app: &AppInfo, _action: &AppAction) -> AppResult<()> {
+            Ok(())
+        }
+        async fn get_focused(&self) -> AppResult<Option<AppInfo>> {
+            Ok(self.blender())
+        }
+    }
+
+    async fn recv(events: &mut broadcast::Receiver<OverlayEvent>) -> OverlayEvent {
+        tokio::time::timeout(Duration::from_secs(2), events.recv())
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_screen_border_edges() {
+        let border = ScreenBorder::new(Region::new(100, 50, 800, 600), 4);
+        assert_eq!(
+            border.edges(),
+            [
+                Region::new(100, 50, 800, 4),
+                Region::new(100, 646, 800, 4),
+                Region::new(100, 50, 4, 600),
+                Region::new(896, 50, 4, 600),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_overlay_follows_target_window() {
+        let screen = Region::new(0, 0, 1920, 1080);
+        let controller = Arc::new(FakeController {
+            window: Mutex::new(Some(Region::new(100, 100, 800, 600))),
+        });
+        let overlay = Arc::new(ControlOverlay::new(
+            controller.clone(),
+            screen,
+            OverlaySettings {
+                border_width: 4,
+                poll_interval_ms: 10,
+            },
+        ));
+        let mut events = overlay.subscribe();
+
+        // Nothing targeted: already full screen, nothing to move
+        assert_eq!(overlay.update().await.unwrap(), None);
+
+        overlay.set_target(Some("Blender".to_string())).await;
+        let handle = overlay.clone().spawn();
+
+        assert_eq!(
+            recv(&mut events).await,
+            OverlayEvent::FollowingWindow {
+                app_name: "Blender".to_string(),
+                border: ScreenBorder::new(Region::new(100, 100, 800, 600), 4),
+            }
+        );
+
+        // The window moves and grows; the border follows
+        *controller.window.lock().unwrap() = Some(Region::new(300, 200, 1000, 700));
+        assert_eq!(
+            recv(&mut events).await.border(),
+            ScreenBorder::new(Region::new(300, 200, 1000, 700), 4)
+        );
+
+        // The app goes away: back to the whole screen
+        *controller.window.lock().unwrap() = None;
+        assert_eq!(
+            recv(&mut events).await,
+            OverlayEvent::FullScreen {
+                border: ScreenBorder::new(screen, 4),
+            }
+        );
+        assert_eq!(overlay.border().await, ScreenBorder::new(screen, 4));
+
+        overlay.stop();
+        tokio::time::timeout(Duration::from_secs(2), handle).await.unwrap().unwrap();
+    }
+}
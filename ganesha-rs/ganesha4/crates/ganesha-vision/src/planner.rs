@@ -122,8 +122,10 @@ pub struct PlanStep {
     pub description: String,
     /// The action to perform
     pub action: PlannedAction,
-    /// Expected state after this step
-    pub expected_state: Option<String>,
+    /// What the screen should show once this step has taken effect. When set,
+    /// the planner re-captures the screen after the action and checks it.
+    #[serde(alias = "expected_state")]
+    pub expected_outcome: Option<String>,
     /// Whether this step is destructive/irreversible
     pub is_destructive: bool,
     /// Retry count for this step
@@ -139,6 +141,7 @@ pub enum PlannedAction {
         element_description: String,
         #[serde(skip_serializing_if = "Option::is_none")]
         element_id: Option<String>,
+        /// Physical screen pixels, clicked as-is
         #[serde(skip_serializing_if = "Option::is_none")]
         coordinates: Option<(i32, i32)>,
     },
@@ -176,6 +179,24 @@ pub enum PlannedAction {
     },
 }
 
+impl PlannedAction {
+    /// Whether the action's target is fixed before it runs. Actions that
+    /// locate elements on screen at execution time are not.
+    pub fn is_target_resolved(&self) -> bool {
+        match self {
+            Self::ClickElement { coordinates, .. } => coordinates.is_some(),
+            Self::TypeText { target_element, .. } => target_element.is_none(),
+            Self::DragDrop { .. } => false,
+            Self::Shortcut { .. }
+            | Self::WaitFor { .. }
+            | Self::Scroll { .. }
+            | Self::FocusApp { .. }
+            | Self::Verify { .. }
+            | Self::AppAction { .. } => true,
+        }
+    }
+}
+
 /// Scroll direction.
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -204,6 +225,19 @@ pub struct ActionPlan {
 }
 
 impl ActionPlan {
+    /// Destructive steps from the current one on that can be confirmed up
+    /// front: those before the first step whose target is only resolved at
+    /// execution time. Anything after such a step may act on a screen that
+    /// step changed, so it is confirmed when reached instead.
+    pub fn preconfirmable_steps(&self) -> Vec<&PlanStep> {
+        self.steps
+            .iter()
+            .skip(self.current_step)
+            .take_while(|step| step.action.is_target_resolved())
+            .filter(|step| step.is_destructive)
+            .collect()
+    }
+
     /// Create a new action plan.
     pub fn new(task: VisionTask, steps: Vec<PlanStep>) -> Self {
         Self {
@@ -267,6 +301,19 @@ pub struct ConfirmationRequest {
     pub screenshot: Option<String>,
 }
 
+impl ConfirmationRequest {
+    /// Request confirmation of a destructive step.
+    pub fn for_step(step: &PlanStep) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            action_description: step.description.clone(),
+            reason: "This action is marked as destructive and may not be reversible".to_string(),
+            step: step.clone(),
+            screenshot: None,
+        }
+    }
+}
+
 /// Status of plan execution.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -300,6 +347,8 @@ pub struct ExecutionContext {
     pub screenshots: Vec<Screenshot>,
     /// Execution history
     pub history: Vec<ExecutionEvent>,
+    /// Steps not run because the user denied them
+    pub skipped_steps: Vec<u32>,
     /// Start time
     pub started_at: Option<i64>,
     /// End time
@@ -326,6 +375,7 @@ pub enum ExecutionEventType {
     StepCompleted,
     StepFailed,
     StepRetried,
+    StepSkipped,
     VerificationPassed,
     VerificationFailed,
     ConfirmationRequested,
@@ -357,6 +407,17 @@ where
 pub trait ConfirmationHandler: Send + Sync {
     /// Request confirmation from user.
     async fn request_confirmation(&self, request: &ConfirmationRequest) -> bool;
+
+    /// Request confirmation of several actions at once, returning one answer
+    /// per request in order. Override to show them together (approve all /
+    /// approve selected / deny all); the default asks one at a time.
+    async fn confirm_batch(&self, requests: &[ConfirmationRequest]) -> Vec<bool> {
+        let mut answers = Vec::with_capacity(requests.len());
+        for request in requests {
+            answers.push(self.request_confirmation(request).await);
+        }
+        answers
+    }
 }
 
 impl<C, I, A, V> ActionPlanner<C, I, A, V>
@@ -419,19 +480,27 @@ where
             .await
             .map_err(|e| PlannerError::AnalysisFailed(e.to_string()))?;
 
-        let analysis = self
+        let mut analysis = self
             .analyzer
             .analyze(&screenshot, None)
             .await
             .map_err(|e| PlannerError::AnalysisFailed(e.to_string()))?;
 
+        if self.config.ocr_fallback {
+            match crate::ocr::recognize_text(&screenshot).await {
+                Ok(text) => analysis.merge_ocr_text(text),
+                Err(e) => tracing::warn!("OCR fallback skipped: {}", e),
+            }
+        }
+
         Ok((screenshot, analysis))
     }
 
     /// Create an action plan for a task.
     pub async fn create_plan(&self, task: VisionTask) -> PlannerResult<ActionPlan> {
-        // Analyze current screen state
+        // Analyze current screen state; the plan works in screen pixels
         let (screenshot, analysis) = self.analyze_screen().await?;
+        let analysis = analysis.to_screen_coords();
 
         // Ask the vision model to create a plan
         let plan_prompt = format!(
@@ -451,7 +520,7 @@ Create a JSON plan with this structure:
             "step_number": 1,
             "description": "Step description",
             "action": {{"type": "click_element", "element_description": "Button text"}},
-            "expected_state": "What should happen after this step",
+            "expected_outcome": "What the screen should show after this step",
             "is_destructive": false
         }}
     ],
@@ -504,7 +573,10 @@ Available action types:
                             step_number: s["step_number"].as_u64()? as u32,
                             description: s["description"].as_str()?.to_string(),
                             action: serde_json::from_value(s["action"].clone()).ok()?,
-                            expected_state: s["expected_state"].as_str().map(|s| s.to_string()),
+                            expected_outcome: s["expected_outcome"]
+                                .as_str()
+                                .or_else(|| s["expected_state"].as_str())
+                                .map(|s| s.to_string()),
                             is_destructive: s["is_destructive"].as_bool().unwrap_or(false),
                             retries: 0,
                         })
@@ -528,22 +600,17 @@ Available action types:
     }
 
     /// Execute a single step of a plan.
-    async fn execute_step(&self, step: &PlanStep) -> PlannerResult<()> {
+    /// `preconfirmed` skips the confirmation prompt for a step the user
+    /// already approved in a batch.
+    async fn execute_step(&self, step: &PlanStep, preconfirmed: bool) -> PlannerResult<()> {
         if self.check_emergency_stop().await {
             return Err(PlannerError::EmergencyStop);
         }
 
         // Check if confirmation is needed
-        if step.is_destructive {
+        if step.is_destructive && !preconfirmed {
             if let Some(ref handler) = self.confirmation_handler {
-                let request = ConfirmationRequest {
-                    id: uuid::Uuid::new_v4().to_string(),
-                    action_description: step.description.clone(),
-                    reason: "This action is marked as destructive and may not be reversible"
-                        .to_string(),
-                    step: step.clone(),
-                    screenshot: None,
-                };
+                let request = ConfirmationRequest::for_step(step);
 
                 if !handler.request_confirmation(&request).await {
                     return Err(PlannerError::UserCancelled);
@@ -764,6 +831,72 @@ Available action types:
         Ok(())
     }
 
+    /// Check that a step's `expected_outcome` is visible on screen.
+    ///
+    /// This is the post-action reality check, as opposed to confirmation,
+    /// which asks for consent before a destructive action. A mismatch is
+    /// re-checked once after `action_delay_ms` (the UI may still be
+    /// updating); if it still fails, `VerificationFailed` is returned and the
+    /// step fails without its action being run again.
+    async fn verify_outcome(
+        &self,
+        step: &PlanStep,
+        context: &mut ExecutionContext,
+    ) -> PlannerResult<()> {
+        let Some(outcome) = &step.expected_outcome else {
+            return Ok(());
+        };
+
+        for attempt in 0..2 {
+            if attempt > 0 {
+                tokio::time::sleep(Duration::from_millis(self.config.safety.action_delay_ms)).await;
+            }
+            if self.check_emergency_stop().await {
+                return Err(PlannerError::EmergencyStop);
+            }
+
+            let screenshot = self
+                .capture
+                .capture_all()
+                .await
+                .map_err(|e| PlannerError::AnalysisFailed(e.to_string()))?;
+            let response = self
+                .analyzer
+                .ask(
+                    &screenshot,
+                    &format!(
+                        "An action was just performed: {}\nDid the expected state change occur? \
+                         Answer 'YES' or 'NO': {}",
+                        step.description, outcome
+                    ),
+                )
+                .await
+                .map_err(|e| PlannerError::AnalysisFailed(e.to_string()))?;
+
+            if outcome_observed(&response) {
+                context.history.push(ExecutionEvent {
+                    timestamp: chrono::Utc::now().timestamp_millis(),
+                    event_type: ExecutionEventType::VerificationPassed,
+                    description: format!("Step {} verified: {}", step.step_number, outcome),
+                });
+                return Ok(());
+            }
+
+            context.history.push(ExecutionEvent {
+                timestamp: chrono::Utc::now().timestamp_millis(),
+                event_type: ExecutionEventType::VerificationFailed,
+                description: format!(
+                    "Step {} check {}: expected outcome not observed: {}",
+                    step.step_number,
+                    attempt + 1,
+                    outcome
+                ),
+            });
+        }
+
+        Err(PlannerError::VerificationFailed(outcome.clone()))
+    }
+
     /// Execute a complete plan.
     pub async fn execute_plan(&self, mut plan: ActionPlan) -> PlannerResult<ExecutionContext> {
         let mut context = ExecutionContext {
@@ -776,12 +909,57 @@ Available action types:
                 event_type: ExecutionEventType::PlanStarted,
                 description: format!("Started plan for: {}", plan.task.description),
             }],
+            skipped_steps: Vec::new(),
             started_at: Some(chrono::Utc::now().timestamp_millis()),
             ended_at: None,
         };
 
         let start_time = std::time::Instant::now();
 
+        // Ask about every destructive step whose target is already known in
+        // one go; the rest are confirmed as they are reached. Denied steps
+        // are skipped and the approved ones still run.
+        let mut preconfirmed = std::collections::HashSet::new();
+        let mut denied = std::collections::HashSet::new();
+        if let Some(ref handler) = self.confirmation_handler {
+            let requests: Vec<ConfirmationRequest> = plan
+                .preconfirmable_steps()
+                .into_iter()
+                .map(ConfirmationRequest::for_step)
+                .collect();
+            if !requests.is_empty() {
+                context.status = ExecutionStatus::WaitingConfirmation;
+                context.history.push(ExecutionEvent {
+                    timestamp: chrono::Utc::now().timestamp_millis(),
+                    event_type: ExecutionEventType::ConfirmationRequested,
+                    description: format!("Requested confirmation of {} step(s)", requests.len()),
+                });
+
+                let answers = handler.confirm_batch(&requests).await;
+                let (approved, rejected): (Vec<_>, Vec<_>) = requests
+                    .iter()
+                    .zip(answers.iter().chain(std::iter::repeat(&false)))
+                    .partition(|(_, approved)| **approved);
+                let approved: Vec<u32> = approved
+                    .into_iter()
+                    .map(|(request, _)| request.step.step_number)
+                    .collect();
+                context.history.push(ExecutionEvent {
+                    timestamp: chrono::Utc::now().timestamp_millis(),
+                    event_type: ExecutionEventType::ConfirmationReceived,
+                    description: format!(
+                        "Approved {} of {} step(s)",
+                        approved.len(),
+                        requests.len()
+                    ),
+                });
+
+                context.status = ExecutionStatus::Running;
+                preconfirmed.extend(approved);
+                denied.extend(rejected.into_iter().map(|(request, _)| request.step.step_number));
+            }
+        }
+
         while let Some(step) = plan.next_step() {
             // Check timeout
             if start_time.elapsed() > plan.task.timeout {
@@ -807,6 +985,17 @@ Available action types:
                 break;
             }
 
+            if denied.contains(&step.step_number) {
+                context.skipped_steps.push(step.step_number);
+                context.history.push(ExecutionEvent {
+                    timestamp: chrono::Utc::now().timestamp_millis(),
+                    event_type: ExecutionEventType::StepSkipped,
+                    description: format!("Step {} skipped (denied): {}", step.step_number, step.description),
+                });
+                plan.advance();
+                continue;
+            }
+
             context.history.push(ExecutionEvent {
                 timestamp: chrono::Utc::now().timestamp_millis(),
                 event_type: ExecutionEventType::StepStarted,
@@ -817,7 +1006,34 @@ Available action types:
             let mut retries = 0;
             let step_clone = step.clone();
             loop {
-                match self.execute_step(&step_clone).await {
+                let result = match self
+                    .execute_step(&step_clone, preconfirmed.contains(&step_clone.step_number))
+                    .await
+                {
+                    // A destructive action already happened and running it
+                    // again could repeat a delete or submit, so a failed
+                    // check fails the step. Other steps retry as usual.
+                    Ok(()) => match self.verify_outcome(&step_clone, &mut context).await {
+                        Err(PlannerError::VerificationFailed(outcome))
+                            if step_clone.is_destructive =>
+                        {
+                            context.status = ExecutionStatus::Failed;
+                            context.error = Some(format!("Verification failed: {}", outcome));
+                            context.history.push(ExecutionEvent {
+                                timestamp: chrono::Utc::now().timestamp_millis(),
+                                event_type: ExecutionEventType::StepFailed,
+                                description: format!(
+                                    "Step {} ran but its expected outcome was not observed: {}",
+                                    step_clone.step_number, outcome
+                                ),
+                            });
+                            return Ok(context);
+                        }
+                        other => other,
+                    },
+                    Err(e) => Err(e),
+                };
+                match result {
                     Ok(()) => {
                         context.history.push(ExecutionEvent {
                             timestamp: chrono::Utc::now().timestamp_millis(),
@@ -879,7 +1095,11 @@ Available action types:
             context.history.push(ExecutionEvent {
                 timestamp: chrono::Utc::now().timestamp_millis(),
                 event_type: ExecutionEventType::PlanCompleted,
-                description: "Plan completed successfully".to_string(),
+                description: if context.skipped_steps.is_empty() {
+                    "Plan completed successfully".to_string()
+                } else {
+                    format!("Plan completed; skipped denied step(s) {:?}", context.skipped_steps)
+                },
             });
         }
 
@@ -890,9 +1110,18 @@ Available action types:
     }
 }
 
+/// Whether the analyzer's answer to a verification question is affirmative.
+fn outcome_observed(response: &str) -> bool {
+    response.trim_start().to_uppercase().starts_with("YES")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::analysis::{AnalysisResult, ExtractedText, UIElement};
+    use crate::apps::DefaultAppController;
+    use crate::capture::{CaptureError, CaptureResult, MonitorInfo, Region, WindowInfo};
+    use crate::config::AppListConfig;
 
     #[test]
     fn test_vision_task_creation() {
@@ -918,7 +1147,7 @@ mod tests {
                     text: "test".to_string(),
                     target_element: None,
                 },
-                expected_state: None,
+                expected_outcome: None,
                 is_destructive: false,
                 retries: 0,
             },
@@ -928,7 +1157,7 @@ mod tests {
                 action: PlannedAction::Shortcut {
                     shortcut: "Ctrl+S".to_string(),
                 },
-                expected_state: None,
+                expected_outcome: None,
                 is_destructive: false,
                 retries: 0,
             },
@@ -947,6 +1176,112 @@ mod tests {
         assert!(plan.is_complete());
     }
 
+    #[test]
+    fn test_outcome_observed() {
+        assert!(outcome_observed("YES"));
+        assert!(outcome_observed("  yes, the dialog is open"));
+        assert!(!outcome_observed("NO"));
+        assert!(!outcome_observed("No - yes button still visible"));
+        assert!(!outcome_observed(""));
+    }
+
+    #[test]
+    fn test_plan_step_accepts_expected_state_alias() {
+        let step: PlanStep = serde_json::from_value(serde_json::json!({
+            "step_number": 1,
+            "description": "Save",
+            "action": {"type": "shortcut", "shortcut": "Ctrl+S"},
+            "expected_state": "Title bar no longer shows an asterisk",
+            "is_destructive": false,
+            "retries": 0
+        }))
+        .unwrap();
+
+        assert_eq!(
+            step.expected_outcome.as_deref(),
+            Some("Title bar no longer shows an asterisk")
+        );
+    }
+
+    #[test]
+    fn test_preconfirmable_steps_stop_at_unresolved_target() {
+        let step = |n: u32, action: PlannedAction, is_destructive: bool| PlanStep {
+            step_number: n,
+            description: format!("Step {}", n),
+            action,
+            expected_outcome: None,
+            is_destructive,
+            retries: 0,
+        };
+        let shortcut = |s: &str| PlannedAction::Shortcut {
+            shortcut: s.to_string(),
+        };
+        let plan = ActionPlan::new(
+            VisionTask::new("Clean up", "Old files removed"),
+            vec![
+                step(1, shortcut("Ctrl+A"), false),
+                step(2, shortcut("Delete"), true),
+                step(
+                    3,
+                    PlannedAction::ClickElement {
+                        element_description: "Empty trash".to_string(),
+                        element_id: None,
+                        coordinates: Some((40, 40)),
+                    },
+                    true,
+                ),
+                // Located on screen at run time: whatever it opens may change
+                // what the following steps act on
+                step(
+                    4,
+                    PlannedAction::ClickElement {
+                        element_description: "Confirm".to_string(),
+                        element_id: None,
+                        coordinates: None,
+                    },
+                    true,
+                ),
+                step(5, shortcut("Delete"), true),
+            ],
+        );
+
+        let numbers: Vec<u32> = plan
+            .preconfirmable_steps()
+            .iter()
+            .map(|s| s.step_number)
+            .collect();
+        assert_eq!(numbers, vec![2, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_confirm_batch_defaults_to_each_request() {
+        struct ApproveDeletes;
+
+        #[async_trait::async_trait]
+        impl ConfirmationHandler for ApproveDeletes {
+            async fn request_confirmation(&self, request: &ConfirmationRequest) -> bool {
+                request.action_description.contains("Delete")
+            }
+        }
+
+        let step = |description: &str| PlanStep {
+            step_number: 1,
+            description: description.to_string(),
+            action: PlannedAction::Shortcut {
+                shortcut: "Delete".to_string(),
+            },
+            expected_outcome: None,
+            is_destructive: true,
+            retries: 0,
+        };
+        let requests = vec![
+            ConfirmationRequest::for_step(&step("Delete draft")),
+            ConfirmationRequest::for_step(&step("Format disk")),
+        ];
+
+        assert_eq!(ApproveDeletes.confirm_batch(&requests).await, vec![true, false]);
+    }
+
     #[test]
     fn test_planned_action_serialization() {
         let action = PlannedAction::ClickElement {
@@ -959,4 +1294,296 @@ mod tests {
         assert!(json.contains("click_element"));
         assert!(json.contains("Save button"));
     }
+
+    /// Capture that always returns the same blank screen
+    struct StillScreen;
+
+    #[async_trait::async_trait]
+    impl ScreenCapture for StillScreen {
+        fn is_available(&self) -> bool {
+            true
+        }
+
+        async fn get_monitors(&self) -> CaptureResult<Vec<MonitorInfo>> {
+            Ok(vec![])
+        }
+
+        async fn get_primary_monitor(&self) -> CaptureResult<MonitorInfo> {
+            Err(CaptureError::NotAvailable)
+        }
+
+        async fn capture_all(&self) -> CaptureResult<Screenshot> {
+            let image = image::DynamicImage::new_rgba8(64, 64);
+            Ok(Screenshot::new(image, Region::new(0, 0, 64, 64), "still"))
+        }
+
+        async fn capture_monitor(&self, _monitor_index: u32) -> CaptureResult<Screenshot> {
+            self.capture_all().await
+        }
+
+        async fn capture_region(&self, _region: Region) -> CaptureResult<Screenshot> {
+            self.capture_all().await
+        }
+
+        async fn get_windows(&self) -> CaptureResult<Vec<WindowInfo>> {
+            Ok(vec![])
+        }
+
+        async fn find_window_by_title(&self, _title: &str) -> CaptureResult<Option<WindowInfo>> {
+            Ok(None)
+        }
+
+        async fn find_windows_by_process(&self, _process_name: &str) -> CaptureResult<Vec<WindowInfo>> {
+            Ok(vec![])
+        }
+
+        async fn capture_window(&self, _window_id: u64) -> CaptureResult<Screenshot> {
+            self.capture_all().await
+        }
+    }
+
+    /// Analyzer that answers each `ask` with the next scripted reply and
+    /// records the questions it was asked
+    struct ScriptedAnalyzer {
+        replies: std::sync::Mutex<std::collections::VecDeque<String>>,
+        questions: Arc<std::sync::Mutex<Vec<String>>>,
+    }
+
+    impl ScriptedAnalyzer {
+        fn new(replies: &[&str]) -> Self {
+            Self {
+                replies: std::sync::Mutex::new(replies.iter().map(|r| r.to_string()).collect()),
+                questions: Default::default(),
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl VisionAnalyzer for ScriptedAnalyzer {
+        async fn analyze(&self, _screenshot: &Screenshot, _prompt: Option<&str>) -> AnalysisResult<ScreenAnalysis> {
+            Ok(ScreenAnalysis {
+                elements: vec![],
+                text_blocks: vec![],
+                description: "blank".to_string(),
+                app_context: None,
+                raw_response: None,
+                timestamp: 0,
+                source_dimensions: None,
+                screen_dimensions: None,
+            })
+        }
+
+        async fn extract_text(&self, _screenshot: &Screenshot) -> AnalysisResult<Vec<ExtractedText>> {
+            Ok(vec![])
+        }
+
+        async fn find_element(&self, _screenshot: &Screenshot, _description: &str) -> AnalysisResult<Option<UIElement>> {
+            Ok(None)
+        }
+
+        async fn ask(&self, _screenshot: &Screenshot, question: &str) -> AnalysisResult<String> {
+            self.questions.lock().unwrap().push(question.to_string());
+            Ok(self.replies.lock().unwrap().pop_front().unwrap_or_default())
+        }
+    }
+
+    fn verify_step(n: u32, condition: &str) -> PlanStep {
+        PlanStep {
+            step_number: n,
+            description: format!("Check {}", condition),
+            action: PlannedAction::Verify {
+                condition: condition.to_string(),
+            },
+            expected_outcome: None,
+            is_destructive: false,
+            retries: 0,
+        }
+    }
+
+    #[cfg(not(feature = "gui-automation"))]
+    type FactoryInput = Box<dyn InputSimulator>;
+
+    /// A planner on a still screen, with input and app control from the
+    /// factories. With gui-automation the factory needs a real display, so
+    /// tests using this only run without it.
+    #[cfg(not(feature = "gui-automation"))]
+    fn still_planner<V: VisionAnalyzer + 'static>(
+        analyzer: V,
+    ) -> ActionPlanner<StillScreen, FactoryInput, DefaultAppController<StillScreen, FactoryInput>, V> {
+        let input = crate::input::create_input_simulator().unwrap();
+        let apps = DefaultAppController::new(
+            StillScreen,
+            crate::input::create_input_simulator().unwrap(),
+            AppListConfig::default(),
+        );
+        ActionPlanner::new(StillScreen, input, apps, analyzer, VisionConfig::default())
+    }
+
+    #[cfg(not(feature = "gui-automation"))]
+    #[tokio::test]
+    async fn test_planner_accepts_factory_built_parts() {
+        let analyzer: Box<dyn VisionAnalyzer> = Box::new(ScriptedAnalyzer::new(&["VERIFIED"]));
+        let planner = still_planner(analyzer);
+
+        let plan = ActionPlan::new(
+            VisionTask::new("Check the screen", "Screen is blank"),
+            vec![verify_step(1, "screen is blank")],
+        );
+        let context = planner.execute_plan(plan).await.unwrap();
+        assert_eq!(context.status, ExecutionStatus::Completed);
+    }
+
+    #[cfg(not(feature = "gui-automation"))]
+    #[tokio::test]
+    async fn test_denied_steps_are_skipped() {
+        struct DenyFormat;
+
+        #[async_trait::async_trait]
+        impl ConfirmationHandler for DenyFormat {
+            async fn request_confirmation(&self, request: &ConfirmationRequest) -> bool {
+                !request.action_description.contains("format")
+            }
+        }
+
+        let destructive = |n: u32, condition: &str| PlanStep {
+            is_destructive: true,
+            ..verify_step(n, condition)
+        };
+        let analyzer = ScriptedAnalyzer::new(&["VERIFIED", "VERIFIED"]);
+        let questions = analyzer.questions.clone();
+        let planner = still_planner(analyzer).with_confirmation_handler(Box::new(DenyFormat));
+
+        let plan = ActionPlan::new(
+            VisionTask::new("Tidy up", "Drafts removed"),
+            vec![
+                verify_step(1, "drafts folder open"),
+                destructive(2, "disk ready to format"),
+                destructive(3, "drafts deleted"),
+            ],
+        );
+        let context = planner.execute_plan(plan).await.unwrap();
+
+        assert_eq!(context.status, ExecutionStatus::Completed);
+        assert_eq!(context.skipped_steps, vec![2]);
+        assert!(context.plan.is_complete());
+        let asked = questions.lock().unwrap();
+        assert_eq!(asked.len(), 2);
+        assert!(asked.iter().all(|q| !q.contains("format")));
+        assert!(asked[1].contains("drafts deleted"));
+    }
+
+    #[cfg(not(feature = "gui-automation"))]
+    fn empty_context(plan: &ActionPlan) -> ExecutionContext {
+        ExecutionContext {
+            status: ExecutionStatus::Running,
+            plan: plan.clone(),
+            error: None,
+            screenshots: Vec::new(),
+            history: Vec::new(),
+            skipped_steps: Vec::new(),
+            started_at: None,
+            ended_at: None,
+        }
+    }
+
+    #[cfg(not(feature = "gui-automation"))]
+    fn expecting(outcome: &str) -> PlanStep {
+        PlanStep {
+            expected_outcome: Some(outcome.to_string()),
+            ..verify_step(1, "save dialog open")
+        }
+    }
+
+    #[cfg(not(feature = "gui-automation"))]
+    #[tokio::test]
+    async fn test_verify_outcome_passes_when_observed() {
+        let analyzer = ScriptedAnalyzer::new(&["Yes, the file list shows report.txt"]);
+        let questions = analyzer.questions.clone();
+        let planner = still_planner(analyzer);
+        let step = expecting("report.txt appears in the file list");
+        let plan = ActionPlan::new(VisionTask::new("Save", "Saved"), vec![step.clone()]);
+        let mut context = empty_context(&plan);
+
+        planner.verify_outcome(&step, &mut context).await.unwrap();
+
+        assert!(questions.lock().unwrap()[0].contains("report.txt appears in the file list"));
+        let events: Vec<_> = context.history.iter().map(|e| e.event_type).collect();
+        assert!(matches!(events[..], [ExecutionEventType::VerificationPassed]));
+
+        // Nothing to check without an expected outcome
+        let unchecked = verify_step(2, "anything");
+        planner.verify_outcome(&unchecked, &mut context).await.unwrap();
+        assert_eq!(context.history.len(), 1);
+    }
+
+    #[cfg(not(feature = "gui-automation"))]
+    #[tokio::test]
+    async fn test_verify_outcome_rechecks_then_fails() {
+        let analyzer = ScriptedAnalyzer::new(&["NO", "No, still the old list"]);
+        let questions = analyzer.questions.clone();
+        let planner = still_planner(analyzer);
+        let step = expecting("report.txt appears in the file list");
+        let plan = ActionPlan::new(VisionTask::new("Save", "Saved"), vec![step.clone()]);
+        let mut context = empty_context(&plan);
+
+        let result = planner.verify_outcome(&step, &mut context).await;
+
+        assert!(matches!(result, Err(PlannerError::VerificationFailed(ref o)) if o == "report.txt appears in the file list"));
+        assert_eq!(questions.lock().unwrap().len(), 2);
+        let events: Vec<_> = context.history.iter().map(|e| e.event_type).collect();
+        assert!(matches!(
+            events[..],
+            [ExecutionEventType::VerificationFailed, ExecutionEventType::VerificationFailed]
+        ));
+    }
+
+    #[cfg(not(feature = "gui-automation"))]
+    #[tokio::test]
+    async fn test_failed_verification_does_not_rerun_step() {
+        // The step's action asks one question each time it runs, so the
+        // question count shows how often the action was performed
+        let analyzer = ScriptedAnalyzer::new(&["VERIFIED", "NO", "NO", "VERIFIED", "YES"]);
+        let questions = analyzer.questions.clone();
+        let planner = still_planner(analyzer);
+        let step = PlanStep {
+            is_destructive: true,
+            ..expecting("order confirmation page shown")
+        };
+        let plan = ActionPlan::new(VisionTask::new("Place order", "Order placed"), vec![step]);
+
+        let context = planner.execute_plan(plan).await.unwrap();
+
+        assert_eq!(context.status, ExecutionStatus::Failed);
+        assert!(context.error.unwrap().contains("order confirmation page shown"));
+        let asked = questions.lock().unwrap();
+        assert_eq!(asked.len(), 3);
+        assert_eq!(asked.iter().filter(|q| q.starts_with("Verify this condition")).count(), 1);
+        assert!(!context
+            .history
+            .iter()
+            .any(|e| matches!(e.event_type, ExecutionEventType::StepRetried)));
+    }
+
+    #[cfg(not(feature = "gui-automation"))]
+    #[tokio::test]
+    async fn test_failed_verification_retries_non_destructive_step() {
+        let analyzer = ScriptedAnalyzer::new(&["VERIFIED", "NO", "NO", "VERIFIED", "YES"]);
+        let questions = analyzer.questions.clone();
+        let planner = still_planner(analyzer);
+        let step = expecting("report.txt appears in the file list");
+        let plan = ActionPlan::new(VisionTask::new("Save", "Saved"), vec![step]);
+
+        let context = planner.execute_plan(plan).await.unwrap();
+
+        assert_eq!(context.status, ExecutionStatus::Completed);
+        let asked = questions.lock().unwrap();
+        assert_eq!(asked.len(), 5);
+        assert_eq!(asked.iter().filter(|q| q.starts_with("Verify this condition")).count(), 2);
+        let retried = context
+            .history
+            .iter()
+            .filter(|e| matches!(e.event_type, ExecutionEventType::StepRetried))
+            .count();
+        assert_eq!(retried, 1);
+    }
 }
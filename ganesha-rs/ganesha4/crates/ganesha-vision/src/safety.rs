@@ -5,18 +5,21 @@
 //! - ActionLimits - rate limiting for clicks, keystrokes, etc.
 //! - ConfirmationRequired - settings for sensitive actions
 //! - Emergency stop (Escape key monitoring)
-//! - Audit logging of all actions
+//! - Audit logging of all actions, with rotation and filtered queries
 
-use crate::config::{AppListConfig, ConfirmationSettings, SafetyLimits, VisionConfig};
+use crate::config::{
+    AppListConfig, AuditRotationSettings, ConfirmationSettings, SafetyLimits, VisionConfig,
+};
 use chrono::{DateTime, Utc};
+use futures::stream::{BoxStream, StreamExt};
 use serde::{Deserialize, Serialize};
-use std::collections::VecDeque;
-use std::path::PathBuf;
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use thiserror::Error;
 use tokio::fs::OpenOptions;
-use tokio::io::AsyncWriteExt;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
 use tokio::sync::RwLock;
 use tracing::{error, info, warn};
 
@@ -52,7 +55,7 @@ pub enum SafetyError {
 pub type SafetyResult<T> = Result<T, SafetyError>;
 
 /// Type of action being performed.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum ActionType {
     MouseClick,
@@ -97,6 +100,16 @@ impl ActionType {
     pub fn is_network_operation(&self) -> bool {
         matches!(self, Self::NetworkRequest)
     }
+
+    /// The rate-limit bucket this action draws from, if it is rate limited.
+    /// Key presses and typed text share the keystroke bucket.
+    pub fn rate_bucket(&self) -> Option<ActionType> {
+        match self {
+            Self::MouseClick => Some(Self::MouseClick),
+            Self::KeyPress | Self::KeyType => Some(Self::KeyPress),
+            _ => None,
+        }
+    }
 }
 
 /// An audit log entry.
@@ -164,57 +177,155 @@ impl AuditEntry {
     }
 }
 
-/// Rate limiter for tracking action frequency.
+/// Filter for `AuditLogger::query`. Unset fields match everything.
+#[derive(Debug, Clone, Default)]
+pub struct AuditFilter {
+    /// Only entries of this action type
+    pub action_type: Option<ActionType>,
+    /// Only entries targeting this app (case-insensitive)
+    pub target_app: Option<String>,
+    /// Only entries at or after this time
+    pub since: Option<DateTime<Utc>>,
+    /// Only entries before this time
+    pub until: Option<DateTime<Utc>>,
+    /// Only allowed (`true`) or blocked (`false`) entries
+    pub allowed: Option<bool>,
+}
+
+impl AuditFilter {
+    /// Match every entry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only entries of this action type.
+    pub fn action_type(mut self, action_type: ActionType) -> Self {
+        self.action_type = Some(action_type);
+        self
+    }
+
+    /// Only entries targeting this app.
+    pub fn target_app(mut self, app: impl Into<String>) -> Self {
+        self.target_app = Some(app.into());
+        self
+    }
+
+    /// Only entries in `[since, until)`.
+    pub fn between(mut self, since: DateTime<Utc>, until: DateTime<Utc>) -> Self {
+        self.since = Some(since);
+        self.until = Some(until);
+        self
+    }
+
+    /// Only allowed or only blocked entries.
+    pub fn allowed(mut self, allowed: bool) -> Self {
+        self.allowed = Some(allowed);
+        self
+    }
+
+    /// Check whether an entry passes the filter.
+    pub fn matches(&self, entry: &AuditEntry) -> bool {
+        self.action_type.is_none_or(|t| entry.action_type == t)
+            && self.target_app.as_ref().is_none_or(|app| {
+                entry
+                    .target_app
+                    .as_ref()
+                    .is_some_and(|target| target.eq_ignore_ascii_case(app))
+            })
+            && self.since.is_none_or(|since| entry.timestamp >= since)
+            && self.until.is_none_or(|until| entry.timestamp < until)
+            && self.allowed.is_none_or(|allowed| entry.allowed == allowed)
+    }
+}
+
+/// Token bucket rate limiter.
+///
+/// Holds one second's worth of tokens, but at least `MIN_BURST`, and refills
+/// continuously, so a limit of 60/minute paces actions at about one per
+/// second instead of allowing 60 at once and then nothing for the rest of
+/// the minute, while a double-click still gets through.
 #[derive(Debug)]
-struct RateLimiter {
-    /// Action timestamps within the window
-    timestamps: VecDeque<Instant>,
-    /// Maximum actions allowed in the window
-    max_actions: u32,
-    /// Time window
-    window: Duration,
+struct TokenBucket {
+    /// Current number of tokens
+    tokens: f64,
+    /// Maximum number of tokens (burst size)
+    capacity: f64,
+    /// Tokens added per second
+    refill_per_sec: f64,
+    /// When tokens were last added
+    last_refill: Instant,
+    /// Recent acquisitions, for reporting the per-minute rate
+    recent: VecDeque<Instant>,
 }
 
-impl RateLimiter {
-    fn new(max_actions: u32, window: Duration) -> Self {
+impl TokenBucket {
+    /// Smallest burst allowed, enough for a double-click plus one more action
+    const MIN_BURST: f64 = 3.0;
+
+    fn new(max_per_minute: u32, now: Instant) -> Self {
+        let refill_per_sec = max_per_minute as f64 / 60.0;
+        let capacity = refill_per_sec.max(Self::MIN_BURST);
         Self {
-            timestamps: VecDeque::new(),
-            max_actions,
-            window,
+            tokens: capacity,
+            capacity,
+            refill_per_sec,
+            last_refill: now,
+            recent: VecDeque::new(),
         }
     }
 
-    fn check_and_record(&mut self) -> bool {
-        let now = Instant::now();
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        self.last_refill = now;
+    }
 
-        // Remove old timestamps
-        while let Some(front) = self.timestamps.front() {
-            if now.duration_since(*front) > self.window {
-                self.timestamps.pop_front();
+    /// Take a token if one is available.
+    fn try_acquire(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        while let Some(front) = self.recent.front() {
+            if now.saturating_duration_since(*front) > Duration::from_secs(60) {
+                self.recent.pop_front();
             } else {
                 break;
             }
         }
 
-        // Check if we're at the limit
-        if self.timestamps.len() >= self.max_actions as usize {
+        // Tolerate float drift so exactly-on-schedule actions are not refused
+        if self.tokens < 1.0 - 1e-9 {
             return false;
         }
-
-        // Record this action
-        self.timestamps.push_back(now);
+        self.tokens = (self.tokens - 1.0).max(0.0);
+        self.recent.push_back(now);
         true
     }
 
-    fn current_rate(&self) -> u32 {
-        let now = Instant::now();
-        self.timestamps
+    /// Tokens available at `now`.
+    fn level(&self, now: Instant) -> f64 {
+        let elapsed = now.saturating_duration_since(self.last_refill).as_secs_f64();
+        (self.tokens + elapsed * self.refill_per_sec).min(self.capacity)
+    }
+
+    /// Actions allowed in the last minute.
+    fn current_rate(&self, now: Instant) -> u32 {
+        self.recent
             .iter()
-            .filter(|t| now.duration_since(**t) <= self.window)
+            .filter(|t| now.saturating_duration_since(**t) <= Duration::from_secs(60))
             .count() as u32
     }
 }
 
+/// Fill level of one rate-limit bucket.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BucketLevel {
+    /// Action type the bucket limits (see `ActionType::rate_bucket`)
+    pub action_type: ActionType,
+    /// Tokens currently available
+    pub tokens: f64,
+    /// Maximum tokens the bucket holds
+    pub capacity: f64,
+}
+
 /// Audit logger for writing action logs.
 pub struct AuditLogger {
     /// Log file path
@@ -223,6 +334,8 @@ pub struct AuditLogger {
     buffer: Arc<RwLock<Vec<AuditEntry>>>,
     /// Maximum buffer size before flush
     buffer_size: usize,
+    /// When to rotate the log file
+    rotation: AuditRotationSettings,
     /// Session ID
     session_id: String,
     /// Whether logging is enabled
@@ -238,6 +351,7 @@ impl AuditLogger {
             log_path,
             buffer: Arc::new(RwLock::new(Vec::new())),
             buffer_size: 100,
+            rotation: config.audit_rotation.clone(),
             session_id: uuid::Uuid::new_v4().to_string(),
             enabled: config.audit_logging,
         }
@@ -281,7 +395,10 @@ impl AuditLogger {
         Ok(())
     }
 
-    /// Flush buffered entries to disk.
+    /// Flush buffered entries to disk, then rotate if the log is due.
+    ///
+    /// The file is synced before any rotation, so a crash never loses
+    /// entries that were already flushed.
     async fn flush_entries(&self, entries: Vec<AuditEntry>) -> SafetyResult<()> {
         if let Some(ref path) = self.log_path {
             let mut file = OpenOptions::new()
@@ -305,11 +422,142 @@ impl AuditLogger {
             file.flush()
                 .await
                 .map_err(|e| SafetyError::AuditError(e.to_string()))?;
+            file.sync_all()
+                .await
+                .map_err(|e| SafetyError::AuditError(e.to_string()))?;
+            drop(file);
+
+            if self.needs_rotation(path).await? {
+                self.rotate(path).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether the log at `path` has outgrown the size or age limit.
+    async fn needs_rotation(&self, path: &Path) -> SafetyResult<bool> {
+        let size = match tokio::fs::metadata(path).await {
+            Ok(metadata) => metadata.len(),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+            Err(e) => return Err(SafetyError::AuditError(e.to_string())),
+        };
+        if self.rotation.max_bytes > 0 && size >= self.rotation.max_bytes {
+            return Ok(true);
+        }
+        if self.rotation.max_age_secs == 0 || size == 0 {
+            return Ok(false);
+        }
+
+        // Entries are appended in order, so the first one is the oldest
+        let file = tokio::fs::File::open(path)
+            .await
+            .map_err(|e| SafetyError::AuditError(e.to_string()))?;
+        let first = BufReader::new(file)
+            .lines()
+            .next_line()
+            .await
+            .map_err(|e| SafetyError::AuditError(e.to_string()))?;
+        let oldest = first.and_then(|line| serde_json::from_str::<AuditEntry>(&line).ok());
+        Ok(oldest.is_some_and(|entry| {
+            let age = Utc::now().signed_duration_since(entry.timestamp);
+            age.num_seconds() >= self.rotation.max_age_secs as i64
+        }))
+    }
+
+    /// Shift `audit.log.N` to `audit.log.N+1` (dropping the oldest) and move
+    /// the live log to `audit.log.1`.
+    async fn rotate(&self, path: &Path) -> SafetyResult<()> {
+        let keep = self.rotation.max_files;
+        if keep == 0 {
+            return tokio::fs::remove_file(path)
+                .await
+                .map_err(|e| SafetyError::AuditError(e.to_string()));
         }
 
+        for n in (1..keep).rev() {
+            let from = rotated_path(path, n);
+            if tokio::fs::try_exists(&from).await.unwrap_or(false) {
+                tokio::fs::rename(&from, rotated_path(path, n + 1))
+                    .await
+                    .map_err(|e| SafetyError::AuditError(e.to_string()))?;
+            }
+        }
+        tokio::fs::rename(path, rotated_path(path, 1))
+            .await
+            .map_err(|e| SafetyError::AuditError(e.to_string()))?;
+
+        info!("Rotated audit log {}", path.display());
         Ok(())
     }
 
+    /// Stream logged entries matching `filter`, oldest first.
+    ///
+    /// Reads the rotated files and then the live log line by line, followed
+    /// by entries still buffered in memory, so the whole history is never
+    /// loaded at once. Lines that fail to parse (e.g. a write cut off by a
+    /// crash) are skipped.
+    pub async fn query(&self, filter: AuditFilter) -> BoxStream<'static, SafetyResult<AuditEntry>> {
+        let mut files = VecDeque::new();
+        if let Some(ref path) = self.log_path {
+            for n in (1..=self.rotation.max_files).rev() {
+                files.push_back(rotated_path(path, n));
+            }
+            files.push_back(path.clone());
+        }
+
+        let state = QueryState {
+            files,
+            lines: None,
+            buffered: self.buffer.read().await.iter().cloned().collect(),
+            filter,
+        };
+
+        futures::stream::try_unfold(state, |mut state| async move {
+            loop {
+                if let Some(lines) = state.lines.as_mut() {
+                    match lines
+                        .next_line()
+                        .await
+                        .map_err(|e| SafetyError::AuditError(e.to_string()))?
+                    {
+                        Some(line) => {
+                            if line.trim().is_empty() {
+                                continue;
+                            }
+                            match serde_json::from_str::<AuditEntry>(&line) {
+                                Ok(entry) if state.filter.matches(&entry) => {
+                                    return Ok(Some((entry, state)));
+                                }
+                                Ok(_) => {}
+                                Err(e) => warn!("Skipping unreadable audit log line: {}", e),
+                            }
+                            continue;
+                        }
+                        None => state.lines = None,
+                    }
+                }
+
+                if let Some(path) = state.files.pop_front() {
+                    match tokio::fs::File::open(&path).await {
+                        Ok(file) => state.lines = Some(BufReader::new(file).lines()),
+                        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                        Err(e) => return Err(SafetyError::AuditError(e.to_string())),
+                    }
+                    continue;
+                }
+
+                while let Some(entry) = state.buffered.pop_front() {
+                    if state.filter.matches(&entry) {
+                        return Ok(Some((entry, state)));
+                    }
+                }
+                return Ok(None);
+            }
+        })
+        .boxed()
+    }
+
     /// Flush all buffered entries.
     pub async fn flush(&self) -> SafetyResult<()> {
         let mut buffer = self.buffer.write().await;
@@ -335,12 +583,28 @@ impl AuditLogger {
     }
 }
 
+/// Cursor for `AuditLogger::query`.
+struct QueryState {
+    /// Files still to read, oldest first
+    files: VecDeque<PathBuf>,
+    /// Lines of the file being read
+    lines: Option<tokio::io::Lines<BufReader<tokio::fs::File>>>,
+    /// Unflushed entries, returned after the files
+    buffered: VecDeque<AuditEntry>,
+    filter: AuditFilter,
+}
+
+/// `audit.log` -> `audit.log.N`
+fn rotated_path(path: &Path, n: usize) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(format!(".{}", n));
+    path.with_file_name(name)
+}
+
 /// Safety guard that enforces all safety rules.
 pub struct SafetyGuard {
-    /// Rate limiter for clicks
-    click_limiter: Arc<RwLock<RateLimiter>>,
-    /// Rate limiter for keystrokes
-    keystroke_limiter: Arc<RwLock<RateLimiter>>,
+    /// Token buckets keyed by `ActionType::rate_bucket`
+    buckets: Arc<RwLock<HashMap<ActionType, TokenBucket>>>,
     /// Total action counter
     action_counter: Arc<RwLock<u32>>,
     /// Emergency stop flag
@@ -360,19 +624,20 @@ pub struct SafetyGuard {
 impl SafetyGuard {
     /// Create a new safety guard.
     pub fn new(config: &VisionConfig) -> Self {
-        let click_limiter = RateLimiter::new(
-            config.safety.max_clicks_per_minute,
-            Duration::from_secs(60),
-        );
-
-        let keystroke_limiter = RateLimiter::new(
-            config.safety.max_keystrokes_per_minute,
-            Duration::from_secs(60),
-        );
+        let now = Instant::now();
+        let buckets = HashMap::from([
+            (
+                ActionType::MouseClick,
+                TokenBucket::new(config.safety.max_clicks_per_minute, now),
+            ),
+            (
+                ActionType::KeyPress,
+                TokenBucket::new(config.safety.max_keystrokes_per_minute, now),
+            ),
+        ]);
 
         Self {
-            click_limiter: Arc::new(RwLock::new(click_limiter)),
-            keystroke_limiter: Arc::new(RwLock::new(keystroke_limiter)),
+            buckets: Arc::new(RwLock::new(buckets)),
             action_counter: Arc::new(RwLock::new(0)),
             emergency_stop: Arc::new(RwLock::new(false)),
             app_config: config.apps.clone(),
@@ -418,30 +683,23 @@ impl SafetyGuard {
         }
 
         // Check rate limits
-        match action_type {
-            ActionType::MouseClick => {
-                let mut limiter = self.click_limiter.write().await;
-                if !limiter.check_and_record() {
-                    let entry = AuditEntry::new(action_type, description)
-                        .blocked("Click rate limit exceeded");
-                    self.audit_logger.log(entry).await?;
-                    return Err(SafetyError::RateLimitExceeded(
-                        "Maximum clicks per minute exceeded".to_string(),
-                    ));
-                }
-            }
-            ActionType::KeyPress | ActionType::KeyType => {
-                let mut limiter = self.keystroke_limiter.write().await;
-                if !limiter.check_and_record() {
-                    let entry = AuditEntry::new(action_type, description)
-                        .blocked("Keystroke rate limit exceeded");
-                    self.audit_logger.log(entry).await?;
-                    return Err(SafetyError::RateLimitExceeded(
-                        "Maximum keystrokes per minute exceeded".to_string(),
-                    ));
-                }
+        if let Some(key) = action_type.rate_bucket() {
+            let allowed = self
+                .buckets
+                .write()
+                .await
+                .get_mut(&key)
+                .is_none_or(|bucket| bucket.try_acquire(Instant::now()));
+            if !allowed {
+                let (reason, message) = if key == ActionType::MouseClick {
+                    ("Click rate limit exceeded", "Maximum clicks per minute exceeded")
+                } else {
+                    ("Keystroke rate limit exceeded", "Maximum keystrokes per minute exceeded")
+                };
+                let entry = AuditEntry::new(action_type, description).blocked(reason);
+                self.audit_logger.log(entry).await?;
+                return Err(SafetyError::RateLimitExceeded(message.to_string()));
             }
-            _ => {}
         }
 
         // Check total action limit
@@ -546,12 +804,38 @@ impl SafetyGuard {
 
     /// Get current click rate.
     pub async fn click_rate(&self) -> u32 {
-        self.click_limiter.read().await.current_rate()
+        self.rate(ActionType::MouseClick).await
     }
 
     /// Get current keystroke rate.
     pub async fn keystroke_rate(&self) -> u32 {
-        self.keystroke_limiter.read().await.current_rate()
+        self.rate(ActionType::KeyPress).await
+    }
+
+    async fn rate(&self, key: ActionType) -> u32 {
+        self.buckets
+            .read()
+            .await
+            .get(&key)
+            .map_or(0, |bucket| bucket.current_rate(Instant::now()))
+    }
+
+    /// Get the fill level of every rate-limit bucket.
+    pub async fn bucket_levels(&self) -> Vec<BucketLevel> {
+        let now = Instant::now();
+        let mut levels: Vec<BucketLevel> = self
+            .buckets
+            .read()
+            .await
+            .iter()
+            .map(|(action_type, bucket)| BucketLevel {
+                action_type: *action_type,
+                tokens: bucket.level(now),
+                capacity: bucket.capacity,
+            })
+            .collect();
+        levels.sort_by_key(|level| format!("{:?}", level.action_type));
+        levels
     }
 
     /// Get total actions performed.
@@ -637,6 +921,8 @@ pub struct SafetyStats {
     pub click_rate: u32,
     /// Current keystroke rate (per minute)
     pub keystroke_rate: u32,
+    /// Current rate-limit bucket levels
+    pub buckets: Vec<BucketLevel>,
     /// Emergency stop triggered count
     pub emergency_stops: u32,
     /// Session ID
@@ -651,6 +937,7 @@ impl SafetyGuard {
             total_blocked: 0, // Would need to track this separately
             click_rate: self.click_rate().await,
             keystroke_rate: self.keystroke_rate().await,
+            buckets: self.bucket_levels().await,
             emergency_stops: 0, // Would need to track this
             session_id: self.audit_logger.session_id().to_string(),
         }
@@ -691,16 +978,87 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_token_bucket_limits_burst() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(180, start);
+
+        // 180/minute holds three tokens
+        assert!(bucket.try_acquire(start));
+        assert!(bucket.try_acquire(start));
+        assert!(bucket.try_acquire(start));
+        assert!(!bucket.try_acquire(start)); // Should be limited now
+        assert_eq!(bucket.current_rate(start), 3);
+
+        // One token back after a third of a second
+        let later = start + Duration::from_millis(340);
+        assert!(bucket.try_acquire(later));
+        assert!(!bucket.try_acquire(later));
+    }
+
+    #[test]
+    fn test_token_bucket_paces_evenly() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(60, start);
+
+        // Try an action every 100ms for two simulated minutes
+        let allowed: Vec<u64> = (0..1200u64)
+            .map(|tick| tick * 100)
+            .filter(|ms| bucket.try_acquire(start + Duration::from_millis(*ms)))
+            .collect();
+
+        // The minimum burst up front, then about one per second
+        assert_eq!(allowed.len(), 122);
+        assert_eq!(allowed[..4], [0, 100, 200, 1000]);
+        for pair in allowed[3..].windows(2) {
+            assert_eq!(pair[1] - pair[0], 1000);
+        }
+        for window in 1..12u64 {
+            let in_window = allowed
+                .iter()
+                .filter(|ms| (window * 10_000..(window + 1) * 10_000).contains(*ms))
+                .count();
+            assert_eq!(in_window, 10);
+        }
+    }
+
     #[tokio::test]
-    async fn test_rate_limiter() {
-        let mut limiter = RateLimiter::new(3, Duration::from_secs(1));
+    async fn test_safety_guard_allows_double_click() {
+        // The default 60 clicks/minute refills one per second
+        let guard = SafetyGuard::new(&VisionConfig::default());
 
-        assert!(limiter.check_and_record());
-        assert!(limiter.check_and_record());
-        assert!(limiter.check_and_record());
-        assert!(!limiter.check_and_record()); // Should be limited now
+        guard.check_action(ActionType::MouseClick, None, "click").await.unwrap();
+        guard.check_action(ActionType::MouseClick, None, "click").await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_safety_guard_reports_bucket_levels() {
+        let config = VisionConfig::default();
+        let guard = SafetyGuard::new(&config);
 
-        assert_eq!(limiter.current_rate(), 3);
+        for _ in 0..3 {
+            guard.check_action(ActionType::MouseClick, None, "click").await.unwrap();
+        }
+        assert!(matches!(
+            guard.check_action(ActionType::MouseClick, None, "click again").await,
+            Err(SafetyError::RateLimitExceeded(_))
+        ));
+
+        let stats = guard.get_stats().await;
+        assert_eq!(stats.click_rate, 3);
+        let clicks = stats
+            .buckets
+            .iter()
+            .find(|b| b.action_type == ActionType::MouseClick)
+            .unwrap();
+        assert_eq!(clicks.capacity, 3.0);
+        assert!(clicks.tokens < 0.5);
+        let keys = stats
+            .buckets
+            .iter()
+            .find(|b| b.action_type == ActionType::KeyPress)
+            .unwrap();
+        assert_eq!(keys.tokens, 5.0);
     }
 
     #[tokio::test]
@@ -716,4 +1074,92 @@ mod tests {
         guard.reset_emergency_stop().await;
         assert!(!guard.is_emergency_stop_active().await);
     }
+
+    #[tokio::test]
+    async fn test_audit_rotation_preserves_query_order() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.log");
+        let config = VisionConfig {
+            audit_log_path: Some(path.to_string_lossy().into_owned()),
+            audit_rotation: AuditRotationSettings {
+                max_bytes: 1,
+                max_age_secs: 0,
+                max_files: 10,
+            },
+            ..Default::default()
+        };
+        let logger = AuditLogger::new(&config);
+
+        // Every flush rotates, so each entry lands in its own file
+        for i in 0..4 {
+            let entry = AuditEntry::new(ActionType::MouseClick, format!("click {}", i))
+                .with_target_app("Blender");
+            logger.log(entry).await.unwrap();
+            logger.flush().await.unwrap();
+        }
+        logger
+            .log(AuditEntry::new(ActionType::KeyType, "unflushed").blocked("rate limit"))
+            .await
+            .unwrap();
+
+        assert!(dir.path().join("audit.log.4").exists());
+        assert!(!path.exists());
+
+        let all: Vec<String> = logger
+            .query(AuditFilter::new())
+            .await
+            .map(|entry| entry.unwrap().description)
+            .collect()
+            .await;
+        assert_eq!(all, vec!["click 0", "click 1", "click 2", "click 3", "unflushed"]);
+
+        let clicks: Vec<AuditEntry> = logger
+            .query(
+                AuditFilter::new()
+                    .action_type(ActionType::MouseClick)
+                    .target_app("blender")
+                    .allowed(true),
+            )
+            .await
+            .map(|entry| entry.unwrap())
+            .collect()
+            .await;
+        assert_eq!(clicks.len(), 4);
+
+        let blocked = logger.query(AuditFilter::new().allowed(false)).await.count().await;
+        assert_eq!(blocked, 1);
+    }
+
+    #[tokio::test]
+    async fn test_audit_rotation_drops_oldest_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.log");
+        let config = VisionConfig {
+            audit_log_path: Some(path.to_string_lossy().into_owned()),
+            audit_rotation: AuditRotationSettings {
+                max_bytes: 1,
+                max_age_secs: 0,
+                max_files: 2,
+            },
+            ..Default::default()
+        };
+        let logger = AuditLogger::new(&config);
+
+        for i in 0..4 {
+            logger
+                .log(AuditEntry::new(ActionType::MouseClick, format!("click {}", i)))
+                .await
+                .unwrap();
+            logger.flush().await.unwrap();
+        }
+
+        assert!(!dir.path().join("audit.log.3").exists());
+        let kept: Vec<String> = logger
+            .query(AuditFilter::new())
+            .await
+            .map(|entry| entry.unwrap().description)
+            .collect()
+            .await;
+        assert_eq!(kept, vec!["click 2", "click 3"]);
+    }
 }
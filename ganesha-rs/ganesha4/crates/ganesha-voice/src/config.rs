@@ -29,6 +29,9 @@ pub struct VoiceConfig {
     pub hotkeys: HotkeyConfig,
     /// Advanced settings
     pub advanced: AdvancedConfig,
+    /// Retry policy for TTS/STT API calls
+    #[serde(default)]
+    pub retry: RetryConfig,
 }
 
 impl Default for VoiceConfig {
@@ -41,6 +44,7 @@ impl Default for VoiceConfig {
             api_keys: ApiKeysConfig::default(),
             hotkeys: HotkeyConfig::default(),
             advanced: AdvancedConfig::default(),
+            retry: RetryConfig::default(),
         }
     }
 }
@@ -87,6 +91,18 @@ impl VoiceConfig {
             ));
         }
 
+        if self.advanced.barge_in_threshold < 0.0 || self.advanced.barge_in_threshold > 1.0 {
+            return Err(VoiceError::ConfigError(
+                "Barge-in threshold must be between 0.0 and 1.0".to_string(),
+            ));
+        }
+
+        if !(0.0..=1.0).contains(&self.retry.jitter) {
+            return Err(VoiceError::ConfigError(
+                "Retry jitter must be between 0.0 and 1.0".to_string(),
+            ));
+        }
+
         // Validate output config
         if self.output.volume < 0.0 || self.output.volume > 1.0 {
             return Err(VoiceError::ConfigError(
@@ -409,10 +425,17 @@ pub struct AdvancedConfig {
     pub echo_cancellation: bool,
     /// Maximum history turns to keep
     pub max_history_turns: usize,
+    /// File to restore conversation history from at startup (and save it to)
+    #[serde(default)]
+    pub history_path: Option<PathBuf>,
     /// Whether to auto-listen after assistant finishes
     pub auto_listen: bool,
     /// Whether to allow interruptions
     pub allow_interruptions: bool,
+    /// Microphone level (0.0 to 1.0) that counts as the user talking over playback.
+    /// Kept above the listening threshold because speaker output leaks into the mic.
+    #[serde(default = "default_barge_in_threshold")]
+    pub barge_in_threshold: f32,
     /// Debug mode (saves audio, logs more)
     pub debug_mode: bool,
 }
@@ -424,13 +447,45 @@ impl Default for AdvancedConfig {
             noise_cancellation: false,
             echo_cancellation: false,
             max_history_turns: 100,
+            history_path: None,
             auto_listen: true,
             allow_interruptions: true,
+            barge_in_threshold: default_barge_in_threshold(),
             debug_mode: false,
         }
     }
 }
 
+fn default_barge_in_threshold() -> f32 {
+    0.08
+}
+
+/// Retry policy for TTS/STT API calls (see `crate::retry`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetryConfig {
+    /// Retries after the first attempt (0 disables retrying)
+    pub max_retries: u32,
+    /// Delay before the first retry; doubles on each further retry
+    pub base_delay_ms: u64,
+    /// Random fraction (0.0 to 1.0) of the delay added to spread out retries
+    pub jitter: f32,
+    /// Time budget for retrying, counted from the first failure and
+    /// covering backoff and retry attempts. The first attempt is not
+    /// limited by it.
+    pub deadline_ms: u64,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            base_delay_ms: 500,
+            jitter: 0.2,
+            deadline_ms: 15_000,
+        }
+    }
+}
+
 /// Builder for VoiceConfig
 pub struct VoiceConfigBuilder {
     config: VoiceConfig,
@@ -516,6 +571,18 @@ impl VoiceConfigBuilder {
         self
     }
 
+    /// Enable/disable interrupting the assistant by speaking over it
+    pub fn allow_interruptions(mut self, allow: bool) -> Self {
+        self.config.advanced.allow_interruptions = allow;
+        self
+    }
+
+    /// Set the microphone level that interrupts playback
+    pub fn barge_in_threshold(mut self, threshold: f32) -> Self {
+        self.config.advanced.barge_in_threshold = threshold.clamp(0.0, 1.0);
+        self
+    }
+
     /// Build the configuration
     pub fn build(self) -> Result<VoiceConfig> {
         self.config.validate()?;
@@ -600,4 +667,19 @@ mod tests {
         config.output.volume = -1.0; // Invalid
         assert!(config.validate().is_err());
     }
+
+    #[test]
+    fn test_barge_in_threshold() {
+        let config = VoiceConfig::default();
+        assert!(config.advanced.barge_in_threshold > config.input.vad.voice_threshold);
+
+        // Configs written before the field existed still load with the default
+        let toml = toml::to_string(&config).unwrap().replace("barge_in_threshold = 0.08", "");
+        let loaded: VoiceConfig = toml::from_str(&toml).unwrap();
+        assert_eq!(loaded.advanced.barge_in_threshold, config.advanced.barge_in_threshold);
+
+        let mut config = VoiceConfig::default();
+        config.advanced.barge_in_threshold = 1.5;
+        assert!(config.validate().is_err());
+    }
 }
@@ -4,7 +4,9 @@
 //! silence detection, transcript generation, and audio history.
 
 use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
+use std::path::Path;
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
@@ -16,7 +18,8 @@ use crate::output::{AudioPlayer, VoiceOutput};
 use crate::{Result, VoiceError};
 
 /// Conversation turn speaker
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Speaker {
     User,
     Assistant,
@@ -240,6 +243,20 @@ impl Transcript {
     }
 }
 
+/// A turn as persisted by `VoiceConversation::save_to` (audio is not saved)
+#[derive(Debug, Serialize, Deserialize)]
+struct SavedTurn {
+    /// "user", "assistant", or "system" in files written by other tools
+    speaker: String,
+    text: String,
+    /// Seconds since the Unix epoch
+    timestamp: u64,
+    #[serde(default)]
+    duration_ms: u64,
+    #[serde(default)]
+    was_interrupted: bool,
+}
+
 /// Voice conversation manager
 pub struct VoiceConversation {
     state: Arc<RwLock<ConversationState>>,
@@ -460,6 +477,72 @@ impl VoiceConversation {
         }
     }
 
+    /// Save the turn history (speaker, text, timestamps) to a JSON file
+    pub async fn save_to(&self, path: &Path) -> Result<()> {
+        let turns: Vec<SavedTurn> = self
+            .history
+            .read()
+            .iter()
+            .map(|t| SavedTurn {
+                speaker: match t.speaker {
+                    Speaker::User => "user".to_string(),
+                    Speaker::Assistant => "assistant".to_string(),
+                },
+                text: t.text.clone(),
+                timestamp: t
+                    .timestamp
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0),
+                duration_ms: t.duration.as_millis() as u64,
+                was_interrupted: t.was_interrupted,
+            })
+            .collect();
+
+        let content = serde_json::to_string_pretty(&turns)
+            .map_err(|e| VoiceError::ConfigError(format!("Failed to serialize history: {}", e)))?;
+
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::write(path, content).await?;
+        Ok(())
+    }
+
+    /// Replace the turn history with one saved by `save_to`.
+    ///
+    /// System prompt entries are skipped (the caller supplies the system prompt
+    /// each session, so replaying it would count it twice), and only the newest
+    /// `max_history_turns` turns are kept. Returns the number of turns restored.
+    pub async fn load_from(&self, path: &Path) -> Result<usize> {
+        let content = tokio::fs::read_to_string(path).await?;
+        let saved: Vec<SavedTurn> = serde_json::from_str(&content)
+            .map_err(|e| VoiceError::ConfigError(format!("Failed to parse history: {}", e)))?;
+
+        let turns: Vec<ConversationTurn> = saved
+            .into_iter()
+            .filter_map(|t| {
+                let speaker = match t.speaker.as_str() {
+                    "user" => Speaker::User,
+                    "assistant" => Speaker::Assistant,
+                    _ => return None,
+                };
+                let mut turn = ConversationTurn::new(speaker, t.text)
+                    .with_duration(Duration::from_millis(t.duration_ms));
+                turn.timestamp = UNIX_EPOCH + Duration::from_secs(t.timestamp);
+                turn.was_interrupted = t.was_interrupted;
+                Some(turn)
+            })
+            .collect();
+
+        let skip = turns.len().saturating_sub(self.config.max_history_turns);
+        let mut history = self.history.write();
+        *history = turns.into_iter().skip(skip).collect();
+
+        info!("Restored {} conversation turns from {}", history.len(), path.display());
+        Ok(history.len())
+    }
+
     /// Clear conversation history
     pub fn clear_history(&self) {
         self.history.write().clear();
@@ -524,6 +607,37 @@ mod tests {
         assert!(!conversation.is_running());
     }
 
+    #[tokio::test]
+    async fn test_history_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.json");
+
+        let conversation = VoiceConversation::default();
+        for i in 0..4 {
+            conversation.add_to_history(ConversationTurn::new(Speaker::User, format!("question {}", i)));
+            conversation.add_to_history(ConversationTurn::new(Speaker::Assistant, format!("answer {}", i)));
+        }
+        conversation.save_to(&path).await.unwrap();
+
+        // A system prompt written alongside the turns must not be restored or counted
+        let mut saved: Vec<serde_json::Value> =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        saved.insert(0, serde_json::json!({"speaker": "system", "text": "You are Ganesha", "timestamp": 0}));
+        std::fs::write(&path, serde_json::to_string(&saved).unwrap()).unwrap();
+
+        let restored = VoiceConversation::new(ConversationConfig {
+            max_history_turns: 3,
+            ..Default::default()
+        });
+        assert_eq!(restored.load_from(&path).await.unwrap(), 3);
+
+        // Oldest turns are dropped; history() returns newest first
+        let history = restored.history(10);
+        let texts: Vec<&str> = history.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(texts, vec!["answer 3", "question 3", "answer 2"]);
+        assert_eq!(history[0].speaker, Speaker::Assistant);
+    }
+
     #[test]
     fn test_conversation_config_default() {
         let config = ConversationConfig::default();
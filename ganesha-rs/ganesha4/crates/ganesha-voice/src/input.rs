@@ -154,6 +154,68 @@ pub struct TranscriptionResult {
     pub duration: Duration,
     /// Individual word segments with timestamps, if available
     pub segments: Vec<TranscriptionSegment>,
+    /// Per-word timing, if the backend reported it (e.g. for live captions)
+    pub words: Option<Vec<WordTiming>>,
+}
+
+/// Timing of a single transcribed word
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WordTiming {
+    pub word: String,
+    /// Start offset from the beginning of the audio, in milliseconds
+    pub start_ms: u32,
+    /// End offset from the beginning of the audio, in milliseconds
+    pub end_ms: u32,
+}
+
+/// Parse the `words` array of a Whisper API verbose JSON response.
+/// Returns `None` when the provider omitted word timings.
+pub fn parse_word_timings(response: &serde_json::Value) -> Option<Vec<WordTiming>> {
+    let words: Vec<WordTiming> = response["words"]
+        .as_array()?
+        .iter()
+        .filter_map(|w| {
+            Some(WordTiming {
+                word: w["word"].as_str()?.trim().to_string(),
+                start_ms: (w["start"].as_f64()? * 1000.0).round() as u32,
+                end_ms: (w["end"].as_f64()? * 1000.0).round() as u32,
+            })
+        })
+        .collect();
+
+    (!words.is_empty()).then_some(words)
+}
+
+/// Approximate word timings from segment timings by spreading each
+/// segment's duration over its words in proportion to their length.
+/// Used for backends that only report segment-level timestamps.
+pub fn word_timings_from_segments(segments: &[TranscriptionSegment]) -> Option<Vec<WordTiming>> {
+    let mut words = Vec::new();
+
+    for segment in segments {
+        let segment_words: Vec<&str> = segment.text.split_whitespace().collect();
+        let total_chars: usize = segment_words.iter().map(|w| w.chars().count()).sum();
+        if total_chars == 0 {
+            continue;
+        }
+
+        let start_ms = (segment.start * 1000.0).round() as u32;
+        let end_ms = ((segment.end * 1000.0).round() as u32).max(start_ms);
+        let span = (end_ms - start_ms) as u64;
+
+        let mut chars_before = 0u64;
+        for word in segment_words {
+            let chars = word.chars().count() as u64;
+            words.push(WordTiming {
+                word: word.to_string(),
+                start_ms: start_ms + (span * chars_before / total_chars as u64) as u32,
+                end_ms: start_ms + (span * (chars_before + chars) / total_chars as u64) as u32,
+            });
+            chars_before += chars;
+        }
+    }
+
+    (!words.is_empty()).then_some(words)
 }
 
 /// A segment of transcription with timing information
@@ -363,6 +425,70 @@ impl AudioRecorder {
         Ok(AudioData::new(samples, self.config.sample_rate.0, 1))
     }
 
+    /// Sample the microphone without keeping audio until its level crosses
+    /// `threshold`, then return.
+    ///
+    /// Used to detect the user talking over playback (barge-in). Dropping the
+    /// future stops sampling.
+    pub async fn detect_voice_activity(&self, threshold: f32) -> Result<()> {
+        let sample_format = self
+            .device
+            .default_input_config()
+            .map_err(|e| VoiceError::AudioError(format!("Failed to get input config: {}", e)))?
+            .sample_format();
+
+        let voice_detected = Arc::new(AtomicBool::new(false));
+        let voice_detected_clone = voice_detected.clone();
+
+        let err_fn = |err| error!("Audio stream error: {}", err);
+
+        let process_audio = move |data: &[f32]| {
+            if data.is_empty() {
+                return;
+            }
+            let rms: f32 = (data.iter().map(|&s| s * s).sum::<f32>() / data.len() as f32).sqrt();
+            if rms > threshold {
+                voice_detected_clone.store(true, Ordering::SeqCst);
+            }
+        };
+
+        let stream = match sample_format {
+            SampleFormat::F32 => self.device.build_input_stream(
+                &self.config,
+                move |data: &[f32], _: &_| process_audio(data),
+                err_fn,
+                None,
+            ),
+            SampleFormat::I16 => {
+                let process = move |data: &[i16], _: &_| {
+                    let converted: Vec<f32> = data.iter().map(|&s| s as f32 / 32768.0).collect();
+                    process_audio(&converted);
+                };
+                self.device
+                    .build_input_stream(&self.config, process, err_fn, None)
+            }
+            _ => {
+                return Err(VoiceError::AudioError(format!(
+                    "Unsupported sample format: {:?}",
+                    sample_format
+                )))
+            }
+        }
+        .map_err(|e| VoiceError::AudioError(format!("Failed to build stream: {}", e)))?;
+
+        stream
+            .play()
+            .map_err(|e| VoiceError::AudioError(format!("Failed to start stream: {}", e)))?;
+
+        while !voice_detected.load(Ordering::SeqCst) {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+
+        drop(stream);
+        debug!("Voice activity over threshold {}", threshold);
+        Ok(())
+    }
+
     /// Record with voice activity detection
     pub async fn record_with_vad(
         &self,
@@ -559,7 +685,9 @@ impl VoiceInput for WhisperInput {
         let mut form = reqwest::multipart::Form::new()
             .part("file", part)
             .text("model", self.model.clone())
-            .text("response_format", "verbose_json");
+            .text("response_format", "verbose_json")
+            .text("timestamp_granularities[]", "word")
+            .text("timestamp_granularities[]", "segment");
 
         if let Some(ref lang) = self.language {
             form = form.text("language", lang.clone());
@@ -575,11 +703,12 @@ impl VoiceInput for WhisperInput {
             .map_err(|e| VoiceError::ApiError(format!("Request failed: {}", e)))?;
 
         if !response.status().is_success() {
+            let status = response.status().as_u16();
             let error_text = response.text().await.unwrap_or_default();
-            return Err(VoiceError::ApiError(format!(
-                "Whisper API error: {}",
-                error_text
-            )));
+            return Err(VoiceError::ApiStatus {
+                status,
+                message: format!("Whisper API error: {}", error_text),
+            });
         }
 
         let result: serde_json::Value = response
@@ -609,6 +738,7 @@ impl VoiceInput for WhisperInput {
                     .collect()
             })
             .unwrap_or_default();
+        let words = parse_word_timings(&result);
 
         Ok(TranscriptionResult {
             text,
@@ -616,6 +746,7 @@ impl VoiceInput for WhisperInput {
             language,
             duration: Duration::from_secs_f64(duration_secs),
             segments,
+            words,
         })
     }
 
@@ -713,12 +844,15 @@ impl VoiceInput for LocalWhisperInput {
                 }
             }
 
+            let words = word_timings_from_segments(&segments);
+
             Ok::<_, VoiceError>(TranscriptionResult {
                 text: text.trim().to_string(),
                 confidence: None,
                 language: None,
                 duration: Duration::from_secs_f64(samples.len() as f64 / 16000.0),
                 segments,
+                words,
             })
         })
         .await
@@ -746,6 +880,10 @@ impl LocalWhisperInput {
             model_path: model_path.into(),
         }
     }
+
+    pub fn with_language(self, _language: &str) -> Self {
+        self
+    }
 }
 
 #[cfg(not(feature = "local-whisper"))]
@@ -779,6 +917,39 @@ mod tests {
         assert!(audio.duration >= Duration::from_millis(900));
     }
 
+    #[test]
+    fn test_parse_word_timings() {
+        let response = serde_json::json!({
+            "text": "Hello world",
+            "words": [
+                {"word": "Hello", "start": 0.0, "end": 0.42},
+                {"word": "world", "start": 0.5, "end": 1.1}
+            ]
+        });
+        let words = parse_word_timings(&response).unwrap();
+        assert_eq!(words.len(), 2);
+        assert_eq!(words[0], WordTiming { word: "Hello".into(), start_ms: 0, end_ms: 420 });
+        assert_eq!(words[1].start_ms, 500);
+        assert_eq!(words[1].end_ms, 1100);
+
+        // Providers that omit word timings degrade to None
+        assert!(parse_word_timings(&serde_json::json!({"text": "Hello"})).is_none());
+    }
+
+    #[test]
+    fn test_word_timings_from_segments() {
+        let segments = vec![TranscriptionSegment {
+            text: " ab cd".to_string(),
+            start: 1.0,
+            end: 2.0,
+        }];
+        let words = word_timings_from_segments(&segments).unwrap();
+        assert_eq!(words[0], WordTiming { word: "ab".into(), start_ms: 1000, end_ms: 1500 });
+        assert_eq!(words[1], WordTiming { word: "cd".into(), start_ms: 1500, end_ms: 2000 });
+
+        assert!(word_timings_from_segments(&[]).is_none());
+    }
+
     #[test]
     fn test_vad_config_default() {
         let config = VadConfig::default();
@@ -6,6 +6,7 @@
 //! - Speech-to-text (STT) via OpenAI Whisper or local whisper.cpp
 //! - Text-to-speech (TTS) via OpenAI TTS or ElevenLabs
 //! - Voice personalities with different speaking styles
+//! - SSML prosody control (pauses, emphasis, pronunciation)
 //! - Conversation management with turn-taking and interrupts
 //! - Audio recording and playback
 //! - Push-to-talk support
@@ -57,19 +58,21 @@ pub mod conversation;
 pub mod input;
 pub mod output;
 pub mod personality;
+pub mod retry;
 pub mod setup;
+pub mod ssml;
 
 pub use config::{VoiceConfig, VoiceConfigBuilder};
 pub use conversation::{ConversationEvent, ConversationState, VoiceConversation};
-pub use input::{AudioData, AudioRecorder, TranscriptionResult, VoiceInput, VoiceInputEvent, WhisperInput, LocalWhisperInput};
-pub use output::{AudioPlayer, OpenAITTS, ElevenLabsTTS, PiperTTS, OpenAIVoice, SpeechAudio, VoiceOutput, VoiceOutputEvent};
+pub use input::{AudioData, AudioRecorder, TranscriptionResult, VoiceInput, VoiceInputEvent, WhisperInput, LocalWhisperInput, WordTiming};
+pub use output::{AudioPlayer, DeviceFormat, OpenAITTS, ElevenLabsTTS, PiperTTS, OpenAIVoice, SpeechAudio, VoiceOutput, VoiceOutputEvent};
 pub use setup::{VoiceModels, VoiceSetupStatus, download_whisper_model, download_piper_voice, WHISPER_MODELS, PIPER_VOICES};
 pub use personality::{BuiltInPersonalities, Personality, PersonalityManager};
 
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use tokio::sync::mpsc;
-use tracing::{info, warn};
+use tracing::{debug, info, warn};
 
 use thiserror::Error;
 
@@ -84,6 +87,10 @@ pub enum VoiceError {
     #[error("API error: {0}")]
     ApiError(String),
 
+    /// Voice API returned an HTTP error status
+    #[error("API error ({status}): {message}")]
+    ApiStatus { status: u16, message: String },
+
     /// Transcription error
     #[error("Transcription error: {0}")]
     TranscriptionError(String),
@@ -105,14 +112,21 @@ pub enum VoiceError {
     IoError(#[from] std::io::Error),
 }
 
+impl VoiceError {
+    /// Whether the call may succeed if retried (rate limits and server errors)
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, VoiceError::ApiStatus { status, .. } if *status == 429 || *status >= 500)
+    }
+}
+
 /// Result type for voice operations
 pub type Result<T> = std::result::Result<T, VoiceError>;
 
 /// Voice manager event
 #[derive(Debug, Clone)]
 pub enum VoiceEvent {
-    /// Voice system initialized
-    Initialized,
+    /// Voice system initialized, with the speech-to-text backend in use (if any)
+    Initialized { stt_backend: Option<String> },
     /// Listening started
     ListeningStarted,
     /// Listening stopped
@@ -133,17 +147,38 @@ pub enum VoiceEvent {
     Error { message: String },
 }
 
+/// Snapshot of what the voice system can use on this machine
+#[derive(Debug, Clone)]
+pub struct VoiceDiagnostics {
+    /// Input (microphone) devices, or why they could not be listed
+    pub input_devices: std::result::Result<Vec<String>, String>,
+    /// Output (speaker) devices, or why they could not be listed
+    pub output_devices: std::result::Result<Vec<String>, String>,
+    /// Local model / TTS installation status
+    pub setup: VoiceSetupStatus,
+}
+
+impl VoiceDiagnostics {
+    /// Whether at least one microphone and one speaker were found
+    pub fn has_audio_devices(&self) -> bool {
+        self.input_devices.as_ref().is_ok_and(|d| !d.is_empty())
+            && self.output_devices.as_ref().is_ok_and(|d| !d.is_empty())
+    }
+}
+
 /// Main interface for the voice system
 pub struct VoiceManager {
     config: VoiceConfig,
     recorder: Option<AudioRecorder>,
     player: Option<AudioPlayer>,
-    whisper: Option<WhisperInput>,
+    whisper: Option<Box<dyn VoiceInput>>,
     tts: Option<Box<dyn VoiceOutput>>,
     personality_manager: PersonalityManager,
     conversation: VoiceConversation,
     is_listening: Arc<AtomicBool>,
     is_speaking: Arc<AtomicBool>,
+    /// Push-to-talk key is held and recording
+    ptt_held: Arc<AtomicBool>,
     event_tx: Option<mpsc::Sender<VoiceEvent>>,
 }
 
@@ -169,9 +204,9 @@ impl VoiceManager {
             None
         };
 
-        // Initialize Whisper
+        // Initialize speech-to-text
         let whisper = if config.enabled {
-            config.api_keys.get_openai_key().map(WhisperInput::new)
+            select_stt_backend(&config, &VoiceModels::new())
         } else {
             None
         };
@@ -209,7 +244,7 @@ impl VoiceManager {
         // Load custom personalities if configured
         if let Some(ref dir) = config.personality.custom_personalities_dir {
             if dir.exists() {
-                match personality_manager.load_from_directory(dir).await {
+                match personality_manager.watch_directory(dir) {
                     Ok(count) => info!("Loaded {} custom personalities", count),
                     Err(e) => warn!("Failed to load custom personalities: {}", e),
                 }
@@ -231,9 +266,20 @@ impl VoiceManager {
         };
         let conversation = VoiceConversation::new(conversation_config);
 
+        // Restore history from a previous session
+        if let Some(ref path) = config.advanced.history_path {
+            if path.exists() {
+                if let Err(e) = conversation.load_from(path).await {
+                    warn!("Failed to restore conversation history: {}", e);
+                }
+            }
+        }
+
         info!(
-            "Voice manager initialized (enabled: {}, TTS: {:?})",
-            config.enabled, config.output.tts_provider
+            "Voice manager initialized (enabled: {}, STT: {}, TTS: {:?})",
+            config.enabled,
+            whisper.as_ref().map(|w| w.name()).unwrap_or("none"),
+            config.output.tts_provider
         );
 
         Ok(Self {
@@ -246,6 +292,7 @@ impl VoiceManager {
             conversation,
             is_listening: Arc::new(AtomicBool::new(false)),
             is_speaking: Arc::new(AtomicBool::new(false)),
+            ptt_held: Arc::new(AtomicBool::new(false)),
             event_tx: None,
         })
     }
@@ -253,6 +300,14 @@ impl VoiceManager {
     /// Set the event channel for receiving voice events
     pub fn set_event_channel(&mut self, tx: mpsc::Sender<VoiceEvent>) {
         self.event_tx = Some(tx);
+        self.emit_event(VoiceEvent::Initialized {
+            stt_backend: self.stt_backend().map(str::to_string),
+        });
+    }
+
+    /// Name of the speech-to-text backend in use, if one is configured
+    pub fn stt_backend(&self) -> Option<&str> {
+        self.whisper.as_ref().map(|w| w.name())
     }
 
     /// Check if voice is enabled
@@ -271,7 +326,7 @@ impl VoiceManager {
     }
 
     /// Get the current personality
-    pub fn current_personality(&self) -> &Personality {
+    pub fn current_personality(&self) -> Arc<Personality> {
         self.personality_manager.current()
     }
 
@@ -295,6 +350,14 @@ impl VoiceManager {
         &self.conversation
     }
 
+    /// Save conversation history to `advanced.history_path`, if configured
+    pub async fn save_history(&self) -> Result<()> {
+        match self.config.advanced.history_path {
+            Some(ref path) => self.conversation.save_to(path).await,
+            None => Ok(()),
+        }
+    }
+
     /// Start listening for voice input
     pub fn start_listening(&self) -> Result<()> {
         if !self.config.enabled {
@@ -337,6 +400,52 @@ impl VoiceManager {
         Ok(Some(audio))
     }
 
+    /// Push-to-talk key pressed: record until `ptt_release`, without VAD.
+    ///
+    /// Returns `false` if the press was ignored because a recording is
+    /// already in progress.
+    pub fn ptt_press(&self) -> Result<bool> {
+        if self.is_listening.load(Ordering::SeqCst)
+            || self
+                .ptt_held
+                .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+                .is_err()
+        {
+            debug!("Push-to-talk press ignored, already recording");
+            return Ok(false);
+        }
+
+        if let Err(e) = self.start_listening() {
+            self.ptt_held.store(false, Ordering::SeqCst);
+            return Err(e);
+        }
+
+        Ok(true)
+    }
+
+    /// Push-to-talk key released: stop recording, transcribe and emit
+    /// `UserFinishedSpeaking`. A release without a press does nothing.
+    pub async fn ptt_release(&self) -> Result<Option<TranscriptionResult>> {
+        if self
+            .ptt_held
+            .compare_exchange(true, false, Ordering::SeqCst, Ordering::SeqCst)
+            .is_err()
+        {
+            return Ok(None);
+        }
+
+        let Some(audio) = self.stop_listening()? else {
+            return Ok(None);
+        };
+
+        let transcription = self.transcribe(&audio).await?;
+        self.emit_event(VoiceEvent::UserFinishedSpeaking {
+            text: transcription.text.clone(),
+        });
+
+        Ok(Some(transcription))
+    }
+
     /// Record audio with voice activity detection
     pub async fn record_with_vad(&self) -> Result<AudioData> {
         if !self.config.enabled {
@@ -384,17 +493,32 @@ impl VoiceManager {
             VoiceError::ConfigError("Whisper not configured".to_string())
         })?;
 
-        whisper.transcribe(audio).await
+        retry::with_retry(
+            &self.config.retry,
+            self.retry_notice("Transcription"),
+            || whisper.transcribe(audio),
+        )
+        .await
     }
 
     /// Speak text using TTS
     pub async fn speak(&self, text: &str) -> Result<()> {
-        self.speak_with_personality(text, self.personality_manager.current())
+        self.speak_with_personality(text, &self.personality_manager.current())
             .await
     }
 
     /// Speak text using a specific personality
     pub async fn speak_with_personality(&self, text: &str, personality: &Personality) -> Result<()> {
+        self.speak_inner(text, personality, false).await.map(|_| ())
+    }
+
+    /// Speak text, stopping as soon as the user starts talking over playback.
+    /// Returns `true` if the user interrupted.
+    pub async fn speak_with_barge_in(&self, text: &str, personality: &Personality) -> Result<bool> {
+        self.speak_inner(text, personality, true).await
+    }
+
+    async fn speak_inner(&self, text: &str, personality: &Personality, barge_in: bool) -> Result<bool> {
         if !self.config.enabled {
             return Err(VoiceError::ConfigError("Voice is not enabled".to_string()));
         }
@@ -413,16 +537,65 @@ impl VoiceManager {
         // Apply personality text modifications
         let modified_text = personality.apply_to_text(text);
 
-        // Generate speech
-        let audio = tts.synthesize(&modified_text).await?;
+        // Generate speech, wrapped in the personality's SSML template if it has one
+        let ssml = personality.wrap_ssml(&modified_text);
+        let audio = retry::with_retry(
+            &self.config.retry,
+            self.retry_notice("Speech synthesis"),
+            || match ssml {
+                Some(ref ssml) => tts.synthesize_ssml(ssml),
+                None => tts.synthesize(&modified_text),
+            },
+        )
+        .await;
+        let audio = match audio {
+            Ok(audio) => audio,
+            Err(e) => {
+                self.is_speaking.store(false, Ordering::SeqCst);
+                return Err(e);
+            }
+        };
 
-        // Play audio
-        player.play_and_wait(&audio, None).await?;
+        // Play audio, keeping the mic open for barge-in if requested
+        let interrupted = match self.recorder.as_ref().filter(|_| barge_in) {
+            Some(recorder) => {
+                let monitor =
+                    recorder.detect_voice_activity(self.config.advanced.barge_in_threshold);
+                let playback = player.play_and_wait(&audio, None);
+                tokio::pin!(monitor, playback);
+
+                tokio::select! {
+                    result = &mut playback => {
+                        result?;
+                        false
+                    }
+                    detected = &mut monitor => match detected {
+                        Ok(()) => true,
+                        Err(e) => {
+                            warn!("Barge-in monitor unavailable: {}", e);
+                            playback.await?;
+                            false
+                        }
+                    }
+                }
+            }
+            None => {
+                player.play_and_wait(&audio, None).await?;
+                false
+            }
+        };
 
         self.is_speaking.store(false, Ordering::SeqCst);
-        self.emit_event(VoiceEvent::AssistantFinishedSpeaking);
+        if interrupted {
+            player.stop();
+            self.emit_event(VoiceEvent::VoiceActivityDetected);
+            self.emit_event(VoiceEvent::UserInterrupted);
+            info!("Playback interrupted by user");
+        } else {
+            self.emit_event(VoiceEvent::AssistantFinishedSpeaking);
+        }
 
-        Ok(())
+        Ok(interrupted)
     }
 
     /// Stop current speech playback
@@ -484,6 +657,31 @@ impl VoiceManager {
         output::list_output_devices()
     }
 
+    /// Inspect audio devices and local voice setup without starting anything
+    pub fn diagnose() -> VoiceDiagnostics {
+        VoiceDiagnostics {
+            input_devices: Self::list_input_devices().map_err(|e| e.to_string()),
+            output_devices: Self::list_output_devices().map_err(|e| e.to_string()),
+            setup: VoiceSetupStatus::check(&VoiceModels::new()),
+        }
+    }
+
+    /// Report a retry attempt so the UI can show that a call is being retried
+    fn retry_notice<'a>(&'a self, operation: &'a str) -> impl FnMut(u32, &VoiceError, std::time::Duration) + 'a {
+        move |attempt, error, delay| {
+            let message = format!(
+                "{} failed ({}), retrying in {}ms (attempt {}/{})",
+                operation,
+                error,
+                delay.as_millis(),
+                attempt,
+                self.config.retry.max_retries
+            );
+            warn!("{}", message);
+            self.emit_event(VoiceEvent::Error { message });
+        }
+    }
+
     /// Emit a voice event
     fn emit_event(&self, event: VoiceEvent) {
         if let Some(ref tx) = self.event_tx {
@@ -496,7 +694,8 @@ impl VoiceManager {
     /// 1. Listen for user input
     /// 2. Transcribe speech to text
     /// 3. Call the response generator
-    /// 4. Speak the response
+    /// 4. Speak the response (the user can cut in by talking when
+    ///    `advanced.allow_interruptions` is set)
     pub async fn run_interaction<F, Fut>(
         &self,
         generate_response: F,
@@ -520,12 +719,60 @@ impl VoiceManager {
         let response = generate_response(user_text.clone()).await?;
 
         // Speak response
-        self.speak(&response).await?;
+        if self.config.advanced.allow_interruptions {
+            self.speak_with_barge_in(&response, &self.personality_manager.current())
+                .await?;
+        } else {
+            self.speak(&response).await?;
+        }
 
         Ok((user_text, response))
     }
 }
 
+/// Pick the speech-to-text backend for this config.
+///
+/// Local whisper.cpp is used when requested and a model is available; otherwise
+/// the OpenAI Whisper API if a key is set; otherwise local whisper.cpp as an
+/// offline fallback. Local whisper requires the `local-whisper` feature.
+fn select_stt_backend(config: &VoiceConfig, models: &VoiceModels) -> Option<Box<dyn VoiceInput>> {
+    let local = || -> Option<Box<dyn VoiceInput>> {
+        if !cfg!(feature = "local-whisper") {
+            return None;
+        }
+        let model_path = config
+            .input
+            .local_whisper_model
+            .clone()
+            .unwrap_or_else(|| models.whisper_model_path());
+        if !model_path.exists() {
+            return None;
+        }
+        let mut whisper = LocalWhisperInput::new(model_path);
+        if let Some(ref lang) = config.input.language {
+            whisper = whisper.with_language(lang);
+        }
+        Some(Box::new(whisper))
+    };
+
+    if config.input.use_local_whisper {
+        if let Some(whisper) = local() {
+            return Some(whisper);
+        }
+        warn!("Local Whisper requested but unavailable, trying OpenAI Whisper");
+    }
+
+    if let Some(key) = config.api_keys.get_openai_key() {
+        let mut whisper = WhisperInput::new(key);
+        if let Some(ref lang) = config.input.language {
+            whisper = whisper.with_language(lang);
+        }
+        return Some(Box::new(whisper));
+    }
+
+    local()
+}
+
 // Implement Default for VoiceManager using async initialization would require
 // a different approach, so we'll skip Default implementation
 
@@ -554,6 +801,50 @@ mod tests {
         assert!(manager.start_listening().is_err());
     }
 
+    #[tokio::test]
+    async fn test_push_to_talk_guards() {
+        let config = VoiceConfigBuilder::new()
+            .enabled(false)
+            .build()
+            .unwrap();
+        let manager = VoiceManager::new(config).await.unwrap();
+
+        // Release without a press is a no-op
+        assert!(manager.ptt_release().await.unwrap().is_none());
+
+        // A failed press does not leave the key stuck down
+        assert!(manager.ptt_press().is_err());
+        assert!(!manager.is_listening());
+        assert!(manager.ptt_release().await.unwrap().is_none());
+
+        // A second press while already recording is ignored
+        manager.is_listening.store(true, Ordering::SeqCst);
+        assert!(!manager.ptt_press().unwrap());
+    }
+
+    #[test]
+    fn test_stt_backend_selection() {
+        let models = VoiceModels {
+            base_dir: "/nonexistent/ganesha-voice".into(),
+            whisper_dir: "/nonexistent/ganesha-voice/whisper".into(),
+            piper_dir: "/nonexistent/ganesha-voice/piper".into(),
+        };
+
+        let mut config = VoiceConfig::default();
+        config.api_keys.use_env_vars = false;
+        config.api_keys.openai = None;
+        assert!(select_stt_backend(&config, &models).is_none());
+
+        config.api_keys.openai = Some("sk-test".to_string());
+        let backend = select_stt_backend(&config, &models).unwrap();
+        assert_eq!(backend.name(), "OpenAI Whisper");
+
+        // Local requested but no model downloaded: fall back to the API
+        config.input.use_local_whisper = true;
+        let backend = select_stt_backend(&config, &models).unwrap();
+        assert_eq!(backend.name(), "OpenAI Whisper");
+    }
+
     #[test]
     fn test_voice_config_validation() {
         let config = VoiceConfig::default();
@@ -7,15 +7,16 @@ use async_trait::async_trait;
 use bytes::Bytes;
 use parking_lot::Mutex;
 use cpal::traits::DeviceTrait;
-use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink};
+use rodio::buffer::SamplesBuffer;
+use rodio::{Decoder, OutputStream, OutputStreamHandle, Sink, Source};
 use std::io::Cursor;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::mpsc;
-use tracing::debug;
+use tracing::{debug, warn};
 
-use crate::{Result, VoiceError};
+use crate::{ssml, Result, VoiceError};
 
 /// OpenAI TTS voice options
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
@@ -73,7 +74,8 @@ impl Default for ElevenLabsVoice {
 /// Voice output settings
 #[derive(Debug, Clone)]
 pub struct VoiceOutputSettings {
-    /// Playback speed (0.5 to 2.0)
+    /// Playback speed (0.5 to 2.0), applied when a clip is resampled for
+    /// the output device
     pub speed: f32,
     /// Volume (0.0 to 1.0)
     pub volume: f32,
@@ -119,6 +121,11 @@ pub struct SpeechAudio {
     pub format: AudioFormat,
     /// Duration of the audio
     pub duration: Option<Duration>,
+    /// Sample rate in Hz. Decoded formats report their own rate; this is
+    /// what raw PCM is played at.
+    pub sample_rate: u32,
+    /// Number of interleaved channels
+    pub channels: u16,
     /// The text that was synthesized
     pub text: String,
 }
@@ -139,6 +146,97 @@ impl SpeechAudio {
             .await
             .map_err(|e| VoiceError::AudioError(format!("Failed to save audio: {}", e)))
     }
+
+    /// Decode to interleaved f32 samples, returning them with the actual
+    /// sample rate and channel count
+    fn decode(&self) -> Result<(Vec<f32>, u32, u16)> {
+        if self.format == AudioFormat::Pcm {
+            // Raw 16-bit little-endian PCM
+            let samples = self
+                .data
+                .chunks_exact(2)
+                .map(|b| i16::from_le_bytes([b[0], b[1]]) as f32 / i16::MAX as f32)
+                .collect();
+            return Ok((samples, self.sample_rate, self.channels));
+        }
+
+        let decoder = Decoder::new(Cursor::new(self.data.to_vec()))
+            .map_err(|e| VoiceError::AudioError(format!("Failed to decode audio: {}", e)))?;
+        let (sample_rate, channels) = (decoder.sample_rate(), decoder.channels());
+        if sample_rate != self.sample_rate || channels != self.channels {
+            debug!(
+                "Decoded audio is {}Hz/{}ch (expected {}Hz/{}ch)",
+                sample_rate, channels, self.sample_rate, self.channels
+            );
+        }
+
+        Ok((decoder.convert_samples().collect(), sample_rate, channels))
+    }
+}
+
+/// Sample rate and channel layout of an output device
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeviceFormat {
+    pub sample_rate: u32,
+    pub channels: u16,
+}
+
+impl DeviceFormat {
+    /// Format of the default output device, if it can be queried
+    pub fn default_output() -> Option<Self> {
+        use cpal::traits::HostTrait;
+
+        let config = cpal::default_host()
+            .default_output_device()?
+            .default_output_config()
+            .ok()?;
+        Some(Self {
+            sample_rate: config.sample_rate().0,
+            channels: config.channels(),
+        })
+    }
+}
+
+/// Linearly resample interleaved samples from `from_rate` to `to_rate`
+pub fn resample_linear(samples: &[f32], channels: u16, from_rate: u32, to_rate: u32) -> Vec<f32> {
+    let channels = channels.max(1) as usize;
+    let frames = samples.len() / channels;
+    if from_rate == to_rate || from_rate == 0 || to_rate == 0 || frames == 0 {
+        return samples.to_vec();
+    }
+
+    let out_frames = (frames as u64 * to_rate as u64 / from_rate as u64) as usize;
+    let step = from_rate as f64 / to_rate as f64;
+    let mut out = Vec::with_capacity(out_frames * channels);
+
+    for i in 0..out_frames {
+        let pos = i as f64 * step;
+        let index = (pos as usize).min(frames - 1);
+        let next = (index + 1).min(frames - 1);
+        let frac = (pos - index as f64) as f32;
+        for c in 0..channels {
+            let a = samples[index * channels + c];
+            let b = samples[next * channels + c];
+            out.push(a + (b - a) * frac);
+        }
+    }
+
+    out
+}
+
+/// Decode `audio` and resample it to the device rate, so playback speed
+/// does not depend on what rate the TTS backend produced.
+///
+/// `speed` is applied in the same pass, by treating the audio as if it were
+/// recorded `speed` times faster. The buffer then always runs at the device
+/// rate, rather than the sink's speed control handing the device a rate it
+/// does not run at.
+fn buffer_for_device(audio: &SpeechAudio, device: Option<DeviceFormat>, speed: f32) -> Result<SamplesBuffer<f32>> {
+    let (samples, sample_rate, channels) = audio.decode()?;
+    let target_rate = device.map(|d| d.sample_rate).unwrap_or(sample_rate);
+    let played_rate = (sample_rate as f64 * speed as f64).round() as u32;
+    let samples = resample_linear(&samples, channels, played_rate, target_rate);
+    Ok(SamplesBuffer::new(channels.max(1), target_rate, samples))
 }
 
 /// Trait for voice output (text-to-speech) implementations
@@ -150,6 +248,12 @@ pub trait VoiceOutput: Send + Sync {
     /// Generate speech from text
     async fn synthesize(&self, text: &str) -> Result<SpeechAudio>;
 
+    /// Generate speech from an SSML document. Backends without SSML support
+    /// speak the text with all tags stripped.
+    async fn synthesize_ssml(&self, ssml: &str) -> Result<SpeechAudio> {
+        self.synthesize(&ssml::strip_tags(ssml)).await
+    }
+
     /// Check if this implementation is available
     async fn is_available(&self) -> bool;
 
@@ -160,6 +264,12 @@ pub trait VoiceOutput: Send + Sync {
     async fn list_voices(&self) -> Result<Vec<String>>;
 }
 
+/// OpenAI TTS returns 24kHz mono MP3
+const OPENAI_SAMPLE_RATE: u32 = 24_000;
+
+/// ElevenLabs' default `mp3_44100_128` output format
+const ELEVENLABS_SAMPLE_RATE: u32 = 44_100;
+
 /// Audio playback manager
 pub struct AudioPlayer {
     _stream: OutputStream,
@@ -167,6 +277,8 @@ pub struct AudioPlayer {
     sink: Arc<Mutex<Option<Sink>>>,
     is_playing: Arc<AtomicBool>,
     settings: VoiceOutputSettings,
+    /// Output device format; `None` plays audio at its own rate
+    device: Option<DeviceFormat>,
 }
 
 impl AudioPlayer {
@@ -175,15 +287,26 @@ impl AudioPlayer {
         let (stream, handle) = OutputStream::try_default()
             .map_err(|e| VoiceError::AudioError(format!("Failed to create output stream: {}", e)))?;
 
+        let device = DeviceFormat::default_output();
+        if device.is_none() {
+            warn!("Could not query output device format; audio will not be resampled");
+        }
+
         Ok(Self {
             _stream: stream,
             handle,
             sink: Arc::new(Mutex::new(None)),
             is_playing: Arc::new(AtomicBool::new(false)),
             settings: VoiceOutputSettings::default(),
+            device,
         })
     }
 
+    /// Format of the output device audio is resampled to
+    pub fn device_format(&self) -> Option<DeviceFormat> {
+        self.device
+    }
+
     /// Set playback settings
     pub fn set_settings(&mut self, settings: VoiceOutputSettings) {
         self.settings = settings;
@@ -202,15 +325,12 @@ impl AudioPlayer {
 
         self.stop();
 
-        let cursor = Cursor::new(audio.data.to_vec());
-        let source = Decoder::new(cursor)
-            .map_err(|e| VoiceError::AudioError(format!("Failed to decode audio: {}", e)))?;
+        let source = buffer_for_device(audio, self.device, self.settings.speed)?;
 
         let sink = Sink::try_new(&self.handle)
             .map_err(|e| VoiceError::AudioError(format!("Failed to create sink: {}", e)))?;
 
         sink.set_volume(self.settings.volume);
-        sink.set_speed(self.settings.speed);
         sink.append(source);
 
         self.is_playing.store(true, Ordering::SeqCst);
@@ -298,12 +418,11 @@ impl AudioPlayer {
         }
     }
 
-    /// Set playback speed (0.5 to 2.0)
+    /// Set playback speed (0.5 to 2.0). Speed is applied while resampling a
+    /// clip for the device, so a clip already playing keeps its speed and
+    /// the new one takes effect from the next clip.
     pub fn set_speed(&mut self, speed: f32) {
         self.settings.speed = speed.clamp(0.5, 2.0);
-        if let Some(ref sink) = *self.sink.lock() {
-            sink.set_speed(self.settings.speed);
-        }
     }
 }
 
@@ -313,6 +432,7 @@ pub struct OpenAITTS {
     voice: OpenAIVoice,
     model: String,
     speed: f32,
+    strict_ssml: bool,
     client: reqwest::Client,
 }
 
@@ -324,6 +444,7 @@ impl OpenAITTS {
             voice: OpenAIVoice::default(),
             model: "tts-1".to_string(),
             speed: 1.0,
+            strict_ssml: false,
             client: reqwest::Client::new(),
         }
     }
@@ -346,6 +467,12 @@ impl OpenAITTS {
         self
     }
 
+    /// Reject SSML with tags this backend cannot translate instead of dropping them
+    pub fn with_strict_ssml(mut self, strict: bool) -> Self {
+        self.strict_ssml = strict;
+        self
+    }
+
     /// Change the voice
     pub fn set_voice(&mut self, voice: OpenAIVoice) {
         self.voice = voice;
@@ -390,8 +517,12 @@ impl VoiceOutput for OpenAITTS {
             .map_err(|e| VoiceError::ApiError(format!("Request failed: {}", e)))?;
 
         if !response.status().is_success() {
+            let status = response.status().as_u16();
             let error_text = response.text().await.unwrap_or_default();
-            return Err(VoiceError::ApiError(format!("TTS API error: {}", error_text)));
+            return Err(VoiceError::ApiStatus {
+                status,
+                message: format!("TTS API error: {}", error_text),
+            });
         }
 
         let bytes = response
@@ -403,10 +534,21 @@ impl VoiceOutput for OpenAITTS {
             data: bytes,
             format: AudioFormat::Mp3,
             duration: None,
+            sample_rate: OPENAI_SAMPLE_RATE,
+            channels: 1,
             text: text.to_string(),
         })
     }
 
+    /// OpenAI has no SSML support, so supported tags become text cues
+    /// (see `ssml::to_plain_text`)
+    async fn synthesize_ssml(&self, ssml: &str) -> Result<SpeechAudio> {
+        if self.strict_ssml {
+            ssml::check_supported(ssml, self.name(), ssml::OPENAI_TAGS)?;
+        }
+        self.synthesize(&ssml::to_plain_text(ssml)).await
+    }
+
     async fn is_available(&self) -> bool {
         !self.api_key.is_empty()
     }
@@ -429,6 +571,7 @@ pub struct ElevenLabsTTS {
     voice: ElevenLabsVoice,
     stability: f32,
     similarity_boost: f32,
+    strict_ssml: bool,
     client: reqwest::Client,
 }
 
@@ -440,6 +583,7 @@ impl ElevenLabsTTS {
             voice: ElevenLabsVoice::default(),
             stability: 0.5,
             similarity_boost: 0.75,
+            strict_ssml: false,
             client: reqwest::Client::new(),
         }
     }
@@ -457,6 +601,12 @@ impl ElevenLabsTTS {
         self
     }
 
+    /// Reject SSML with tags ElevenLabs does not honor instead of dropping them
+    pub fn with_strict_ssml(mut self, strict: bool) -> Self {
+        self.strict_ssml = strict;
+        self
+    }
+
     /// Set voice by ID
     pub fn set_voice_id(&mut self, voice_id: &str) {
         self.voice.voice_id = voice_id.to_string();
@@ -537,11 +687,12 @@ impl VoiceOutput for ElevenLabsTTS {
             .map_err(|e| VoiceError::ApiError(format!("Request failed: {}", e)))?;
 
         if !response.status().is_success() {
+            let status = response.status().as_u16();
             let error_text = response.text().await.unwrap_or_default();
-            return Err(VoiceError::ApiError(format!(
-                "ElevenLabs API error: {}",
-                error_text
-            )));
+            return Err(VoiceError::ApiStatus {
+                status,
+                message: format!("ElevenLabs API error: {}", error_text),
+            });
         }
 
         let bytes = response
@@ -553,10 +704,20 @@ impl VoiceOutput for ElevenLabsTTS {
             data: bytes,
             format: AudioFormat::Mp3,
             duration: None,
+            sample_rate: ELEVENLABS_SAMPLE_RATE,
+            channels: 1,
             text: text.to_string(),
         })
     }
 
+    /// ElevenLabs reads break, emphasis and phoneme tags inline; other tags are dropped
+    async fn synthesize_ssml(&self, ssml: &str) -> Result<SpeechAudio> {
+        if self.strict_ssml {
+            ssml::check_supported(ssml, self.name(), ssml::ELEVENLABS_TAGS)?;
+        }
+        self.synthesize(&ssml::retain_tags(ssml, ssml::ELEVENLABS_TAGS)).await
+    }
+
     async fn is_available(&self) -> bool {
         !self.api_key.is_empty()
     }
@@ -711,10 +872,17 @@ impl VoiceOutput for PiperTTS {
         // Clean up temp file
         let _ = std::fs::remove_file(&output_file);
 
+        // The WAV header is authoritative; fall back to Piper's usual format
+        let (sample_rate, channels) = hound::WavReader::new(Cursor::new(&audio_data))
+            .map(|reader| (reader.spec().sample_rate, reader.spec().channels))
+            .unwrap_or((22050, 1));
+
         Ok(SpeechAudio {
             data: Bytes::from(audio_data),
             format: AudioFormat::Wav,
             duration: None,
+            sample_rate,
+            channels,
             text: text.to_string(),
         })
     }
@@ -781,6 +949,70 @@ mod tests {
         assert!(settings.playback_enabled);
     }
 
+    #[test]
+    fn test_resample_to_device_rate() {
+        // One second of 22.05kHz mono PCM
+        let data: Vec<u8> = (0..22_050)
+            .flat_map(|i| (((i % 100) as i16) * 100).to_le_bytes())
+            .collect();
+        let audio = SpeechAudio {
+            data: Bytes::from(data),
+            format: AudioFormat::Pcm,
+            duration: None,
+            sample_rate: 22_050,
+            channels: 1,
+            text: String::new(),
+        };
+        let device = DeviceFormat {
+            sample_rate: 48_000,
+            channels: 2,
+        };
+
+        let buffer = buffer_for_device(&audio, Some(device), 1.0).unwrap();
+        assert_eq!(buffer.sample_rate(), 48_000);
+        assert_eq!(buffer.channels(), 1);
+        assert_eq!(buffer.count(), 48_000);
+    }
+
+    #[test]
+    fn test_speed_is_applied_at_the_device_rate() {
+        // One second of 22.05kHz mono PCM on a 48kHz device
+        let audio = SpeechAudio {
+            data: Bytes::from(vec![0u8; 2 * 22_050]),
+            format: AudioFormat::Pcm,
+            duration: None,
+            sample_rate: 22_050,
+            channels: 1,
+            text: String::new(),
+        };
+        let device = DeviceFormat {
+            sample_rate: 48_000,
+            channels: 1,
+        };
+
+        // Double speed: half a second, still at the device rate
+        let fast = buffer_for_device(&audio, Some(device), 2.0).unwrap();
+        assert_eq!(fast.sample_rate(), 48_000);
+        assert_eq!(fast.count(), 24_000);
+
+        let slow = buffer_for_device(&audio, Some(device), 0.5).unwrap();
+        assert_eq!(slow.sample_rate(), 48_000);
+        assert_eq!(slow.count(), 96_000);
+
+        // With no known device the audio's own rate is kept
+        let fast = buffer_for_device(&audio, None, 2.0).unwrap();
+        assert_eq!(fast.sample_rate(), 22_050);
+        assert_eq!(fast.count(), 11_025);
+    }
+
+    #[test]
+    fn test_resample_linear_stereo() {
+        let samples = [0.0, 1.0, 1.0, 0.0];
+        let out = resample_linear(&samples, 2, 1, 2);
+        assert_eq!(out, vec![0.0, 1.0, 0.5, 0.5, 1.0, 0.0, 1.0, 0.0]);
+        assert_eq!(resample_linear(&samples, 2, 44_100, 44_100), samples.to_vec());
+    }
+
     #[test]
     fn test_elevenlabs_voice_default() {
         let voice = ElevenLabsVoice::default();
@@ -3,9 +3,13 @@
 //! Provides different voice personalities that modify how the AI assistant
 //! communicates, including speaking style, voice selection, and system prompts.
 
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::{info, warn};
 
 use crate::output::OpenAIVoice;
 use crate::{Result, VoiceError};
@@ -32,6 +36,10 @@ pub struct Personality {
     /// Custom phrases for specific situations
     #[serde(default)]
     pub custom_phrases: HashMap<String, String>,
+    /// SSML wrapped around spoken text; `{text}` marks where the text goes,
+    /// e.g. `<speak><prosody rate="slow">{text}</prosody></speak>`
+    #[serde(default)]
+    pub ssml_template: Option<String>,
 }
 
 /// Voice selection for a personality
@@ -121,6 +129,7 @@ impl Personality {
             greeting: None,
             farewell: None,
             custom_phrases: HashMap::new(),
+            ssml_template: None,
         }
     }
 
@@ -166,6 +175,19 @@ impl Personality {
         self
     }
 
+    /// Set the SSML template (`{text}` is replaced by the spoken text)
+    pub fn with_ssml_template(mut self, template: impl Into<String>) -> Self {
+        self.ssml_template = Some(template.into());
+        self
+    }
+
+    /// Wrap text in the SSML template, if this personality has one
+    pub fn wrap_ssml(&self, text: &str) -> Option<String> {
+        self.ssml_template
+            .as_ref()
+            .map(|template| template.replace("{text}", &crate::ssml::escape(text)))
+    }
+
     /// Get the OpenAI voice for this personality
     pub fn openai_voice(&self) -> OpenAIVoice {
         self.voice.openai_voice
@@ -209,7 +231,12 @@ impl Personality {
             .await
             .map_err(|e| VoiceError::ConfigError(format!("Failed to read personality file: {}", e)))?;
 
-        toml::from_str(&content)
+        Self::parse_toml(&content)
+    }
+
+    /// Parse a personality from TOML
+    fn parse_toml(content: &str) -> Result<Self> {
+        toml::from_str(content)
             .map_err(|e| VoiceError::ConfigError(format!("Failed to parse personality: {}", e)))
     }
 
@@ -377,10 +404,69 @@ impl BuiltInPersonalities {
     }
 }
 
+/// Personalities shared between the manager and its directory watcher
+struct Registry {
+    personalities: HashMap<String, Arc<Personality>>,
+    /// Personality ID loaded from each custom file
+    sources: HashMap<PathBuf, String>,
+    current: String,
+}
+
+impl Registry {
+    /// Apply the on-disk state of one personality file to `personalities`.
+    ///
+    /// An invalid file keeps the previously loaded version; a deleted file
+    /// removes its personality unless it is the one currently selected.
+    fn apply_file(&mut self, personalities: &mut HashMap<String, Arc<Personality>>, path: &Path) -> bool {
+        if path.exists() {
+            match std::fs::read_to_string(path)
+                .map_err(|e| VoiceError::ConfigError(format!("Failed to read personality file: {}", e)))
+                .and_then(|content| Personality::parse_toml(&content))
+            {
+                Ok(personality) => {
+                    let id = personality.id.clone();
+                    if let Some(old_id) = self.sources.insert(path.to_path_buf(), id.clone()) {
+                        if old_id != id && old_id != self.current {
+                            personalities.remove(&old_id);
+                        }
+                    }
+                    personalities.insert(id, Arc::new(personality));
+                    true
+                }
+                Err(e) => {
+                    let selected = self.sources.get(path) == Some(&self.current);
+                    if selected {
+                        warn!("Keeping previous version of current personality; {:?} is invalid: {}", path, e);
+                    } else {
+                        warn!("Failed to load personality from {:?}: {}", path, e);
+                    }
+                    false
+                }
+            }
+        } else {
+            if let Some(id) = self.sources.remove(path) {
+                if id == self.current {
+                    warn!("Personality file for current personality '{}' was removed; keeping it loaded", id);
+                } else if BuiltInPersonalities::by_id(&id).is_none() {
+                    personalities.remove(&id);
+                }
+            }
+            false
+        }
+    }
+}
+
+fn is_personality_file(path: &Path) -> bool {
+    path.extension().map(|e| e == "toml").unwrap_or(false)
+}
+
 /// Personality manager for loading and managing personalities
 pub struct PersonalityManager {
-    personalities: HashMap<String, Personality>,
-    current: String,
+    registry: Arc<RwLock<Registry>>,
+    /// Directory of custom personality files, once one has been loaded
+    directory: Option<PathBuf>,
+    /// Keeps the directory watcher alive (see `watch_directory`)
+    watcher: Option<RecommendedWatcher>,
 }
 
 impl PersonalityManager {
@@ -388,26 +474,36 @@ impl PersonalityManager {
     pub fn new() -> Self {
         let mut personalities = HashMap::new();
         for p in BuiltInPersonalities::all() {
-            personalities.insert(p.id.clone(), p);
+            personalities.insert(p.id.clone(), Arc::new(p));
         }
 
         Self {
-            personalities,
-            current: "friendly".to_string(),
+            registry: Arc::new(RwLock::new(Registry {
+                personalities,
+                sources: HashMap::new(),
+                current: "friendly".to_string(),
+            })),
+            directory: None,
+            watcher: None,
         }
     }
 
     /// Get the current personality
-    pub fn current(&self) -> &Personality {
-        self.personalities
-            .get(&self.current)
-            .unwrap_or_else(|| self.personalities.get("friendly").unwrap())
+    pub fn current(&self) -> Arc<Personality> {
+        let registry = self.registry.read();
+        registry
+            .personalities
+            .get(&registry.current)
+            .or_else(|| registry.personalities.get("friendly"))
+            .cloned()
+            .unwrap_or_else(|| Arc::new(BuiltInPersonalities::friendly()))
     }
 
     /// Set the current personality by ID
     pub fn set_current(&mut self, id: &str) -> Result<()> {
-        if self.personalities.contains_key(id) {
-            self.current = id.to_string();
+        let mut registry = self.registry.write();
+        if registry.personalities.contains_key(id) {
+            registry.current = id.to_string();
             Ok(())
         } else {
             Err(VoiceError::ConfigError(format!(
@@ -419,7 +515,10 @@ impl PersonalityManager {
 
     /// Add a custom personality
     pub fn add(&mut self, personality: Personality) {
-        self.personalities.insert(personality.id.clone(), personality);
+        self.registry
+            .write()
+            .personalities
+            .insert(personality.id.clone(), Arc::new(personality));
     }
 
     /// Remove a personality (cannot remove built-in ones)
@@ -430,26 +529,27 @@ impl PersonalityManager {
             ));
         }
 
-        self.personalities.remove(id);
-        if self.current == id {
-            self.current = "friendly".to_string();
+        let mut registry = self.registry.write();
+        registry.personalities.remove(id);
+        if registry.current == id {
+            registry.current = "friendly".to_string();
         }
         Ok(())
     }
 
     /// Get a personality by ID
-    pub fn get(&self, id: &str) -> Option<&Personality> {
-        self.personalities.get(id)
+    pub fn get(&self, id: &str) -> Option<Arc<Personality>> {
+        self.registry.read().personalities.get(id).cloned()
     }
 
     /// List all personality IDs
-    pub fn list(&self) -> Vec<&str> {
-        self.personalities.keys().map(|s| s.as_str()).collect()
+    pub fn list(&self) -> Vec<String> {
+        self.registry.read().personalities.keys().cloned().collect()
     }
 
     /// Load custom personalities from a directory
     pub async fn load_from_directory(&mut self, dir: &Path) -> Result<usize> {
-        let mut count = 0;
+        let mut paths = Vec::new();
 
         let mut entries = tokio::fs::read_dir(dir)
             .await
@@ -459,21 +559,83 @@ impl PersonalityManager {
             VoiceError::ConfigError(format!("Failed to read directory entry: {}", e))
         })? {
             let path = entry.path();
-            if path.extension().map(|e| e == "toml").unwrap_or(false) {
-                match Personality::load_from_file(&path).await {
-                    Ok(personality) => {
-                        self.add(personality);
-                        count += 1;
-                    }
-                    Err(e) => {
-                        tracing::warn!("Failed to load personality from {:?}: {}", path, e);
+            if is_personality_file(&path) {
+                paths.push(path);
+            }
+        }
+
+        self.directory = Some(dir.to_path_buf());
+        Ok(Self::apply_files(&self.registry, &paths))
+    }
+
+    /// Re-read every personality file in the loaded directory and swap the
+    /// results in at once. Returns the number of files loaded successfully.
+    pub fn reload_all(&self) -> Result<usize> {
+        let Some(ref dir) = self.directory else {
+            return Ok(0);
+        };
+
+        let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+            .map_err(|e| VoiceError::ConfigError(format!("Failed to read directory: {}", e)))?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| is_personality_file(path))
+            .collect();
+
+        // Files loaded before but gone now
+        let known: Vec<PathBuf> = self.registry.read().sources.keys().cloned().collect();
+        for path in known {
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+
+        Ok(Self::apply_files(&self.registry, &paths))
+    }
+
+    /// Load `dir` and keep reloading personality files as they change on disk
+    /// until the manager is dropped. Returns the number of files loaded.
+    pub fn watch_directory(&mut self, dir: &Path) -> Result<usize> {
+        self.directory = Some(dir.to_path_buf());
+        let count = self.reload_all()?;
+
+        let registry = self.registry.clone();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            match event {
+                Ok(event) => {
+                    let paths: Vec<PathBuf> = event
+                        .paths
+                        .into_iter()
+                        .filter(|path| is_personality_file(path))
+                        .collect();
+                    if !paths.is_empty() {
+                        let loaded = Self::apply_files(&registry, &paths);
+                        info!("Reloaded {} personality file(s)", loaded);
                     }
                 }
+                Err(e) => warn!("Personality watcher error: {}", e),
             }
-        }
+        })
+        .map_err(|e| VoiceError::ConfigError(format!("Failed to watch personalities: {}", e)))?;
+
+        watcher
+            .watch(dir, RecursiveMode::NonRecursive)
+            .map_err(|e| VoiceError::ConfigError(format!("Failed to watch {:?}: {}", dir, e)))?;
+        self.watcher = Some(watcher);
 
         Ok(count)
     }
+
+    /// Apply file changes to a copy of the map, then swap it in
+    fn apply_files(registry: &RwLock<Registry>, paths: &[PathBuf]) -> usize {
+        let mut registry = registry.write();
+        let mut personalities = registry.personalities.clone();
+        let loaded = paths
+            .iter()
+            .filter(|path| registry.apply_file(&mut personalities, path))
+            .count();
+        registry.personalities = personalities;
+        loaded
+    }
 }
 
 impl Default for PersonalityManager {
@@ -510,6 +672,33 @@ mod tests {
         assert!(manager.set_current("nonexistent").is_err());
     }
 
+    #[test]
+    fn test_reload_all_keeps_valid_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pirate.toml");
+
+        let mut pirate = BuiltInPersonalities::friendly();
+        pirate.id = "pirate".to_string();
+        pirate.name = "Pirate".to_string();
+        std::fs::write(&path, toml::to_string(&pirate).unwrap()).unwrap();
+
+        let mut manager = PersonalityManager::new();
+        manager.directory = Some(dir.path().to_path_buf());
+        assert_eq!(manager.reload_all().unwrap(), 1);
+        manager.set_current("pirate").unwrap();
+
+        // A valid edit is picked up
+        pirate.name = "Captain".to_string();
+        std::fs::write(&path, toml::to_string(&pirate).unwrap()).unwrap();
+        assert_eq!(manager.reload_all().unwrap(), 1);
+        assert_eq!(manager.current().name, "Captain");
+
+        // A broken edit keeps the previous version
+        std::fs::write(&path, "id = [not valid").unwrap();
+        assert_eq!(manager.reload_all().unwrap(), 0);
+        assert_eq!(manager.current().name, "Captain");
+    }
+
     #[test]
     fn test_personality_text_application() {
         let professional = BuiltInPersonalities::professional();
@@ -537,4 +726,16 @@ mod tests {
         assert_eq!(custom.name, "Robot");
         assert_eq!(custom.greeting, Some("GREETINGS HUMAN".to_string()));
     }
+
+    #[test]
+    fn test_ssml_template() {
+        assert!(BuiltInPersonalities::friendly().wrap_ssml("Hi").is_none());
+
+        let calm = Personality::new("calm", "Calm")
+            .with_ssml_template(r#"<speak><break time="300ms"/>{text}</speak>"#);
+        assert_eq!(
+            calm.wrap_ssml("Fish & chips").unwrap(),
+            r#"<speak><break time="300ms"/>Fish &amp; chips</speak>"#
+        );
+    }
 }
@@ -0,0 +1,195 @@
+//! Retry with exponential backoff for voice API calls.
+//!
+//! Rate limits (429) and server errors (5xx) are retried; other errors,
+//! including auth failures, are returned immediately. Retrying stops once
+//! the policy's deadline would be exceeded. The deadline starts at the
+//! first failure, so a slow but successful first attempt (a long synthesis)
+//! is never cut short by it.
+
+use std::future::Future;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crate::config::RetryConfig;
+use crate::{Result, VoiceError};
+
+/// Delay before retry number `attempt + 1`: the base delay doubled per
+/// attempt, plus up to `jitter` of itself at random
+pub fn backoff_delay(policy: &RetryConfig, attempt: u32) -> Duration {
+    let base = policy
+        .base_delay_ms
+        .saturating_mul(1u64 << attempt.min(16))
+        .min(policy.deadline_ms);
+    let jitter = (base as f64 * policy.jitter.clamp(0.0, 1.0) as f64 * jitter_fraction()) as u64;
+    Duration::from_millis(base + jitter)
+}
+
+/// Cheap pseudo-random fraction in [0, 1) - good enough to spread out retries
+fn jitter_fraction() -> f64 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    (nanos % 1000) as f64 / 1000.0
+}
+
+/// Run `op`, retrying transient failures according to `policy`.
+///
+/// `on_retry` is called before each retry with the attempt number (starting
+/// at 1), the error that triggered it and the delay before trying again.
+/// Backoff and retry attempts share `policy.deadline_ms`, counted from the
+/// first failure; a retry still running at the deadline is abandoned.
+pub async fn with_retry<T, F, Fut>(
+    policy: &RetryConfig,
+    mut on_retry: impl FnMut(u32, &VoiceError, Duration),
+    mut op: F,
+) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let deadline = Duration::from_millis(policy.deadline_ms);
+    let mut retry_start: Option<Instant> = None;
+    let mut attempt = 0;
+
+    loop {
+        let result = match retry_start {
+            None => op().await,
+            Some(start) => {
+                let remaining = deadline.saturating_sub(start.elapsed());
+                match tokio::time::timeout(remaining, op()).await {
+                    Ok(result) => result,
+                    Err(_) => {
+                        return Err(VoiceError::ApiError(format!(
+                            "No response within the {}ms retry deadline",
+                            policy.deadline_ms
+                        )))
+                    }
+                }
+            }
+        };
+
+        match result {
+            Ok(value) => return Ok(value),
+            Err(e) if e.is_retryable() && attempt < policy.max_retries => {
+                let start = *retry_start.get_or_insert_with(Instant::now);
+                let delay = backoff_delay(policy, attempt);
+                if start.elapsed() + delay >= deadline {
+                    return Err(e);
+                }
+                attempt += 1;
+                on_retry(attempt, &e, delay);
+                tokio::time::sleep(delay).await;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn policy(max_retries: u32, base_delay_ms: u64, deadline_ms: u64) -> RetryConfig {
+        RetryConfig {
+            max_retries,
+            base_delay_ms,
+            jitter: 0.0,
+            deadline_ms,
+        }
+    }
+
+    fn status(status: u16) -> VoiceError {
+        VoiceError::ApiStatus {
+            status,
+            message: "test".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_backoff_doubles() {
+        let policy = policy(5, 100, 10_000);
+        assert_eq!(backoff_delay(&policy, 0), Duration::from_millis(100));
+        assert_eq!(backoff_delay(&policy, 1), Duration::from_millis(200));
+        assert_eq!(backoff_delay(&policy, 3), Duration::from_millis(800));
+    }
+
+    #[tokio::test]
+    async fn test_retries_rate_limit_then_succeeds() {
+        let calls = AtomicU32::new(0);
+        let mut retries = Vec::new();
+
+        let result = with_retry(
+            &policy(3, 1, 5_000),
+            |attempt, _, _| retries.push(attempt),
+            || async {
+                match calls.fetch_add(1, Ordering::SeqCst) {
+                    0 => Err(status(429)),
+                    1 => Err(status(503)),
+                    _ => Ok("done"),
+                }
+            },
+        )
+        .await;
+
+        assert_eq!(result.unwrap(), "done");
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert_eq!(retries, vec![1, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_auth_errors_fail_fast() {
+        let calls = AtomicU32::new(0);
+
+        let result: Result<()> = with_retry(&policy(3, 1, 5_000), |_, _, _| {}, || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err(status(401))
+        })
+        .await;
+
+        assert!(matches!(result, Err(VoiceError::ApiStatus { status: 401, .. })));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_deadline_stops_retrying() {
+        let calls = AtomicU32::new(0);
+
+        // The first backoff already overshoots the deadline, so no retry happens
+        let result: Result<()> = with_retry(&policy(5, 200, 100), |_, _, _| {}, || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            Err(status(500))
+        })
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_deadline_does_not_limit_first_attempt() {
+        let calls = AtomicU32::new(0);
+
+        // Succeeds, but only after longer than the whole deadline
+        let result = with_retry(&policy(3, 1, 20), |_, _, _| {}, || async {
+            calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(60)).await;
+            Ok("slow")
+        })
+        .await;
+        assert_eq!(result.unwrap(), "slow");
+
+        // A retry that outlasts the deadline is abandoned
+        calls.store(0, Ordering::SeqCst);
+        let result: Result<&str> = with_retry(&policy(3, 1, 20), |_, _, _| {}, || async {
+            if calls.fetch_add(1, Ordering::SeqCst) == 0 {
+                return Err(status(503));
+            }
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            Ok("too late")
+        })
+        .await;
+        assert!(matches!(result, Err(VoiceError::ApiError(_))), "{:?}", result);
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+}
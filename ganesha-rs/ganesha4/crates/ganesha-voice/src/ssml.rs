@@ -0,0 +1,321 @@
+//! SSML helpers for text-to-speech backends.
+//!
+//! Backends honor different subsets of SSML:
+//! - ElevenLabs: `<break>`, `<emphasis>` and `<phoneme>` are passed through
+//! - OpenAI: no SSML support; `<break>`, `<emphasis>`, `<sub>` and
+//!   `<say-as interpret-as="characters">` are rewritten as plain-text cues
+//! - Anything else: tags are stripped and the text is spoken as-is
+//!
+//! `<speak>`, `<p>` and `<s>` are structural and accepted everywhere.
+
+use crate::{Result, VoiceError};
+
+/// Tags every backend accepts (they only group text)
+const STRUCTURAL_TAGS: &[&str] = &["speak", "p", "s"];
+
+/// Tags ElevenLabs understands natively
+pub const ELEVENLABS_TAGS: &[&str] = &["break", "emphasis", "phoneme"];
+
+/// Tags the OpenAI backend translates into text heuristics
+pub const OPENAI_TAGS: &[&str] = &["break", "emphasis", "sub", "say-as"];
+
+/// A single SSML tag
+#[derive(Debug, Clone, Copy)]
+struct Tag<'a> {
+    name: &'a str,
+    closing: bool,
+    self_closing: bool,
+    /// Everything between `<` and `>`
+    raw: &'a str,
+}
+
+impl<'a> Tag<'a> {
+    fn parse(raw: &'a str) -> Self {
+        let closing = raw.starts_with('/');
+        let self_closing = raw.ends_with('/');
+        let body = raw.trim_start_matches('/').trim_end_matches('/');
+        let name = body.split_whitespace().next().unwrap_or("");
+        Self { name, closing, self_closing, raw }
+    }
+
+    /// Value of an attribute, quoted with either `"` or `'`. The whole
+    /// name must match, so `rate` does not find `xrate`.
+    fn attr(&self, name: &str) -> Option<&'a str> {
+        let raw = self.raw;
+        for quote in ['"', '\''] {
+            let needle = format!("{}={}", name, quote);
+            let found = raw
+                .match_indices(&needle)
+                .find(|(start, _)| raw[..*start].ends_with(char::is_whitespace));
+            if let Some((start, _)) = found {
+                let value = &raw[start + needle.len()..];
+                return value.find(quote).map(|end| &value[..end]);
+            }
+        }
+        None
+    }
+}
+
+#[derive(Debug)]
+enum Token<'a> {
+    Text(&'a str),
+    Tag(Tag<'a>),
+}
+
+fn tokenize(ssml: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    let mut rest = ssml;
+
+    while let Some(start) = rest.find('<') {
+        if start > 0 {
+            tokens.push(Token::Text(&rest[..start]));
+        }
+        match rest[start..].find('>') {
+            Some(end) => {
+                let raw = rest[start + 1..start + end].trim();
+                // Skip comments and XML declarations
+                if !raw.starts_with('!') && !raw.starts_with('?') {
+                    tokens.push(Token::Tag(Tag::parse(raw)));
+                }
+                rest = &rest[start + end + 1..];
+            }
+            None => {
+                // Unterminated tag: treat the remainder as text
+                tokens.push(Token::Text(&rest[start..]));
+                rest = "";
+            }
+        }
+    }
+    if !rest.is_empty() {
+        tokens.push(Token::Text(rest));
+    }
+
+    tokens
+}
+
+/// Escape text for inclusion in an SSML document
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Remove all tags, keeping only the spoken text
+pub fn strip_tags(ssml: &str) -> String {
+    let text: String = tokenize(ssml)
+        .into_iter()
+        .map(|token| match token {
+            Token::Text(text) => text,
+            // Keep words on either side of a tag apart
+            Token::Tag(_) => " ",
+        })
+        .collect();
+    collapse_whitespace(&unescape(&text))
+}
+
+/// Fail if the document uses a tag the backend does not honor.
+/// The error lists the tags that are supported.
+pub fn check_supported(ssml: &str, backend: &str, supported: &[&str]) -> Result<()> {
+    for token in tokenize(ssml) {
+        if let Token::Tag(tag) = token {
+            if !STRUCTURAL_TAGS.contains(&tag.name) && !supported.contains(&tag.name) {
+                return Err(VoiceError::ConfigError(format!(
+                    "Unsupported SSML tag <{}> for {}; supported tags: {}",
+                    tag.name,
+                    backend,
+                    supported
+                        .iter()
+                        .chain(STRUCTURAL_TAGS)
+                        .map(|t| format!("<{}>", t))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Keep only the listed tags (dropping `<speak>` and anything unsupported).
+/// If any tag is kept the result is still markup, so its text stays
+/// escaped; otherwise it is plain text.
+pub fn retain_tags(ssml: &str, supported: &[&str]) -> String {
+    let tokens = tokenize(ssml);
+    let markup = tokens
+        .iter()
+        .any(|token| matches!(token, Token::Tag(tag) if supported.contains(&tag.name)));
+
+    let mut out = String::new();
+    for token in tokens {
+        match token {
+            Token::Text(text) if markup => out.push_str(&escape(&unescape(text))),
+            Token::Text(text) => out.push_str(&unescape(text)),
+            Token::Tag(tag) if supported.contains(&tag.name) => {
+                out.push('<');
+                out.push_str(tag.raw);
+                out.push('>');
+            }
+            Token::Tag(_) => out.push(' '),
+        }
+    }
+    collapse_whitespace(&out)
+}
+
+/// Length of a `<break>` in milliseconds, from its `time` or `strength`
+fn break_ms(tag: &Tag) -> u32 {
+    if let Some(time) = tag.attr("time") {
+        let time = time.trim();
+        let parsed = if let Some(ms) = time.strip_suffix("ms") {
+            ms.trim().parse::<f32>().ok()
+        } else if let Some(secs) = time.strip_suffix('s') {
+            secs.trim().parse::<f32>().ok().map(|s| s * 1000.0)
+        } else {
+            None
+        };
+        if let Some(ms) = parsed {
+            return ms.max(0.0) as u32;
+        }
+    }
+    match tag.attr("strength") {
+        Some("none") => 0,
+        Some("x-weak") | Some("weak") => 250,
+        Some("strong") | Some("x-strong") => 1000,
+        _ => 500,
+    }
+}
+
+/// Rewrite SSML as plain text with cues a non-SSML voice responds to:
+/// pauses become commas or ellipses, emphasis becomes capitals, `<sub>`
+/// is replaced by its alias and `say-as characters` is spelled out.
+pub fn to_plain_text(ssml: &str) -> String {
+    let mut out = String::new();
+    let mut emphasis = 0usize;
+    let mut skip_sub = false;
+    let mut spell_out = false;
+
+    for token in tokenize(ssml) {
+        match token {
+            Token::Text(text) => {
+                if skip_sub {
+                    continue;
+                }
+                let text = unescape(text);
+                if spell_out {
+                    let spelled: Vec<String> = text
+                        .chars()
+                        .filter(|c| !c.is_whitespace())
+                        .map(|c| c.to_string())
+                        .collect();
+                    out.push_str(&spelled.join(" "));
+                } else if emphasis > 0 {
+                    out.push_str(&text.to_uppercase());
+                } else {
+                    out.push_str(&text);
+                }
+            }
+            Token::Tag(tag) => match (tag.name, tag.closing) {
+                ("break", _) => {
+                    let ms = break_ms(&tag);
+                    if ms > 0 {
+                        out.truncate(out.trim_end().len());
+                        out.push_str(if ms >= 700 { "... " } else { ", " });
+                    }
+                }
+                ("emphasis", false) if !tag.self_closing => emphasis += 1,
+                ("emphasis", true) => emphasis = emphasis.saturating_sub(1),
+                ("sub", false) => {
+                    if let Some(alias) = tag.attr("alias") {
+                        out.push_str(&unescape(alias));
+                        skip_sub = !tag.self_closing;
+                    }
+                }
+                ("sub", true) => skip_sub = false,
+                ("say-as", false) => {
+                    spell_out = matches!(
+                        tag.attr("interpret-as"),
+                        Some("characters") | Some("spell-out")
+                    ) && !tag.self_closing;
+                }
+                ("say-as", true) => spell_out = false,
+                _ => out.push(' '),
+            },
+        }
+    }
+
+    collapse_whitespace(&out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_tags() {
+        let ssml = r#"<speak>Hello <break time="500ms"/>world &amp; friends</speak>"#;
+        assert_eq!(strip_tags(ssml), "Hello world & friends");
+    }
+
+    #[test]
+    fn test_to_plain_text() {
+        let ssml = r#"<speak>Wait<break time="1s"/>this is <emphasis>really</emphasis> important. Run <say-as interpret-as="characters">npm</say-as> or <sub alias="World Wide Web">WWW</sub>.</speak>"#;
+        assert_eq!(
+            to_plain_text(ssml),
+            "Wait... this is REALLY important. Run n p m or World Wide Web."
+        );
+        assert_eq!(to_plain_text(r#"One<break strength="weak"/> two"#), "One, two");
+    }
+
+    #[test]
+    fn test_retain_tags() {
+        let ssml = r#"<speak><prosody rate="slow">Hi</prosody><break time="1.5s"/><emphasis>there</emphasis></speak>"#;
+        assert_eq!(
+            retain_tags(ssml, ELEVENLABS_TAGS),
+            r#"Hi <break time="1.5s"/><emphasis>there</emphasis>"#
+        );
+
+        // Text next to kept tags stays escaped; without them it is plain
+        let ssml = r#"<speak>Tom &amp; Jerry<break time="1s"/>say &lt;hi&gt;</speak>"#;
+        assert_eq!(
+            retain_tags(ssml, ELEVENLABS_TAGS),
+            r#"Tom &amp; Jerry<break time="1s"/>say &lt;hi&gt;"#
+        );
+        assert_eq!(retain_tags(ssml, &[]), "Tom & Jerry say <hi>");
+    }
+
+    #[test]
+    fn test_attr_matches_whole_name() {
+        let tag = Tag::parse(r#"prosody xrate="fast" rate='slow'"#);
+        assert_eq!(tag.attr("rate"), Some("slow"));
+        assert_eq!(tag.attr("xrate"), Some("fast"));
+        assert_eq!(tag.attr("ate"), None);
+
+        // An unrelated attribute ending in "time" is not a break length
+        assert_eq!(break_ms(&Tag::parse(r#"break xtime="2s"/"#)), 500);
+    }
+
+    #[test]
+    fn test_check_supported() {
+        let ok = r#"<speak><p>Hi <break time="1s"/></p></speak>"#;
+        assert!(check_supported(ok, "ElevenLabs TTS", ELEVENLABS_TAGS).is_ok());
+
+        let err = check_supported(r#"<prosody pitch="high">Hi</prosody>"#, "OpenAI TTS", OPENAI_TAGS)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("<prosody>"));
+        assert!(err.contains("OpenAI TTS"));
+        assert!(err.contains("<break>, <emphasis>, <sub>, <say-as>"));
+    }
+}
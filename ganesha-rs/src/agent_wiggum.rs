@@ -12,11 +12,13 @@ use crate::orchestrator::wiggum::{VerificationResult, VerificationIssue, IssueSe
 use crate::core::config::{ProviderConfig, ModelTier};
 use crate::orchestrator::{ForkedContext, MiniMeTask};
 use crate::orchestrator::minime;
+use crate::sentinel::{self, QuarantineStatus, Sentinel, Verdict};
 use console::style;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 use uuid::Uuid;
 
@@ -34,6 +36,9 @@ pub struct AgentConfig {
     pub verbose: bool,
     pub temperature: f32,
     pub seed: Option<i64>,
+    /// Screens shell commands and file writes before they run. Halted calls
+    /// fail; quarantined ones are parked until released (see `run_released`)
+    pub sentinel: Option<Arc<Sentinel>>,
 }
 
 impl Default for AgentConfig {
@@ -50,6 +55,7 @@ impl Default for AgentConfig {
             sandbox_dir: None,
             verify_actions: true,
             verbose: false,
+            sentinel: None,
         }
     }
 }
@@ -91,6 +97,8 @@ pub struct SessionResult {
     pub files_created: Vec<String>,
     pub files_modified: Vec<String>,
     pub commands_executed: Vec<String>,
+    /// Tokens reported by the provider (0 if it doesn't report usage)
+    pub tokens_used: usize,
 }
 
 /// The Wiggum-enabled Agent
@@ -106,6 +114,8 @@ pub struct WiggumAgent {
     active_minime: HashMap<Uuid, MiniMeTask>,
     /// Completed Mini-Me results
     minime_results: Vec<MiniMeResult>,
+    /// Tool calls parked in the Sentinel's quarantine, by queue ID
+    held_calls: HashMap<String, ToolCall>,
 }
 
 /// Result from a Mini-Me sub-agent
@@ -141,6 +151,7 @@ impl WiggumAgent {
             commands_executed: vec![],
             active_minime: HashMap::new(),
             minime_results: vec![],
+            held_calls: HashMap::new(),
         }
     }
 
@@ -263,6 +274,7 @@ Output tool calls in JSON format. When done, summarize what was accomplished."#,
         let start = Instant::now();
         let mut actions = vec![];
         let mut final_response = String::new();
+        let mut tokens_used = 0;
 
         // Initialize conversation
         self.messages.push(Message {
@@ -287,7 +299,10 @@ Output tool calls in JSON format. When done, summarize what was accomplished."#,
 
             // Call LLM
             let response = match self.call_llm().await {
-                Ok(r) => r,
+                Ok((r, tokens)) => {
+                    tokens_used += tokens;
+                    r
+                }
                 Err(e) => {
                     return Err(format!("LLM error: {}", e).into());
                 }
@@ -369,9 +384,25 @@ Output tool calls in JSON format. When done, summarize what was accomplished."#,
             files_created: self.files_created.clone(),
             files_modified: self.files_modified.clone(),
             commands_executed: self.commands_executed.clone(),
+            tokens_used,
         })
     }
 
+    /// Replay earlier prompt/response pairs into the conversation, e.g. when
+    /// resuming a flux session from its journal
+    pub fn restore_conversation(&mut self, exchanges: &[(String, String)]) {
+        for (prompt, response) in exchanges {
+            self.messages.push(Message {
+                role: "user".into(),
+                content: prompt.clone(),
+            });
+            self.messages.push(Message {
+                role: "assistant".into(),
+                content: response.clone(),
+            });
+        }
+    }
+
     /// Execute a tool call with Wiggum verification and retry
     async fn execute_with_verification(&mut self, tool_call: &ToolCall) -> ActionResult {
         let mut retries = 0;
@@ -420,6 +451,20 @@ Output tool calls in JSON format. When done, summarize what was accomplished."#,
                 };
             }
 
+            if let Some(error) = self.screen_tool_call(tool_call, &args) {
+                if self.config.verbose {
+                    println!("    {} {}", style("⛔").yellow(), error);
+                }
+                return ActionResult {
+                    tool_name: tool_call.name.clone(),
+                    success: false,
+                    output: error,
+                    verified: false,
+                    verification_notes,
+                    retries,
+                };
+            }
+
             // Execute the regular tool
             let result = execute_tool(
                 &tool_call.name,
@@ -491,6 +536,86 @@ Output tool calls in JSON format. When done, summarize what was accomplished."#,
         }
     }
 
+    /// How the Sentinel sees a tool call; `None` for ones it doesn't screen
+    fn sentinel_context(&self, tool_name: &str, args: &Value) -> Option<sentinel::ActionContext> {
+        let (action_type, content) = match tool_name {
+            "bash" => (sentinel::ActionType::ShellCommand, args["command"].as_str()?),
+            "write" | "edit" => (sentinel::ActionType::FileWrite, args["path"].as_str()?),
+            _ => return None,
+        };
+        Some(sentinel::ActionContext {
+            action_type,
+            content: content.to_string(),
+            timestamp: Instant::now(),
+            working_dir: Some(self.cwd.display().to_string()),
+            target_app: None,
+            screen_context: None,
+        })
+    }
+
+    /// Check a tool call with the Sentinel before it runs. Returns the
+    /// reason when it is blocked, waiting in quarantine or was rejected there.
+    fn screen_tool_call(&mut self, tool_call: &ToolCall, args: &Value) -> Option<String> {
+        let sentinel = self.config.sentinel.clone()?;
+        let context = self.sentinel_context(&tool_call.name, args)?;
+
+        match sentinel.claim(&context) {
+            Some(held) => match held.status {
+                QuarantineStatus::Released => {
+                    self.held_calls.remove(&held.id);
+                    None
+                }
+                QuarantineStatus::Pending => {
+                    self.held_calls.insert(held.id.clone(), tool_call.clone());
+                    Some(format!("Held in quarantine as {} pending release: {}", held.id, held.analysis.reason))
+                }
+                QuarantineStatus::Rejected => {
+                    self.held_calls.remove(&held.id);
+                    Some(format!("Rejected from quarantine ({}): {}", held.id, held.analysis.reason))
+                }
+            },
+            None => {
+                let analysis = sentinel.analyze(&context);
+                match analysis.verdict {
+                    Verdict::Halt => Some(format!("Blocked by Sentinel: {}", analysis.reason)),
+                    Verdict::Quarantine => {
+                        let reason = analysis.reason.clone();
+                        let id = sentinel.quarantine(&context, analysis);
+                        self.held_calls.insert(id.clone(), tool_call.clone());
+                        Some(format!("Held in quarantine as {} pending release: {}", id, reason))
+                    }
+                    Verdict::Allow | Verdict::Warn | Verdict::Analyze => None,
+                }
+            }
+        }
+    }
+
+    /// Run the parked tool calls that have been released since they were
+    /// held. Pending ones stay parked; rejected ones come back as failed
+    /// results and are dropped.
+    pub async fn run_released(&mut self) -> Vec<ActionResult> {
+        let Some(sentinel) = self.config.sentinel.clone() else {
+            return vec![];
+        };
+        let pending: Vec<String> = sentinel.pending().into_iter().map(|q| q.id).collect();
+
+        let mut ids: Vec<String> = self.held_calls.keys().cloned().collect();
+        ids.sort_by_key(|id| id.trim_start_matches("q-").parse::<u64>().unwrap_or(u64::MAX));
+        let mut results = vec![];
+        for id in ids {
+            if pending.contains(&id) {
+                continue;
+            }
+            let Some(tool_call) = self.held_calls.get(&id).cloned() else {
+                continue;
+            };
+            let result = self.execute_with_verification(&tool_call).await;
+            self.held_calls.remove(&id);
+            results.push(result);
+        }
+        results
+    }
+
     /// Verify an action's result
     async fn verify_action(&self, tool_name: &str, args: &Value, output: &str, success: bool) -> VerificationResult {
         // Quick heuristic verification
@@ -668,7 +793,9 @@ Output tool calls in JSON format. When done, summarize what was accomplished."#,
     }
 
     /// Call LLM API
-    async fn call_llm(&self) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+    /// Send the conversation, returning the reply and the total tokens the
+    /// provider reports for the call
+    async fn call_llm(&self) -> Result<(String, usize), Box<dyn std::error::Error + Send + Sync>> {
         let client = reqwest::Client::builder()
             .timeout(Duration::from_secs(180))
             .build()?;
@@ -707,10 +834,12 @@ Output tool calls in JSON format. When done, summarize what was accomplished."#,
         }
 
         let json: Value = response.json().await?;
-        Ok(json["choices"][0]["message"]["content"]
+        let content = json["choices"][0]["message"]["content"]
             .as_str()
             .unwrap_or("")
-            .to_string())
+            .to_string();
+        let tokens = json["usage"]["total_tokens"].as_u64().unwrap_or(0) as usize;
+        Ok((content, tokens))
     }
 
     /// Extract tool calls from response
@@ -3,6 +3,7 @@
 //! ASCII art, colors, and interactive prompts.
 
 use crate::core::{Action, ConsentHandler, ConsentResult, ExecutionPlan, RiskLevel};
+use crate::core::access_control::ManipulationHit;
 use console::{style, Style, Term};
 use dialoguer::{theme::ColorfulTheme, Confirm, Select};
 
@@ -440,6 +441,19 @@ impl ConsentHandler for CliConsent {
             _ => ConsentResult::Cancel,
         }
     }
+
+    fn confirm_manipulation(&self, task: &str, hit: &ManipulationHit) -> bool {
+        println!();
+        println!("{} Task looks like a manipulation attempt", style("[WARNING]").yellow().bold());
+        println!("  Task: {}", style(task).bold());
+        println!("  Matched {}", hit);
+
+        Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt("Plan it anyway?")
+            .default(false)
+            .interact()
+            .unwrap_or(false)
+    }
 }
 
 /// Auto-approve consent handler (for --auto flag)
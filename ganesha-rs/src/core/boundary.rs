@@ -0,0 +1,289 @@
+//! Project root boundary
+//!
+//! With a boundary set, a task may only `cd` within the project root and
+//! may only modify files under it. Commands are scanned for the paths they
+//! change into or write to: `cd` targets, redirect targets, and the operands
+//! of `rm`, `mv`, `cp`, `touch`, `mkdir`, `tee`, `dd of=` and similar. Each
+//! path is resolved against the working directory, with `..` and symlinks
+//! followed, before checking it lies under the (also resolved) root. Like
+//! the shell's `cd`, a `cd` target drops `..` with the component written
+//! before it, before any symlinks are followed.
+//!
+//! This is a filter for model mistakes, not a sandbox: paths built from
+//! shell variables other than `$HOME`, or hidden inside scripts, are not
+//! seen.
+
+use super::shell_split::{is_assignment, segments};
+use std::path::{Component, Path, PathBuf};
+
+/// How a command leaves the boundary
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EscapeKind {
+    /// Changes directory outside the root
+    Cd,
+    /// Writes, moves or deletes outside the root
+    Write,
+}
+
+/// The first path in a command that resolves outside the root
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BoundaryEscape {
+    pub kind: EscapeKind,
+    /// The path as written in the command
+    pub raw: String,
+    /// Where it resolves to
+    pub resolved: PathBuf,
+}
+
+impl std::fmt::Display for BoundaryEscape {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let action = match self.kind {
+            EscapeKind::Cd => "changes directory to",
+            EscapeKind::Write => "writes to",
+        };
+        write!(f, "{} `{}` ({})", action, self.raw, self.resolved.display())
+    }
+}
+
+/// Devices commands may always write to
+const ALLOWED_DEVICES: &[&str] = &["/dev/null", "/dev/stdout", "/dev/stderr", "/dev/tty"];
+
+/// Find the first `cd` or write in `command` that leaves `root`, resolving
+/// relative paths from `cwd`
+pub fn find_escape(command: &str, cwd: &Path, root: &Path) -> Option<BoundaryEscape> {
+    let root = resolve_path(root, Path::new("/"));
+    // Later segments run in the directory an earlier `cd` moved to
+    let mut cwd = resolve_path(cwd, Path::new("/"));
+
+    for segment in segments(command) {
+        let words: Vec<String> = segment.words.into_iter().map(|w| w.text).collect();
+        for (kind, raw) in touched_paths(&words) {
+            let Some(path) = expand_home(&raw) else {
+                continue;
+            };
+            let path = match kind {
+                EscapeKind::Cd => apply_dot_dot(&path, &cwd),
+                EscapeKind::Write => path,
+            };
+            let resolved = resolve_path(&path, &cwd);
+            if kind == EscapeKind::Write && ALLOWED_DEVICES.iter().any(|d| resolved == Path::new(d)) {
+                continue;
+            }
+            if !resolved.starts_with(&root) {
+                return Some(BoundaryEscape { kind, raw, resolved });
+            }
+            // Keep the path as `cd` saw it, so a later `..` is applied to it
+            if kind == EscapeKind::Cd {
+                cwd = path;
+            }
+        }
+    }
+    None
+}
+
+/// Make `path` absolute against `cwd` and resolve `.`, `..` and symlinks.
+/// Components that don't exist yet are kept as written.
+pub fn resolve_path(path: &Path, cwd: &Path) -> PathBuf {
+    let absolute = if path.is_absolute() { path.to_path_buf() } else { cwd.join(path) };
+    let mut resolved = PathBuf::new();
+    for component in absolute.components() {
+        match component {
+            Component::Prefix(_) | Component::RootDir => resolved.push(component),
+            Component::CurDir => {}
+            // `resolved` is already free of symlinks, so popping gives the
+            // real parent
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            Component::Normal(name) => {
+                resolved.push(name);
+                if let Ok(real) = resolved.canonicalize() {
+                    resolved = real;
+                }
+            }
+        }
+    }
+    resolved
+}
+
+/// Make `path` absolute against `cwd` and apply `.` and `..` as written,
+/// without following symlinks, the way `cd` does: `cd link/..` stays where
+/// `link` is
+fn apply_dot_dot(path: &Path, cwd: &Path) -> PathBuf {
+    let absolute = if path.is_absolute() { path.to_path_buf() } else { cwd.join(path) };
+    let mut logical = PathBuf::new();
+    for component in absolute.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                logical.pop();
+            }
+            other => logical.push(other),
+        }
+    }
+    logical
+}
+
+/// Expand a leading `~` or `$HOME`. Returns `None` for paths that depend on
+/// other variables or substitutions, which can't be resolved here.
+fn expand_home(raw: &str) -> Option<PathBuf> {
+    let rest = ["~", "$HOME", "${HOME}"].iter().find_map(|prefix| {
+        raw.strip_prefix(prefix)
+            .filter(|rest| rest.is_empty() || rest.starts_with('/'))
+    });
+    let path = match rest {
+        Some(rest) => dirs::home_dir()?.join(rest.trim_start_matches('/')),
+        None => PathBuf::from(raw),
+    };
+    let text = path.to_string_lossy();
+    (!text.contains('$') && !text.contains('`')).then_some(path)
+}
+
+/// Commands whose operands are all modified
+const WRITES_ALL: &[&str] = &["rm", "rmdir", "mv", "touch", "mkdir", "tee", "truncate", "shred", "unlink"];
+/// Commands that modify only their last operand
+const WRITES_LAST: &[&str] = &["cp", "ln", "install", "rsync"];
+/// Commands whose first operand is a mode or owner, not a path
+const WRITES_AFTER_FIRST: &[&str] = &["chmod", "chown", "chgrp"];
+
+/// Paths a single command segment changes into or writes to
+fn touched_paths(tokens: &[String]) -> Vec<(EscapeKind, String)> {
+    let mut paths = Vec::new();
+    let mut words = Vec::new();
+
+    // Pull out redirects first; what's left is the command and its arguments
+    let mut iter = tokens.iter();
+    while let Some(token) = iter.next() {
+        match redirect_target(token) {
+            Some("") => {
+                if let Some(target) = iter.next() {
+                    paths.push((EscapeKind::Write, target.clone()));
+                }
+            }
+            Some(target) if !target.starts_with('&') => paths.push((EscapeKind::Write, target.to_string())),
+            Some(_) => {}
+            None => words.push(token.as_str()),
+        }
+    }
+
+    // Skip `sudo` and leading `VAR=value` assignments
+    let start = words
+        .iter()
+        .position(|w| *w != "sudo" && !is_assignment(w))
+        .unwrap_or(words.len());
+    let Some((program, args)) = words[start..].split_first() else {
+        return paths;
+    };
+    let program = program.rsplit('/').next().unwrap_or(program);
+    let operands: Vec<&str> = args.iter().copied().filter(|a| !a.starts_with('-')).collect();
+
+    let written: &[&str] = match program {
+        "cd" => {
+            if let Some(target) = operands.first().filter(|t| **t != "-") {
+                paths.push((EscapeKind::Cd, target.to_string()));
+            } else if operands.is_empty() {
+                paths.push((EscapeKind::Cd, "~".to_string()));
+            }
+            &[]
+        }
+        "dd" => {
+            for arg in args {
+                if let Some(target) = arg.strip_prefix("of=") {
+                    paths.push((EscapeKind::Write, target.to_string()));
+                }
+            }
+            &[]
+        }
+        p if WRITES_ALL.contains(&p) => &operands,
+        p if WRITES_LAST.contains(&p) => operands.last().map(std::slice::from_ref).unwrap_or(&[]),
+        p if WRITES_AFTER_FIRST.contains(&p) => operands.get(1..).unwrap_or(&[]),
+        _ => &[],
+    };
+    paths.extend(written.iter().map(|p| (EscapeKind::Write, p.to_string())));
+    paths
+}
+
+/// The target of a redirect token: `Some("")` for a bare operator whose
+/// target is the next token, `None` if the token isn't a redirect
+fn redirect_target(token: &str) -> Option<&str> {
+    ["&>>", "&>", "2>>", "1>>", ">>", "2>", "1>", ">|", ">"]
+        .iter()
+        .find_map(|op| token.strip_prefix(op))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn project() -> (tempfile::TempDir, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("project");
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        (dir, root)
+    }
+
+    fn escape(command: &str, root: &Path) -> Option<EscapeKind> {
+        find_escape(command, root, root).map(|e| e.kind)
+    }
+
+    #[test]
+    fn test_cd_outside_root_is_caught() {
+        let (_dir, root) = project();
+        assert_eq!(escape("cd /etc", &root), Some(EscapeKind::Cd));
+        assert_eq!(escape("cd src/../..", &root), Some(EscapeKind::Cd));
+        assert_eq!(escape("cd", &root), Some(EscapeKind::Cd));
+        assert_eq!(escape("cd src && cd ..", &root), None);
+        assert_eq!(escape("cd src && cd ../..", &root), Some(EscapeKind::Cd));
+    }
+
+    #[test]
+    fn test_writes_outside_root_are_caught() {
+        let (_dir, root) = project();
+        assert_eq!(escape("echo hi > /tmp/out.txt", &root), Some(EscapeKind::Write));
+        assert_eq!(escape("echo hi >>../notes", &root), Some(EscapeKind::Write));
+        assert_eq!(escape("rm -rf build ../other", &root), Some(EscapeKind::Write));
+        assert_eq!(escape("cp src/main.rs /usr/local/bin/", &root), Some(EscapeKind::Write));
+        assert_eq!(escape("sudo dd if=img of=/dev/sda", &root), Some(EscapeKind::Write));
+        assert_eq!(escape("ls && touch \"../a b\"", &root), Some(EscapeKind::Write));
+
+        // Reads and writes inside are fine
+        assert_eq!(escape("cat /etc/hosts > hosts.txt", &root), None);
+        assert_eq!(escape("cp /etc/hosts src/", &root), None);
+        assert_eq!(escape("chmod 755 src", &root), None);
+        assert_eq!(escape("make 2>&1 | tee build.log", &root), None);
+        assert_eq!(escape("grep -r foo . > /dev/null", &root), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinks_are_resolved_before_checking() {
+        let (dir, root) = project();
+        let outside = dir.path().join("outside");
+        std::fs::create_dir(&outside).unwrap();
+        std::os::unix::fs::symlink(&outside, root.join("link")).unwrap();
+
+        assert_eq!(escape("touch link/file", &root), Some(EscapeKind::Write));
+        assert_eq!(escape("cd link", &root), Some(EscapeKind::Cd));
+        // For a write, `link/..` is the parent of the link's target
+        assert_eq!(
+            resolve_path(Path::new("link/../x"), &root),
+            dir.path().canonicalize().unwrap().join("x")
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_cd_applies_dot_dot_before_symlinks() {
+        let (_dir, root) = project();
+        std::fs::create_dir_all(root.join("x/y")).unwrap();
+        std::os::unix::fs::symlink(root.join("x/y"), root.join("a")).unwrap();
+
+        // The shell's `cd a/../..` goes to the project's parent, even though
+        // the link's target is two levels down
+        assert_eq!(escape("cd a/../..", &root), Some(EscapeKind::Cd));
+        assert_eq!(escape("cd a/..", &root), None);
+        // After `cd a` the shell is in `a`, not in the link's target
+        assert_eq!(escape("cd a && cd ../..", &root), Some(EscapeKind::Cd));
+        assert_eq!(escape("cd a && touch ../../f", &root), None);
+    }
+}
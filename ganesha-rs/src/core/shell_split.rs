@@ -0,0 +1,504 @@
+//! Shell command-line lexing and `&&` / `;` chain splitting
+//!
+//! [`tokenize`] is the one quote-aware lexer for command lines. The boundary
+//! check reads the words of each [`segment`](segments), the interactive guard
+//! looks for programs in [`command position`](command_words), and
+//! [`split_chain`] cuts a line into steps, all from the same tokens.
+//!
+//! Models like to pack a whole task into one action (`cd app && npm ci &&
+//! npm test`). Run as one shell command, a failure anywhere shows up as a
+//! single failed result. Split into steps, each gets its own result, so the
+//! analysis can see which step broke.
+//!
+//! Only chains whose meaning survives running each step in its own shell are
+//! split: top-level `&&`, `;` and newlines, outside quotes, subshells and
+//! command substitutions. Pipes stay inside their step. Anything else (`||`,
+//! background `&`, here-docs, comments, compound commands, or steps that
+//! change shell state like `export`) leaves the command whole.
+
+use std::ops::Range;
+
+/// An operator that ends one command of a line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Separator {
+    /// `&&`
+    And,
+    /// `||`
+    Or,
+    /// `|`
+    Pipe,
+    /// `&`: runs the command before it in the background
+    Background,
+    /// `;` or a newline
+    Then,
+}
+
+/// A word of a command line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Word {
+    /// The text with quotes and backslash escapes removed. Backticks are
+    /// kept, as their text is replaced when the command runs.
+    pub text: String,
+    /// Whether any of it was quoted, so it is not a plain word
+    pub quoted: bool,
+    /// Byte range in the command line
+    pub span: Range<usize>,
+}
+
+/// A lexed piece of a command line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Token {
+    Word(Word),
+    Separator(Separator, Range<usize>),
+    /// `(` of a subshell or `$(` of a command substitution
+    Open(Range<usize>),
+    /// The `)` closing one
+    Close(Range<usize>),
+    /// A comment, here-doc operator or unclosed quote, which isn't lexed
+    /// any further
+    Unsupported(Range<usize>),
+}
+
+impl Token {
+    /// Byte range in the command line
+    pub fn span(&self) -> Range<usize> {
+        match self {
+            Token::Word(word) => word.span.clone(),
+            Token::Separator(_, span) | Token::Open(span) | Token::Close(span) | Token::Unsupported(span) => {
+                span.clone()
+            }
+        }
+    }
+}
+
+/// The word being read: start offset, text, and whether any of it was quoted
+type PartialWord = Option<(usize, String, bool)>;
+
+fn end_word(word: &mut PartialWord, end: usize, tokens: &mut Vec<Token>) {
+    if let Some((start, text, quoted)) = word.take() {
+        tokens.push(Token::Word(Word { text, quoted, span: start..end }));
+    }
+}
+
+/// Split a command line into words and operators. Redirects (`>file`,
+/// `2>&1`) stay part of their word.
+pub fn tokenize(command: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut word: PartialWord = None;
+    // Last unquoted character of the current word, for `>&`, `>|` and `$(`
+    let mut last: Option<char> = None;
+    let mut chars = command.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        let next = chars.peek().map(|&(_, n)| n);
+        match c {
+            '\'' | '"' | '`' => {
+                let (start, text, quoted) = word.get_or_insert_with(|| (i, String::new(), false));
+                *quoted = true;
+                if c == '`' {
+                    text.push(c);
+                }
+                let mut closed = false;
+                while let Some((_, q)) = chars.next() {
+                    if q == c {
+                        closed = true;
+                        break;
+                    }
+                    if q == '\\' && c != '\'' {
+                        if c == '`' {
+                            text.push(q);
+                        }
+                        text.extend(chars.next().map(|(_, e)| e));
+                    } else {
+                        text.push(q);
+                    }
+                }
+                if !closed {
+                    tokens.push(Token::Unsupported(*start..command.len()));
+                    return tokens;
+                }
+                if c == '`' {
+                    text.push(c);
+                }
+                last = None;
+                continue;
+            }
+            '\\' => {
+                chars.next();
+                // A backslash-newline just continues the line
+                if next != Some('\n') {
+                    let (_, text, _) = word.get_or_insert_with(|| (i, String::new(), false));
+                    text.extend(next);
+                }
+                last = None;
+                continue;
+            }
+            '#' if word.is_none() => {
+                let end = command[i..].find('\n').map_or(command.len(), |n| i + n);
+                while chars.next_if(|&(j, _)| j < end).is_some() {}
+                tokens.push(Token::Unsupported(i..end));
+                continue;
+            }
+            '<' if next == Some('<') => {
+                end_word(&mut word, i, &mut tokens);
+                chars.next();
+                let end = if chars.next_if(|&(_, n)| n == '<').is_some() { i + 3 } else { i + 2 };
+                tokens.push(Token::Unsupported(i..end));
+            }
+            // `>&2`, `&>file`, `<&0` and `>|file` are redirects
+            '&' if matches!(last, Some('>' | '<')) || next == Some('>') => {
+                word.get_or_insert_with(|| (i, String::new(), false)).1.push(c);
+                last = Some(c);
+                continue;
+            }
+            '|' if last == Some('>') => {
+                word.get_or_insert_with(|| (i, String::new(), false)).1.push(c);
+                last = Some(c);
+                continue;
+            }
+            '&' | '|' => {
+                end_word(&mut word, i, &mut tokens);
+                let separator = match (c, next == Some(c)) {
+                    ('&', true) => Separator::And,
+                    ('&', false) => Separator::Background,
+                    (_, true) => Separator::Or,
+                    (_, false) => Separator::Pipe,
+                };
+                let len = if next == Some(c) {
+                    chars.next();
+                    2
+                } else {
+                    1
+                };
+                tokens.push(Token::Separator(separator, i..i + len));
+            }
+            ';' | '\n' => {
+                end_word(&mut word, i, &mut tokens);
+                tokens.push(Token::Separator(Separator::Then, i..i + 1));
+            }
+            '(' if last == Some('$') => {
+                // Take the `$` back off the word it started
+                if let Some((_, text, quoted)) = &mut word {
+                    text.pop();
+                    if text.is_empty() && !*quoted {
+                        word = None;
+                    }
+                }
+                end_word(&mut word, i - 1, &mut tokens);
+                tokens.push(Token::Open(i - 1..i + 1));
+            }
+            '(' => {
+                end_word(&mut word, i, &mut tokens);
+                tokens.push(Token::Open(i..i + 1));
+            }
+            ')' => {
+                end_word(&mut word, i, &mut tokens);
+                tokens.push(Token::Close(i..i + 1));
+            }
+            c if c.is_whitespace() => end_word(&mut word, i, &mut tokens),
+            c => {
+                word.get_or_insert_with(|| (i, String::new(), false)).1.push(c);
+                last = Some(c);
+                continue;
+            }
+        }
+        last = None;
+    }
+    end_word(&mut word, command.len(), &mut tokens);
+    tokens
+}
+
+/// One simple command of a line: the words between separators and
+/// parentheses
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    /// From its first word up to whatever ends it
+    pub span: Range<usize>,
+    pub words: Vec<Word>,
+}
+
+/// Words that run the command after them: `sudo apt install` still runs
+/// `apt` in command position
+const COMMAND_PREFIXES: &[&str] = &["sudo", "doas", "env", "nohup", "time", "exec", "command", "builtin"];
+
+impl Segment {
+    /// Words in command position: the first, and any after a prefix like
+    /// `sudo`, `env` or a `NAME=value` assignment. A quoted word is never
+    /// a command.
+    pub fn commands(&self) -> Vec<&Word> {
+        let mut commands = Vec::new();
+        let mut after_prefix = false;
+        for word in &self.words {
+            // Options of a prefix, like `sudo -E`
+            if after_prefix && !word.quoted && word.text.starts_with('-') {
+                continue;
+            }
+            if word.quoted {
+                break;
+            }
+            commands.push(word);
+            if !COMMAND_PREFIXES.contains(&word.text.as_str()) && !is_assignment(&word.text) {
+                break;
+            }
+            after_prefix = true;
+        }
+        commands
+    }
+}
+
+/// Split a command line into its simple commands, at separators and at the
+/// parentheses of subshells and command substitutions
+pub fn segments(command: &str) -> Vec<Segment> {
+    let mut segments = Vec::new();
+    let mut words: Vec<Word> = Vec::new();
+
+    let mut finish = |words: &mut Vec<Word>, end: usize| {
+        if let Some(first) = words.first() {
+            segments.push(Segment { span: first.span.start..end, words: std::mem::take(words) });
+        }
+    };
+    for token in tokenize(command) {
+        match token {
+            Token::Word(word) => words.push(word),
+            Token::Unsupported(_) => {}
+            other => finish(&mut words, other.span().start),
+        }
+    }
+    finish(&mut words, command.len());
+    segments
+}
+
+/// Where each command starts in `command`, as `(start, segment_end)` byte
+/// offsets: the word in [command position](Segment::commands) and the end
+/// of the segment it runs in
+pub fn command_words(command: &str) -> Vec<(usize, usize)> {
+    segments(command)
+        .iter()
+        .flat_map(|segment| segment.commands().into_iter().map(|word| (word.span.start, segment.span.end)))
+        .collect()
+}
+
+/// Whether `word` is a `NAME=value` assignment
+pub fn is_assignment(word: &str) -> bool {
+    word.split_once('=')
+        .is_some_and(|(name, _)| !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'))
+}
+
+/// How a step joins the one before it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainOp {
+    /// `&&`: runs only if the previous step succeeded
+    And,
+    /// `;` or a newline: runs regardless
+    Then,
+}
+
+/// One command of a chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChainStep {
+    pub command: String,
+    /// Operator before this step; `None` for the first
+    pub after: Option<ChainOp>,
+}
+
+/// Builtins whose effect would be lost if their step ran in its own shell.
+/// `cd` is not here: the engine carries the working directory between steps.
+const STATEFUL_BUILTINS: &[&str] = &[
+    "export", "unset", "set", "source", ".", "alias", "unalias", "pushd", "popd", "shopt", "umask", "eval",
+    "exec", "trap", "declare", "local", "readonly",
+];
+
+/// Keywords of compound commands and brace groups, which have their own
+/// `;` and newlines
+const COMPOUND_KEYWORDS: &[&str] = &[
+    "{", "}", "if", "then", "elif", "else", "fi", "for", "while", "until", "do", "done", "case", "esac", "select",
+    "function",
+];
+
+/// Split `command` into its chained steps. Returns `None` unless it is a
+/// chain of at least two steps that can safely run one at a time.
+pub fn split_chain(command: &str) -> Option<Vec<ChainStep>> {
+    // Each step's span and its words outside parentheses
+    let mut steps: Vec<(Range<usize>, Option<ChainOp>, Vec<String>)> = Vec::new();
+    let mut current: Option<(Range<usize>, Vec<String>)> = None;
+    let mut after = None;
+    let mut depth = 0usize;
+
+    let mut finish = |current: &mut Option<(Range<usize>, Vec<String>)>, after: &mut Option<ChainOp>, next: ChainOp| {
+        if let Some((span, words)) = current.take() {
+            steps.push((span, after.take(), words));
+        } else if next == ChainOp::And || *after == Some(ChainOp::And) {
+            // `&& &&` or a chain starting with `&&`: not valid shell
+            return false;
+        }
+        if !steps.is_empty() {
+            *after = Some(match (*after, next) {
+                (Some(ChainOp::And), _) | (_, ChainOp::And) => ChainOp::And,
+                _ => ChainOp::Then,
+            });
+        }
+        true
+    };
+
+    for token in tokenize(command) {
+        let span = token.span();
+        match token {
+            Token::Unsupported(_) => return None,
+            Token::Open(_) => depth += 1,
+            Token::Close(_) => depth = depth.checked_sub(1)?,
+            Token::Separator(separator @ (Separator::And | Separator::Then), _) if depth == 0 => {
+                let next = if separator == Separator::And { ChainOp::And } else { ChainOp::Then };
+                if !finish(&mut current, &mut after, next) {
+                    return None;
+                }
+                continue;
+            }
+            Token::Separator(Separator::Or | Separator::Background, _) if depth == 0 => return None,
+            _ => {}
+        }
+        let (step, words) = current.get_or_insert_with(|| (span.clone(), Vec::new()));
+        step.end = span.end;
+        if let Token::Word(word) = token {
+            if depth == 0 {
+                words.push(word.text);
+            }
+        }
+    }
+
+    if depth != 0 {
+        return None;
+    }
+    if let Some((span, words)) = current {
+        steps.push((span, after, words));
+    } else if after == Some(ChainOp::And) {
+        return None;
+    }
+
+    if steps.len() < 2 || !steps.iter().all(|(_, _, words)| runs_alone(words)) {
+        return None;
+    }
+    Some(
+        steps
+            .into_iter()
+            .map(|(span, after, _)| ChainStep { command: command[span].to_string(), after })
+            .collect(),
+    )
+}
+
+/// Whether a step, given its words outside parentheses, means the same run
+/// in a shell of its own
+fn runs_alone(words: &[String]) -> bool {
+    let first = words.first().map(String::as_str).unwrap_or_default();
+    if STATEFUL_BUILTINS.contains(&first) || COMPOUND_KEYWORDS.contains(&first) {
+        return false;
+    }
+    // A bare `NAME=value` sets a shell variable for later steps
+    if !words.is_empty() && words.iter().all(|w| is_assignment(w)) {
+        return false;
+    }
+    // The engine follows `cd <dir>`, but not `cd`, `cd -` or `cd a b`
+    if first == "cd" {
+        return words.len() == 2 && words[1] != "-";
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn commands(command: &str) -> Option<Vec<String>> {
+        split_chain(command).map(|steps| steps.into_iter().map(|s| s.command).collect())
+    }
+
+    #[test]
+    fn test_splits_top_level_chains() {
+        let steps = split_chain("mkdir -p build && cd build; cmake .. && make -j4").unwrap();
+        let ops: Vec<Option<ChainOp>> = steps.iter().map(|s| s.after).collect();
+        assert_eq!(
+            steps.iter().map(|s| s.command.as_str()).collect::<Vec<_>>(),
+            ["mkdir -p build", "cd build", "cmake ..", "make -j4"]
+        );
+        assert_eq!(ops, [None, Some(ChainOp::And), Some(ChainOp::Then), Some(ChainOp::And)]);
+
+        assert_eq!(
+            commands("echo 'a && b' && ls | grep \"x;y\" && (cd /tmp && ls) && echo $(date; id)"),
+            Some(vec![
+                "echo 'a && b'".into(),
+                "ls | grep \"x;y\"".into(),
+                "(cd /tmp && ls)".into(),
+                "echo $(date; id)".into(),
+            ])
+        );
+        assert_eq!(
+            commands("make 2>&1 && find . -name '*.o' -exec rm {} \\;"),
+            Some(vec!["make 2>&1".into(), "find . -name '*.o' -exec rm {} \\;".into()])
+        );
+    }
+
+    #[test]
+    fn test_segments_unquote_words_and_split_at_operators() {
+        let words = |command: &str| -> Vec<Vec<String>> {
+            segments(command)
+                .into_iter()
+                .map(|s| s.words.into_iter().map(|w| w.text).collect())
+                .collect()
+        };
+        assert_eq!(
+            words("cd \"my dir\" && ls 2>&1 >out.txt | tee -a 'a b'; echo $(rm x)"),
+            [
+                vec!["cd", "my dir"],
+                vec!["ls", "2>&1", ">out.txt"],
+                vec!["tee", "-a", "a b"],
+                vec!["echo"],
+                vec!["rm", "x"],
+            ]
+        );
+        assert_eq!(words("echo '; rm x' # rm y\nls &>log &"), [vec!["echo", "; rm x"], vec!["ls", "&>log"]]);
+        assert!(matches!(tokenize("echo 'open").last(), Some(Token::Unsupported(span)) if *span == (5..10)));
+    }
+
+    #[test]
+    fn test_command_words_skip_arguments_and_quotes() {
+        let starts = |command: &str| -> Vec<String> {
+            command_words(command)
+                .into_iter()
+                .map(|(start, end)| command[start..end].to_string())
+                .collect()
+        };
+        assert_eq!(starts("apt install foo"), ["apt install foo"]);
+        assert_eq!(
+            starts("sudo -E apt-get install x && FOO=1 npm init; ls 2>&1 | grep 'a;b'"),
+            [
+                "sudo -E apt-get install x ",
+                "apt-get install x ",
+                "FOO=1 npm init",
+                "npm init",
+                "ls 2>&1 ",
+                "grep 'a;b'"
+            ]
+        );
+        assert_eq!(starts("echo apt install foo"), ["echo apt install foo"]);
+        assert_eq!(starts("echo \"x; vim\" `vim`"), ["echo \"x; vim\" `vim`"]);
+    }
+
+    #[test]
+    fn test_leaves_unsafe_commands_whole() {
+        for command in [
+            "ls -la",
+            "ls | wc -l",
+            "make || echo failed",
+            "sleep 5 & echo started",
+            "cat > f << 'EOF'\na && b\nEOF",
+            "export PATH=/opt/bin:$PATH && tool",
+            "X=1; echo $X",
+            "cd && ls",
+            "for f in *; do echo $f; done",
+            "echo 'unterminated && ls",
+            "ls && && pwd",
+            "ls && # comment",
+        ] {
+            assert_eq!(split_chain(command), None, "{}", command);
+        }
+    }
+}
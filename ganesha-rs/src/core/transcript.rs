@@ -0,0 +1,186 @@
+//! Replayable session transcripts
+//!
+//! `/log` keeps a readable text log. A transcript is the structured
+//! counterpart: each user input with the sessions it produced (the plan, its
+//! results and their timestamps), as saved in the session directory. Replaying
+//! a transcript sends the same inputs again and compares the new plans with the
+//! recorded ones, which shows whether a prompt or model change altered
+//! behaviour.
+
+use super::{ExecutionPlan, GaneshaError, Session};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Format version written to exported transcripts
+pub const TRANSCRIPT_VERSION: u32 = 1;
+
+/// One user input and the plan/execute rounds it took
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TranscriptTurn {
+    pub input: String,
+    pub started_at: DateTime<Utc>,
+    pub sessions: Vec<Session>,
+}
+
+impl TranscriptTurn {
+    pub fn new(input: impl Into<String>) -> Self {
+        Self {
+            input: input.into(),
+            started_at: Utc::now(),
+            sessions: Vec::new(),
+        }
+    }
+
+    /// Commands of the first plan made for this input
+    pub fn first_plan_commands(&self) -> Vec<String> {
+        self.sessions
+            .iter()
+            .find_map(|s| s.plan.as_ref())
+            .map(plan_commands)
+            .unwrap_or_default()
+    }
+}
+
+/// Everything a run did, in order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Transcript {
+    pub version: u32,
+    pub created_at: DateTime<Utc>,
+    /// Provider that served the run, when known
+    #[serde(default)]
+    pub provider: Option<String>,
+    pub turns: Vec<TranscriptTurn>,
+}
+
+impl Default for Transcript {
+    fn default() -> Self {
+        Self {
+            version: TRANSCRIPT_VERSION,
+            created_at: Utc::now(),
+            provider: None,
+            turns: Vec::new(),
+        }
+    }
+}
+
+impl Transcript {
+    /// Start a turn for a new user input
+    pub fn begin_turn(&mut self, input: &str) {
+        self.turns.push(TranscriptTurn::new(input));
+    }
+
+    /// Add a finished session to the current turn. Sessions run outside a
+    /// turn get one of their own.
+    pub fn record(&mut self, session: Session) {
+        if self.turns.is_empty() {
+            self.begin_turn(&session.task);
+        }
+        if let Some(turn) = self.turns.last_mut() {
+            turn.sessions.push(session);
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, GaneshaError> {
+        let json = std::fs::read_to_string(path)?;
+        let transcript: Self = serde_json::from_str(&json)
+            .map_err(|e| GaneshaError::IoError(std::io::Error::new(std::io::ErrorKind::InvalidData, e)))?;
+        if transcript.version > TRANSCRIPT_VERSION {
+            return Err(GaneshaError::IoError(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "transcript version {} is newer than supported ({})",
+                    transcript.version, TRANSCRIPT_VERSION
+                ),
+            )));
+        }
+        Ok(transcript)
+    }
+}
+
+/// How a replayed input compared with the recording
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayOutcome {
+    pub input: String,
+    pub expected: Vec<String>,
+    /// Commands of the new plan, or the planning error
+    pub actual: Result<Vec<String>, String>,
+}
+
+impl ReplayOutcome {
+    /// Whether the new plan runs the same commands
+    pub fn matches(&self) -> bool {
+        self.actual.as_ref().is_ok_and(|actual| normalized(actual) == normalized(&self.expected))
+    }
+}
+
+/// The commands a plan runs. Conversational replies are actions with an
+/// empty command, so they are left out.
+pub fn plan_commands(plan: &ExecutionPlan) -> Vec<String> {
+    plan.actions
+        .iter()
+        .filter(|a| !a.command.trim().is_empty())
+        .map(|a| a.command.clone())
+        .collect()
+}
+
+/// Commands with whitespace runs collapsed, so formatting noise isn't a change
+fn normalized(commands: &[String]) -> Vec<String> {
+    commands
+        .iter()
+        .map(|c| c.split_whitespace().collect::<Vec<_>>().join(" "))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Action, ActionType, ExecutionPlan, RiskLevel};
+
+    fn session(task: &str, commands: &[&str]) -> Session {
+        let mut session = Session::new(task);
+        session.plan = Some(ExecutionPlan {
+            actions: commands
+                .iter()
+                .map(|c| Action {
+                    id: "1".into(),
+                    action_type: ActionType::Shell,
+                    command: c.to_string(),
+                    explanation: String::new(),
+                    risk_level: RiskLevel::Low,
+                    reversible: true,
+                    reverse_command: None,
+                    question: None,
+                })
+                .collect(),
+            ..ExecutionPlan::new(task)
+        });
+        session
+    }
+
+    #[test]
+    fn test_sessions_are_grouped_by_turn() {
+        let mut transcript = Transcript::default();
+        transcript.record(session("orphan", &["pwd"]));
+        transcript.begin_turn("check disk");
+        transcript.record(session("check disk", &["df -h"]));
+        transcript.record(session("check disk [continuing]", &["du -sh ."]));
+
+        let inputs: Vec<&str> = transcript.turns.iter().map(|t| t.input.as_str()).collect();
+        assert_eq!(inputs, ["orphan", "check disk"]);
+        assert_eq!(transcript.turns[1].sessions.len(), 2);
+        assert_eq!(transcript.turns[1].first_plan_commands(), ["df -h"]);
+    }
+
+    #[test]
+    fn test_replay_outcome_ignores_whitespace() {
+        let outcome = |actual: Result<Vec<&str>, &str>| ReplayOutcome {
+            input: "x".into(),
+            expected: vec!["ls  -la".into()],
+            actual: actual.map(|a| a.iter().map(|s| s.to_string()).collect()).map_err(str::to_string),
+        };
+        assert!(outcome(Ok(vec!["ls -la"])).matches());
+        assert!(!outcome(Ok(vec!["ls"])).matches());
+        assert!(!outcome(Err("planning failed")).matches());
+    }
+}
@@ -9,10 +9,15 @@
 //!
 //! On X11: Uses xsetroot/xcursor for actual cursor replacement
 //! Fallback: Overlay window that follows the cursor
+//! Headless: Nothing is drawn on screen; the cursor is composited into
+//! captured screenshots instead (CI, demo and replay videos)
 
+use base64_lib::{engine::general_purpose::STANDARD as BASE64, Engine};
+use crate::vision::Screenshot;
 use std::path::PathBuf;
 use std::process::{Child, Command, Stdio};
-use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
 use std::time::{Duration, Instant};
 use std::io::Write;
 use std::env;
@@ -30,6 +35,108 @@ pub enum CursorStyle {
     Custom,
 }
 
+impl CursorStyle {
+    /// Fill color, shared by the X11 cursor, the overlay and rendered frames
+    pub fn color(&self) -> [u8; 3] {
+        match self {
+            CursorStyle::Ganesha | CursorStyle::Custom => [0xFF, 0xD7, 0x00], // Gold
+            CursorStyle::Trunk => [0xB0, 0xB8, 0xC4],                         // Elephant grey
+            CursorStyle::Eye => [0x00, 0xC8, 0xFF],                           // Cyan
+        }
+    }
+
+    /// Outline color
+    pub fn outline(&self) -> [u8; 3] {
+        [0xFF, 0x8C, 0x00] // Dark gold
+    }
+
+    /// Fill color as `#RRGGBB`
+    pub fn color_hex(&self) -> String {
+        hex(self.color())
+    }
+
+    /// Outline color as `#RRGGBB`
+    pub fn outline_hex(&self) -> String {
+        hex(self.outline())
+    }
+
+    /// Color of the glyph at offset (`dx`, `dy`) from the hotspot, for a
+    /// glyph `size` pixels across. `None` leaves the pixel untouched.
+    ///
+    /// Ganesha and Custom draw a gold disc with a dark-gold core (the same
+    /// shape as the X11 fallback cursor), Trunk an arrow pointer with its tip
+    /// on the hotspot, and Eye an eye with a dark pupil.
+    fn glyph_pixel(&self, dx: i32, dy: i32, size: u32) -> Option<[u8; 3]> {
+        let r = (size.max(4) / 2) as f32;
+        let (x, y) = (dx as f32, dy as f32);
+        match self {
+            CursorStyle::Ganesha | CursorStyle::Custom => {
+                let d = (x * x + y * y).sqrt();
+                if d <= r * 0.4 {
+                    Some(self.outline())
+                } else if d <= r {
+                    Some(self.color())
+                } else {
+                    None
+                }
+            }
+            CursorStyle::Trunk => {
+                // Right triangle down and to the right of the tip, with a
+                // one-pixel outline along the slanted edge
+                let len = 2.0 * r;
+                if x < 0.0 || y < 0.0 || y > len || x > y * 0.6 {
+                    None
+                } else if x > y * 0.6 - 1.5 || y > len - 1.5 {
+                    Some(self.outline())
+                } else {
+                    Some(self.color())
+                }
+            }
+            CursorStyle::Eye => {
+                let e = (x / r).powi(2) + (y / (r * 0.55)).powi(2);
+                if (x * x + y * y).sqrt() <= r * 0.25 {
+                    Some([0x10, 0x10, 0x10])
+                } else if e <= 1.0 {
+                    Some(if e > 0.75 { self.outline() } else { self.color() })
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    /// Paint the glyph into a tightly packed RGBA buffer with its hotspot at
+    /// (`x`, `y`). Parts outside the buffer are clipped.
+    pub fn draw(&self, pixels: &mut [u8], width: u32, height: u32, x: i32, y: i32, size: u32) {
+        let reach = size.max(4) as i32;
+        for py in (y - reach).max(0)..(y + reach + 1).min(height as i32) {
+            for px in (x - reach).max(0)..(x + reach + 1).min(width as i32) {
+                if let Some([r, g, b]) = self.glyph_pixel(px - x, py - y, size) {
+                    let i = (py as usize * width as usize + px as usize) * 4;
+                    if let Some(pixel) = pixels.get_mut(i..i + 4) {
+                        pixel.copy_from_slice(&[r, g, b, 0xFF]);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn hex([r, g, b]: [u8; 3]) -> String {
+    format!("#{:02X}{:02X}{:02X}", r, g, b)
+}
+
+/// Where the AI cursor is shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CursorBackend {
+    /// Overlay window on the live X11 display
+    #[default]
+    X11,
+    /// No display; the cursor only appears in frames passed to
+    /// `AiCursor::render_into`
+    Headless,
+}
+
 /// AI Cursor controller
 pub struct AiCursor {
     /// Current style
@@ -46,6 +153,10 @@ pub struct AiCursor {
     custom_symbol: String,
     /// Cursor size
     size: u32,
+    /// Where the cursor is drawn
+    backend: CursorBackend,
+    /// Last position the cursor was shown at
+    position: Arc<Mutex<Option<(i32, i32)>>>,
 }
 
 impl Default for AiCursor {
@@ -64,9 +175,17 @@ impl AiCursor {
             is_visible: Arc::new(Mutex::new(false)),
             custom_symbol: String::new(),
             size: 48, // Larger than normal cursor
+            backend: CursorBackend::X11,
+            position: Arc::new(Mutex::new(None)),
         }
     }
 
+    /// Set where the cursor is drawn
+    pub fn with_backend(mut self, backend: CursorBackend) -> Self {
+        self.backend = backend;
+        self
+    }
+
     /// Set cursor style
     pub fn with_style(mut self, style: CursorStyle) -> Self {
         self.style = style;
@@ -102,10 +221,21 @@ impl AiCursor {
         }
     }
 
+    /// Last position the cursor was shown at
+    pub fn position(&self) -> Option<(i32, i32)> {
+        *self.position.lock().expect("Position lock poisoned - unable to read cursor position")
+    }
+
     /// Show the AI cursor at a specific position
     pub fn show_at(&self, x: i32, y: i32) -> Result<(), String> {
         // Update last action time
         *self.last_action.lock().expect("Last action lock poisoned - unable to update cursor timestamp") = Instant::now();
+        *self.position.lock().expect("Position lock poisoned - unable to update cursor position") = Some((x, y));
+
+        if self.backend == CursorBackend::Headless {
+            *self.is_visible.lock().expect("Visibility lock poisoned - unable to update cursor visibility") = true;
+            return Ok(());
+        }
 
         // Kill existing overlay if any
         self.hide();
@@ -128,7 +258,7 @@ impl AiCursor {
                 "--sticky",
                 "--geometry",
                 &format!("{}x{}+{}+{}", self.size + 10, self.size + 10, offset_x, offset_y),
-                "--fore", "#FFD700",  // Gold color
+                "--fore", &self.style.color_hex(),
                 "--back", "#00000080", // Semi-transparent black
                 "--fontname", &format!("Sans {}", self.size),
                 "--timeout", &(self.linger_duration.as_secs() + 5).to_string(), // Auto-close
@@ -257,10 +387,43 @@ impl AiCursor {
     /// This requires xcursor-themes and xdotool
     pub fn set_system_cursor(&self) -> Result<(), String> {
         // Create and apply custom cursor
-        let cursor_manager = X11CursorManager::new()?;
+        let cursor_manager = X11CursorManager::new()?.with_style(self.style);
         cursor_manager.set_ganesha_cursor()
     }
 
+    /// Composite the cursor into a captured frame at its last shown
+    /// position, returning the annotated frame as PNG. Positions are in
+    /// screenshot pixels.
+    pub fn render_into(&self, screenshot: &Screenshot) -> Result<Screenshot, String> {
+        let (x, y) = self.position().ok_or("Cursor has not been shown yet")?;
+        self.render_at(screenshot, x, y)
+    }
+
+    /// Composite the cursor into a captured frame at (`x`, `y`)
+    pub fn render_at(&self, screenshot: &Screenshot, x: i32, y: i32) -> Result<Screenshot, String> {
+        let bytes = BASE64
+            .decode(&screenshot.data)
+            .map_err(|e| format!("Invalid screenshot data: {}", e))?;
+        let mut image = xcap::image::load_from_memory(&bytes)
+            .map_err(|e| format!("Failed to decode screenshot: {}", e))?
+            .to_rgba8();
+
+        let (width, height) = image.dimensions();
+        self.style.draw(&mut image, width, height, x, y, self.size);
+
+        let mut buffer = std::io::Cursor::new(Vec::new());
+        image
+            .write_to(&mut buffer, xcap::image::ImageFormat::Png)
+            .map_err(|e| format!("Failed to encode frame: {}", e))?;
+
+        Ok(Screenshot {
+            data: BASE64.encode(buffer.into_inner()),
+            width,
+            height,
+            ..screenshot.clone()
+        })
+    }
+
     /// Restore the default system cursor
     pub fn restore_system_cursor(&self) -> Result<(), String> {
         let cursor_manager = X11CursorManager::new()?;
@@ -276,6 +439,8 @@ impl AiCursor {
 pub struct X11CursorManager {
     cursor_dir: PathBuf,
     original_theme: Option<String>,
+    /// Colors for the generated cursor image
+    style: CursorStyle,
 }
 
 impl X11CursorManager {
@@ -299,9 +464,16 @@ impl X11CursorManager {
         Ok(Self {
             cursor_dir,
             original_theme,
+            style: CursorStyle::Ganesha,
         })
     }
 
+    /// Set the style whose colors the generated cursor uses
+    pub fn with_style(mut self, style: CursorStyle) -> Self {
+        self.style = style;
+        self
+    }
+
     /// Get current cursor theme name
     fn get_current_theme() -> Option<String> {
         // Try gsettings (GNOME)
@@ -391,14 +563,16 @@ static char *ganesha_cursor[] = {
     /// Create the Ganesha cursor as PNG (better quality)
     fn create_ganesha_cursor_png(&self) -> Result<PathBuf, String> {
         let png_path = self.cursor_dir.join("ganesha.png");
+        let fill = self.style.color_hex();
+        let outline = self.style.outline_hex();
 
         // Use ImageMagick to create a high-quality cursor from text
         let result = Command::new("convert")
             .args([
                 "-size", "48x48",
                 "-background", "transparent",
-                "-fill", "#FFD700",      // Gold
-                "-stroke", "#FF8C00",    // Dark gold outline
+                "-fill", &fill,
+                "-stroke", &outline,
                 "-strokewidth", "1",
                 "-font", "Noto-Sans-Symbols2",
                 "-pointsize", "36",
@@ -414,11 +588,11 @@ static char *ganesha_cursor[] = {
                 .args([
                     "-size", "32x32",
                     "xc:transparent",
-                    "-fill", "#FFD700",
-                    "-stroke", "#FF8C00",
+                    "-fill", &fill,
+                    "-stroke", &outline,
                     "-strokewidth", "2",
                     "-draw", "circle 16,16 16,4",
-                    "-fill", "#FF8C00",
+                    "-fill", &outline,
                     "-draw", "circle 16,16 16,10",
                     png_path.to_str().unwrap(),
                 ])
@@ -566,7 +740,7 @@ pub struct TracerMouse {
 }
 
 /// Easing function type for mouse movement
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy)]
 pub enum EasingType {
     /// Linear movement (constant speed)
     Linear,
@@ -578,6 +752,20 @@ pub enum EasingType {
     EaseInOut,
     /// Exponential ease out (very fast start, gradual slow)
     ExpoOut,
+    /// Caller-supplied curve mapping time (0.0-1.0) to distance covered.
+    /// Values outside 0.0-1.0 overshoot or pull back from the path.
+    Custom(fn(f64) -> f64),
+}
+
+/// `Custom` curves are equal when they are the same function pointer.
+/// Identical closures written in two places may still compare unequal.
+impl PartialEq for EasingType {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (EasingType::Custom(a), EasingType::Custom(b)) => std::ptr::fn_addr_eq(*a, *b),
+            _ => std::mem::discriminant(self) == std::mem::discriminant(other),
+        }
+    }
 }
 
 impl Default for TracerMouse {
@@ -629,9 +817,31 @@ impl TracerMouse {
             EasingType::ExpoOut => {
                 if t >= 1.0 { 1.0 } else { 1.0 - 2.0_f64.powf(-10.0 * t) }
             }
+            EasingType::Custom(curve) => curve(t),
         }
     }
 
+    /// Points the cursor passes through moving from `from` to `to`, without
+    /// moving it. One point per step, always ending exactly at `to`.
+    pub fn record_path(&self, from: (i32, i32), to: (i32, i32)) -> Vec<(i32, i32)> {
+        let steps = self.steps.max(1);
+        let mut path: Vec<(i32, i32)> = (1..steps)
+            .map(|i| {
+                let eased_t = self.ease(i as f64 / steps as f64);
+                (
+                    from.0 + ((to.0 - from.0) as f64 * eased_t) as i32,
+                    from.1 + ((to.1 - from.1) as f64 * eased_t) as i32,
+                )
+            })
+            .collect();
+        path.push(to);
+        path
+    }
+
+    fn step_delay(&self) -> Duration {
+        Duration::from_micros((self.duration_ms * 1000) / self.steps.max(1) as u64)
+    }
+
     /// Get current mouse position
     pub fn get_position() -> Result<(i32, i32), String> {
         let output = Command::new("xdotool")
@@ -671,48 +881,29 @@ impl TracerMouse {
 
     /// Move mouse from point A to point B with smooth animation
     pub fn move_from_to(&self, start_x: i32, start_y: i32, end_x: i32, end_y: i32) -> Result<(), String> {
-        let step_delay = Duration::from_micros((self.duration_ms * 1000) / self.steps as u64);
-
-        for i in 1..=self.steps {
-            let t = i as f64 / self.steps as f64;
-            let eased_t = self.ease(t);
-
-            let current_x = start_x + ((end_x - start_x) as f64 * eased_t) as i32;
-            let current_y = start_y + ((end_y - start_y) as f64 * eased_t) as i32;
+        let step_delay = self.step_delay();
 
-            Self::move_instant(current_x, current_y)?;
+        for (x, y) in self.record_path((start_x, start_y), (end_x, end_y)) {
+            Self::move_instant(x, y)?;
             std::thread::sleep(step_delay);
         }
-
-        // Ensure we end exactly at target
-        Self::move_instant(end_x, end_y)?;
         Ok(())
     }
 
     /// Move mouse with visible "tracer" effect (shows trail)
     pub fn move_with_tracer(&self, target_x: i32, target_y: i32, cursor: &AiCursor) -> Result<(), String> {
-        let (start_x, start_y) = Self::get_position()?;
-        let step_delay = Duration::from_micros((self.duration_ms * 1000) / self.steps as u64);
-
-        for i in 1..=self.steps {
-            let t = i as f64 / self.steps as f64;
-            let eased_t = self.ease(t);
-
-            let current_x = start_x + ((target_x - start_x) as f64 * eased_t) as i32;
-            let current_y = start_y + ((target_y - start_y) as f64 * eased_t) as i32;
+        let start = Self::get_position()?;
+        let step_delay = self.step_delay();
 
-            Self::move_instant(current_x, current_y)?;
+        for (x, y) in self.record_path(start, (target_x, target_y)) {
+            Self::move_instant(x, y)?;
 
             // Update cursor overlay position
-            cursor.on_mouse_action(current_x, current_y).ok();
+            cursor.on_mouse_action(x, y).ok();
 
             std::thread::sleep(step_delay);
         }
 
-        // Ensure we end exactly at target
-        Self::move_instant(target_x, target_y)?;
-        cursor.on_mouse_action(target_x, target_y).ok();
-
         Ok(())
     }
 
@@ -820,6 +1011,11 @@ pub fn smooth_move(x: i32, y: i32) -> Result<(), String> {
     TracerMouse::new().move_to(x, y)
 }
 
+/// Smooth move paced by `speed`
+pub fn smooth_move_paced(x: i32, y: i32, speed: SpeedMode) -> Result<(), String> {
+    speed.create_tracer().move_to(x, y)
+}
+
 /// Convenience function for quick smooth click
 pub fn smooth_click(x: i32, y: i32) -> Result<(), String> {
     TracerMouse::new().click_at(x, y)
@@ -853,45 +1049,58 @@ pub enum SpeedMode {
     /// Beast - AI outperforms the whole IT department
     /// "Maximum performance - unleash the full power"
     Beast,
+    /// Scaled - Normal pacing with the delay between actions multiplied
+    /// (2.0 = twice as long, 0.5 = half)
+    /// "Debugging a replay - slow it down just enough to follow"
+    Scaled(f32),
+    /// Step-through - pause before each action until `SpeedController::step`
+    /// (or the confirmation callback) advances it
+    /// "Debugging a replay - one action at a time"
+    StepThrough,
 }
 
 impl SpeedMode {
     /// Get the animation duration in milliseconds
     pub fn animation_ms(&self) -> u64 {
         match self {
-            SpeedMode::StepByStep => 1000,
+            SpeedMode::StepByStep | SpeedMode::StepThrough => 1000,
             SpeedMode::Audit => 500,
             SpeedMode::Slow => 300,
             SpeedMode::Normal => 150,
             SpeedMode::Fast => 50,
             SpeedMode::PowerUser => 20,
             SpeedMode::Beast => 0,
+            SpeedMode::Scaled(_) => SpeedMode::Normal.animation_ms(),
         }
     }
 
     /// Get the delay between actions in milliseconds
     pub fn action_delay_ms(&self) -> u64 {
         match self {
-            SpeedMode::StepByStep => 0, // Special: wait for confirmation
+            SpeedMode::StepByStep | SpeedMode::StepThrough => 0, // Special: wait for confirmation
             SpeedMode::Audit => 2000,
             SpeedMode::Slow => 500,
             SpeedMode::Normal => 100,
             SpeedMode::Fast => 30,
             SpeedMode::PowerUser => 10,
             SpeedMode::Beast => 0,
+            SpeedMode::Scaled(factor) => {
+                (SpeedMode::Normal.action_delay_ms() as f32 * factor.max(0.0)).round() as u64
+            }
         }
     }
 
     /// Get animation steps (higher = smoother)
     pub fn steps(&self) -> u32 {
         match self {
-            SpeedMode::StepByStep => 30,
+            SpeedMode::StepByStep | SpeedMode::StepThrough => 30,
             SpeedMode::Audit => 30,
             SpeedMode::Slow => 25,
             SpeedMode::Normal => 20,
             SpeedMode::Fast => 10,
             SpeedMode::PowerUser => 5,
             SpeedMode::Beast => 1,
+            SpeedMode::Scaled(_) => SpeedMode::Normal.steps(),
         }
     }
 
@@ -908,13 +1117,37 @@ impl SpeedMode {
     }
 }
 
+/// Replay progress reported by `SpeedController::status`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ControllerStatus {
+    pub mode: SpeedMode,
+    /// 1-based index of the action being run (0 before the first)
+    pub action_index: usize,
+    /// Total actions in the replay (0 if unknown)
+    pub total_actions: usize,
+}
+
 /// Speed controller for AI actions
+///
+/// Pacing only - this never blocks or allows anything on safety grounds.
+/// In `StepByStep` mode it pauses before each action until the confirmation
+/// callback (or the terminal prompt) advances it. In `StepThrough` mode it
+/// waits for a `step()` call from another thread instead, unless a
+/// confirmation callback is set.
 pub struct SpeedController {
     mode: SpeedMode,
     /// Callback for step-by-step confirmation
     confirmation_callback: Option<Box<dyn Fn(&str) -> bool + Send + Sync>>,
     /// Current action description
     current_action: String,
+    /// Actions started since `begin_replay`
+    action_index: AtomicUsize,
+    /// Actions expected in the current replay
+    total_actions: AtomicUsize,
+    /// Steps granted by `step()` and not yet used, for `StepThrough`
+    steps: Mutex<usize>,
+    /// Wakes an action waiting for a step
+    step_signal: Condvar,
 }
 
 impl Default for SpeedController {
@@ -929,6 +1162,10 @@ impl SpeedController {
             mode: SpeedMode::Normal,
             confirmation_callback: None,
             current_action: String::new(),
+            action_index: AtomicUsize::new(0),
+            total_actions: AtomicUsize::new(0),
+            steps: Mutex::new(0),
+            step_signal: Condvar::new(),
         }
     }
 
@@ -950,6 +1187,37 @@ impl SpeedController {
         self.confirmation_callback = Some(Box::new(callback));
     }
 
+    /// Reset progress for a replay of `total` actions
+    pub fn begin_replay(&self, total: usize) {
+        self.action_index.store(0, Ordering::SeqCst);
+        self.total_actions.store(total, Ordering::SeqCst);
+    }
+
+    /// Let the next action run in `StepThrough` mode. Steps given ahead of
+    /// time are saved up, one per action.
+    pub fn step(&self) {
+        *self.steps.lock().unwrap() += 1;
+        self.step_signal.notify_one();
+    }
+
+    /// Block until `step()` grants an action
+    fn wait_for_step(&self) {
+        let mut steps = self.steps.lock().unwrap();
+        while *steps == 0 {
+            steps = self.step_signal.wait(steps).unwrap();
+        }
+        *steps -= 1;
+    }
+
+    /// Current mode and replay progress, e.g. for an "action 3/7" display
+    pub fn status(&self) -> ControllerStatus {
+        ControllerStatus {
+            mode: self.mode,
+            action_index: self.action_index.load(Ordering::SeqCst),
+            total_actions: self.total_actions.load(Ordering::SeqCst),
+        }
+    }
+
     /// Get tracer mouse for current speed
     pub fn tracer(&self) -> TracerMouse {
         self.mode.create_tracer()
@@ -965,6 +1233,18 @@ impl SpeedController {
 
     /// Request confirmation before action (for step-by-step mode)
     pub fn confirm_action(&self, description: &str) -> bool {
+        self.action_index.fetch_add(1, Ordering::SeqCst);
+
+        if self.mode == SpeedMode::StepThrough {
+            return match self.confirmation_callback {
+                Some(ref callback) => callback(description),
+                None => {
+                    self.wait_for_step();
+                    true
+                }
+            };
+        }
+
         if !self.mode.requires_confirmation() {
             return true; // Auto-confirm for other modes
         }
@@ -1043,9 +1323,9 @@ impl SpeedController {
         }
 
         let delay = match self.mode {
-            SpeedMode::StepByStep | SpeedMode::Audit => 100,
+            SpeedMode::StepByStep | SpeedMode::StepThrough | SpeedMode::Audit => 100,
             SpeedMode::Slow => 50,
-            SpeedMode::Normal => 20,
+            SpeedMode::Normal | SpeedMode::Scaled(_) => 20,
             SpeedMode::Fast => 10,
             SpeedMode::PowerUser => 5,
             SpeedMode::Beast => 0,
@@ -1113,6 +1393,8 @@ impl SpeedMode {
             SpeedMode::Fast => "Efficient: I trust the AI, just get it done",
             SpeedMode::PowerUser => "IT guy after coffee: Speed it up!",
             SpeedMode::Beast => "Beast mode: Outperforms the whole IT department",
+            SpeedMode::Scaled(_) => "Scaled: Normal pacing, slowed down or sped up for debugging",
+            SpeedMode::StepThrough => "Step-through: One action per step, for debugging replays",
         }
     }
 }
@@ -1304,4 +1586,121 @@ mod tests {
 
         assert_eq!(cursor.get_symbol(), "🔮");
     }
+
+    #[test]
+    fn test_scaled_speed_mode() {
+        assert_eq!(SpeedMode::Scaled(1.0).action_delay_ms(), SpeedMode::Normal.action_delay_ms());
+        assert_eq!(SpeedMode::Scaled(2.5).action_delay_ms(), 250);
+        assert_eq!(SpeedMode::Scaled(-1.0).action_delay_ms(), 0);
+        assert!(!SpeedMode::Scaled(10.0).requires_confirmation());
+    }
+
+    #[test]
+    fn test_step_through_waits_for_step() {
+        let mut controller = SpeedController::new();
+        controller.set_mode(SpeedMode::StepThrough);
+        controller.begin_replay(2);
+        let controller = Arc::new(controller);
+
+        let (done_tx, done_rx) = std::sync::mpsc::channel();
+        let runner = {
+            let controller = controller.clone();
+            std::thread::spawn(move || {
+                for n in 1..=2 {
+                    controller.execute("action", || done_tx.send(n).unwrap());
+                }
+            })
+        };
+
+        // Nothing runs until a step arrives
+        assert!(done_rx.recv_timeout(Duration::from_millis(200)).is_err());
+        assert_eq!(controller.status().action_index, 1);
+
+        controller.step();
+        assert_eq!(done_rx.recv_timeout(Duration::from_secs(5)), Ok(1));
+        assert!(done_rx.recv_timeout(Duration::from_millis(200)).is_err());
+
+        controller.step();
+        assert_eq!(done_rx.recv_timeout(Duration::from_secs(5)), Ok(2));
+        runner.join().unwrap();
+        let status = controller.status();
+        assert_eq!((status.action_index, status.total_actions), (2, 2));
+    }
+
+    #[test]
+    fn test_step_through_reports_progress() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let mut controller = SpeedController::new();
+        controller.set_mode(SpeedMode::StepByStep);
+        let log = seen.clone();
+        controller.set_confirmation_callback(move |desc| {
+            log.lock().unwrap().push(desc.to_string());
+            desc != "skip me"
+        });
+
+        controller.begin_replay(3);
+        assert_eq!(controller.status().action_index, 0);
+
+        assert_eq!(controller.execute("first", || 1), Some(1));
+        assert_eq!(controller.execute("skip me", || 2), None);
+        let status = controller.status();
+        assert_eq!((status.action_index, status.total_actions), (2, 3));
+        assert_eq!(status.mode, SpeedMode::StepByStep);
+        assert_eq!(*seen.lock().unwrap(), vec!["first", "skip me"]);
+    }
+
+    #[test]
+    fn test_record_path_follows_easing() {
+        assert_eq!(EasingType::EaseOut, EasingType::EaseOut);
+        assert_ne!(EasingType::EaseOut, EasingType::Linear);
+        let curve: fn(f64) -> f64 = |t| t;
+        let custom = EasingType::Custom(curve);
+        assert_eq!(custom, custom);
+        assert_eq!(custom, EasingType::Custom(curve));
+        assert_ne!(custom, EasingType::Custom(|t| t * t));
+        assert_ne!(custom, EasingType::Linear);
+
+        let linear = TracerMouse::new().with_steps(4).with_easing(EasingType::Linear);
+        assert_eq!(
+            linear.record_path((0, 0), (100, 40)),
+            vec![(25, 10), (50, 20), (75, 30), (100, 40)]
+        );
+
+        // A custom curve that overshoots, and still lands on the target
+        let overshoot = TracerMouse::new()
+            .with_steps(4)
+            .with_easing(EasingType::Custom(|t| t * 1.5));
+        let path = overshoot.record_path((0, 0), (100, 0));
+        assert_eq!(path, vec![(37, 0), (75, 0), (112, 0), (100, 0)]);
+
+        let beast = SpeedMode::Beast.create_tracer();
+        assert_eq!(beast.record_path((10, 10), (20, 20)), vec![(20, 20)]);
+    }
+
+    #[test]
+    fn test_headless_cursor_draws_style() {
+        let cursor = AiCursor::new()
+            .with_style(CursorStyle::Eye)
+            .with_size(10)
+            .with_backend(CursorBackend::Headless);
+        cursor.show_at(8, 8).unwrap();
+        assert_eq!(cursor.position(), Some((8, 8)));
+        assert!(cursor.is_visible());
+
+        let (width, height) = (20u32, 20u32);
+        let at = |pixels: &[u8], x: usize, y: usize| -> [u8; 4] {
+            pixels[(y * width as usize + x) * 4..][..4].try_into().unwrap()
+        };
+
+        let mut pixels = vec![0u8; (width * height * 4) as usize];
+        CursorStyle::Eye.draw(&mut pixels, width, height, 8, 8, 10);
+        assert_eq!(at(&pixels, 8, 8), [0x10, 0x10, 0x10, 0xFF], "pupil on the hotspot");
+        assert_eq!(at(&pixels, 11, 8), [0x00, 0xC8, 0xFF, 0xFF], "iris in the style color");
+        assert_eq!(at(&pixels, 8, 14), [0, 0, 0, 0], "outside the eye is untouched");
+        assert_eq!(CursorStyle::Ganesha.color_hex(), "#FFD700");
+
+        // Clipped at the frame edge instead of panicking
+        CursorStyle::Trunk.draw(&mut pixels, width, height, 18, 18, 10);
+        assert_eq!(at(&pixels, 18, 19), [0xFF, 0x8C, 0x00, 0xFF]);
+    }
 }
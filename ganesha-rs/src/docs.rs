@@ -6,10 +6,23 @@
 //! - Task being performed
 //!
 //! Pluggable backend: Context7, local docs, custom knowledge base, etc.
+//!
+//! Library docs can be pinned to a version with `DocsLoader::fetch`. Fetched
+//! docs are cached on disk per library and version, so they are still served
+//! (marked stale once old) when offline or in cache-only mode.
 
 use async_trait::async_trait;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Age after which cached library docs are reported as stale
+const LIBRARY_DOCS_TTL_SECS: u64 = 24 * 60 * 60;
+
+/// Content word overlap at which two snippets count as the same
+const NEAR_DUPLICATE: f32 = 0.8;
 
 // ═══════════════════════════════════════════════════════════════════════════════
 // TRAIT: Documentation Provider (pluggable backend)
@@ -27,6 +40,18 @@ pub trait DocsProvider: Send + Sync {
     /// Fetch docs for a specific topic
     async fn fetch(&self, query: &str) -> Result<Vec<DocSnippet>, String>;
 
+    /// Fetch docs for a library, pinned to `version` if given. Providers
+    /// without version support ignore it.
+    async fn fetch_version(&self, library: &str, version: Option<&str>) -> Result<Vec<DocSnippet>, String> {
+        let _ = version;
+        self.fetch(library).await
+    }
+
+    /// Whether the provider works offline (consulted before remote ones)
+    fn is_local(&self) -> bool {
+        false
+    }
+
     /// Fetch docs relevant to current app context
     async fn fetch_for_app(&self, app_name: &str, task: &str) -> Result<Vec<DocSnippet>, String>;
 
@@ -47,6 +72,51 @@ pub struct DocSnippet {
     pub category: String,
     /// Tags for filtering
     pub tags: Vec<String>,
+    /// Name of the provider that supplied it (filled in by `DocsLoader`
+    /// when a provider leaves it empty)
+    #[serde(default)]
+    pub provider: String,
+    /// Stand-in text from a provider that is not wired up yet. Never cached.
+    #[serde(default)]
+    pub placeholder: bool,
+}
+
+/// Where `DocsLoader::fetch` got its docs from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum DocsOrigin {
+    /// A local provider
+    Local,
+    /// A remote provider, just now
+    Live,
+    /// The docs cache
+    Cache,
+}
+
+/// Library docs returned by `DocsLoader::fetch`
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct LibraryDocs {
+    pub library: String,
+    /// Pinned version, `None` for latest
+    pub version: Option<String>,
+    pub snippets: Vec<DocSnippet>,
+    /// Unix timestamp (secs) of the fetch
+    pub fetched_at: u64,
+    #[serde(skip, default = "default_origin")]
+    pub origin: DocsOrigin,
+    /// Served from a cache entry older than a day
+    #[serde(skip)]
+    pub stale: bool,
+}
+
+fn default_origin() -> DocsOrigin {
+    DocsOrigin::Cache
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
 }
 
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -58,6 +128,16 @@ pub struct DocsLoader {
     providers: Vec<Arc<dyn DocsProvider>>,
     cache: tokio::sync::RwLock<HashMap<String, CachedDocs>>,
     cache_ttl_secs: u64,
+    /// Library docs by `library@version`
+    library_cache: tokio::sync::RwLock<HashMap<String, LibraryDocs>>,
+    /// Where library docs are persisted (memory only if unset)
+    cache_dir: Option<PathBuf>,
+    /// Never call remote providers
+    cache_only: AtomicBool,
+    /// Maximum snippets returned by `get_context_docs`
+    top_k: usize,
+    /// Trust multiplier per provider name (1.0 if unset)
+    provider_weights: HashMap<String, f32>,
 }
 
 #[derive(Clone)]
@@ -72,14 +152,211 @@ impl DocsLoader {
             providers: Vec::new(),
             cache: tokio::sync::RwLock::new(HashMap::new()),
             cache_ttl_secs: 300, // 5 minute cache
+            library_cache: tokio::sync::RwLock::new(HashMap::new()),
+            cache_dir: None,
+            cache_only: AtomicBool::new(false),
+            top_k: 10,
+            provider_weights: HashMap::new(),
         }
     }
 
+    /// Set how many snippets `get_context_docs` returns
+    pub fn with_top_k(mut self, top_k: usize) -> Self {
+        self.top_k = top_k;
+        self
+    }
+
+    /// Weight a provider's snippets when ranking (e.g. 1.5 for a trusted
+    /// internal knowledge base, 0.5 for a noisy one)
+    pub fn set_provider_weight(&mut self, provider: &str, weight: f32) {
+        self.provider_weights.insert(provider.to_string(), weight.max(0.0));
+    }
+
+    fn provider_weight(&self, snippet: &DocSnippet) -> f32 {
+        self.provider_weights.get(&snippet.provider).copied().unwrap_or(1.0)
+    }
+
+    /// Rank snippets from all providers against `query` and keep the best
+    /// `top_k`.
+    ///
+    /// Each snippet scores `0.6 * query word overlap + 0.4 * its relevance`,
+    /// times its provider's weight. Near-identical snippets (80% shared
+    /// content words) collapse into the one from the better source: higher
+    /// provider weight times relevance.
+    pub fn rank(&self, query: &str, snippets: Vec<DocSnippet>, top_k: usize) -> Vec<DocSnippet> {
+        let query_words = words(query);
+        let quality = |s: &DocSnippet| self.provider_weight(s) * s.relevance;
+
+        let mut scored: Vec<(f32, HashSet<String>, DocSnippet)> = snippets
+            .into_iter()
+            .map(|snippet| {
+                let text = format!("{} {} {}", snippet.source, snippet.tags.join(" "), snippet.content);
+                let overlap = if query_words.is_empty() {
+                    0.0
+                } else {
+                    let text_words = words(&text);
+                    query_words.intersection(&text_words).count() as f32 / query_words.len() as f32
+                };
+                let score = (0.6 * overlap + 0.4 * snippet.relevance) * self.provider_weight(&snippet);
+                (score, words(&snippet.content), snippet)
+            })
+            .collect();
+        scored.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap_or(std::cmp::Ordering::Equal));
+
+        let mut kept: Vec<(HashSet<String>, DocSnippet)> = Vec::new();
+        for (_, content_words, snippet) in scored {
+            let duplicate = kept.iter().position(|(kept_words, kept_snippet)| {
+                kept_snippet.source == snippet.source
+                    || similarity(kept_words, &content_words) >= NEAR_DUPLICATE
+            });
+            match duplicate {
+                Some(i) => {
+                    if quality(&snippet) > quality(&kept[i].1) {
+                        kept[i] = (content_words, snippet);
+                    }
+                }
+                None => kept.push((content_words, snippet)),
+            }
+        }
+
+        kept.into_iter().take(top_k).map(|(_, snippet)| snippet).collect()
+    }
+
+    /// Persist fetched library docs under `dir` so they survive restarts
+    pub fn with_cache_dir(mut self, dir: PathBuf) -> Self {
+        self.cache_dir = Some(dir);
+        self
+    }
+
     /// Add a documentation provider
     pub fn add_provider(&mut self, provider: Arc<dyn DocsProvider>) {
         self.providers.push(provider);
     }
 
+    /// Only use local providers and cached docs (fully offline runs)
+    pub fn set_cache_only(&self, cache_only: bool) {
+        self.cache_only.store(cache_only, Ordering::SeqCst);
+    }
+
+    pub fn is_cache_only(&self) -> bool {
+        self.cache_only.load(Ordering::SeqCst)
+    }
+
+    /// Providers that may be called right now: local ones first, remote
+    /// ones after unless in cache-only mode
+    fn usable_providers(&self) -> impl Iterator<Item = &Arc<dyn DocsProvider>> {
+        let remote_allowed = !self.is_cache_only();
+        let local = self.providers.iter().filter(|p| p.is_local());
+        let remote = self.providers.iter().filter(move |p| remote_allowed && !p.is_local());
+        local.chain(remote)
+    }
+
+    /// Fetch docs for a library, pinned to `version` (latest if `None`).
+    ///
+    /// Local providers are consulted first. Otherwise a cached copy younger
+    /// than a day is used, then remote providers, and finally any older
+    /// cached copy, marked stale.
+    pub async fn fetch(&self, library: &str, version: Option<&str>) -> Result<LibraryDocs, String> {
+        let key = library_cache_key(library, version);
+        let fetched = |snippets: Vec<DocSnippet>, origin: DocsOrigin| LibraryDocs {
+            library: library.to_string(),
+            version: version.map(str::to_string),
+            snippets,
+            fetched_at: now_secs(),
+            origin,
+            stale: false,
+        };
+
+        for provider in self.usable_providers().filter(|p| p.is_local()) {
+            if let Ok(snippets) = provider.fetch_version(library, version).await {
+                if !snippets.is_empty() {
+                    return Ok(fetched(attribute(provider.as_ref(), snippets), DocsOrigin::Local));
+                }
+            }
+        }
+
+        let cached = self.cached_library_docs(&key).await;
+        if let Some(docs) = cached.as_ref().filter(|d| !d.stale) {
+            return Ok(docs.clone());
+        }
+
+        for provider in self.usable_providers().filter(|p| !p.is_local()) {
+            if !provider.is_available().await {
+                continue;
+            }
+            match provider.fetch_version(library, version).await {
+                Ok(snippets) if !snippets.is_empty() => {
+                    let docs = fetched(attribute(provider.as_ref(), snippets), DocsOrigin::Live);
+                    if !has_placeholder(&docs.snippets) {
+                        self.store_library_docs(&key, &docs).await;
+                    }
+                    return Ok(docs);
+                }
+                _ => continue,
+            }
+        }
+
+        cached.ok_or_else(|| {
+            format!(
+                "No docs for {}@{} (not cached{})",
+                library,
+                version.unwrap_or("latest"),
+                if self.is_cache_only() { ", cache-only mode" } else { "" }
+            )
+        })
+    }
+
+    /// Cached library docs from memory or disk, with staleness set
+    async fn cached_library_docs(&self, key: &str) -> Option<LibraryDocs> {
+        let in_memory = self.library_cache.read().await.get(key).cloned();
+        let mut docs = match in_memory {
+            Some(docs) => docs,
+            None => {
+                let path = self.cache_dir.as_deref().map(|dir| cache_file(dir, key))?;
+                let docs: LibraryDocs = serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()?;
+                self.library_cache.write().await.insert(key.to_string(), docs.clone());
+                docs
+            }
+        };
+        if has_placeholder(&docs.snippets) {
+            return None;
+        }
+        docs.origin = DocsOrigin::Cache;
+        docs.stale = now_secs().saturating_sub(docs.fetched_at) >= LIBRARY_DOCS_TTL_SECS;
+        Some(docs)
+    }
+
+    async fn store_library_docs(&self, key: &str, docs: &LibraryDocs) {
+        self.library_cache.write().await.insert(key.to_string(), docs.clone());
+
+        if let Some(dir) = &self.cache_dir {
+            let written = std::fs::create_dir_all(dir).and_then(|_| {
+                let json = serde_json::to_string(docs).map_err(std::io::Error::other)?;
+                std::fs::write(cache_file(dir, key), json)
+            });
+            if let Err(e) = written {
+                tracing::warn!("Failed to cache docs for {}: {}", key, e);
+            }
+        }
+    }
+
+    /// Drop all cached docs, in memory and on disk
+    pub async fn clear_cache(&self) {
+        self.cache.write().await.clear();
+        self.library_cache.write().await.clear();
+
+        if let Some(dir) = &self.cache_dir {
+            if let Ok(entries) = std::fs::read_dir(dir) {
+                for entry in entries.flatten() {
+                    let path = entry.path();
+                    if path.extension().is_some_and(|ext| ext == "json") {
+                        let _ = std::fs::remove_file(path);
+                    }
+                }
+            }
+        }
+    }
+
     /// Get docs for current context (auto-detects best source)
     pub async fn get_context_docs(
         &self,
@@ -103,33 +380,28 @@ impl DocsLoader {
         let mut all_docs = Vec::new();
 
         // Try each provider
-        for provider in &self.providers {
+        for provider in self.usable_providers() {
             if !provider.is_available().await {
                 continue;
             }
 
             // Fetch app-specific docs
             if let Ok(docs) = provider.fetch_for_app(app_name, task).await {
-                all_docs.extend(docs);
+                all_docs.extend(attribute(provider.as_ref(), docs));
             }
 
             // Fetch OS patterns
             if let Ok(docs) = provider.fetch_os_patterns(os, desktop_env).await {
-                all_docs.extend(docs);
+                all_docs.extend(attribute(provider.as_ref(), docs));
             }
         }
 
-        // Sort by relevance
-        all_docs.sort_by(|a, b| b.relevance.partial_cmp(&a.relevance).unwrap_or(std::cmp::Ordering::Equal));
-
-        // Deduplicate
-        all_docs.dedup_by(|a, b| a.source == b.source);
-
-        // Take top results
-        all_docs.truncate(10);
+        // Rank, merge overlapping snippets and take top results
+        let query = format!("{} {} {} {}", app_name, task, os, desktop_env);
+        let all_docs = self.rank(&query, all_docs, self.top_k);
 
-        // Cache results
-        {
+        // Cache results, unless a provider only had stand-in text
+        if !has_placeholder(&all_docs) {
             let mut cache = self.cache.write().await;
             cache.insert(cache_key, CachedDocs {
                 snippets: all_docs.clone(),
@@ -167,6 +439,49 @@ impl DocsLoader {
     }
 }
 
+/// Mark snippets a provider left unattributed as coming from it
+fn attribute(provider: &dyn DocsProvider, mut snippets: Vec<DocSnippet>) -> Vec<DocSnippet> {
+    for snippet in snippets.iter_mut().filter(|s| s.provider.is_empty()) {
+        snippet.provider = provider.name().to_string();
+    }
+    snippets
+}
+
+/// Whether any snippet is stand-in text that must not be cached
+fn has_placeholder(snippets: &[DocSnippet]) -> bool {
+    snippets.iter().any(|s| s.placeholder)
+}
+
+/// Lowercase words of two or more characters
+fn words(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.len() > 1)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Shared fraction of two word sets (Jaccard)
+fn similarity(a: &HashSet<String>, b: &HashSet<String>) -> f32 {
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    a.intersection(b).count() as f32 / a.union(b).count() as f32
+}
+
+/// Cache key for a library version; the version is part of the key so pinned
+/// and latest docs never mix
+fn library_cache_key(library: &str, version: Option<&str>) -> String {
+    format!("{}@{}", library.to_lowercase(), version.unwrap_or("latest"))
+}
+
+fn cache_file(dir: &Path, key: &str) -> PathBuf {
+    let name: String = key
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || "@.-_".contains(c) { c } else { '_' })
+        .collect();
+    dir.join(format!("{}.json", name))
+}
+
 // ═══════════════════════════════════════════════════════════════════════════════
 // CONTEXT7 PROVIDER (MCP-based)
 // ═══════════════════════════════════════════════════════════════════════════════
@@ -250,6 +565,31 @@ impl DocsProvider for Context7Provider {
             relevance: 0.5,
             category: "general".into(),
             tags: vec![query.to_string()],
+            provider: self.name().into(),
+            placeholder: true,
+        }])
+    }
+
+    async fn fetch_version(&self, library: &str, version: Option<&str>) -> Result<Vec<DocSnippet>, String> {
+        let Some(library_id) = self.find_library(library) else {
+            return self.fetch(library).await;
+        };
+
+        // Context7 pins versions as `<library id>/<version>`
+        let pinned = match version {
+            Some(version) => format!("{}/{}", library_id, version),
+            None => library_id.clone(),
+        };
+
+        // TODO: Call Context7 MCP get-library-docs with the pinned ID
+        Ok(vec![DocSnippet {
+            source: format!("Context7: {}", pinned),
+            content: format!("Documentation for '{}' would be fetched from Context7 MCP", pinned),
+            relevance: 0.7,
+            category: "api".into(),
+            tags: vec![library.to_lowercase()],
+            provider: self.name().into(),
+            placeholder: true,
         }])
     }
 
@@ -271,6 +611,8 @@ impl DocsProvider for Context7Provider {
             relevance: 0.8,
             category: "gui".into(),
             tags: vec![app_name.to_lowercase(), "gui".into()],
+            provider: self.name().into(),
+            placeholder: true,
         }])
     }
 
@@ -294,6 +636,8 @@ impl DocsProvider for Context7Provider {
             relevance: 0.6,
             category: "os".into(),
             tags: vec![os.to_lowercase(), desktop_env.to_lowercase()],
+            provider: self.name().into(),
+            placeholder: true,
         }])
     }
 }
@@ -340,6 +684,8 @@ impl LocalDocsProvider {
                 relevance: 1.0,
                 category: "gui".into(),
                 tags: vec!["browser".into(), "web".into()],
+                provider: "Local".into(),
+                placeholder: false,
             },
         ]);
 
@@ -372,6 +718,8 @@ impl LocalDocsProvider {
                 relevance: 1.0,
                 category: "gui".into(),
                 tags: vec!["ebay".into(), "shopping".into()],
+                provider: "Local".into(),
+                placeholder: false,
             },
         ]);
 
@@ -404,6 +752,8 @@ impl LocalDocsProvider {
                 relevance: 0.9,
                 category: "os".into(),
                 tags: vec!["linux".into(), "gnome".into(), "x11".into()],
+                provider: "Local".into(),
+                placeholder: false,
             },
         ]);
 
@@ -434,6 +784,38 @@ impl DocsProvider for LocalDocsProvider {
         true // Always available
     }
 
+    fn is_local(&self) -> bool {
+        true
+    }
+
+    async fn fetch_version(&self, library: &str, version: Option<&str>) -> Result<Vec<DocSnippet>, String> {
+        // Docs dir files: `<library>@<version>.md`, falling back to `<library>.md`
+        if let Some(dir) = &self.docs_dir {
+            let library = library.to_lowercase();
+            let mut names = Vec::new();
+            if let Some(version) = version {
+                names.push(format!("{}@{}.md", library, version));
+            }
+            names.push(format!("{}.md", library));
+
+            for name in names {
+                if let Ok(content) = std::fs::read_to_string(dir.join(&name)) {
+                    return Ok(vec![DocSnippet {
+                        source: name,
+                        content,
+                        relevance: 1.0,
+                        category: "api".into(),
+                        tags: vec![library],
+                        provider: self.name().into(),
+                        placeholder: false,
+                    }]);
+                }
+            }
+        }
+
+        self.fetch(library).await
+    }
+
     async fn fetch(&self, query: &str) -> Result<Vec<DocSnippet>, String> {
         let query_lower = query.to_lowercase();
         let mut results = Vec::new();
@@ -470,7 +852,11 @@ impl Default for DocsLoader {
         loader.add_provider(Arc::new(Context7Provider::new()));
         loader.add_provider(Arc::new(LocalDocsProvider::new()));
 
-        loader
+        let cache_dir = dirs::home_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join(".ganesha")
+            .join("docs_cache");
+        loader.with_cache_dir(cache_dir)
     }
 }
 
@@ -504,4 +890,127 @@ mod tests {
         assert!(!docs.is_empty());
         assert!(docs[0].content.contains("s-item"));
     }
+
+    /// Remote provider returning real (non-placeholder) docs
+    struct RemoteDocs;
+
+    #[async_trait]
+    impl DocsProvider for RemoteDocs {
+        fn name(&self) -> &str {
+            "Remote"
+        }
+
+        async fn is_available(&self) -> bool {
+            true
+        }
+
+        async fn fetch(&self, query: &str) -> Result<Vec<DocSnippet>, String> {
+            self.fetch_version(query, None).await
+        }
+
+        async fn fetch_version(&self, library: &str, version: Option<&str>) -> Result<Vec<DocSnippet>, String> {
+            Ok(vec![DocSnippet {
+                source: format!("{}@{}", library, version.unwrap_or("latest")),
+                content: format!("# {}", library),
+                relevance: 0.7,
+                category: "api".into(),
+                tags: vec![],
+                provider: String::new(),
+                placeholder: false,
+            }])
+        }
+
+        async fn fetch_for_app(&self, _app_name: &str, _task: &str) -> Result<Vec<DocSnippet>, String> {
+            Ok(vec![])
+        }
+
+        async fn fetch_os_patterns(&self, _os: &str, _desktop_env: &str) -> Result<Vec<DocSnippet>, String> {
+            Ok(vec![])
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pinned_fetch_is_cached_per_version() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache_dir = dir.path().join("cache");
+
+        let mut loader = DocsLoader::new().with_cache_dir(cache_dir.clone());
+        loader.add_provider(Arc::new(RemoteDocs));
+
+        let docs = loader.fetch("tokio", Some("1.38")).await.unwrap();
+        assert_eq!(docs.origin, DocsOrigin::Live);
+        assert_eq!(docs.snippets[0].source, "tokio@1.38");
+        assert_eq!(docs.snippets[0].provider, "Remote");
+        assert_eq!(loader.fetch("tokio", Some("1.38")).await.unwrap().origin, DocsOrigin::Cache);
+
+        // Offline: a new loader serves what is on disk, but only that version
+        let offline = DocsLoader::new().with_cache_dir(cache_dir);
+        offline.set_cache_only(true);
+        let docs = offline.fetch("tokio", Some("1.38")).await.unwrap();
+        assert_eq!((docs.origin, docs.stale), (DocsOrigin::Cache, false));
+        assert_eq!(docs.version.as_deref(), Some("1.38"));
+        assert!(offline.fetch("tokio", None).await.is_err());
+
+        offline.clear_cache().await;
+        assert!(offline.fetch("tokio", Some("1.38")).await.is_err());
+
+        // Configured local docs win over remote providers
+        let docs_dir = dir.path().join("docs");
+        std::fs::create_dir_all(&docs_dir).unwrap();
+        std::fs::write(docs_dir.join("tokio@1.38.md"), "# tokio 1.38").unwrap();
+        loader.add_provider(Arc::new(LocalDocsProvider::new().with_docs_dir(docs_dir)));
+        let docs = loader.fetch("tokio", Some("1.38")).await.unwrap();
+        assert_eq!(docs.origin, DocsOrigin::Local);
+        assert_eq!(docs.snippets[0].content, "# tokio 1.38");
+    }
+
+    #[tokio::test]
+    async fn test_context7_placeholder_is_not_cached() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache_dir = dir.path().join("cache");
+        let mut context7 = Context7Provider::new();
+        context7.add_library("tokio", "tokio-rs/tokio");
+
+        let mut loader = DocsLoader::new().with_cache_dir(cache_dir.clone());
+        loader.add_provider(Arc::new(context7));
+
+        let docs = loader.fetch("tokio", Some("1.38")).await.unwrap();
+        assert!(docs.snippets[0].placeholder);
+        assert!(docs.snippets[0].source.contains("tokio-rs/tokio/1.38"));
+        assert_eq!(loader.fetch("tokio", Some("1.38")).await.unwrap().origin, DocsOrigin::Live);
+        assert!(!cache_dir.exists());
+
+        loader.set_cache_only(true);
+        assert!(loader.fetch("tokio", Some("1.38")).await.is_err());
+    }
+
+    #[test]
+    fn test_rank_merges_duplicates_from_trusted_source() {
+        let snippet = |source: &str, content: &str, relevance: f32, provider: &str| DocSnippet {
+            source: source.into(),
+            content: content.into(),
+            relevance,
+            category: "gui".into(),
+            tags: vec![],
+            provider: provider.into(),
+            placeholder: false,
+        };
+        let mut loader = DocsLoader::new();
+        loader.set_provider_weight("Internal", 1.5);
+
+        let ranked = loader.rank(
+            "firefox open private window",
+            vec![
+                snippet("Shortcuts", "Press Ctrl+Shift+P to open a private window in Firefox", 0.9, "Context7"),
+                snippet("Firefox tips", "Press Ctrl+Shift+P to open a private window in Firefox.", 0.6, "Internal"),
+                snippet("GNOME", "Super opens the activities overview", 0.5, "Local"),
+                snippet("Tabs", "Firefox tabs can be pinned from the tab context menu", 0.5, "Context7"),
+            ],
+            2,
+        );
+
+        let sources: Vec<&str> = ranked.iter().map(|s| s.source.as_str()).collect();
+        assert_eq!(sources, ["Firefox tips", "Tabs"]);
+        assert_eq!(ranked[0].provider, "Internal");
+    }
 }
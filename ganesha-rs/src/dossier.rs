@@ -9,10 +9,17 @@
 
 use std::collections::HashMap;
 use std::process::Command;
-use std::time::{SystemTime, UNIX_EPOCH};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Installed apps rarely change; rescan them at most this often
+pub const INSTALLED_APPS_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Last installed-apps scan (Unix time, apps), shared across dossiers
+static INSTALLED_APPS: Mutex<Option<(u64, Vec<InstalledApp>)>> = Mutex::new(None);
 
 /// Complete system state snapshot
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct SystemDossier {
     /// Timestamp of this snapshot
     pub timestamp: u64,
@@ -37,9 +44,56 @@ pub struct SystemDossier {
 
     /// Network interfaces and stats
     pub network: Vec<NetworkInterface>,
+
+    /// When each section was last refreshed
+    #[serde(default)]
+    pub refreshed: RefreshTimes,
+}
+
+/// Unix timestamps of the last refresh of each dossier section
+#[derive(Debug, Clone, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RefreshTimes {
+    pub windows: u64,
+    pub processes: u64,
+    pub installed_apps: u64,
+    pub resources: u64,
+    pub network: u64,
+}
+
+/// What changed between two dossiers
+#[derive(Debug, Clone, Default)]
+pub struct DossierDiff {
+    /// Windows present now but not before
+    pub windows_opened: Vec<WindowInfo>,
+    /// Windows present before but not now
+    pub windows_closed: Vec<WindowInfo>,
+    /// Processes that entered the top-process list
+    pub processes_started: Vec<ProcessInfo>,
+    /// Processes that left the top-process list
+    pub processes_stopped: Vec<ProcessInfo>,
+    /// Newly focused window, if focus moved
+    pub focus_changed: Option<WindowInfo>,
 }
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+impl DossierDiff {
+    /// Whether nothing changed
+    pub fn is_empty(&self) -> bool {
+        self.windows_opened.is_empty()
+            && self.windows_closed.is_empty()
+            && self.processes_started.is_empty()
+            && self.processes_stopped.is_empty()
+            && self.focus_changed.is_none()
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct OsInfo {
     pub name: String,           // "Ubuntu", "Arch Linux", "Windows 11"
     pub version: String,        // "24.04", "rolling", "23H2"
@@ -49,7 +103,7 @@ pub struct OsInfo {
     pub desktop_env: String,    // "GNOME", "KDE", "Windows Explorer"
 }
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct DisplayInfo {
     pub width: u32,
     pub height: u32,
@@ -58,7 +112,7 @@ pub struct DisplayInfo {
     pub active_monitor: usize,  // Which monitor has focus
 }
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct MonitorInfo {
     pub name: String,
     pub width: u32,
@@ -68,7 +122,7 @@ pub struct MonitorInfo {
     pub primary: bool,
 }
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct WindowInfo {
     pub id: String,             // Window ID (X11 window id, HWND, etc)
     pub title: String,
@@ -86,7 +140,7 @@ pub struct WindowInfo {
     pub workspace: u32,         // Virtual desktop number
 }
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct ProcessInfo {
     pub pid: u32,
     pub name: String,
@@ -97,7 +151,7 @@ pub struct ProcessInfo {
     pub user: String,
 }
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct InstalledApp {
     pub name: String,
     pub exec: String,           // Command to launch
@@ -106,7 +160,7 @@ pub struct InstalledApp {
     pub desktop_file: String,   // Path to .desktop file
 }
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct ResourceStats {
     pub cpu_percent: f32,
     pub cpu_cores: u32,
@@ -121,7 +175,7 @@ pub struct ResourceStats {
     pub load_avg: [f32; 3],     // 1min, 5min, 15min
 }
 
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct NetworkInterface {
     pub name: String,
     pub ip: String,
@@ -143,12 +197,12 @@ impl SystemDossier {
     ///
     /// This data helps the AI understand the user's environment
     /// and provide contextually appropriate responses.
+    ///
+    /// Installed apps come from a cache refreshed every `INSTALLED_APPS_TTL`.
     #[cfg(target_os = "linux")]
     pub fn collect() -> Result<Self, String> {
-        let timestamp = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map(|d| d.as_secs())
-            .unwrap_or(0);
+        let timestamp = unix_now();
+        let (apps_scanned, installed_apps) = cached_installed_apps(false)?;
 
         Ok(Self {
             timestamp,
@@ -156,12 +210,88 @@ impl SystemDossier {
             display: collect_display_info()?,
             windows: collect_windows()?,
             processes: collect_processes()?,
-            installed_apps: collect_installed_apps()?,
+            installed_apps,
             resources: collect_resources()?,
             network: collect_network()?,
+            refreshed: RefreshTimes {
+                windows: timestamp,
+                processes: timestamp,
+                installed_apps: apps_scanned,
+                resources: timestamp,
+                network: timestamp,
+            },
         })
     }
 
+    /// Re-read only the window list
+    #[cfg(target_os = "linux")]
+    pub fn refresh_windows(&mut self) -> Result<(), String> {
+        self.windows = collect_windows()?;
+        self.refreshed.windows = unix_now();
+        Ok(())
+    }
+
+    /// Re-read only the top processes
+    #[cfg(target_os = "linux")]
+    pub fn refresh_processes(&mut self) -> Result<(), String> {
+        self.processes = collect_processes()?;
+        self.refreshed.processes = unix_now();
+        Ok(())
+    }
+
+    /// Re-read only resource stats
+    #[cfg(target_os = "linux")]
+    pub fn refresh_resources(&mut self) -> Result<(), String> {
+        self.resources = collect_resources()?;
+        self.refreshed.resources = unix_now();
+        Ok(())
+    }
+
+    /// Pick up installed apps from the shared cache, rescanning if it is
+    /// older than `INSTALLED_APPS_TTL` or `force` is set
+    #[cfg(target_os = "linux")]
+    pub fn refresh_installed_apps(&mut self, force: bool) -> Result<(), String> {
+        let (scanned, apps) = cached_installed_apps(force)?;
+        self.installed_apps = apps;
+        self.refreshed.installed_apps = scanned;
+        Ok(())
+    }
+
+    /// What changed since `previous`: windows by ID, processes by PID, and
+    /// the focused window
+    pub fn diff(&self, previous: &SystemDossier) -> DossierDiff {
+        let windows_opened = self.windows.iter()
+            .filter(|w| !previous.windows.iter().any(|p| p.id == w.id))
+            .cloned()
+            .collect();
+        let windows_closed = previous.windows.iter()
+            .filter(|p| !self.windows.iter().any(|w| w.id == p.id))
+            .cloned()
+            .collect();
+        let processes_started = self.processes.iter()
+            .filter(|p| !previous.processes.iter().any(|q| q.pid == p.pid))
+            .cloned()
+            .collect();
+        let processes_stopped = previous.processes.iter()
+            .filter(|q| !self.processes.iter().any(|p| p.pid == q.pid))
+            .cloned()
+            .collect();
+
+        let focused = self.focused_window();
+        let focus_changed = match (focused, previous.focused_window()) {
+            (Some(now), Some(before)) if now.id == before.id => None,
+            (now, _) => now.cloned(),
+        };
+
+        DossierDiff {
+            windows_opened,
+            windows_closed,
+            processes_started,
+            processes_stopped,
+            focus_changed,
+        }
+    }
+
     /// Get focused window
     pub fn focused_window(&self) -> Option<&WindowInfo> {
         self.windows.iter().find(|w| w.is_focused)
@@ -462,6 +592,25 @@ fn collect_processes() -> Result<Vec<ProcessInfo>, String> {
     Ok(processes)
 }
 
+/// Installed apps from the shared cache with their scan time, rescanning
+/// when stale or forced
+#[cfg(target_os = "linux")]
+fn cached_installed_apps(force: bool) -> Result<(u64, Vec<InstalledApp>), String> {
+    let mut cache = INSTALLED_APPS.lock().unwrap_or_else(|e| e.into_inner());
+    let now = unix_now();
+
+    match cache.as_ref() {
+        Some((scanned, apps)) if !force && now.saturating_sub(*scanned) < INSTALLED_APPS_TTL.as_secs() => {
+            Ok((*scanned, apps.clone()))
+        }
+        _ => {
+            let apps = collect_installed_apps()?;
+            *cache = Some((now, apps.clone()));
+            Ok((now, apps))
+        }
+    }
+}
+
 #[cfg(target_os = "linux")]
 fn collect_installed_apps() -> Result<Vec<InstalledApp>, String> {
     let mut apps = Vec::new();
@@ -656,4 +805,45 @@ mod tests {
         println!("{}", d.summarize());
         assert!(!d.os.name.is_empty());
     }
+
+    fn window(id: &str, focused: bool) -> WindowInfo {
+        WindowInfo {
+            id: id.into(),
+            title: format!("Window {}", id),
+            is_focused: focused,
+            ..Default::default()
+        }
+    }
+
+    fn process(pid: u32) -> ProcessInfo {
+        ProcessInfo {
+            pid,
+            name: format!("proc{}", pid),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_diff_reports_opened_closed_and_focus() {
+        let before = SystemDossier {
+            windows: vec![window("0x1", true), window("0x2", false)],
+            processes: vec![process(10), process(11)],
+            ..Default::default()
+        };
+        let after = SystemDossier {
+            windows: vec![window("0x2", false), window("0x3", true)],
+            processes: vec![process(11), process(12)],
+            ..Default::default()
+        };
+
+        let diff = after.diff(&before);
+        assert_eq!(diff.windows_opened.len(), 1);
+        assert_eq!(diff.windows_opened[0].id, "0x3");
+        assert_eq!(diff.windows_closed[0].id, "0x1");
+        assert_eq!(diff.processes_started[0].pid, 12);
+        assert_eq!(diff.processes_stopped[0].pid, 10);
+        assert_eq!(diff.focus_changed.unwrap().id, "0x3");
+
+        assert!(after.diff(&after).is_empty());
+    }
 }
@@ -7,21 +7,24 @@
 //!
 //! Features:
 //! - Duration-based: `--flux "1 hour"` or `--flux "30m"`
-//! - Target time: `--until "11:11"` or `--until "23:30"`
+//! - Target time: `--until "11:11"`, `--until "tomorrow 9am"`, `--until "14:00 UTC"`
 //! - Auto-extend: `--flux auto` (runs until manually stopped)
 //! - Extend mid-run: Press 'e' to add more time
 //! - FluxCanvas: Persistent workspace for accumulating work across iterations
+//! - SessionJournal: JSONL log of every iteration, replayed by `--resume`
 
-use chrono::{Duration, Local, NaiveTime, Timelike};
+use chrono::{DateTime, Duration, FixedOffset, Local, NaiveDate, NaiveTime, TimeZone};
 use console::style;
 use rusqlite::{Connection, params};
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::io::{self, Read};
+use std::io::{self, BufRead, IsTerminal, Read, Write};
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
+use crate::sentinel::Sentinel;
 
 /// Generate output filename from task description
 fn generate_output_filename(task: &str) -> String {
@@ -123,7 +126,7 @@ pub fn parse_duration(input: &str) -> Option<Duration> {
     }
 }
 
-/// Parse a target time like "11:11", "23:30", "11:11 PM"
+/// Parse a target time like "11:11", "23:30", "11:11 PM", "9am"
 pub fn parse_target_time(input: &str) -> Option<NaiveTime> {
     let input = input.trim().to_uppercase();
 
@@ -135,45 +138,130 @@ pub fn parse_target_time(input: &str) -> Option<NaiveTime> {
         .replace("AM", "")
         .replace(" ", "");
 
-    // Parse HH:MM or H:MM
+    // Parse HH:MM or H:MM ("9am" needs the AM/PM to count as a time)
     let parts: Vec<&str> = time_part.split(':').collect();
-    if parts.len() >= 2 {
-        if let (Ok(mut hours), Ok(mins)) = (parts[0].parse::<u32>(), parts[1].parse::<u32>()) {
-            // Convert 12-hour to 24-hour
-            if is_pm && hours < 12 {
-                hours += 12;
-            } else if is_am && hours == 12 {
-                hours = 0;
-            }
-
-            return NaiveTime::from_hms_opt(hours, mins, 0);
+    let mins = match parts.len() {
+        1 if is_am || is_pm => Some(0),
+        n if n >= 2 => parts[1].parse::<u32>().ok(),
+        _ => None,
+    };
+    if let (Ok(mut hours), Some(mins)) = (parts[0].parse::<u32>(), mins) {
+        // Convert 12-hour to 24-hour
+        if is_pm && hours < 12 {
+            hours += 12;
+        } else if is_am && hours == 12 {
+            hours = 0;
         }
+
+        return NaiveTime::from_hms_opt(hours, mins, 0);
     }
 
     None
 }
 
-/// Calculate duration until a target time (handles next-day wrap)
+/// Common timezone abbreviations and their UTC offsets in minutes
+const TIMEZONES: [(&str, i32); 16] = [
+    ("utc", 0), ("gmt", 0), ("z", 0),
+    ("est", -300), ("edt", -240), ("cst", -360), ("cdt", -300),
+    ("mst", -420), ("mdt", -360), ("pst", -480), ("pdt", -420),
+    ("bst", 60), ("cet", 60), ("cest", 120), ("ist", 330), ("jst", 540),
+];
+
+/// Parse a timezone like "UTC", "PST", "+02:00", "-0500" or "UTC+2"
+fn parse_offset(tz: &str) -> Option<FixedOffset> {
+    let tz = tz.trim().to_lowercase();
+    if let Some(&(_, mins)) = TIMEZONES.iter().find(|(name, _)| *name == tz) {
+        return FixedOffset::east_opt(mins * 60);
+    }
+
+    let tz = tz.strip_prefix("utc").or_else(|| tz.strip_prefix("gmt")).unwrap_or(&tz);
+    let (sign, digits) = match tz.chars().next()? {
+        '+' => (1, &tz[1..]),
+        '-' => (-1, &tz[1..]),
+        _ => return None,
+    };
+    let (hours, mins) = match digits.split_once(':') {
+        Some(parts) => parts,
+        None if digits.len() > 2 => digits.split_at(digits.len() - 2),
+        None => (digits, "0"),
+    };
+    let mins = hours.parse::<i32>().ok()? * 60 + mins.parse::<i32>().ok()?;
+    FixedOffset::east_opt(sign * mins * 60)
+}
+
+/// Split a trailing timezone off a time: "14:00 UTC", "9am PST", "14:00+02:00"
+fn split_timezone(input: &str) -> (&str, Option<FixedOffset>) {
+    if let Some((time, tz)) = input.rsplit_once(' ') {
+        if let Some(offset) = parse_offset(tz) {
+            return (time.trim(), Some(offset));
+        }
+    }
+    if let Some(pos) = input.find(['+', '-']) {
+        if let Some(offset) = parse_offset(&input[pos..]) {
+            return (input[..pos].trim(), Some(offset));
+        }
+    }
+    (input, None)
+}
+
+/// Resolve a target like "11:11", "tomorrow 9am", "in 90 minutes" or
+/// "14:00 UTC" against `now`. The result is always after `now`: a time of
+/// day that has already passed means tomorrow, and "today" with a past time
+/// is rejected rather than rolled over.
+pub fn parse_target_datetime(input: &str, now: DateTime<FixedOffset>) -> Option<DateTime<FixedOffset>> {
+    let input = input.trim().to_lowercase();
+
+    if let Some(rest) = input.strip_prefix("in ") {
+        return parse_duration(rest).map(|d| now + d);
+    }
+
+    let (day, rest) = if let Some(rest) = input.strip_prefix("tomorrow") {
+        (Some(1), rest)
+    } else if let Some(rest) = input.strip_prefix("today") {
+        (Some(0), rest)
+    } else {
+        (None, input.as_str())
+    };
+
+    let (time, offset) = split_timezone(rest.trim());
+    let offset = offset.unwrap_or(*now.offset());
+    let time = parse_target_time(time)?;
+    let today = now.with_timezone(&offset).date_naive();
+    let at = |date: NaiveDate| offset.from_local_datetime(&date.and_time(time)).single();
+
+    match day {
+        Some(days) => at(today + Duration::days(days)).filter(|t| *t > now),
+        None => at(today)
+            .filter(|t| *t > now)
+            .or_else(|| at(today + Duration::days(1))),
+    }
+}
+
+/// Duration until an `--until` target (see `parse_target_datetime`)
+pub fn parse_until(input: &str) -> Option<Duration> {
+    let now = Local::now().fixed_offset();
+    parse_target_datetime(input, now).map(|target| target - now)
+}
+
+/// Calculate duration until a target time (handles next-day wrap). A target
+/// equal to the current time means tomorrow, never zero.
 pub fn duration_until(target: NaiveTime) -> Duration {
-    let now = Local::now().time();
+    time_until(target, Local::now().time())
+}
 
-    if target > now {
-        // Target is later today
-        let hours_diff = target.hour() as i64 - now.hour() as i64;
-        let mins_diff = target.minute() as i64 - now.minute() as i64;
-        Duration::minutes(hours_diff * 60 + mins_diff)
+fn time_until(target: NaiveTime, now: NaiveTime) -> Duration {
+    let diff = target - now;
+    if diff > Duration::zero() {
+        diff
     } else {
-        // Target is tomorrow
-        let until_midnight = Duration::hours(24) - Duration::hours(now.hour() as i64)
-            - Duration::minutes(now.minute() as i64);
-        let after_midnight = Duration::hours(target.hour() as i64)
-            + Duration::minutes(target.minute() as i64);
-        until_midnight + after_midnight
+        diff + Duration::days(1)
     }
 }
 
 /// Flux Capacitor configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FluxConfig {
+    #[serde(with = "duration_secs")]
     pub duration: Duration,
     pub task: String,
     pub auto_extend: bool,
@@ -181,9 +269,150 @@ pub struct FluxConfig {
     pub model: String,
     pub auto_approve: bool,
     pub verbose: bool,
-    pub temperature: f32,
+    /// Sampling temperature; `None` means `DEFAULT_TEMPERATURE`
+    pub temperature: Option<f32>,
     pub seed: Option<i64>,
+    #[serde(default)]
     pub resume: Option<String>,
+    /// When `auto_extend` keeps extending and when it stops
+    #[serde(default)]
+    pub auto_policy: AutoExtendPolicy,
+}
+
+/// Temperature used when neither the command line nor a resumed journal sets one
+pub const DEFAULT_TEMPERATURE: f32 = 0.7;
+
+impl FluxConfig {
+    /// Take over the task and sampling settings of a journaled run, keeping
+    /// whatever was given on the command line
+    pub fn inherit(&mut self, saved: &FluxConfig) {
+        if self.task.is_empty() {
+            self.task = saved.task.clone();
+        }
+        self.temperature = self.temperature.or(saved.temperature);
+        self.seed = self.seed.or(saved.seed);
+    }
+}
+
+/// Auto-extend policy for `--flux auto`
+///
+/// The run starts with one `slice` and is extended by another slice each
+/// time it runs out, but only if the last slice produced something (new
+/// canvas items or files). It winds down early after `stall_after`
+/// unproductive iterations in a row, and never runs past `ceiling`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AutoExtendPolicy {
+    /// Initial window and size of each extension
+    #[serde(with = "duration_secs")]
+    pub slice: Duration,
+    /// Absolute wall-clock limit, whatever the progress
+    #[serde(with = "duration_secs")]
+    pub ceiling: Duration,
+    /// Unproductive iterations in a row before winding down
+    pub stall_after: usize,
+}
+
+impl Default for AutoExtendPolicy {
+    fn default() -> Self {
+        Self {
+            slice: Duration::minutes(15),
+            ceiling: Duration::hours(8),
+            stall_after: 5,
+        }
+    }
+}
+
+/// An auto-extend decision, journaled for auditing
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExtendDecision {
+    Extend {
+        #[serde(with = "duration_secs")]
+        by: Duration,
+        reason: String,
+    },
+    Stop {
+        reason: String,
+    },
+}
+
+/// Tracks progress for an `AutoExtendPolicy` across iterations
+#[derive(Debug, Clone)]
+pub struct AutoExtender {
+    pub policy: AutoExtendPolicy,
+    /// Unproductive iterations in a row
+    idle: usize,
+    /// Productive iterations since the last extension
+    productive: usize,
+}
+
+impl AutoExtender {
+    pub fn new(policy: AutoExtendPolicy) -> Self {
+        Self { policy, idle: 0, productive: 0 }
+    }
+
+    /// Record whether an iteration produced anything
+    pub fn record(&mut self, productive: bool) {
+        if productive {
+            self.idle = 0;
+            self.productive += 1;
+        } else {
+            self.idle += 1;
+        }
+    }
+
+    /// Decide what to do after an iteration, given how long the run has
+    /// been going and whether its current deadline has passed. `None` means
+    /// keep going as planned.
+    pub fn decide(&mut self, elapsed: Duration, time_up: bool) -> Option<ExtendDecision> {
+        if elapsed >= self.policy.ceiling {
+            return Some(ExtendDecision::Stop {
+                reason: format!("reached the {} ceiling", format_duration(self.policy.ceiling)),
+            });
+        }
+        if self.idle >= self.policy.stall_after {
+            return Some(ExtendDecision::Stop {
+                reason: format!("no progress in the last {} iterations", self.idle),
+            });
+        }
+        if !time_up {
+            return None;
+        }
+        if self.productive == 0 {
+            return Some(ExtendDecision::Stop {
+                reason: "no progress since the last extension".into(),
+            });
+        }
+
+        let by = self.policy.slice.min(self.policy.ceiling - elapsed);
+        let reason = format!("{} productive iterations since the last extension", self.productive);
+        self.productive = 0;
+        Some(ExtendDecision::Extend { by, reason })
+    }
+}
+
+/// Format a duration as "8h", "15m" or "1h30m"
+fn format_duration(duration: Duration) -> String {
+    let (hours, mins) = (duration.num_hours(), duration.num_minutes() % 60);
+    match (hours, mins) {
+        (0, m) => format!("{}m", m),
+        (h, 0) => format!("{}h", h),
+        (h, m) => format!("{}h{}m", h, m),
+    }
+}
+
+/// Serialize a chrono `Duration` as whole seconds
+mod duration_secs {
+    use chrono::Duration;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i64(duration.num_seconds())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        i64::deserialize(deserializer).map(Duration::seconds)
+    }
 }
 
 /// Flux Capacitor status
@@ -217,6 +446,11 @@ impl FluxStatus {
         }
     }
 
+    /// Wall-clock time since this run started
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_std(self.start_time.elapsed()).unwrap_or_else(|_| Duration::zero())
+    }
+
     pub fn is_time_up(&self) -> bool {
         Local::now() >= self.end_time
     }
@@ -852,6 +1086,171 @@ impl FluxCanvas {
     }
 }
 
+// ═══════════════════════════════════════════════════════════════════════════════
+// SESSION JOURNAL - Append-only record of every iteration
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// Characters of tool output kept per journaled action
+const JOURNAL_OUTPUT_CHARS: usize = 500;
+
+/// One tool call made during an iteration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JournalAction {
+    pub tool: String,
+    pub success: bool,
+    /// Tool output, truncated
+    pub output: String,
+}
+
+/// What happened in one flux iteration
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IterationRecord {
+    pub iteration: usize,
+    pub timestamp: chrono::DateTime<Local>,
+    /// Full prompt sent to the agent
+    pub prompt: String,
+    pub success: bool,
+    /// Agent's final response (empty on error)
+    pub response: String,
+    pub actions: Vec<JournalAction>,
+    pub items_added: usize,
+    pub files_added: usize,
+    pub tokens_used: usize,
+    pub error: Option<String>,
+}
+
+/// A line in the journal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum JournalRecord {
+    /// First line: the configuration the session started with
+    Start {
+        session_id: String,
+        started_at: chrono::DateTime<Local>,
+        config: FluxConfig,
+    },
+    Iteration(IterationRecord),
+    /// An auto-extend decision and why it was made
+    AutoExtend {
+        iteration: usize,
+        timestamp: chrono::DateTime<Local>,
+        decision: ExtendDecision,
+    },
+}
+
+/// Append-only JSONL journal of a flux session, flushed after every
+/// iteration so a crash loses at most the iteration in progress
+pub struct SessionJournal {
+    pub path: PathBuf,
+    file: fs::File,
+}
+
+impl SessionJournal {
+    /// Journal location for a session, next to its canvas database
+    pub fn path_for(session_id: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("{}.jsonl", session_id))
+    }
+
+    /// Open the session's journal for appending, starting it with `config`
+    /// if it does not exist yet
+    pub fn open(session_id: &str, config: &FluxConfig) -> io::Result<Self> {
+        let path = Self::path_for(session_id);
+        let is_new = !path.exists();
+        let file = fs::OpenOptions::new().create(true).append(true).open(&path)?;
+        let mut journal = Self { path, file };
+
+        if is_new {
+            journal.write(&JournalRecord::Start {
+                session_id: session_id.to_string(),
+                started_at: Local::now(),
+                config: config.clone(),
+            })?;
+        }
+        Ok(journal)
+    }
+
+    /// Record a finished iteration
+    pub fn append(&mut self, record: IterationRecord) -> io::Result<()> {
+        self.write(&JournalRecord::Iteration(record))
+    }
+
+    /// Record an auto-extend decision
+    pub fn append_decision(&mut self, iteration: usize, decision: ExtendDecision) -> io::Result<()> {
+        self.write(&JournalRecord::AutoExtend {
+            iteration,
+            timestamp: Local::now(),
+            decision,
+        })
+    }
+
+    fn write(&mut self, record: &JournalRecord) -> io::Result<()> {
+        let line = serde_json::to_string(record).map_err(io::Error::other)?;
+        writeln!(self.file, "{}", line)?;
+        self.file.sync_data()
+    }
+
+    /// Read every record of a session's journal. A truncated last line (a
+    /// crash mid-write) is skipped.
+    pub fn read(session_id: &str) -> io::Result<Vec<JournalRecord>> {
+        let file = fs::File::open(Self::path_for(session_id))?;
+        Ok(io::BufReader::new(file)
+            .lines()
+            .map_while(Result::ok)
+            .filter_map(|line| serde_json::from_str(&line).ok())
+            .collect())
+    }
+}
+
+/// Session state rebuilt from a journal
+#[derive(Debug, Clone)]
+pub struct ResumedSession {
+    pub session_id: String,
+    /// Configuration the session was started with
+    pub config: FluxConfig,
+    pub iterations: usize,
+    pub successes: usize,
+    pub failures: usize,
+    pub tokens_used: usize,
+    /// Prompt/response pairs of successful iterations, oldest first, for
+    /// restoring the agent's conversation
+    pub exchanges: Vec<(String, String)>,
+}
+
+/// Rebuild a session's configuration, counters and conversation from its
+/// journal
+pub fn resume_from_journal(session_id: &str) -> Result<ResumedSession, String> {
+    let records = SessionJournal::read(session_id)
+        .map_err(|e| format!("Cannot read journal for '{}': {}", session_id, e))?;
+
+    let mut records = records.into_iter();
+    let Some(JournalRecord::Start { session_id, config, .. }) = records.next() else {
+        return Err(format!("Journal for '{}' has no start record", session_id));
+    };
+
+    let mut resumed = ResumedSession {
+        session_id,
+        config,
+        iterations: 0,
+        successes: 0,
+        failures: 0,
+        tokens_used: 0,
+        exchanges: Vec::new(),
+    };
+    for record in records {
+        if let JournalRecord::Iteration(it) = record {
+            resumed.iterations = resumed.iterations.max(it.iteration);
+            resumed.tokens_used += it.tokens_used;
+            if it.success {
+                resumed.successes += 1;
+                resumed.exchanges.push((it.prompt, it.response));
+            } else {
+                resumed.failures += 1;
+            }
+        }
+    }
+    Ok(resumed)
+}
+
 /// Print the Flux Capacitor banner
 pub fn print_flux_banner(end_time: &chrono::DateTime<Local>, task: &str) {
     println!();
@@ -1040,10 +1439,18 @@ fn parse_file_blocks(response: &str) -> HashMap<String, String> {
 }
 
 /// Run the Flux Capacitor
-pub async fn run_flux_capacitor(config: FluxConfig) -> Result<FluxStatus, String> {
+pub async fn run_flux_capacitor(
+    mut config: FluxConfig,
+    sentinel: Option<Arc<Sentinel>>,
+) -> Result<FluxStatus, String> {
     use crate::agent_wiggum::{AgentConfig, WiggumAgent};
 
-    let mut status = FluxStatus::new(config.duration);
+    // Auto mode starts with one slice and extends while progress is made
+    let mut extender = config.auto_extend.then(|| AutoExtender::new(config.auto_policy.clone()));
+    let mut status = match extender {
+        Some(ref ext) => FluxStatus::new(ext.policy.slice.min(config.duration)),
+        None => FluxStatus::new(config.duration),
+    };
 
     // Load existing canvas if resuming, otherwise create new
     let mut canvas = if let Some(ref session) = config.resume {
@@ -1069,7 +1476,45 @@ pub async fn run_flux_capacitor(config: FluxConfig) -> Result<FluxStatus, String
         FluxCanvas::new(&config.task)
     };
 
+    // Replay the journal of a resumed session. Duration, provider and model
+    // come from the command line; the task and sampling settings carry over
+    // unless given there too.
+    let resumed = match config.resume {
+        Some(_) => match resume_from_journal(&canvas.session_id) {
+            Ok(resumed) => {
+                config.inherit(&resumed.config);
+                status.iterations = resumed.iterations;
+                status.successes = resumed.successes;
+                status.failures = resumed.failures;
+                println!("{} Replayed journal: {} iterations, {} tokens",
+                    style("♻️").green(),
+                    resumed.iterations,
+                    resumed.tokens_used
+                );
+                Some(resumed)
+            }
+            Err(e) => {
+                println!("{} {}", style("⚠").yellow(), e);
+                None
+            }
+        },
+        None => None,
+    };
+    if config.task.is_empty() {
+        return Err("No task given and no journal to resume it from".into());
+    }
+
+    let mut journal = SessionJournal::open(&canvas.session_id, &config)
+        .map_err(|e| format!("Failed to open session journal: {}", e))?;
+
     print_flux_banner(&status.end_time, &config.task);
+    if let Some(ref ext) = extender {
+        println!("{}  Auto-extend: {} slices, {} ceiling",
+            style("║").cyan(),
+            format_duration(ext.policy.slice),
+            format_duration(ext.policy.ceiling)
+        );
+    }
 
     // Show canvas info if we detected a target
     if let Some(target) = canvas.target_count {
@@ -1098,19 +1543,29 @@ pub async fn run_flux_capacitor(config: FluxConfig) -> Result<FluxStatus, String
         auto_approve: config.auto_approve,
         verify_actions: true,
         verbose: config.verbose,
-        temperature: config.temperature,
+        temperature: config.temperature.unwrap_or(DEFAULT_TEMPERATURE),
         seed: config.seed,
+        sentinel: sentinel.clone(),
         ..Default::default()
     };
 
     let mut agent = WiggumAgent::new(agent_config);
+    if let Some(ref resumed) = resumed {
+        agent.restore_conversation(&resumed.exchanges);
+    }
 
     // Main flux loop
     while running.load(Ordering::SeqCst) && !status.is_time_up() && !canvas.target_reached() {
         status.iterations += 1;
 
         // Check for user input (non-blocking)
-        if let Some(extension) = check_for_extend_request() {
+        if let Some(mut extension) = check_for_extend_request() {
+            // Manual extensions also respect the auto-extend ceiling
+            if let Some(ref ext) = extender {
+                let limit = ext.policy.ceiling - status.elapsed();
+                let remaining = status.remaining();
+                extension = ((remaining + extension).min(limit) - remaining).max(Duration::zero());
+            }
             status.extend(extension);
             println!(
                 "{} Extended! New end time: {}",
@@ -1138,8 +1593,29 @@ pub async fn run_flux_capacitor(config: FluxConfig) -> Result<FluxStatus, String
         };
 
         // Run the task
+        let mut record = IterationRecord {
+            iteration: status.iterations,
+            timestamp: Local::now(),
+            prompt: contextual_task.clone(),
+            success: false,
+            response: String::new(),
+            actions: Vec::new(),
+            items_added: 0,
+            files_added: 0,
+            tokens_used: 0,
+            error: None,
+        };
+
         match agent.run_task(&contextual_task).await {
             Ok(result) => {
+                record.success = true;
+                record.response = result.final_response.clone();
+                record.tokens_used = result.tokens_used;
+                record.actions = result.actions.iter().map(|a| JournalAction {
+                    tool: a.tool_name.clone(),
+                    success: a.success,
+                    output: a.output.chars().take(JOURNAL_OUTPUT_CHARS).collect(),
+                }).collect();
                 status.successes += 1;
 
                 // Parse response for new items (lines starting with "ITEM:")
@@ -1165,6 +1641,7 @@ pub async fn run_flux_capacitor(config: FluxConfig) -> Result<FluxStatus, String
 
                 // Parse response for new files (FILE: path followed by code block)
                 let new_files = parse_file_blocks(&result.final_response);
+                record.files_added = new_files.len();
                 if !new_files.is_empty() {
                     let file_count = new_files.len();
                     canvas.add_files(new_files);
@@ -1173,6 +1650,7 @@ pub async fn run_flux_capacitor(config: FluxConfig) -> Result<FluxStatus, String
 
                 // Print status with canvas progress
                 let items_added = new_items.len();
+                record.items_added = items_added;
                 print_iteration_status_with_canvas(&status, &canvas, Some(true), items_added);
 
                 if config.verbose && new_items.is_empty() {
@@ -1181,6 +1659,7 @@ pub async fn run_flux_capacitor(config: FluxConfig) -> Result<FluxStatus, String
             }
             Err(e) => {
                 status.failures += 1;
+                record.error = Some(e.to_string());
                 print_iteration_status_with_canvas(&status, &canvas, Some(false), 0);
 
                 if config.verbose {
@@ -1189,6 +1668,47 @@ pub async fn run_flux_capacitor(config: FluxConfig) -> Result<FluxStatus, String
             }
         }
 
+        // Risky actions are parked rather than run; say so as they pile up
+        if let Some(ref sentinel) = sentinel {
+            let held = record.actions.iter().filter(|a| a.output.starts_with("Held in quarantine")).count();
+            if held > 0 {
+                println!("{} {} action(s) held in quarantine ({} waiting); you'll be asked about them when the run ends",
+                    style("⏸").yellow(),
+                    held,
+                    sentinel.pending().len()
+                );
+            }
+        }
+
+        let productive = record.items_added + record.files_added > 0;
+        if let Err(e) = journal.append(record) {
+            println!("{} Failed to write journal: {}", style("⚠").yellow(), e);
+        }
+
+        if let Some(ref mut ext) = extender {
+            ext.record(productive);
+            if let Some(decision) = ext.decide(status.elapsed(), status.is_time_up()) {
+                match decision {
+                    ExtendDecision::Extend { by, ref reason } => {
+                        status.extend(by);
+                        println!("{} Auto-extended by {} ({}). New end time: {}",
+                            style("⏰").green(),
+                            format_duration(by),
+                            reason,
+                            style(status.end_time.format("%H:%M:%S")).yellow().bold()
+                        );
+                    }
+                    ExtendDecision::Stop { ref reason } => {
+                        println!("{} Winding down: {}", style("⏹").yellow(), reason);
+                        running.store(false, Ordering::SeqCst);
+                    }
+                }
+                if let Err(e) = journal.append_decision(status.iterations, decision) {
+                    println!("{} Failed to write journal: {}", style("⚠").yellow(), e);
+                }
+            }
+        }
+
         // Small delay between iterations
         tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
     }
@@ -1196,6 +1716,10 @@ pub async fn run_flux_capacitor(config: FluxConfig) -> Result<FluxStatus, String
     // Export canvas contents
     println!();
 
+    if let Some(ref sentinel) = sentinel {
+        review_quarantine(&mut agent, sentinel).await;
+    }
+
     // Export items if we accumulated any
     let final_item_count = canvas.item_count();
     if final_item_count > 0 {
@@ -1251,6 +1775,45 @@ pub async fn run_flux_capacitor(config: FluxConfig) -> Result<FluxStatus, String
     Ok(status)
 }
 
+/// Ask about each action the Sentinel held during the run, then run the
+/// released ones. Without a terminal to ask on, they are left pending.
+async fn review_quarantine(agent: &mut crate::agent_wiggum::WiggumAgent, sentinel: &Sentinel) {
+    let pending = sentinel.pending();
+    if pending.is_empty() {
+        return;
+    }
+
+    println!("{} {} action(s) held in quarantine during the run",
+        style("⏸").yellow(),
+        pending.len()
+    );
+    if !io::stdin().is_terminal() {
+        println!("{} Left pending: no terminal to ask for release", style("⚠").yellow());
+        return;
+    }
+
+    for held in pending {
+        println!("  {}  {}", style(&held.id).cyan().bold(), held.action.content);
+        println!("       {} {}", style("held:").dim(), held.analysis.reason);
+        print!("       Release it? [y/N]: ");
+        let _ = io::stdout().flush();
+
+        let mut answer = String::new();
+        let release = io::stdin().lock().read_line(&mut answer).is_ok()
+            && matches!(answer.trim().to_lowercase().as_str(), "y" | "yes");
+        if release {
+            sentinel.release(&held.id);
+        } else {
+            sentinel.reject(&held.id);
+        }
+    }
+
+    for result in agent.run_released().await {
+        let mark = if result.success { style("✓").green() } else { style("✗").red() };
+        println!("{} {} {}", mark, style(&result.tool_name).bold(), style(result.output.lines().next().unwrap_or("")).dim());
+    }
+}
+
 /// Check for extension request (non-blocking stdin check)
 fn check_for_extend_request() -> Option<Duration> {
     // This is a simplified check - in practice we'd use terminal raw mode
@@ -1429,5 +1992,163 @@ mod tests {
         assert_eq!(parse_target_time("23:30"), NaiveTime::from_hms_opt(23, 30, 0));
         assert_eq!(parse_target_time("11:11 PM"), NaiveTime::from_hms_opt(23, 11, 0));
         assert_eq!(parse_target_time("11:11 AM"), NaiveTime::from_hms_opt(11, 11, 0));
+        assert_eq!(parse_target_time("9am"), NaiveTime::from_hms_opt(9, 0, 0));
+        assert_eq!(parse_target_time("12 PM"), NaiveTime::from_hms_opt(12, 0, 0));
+        assert_eq!(parse_target_time("9"), None);
+    }
+
+    fn at(rfc3339: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(rfc3339).unwrap()
+    }
+
+    #[test]
+    fn test_past_time_rolls_over_to_tomorrow() {
+        let now = at("2024-03-10T11:30:00+01:00");
+        assert_eq!(parse_target_datetime("11:11", now), Some(at("2024-03-11T11:11:00+01:00")));
+        assert_eq!(parse_target_datetime("11:45", now), Some(at("2024-03-10T11:45:00+01:00")));
+        assert_eq!(parse_target_datetime("tomorrow 9am", now), Some(at("2024-03-11T09:00:00+01:00")));
+        assert_eq!(parse_target_datetime("today 9am", now), None);
+        assert_eq!(parse_target_datetime("in 90 minutes", now), Some(at("2024-03-10T13:00:00+01:00")));
+
+        let eleven = NaiveTime::from_hms_opt(11, 11, 0).unwrap();
+        assert_eq!(time_until(eleven, NaiveTime::from_hms_opt(11, 30, 0).unwrap()), Duration::minutes(23 * 60 + 41));
+        assert_eq!(time_until(eleven, eleven), Duration::days(1));
+    }
+
+    #[test]
+    fn test_timezone_qualified_times() {
+        let now = at("2024-03-10T11:30:00+01:00");
+        // 14:00 UTC is 15:00 here, still today
+        assert_eq!(parse_target_datetime("14:00 UTC", now), Some(at("2024-03-10T14:00:00Z")));
+        assert_eq!(parse_target_datetime("14:00+02:00", now), Some(at("2024-03-10T14:00:00+02:00")));
+        assert_eq!(parse_target_datetime("9am PST", now), Some(at("2024-03-10T09:00:00-08:00")));
+        assert_eq!(parse_target_datetime("5:30 am ist", now), Some(at("2024-03-11T05:30:00+05:30")));
+        assert_eq!(parse_target_datetime("tomorrow 8:00 -0500", now), Some(at("2024-03-11T08:00:00-05:00")));
+        assert_eq!(parse_target_datetime("9am mars", now), None);
+    }
+
+    #[test]
+    fn test_auto_extend_needs_progress_and_respects_ceiling() {
+        let policy = AutoExtendPolicy {
+            slice: Duration::minutes(15),
+            ceiling: Duration::minutes(40),
+            stall_after: 3,
+        };
+        let mut ext = AutoExtender::new(policy.clone());
+
+        ext.record(true);
+        assert_eq!(ext.decide(Duration::minutes(5), false), None);
+        assert!(matches!(
+            ext.decide(Duration::minutes(15), true),
+            Some(ExtendDecision::Extend { by, .. }) if by == Duration::minutes(15)
+        ));
+        // The last extension is cut short by the ceiling
+        ext.record(true);
+        assert!(matches!(
+            ext.decide(Duration::minutes(30), true),
+            Some(ExtendDecision::Extend { by, .. }) if by == Duration::minutes(10)
+        ));
+        ext.record(true);
+        assert!(matches!(ext.decide(Duration::minutes(40), true), Some(ExtendDecision::Stop { .. })));
+
+        // A slice without progress is not extended
+        let mut ext = AutoExtender::new(policy.clone());
+        ext.record(false);
+        assert!(matches!(ext.decide(Duration::minutes(15), true), Some(ExtendDecision::Stop { .. })));
+
+        // A stall winds down before the deadline
+        let mut ext = AutoExtender::new(policy);
+        ext.record(true);
+        for _ in 0..3 {
+            ext.record(false);
+        }
+        assert_eq!(
+            ext.decide(Duration::minutes(5), false),
+            Some(ExtendDecision::Stop { reason: "no progress in the last 3 iterations".into() })
+        );
+    }
+
+    #[test]
+    fn test_journal_resume_round_trip() {
+        let session_id = format!("flux_test_journal_{}", std::process::id());
+        let config = FluxConfig {
+            duration: Duration::minutes(30),
+            task: "list 10 facts".into(),
+            auto_extend: false,
+            provider_url: "http://localhost:1234".into(),
+            model: "local".into(),
+            auto_approve: true,
+            verbose: false,
+            temperature: Some(0.9),
+            seed: Some(7),
+            resume: None,
+            auto_policy: AutoExtendPolicy::default(),
+        };
+        let iteration = |n: usize, ok: bool| IterationRecord {
+            iteration: n,
+            timestamp: Local::now(),
+            prompt: format!("prompt {}", n),
+            success: ok,
+            response: if ok { format!("ITEM: fact {}", n) } else { String::new() },
+            actions: vec![],
+            items_added: ok as usize,
+            files_added: 0,
+            tokens_used: 100,
+            error: (!ok).then(|| "LLM error".into()),
+        };
+
+        let mut journal = SessionJournal::open(&session_id, &config).unwrap();
+        journal.append(iteration(1, true)).unwrap();
+        journal.append(iteration(2, false)).unwrap();
+        drop(journal);
+
+        // Reopening appends without a second start record
+        let mut journal = SessionJournal::open(&session_id, &config).unwrap();
+        journal.append(iteration(3, true)).unwrap();
+        // A crash mid-write leaves a partial line
+        write!(journal.file, "{{\"type\":\"iter").unwrap();
+
+        let resumed = resume_from_journal(&session_id).unwrap();
+        fs::remove_file(&journal.path).unwrap();
+
+        assert_eq!(resumed.config.task, "list 10 facts");
+        assert_eq!(resumed.config.duration, Duration::minutes(30));
+        assert_eq!(resumed.config.seed, Some(7));
+        assert_eq!((resumed.iterations, resumed.successes, resumed.failures), (3, 2, 1));
+        assert_eq!(resumed.tokens_used, 300);
+        assert_eq!(
+            resumed.exchanges,
+            vec![
+                ("prompt 1".to_string(), "ITEM: fact 1".to_string()),
+                ("prompt 3".to_string(), "ITEM: fact 3".to_string()),
+            ]
+        );
+        assert!(resume_from_journal("flux_no_such_session").is_err());
+    }
+
+    #[test]
+    fn test_resume_keeps_command_line_settings() {
+        let config = |task: &str, temperature: Option<f32>, seed: Option<i64>| FluxConfig {
+            duration: Duration::minutes(30),
+            task: task.into(),
+            auto_extend: false,
+            provider_url: "http://localhost:1234".into(),
+            model: "local".into(),
+            auto_approve: true,
+            verbose: false,
+            temperature,
+            seed,
+            resume: None,
+            auto_policy: AutoExtendPolicy::default(),
+        };
+        let saved = config("list 10 facts", Some(0.9), Some(7));
+
+        let mut cli = config("", None, None);
+        cli.inherit(&saved);
+        assert_eq!((cli.task.as_str(), cli.temperature, cli.seed), ("list 10 facts", Some(0.9), Some(7)));
+
+        let mut cli = config("list 5 facts", Some(0.2), Some(1));
+        cli.inherit(&saved);
+        assert_eq!((cli.task.as_str(), cli.temperature, cli.seed), ("list 5 facts", Some(0.2), Some(1)));
     }
 }
@@ -37,7 +37,7 @@ pub mod zones;
 #[cfg(feature = "computer-use")]
 pub mod dossier;
 
-// Temporal memory - SpacetimeDB-backed activity log
+// Temporal memory - activity log with SQLite/SpacetimeDB backends
 #[cfg(feature = "computer-use")]
 pub mod memory;
 
@@ -82,7 +82,7 @@ pub use input::{InputController, GuiAutomation};
 pub use voice::{VoiceController, VoiceStream};
 
 // Sentinel is always available
-pub use sentinel::{Sentinel, SentinelAnalysis, Verdict, ThreatCategory, Severity};
+pub use sentinel::{QuarantinedAction, Sentinel, SentinelAnalysis, SentinelRule, Verdict, ThreatCategory, Severity};
 
 // Reactive Agent
 #[cfg(feature = "computer-use")]
@@ -91,15 +91,18 @@ pub use sentinel::{Sentinel, SentinelAnalysis, Verdict, ThreatCategory, Severity
 
 // Zone filtering
 #[cfg(feature = "computer-use")]
-pub use zones::{Zone, ZoneManager, ZoneType, detect_motion, hash_region};
+pub use zones::{Zone, ZoneEvent, ZoneManager, ZoneType, ScreenFrame, detect_motion, hash_region};
 
 // System dossier
 #[cfg(feature = "computer-use")]
-pub use dossier::{SystemDossier, WindowInfo, ProcessInfo, InstalledApp};
+pub use dossier::{SystemDossier, DossierDiff, RefreshTimes, WindowInfo, ProcessInfo, InstalledApp};
 
 // Temporal memory
 #[cfg(feature = "computer-use")]
-pub use memory::{TemporalMemory, ScreenSnapshot, ActionRecord, GoalProgress};
+pub use memory::{
+    TemporalMemory, ScreenSnapshot, ActionRecord, GoalProgress,
+    TemporalMemoryBackend, MemoryBackendConfig, SqliteBackend, StallReason,
+};
 
 // Activity overlay
 #[cfg(feature = "computer-use")]
@@ -107,10 +110,10 @@ pub use overlay::{ActivityOverlay, OverlayPosition, OverlayState};
 
 // Documentation loader
 #[cfg(feature = "computer-use")]
-pub use docs::{DocsLoader, DocsProvider, DocSnippet, Context7Provider, LocalDocsProvider};
+pub use docs::{DocsLoader, DocsProvider, DocSnippet, DocsOrigin, LibraryDocs, Context7Provider, LocalDocsProvider};
 
 // Smell Test - always available
-pub use smell::{Trunk, SmellTest, SmellWarning, SmellCategory, quick_smell};
+pub use smell::{Trunk, SmellTest, SmellReport, CategorySmell, SmellWarning, SmellCategory, quick_smell};
 
 // Web Search - always available
 pub use websearch::{SearchResult, SearchResponse, search as web_search, format_results as format_search_results};
@@ -118,10 +121,10 @@ pub use websearch::{SearchResult, SearchResponse, search as web_search, format_r
 // AI Cursor and mouse control
 #[cfg(feature = "computer-use")]
 pub use cursor::{
-    AiCursor, CursorStyle, X11CursorManager,
+    AiCursor, CursorStyle, CursorBackend, X11CursorManager,
     TracerMouse, EasingType, ScrollDirection,
-    SpeedMode, SpeedController,
-    smooth_move, smooth_click,
+    SpeedMode, SpeedController, ControllerStatus,
+    smooth_move, smooth_move_paced, smooth_click,
 };
 pub mod app_knowledge;
 
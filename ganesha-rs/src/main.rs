@@ -19,7 +19,10 @@ mod menu;
 mod pretty;
 mod providers;
 mod orchestrator;
+mod sentinel;
+mod smell;
 mod tui;
+mod vision_probe;
 mod voice;
 mod websearch;
 mod workflow;
@@ -70,6 +73,18 @@ struct Args {
     #[arg(long)]
     code: bool,
 
+    /// Adaptive execution: re-plan mid-task when a command's output invalidates later steps
+    #[arg(long)]
+    adaptive: bool,
+
+    /// Run `a && b; c` commands one step at a time so each step gets its own result
+    #[arg(long)]
+    split_chains: bool,
+
+    /// Ask providers that support it (Anthropic) to cache the planning prompt between turns
+    #[arg(long)]
+    prompt_cache: bool,
+
     /// Interactive REPL mode (default when no task given)
     #[arg(short, long, default_value_t = true)]
     interactive: bool,
@@ -126,13 +141,17 @@ struct Args {
     #[arg(long, value_name = "DURATION")]
     flux: Option<String>,
 
-    /// Flux Capacitor: Run until specified time (e.g., "11:11", "23:30", "11:11 PM")
+    /// Flux Capacitor: Hard limit on how long "--flux auto" may keep extending (default 8h)
+    #[arg(long, value_name = "DURATION")]
+    flux_max: Option<String>,
+
+    /// Flux Capacitor: Run until specified time (e.g., "11:11", "11:11 PM", "tomorrow 9am", "14:00 UTC")
     #[arg(long, value_name = "TIME")]
     until: Option<String>,
 
     /// LLM temperature (0.0-2.0, higher = more creative, default 0.7 for flux)
-    #[arg(long, value_name = "TEMP", default_value = "0.7")]
-    temp: f32,
+    #[arg(long, value_name = "TEMP")]
+    temp: Option<f32>,
 
     /// Random seed for reproducible outputs
     #[arg(long, value_name = "SEED")]
@@ -150,6 +169,44 @@ struct Args {
     #[arg(long)]
     uninstall: bool,
 
+    /// Maximum execute/analyze rounds per task (default 10 in the REPL, 5 for a task given here)
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..=100))]
+    max_iterations: Option<u32>,
+
+    /// Re-run the inputs of a transcript saved with /transcript or --transcript
+    /// and report where the plans differ from the recording
+    #[arg(long, value_name = "FILE")]
+    replay: Option<std::path::PathBuf>,
+
+    /// With --replay: only plan, don't execute anything
+    #[arg(long, requires = "replay")]
+    dry_run: bool,
+
+    /// With --replay: put the provider the transcript was recorded with first
+    #[arg(long, requires = "replay")]
+    same_provider: bool,
+
+    /// Save a replayable transcript of the run to this file on exit
+    #[arg(long, value_name = "FILE")]
+    transcript: Option<std::path::PathBuf>,
+
+    /// Keep tasks inside this directory: no `cd` out of it, no writes outside it
+    #[arg(long, value_name = "DIR")]
+    root: Option<std::path::PathBuf>,
+
+    /// Refuse plans scoring below this on the smell test (0-100)
+    #[arg(long, value_name = "SCORE", value_parser = clap::value_parser!(u8).range(0..=100))]
+    smell_threshold: Option<u8>,
+
+    /// Screen commands with the Sentinel; risky ones are held in quarantine
+    /// until released with /quarantine
+    #[arg(long)]
+    sentinel: bool,
+
+    /// Load extra Sentinel rules from this TOML file (implies --sentinel)
+    #[arg(long, value_name = "FILE")]
+    sentinel_rules: Option<std::path::PathBuf>,
+
     /// Resume the last session
     #[arg(long)]
     last: bool,
@@ -333,9 +390,25 @@ async fn main() {
         }
     }
 
+    let replay = args.replay.as_ref().map(|path| match core::transcript::Transcript::load(path) {
+        Ok(transcript) => transcript,
+        Err(e) => {
+            print_error(&format!("Cannot read transcript {}: {}", path.display(), e));
+            std::process::exit(1);
+        }
+    });
+
     // Create provider chain (TODO: migrate to ProviderManager)
-    let chain = ProviderChain::default_chain();
+    let mut chain = ProviderChain::default_chain();
+    if args.same_provider {
+        if let Some(recorded) = replay.as_ref().and_then(|t| t.provider.as_deref()) {
+            if !chain.prefer(recorded) {
+                print_warning(&format!("Recorded provider {} is not configured; using the default chain", recorded));
+            }
+        }
+    }
     let available = chain.get_available();
+    let primary_provider = available.first().map(|p| p.to_string());
 
     if available.is_empty() {
         print_error("No LLM providers available");
@@ -375,9 +448,7 @@ async fn main() {
 
     // Wiggum agent mode - with verification loop
     if args.wiggum {
-        let (provider_url, model) = menu::get_first_priority_provider()
-            .or_else(|| chain.get_first_available_url())
-            .unwrap_or_else(|| ("http://localhost:1234".to_string(), "default".to_string()));
+        let (provider_url, model) = select_provider(&chain, &task, args.quiet).await;
 
         let config = agent_wiggum::AgentConfig {
             provider_url,
@@ -385,6 +456,7 @@ async fn main() {
             auto_approve: args.auto,
             verify_actions: true,
             verbose: !args.quiet,
+            sentinel: build_sentinel(&args, args.auto),
             ..Default::default()
         };
 
@@ -445,9 +517,7 @@ async fn main() {
 
     // Flux Capacitor mode - time-boxed autonomous execution
     if args.flux.is_some() || args.until.is_some() {
-        let (provider_url, model) = menu::get_first_priority_provider()
-            .or_else(|| chain.get_first_available_url())
-            .unwrap_or_else(|| ("http://localhost:1234".to_string(), "default".to_string()));
+        let (provider_url, model) = select_provider(&chain, &task, args.quiet).await;
 
         // Calculate duration
         let duration = if let Some(ref flux_str) = args.flux {
@@ -459,10 +529,10 @@ async fn main() {
                 }
             }
         } else if let Some(ref until_str) = args.until {
-            match flux::parse_target_time(until_str) {
-                Some(target) => flux::duration_until(target),
+            match flux::parse_until(until_str) {
+                Some(duration) => duration,
                 None => {
-                    print_error(&format!("Invalid time: '{}'. Try '11:11', '23:30', or '11:11 PM'", until_str));
+                    print_error(&format!("Invalid time: '{}'. Try '11:11', '11:11 PM', 'tomorrow 9am', 'in 90 minutes', or '14:00 UTC'", until_str));
                     std::process::exit(1);
                 }
             }
@@ -470,11 +540,23 @@ async fn main() {
             unreachable!()
         };
 
-        if task.is_empty() {
+        // A resumed session takes its task from the journal
+        if task.is_empty() && args.resume.is_none() {
             print_error("Flux Capacitor requires a task. Example: ganesha --flux 1h \"optimize this code\"");
             std::process::exit(1);
         }
 
+        let mut auto_policy = flux::AutoExtendPolicy::default();
+        if let Some(ref max_str) = args.flux_max {
+            match flux::parse_duration(max_str) {
+                Some(ceiling) => auto_policy.ceiling = ceiling,
+                None => {
+                    print_error(&format!("Invalid --flux-max: '{}'. Try '4h' or '90m'", max_str));
+                    std::process::exit(1);
+                }
+            }
+        }
+
         let config = flux::FluxConfig {
             duration,
             task: task.clone(),
@@ -486,9 +568,12 @@ async fn main() {
             temperature: args.temp,
             seed: args.seed,
             resume: args.resume.clone(),
+            auto_policy,
         };
 
-        match flux::run_flux_capacitor(config).await {
+        // Nobody is around to confirm a warning mid-run, so those are held too
+        let sentinel = build_sentinel(&args, true);
+        match flux::run_flux_capacitor(config, sentinel).await {
             Ok(status) => {
                 if status.iterations > 0 {
                     println!("{} Flux Capacitor completed {} iterations",
@@ -505,9 +590,7 @@ async fn main() {
 
     // Agent mode - full coding assistant with tool use
     if args.agent {
-        let (provider_url, model) = menu::get_first_priority_provider()
-            .or_else(|| chain.get_first_available_url())
-            .unwrap_or_else(|| ("http://localhost:1234".to_string(), "default".to_string()));
+        let (provider_url, model) = select_provider(&chain, &task, args.quiet).await;
 
         println!("\n{}", style("─".repeat(60)).dim());
         println!("{}", style("Starting Agent Mode...").cyan().bold());
@@ -527,6 +610,20 @@ async fn main() {
     if args.auto {
         let mut engine = GaneshaEngine::new(chain, AutoConsent, policy);
         engine.auto_approve = true;
+        engine.adaptive_execution = args.adaptive;
+        engine.split_chains = args.split_chains;
+        engine.prompt_caching = args.prompt_cache;
+        engine.smell_threshold = args.smell_threshold;
+        engine.root_boundary = args.root.clone();
+        engine.sentinel = build_sentinel(&args, true);
+        engine.transcript.provider = primary_provider.clone();
+        engine.max_iterations = args.max_iterations.map(|n| n as usize);
+
+        if let Some(ref transcript) = replay {
+            let all_matched = run_replay(&mut engine, transcript, args.dry_run).await;
+            save_transcript(&engine, args.transcript.as_deref());
+            std::process::exit(if all_matched { 0 } else { 1 });
+        }
 
         // Process initial task if provided
         if !task.is_empty() {
@@ -537,25 +634,42 @@ async fn main() {
         if should_be_interactive {
             run_repl(&mut engine, args.code).await;
         }
+        save_transcript(&engine, args.transcript.as_deref());
     } else {
         let mut engine = GaneshaEngine::new(chain, CliConsent::new(), policy);
+        engine.adaptive_execution = args.adaptive;
+        engine.split_chains = args.split_chains;
+        engine.prompt_caching = args.prompt_cache;
+        engine.smell_threshold = args.smell_threshold;
+        engine.root_boundary = args.root.clone();
+        engine.sentinel = build_sentinel(&args, false);
+        engine.transcript.provider = primary_provider.clone();
+        engine.max_iterations = args.max_iterations.map(|n| n as usize);
+
+        if let Some(ref transcript) = replay {
+            let all_matched = run_replay(&mut engine, transcript, args.dry_run).await;
+            save_transcript(&engine, args.transcript.as_deref());
+            std::process::exit(if all_matched { 0 } else { 1 });
+        }
 
         // Process initial task if provided
         if !task.is_empty() {
             run_task(&mut engine, &task, args.code).await;
             // If task was provided and --no-interactive, exit
             if args.no_interactive {
+                save_transcript(&engine, args.transcript.as_deref());
                 return;
             }
         }
 
         // Enter REPL for interactive experience
         run_repl(&mut engine, args.code).await;
+        save_transcript(&engine, args.transcript.as_deref());
     }
 }
 
 /// Interactive REPL loop with proper line editing
-async fn run_repl<C: core::ConsentHandler>(
+async fn run_repl<C: core::AsyncConsentHandler>(
     engine: &mut GaneshaEngine<ProviderChain, C>,
     code_mode: bool,
 ) {
@@ -567,91 +681,10 @@ async fn run_repl<C: core::ConsentHandler>(
     // Initialize workflow engine
     let mut workflow = WorkflowEngine::new();
 
-    // Configure vision from saved config (not hardcoded)
-    // Read the ProviderManager's config to get the vision provider setting
-    let config_path = dirs::home_dir()
-        .unwrap_or_else(|| std::path::PathBuf::from("."))
-        .join(".ganesha")
-        .join("config.json");
-
-    let vision_configured = if config_path.exists() {
-        // Try to read vision config from saved settings
-        if let Ok(content) = std::fs::read_to_string(&config_path) {
-            if let Ok(config) = serde_json::from_str::<serde_json::Value>(&content) {
-                if let Some(vision) = config.get("tiers").and_then(|t| t.get("vision")) {
-                    let endpoint = vision.get("endpoint").and_then(|e| e.as_str()).unwrap_or("");
-                    let model = vision.get("model").and_then(|m| m.as_str()).unwrap_or("");
-                    let description = vision.get("description").and_then(|d| d.as_str()).unwrap_or("Vision");
-
-                    if !endpoint.is_empty() && !model.is_empty() {
-                        // Check if the vision endpoint is actually available
-                        let endpoints = config.get("endpoints");
-                        let endpoint_url = endpoints
-                            .and_then(|e| e.get(endpoint))
-                            .and_then(|e| e.get("base_url"))
-                            .and_then(|u| u.as_str())
-                            .unwrap_or("");
-
-                        if !endpoint_url.is_empty() {
-                            let check_url = format!("{}/v1/models", endpoint_url);
-                            let is_online = reqwest::blocking::Client::builder()
-                                .timeout(std::time::Duration::from_secs(2))
-                                .build()
-                                .ok()
-                                .and_then(|c| c.get(&check_url).send().ok())
-                                .map(|r| r.status().is_success())
-                                .unwrap_or(false);
-
-                            if is_online {
-                                workflow.configure_vision(false, Some((endpoint.to_string(), model.to_string())));
-                                print_info(&format!("Vision: {} ({})", description, model));
-                                true
-                            } else {
-                                false
-                            }
-                        } else {
-                            false
-                        }
-                    } else {
-                        false
-                    }
-                } else {
-                    false
-                }
-            } else {
-                false
-            }
-        } else {
-            false
-        }
-    } else {
-        false
-    };
-
-    // Fallback: auto-detect vision providers if not configured
-    if !vision_configured {
-        // Check available providers for vision capability using async reqwest
-        async fn check_provider(url: &str) -> bool {
-            let client = match reqwest::Client::builder()
-                .timeout(std::time::Duration::from_secs(2))
-                .build() {
-                    Ok(c) => c,
-                    Err(_) => return false,
-                };
-            match client.get(format!("{}/v1/models", url)).send().await {
-                Ok(r) => r.status().is_success(),
-                Err(_) => false,
-            }
-        }
-
-        // Check local LM Studio for vision capability
-        if check_provider("http://localhost:1234").await {
-            workflow.configure_vision(false, Some(("lmstudio".to_string(), "default".to_string())));
-            print_info("Vision: LM Studio (local)");
-        } else if std::env::var("ANTHROPIC_API_KEY").is_ok() {
-            workflow.configure_vision(false, Some(("anthropic".to_string(), "claude-sonnet-4-5-20250514".to_string())));
-            print_info("Vision: Anthropic Claude (fallback)");
-        }
+    // Configure vision from saved config, falling back to auto-detection
+    if let Some(vision) = vision_probe::VisionProbe::default().detect().await {
+        print_info(&format!("Vision: {}", vision.description.as_deref().unwrap_or("enabled")));
+        workflow.configure_vision(false, vision.cloud_vision_provider.zip(vision.cloud_vision_model));
     }
 
     // Session log for /log command
@@ -744,12 +777,14 @@ async fn run_repl<C: core::ConsentHandler>(
                     println!("  /sysadmin      Switch to SysAdmin mode (system tasks)");
                     println!("  /high          Toggle high reasoning mode (detailed analysis)");
                     println!("  /flux          Start Flux Capacitor loop (continuous improvement)");
+                    println!("  /quarantine    List actions held by the Sentinel (release|reject <id>)");
 
                     println!("\n{}", style("MEMORY & SESSION:").yellow().bold());
                     println!("  /recall        Show conversation history");
                     println!("  /clear         Clear conversation history");
                     println!("  /session-status Show full session & workflow status");
                     println!("  /log [file]    Save session transcript to file");
+                    println!("  /transcript [file] Save replayable JSON transcript (for --replay)");
 
                     println!("\n{}", style("SETTINGS & CONFIGURATION:").yellow().bold());
                     println!("  /settings      Open settings menu");
@@ -1070,6 +1105,12 @@ with real GitLab repositories and documentation.
                     continue;
                 }
 
+                // Sentinel quarantine review
+                if input == "/quarantine" || input.starts_with("/quarantine ") {
+                    handle_quarantine(engine, input.trim_start_matches("/quarantine").trim()).await;
+                    continue;
+                }
+
                 // Flux Capacitor continuous improvement mode
                 if input == "/flux" || input.starts_with("/flux ") {
                     println!("\n{}", style("⚡ FLUX CAPACITOR - Continuous Improvement Loop ⚡").cyan().bold());
@@ -1328,6 +1369,20 @@ with real GitLab repositories and documentation.
                     continue;
                 }
 
+                // Handle /transcript command
+                if input.to_lowercase().starts_with("/transcript") {
+                    let filename = input.strip_prefix("/transcript").map(|s| s.trim()).filter(|s| !s.is_empty());
+                    let transcript_file = filename.map(|f| f.to_string()).unwrap_or_else(|| {
+                        format!("ganesha-transcript-{}.json", Local::now().format("%Y%m%d-%H%M%S"))
+                    });
+
+                    match engine.export_transcript(std::path::Path::new(&transcript_file)) {
+                        Ok(_) => println!("{} Transcript saved to: {} (replay with --replay)", style("✓").green(), transcript_file),
+                        Err(e) => println!("{} Failed to save transcript: {}", style("✗").red(), e),
+                    }
+                    continue;
+                }
+
                 // Handle /log command
                 if input.to_lowercase().starts_with("/log") {
                     let filename = input.strip_prefix("/log").map(|s| s.trim()).filter(|s| !s.is_empty());
@@ -1922,6 +1977,21 @@ fn create_spinner(msg: &str) -> indicatif::ProgressBar {
     spinner
 }
 
+/// Token callback that shows the tail of a streaming response in the spinner
+fn stream_preview(spinner: &indicatif::ProgressBar, msg: &str) -> impl FnMut(&str) + Send {
+    const PREVIEW_CHARS: usize = 60;
+
+    let spinner = spinner.clone();
+    let msg = msg.to_string();
+    let mut tail = String::new();
+    move |token| {
+        tail.push_str(token);
+        let flat: Vec<char> = tail.split_whitespace().collect::<Vec<_>>().join(" ").chars().collect();
+        tail = flat[flat.len().saturating_sub(PREVIEW_CHARS)..].iter().collect();
+        spinner.set_message(format!("{} {}", msg, style(&tail).dim()));
+    }
+}
+
 /// Display multiple choice question and get user's answer
 fn ask_multiple_choice(question: &core::MultipleChoiceQuestion) -> Option<String> {
     use dialoguer::{theme::ColorfulTheme, Select, Input};
@@ -2034,7 +2104,8 @@ fn is_image_analysis_request(input: &str) -> bool {
 
     // Must have analysis intent
     let analysis_keywords = ["describe", "analyze", "what's in", "what is in", "tell me about",
-                             "show me", "look at", "examine", "what does", "explain"];
+                             "show me", "look at", "examine", "what does", "explain",
+                             "compare", "difference"];
     let has_analysis = analysis_keywords.iter().any(|k| lower.contains(k));
     if !has_analysis {
         return false;
@@ -2062,6 +2133,14 @@ fn is_image_analysis_request(input: &str) -> bool {
     has_image_word || has_image_extension
 }
 
+/// Check if user wants several images compared against each other
+fn is_image_comparison_request(input: &str) -> bool {
+    let lower = input.to_lowercase();
+    ["compare", "comparison", "difference", "differences", "diff between", "what changed", "versus", " vs "]
+        .iter()
+        .any(|k| lower.contains(k))
+}
+
 /// Find all image files in a directory (recursive, with limit)
 fn find_images_in_directory(dir: &std::path::Path, max_images: usize) -> Vec<std::path::PathBuf> {
     let mut images = Vec::new();
@@ -2123,15 +2202,6 @@ async fn analyze_image_with_vision(
         .map_err(|e| format!("Failed to read image: {}", e))?;
     let base64_image = base64_lib::engine::general_purpose::STANDARD.encode(&image_data);
 
-    // Determine the endpoint URL from provider name
-    let endpoint = match vision_provider {
-        "lmstudio" | "local" => "http://localhost:1234/v1/chat/completions",
-        "anthropic" => "https://api.anthropic.com/v1/messages",
-        "openai" => "https://api.openai.com/v1/chat/completions",
-        _ if vision_provider.starts_with("http") => vision_provider,
-        _ => "http://localhost:1234/v1/chat/completions", // Default to local LM Studio
-    };
-
     // For Anthropic, we need special handling
     if vision_provider == "anthropic" {
         return analyze_image_anthropic(&base64_image, query, vision_model).await;
@@ -2139,7 +2209,7 @@ async fn analyze_image_with_vision(
 
     // Use VisionAnalyzer for OpenAI-compatible endpoints
     let config = VisionConfig {
-        endpoint: endpoint.to_string(),
+        endpoint: vision_endpoint(vision_provider).to_string(),
         model: vision_model.to_string(),
         timeout: std::time::Duration::from_secs(60),
     };
@@ -2151,6 +2221,41 @@ async fn analyze_image_with_vision(
         .map_err(|e| format!("Vision analysis failed: {}", e))
 }
 
+/// Determine the vision endpoint URL from provider name
+fn vision_endpoint(vision_provider: &str) -> &str {
+    match vision_provider {
+        "lmstudio" | "local" => "http://localhost:1234/v1/chat/completions",
+        "anthropic" => "https://api.anthropic.com/v1/messages",
+        "openai" => "https://api.openai.com/v1/chat/completions",
+        _ if vision_provider.starts_with("http") => vision_provider,
+        _ => "http://localhost:1234/v1/chat/completions", // Default to local LM Studio
+    }
+}
+
+/// Compare several images in one vision request (provider must accept multiple images)
+async fn compare_images_with_vision(
+    image_paths: &[std::path::PathBuf],
+    query: &str,
+    vision_provider: &str,
+    vision_model: &str,
+) -> Result<String, String> {
+    use crate::orchestrator::vision::{VisionAnalyzer, VisionConfig, VisionImage};
+
+    let images = image_paths
+        .iter()
+        .map(|p| VisionImage::from_path(p))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let analyzer = VisionAnalyzer::new(VisionConfig {
+        endpoint: vision_endpoint(vision_provider).to_string(),
+        model: vision_model.to_string(),
+        timeout: std::time::Duration::from_secs(120),
+    });
+
+    analyzer.compare_images(&images, query).await
+        .map_err(|e| format!("Vision comparison failed: {}", e))
+}
+
 /// Analyze image using Anthropic API (different format)
 async fn analyze_image_anthropic(
     base64_image: &str,
@@ -2209,8 +2314,186 @@ async fn analyze_image_anthropic(
         .to_string())
 }
 
+/// Re-run each input of a recorded transcript and compare the first plan
+/// with the recorded one. In dry-run mode inputs are only planned, so no
+/// command runs. Returns true if every plan matched.
+async fn run_replay<C: core::AsyncConsentHandler>(
+    engine: &mut GaneshaEngine<ProviderChain, C>,
+    transcript: &core::transcript::Transcript,
+    dry_run: bool,
+) -> bool {
+    use core::transcript::ReplayOutcome;
+
+    let total = transcript.turns.len();
+    let mut outcomes = Vec::with_capacity(total);
+
+    for (i, turn) in transcript.turns.iter().enumerate() {
+        println!("\n{} {}", style(format!("[replay {}/{}]", i + 1, total)).cyan().bold(), turn.input);
+
+        let actual = if dry_run {
+            engine.start_task(&turn.input);
+            engine
+                .plan(&turn.input)
+                .await
+                .map(|plan| core::transcript::plan_commands(&plan))
+                .map_err(|e| e.to_string())
+        } else {
+            run_task(engine, &turn.input, false).await;
+            Ok(engine
+                .transcript
+                .turns
+                .last()
+                .map(|t| t.first_plan_commands())
+                .unwrap_or_default())
+        };
+
+        let outcome = ReplayOutcome {
+            input: turn.input.clone(),
+            expected: turn.first_plan_commands(),
+            actual,
+        };
+        if outcome.matches() {
+            println!("  {} same plan", style("✓").green());
+        } else {
+            println!("  {} plan changed", style("✗").red());
+            println!("    recorded: {:?}", outcome.expected);
+            match &outcome.actual {
+                Ok(commands) => println!("    replayed: {:?}", commands),
+                Err(e) => println!("    replayed: planning failed: {}", e),
+            }
+        }
+        outcomes.push(outcome);
+    }
+
+    let matched = outcomes.iter().filter(|o| o.matches()).count();
+    println!();
+    if matched == total {
+        print_success(&format!("Replay: all {} input(s) produced the recorded plan", total));
+    } else {
+        print_warning(&format!("Replay: {} of {} input(s) produced a different plan", total - matched, total));
+    }
+    matched == total
+}
+
+/// Endpoint and model for the standalone modes: the user's first-priority
+/// provider if configured, else the chain's best fit for the task, else the
+/// first endpoint that answers at all (e.g. a remote server whose model list
+/// has no safe model), else the LM Studio default
+async fn select_provider(chain: &ProviderChain, task: &str, quiet: bool) -> (String, String) {
+    if let Some(provider) = menu::get_first_priority_provider() {
+        return provider;
+    }
+
+    // Probing endpoints makes blocking /v1/models requests, so keep them off
+    // the async runtime
+    let mut endpoints = ProviderChain::new();
+    endpoints.provider_urls = chain.provider_urls.clone();
+    let task = task.to_string();
+    let picked = tokio::task::spawn_blocking(move || match endpoints.select_for_task(&task) {
+        Some(selection) => Ok(selection),
+        None => Err(endpoints.get_first_available_url()),
+    })
+    .await
+    .unwrap_or(Err(None));
+
+    match picked {
+        Ok(selection) => {
+            if !quiet {
+                print_info(&format!("Using {}-tier model {}", selection.tier, selection.model));
+            }
+            (selection.url, selection.model)
+        }
+        Err(Some(available)) => available,
+        Err(None) => ("http://localhost:1234".to_string(), "default".to_string()),
+    }
+}
+
+/// The Sentinel asked for with `--sentinel` / `--sentinel-rules`, if any.
+/// Unattended runs also quarantine actions that would only warn.
+fn build_sentinel(args: &Args, unattended: bool) -> Option<std::sync::Arc<sentinel::Sentinel>> {
+    if !args.sentinel && args.sentinel_rules.is_none() {
+        return None;
+    }
+    let sentinel = sentinel::Sentinel::default();
+    if let Some(ref path) = args.sentinel_rules {
+        match sentinel.load_rules(path) {
+            Ok(count) => print_info(&format!("Sentinel: loaded {} rule(s) from {}", count, path.display())),
+            Err(e) => {
+                print_error(&format!("Invalid Sentinel rules in {}: {}", path.display(), e));
+                std::process::exit(1);
+            }
+        }
+    }
+    sentinel.set_quarantine_warnings(unattended);
+    Some(std::sync::Arc::new(sentinel))
+}
+
+/// `/quarantine [pending | release <id> | reject <id>]`: review the actions
+/// the Sentinel is holding. A released action runs right away.
+async fn handle_quarantine<C: core::AsyncConsentHandler>(
+    engine: &mut GaneshaEngine<ProviderChain, C>,
+    args: &str,
+) {
+    let Some(sentinel) = engine.sentinel.clone() else {
+        print_warning("The Sentinel is off. Start ganesha with --sentinel to hold risky actions.");
+        return;
+    };
+
+    let mut words = args.split_whitespace();
+    match (words.next(), words.next()) {
+        (None, _) | (Some("pending"), None) => {
+            let pending = sentinel.pending();
+            if pending.is_empty() {
+                print_info("Nothing in quarantine");
+                return;
+            }
+            println!("\n{}", style("QUARANTINED ACTIONS:").yellow().bold());
+            for held in pending {
+                println!("  {}  {}", style(&held.id).cyan().bold(), held.action.content);
+                println!("       {} {}", style("held:").dim(), held.analysis.reason);
+                if let Some(ref remediation) = held.analysis.remediation {
+                    println!("       {} {}", style("hint:").dim(), remediation);
+                }
+            }
+            println!("\n{}", style("/quarantine release <id> to run it, /quarantine reject <id> to drop it").dim());
+        }
+        (Some("release"), Some(id)) => {
+            if !sentinel.release(id) {
+                print_error(&format!("No pending action {}", id));
+                return;
+            }
+            match engine.run_released(id).await {
+                Some(results) => {
+                    for result in results {
+                        let output = result.error.as_deref().unwrap_or(&result.output);
+                        print_action_summary(&result.command, result.success, output, result.duration_ms);
+                    }
+                }
+                None => print_success(&format!("Released {}; it runs the next time it comes up", id)),
+            }
+        }
+        (Some("reject"), Some(id)) => {
+            if sentinel.reject(id) {
+                print_success(&format!("Rejected {}", id));
+            } else {
+                print_error(&format!("No pending action {}", id));
+            }
+        }
+        _ => print_error("Usage: /quarantine [pending | release <id> | reject <id>]"),
+    }
+}
+
+/// Write the engine's transcript to `path`, if one was requested
+fn save_transcript<C: core::AsyncConsentHandler>(engine: &GaneshaEngine<ProviderChain, C>, path: Option<&std::path::Path>) {
+    let Some(path) = path else { return };
+    match engine.export_transcript(path) {
+        Ok(()) => print_info(&format!("Transcript saved to {}", path.display())),
+        Err(e) => print_error(&format!("Failed to save transcript: {}", e)),
+    }
+}
+
 /// Run a task autonomously - execute commands, analyze results, continue until done
-async fn run_task_with_log<C: core::ConsentHandler>(
+async fn run_task_with_log<C: core::AsyncConsentHandler>(
     engine: &mut GaneshaEngine<ProviderChain, C>,
     task: &str,
     code_mode: bool,
@@ -2230,6 +2513,7 @@ async fn run_task_with_log<C: core::ConsentHandler>(
     } else {
         task.to_string()
     };
+    engine.start_task(&task);
 
     // Check if this is an image analysis request
     let is_vision_request = is_image_analysis_request(&task);
@@ -2255,6 +2539,22 @@ async fn run_task_with_log<C: core::ConsentHandler>(
         if !image_paths.is_empty() {
             // We have image files to analyze
             if let Some((provider, model)) = vision_config {
+                let comparing = image_paths.len() > 1 && is_image_comparison_request(&task);
+
+                if comparing && orchestrator::vision::supports_multi_image(provider) {
+                    println!("{} Comparing {} images...", style("👁️").cyan(), image_paths.len());
+                    match compare_images_with_vision(&image_paths, &task, provider, model).await {
+                        Ok(analysis) => {
+                            pretty::print_box("📷 Comparison", &analysis);
+                            return analysis;
+                        }
+                        Err(e) => {
+                            // Fall through to one-at-a-time analysis
+                            pretty::print_warning(&e);
+                        }
+                    }
+                }
+
                 println!("{} Analyzing {} image(s)...", style("👁️").cyan(), image_paths.len());
 
                 let mut results = Vec::new();
@@ -2276,6 +2576,21 @@ async fn run_task_with_log<C: core::ConsentHandler>(
                     }
                 }
 
+                // Single-image providers: summarize the per-image descriptions into one comparison
+                if comparing {
+                    use providers::LlmProvider;
+                    let system = "You compare images using only the descriptions provided. \
+                        Give a single combined analysis: what the images have in common and how they differ.";
+                    let user = format!("Request: {}\n\nImage descriptions:\n\n{}", task, results.join("\n\n"));
+                    match engine.llm.generate(system, &user).await {
+                        Ok(summary) => {
+                            pretty::print_box("📷 Comparison", &summary);
+                            return summary;
+                        }
+                        Err(e) => pretty::print_warning(&format!("Comparison summary failed: {}", e)),
+                    }
+                }
+
                 if !results.is_empty() {
                     return results.join("\n\n");
                 }
@@ -2287,7 +2602,7 @@ async fn run_task_with_log<C: core::ConsentHandler>(
 
     let mut all_outputs: Vec<String> = vec![];
     let mut actions_taken: Vec<String> = vec![];
-    let max_iterations = 10;  // Allow more iterations for complex tasks
+    let max_iterations = engine.max_iterations.unwrap_or(core::DEFAULT_MAX_ITERATIONS);
 
     // Detect if this is a browser task (to avoid overlapping spinner messages)
     let task_lower = task.to_lowercase();
@@ -2308,7 +2623,7 @@ async fn run_task_with_log<C: core::ConsentHandler>(
 
     let mut current_task = task.clone();
 
-    let mut current_plan = match engine.plan(&current_task).await {
+    let mut current_plan = match engine.plan_streaming(&current_task, stream_preview(&spinner, thinking_msg)).await {
         Ok(p) => {
             spinner.finish_and_clear();
             p
@@ -2332,7 +2647,7 @@ async fn run_task_with_log<C: core::ConsentHandler>(
                     println!("{} Got it! Let me proceed with: {}", style("✓").green(), style(&answer).cyan());
 
                     let spinner = create_spinner("🐘 Thinking...");
-                    current_plan = match engine.plan(&current_task).await {
+                    current_plan = match engine.plan_streaming(&current_task, stream_preview(&spinner, "🐘 Thinking...")).await {
                         Ok(p) => {
                             spinner.finish_and_clear();
                             p
@@ -2363,13 +2678,38 @@ async fn run_task_with_log<C: core::ConsentHandler>(
         return all_outputs.join("\n");
     }
 
+    // Show shell output live as it arrives; remember which commands printed
+    // something so their output isn't repeated below
+    let streamed: std::sync::Arc<std::sync::Mutex<std::collections::HashSet<String>>> = Default::default();
+    let sink_streamed = streamed.clone();
+    engine.on_output = Some(std::sync::Arc::new(move |line: &core::OutputLine<'_>| {
+        let mut streamed = sink_streamed.lock().unwrap();
+        if streamed.insert(line.command.to_string()) {
+            println!("{} {}", style("Running:").dim(), style(line.command).white());
+        }
+        if line.stderr {
+            println!("  {}", style(line.text).yellow().dim());
+        } else {
+            println!("  {}", style(line.text).dim());
+        }
+    }));
+
     // AGENTIC LOOP: Execute → Analyze → Continue until done
+    // `pending` is set when the model asked for more actions that haven't run yet
+    let mut pending = false;
     for _iteration in 0..max_iterations {
+        pending = false;
         let has_actions = current_plan.actions.iter().any(|a| !a.command.is_empty());
         if !has_actions {
             break;
         }
 
+        if engine.repeats_last_plan(&current_plan) {
+            print_warning("No progress: the model proposed the same commands as last time. Stopping.");
+            print_unfinished(&current_plan);
+            break;
+        }
+
         // Check if plan has MCP/browser actions for spinner message
         let has_browser_action = current_plan.actions.iter().any(|a|
             a.command.starts_with("playwright:") || a.command.starts_with("browser:")
@@ -2400,6 +2740,7 @@ async fn run_task_with_log<C: core::ConsentHandler>(
                     s.finish_and_clear();
                 }
                 if matches!(e, core::GaneshaError::UserCancelled) {
+                    engine.on_output = None;
                     return "User cancelled".to_string();
                 }
                 print_error(&format!("{}", e));
@@ -2479,6 +2820,19 @@ async fn run_task_with_log<C: core::ConsentHandler>(
                         println!("  {}: {}", style("Error").red(), err);
                     }
                 }
+            } else if streamed.lock().unwrap().remove(&result.command) {
+                // Output was already shown live
+                let status = if result.success {
+                    style("Command finished (SUCCESS)").green()
+                } else {
+                    style("Command finished (FAILED)").red()
+                };
+                println!("{}", status);
+                if !result.success {
+                    if let Some(ref err) = result.error {
+                        println!("  {}: {}", style("Error").red(), err.lines().next().unwrap_or(""));
+                    }
+                }
             } else {
                 // Regular shell command output
                 println!("{} {}", style("Running:").dim(), style(&result.command).white());
@@ -2532,6 +2886,7 @@ async fn run_task_with_log<C: core::ConsentHandler>(
                 if let Some(plan) = next_plan {
                     if plan.actions.iter().any(|a| !a.command.is_empty()) {
                         current_plan = plan;
+                        pending = true;
                         continue;  // Go back and execute new commands
                     }
                 }
@@ -2592,10 +2947,31 @@ async fn run_task_with_log<C: core::ConsentHandler>(
         }
     }
 
+    if pending {
+        print_warning(&format!("Reached iteration limit ({}). Raise it with --max-iterations.", max_iterations));
+        print_unfinished(&current_plan);
+    }
+
+    engine.on_output = None;
     all_outputs.join("\n")
 }
 
-async fn run_task<C: core::ConsentHandler>(
+/// List the commands of a plan the agentic loop stopped before running
+fn print_unfinished(plan: &core::ExecutionPlan) {
+    let remaining: Vec<&core::Action> = plan.actions.iter().filter(|a| !a.command.is_empty()).collect();
+    if remaining.is_empty() {
+        return;
+    }
+    println!("{}", style("Not run:").yellow());
+    for action in remaining {
+        println!("  {} {}", style("-").dim(), action.command);
+        if !action.explanation.is_empty() {
+            println!("    {}", style(&action.explanation).dim());
+        }
+    }
+}
+
+async fn run_task<C: core::AsyncConsentHandler>(
     engine: &mut GaneshaEngine<ProviderChain, C>,
     task: &str,
     code_mode: bool,
@@ -2610,9 +2986,10 @@ async fn run_task<C: core::ConsentHandler>(
 
     // Start timing from user prompt
     let task_start = std::time::Instant::now();
+    engine.start_task(&task);
 
     // Agentic loop - plan, execute, analyze, repeat if needed
-    let max_iterations = 5;  // Safety limit
+    let max_iterations = engine.max_iterations.unwrap_or(core::DEFAULT_MAX_TASK_ITERATIONS);
     let mut current_task = task.clone();
 
     for _iteration in 0..max_iterations {
@@ -2625,6 +3002,12 @@ async fn run_task<C: core::ConsentHandler>(
             }
         };
 
+        if engine.repeats_last_plan(&plan) {
+            print_warning("No progress: the model proposed the same commands as last time. Stopping.");
+            print_unfinished(&plan);
+            return;
+        }
+
         // Check if this is a response-only plan (no commands)
         let has_commands = plan.actions.iter().any(|a| !a.command.is_empty());
 
@@ -2703,6 +3086,9 @@ async fn run_task<C: core::ConsentHandler>(
                                 return;
                             }
                         }
+                    } else {
+                        print_warning(&format!("Reached iteration limit ({}). Raise it with --max-iterations.", max_iterations));
+                        print_unfinished(&plan);
                     }
                 } else {
                     // No more actions needed, we're done
@@ -2753,8 +3139,24 @@ fn handle_config(action: ConfigAction) {
         }
 
         ConfigAction::SetLevel { level } => {
-            println!("Access level set to: {}", level);
-            println!("(Config persistence not yet implemented in Rust version)");
+            let level: core::access_control::AccessLevel = match level.parse() {
+                Ok(level) => level,
+                Err(e) => {
+                    print_error(&e);
+                    std::process::exit(1);
+                }
+            };
+
+            match core::access_control::save_access_level(level) {
+                Ok(path) => {
+                    println!("Access level set to: {:?}", level);
+                    println!("Saved to: {}", path.display());
+                }
+                Err(e) => {
+                    print_error(&format!("Failed to save policy: {}", e));
+                    std::process::exit(1);
+                }
+            }
         }
 
         ConfigAction::Test { command } => {
@@ -2775,6 +3177,7 @@ fn handle_config(action: ConfigAction) {
                 println!("{}", console::style("✗ DENIED").red().bold());
             }
             println!("Reason: {}", result.reason);
+            println!("Decision: {}", result.explanation());
         }
     }
 }
@@ -0,0 +1,376 @@
+//! Storage backends for temporal memory
+//!
+//! `TemporalMemory` keeps a ring buffer of recent activity for context
+//! generation and mirrors writes to an optional backend for durable history:
+//!
+//! - `SqliteBackend` - a local database file, no external service needed
+//! - `SpacetimeDbBackend` - shared/networked storage (client not wired yet,
+//!   so writes fail with `BackendError::Unavailable`)
+//!
+//! The backend is read from the `[memory]` table of the Ganesha config file:
+//!
+//! ```toml
+//! [memory]
+//! backend = "sqlite"
+//! path = "/home/me/.ganesha/memory/temporal.db"
+//! ```
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use super::{ActionRecord, GoalProgress, ScreenSnapshot};
+use crate::core::config::{ConfigManager, GaneshaConfig};
+
+/// Errors from a temporal memory backend
+#[derive(Debug, thiserror::Error)]
+pub enum BackendError {
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+
+    #[error("Failed to encode record: {0}")]
+    Encode(#[from] serde_json::Error),
+
+    #[error("Failed to create memory directory: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Backend unavailable: {0}")]
+    Unavailable(String),
+
+    #[error("Invalid [memory] config: {0}")]
+    Config(#[from] toml::de::Error),
+}
+
+/// Durable storage for temporal memory records
+pub trait TemporalMemoryBackend: Send + Sync {
+    /// Short name for logs and stats
+    fn name(&self) -> &'static str;
+
+    /// Store a screen snapshot
+    fn record_snapshot(&self, snapshot: &ScreenSnapshot) -> Result<(), BackendError>;
+
+    /// Store an action
+    fn record_action(&self, action: &ActionRecord) -> Result<(), BackendError>;
+
+    /// Store a goal progress update
+    fn record_goal_progress(&self, progress: &GoalProgress) -> Result<(), BackendError>;
+
+    /// Progress history for a goal, oldest first
+    fn query_goal_progress(&self, goal: &str) -> Result<Vec<GoalProgress>, BackendError>;
+
+    /// Actions recorded at or after `since_ms`, oldest first
+    fn query_actions(&self, since_ms: u64) -> Result<Vec<ActionRecord>, BackendError>;
+
+    /// Highest record ID stored, so a reopened log keeps IDs unique
+    fn last_id(&self) -> Result<u64, BackendError>;
+}
+
+/// Which backend temporal memory persists to
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "backend", rename_all = "lowercase")]
+pub enum MemoryBackendConfig {
+    /// Keep activity in memory only (lost on exit)
+    #[default]
+    None,
+    /// Local SQLite database file
+    Sqlite {
+        #[serde(default = "default_sqlite_path")]
+        path: PathBuf,
+    },
+    /// SpacetimeDB instance, for sharing memory between agents
+    SpacetimeDb { url: String },
+}
+
+fn default_sqlite_path() -> PathBuf {
+    dirs::home_dir()
+        .unwrap_or_else(|| PathBuf::from("."))
+        .join(".ganesha")
+        .join("memory")
+        .join("temporal.db")
+}
+
+impl MemoryBackendConfig {
+    /// Local SQLite log at the default location
+    pub fn local() -> Self {
+        Self::Sqlite {
+            path: default_sqlite_path(),
+        }
+    }
+
+    /// Backend selected by the `[memory]` table of the Ganesha config file
+    pub fn load() -> Result<Self, BackendError> {
+        Self::from_ganesha_config(&ConfigManager::new().load())
+    }
+
+    /// Backend selected by `config.memory`; no table means in-memory only
+    pub fn from_ganesha_config(config: &GaneshaConfig) -> Result<Self, BackendError> {
+        if config.memory.is_empty() {
+            return Ok(Self::None);
+        }
+        Ok(toml::Value::Table(config.memory.clone()).try_into()?)
+    }
+
+    /// Open the configured backend, if any
+    pub fn open(&self) -> Result<Option<Box<dyn TemporalMemoryBackend>>, BackendError> {
+        Ok(match self {
+            Self::None => None,
+            Self::Sqlite { path } => Some(Box::new(SqliteBackend::open(path)?)),
+            Self::SpacetimeDb { url } => Some(Box::new(SpacetimeDbBackend::new(url))),
+        })
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// SQLITE
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// SQLite-backed temporal log (embedded, no server needed)
+pub struct SqliteBackend {
+    conn: Mutex<Connection>,
+}
+
+impl SqliteBackend {
+    /// Open or create the database at `path`
+    pub fn open(path: &Path) -> Result<Self, BackendError> {
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        Self::init(Connection::open(path)?)
+    }
+
+    /// Database that lives only as long as the backend
+    pub fn in_memory() -> Result<Self, BackendError> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self, BackendError> {
+        conn.execute_batch(r#"
+            CREATE TABLE IF NOT EXISTS snapshots (
+                id INTEGER PRIMARY KEY,
+                timestamp_ms INTEGER NOT NULL,
+                context TEXT NOT NULL,
+                title TEXT NOT NULL,
+                screen_hash INTEGER NOT NULL,
+                active_zones TEXT NOT NULL, -- JSON array
+                vision_description TEXT NOT NULL,
+                markdown_summary TEXT NOT NULL,
+                anomalies TEXT NOT NULL     -- JSON array
+            );
+            CREATE INDEX IF NOT EXISTS idx_snapshots_time ON snapshots(timestamp_ms);
+
+            CREATE TABLE IF NOT EXISTS actions (
+                id INTEGER PRIMARY KEY,
+                timestamp_ms INTEGER NOT NULL,
+                snapshot_id INTEGER NOT NULL,
+                action_type TEXT NOT NULL,
+                target TEXT NOT NULL,
+                success INTEGER NOT NULL,
+                ant_result TEXT NOT NULL,
+                eagle_verified INTEGER NOT NULL,
+                error TEXT,
+                duration_ms INTEGER NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_actions_time ON actions(timestamp_ms);
+
+            CREATE TABLE IF NOT EXISTS goal_progress (
+                id INTEGER PRIMARY KEY,
+                timestamp_ms INTEGER NOT NULL,
+                goal TEXT NOT NULL,
+                keywords TEXT NOT NULL,     -- JSON array
+                progress REAL NOT NULL,
+                step INTEGER NOT NULL,
+                snapshot_id INTEGER NOT NULL,
+                status TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_goal_progress_goal ON goal_progress(goal);
+        "#)?;
+
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+}
+
+impl TemporalMemoryBackend for SqliteBackend {
+    fn name(&self) -> &'static str {
+        "sqlite"
+    }
+
+    fn record_snapshot(&self, s: &ScreenSnapshot) -> Result<(), BackendError> {
+        self.conn.lock().unwrap().execute(
+            "INSERT OR REPLACE INTO snapshots
+             (id, timestamp_ms, context, title, screen_hash, active_zones,
+              vision_description, markdown_summary, anomalies)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                s.id as i64,
+                s.timestamp_ms as i64,
+                s.context,
+                s.title,
+                // Stored bit-for-bit; SQLite integers are signed
+                s.screen_hash as i64,
+                serde_json::to_string(&s.active_zones)?,
+                s.vision_description,
+                s.markdown_summary,
+                serde_json::to_string(&s.anomalies)?,
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn record_action(&self, a: &ActionRecord) -> Result<(), BackendError> {
+        self.conn.lock().unwrap().execute(
+            "INSERT OR REPLACE INTO actions
+             (id, timestamp_ms, snapshot_id, action_type, target, success,
+              ant_result, eagle_verified, error, duration_ms)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                a.id as i64,
+                a.timestamp_ms as i64,
+                a.snapshot_id as i64,
+                a.action_type,
+                a.target,
+                a.success,
+                a.ant_result,
+                a.eagle_verified,
+                a.error,
+                a.duration_ms as i64,
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn record_goal_progress(&self, g: &GoalProgress) -> Result<(), BackendError> {
+        self.conn.lock().unwrap().execute(
+            "INSERT OR REPLACE INTO goal_progress
+             (id, timestamp_ms, goal, keywords, progress, step, snapshot_id, status)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                g.id as i64,
+                g.timestamp_ms as i64,
+                g.goal,
+                serde_json::to_string(&g.keywords)?,
+                g.progress as f64,
+                g.step,
+                g.snapshot_id as i64,
+                g.status,
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn query_goal_progress(&self, goal: &str) -> Result<Vec<GoalProgress>, BackendError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, timestamp_ms, goal, keywords, progress, step, snapshot_id, status
+             FROM goal_progress WHERE goal = ?1 ORDER BY id",
+        )?;
+        let rows = stmt.query_map(params![goal], |row| {
+            let keywords: String = row.get(3)?;
+            Ok(GoalProgress {
+                id: row.get::<_, i64>(0)? as u64,
+                timestamp_ms: row.get::<_, i64>(1)? as u64,
+                goal: row.get(2)?,
+                keywords: serde_json::from_str(&keywords).unwrap_or_default(),
+                progress: row.get::<_, f64>(4)? as f32,
+                step: row.get(5)?,
+                snapshot_id: row.get::<_, i64>(6)? as u64,
+                status: row.get(7)?,
+            })
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    fn query_actions(&self, since_ms: u64) -> Result<Vec<ActionRecord>, BackendError> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, timestamp_ms, snapshot_id, action_type, target, success,
+                    ant_result, eagle_verified, error, duration_ms
+             FROM actions WHERE timestamp_ms >= ?1 ORDER BY id",
+        )?;
+        let rows = stmt.query_map(params![since_ms as i64], |row| {
+            Ok(ActionRecord {
+                id: row.get::<_, i64>(0)? as u64,
+                timestamp_ms: row.get::<_, i64>(1)? as u64,
+                snapshot_id: row.get::<_, i64>(2)? as u64,
+                action_type: row.get(3)?,
+                target: row.get(4)?,
+                success: row.get(5)?,
+                ant_result: row.get(6)?,
+                eagle_verified: row.get(7)?,
+                error: row.get(8)?,
+                duration_ms: row.get::<_, i64>(9)? as u64,
+            })
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    fn last_id(&self) -> Result<u64, BackendError> {
+        let conn = self.conn.lock().unwrap();
+        let id: Option<i64> = conn
+            .query_row(
+                "SELECT MAX(id) FROM (
+                    SELECT id FROM snapshots
+                    UNION ALL SELECT id FROM actions
+                    UNION ALL SELECT id FROM goal_progress
+                 )",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+        Ok(id.unwrap_or(0) as u64)
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════════════
+// SPACETIMEDB (stub - implement when SpacetimeDB is added)
+// ═══════════════════════════════════════════════════════════════════════════════
+
+/// SpacetimeDB-backed temporal log for networked/shared memory
+/// TODO: Implement when adding spacetimedb dependency
+pub struct SpacetimeDbBackend {
+    url: String,
+}
+
+impl SpacetimeDbBackend {
+    pub fn new(url: &str) -> Self {
+        // TODO: Connect to SpacetimeDB
+        Self { url: url.to_string() }
+    }
+
+    fn unavailable(&self) -> BackendError {
+        BackendError::Unavailable(format!("SpacetimeDB client not integrated ({})", self.url))
+    }
+}
+
+impl TemporalMemoryBackend for SpacetimeDbBackend {
+    fn name(&self) -> &'static str {
+        "spacetimedb"
+    }
+
+    fn record_snapshot(&self, _snapshot: &ScreenSnapshot) -> Result<(), BackendError> {
+        Err(self.unavailable())
+    }
+
+    fn record_action(&self, _action: &ActionRecord) -> Result<(), BackendError> {
+        Err(self.unavailable())
+    }
+
+    fn record_goal_progress(&self, _progress: &GoalProgress) -> Result<(), BackendError> {
+        Err(self.unavailable())
+    }
+
+    fn query_goal_progress(&self, _goal: &str) -> Result<Vec<GoalProgress>, BackendError> {
+        Err(self.unavailable())
+    }
+
+    fn query_actions(&self, _since_ms: u64) -> Result<Vec<ActionRecord>, BackendError> {
+        Err(self.unavailable())
+    }
+
+    fn last_id(&self) -> Result<u64, BackendError> {
+        Ok(0)
+    }
+}
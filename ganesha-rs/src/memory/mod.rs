@@ -1,4 +1,4 @@
-//! Temporal Memory - SpacetimeDB-backed screen activity log
+//! Temporal Memory - persistent screen activity log
 //!
 //! Instead of relying on LLM context (which gets summarized/lost),
 //! we persist all screen activity to a temporal database:
@@ -13,6 +13,17 @@
 //! - "What actions have we tried for this goal?"
 //! - "When did this element last change?"
 //! - "What's the pattern of failures?"
+//!
+//! Recent activity lives in memory; a `TemporalMemoryBackend` (local SQLite
+//! or SpacetimeDB) keeps the full history across runs.
+
+mod backend;
+mod stall;
+
+pub use backend::{
+    BackendError, MemoryBackendConfig, SpacetimeDbBackend, SqliteBackend, TemporalMemoryBackend,
+};
+pub use stall::{action_similarity, StallReason, DEFAULT_STALL_SIMILARITY, DEFAULT_STALL_WINDOW};
 
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
@@ -124,7 +135,7 @@ pub struct ObstacleRecord {
 // IN-MEMORY TEMPORAL STORE (before SpacetimeDB integration)
 // ═══════════════════════════════════════════════════════════════════════════════
 
-/// Temporal memory store - can be backed by SpacetimeDB
+/// Temporal memory store - optionally persisted through a backend
 pub struct TemporalMemory {
     /// Screen snapshots (ring buffer, keeps last N)
     snapshots: RwLock<VecDeque<ScreenSnapshot>>,
@@ -142,6 +153,10 @@ pub struct TemporalMemory {
     max_entries: usize,
     /// When memory was started
     start_time: Instant,
+    /// Durable storage for snapshots, actions and goal progress
+    backend: Option<Box<dyn TemporalMemoryBackend>>,
+    /// Similarity at which actions count as repeats for stall detection
+    stall_similarity: f32,
 }
 
 impl TemporalMemory {
@@ -155,6 +170,51 @@ impl TemporalMemory {
             next_id: RwLock::new(1),
             max_entries,
             start_time: Instant::now(),
+            backend: None,
+            stall_similarity: DEFAULT_STALL_SIMILARITY,
+        }
+    }
+
+    /// Persist activity through `backend`. IDs continue after the highest
+    /// one already stored.
+    pub fn with_backend(
+        max_entries: usize,
+        backend: Box<dyn TemporalMemoryBackend>,
+    ) -> Result<Self, BackendError> {
+        let mut memory = Self::new(max_entries);
+        *memory.next_id.get_mut().unwrap() = backend.last_id()? + 1;
+        memory.backend = Some(backend);
+        Ok(memory)
+    }
+
+    /// Create a store using the configured backend
+    pub fn from_config(
+        max_entries: usize,
+        config: &MemoryBackendConfig,
+    ) -> Result<Self, BackendError> {
+        match config.open()? {
+            Some(backend) => Self::with_backend(max_entries, backend),
+            None => Ok(Self::new(max_entries)),
+        }
+    }
+
+    /// Create a store using the backend from the Ganesha config file
+    pub fn load_configured(max_entries: usize) -> Result<Self, BackendError> {
+        Self::from_config(max_entries, &MemoryBackendConfig::load()?)
+    }
+
+    /// Name of the persistence backend, if any
+    pub fn backend_name(&self) -> Option<&'static str> {
+        self.backend.as_deref().map(|b| b.name())
+    }
+
+    /// Hand a record to the backend. Failures are logged rather than
+    /// returned so a storage problem never stops the agent.
+    fn persist(&self, write: impl FnOnce(&dyn TemporalMemoryBackend) -> Result<(), BackendError>) {
+        if let Some(backend) = self.backend.as_deref() {
+            if let Err(e) = write(backend) {
+                tracing::warn!("Temporal memory {} write failed: {}", backend.name(), e);
+            }
         }
     }
 
@@ -199,6 +259,7 @@ impl TemporalMemory {
             markdown_summary: markdown.chars().take(1000).collect(),
             anomalies,
         };
+        self.persist(|b| b.record_snapshot(&snapshot));
 
         let mut snapshots = self.snapshots.write().unwrap();
         if snapshots.len() >= self.max_entries {
@@ -233,6 +294,7 @@ impl TemporalMemory {
             error: error.map(|s| s.to_string()),
             duration_ms,
         };
+        self.persist(|b| b.record_action(&action));
 
         let mut actions = self.actions.write().unwrap();
         if actions.len() >= self.max_entries {
@@ -263,6 +325,7 @@ impl TemporalMemory {
             snapshot_id,
             status: status.to_string(),
         };
+        self.persist(|b| b.record_goal_progress(&record));
 
         let mut goals = self.goals.write().unwrap();
         if goals.len() >= self.max_entries {
@@ -423,6 +486,18 @@ impl TemporalMemory {
             .collect()
     }
 
+    /// Full progress history for a goal from the backend, including earlier
+    /// runs. Falls back to the in-memory history without a usable backend.
+    pub fn query_goal_progress(&self, goal: &str) -> Vec<GoalProgress> {
+        if let Some(backend) = self.backend.as_deref() {
+            match backend.query_goal_progress(goal) {
+                Ok(history) => return history,
+                Err(e) => tracing::warn!("Temporal memory {} query failed: {}", backend.name(), e),
+            }
+        }
+        self.goal_history(goal)
+    }
+
     /// Check if we're stuck (no progress in N steps)
     pub fn is_stuck(&self, goal: &str, threshold_steps: u32) -> bool {
         let history = self.goal_history(goal);
@@ -500,6 +575,10 @@ impl TemporalMemory {
             }
         }
 
+        if let Some(stall) = self.detect_goal_stall(goal, DEFAULT_STALL_WINDOW) {
+            context.push_str(&format!("⚠️ STALLED: {} - try a different approach\n", stall));
+        }
+
         // Truncate if too long
         if context.len() > max_chars {
             context.truncate(max_chars - 20);
@@ -518,8 +597,9 @@ impl TemporalMemory {
         let obstacles = self.obstacles.read().unwrap().len();
 
         format!(
-            "Memory: {} snapshots, {} actions, {} goals, {} zones, {} obstacles | Uptime: {}s",
+            "Memory: {} snapshots, {} actions, {} goals, {} zones, {} obstacles | Backend: {} | Uptime: {}s",
             snapshots, actions, goals, zones, obstacles,
+            self.backend_name().unwrap_or("none"),
             self.start_time.elapsed().as_secs()
         )
     }
@@ -609,4 +689,137 @@ mod tests {
         assert!(ctx.contains("eBay"));
         println!("{}", ctx);
     }
+
+    #[test]
+    fn test_sqlite_backend_persists_across_runs() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = MemoryBackendConfig::Sqlite {
+            path: dir.path().join("temporal.db"),
+        };
+        let goal = "search ebay for vintage synth";
+
+        let first_id = {
+            let memory = TemporalMemory::from_config(100, &config).unwrap();
+            assert_eq!(memory.backend_name(), Some("sqlite"));
+            memory.record_goal_progress(goal, vec!["synth".into()], 0.2, 1, 0, "in_progress");
+            memory.record_action(0, "CLICK", "search", false, "", false, Some("not found"), 20)
+        };
+
+        // A new run sees the earlier history and keeps IDs unique
+        let memory = TemporalMemory::from_config(100, &config).unwrap();
+        assert!(memory.goal_history(goal).is_empty());
+        let id = memory.record_goal_progress(goal, vec!["synth".into()], 0.6, 2, 0, "in_progress");
+        assert!(id > first_id);
+
+        let history = memory.query_goal_progress(goal);
+        assert_eq!(history.iter().map(|g| g.step).collect::<Vec<_>>(), [1, 2]);
+        assert_eq!(history[0].keywords, ["synth"]);
+
+        let backend = SqliteBackend::open(&dir.path().join("temporal.db")).unwrap();
+        let actions = backend.query_actions(0).unwrap();
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0].error.as_deref(), Some("not found"));
+    }
+
+    #[test]
+    fn test_backend_config_read_from_ganesha_config() {
+        use crate::core::config::GaneshaConfig;
+
+        let mut text = toml::to_string(&GaneshaConfig::default()).unwrap();
+        assert_eq!(
+            MemoryBackendConfig::from_ganesha_config(&toml::from_str(&text).unwrap()).unwrap(),
+            MemoryBackendConfig::None
+        );
+
+        text.push_str("\n[memory]\nbackend = \"sqlite\"\npath = \"/tmp/temporal.db\"\n");
+        let config: GaneshaConfig = toml::from_str(&text).unwrap();
+        let expected = MemoryBackendConfig::Sqlite {
+            path: "/tmp/temporal.db".into(),
+        };
+        assert_eq!(MemoryBackendConfig::from_ganesha_config(&config).unwrap(), expected);
+
+        // Saving the config keeps the section
+        let resaved: GaneshaConfig = toml::from_str(&toml::to_string(&config).unwrap()).unwrap();
+        assert_eq!(MemoryBackendConfig::from_ganesha_config(&resaved).unwrap(), expected);
+    }
+
+    #[test]
+    fn test_spacetimedb_writes_are_rejected() {
+        let goal = "search ebay for vintage synth";
+        let memory = TemporalMemory::new(10);
+        memory.record_goal_progress(goal, vec![], 0.2, 1, 0, "in_progress");
+        let progress = memory.goal_history(goal).pop().unwrap();
+
+        let backend = SpacetimeDbBackend::new("http://localhost:3000");
+        assert!(matches!(
+            backend.record_goal_progress(&progress),
+            Err(BackendError::Unavailable(_))
+        ));
+    }
+
+    #[test]
+    fn test_detect_stall() {
+        let goal = "open settings";
+        let act = |memory: &TemporalMemory, action_type: &str, target: &str| {
+            memory.record_action(0, action_type, target, true, "", false, None, 10);
+        };
+
+        // A→B→A→B with flat progress
+        let memory = TemporalMemory::new(100);
+        memory.record_goal_progress(goal, vec![], 0.3, 1, 0, "in_progress");
+        for _ in 0..3 {
+            act(&memory, "CLICK", "menu button");
+            act(&memory, "KEY", "Escape");
+        }
+        memory.record_goal_progress(goal, vec![], 0.3, 2, 0, "in_progress");
+        assert_eq!(
+            memory.detect_stall(6),
+            Some(StallReason::Oscillation {
+                first: "CLICK menu button".into(),
+                second: "KEY Escape".into(),
+                cycles: 3,
+            })
+        );
+        assert_eq!(memory.detect_stall(10), None, "not enough actions yet");
+        assert!(memory.generate_context(goal, 2000).contains("STALLED: oscillating"));
+
+        // Near-identical targets count as the same action
+        let memory = TemporalMemory::new(100).with_stall_similarity(0.5);
+        act(&memory, "SCROLL", "down");
+        act(&memory, "CLICK", "Next page");
+        act(&memory, "CLICK", "next");
+        act(&memory, "CLICK", "next page");
+        assert!(matches!(
+            memory.detect_stall(4),
+            Some(StallReason::RepeatedAction { count: 3, .. })
+        ));
+
+        // Moving progress is not a stall
+        let memory = TemporalMemory::new(100);
+        for step in 1..=4 {
+            act(&memory, "CLICK", "next");
+            memory.record_goal_progress(goal, vec![], step as f32 * 0.2, step, 0, "in_progress");
+        }
+        assert_eq!(memory.detect_stall(4), None);
+    }
+
+    #[test]
+    fn test_stall_context_uses_the_requested_goal() {
+        let (stuck, busy) = ("open settings", "download file");
+        let memory = TemporalMemory::new(100);
+        memory.record_goal_progress(stuck, vec![], 0.3, 1, 0, "in_progress");
+        for _ in 0..3 {
+            memory.record_action(0, "CLICK", "menu button", true, "", false, None, 10);
+            memory.record_action(0, "KEY", "Escape", true, "", false, None, 10);
+        }
+        memory.record_goal_progress(stuck, vec![], 0.3, 2, 0, "in_progress");
+        // Another goal moving along must not hide the stall on `stuck`
+        memory.record_goal_progress(busy, vec![], 0.1, 1, 0, "in_progress");
+        memory.record_goal_progress(busy, vec![], 0.9, 2, 0, "in_progress");
+
+        assert_eq!(memory.detect_stall(6), None);
+        assert!(memory.detect_goal_stall(stuck, 6).is_some());
+        assert!(memory.generate_context(stuck, 2000).contains("STALLED: oscillating"));
+        assert!(!memory.generate_context(busy, 2000).contains("STALLED"));
+    }
 }
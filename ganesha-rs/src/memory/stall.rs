@@ -0,0 +1,217 @@
+//! Stall detection - noticing when the agent is spinning its wheels
+//!
+//! Looks at the last few actions and the goal progress recorded alongside
+//! them. While progress is flat, three patterns count as a stall:
+//!
+//! - Oscillation: alternating between two actions or two screens (A→B→A→B),
+//!   the usual failure mode with small local models
+//! - Repetition: the same action over and over
+//! - No progress: a full window of actions without progress moving
+
+use std::fmt;
+
+use super::{ActionRecord, TemporalMemory};
+
+/// Actions examined by `generate_context`'s stall check
+pub const DEFAULT_STALL_WINDOW: usize = 6;
+
+/// Action similarity at or above which two actions count as the same
+pub const DEFAULT_STALL_SIMILARITY: f32 = 0.8;
+
+/// Progress change below which a goal counts as not advancing
+const MIN_PROGRESS_DELTA: f32 = 0.05;
+
+/// Identical actions in a row that count as repetition
+const MIN_REPEATS: usize = 3;
+
+/// Alternating steps (A, B, A, B) that count as oscillation
+const MIN_OSCILLATION_STEPS: usize = 4;
+
+/// Why the agent looks stuck
+#[derive(Debug, Clone, PartialEq)]
+pub enum StallReason {
+    /// Alternating between two actions or two screen states
+    Oscillation {
+        first: String,
+        second: String,
+        /// Full A→B cycles seen
+        cycles: usize,
+    },
+    /// The same action repeated back to back
+    RepeatedAction {
+        action_type: String,
+        target: String,
+        count: usize,
+    },
+    /// A whole window of actions without progress changing
+    NoProgress {
+        actions: usize,
+        /// Latest progress (0.0-1.0)
+        progress: f32,
+    },
+}
+
+impl fmt::Display for StallReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StallReason::Oscillation { first, second, cycles } => write!(
+                f,
+                "oscillating between '{}' and '{}' ({} cycles)",
+                first, second, cycles
+            ),
+            StallReason::RepeatedAction { action_type, target, count } => {
+                write!(f, "repeated {} {} {} times", action_type, target, count)
+            }
+            StallReason::NoProgress { actions, progress } => write!(
+                f,
+                "no progress in {} actions (stuck at {:.0}%)",
+                actions,
+                progress * 100.0
+            ),
+        }
+    }
+}
+
+/// How alike two actions are, 0.0-1.0. Different action types never match;
+/// targets are compared by their shared words.
+pub fn action_similarity(a: &ActionRecord, b: &ActionRecord) -> f32 {
+    if a.action_type != b.action_type {
+        return 0.0;
+    }
+
+    let words = |s: &str| -> Vec<String> {
+        s.to_lowercase().split_whitespace().map(str::to_string).collect()
+    };
+    let (a_words, b_words) = (words(&a.target), words(&b.target));
+    if a_words.is_empty() && b_words.is_empty() {
+        return 1.0;
+    }
+
+    let shared = a_words.iter().filter(|w| b_words.contains(w)).count();
+    let total = a_words.len() + b_words.len() - shared;
+    shared as f32 / total as f32
+}
+
+/// Length of the alternating A, B, A, B... run at the end of `items`, where
+/// `same` decides whether two items are the same state
+fn alternating_tail<T>(items: &[T], same: impl Fn(&T, &T) -> bool) -> usize {
+    let n = items.len();
+    if n < 2 || same(&items[n - 1], &items[n - 2]) {
+        return n.min(1);
+    }
+
+    let mut run = 2;
+    while run < n {
+        let i = n - 1 - run;
+        if !same(&items[i], &items[i + 2]) || same(&items[i], &items[i + 1]) {
+            break;
+        }
+        run += 1;
+    }
+    run
+}
+
+fn describe(action: &ActionRecord) -> String {
+    format!("{} {}", action.action_type, action.target).trim().to_string()
+}
+
+impl TemporalMemory {
+    /// Set the similarity (0.0-1.0) at which two actions count as the same
+    /// for stall detection
+    pub fn with_stall_similarity(mut self, threshold: f32) -> Self {
+        self.stall_similarity = threshold.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Check the last `window` actions for a stall. Returns `None` while the
+    /// latest goal is still making progress or fewer than `window` actions
+    /// have been recorded.
+    pub fn detect_stall(&self, window: usize) -> Option<StallReason> {
+        let latest = self.goals.read().unwrap().back().map(|g| g.goal.clone());
+        self.stall_for(latest.as_deref(), window)
+    }
+
+    /// Like `detect_stall`, but judges progress on `goal` rather than on
+    /// whichever goal was updated last
+    pub fn detect_goal_stall(&self, goal: &str, window: usize) -> Option<StallReason> {
+        self.stall_for(Some(goal), window)
+    }
+
+    fn stall_for(&self, goal: Option<&str>, window: usize) -> Option<StallReason> {
+        let window = window.max(2);
+        let actions: Vec<ActionRecord> = {
+            let actions = self.actions.read().unwrap();
+            let skip = actions.len().checked_sub(window)?;
+            actions.iter().skip(skip).cloned().collect()
+        };
+        let since = actions[0].timestamp_ms;
+
+        // Progress over the window, counting the last update before it as
+        // the starting point
+        let progress: Option<(f32, f32)> = goal.and_then(|goal| {
+            let goals = self.goals.read().unwrap();
+            let mut history = goals.iter().rev().filter(|g| g.goal == goal).peekable();
+            let latest = history.peek()?.progress;
+            let (mut low, mut high) = (latest, latest);
+            for g in history {
+                low = low.min(g.progress);
+                high = high.max(g.progress);
+                if g.timestamp_ms < since {
+                    break;
+                }
+            }
+            Some((high - low, latest))
+        });
+        if matches!(progress, Some((delta, _)) if delta >= MIN_PROGRESS_DELTA) {
+            return None;
+        }
+
+        let same = |a: &ActionRecord, b: &ActionRecord| {
+            action_similarity(a, b) >= self.stall_similarity
+        };
+
+        let steps = alternating_tail(&actions, same);
+        if steps >= MIN_OSCILLATION_STEPS {
+            let n = actions.len();
+            return Some(StallReason::Oscillation {
+                first: describe(&actions[n - 2]),
+                second: describe(&actions[n - 1]),
+                cycles: steps / 2,
+            });
+        }
+
+        let last = &actions[actions.len() - 1];
+        let repeats = actions.iter().rev().take_while(|a| same(a, last)).count();
+        if repeats >= MIN_REPEATS {
+            return Some(StallReason::RepeatedAction {
+                action_type: last.action_type.clone(),
+                target: last.target.clone(),
+                count: repeats,
+            });
+        }
+
+        // Actions that differ but flip the screen between two states
+        let screens: Vec<(u64, String)> = self
+            .snapshots
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|s| s.timestamp_ms >= since)
+            .map(|s| (s.screen_hash, s.title.clone()))
+            .collect();
+        let steps = alternating_tail(&screens, |a, b| a.0 == b.0);
+        if steps >= MIN_OSCILLATION_STEPS {
+            let n = screens.len();
+            return Some(StallReason::Oscillation {
+                first: screens[n - 2].1.clone(),
+                second: screens[n - 1].1.clone(),
+                cycles: steps / 2,
+            });
+        }
+
+        progress.map(|(_, latest)| StallReason::NoProgress {
+            actions: actions.len(),
+            progress: latest,
+        })
+    }
+}
@@ -1432,7 +1432,7 @@ impl ProviderManager {
         } else if model_id.contains("4o") || model_id.contains("4-turbo") {
             128000
         } else {
-            32000
+            crate::providers::DEFAULT_CONTEXT_WINDOW as u32
         }
     }
 
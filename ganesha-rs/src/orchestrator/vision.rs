@@ -8,8 +8,10 @@
 //! - Reading screen content
 //! - Verifying action results
 //! - Detecting errors/dialogs
+//! - Comparing several images in a single request
 
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 use std::time::Duration;
 
 /// Screen analysis result - strict JSON format from vision model
@@ -86,6 +88,134 @@ impl Default for VisionConfig {
     }
 }
 
+/// A labelled image for multi-image requests
+#[derive(Debug, Clone)]
+pub struct VisionImage {
+    /// Name the model uses to refer to this image (usually the filename)
+    pub label: String,
+    pub media_type: String,
+    /// Base64-encoded image data
+    pub data: String,
+}
+
+impl VisionImage {
+    pub fn new(label: impl Into<String>, media_type: impl Into<String>, data: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            media_type: media_type.into(),
+            data: data.into(),
+        }
+    }
+
+    /// Read and encode an image file, inferring the media type from its extension
+    pub fn from_path(path: &Path) -> Result<Self, String> {
+        use base64_lib::Engine;
+
+        let bytes = std::fs::read(path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let media_type = match path.extension().and_then(|e| e.to_str()).map(|e| e.to_lowercase()).as_deref() {
+            Some("jpg") | Some("jpeg") => "image/jpeg",
+            Some("gif") => "image/gif",
+            Some("webp") => "image/webp",
+            _ => "image/png",
+        };
+        let label = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+
+        Ok(Self::new(label, media_type, base64_lib::engine::general_purpose::STANDARD.encode(bytes)))
+    }
+}
+
+/// Whether a vision provider accepts several images in one request.
+/// Local servers often run single-image models, so they get the sequential path.
+pub fn supports_multi_image(provider: &str) -> bool {
+    matches!(provider, "anthropic" | "openai")
+        || provider.contains("anthropic.com")
+        || provider.contains("api.openai.com")
+}
+
+/// Prompt asking for one combined comparison of the labelled images
+pub fn comparison_prompt(images: &[VisionImage], query: &str) -> String {
+    let labels: Vec<String> = images
+        .iter()
+        .enumerate()
+        .map(|(i, img)| format!("Image {} ({})", i + 1, img.label))
+        .collect();
+    format!(
+        "You are given {} images: {}. Compare them directly and give a single combined analysis: \
+         what they have in common, what differs between them, and anything notable about the differences. \
+         Refer to each image by its number.\n\nRequest: {}",
+        images.len(),
+        labels.join(", "),
+        query
+    )
+}
+
+/// Anthropic Messages request with every image in one content array.
+/// Each image is preceded by a text label so the model can tell them apart.
+pub fn anthropic_multi_image_request(
+    model: &str,
+    images: &[VisionImage],
+    prompt: &str,
+    max_tokens: u32,
+) -> serde_json::Value {
+    let mut content = Vec::new();
+    for (i, img) in images.iter().enumerate() {
+        content.push(serde_json::json!({
+            "type": "text",
+            "text": format!("Image {}: {}", i + 1, img.label)
+        }));
+        content.push(serde_json::json!({
+            "type": "image",
+            "source": {
+                "type": "base64",
+                "media_type": img.media_type,
+                "data": img.data
+            }
+        }));
+    }
+    content.push(serde_json::json!({"type": "text", "text": prompt}));
+
+    serde_json::json!({
+        "model": model,
+        "max_tokens": max_tokens,
+        "messages": [
+            {"role": "user", "content": content}
+        ]
+    })
+}
+
+/// OpenAI-compatible chat request with every image in one user message
+pub fn openai_multi_image_request(
+    model: &str,
+    images: &[VisionImage],
+    prompt: &str,
+    max_tokens: u32,
+) -> serde_json::Value {
+    let mut content = Vec::new();
+    for (i, img) in images.iter().enumerate() {
+        content.push(serde_json::json!({
+            "type": "text",
+            "text": format!("Image {}: {}", i + 1, img.label)
+        }));
+        content.push(serde_json::json!({
+            "type": "image_url",
+            "image_url": {
+                "url": format!("data:{};base64,{}", img.media_type, img.data)
+            }
+        }));
+    }
+    content.push(serde_json::json!({"type": "text", "text": prompt}));
+
+    serde_json::json!({
+        "model": model,
+        "messages": [
+            {"role": "user", "content": content}
+        ],
+        "temperature": 0.1,
+        "max_tokens": max_tokens
+    })
+}
+
 /// The vision analyzer
 pub struct VisionAnalyzer {
     config: VisionConfig,
@@ -99,6 +229,8 @@ impl VisionAnalyzer {
         let is_anthropic = config.endpoint.contains("anthropic.com");
         let api_key = if is_anthropic {
             std::env::var("ANTHROPIC_API_KEY").ok()
+        } else if config.endpoint.contains("api.openai.com") {
+            std::env::var("OPENAI_API_KEY").ok()
         } else {
             None
         };
@@ -115,6 +247,16 @@ impl VisionAnalyzer {
         Self::new(VisionConfig::default())
     }
 
+    /// A request to the configured endpoint with the provider's auth headers
+    fn post(&self, body: &serde_json::Value) -> reqwest::RequestBuilder {
+        let req = self.client.post(&self.config.endpoint).json(body);
+        match (&self.api_key, self.is_anthropic) {
+            (Some(key), true) => req.header("x-api-key", key).header("anthropic-version", "2023-06-01"),
+            (Some(key), false) => req.bearer_auth(key),
+            (None, _) => req,
+        }
+    }
+
     /// Capture and analyze the current screen
     #[cfg(feature = "vision")]
     pub async fn analyze_screen(&self) -> Result<ScreenAnalysis, Box<dyn std::error::Error + Send + Sync>> {
@@ -165,12 +307,7 @@ CRITICAL: Your entire response must be a single JSON object starting with { and
                 ]
             });
 
-            let mut req = self.client.post(&self.config.endpoint).json(&request);
-            if let Some(ref key) = self.api_key {
-                req = req.header("x-api-key", key)
-                    .header("anthropic-version", "2023-06-01");
-            }
-            req.send().await?
+            self.post(&request).send().await?
         } else {
             // OpenAI-compatible format
             let user_content = serde_json::json!([
@@ -196,11 +333,7 @@ CRITICAL: Your entire response must be a single JSON object starting with { and
                 "max_tokens": 2000
             });
 
-            self.client
-                .post(&self.config.endpoint)
-                .json(&request)
-                .send()
-                .await?
+            self.post(&request).send().await?
         };
 
         if !response.status().is_success() {
@@ -259,12 +392,7 @@ CRITICAL: Your entire response must be a single JSON object starting with { and
                 ]
             });
 
-            let mut req = self.client.post(&self.config.endpoint).json(&request);
-            if let Some(ref key) = self.api_key {
-                req = req.header("x-api-key", key)
-                    .header("anthropic-version", "2023-06-01");
-            }
-            req.send().await?
+            self.post(&request).send().await?
         } else {
             // OpenAI-compatible format
             let user_content = serde_json::json!([
@@ -290,11 +418,7 @@ CRITICAL: Your entire response must be a single JSON object starting with { and
                 "max_tokens": 100
             });
 
-            self.client
-                .post(&self.config.endpoint)
-                .json(&request)
-                .send()
-                .await?
+            self.post(&request).send().await?
         };
 
         if !response.status().is_success() {
@@ -319,6 +443,41 @@ CRITICAL: Your entire response must be a single JSON object starting with { and
         Ok(content.to_string())
     }
 
+    /// Send several images in one request and return a single combined comparison
+    pub async fn compare_images(&self, images: &[VisionImage], query: &str) -> Result<String, Box<dyn std::error::Error + Send + Sync>> {
+        let prompt = comparison_prompt(images, query);
+
+        let response = if self.is_anthropic {
+            let request = anthropic_multi_image_request(&self.config.model, images, &prompt, 2000);
+
+            self.post(&request).send().await?
+        } else {
+            let request = openai_multi_image_request(&self.config.model, images, &prompt, 2000);
+
+            self.post(&request).send().await?
+        };
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(format!("Vision API error {}: {}", status, body).into());
+        }
+
+        let json: serde_json::Value = response.json().await?;
+        let content = if self.is_anthropic {
+            json["content"][0]["text"].as_str().unwrap_or("")
+        } else {
+            let msg = &json["choices"][0]["message"];
+            let c = msg["content"].as_str().unwrap_or("");
+            if c.is_empty() {
+                msg["reasoning_content"].as_str().unwrap_or("")
+            } else {
+                c
+            }
+        };
+        Ok(content.to_string())
+    }
+
     /// Check if a specific element is visible
     pub async fn is_visible(&self, base64_image: &str, element: &str) -> Result<bool, Box<dyn std::error::Error + Send + Sync>> {
         let query = format!("Is '{}' visible on screen? Answer YES or NO only.", element);
@@ -503,6 +662,66 @@ mod tests {
         assert!(result.confidence > 0.9);
     }
 
+    #[test]
+    fn test_anthropic_multi_image_request() {
+        let images = vec![
+            VisionImage::new("before.png", "image/png", "AAAA"),
+            VisionImage::new("after.jpg", "image/jpeg", "BBBB"),
+        ];
+        let prompt = comparison_prompt(&images, "What changed?");
+        let request = anthropic_multi_image_request("claude-test", &images, &prompt, 1024);
+
+        assert_eq!(request["model"], "claude-test");
+        assert_eq!(request["max_tokens"], 1024);
+        let messages = request["messages"].as_array().unwrap();
+        assert_eq!(messages.len(), 1);
+
+        // label, image, label, image, prompt - all in one user message
+        let content = messages[0]["content"].as_array().unwrap();
+        assert_eq!(content.len(), 5);
+        assert_eq!(content[0]["text"], "Image 1: before.png");
+        assert_eq!(content[1]["type"], "image");
+        assert_eq!(content[1]["source"]["media_type"], "image/png");
+        assert_eq!(content[1]["source"]["data"], "AAAA");
+        assert_eq!(content[2]["text"], "Image 2: after.jpg");
+        assert_eq!(content[3]["source"]["media_type"], "image/jpeg");
+        assert_eq!(content[3]["source"]["data"], "BBBB");
+        assert_eq!(content[4]["type"], "text");
+        let text = content[4]["text"].as_str().unwrap();
+        assert!(text.contains("2 images"));
+        assert!(text.contains("What changed?"));
+    }
+
+    #[test]
+    fn test_supports_multi_image() {
+        assert!(supports_multi_image("anthropic"));
+        assert!(supports_multi_image("https://api.anthropic.com/v1/messages"));
+        assert!(supports_multi_image("openai"));
+        assert!(!supports_multi_image("lmstudio"));
+        assert!(!supports_multi_image("http://localhost:1234/v1/chat/completions"));
+    }
+
+    #[test]
+    fn test_requests_carry_provider_auth() {
+        let analyzer = |endpoint: &str| VisionAnalyzer {
+            api_key: Some("sk-test".into()),
+            ..VisionAnalyzer::new(VisionConfig { endpoint: endpoint.into(), ..VisionConfig::default() })
+        };
+        let headers = |analyzer: VisionAnalyzer| analyzer.post(&serde_json::json!({})).build().unwrap().headers().clone();
+
+        let openai = headers(analyzer("https://api.openai.com/v1/chat/completions"));
+        assert_eq!(openai["authorization"], "Bearer sk-test");
+        assert!(openai.get("x-api-key").is_none());
+
+        let anthropic = headers(analyzer("https://api.anthropic.com/v1/messages"));
+        assert_eq!(anthropic["x-api-key"], "sk-test");
+        assert!(anthropic.get("authorization").is_none());
+
+        // Local servers get no key
+        let local = headers(VisionAnalyzer::with_defaults());
+        assert!(local.get("authorization").is_none());
+    }
+
     #[test]
     fn test_screen_state_parsing() {
         let states = ["ready", "loading", "error", "dialog", "busy", "unknown"];
@@ -6,11 +6,17 @@
 //! 3. Anthropic Claude (cloud)
 //! 4. OpenAI (cloud)
 
+pub mod tiers;
+
 use async_trait::async_trait;
+use futures::Stream;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::pin::Pin;
 use std::time::Duration;
 use thiserror::Error;
+use tiers::{get_model_tier, QualityTier};
 
 #[derive(Error, Debug)]
 pub enum ProviderError {
@@ -32,20 +38,78 @@ pub enum ProviderError {
 pub struct ChatMessage {
     pub role: String,
     pub content: String,
+    /// Prompt-cache breakpoint hint; only Anthropic acts on it (system messages)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub cacheable: bool,
 }
 
 impl ChatMessage {
     pub fn system(content: &str) -> Self {
-        Self { role: "system".into(), content: content.into() }
+        Self { role: "system".into(), content: content.into(), cacheable: false }
     }
     pub fn user(content: &str) -> Self {
-        Self { role: "user".into(), content: content.into() }
+        Self { role: "user".into(), content: content.into(), cacheable: false }
     }
     pub fn assistant(content: &str) -> Self {
-        Self { role: "assistant".into(), content: content.into() }
+        Self { role: "assistant".into(), content: content.into(), cacheable: false }
+    }
+    /// Ask the provider to cache the prompt up to and including this message
+    pub fn cacheable(mut self) -> Self {
+        self.cacheable = true;
+        self
+    }
+}
+
+/// Context window assumed for providers that don't declare one. Local models
+/// are commonly served with 32k, so this errs on the small side.
+pub const DEFAULT_CONTEXT_WINDOW: usize = 32_768;
+
+/// Rough token estimate: one token per four characters plus per-message framing
+pub fn estimate_tokens(messages: &[ChatMessage]) -> usize {
+    messages.iter().map(message_tokens).sum()
+}
+
+fn message_tokens(message: &ChatMessage) -> usize {
+    message.content.chars().count().div_ceil(4) + 4
+}
+
+/// Drop the oldest non-system messages until the estimated prompt plus
+/// `reserve_for_completion` fits `context_window`. System messages and the
+/// final message are always kept. Returns the kept messages and the indices
+/// of those dropped, oldest first.
+pub fn fit_to_window(
+    messages: &[ChatMessage],
+    context_window: usize,
+    reserve_for_completion: usize,
+) -> (Vec<ChatMessage>, Vec<usize>) {
+    let budget = context_window.saturating_sub(reserve_for_completion);
+    let mut total = estimate_tokens(messages);
+    let last = messages.len().saturating_sub(1);
+    let mut dropped = Vec::new();
+
+    for (i, message) in messages.iter().enumerate() {
+        if total <= budget {
+            break;
+        }
+        if i == last || message.role == "system" {
+            continue;
+        }
+        total -= message_tokens(message);
+        dropped.push(i);
     }
+
+    let kept = messages
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !dropped.contains(i))
+        .map(|(_, m)| m.clone())
+        .collect();
+    (kept, dropped)
 }
 
+/// Response text as it arrives, one chunk per item
+pub type TokenStream = Pin<Box<dyn Stream<Item = Result<String, ProviderError>> + Send>>;
+
 /// LLM Provider trait
 #[async_trait]
 pub trait LlmProvider: Send + Sync {
@@ -57,6 +121,18 @@ pub trait LlmProvider: Send + Sync {
 
     /// Multi-turn generation with conversation history
     async fn generate_with_history(&self, messages: &[ChatMessage]) -> Result<String, ProviderError>;
+
+    /// Tokens the model accepts (prompt plus completion)
+    fn context_window(&self) -> usize {
+        DEFAULT_CONTEXT_WINDOW
+    }
+
+    /// Multi-turn generation that yields tokens as they arrive.
+    /// Providers that can't stream return the whole response as a single chunk.
+    async fn generate_stream_with_history(&self, messages: &[ChatMessage]) -> Result<TokenStream, ProviderError> {
+        let response = self.generate_with_history(messages).await?;
+        Ok(Box::pin(futures::stream::once(async move { Ok(response) })))
+    }
 }
 
 /// OpenAI-compatible provider (LM Studio, OpenAI, etc.)
@@ -264,6 +340,103 @@ impl LlmProvider for OpenAiCompatible {
             .map(|c| c.message.content.clone())
             .ok_or_else(|| ProviderError::Api("No response content".into()))
     }
+
+    async fn generate_stream_with_history(&self, messages: &[ChatMessage]) -> Result<TokenStream, ProviderError> {
+        let url = format!("{}/v1/chat/completions", self.base_url);
+
+        let request = ChatRequest {
+            model: self.model.clone(),
+            messages: messages.iter().map(|m| Message {
+                role: m.role.clone(),
+                content: m.content.clone(),
+            }).collect(),
+            temperature: 0.3,
+            max_tokens: 65536,
+            stream: true,
+        };
+
+        let mut req = self.client.post(&url).json(&request);
+
+        if let Some(ref key) = self.api_key {
+            req = req.bearer_auth(key);
+        }
+
+        let response = req.send().await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(ProviderError::Api(format!("{}: {}", status, body)));
+        }
+
+        Ok(sse_token_stream(response))
+    }
+}
+
+/// One line of an OpenAI-style server-sent event stream
+#[derive(Debug, PartialEq)]
+enum SseLine {
+    Token(String),
+    Done,
+    Skip,
+}
+
+fn parse_sse_line(line: &str) -> SseLine {
+    let Some(data) = line.strip_prefix("data:") else {
+        return SseLine::Skip;
+    };
+    let data = data.trim();
+    if data == "[DONE]" {
+        return SseLine::Done;
+    }
+    serde_json::from_str::<serde_json::Value>(data)
+        .ok()
+        .and_then(|v| v["choices"][0]["delta"]["content"].as_str().map(str::to_string))
+        .filter(|t| !t.is_empty())
+        .map_or(SseLine::Skip, SseLine::Token)
+}
+
+/// Turn a streaming chat completion into a stream of content deltas.
+/// Bytes are buffered per line so multi-byte characters split across
+/// network chunks decode intact.
+fn sse_token_stream(response: reqwest::Response) -> TokenStream {
+    struct State {
+        response: reqwest::Response,
+        buffer: Vec<u8>,
+        pending: VecDeque<String>,
+        done: bool,
+    }
+
+    let state = State { response, buffer: Vec::new(), pending: VecDeque::new(), done: false };
+
+    Box::pin(futures::stream::unfold(state, |mut state| async move {
+        loop {
+            if let Some(token) = state.pending.pop_front() {
+                return Some((Ok(token), state));
+            }
+            if state.done {
+                return None;
+            }
+            match state.response.chunk().await {
+                Ok(Some(bytes)) => {
+                    state.buffer.extend_from_slice(&bytes);
+                    while let Some(pos) = state.buffer.iter().position(|b| *b == b'\n') {
+                        let line: Vec<u8> = state.buffer.drain(..=pos).collect();
+                        match parse_sse_line(String::from_utf8_lossy(&line).trim()) {
+                            SseLine::Token(token) => state.pending.push_back(token),
+                            SseLine::Done => state.done = true,
+                            SseLine::Skip => {}
+                        }
+                    }
+                }
+                Ok(None) => state.done = true,
+                Err(e) => {
+                    state.done = true;
+                    return Some((Err(e.into()), state));
+                }
+            }
+        }
+    }))
 }
 
 /// Ollama provider
@@ -401,11 +574,56 @@ pub struct Anthropic {
 struct AnthropicRequest {
     model: String,
     max_tokens: u32,
-    system: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    system: Vec<AnthropicSystemBlock>,
     messages: Vec<Message>,
     temperature: f32,
 }
 
+#[derive(Serialize)]
+struct AnthropicSystemBlock {
+    r#type: &'static str,
+    text: String,
+    /// `{"type": "ephemeral"}` marks a prompt-cache breakpoint
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cache_control: Option<serde_json::Value>,
+}
+
+impl AnthropicSystemBlock {
+    fn new(text: &str, cacheable: bool) -> Self {
+        Self {
+            r#type: "text",
+            text: text.into(),
+            cache_control: cacheable.then(|| serde_json::json!({"type": "ephemeral"})),
+        }
+    }
+}
+
+/// Anthropic request body for a conversation; system messages become system
+/// blocks, keeping any cache breakpoints
+fn anthropic_history_request(model: &str, messages: &[ChatMessage]) -> AnthropicRequest {
+    let system = messages.iter()
+        .filter(|m| m.role == "system")
+        .map(|m| AnthropicSystemBlock::new(&m.content, m.cacheable))
+        .collect();
+
+    let non_system: Vec<Message> = messages.iter()
+        .filter(|m| m.role != "system")
+        .map(|m| Message {
+            role: m.role.clone(),
+            content: m.content.clone(),
+        })
+        .collect();
+
+    AnthropicRequest {
+        model: model.into(),
+        max_tokens: 65536,  // Large responses - half of typical 131k context for big generations
+        system,
+        messages: non_system,
+        temperature: 0.3,
+    }
+}
+
 #[derive(Deserialize)]
 struct AnthropicResponse {
     content: Vec<ContentBlock>,
@@ -440,6 +658,10 @@ impl LlmProvider for Anthropic {
         "anthropic"
     }
 
+    fn context_window(&self) -> usize {
+        200_000
+    }
+
     fn is_available(&self) -> bool {
         !self.api_key.is_empty()
     }
@@ -448,7 +670,7 @@ impl LlmProvider for Anthropic {
         let request = AnthropicRequest {
             model: self.model.clone(),
             max_tokens: 2000,
-            system: system.into(),
+            system: if system.is_empty() { vec![] } else { vec![AnthropicSystemBlock::new(system, false)] },
             messages: vec![Message {
                 role: "user".into(),
                 content: user.into(),
@@ -481,27 +703,7 @@ impl LlmProvider for Anthropic {
     }
 
     async fn generate_with_history(&self, messages: &[ChatMessage]) -> Result<String, ProviderError> {
-        // Extract system message and user/assistant messages
-        let system = messages.iter()
-            .find(|m| m.role == "system")
-            .map(|m| m.content.clone())
-            .unwrap_or_default();
-
-        let non_system: Vec<Message> = messages.iter()
-            .filter(|m| m.role != "system")
-            .map(|m| Message {
-                role: m.role.clone(),
-                content: m.content.clone(),
-            })
-            .collect();
-
-        let request = AnthropicRequest {
-            model: self.model.clone(),
-            max_tokens: 65536,  // Large responses - half of typical 131k context for big generations
-            system,
-            messages: non_system,
-            temperature: 0.3,
-        };
+        let request = anthropic_history_request(&self.model, messages);
 
         let response = self
             .client
@@ -528,6 +730,14 @@ impl LlmProvider for Anthropic {
     }
 }
 
+/// Endpoint and model chosen by `ProviderChain::select_for_task`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModelSelection {
+    pub url: String,
+    pub model: String,
+    pub tier: QualityTier,
+}
+
 /// Provider chain with fallback
 pub struct ProviderChain {
     providers: Vec<Box<dyn LlmProvider>>,
@@ -618,6 +828,38 @@ impl ProviderChain {
         None
     }
 
+    /// Pick the endpoint and model that suit a task: the cheapest model that
+    /// meets the tier the task calls for (`QualityTier::for_task`), or the
+    /// strongest one available if none does. Models are those each reachable
+    /// endpoint lists, classified with `tiers::get_model_tier`; unsafe ones
+    /// are never picked.
+    pub fn select_for_task(&self, task_hint: &str) -> Option<ModelSelection> {
+        let candidates: Vec<(String, String)> = self
+            .provider_urls
+            .iter()
+            .filter_map(|(url, configured)| {
+                let models = list_models(url)?;
+                let models = if models.is_empty() { vec![configured.clone()] } else { models };
+                Some(models.into_iter().map(move |model| (url.clone(), model)))
+            })
+            .flatten()
+            .collect();
+        choose_model(candidates, QualityTier::for_task(task_hint))
+    }
+
+    /// Move the provider named `name` to the front of the chain. Returns
+    /// false if the chain has no such provider.
+    pub fn prefer(&mut self, name: &str) -> bool {
+        match self.providers.iter().position(|p| p.name() == name) {
+            Some(index) => {
+                let provider = self.providers.remove(index);
+                self.providers.insert(0, provider);
+                true
+            }
+            None => false,
+        }
+    }
+
     pub fn get_available(&self) -> Vec<&str> {
         self.providers
             .iter()
@@ -627,12 +869,68 @@ impl ProviderChain {
     }
 }
 
+/// Model IDs an OpenAI-compatible endpoint serves, or `None` if it is down
+fn list_models(url: &str) -> Option<Vec<String>> {
+    let models_url = format!("{}/v1/models", url);
+    let handle = std::thread::spawn(move || {
+        let response = reqwest::blocking::Client::new()
+            .get(&models_url)
+            .timeout(std::time::Duration::from_secs(2))
+            .send()
+            .ok()
+            .filter(|r| r.status().is_success())?;
+        let body: serde_json::Value = response.json().ok()?;
+        Some(
+            body["data"]
+                .as_array()
+                .map(|models| {
+                    models
+                        .iter()
+                        .filter_map(|m| m["id"].as_str().map(str::to_string))
+                        .collect()
+                })
+                .unwrap_or_default(),
+        )
+    });
+    handle.join().ok().flatten()
+}
+
+/// Cheapest `(url, model)` candidate that meets `need`, else the strongest.
+/// Ties go to the earlier candidate, i.e. the one higher in the chain.
+/// Unsafe models are left out.
+fn choose_model(candidates: Vec<(String, String)>, need: QualityTier) -> Option<ModelSelection> {
+    let classified: Vec<(u8, ModelSelection)> = candidates
+        .into_iter()
+        .filter_map(|(url, model)| {
+            let tier = get_model_tier(&model);
+            Some((tier.rank()?, ModelSelection { tier, url, model }))
+        })
+        .collect();
+    let need = need.rank().unwrap_or(0);
+    let sufficient = classified
+        .iter()
+        .filter(|(rank, _)| *rank >= need)
+        .min_by_key(|(rank, _)| *rank);
+    sufficient
+        .or_else(|| classified.iter().rev().max_by_key(|(rank, _)| *rank))
+        .map(|(_, selection)| selection.clone())
+}
+
 #[async_trait]
 impl LlmProvider for ProviderChain {
     fn name(&self) -> &str {
         "chain"
     }
 
+    /// Smallest window in the chain, since any provider may end up serving the request
+    fn context_window(&self) -> usize {
+        self.providers
+            .iter()
+            .map(|p| p.context_window())
+            .min()
+            .unwrap_or(DEFAULT_CONTEXT_WINDOW)
+    }
+
     fn is_available(&self) -> bool {
         self.providers.iter().any(|p| p.is_available())
     }
@@ -682,6 +980,30 @@ impl LlmProvider for ProviderChain {
             Err(ProviderError::Api(errors.join("; ")))
         }
     }
+
+    async fn generate_stream_with_history(&self, messages: &[ChatMessage]) -> Result<TokenStream, ProviderError> {
+        let mut errors = vec![];
+
+        // Fallback only covers starting the stream; errors mid-stream surface to the caller
+        for provider in &self.providers {
+            if !provider.is_available() {
+                continue;
+            }
+
+            match provider.generate_stream_with_history(messages).await {
+                Ok(stream) => return Ok(stream),
+                Err(e) => {
+                    errors.push(format!("{}: {}", provider.name(), e));
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            Err(ProviderError::NoProviders)
+        } else {
+            Err(ProviderError::Api(errors.join("; ")))
+        }
+    }
 }
 
 impl Default for ProviderChain {
@@ -689,3 +1011,95 @@ impl Default for ProviderChain {
         Self::default_chain()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_choose_model_matches_task() {
+        let candidates = || {
+            vec![
+                ("http://localhost:1234".to_string(), "llama-3.2-3b-instruct".to_string()),
+                ("http://localhost:1234".to_string(), "mistral-7b-instruct".to_string()),
+                ("http://localhost:1234".to_string(), "qwen2.5-coder-32b".to_string()),
+                ("https://api.anthropic.com".to_string(), "claude-sonnet-4-20250514".to_string()),
+            ]
+        };
+        let pick = |task: &str| choose_model(candidates(), QualityTier::for_task(task)).map(|s| (s.model, s.tier));
+
+        assert_eq!(
+            pick("write code to parse the nginx access log"),
+            Some(("claude-sonnet-4-20250514".into(), QualityTier::Exceptional))
+        );
+        assert_eq!(
+            pick("plan the migration to postgres"),
+            Some(("qwen2.5-coder-32b".into(), QualityTier::Capable))
+        );
+        // Cheapest safe model; the 3b one is unsafe
+        assert_eq!(pick("what time is it"), Some(("mistral-7b-instruct".into(), QualityTier::Limited)));
+
+        // Nothing strong enough: the strongest there is
+        let local = vec![("http://localhost:1234".to_string(), "mistral-7b-instruct".to_string())];
+        assert_eq!(
+            choose_model(local, QualityTier::Exceptional).map(|s| s.tier),
+            Some(QualityTier::Limited)
+        );
+        let unsafe_only = vec![("http://localhost:1234".to_string(), "tinyllama".to_string())];
+        assert_eq!(choose_model(unsafe_only, QualityTier::Limited), None);
+        assert_eq!(choose_model(Vec::new(), QualityTier::Limited), None);
+    }
+
+    #[test]
+    fn test_parse_sse_line() {
+        assert_eq!(
+            parse_sse_line(r#"data: {"choices":[{"delta":{"content":"{\"act"}}]}"#),
+            SseLine::Token("{\"act".into())
+        );
+        assert_eq!(parse_sse_line(r#"data: {"choices":[{"delta":{"role":"assistant"}}]}"#), SseLine::Skip);
+        assert_eq!(parse_sse_line("data: [DONE]"), SseLine::Done);
+        assert_eq!(parse_sse_line(": keep-alive"), SseLine::Skip);
+    }
+
+    #[test]
+    fn test_anthropic_request_marks_cacheable_system_prompt() {
+        let messages = vec![
+            ChatMessage::system("planning prompt").cacheable(),
+            ChatMessage::system("[2 earlier message(s) omitted]"),
+            ChatMessage::user("list files"),
+        ];
+        let body = serde_json::to_value(anthropic_history_request("claude", &messages)).unwrap();
+
+        assert_eq!(body["system"][0]["text"], "planning prompt");
+        assert_eq!(body["system"][0]["cache_control"]["type"], "ephemeral");
+        assert!(body["system"][1].get("cache_control").is_none());
+        assert_eq!(body["messages"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_fit_to_window() {
+        // 40 chars = 10 tokens + 4 framing per message
+        let text = "x".repeat(40);
+        let messages = vec![
+            ChatMessage::system(&text),
+            ChatMessage::user(&text),
+            ChatMessage::assistant(&text),
+            ChatMessage::user(&text),
+        ];
+        assert_eq!(estimate_tokens(&messages), 56);
+
+        let (kept, dropped) = fit_to_window(&messages, 100, 40);
+        assert!(dropped.is_empty());
+        assert_eq!(kept.len(), 4);
+
+        let (kept, dropped) = fit_to_window(&messages, 100, 60);
+        assert_eq!(dropped, vec![1, 2]);
+        assert_eq!(kept.len(), 2);
+        assert_eq!(kept[0].role, "system");
+
+        // Pinned messages are kept even when they alone overflow
+        let (kept, dropped) = fit_to_window(&messages, 10, 0);
+        assert_eq!(dropped, vec![1, 2]);
+        assert_eq!(kept.len(), 2);
+    }
+}
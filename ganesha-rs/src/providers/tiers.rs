@@ -0,0 +1,203 @@
+//! Model quality tiers
+//!
+//! Classification of models by their capability for agentic tasks, ported
+//! from the ganesha4 provider table (`ganesha-providers/src/tiers.rs`):
+//!
+//! - **Exceptional**: best models for complex, multi-step tasks
+//! - **Capable**: good for most tasks with occasional issues
+//! - **Limited**: works for simple tasks, may struggle with complex ones
+//! - **Unsafe**: may produce dangerous or incorrect commands
+//!
+//! Known names are matched on token boundaries and the longest match wins,
+//! so `gpt-4o-mini` is not mistaken for `gpt-4o`. Unlisted local models are
+//! judged by their parameter count (`32b`, `8x7b`).
+
+use std::fmt;
+
+/// Model quality tier. Separate from `core::config::ModelTier`, which is
+/// the provider slot a configured model fills.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum QualityTier {
+    /// Best for agentic tasks
+    Exceptional,
+    /// Good for most tasks
+    Capable,
+    /// Simple tasks only
+    Limited,
+    /// May be dangerous
+    Unsafe,
+    /// Unknown model
+    Unknown,
+}
+
+impl QualityTier {
+    /// Position on the capability scale, lowest first. Unknown models are
+    /// trusted with simple tasks only; unsafe ones with nothing.
+    pub fn rank(self) -> Option<u8> {
+        match self {
+            Self::Unsafe => None,
+            Self::Limited | Self::Unknown => Some(1),
+            Self::Capable => Some(2),
+            Self::Exceptional => Some(3),
+        }
+    }
+
+    /// Tier a task calls for, judged from its wording: writing code wants an
+    /// exceptional model, a quick question the cheapest that is safe
+    pub fn for_task(task_hint: &str) -> Self {
+        const EXCEPTIONAL: &[&str] = &[
+            "code", "coding", "program", "implement", "refactor", "debug", "function", "script",
+            "algorithm", "architect", "compile", "bug", "test",
+        ];
+        const CAPABLE: &[&str] = &[
+            "plan", "analy", "explain", "compare", "research", "investigat", "optimi", "migrat",
+            "review", "design", "configur", "troubleshoot", "why",
+        ];
+        const TRIVIAL: &[&str] = &["what time", "what date", "what day", "hello", "thanks", "thank you"];
+
+        let hint = task_hint.to_lowercase();
+        let words: Vec<&str> = hint.split(|c: char| !c.is_alphanumeric()).filter(|w| !w.is_empty()).collect();
+        let mentions = |stems: &[&str]| words.iter().any(|w| stems.iter().any(|s| w.starts_with(s)));
+
+        if mentions(EXCEPTIONAL) {
+            Self::Exceptional
+        } else if mentions(CAPABLE) {
+            Self::Capable
+        } else if TRIVIAL.iter().any(|p| hint.contains(p)) || words.len() <= 3 {
+            Self::Limited
+        } else {
+            Self::Capable
+        }
+    }
+}
+
+impl fmt::Display for QualityTier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Exceptional => write!(f, "exceptional"),
+            Self::Capable => write!(f, "capable"),
+            Self::Limited => write!(f, "limited"),
+            Self::Unsafe => write!(f, "unsafe"),
+            Self::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// Known model tiers
+const MODEL_TIERS: &[(&str, QualityTier)] = &[
+    // Exceptional tier - best for agentic tasks
+    ("claude-3-5-sonnet", QualityTier::Exceptional),
+    ("claude-sonnet-4", QualityTier::Exceptional),
+    ("claude-opus-4", QualityTier::Exceptional),
+    ("claude-3-opus", QualityTier::Exceptional),
+    ("gpt-4o", QualityTier::Exceptional),
+    ("gpt-4-turbo", QualityTier::Exceptional),
+    ("o1", QualityTier::Exceptional),
+    ("o1-preview", QualityTier::Exceptional),
+    ("gemini-2.0-pro", QualityTier::Exceptional),
+    ("gemini-1.5-pro", QualityTier::Exceptional),
+    ("deepseek-v3", QualityTier::Exceptional),
+    ("qwen-2.5-72b", QualityTier::Exceptional),
+    ("llama-3.1-405b", QualityTier::Exceptional),
+    // Capable tier
+    ("gpt-4o-mini", QualityTier::Capable),
+    ("o3-mini", QualityTier::Capable),
+    ("claude-3-5-haiku", QualityTier::Capable),
+    ("claude-3-haiku", QualityTier::Capable),
+    ("llama-3.1-70b", QualityTier::Capable),
+    ("mistral-large", QualityTier::Capable),
+    ("qwen-2.5-32b", QualityTier::Capable),
+    ("gemini-2.0-flash", QualityTier::Capable),
+    ("gemini-1.5-flash", QualityTier::Capable),
+    ("deepseek-coder", QualityTier::Capable),
+    // Limited tier
+    ("llama-3.1-8b", QualityTier::Limited),
+    ("mistral-7b", QualityTier::Limited),
+    ("phi-3", QualityTier::Limited),
+    ("qwen-2.5-7b", QualityTier::Limited),
+    ("gemma-2-9b", QualityTier::Limited),
+    // Unsafe tier - very small or untuned models
+    ("phi-2", QualityTier::Unsafe),
+    ("tinyllama", QualityTier::Unsafe),
+];
+
+/// Get the tier for a model by ID
+pub fn get_model_tier(model_id: &str) -> QualityTier {
+    let lower = model_id.to_lowercase();
+
+    let known = MODEL_TIERS
+        .iter()
+        .filter(|(pattern, _)| contains_token(&lower, pattern))
+        .max_by_key(|(pattern, _)| pattern.len());
+    if let Some(&(_, tier)) = known {
+        return tier;
+    }
+
+    match parameter_billions(&lower) {
+        Some(b) if b >= 300.0 => QualityTier::Exceptional,
+        Some(b) if b >= 30.0 => QualityTier::Capable,
+        Some(b) if b >= 4.0 => QualityTier::Limited,
+        Some(_) => QualityTier::Unsafe,
+        None => QualityTier::Unknown,
+    }
+}
+
+/// Whether `pattern` occurs in `name` as whole tokens: not preceded or
+/// followed by a letter or digit (`mini` is not in `gemini`)
+fn contains_token(name: &str, pattern: &str) -> bool {
+    name.match_indices(pattern).any(|(start, _)| {
+        let before = name[..start].chars().next_back();
+        let after = name[start + pattern.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
+/// Parameter count from a `32b` / `3.8b` / `8x7b` token of the name
+fn parameter_billions(name: &str) -> Option<f32> {
+    name.split(|c: char| !(c.is_ascii_alphanumeric() || c == '.'))
+        .filter_map(|token| {
+            let size = token.strip_suffix('b')?;
+            match size.split_once('x') {
+                Some((experts, each)) => Some(experts.parse::<f32>().ok()? * each.parse::<f32>().ok()?),
+                None => size.parse::<f32>().ok(),
+            }
+        })
+        .reduce(f32::max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_known_models() {
+        assert_eq!(get_model_tier("claude-3-5-sonnet-20241022"), QualityTier::Exceptional);
+        assert_eq!(get_model_tier("gpt-4o"), QualityTier::Exceptional);
+        assert_eq!(get_model_tier("gpt-4o-mini-2024-07-18"), QualityTier::Capable);
+        assert_eq!(get_model_tier("o3-mini"), QualityTier::Capable);
+        assert_eq!(get_model_tier("gemini-2.0-flash-exp"), QualityTier::Capable);
+        assert_eq!(get_model_tier("gemini-1.5-pro-002"), QualityTier::Exceptional);
+        assert_eq!(get_model_tier("meta-llama/llama-3.1-8b-instruct"), QualityTier::Limited);
+        assert_eq!(get_model_tier("tinyllama-1.1b-chat"), QualityTier::Unsafe);
+    }
+
+    #[test]
+    fn test_names_match_on_token_boundaries() {
+        // Neither `o1` nor a size hides inside another token
+        assert_eq!(get_model_tier("gemini-exp-1206"), QualityTier::Unknown);
+        assert_eq!(get_model_tier("foo1-model"), QualityTier::Unknown);
+        assert_eq!(get_model_tier("qwen2.5-coder-32b-instruct"), QualityTier::Capable);
+        assert_eq!(get_model_tier("mixtral-8x7b"), QualityTier::Capable);
+        assert_eq!(get_model_tier("llama-3.2-3b-instruct"), QualityTier::Unsafe);
+        assert_eq!(get_model_tier("default"), QualityTier::Unknown);
+    }
+
+    #[test]
+    fn test_task_tiers() {
+        assert_eq!(QualityTier::for_task("write code to parse the nginx log"), QualityTier::Exceptional);
+        assert_eq!(QualityTier::for_task("plan the database migration"), QualityTier::Capable);
+        assert_eq!(QualityTier::for_task("what time is it"), QualityTier::Limited);
+        // "decode" is not "code"
+        assert_eq!(QualityTier::for_task("decode this base64 string for me please"), QualityTier::Capable);
+    }
+}
@@ -0,0 +1,121 @@
+//! Quarantine - actions parked pending human release
+//!
+//! A `Verdict::Quarantine` action is neither run nor dropped: it waits in the
+//! Sentinel's queue with the analysis that held it until someone releases or
+//! rejects it. Executors check the queue with `Sentinel::claim` before
+//! running an action, so a released action runs the next time it comes up
+//! and a rejected one keeps failing without being analyzed again.
+
+use std::sync::atomic::Ordering;
+use std::time::Instant;
+
+use super::{ActionContext, Sentinel, SentinelAnalysis};
+
+/// Where a quarantined action stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QuarantineStatus {
+    /// Waiting for a human decision
+    Pending,
+    /// Released; runs once the next time it is claimed
+    Released,
+    /// Rejected; stays blocked
+    Rejected,
+}
+
+/// An action held by the Sentinel
+#[derive(Debug, Clone)]
+pub struct QuarantinedAction {
+    /// Queue ID (`q-1`, `q-2`, ...)
+    pub id: String,
+    /// The held action
+    pub action: ActionContext,
+    /// Why it was held
+    pub analysis: SentinelAnalysis,
+    /// Current decision
+    pub status: QuarantineStatus,
+    /// When it was held
+    pub held_at: Instant,
+}
+
+impl QuarantinedAction {
+    fn holds(&self, action: &ActionContext) -> bool {
+        self.action.action_type == action.action_type && self.action.content == action.content
+    }
+}
+
+impl Sentinel {
+    /// Hold an action until it is released or rejected, returning its queue
+    /// ID. An action already waiting keeps its existing entry.
+    pub fn quarantine(&self, action: &ActionContext, analysis: SentinelAnalysis) -> String {
+        let mut queue = self.quarantine.write().unwrap();
+        if let Some(held) = queue
+            .iter()
+            .find(|q| q.status == QuarantineStatus::Pending && q.holds(action))
+        {
+            return held.id.clone();
+        }
+
+        let id = format!("q-{}", self.next_quarantine_id.fetch_add(1, Ordering::SeqCst) + 1);
+        queue.push(QuarantinedAction {
+            id: id.clone(),
+            action: action.clone(),
+            analysis,
+            status: QuarantineStatus::Pending,
+            held_at: Instant::now(),
+        });
+        id
+    }
+
+    /// Actions waiting for a decision, oldest first
+    pub fn pending(&self) -> Vec<QuarantinedAction> {
+        self.quarantine
+            .read()
+            .unwrap()
+            .iter()
+            .filter(|q| q.status == QuarantineStatus::Pending)
+            .cloned()
+            .collect()
+    }
+
+    /// Let a pending action run. Returns false if `id` is not pending.
+    pub fn release(&self, id: &str) -> bool {
+        self.decide(id, QuarantineStatus::Released)
+    }
+
+    /// Refuse a pending action. Returns false if `id` is not pending.
+    pub fn reject(&self, id: &str) -> bool {
+        self.decide(id, QuarantineStatus::Rejected)
+    }
+
+    fn decide(&self, id: &str, status: QuarantineStatus) -> bool {
+        let mut queue = self.quarantine.write().unwrap();
+        match queue
+            .iter_mut()
+            .find(|q| q.id == id && q.status == QuarantineStatus::Pending)
+        {
+            Some(held) => {
+                held.status = status;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Look up the quarantine entry for an action about to run. A released
+    /// entry is removed, so the release covers a single run; pending and
+    /// rejected entries stay queued.
+    pub fn claim(&self, action: &ActionContext) -> Option<QuarantinedAction> {
+        let mut queue = self.quarantine.write().unwrap();
+        let index = queue.iter().position(|q| q.holds(action))?;
+        if queue[index].status == QuarantineStatus::Released {
+            Some(queue.remove(index))
+        } else {
+            Some(queue[index].clone())
+        }
+    }
+
+    /// Forget all quarantined actions, including rejected ones
+    pub fn clear_quarantine(&self) {
+        self.quarantine.write().unwrap().clear();
+    }
+}
@@ -0,0 +1,147 @@
+//! User-defined Sentinel rules
+//!
+//! Operators can add their own dangerous-command patterns on top of the
+//! built-in checks, or whitelist commands a broad check would otherwise flag.
+//!
+//! Rules are checked in the order they were added and the first match wins,
+//! so whitelist entries must come before the broader rules they carve out of.
+//!
+//! ```toml
+//! # Allow reading the project's .env.example despite the credential check
+//! [[rules]]
+//! name = "env-example"
+//! pattern = '^cat \.env\.example$'
+//! action = "Allow"
+//!
+//! [[rules]]
+//! name = "terraform-destroy"
+//! pattern = '(?i)terraform\s+destroy'
+//! category = "SystemCorruption"
+//! severity = "Critical"
+//! action = "Halt"
+//! ```
+
+use std::path::Path;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::{Severity, ThreatCategory, Verdict};
+
+/// A custom threat pattern
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SentinelRule {
+    /// Rule name, reported in `SentinelAnalysis::rule`
+    pub name: String,
+    /// Regex matched against the action content (the command line for shell
+    /// commands). Matching is case-sensitive unless the pattern uses `(?i)`.
+    pub pattern: String,
+    /// Threat category reported when the rule fires
+    #[serde(default = "default_category")]
+    pub category: ThreatCategory,
+    /// Severity reported when the rule fires
+    #[serde(default = "default_severity")]
+    pub severity: Severity,
+    /// Verdict when the rule fires. `Allow` whitelists the action and skips
+    /// the built-in pattern checks.
+    #[serde(default = "default_action")]
+    pub action: Verdict,
+}
+
+fn default_category() -> ThreatCategory {
+    ThreatCategory::Unknown
+}
+
+fn default_severity() -> Severity {
+    Severity::Medium
+}
+
+fn default_action() -> Verdict {
+    Verdict::Warn
+}
+
+impl SentinelRule {
+    /// Create a rule that flags matching actions
+    pub fn new(
+        name: impl Into<String>,
+        pattern: impl Into<String>,
+        category: ThreatCategory,
+        severity: Severity,
+        action: Verdict,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            pattern: pattern.into(),
+            category,
+            severity,
+            action,
+        }
+    }
+
+    /// Create a whitelist rule that lets matching actions through
+    pub fn allow(name: impl Into<String>, pattern: impl Into<String>) -> Self {
+        Self::new(name, pattern, ThreatCategory::Unknown, Severity::Low, Verdict::Allow)
+    }
+
+    /// Whether this rule whitelists what it matches
+    pub fn is_whitelist(&self) -> bool {
+        self.action == Verdict::Allow
+    }
+}
+
+/// Errors from adding or loading rules
+#[derive(Debug, thiserror::Error)]
+pub enum RuleError {
+    #[error("Invalid pattern in rule '{name}': {source}")]
+    InvalidPattern {
+        name: String,
+        #[source]
+        source: regex::Error,
+    },
+
+    #[error("Failed to read rules file: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Failed to parse rules file: {0}")]
+    Parse(#[from] toml::de::Error),
+}
+
+/// Layout of a rules file
+#[derive(Debug, Default, Deserialize)]
+struct RulesFile {
+    #[serde(default)]
+    rules: Vec<SentinelRule>,
+}
+
+/// A rule with its pattern compiled
+#[derive(Debug, Clone)]
+pub(super) struct CompiledRule {
+    pub(super) rule: SentinelRule,
+    regex: Regex,
+}
+
+impl CompiledRule {
+    pub(super) fn compile(rule: SentinelRule) -> Result<Self, RuleError> {
+        let regex = Regex::new(&rule.pattern).map_err(|source| RuleError::InvalidPattern {
+            name: rule.name.clone(),
+            source,
+        })?;
+        Ok(Self { rule, regex })
+    }
+
+    pub(super) fn is_match(&self, content: &str) -> bool {
+        self.regex.is_match(content)
+    }
+}
+
+/// Parse and compile the `[[rules]]` of a TOML document, keeping file order.
+/// Nothing is returned unless every rule compiles.
+pub(super) fn parse_rules(content: &str) -> Result<Vec<CompiledRule>, RuleError> {
+    let file: RulesFile = toml::from_str(content)?;
+    file.rules.into_iter().map(CompiledRule::compile).collect()
+}
+
+/// Read and compile a rules file
+pub(super) fn load_rules(path: &Path) -> Result<Vec<CompiledRule>, RuleError> {
+    parse_rules(&std::fs::read_to_string(path)?)
+}
@@ -0,0 +1,267 @@
+//! Vision provider detection for the REPL
+//!
+//! Candidates are tried in order: the vision tier saved in
+//! `~/.ganesha/config.json`, then a local LM Studio, then Anthropic if
+//! `ANTHROPIC_API_KEY` is set. Endpoints are health-checked concurrently with
+//! a short timeout, and the outcome is cached in
+//! `~/.ganesha/vision_cache.json` so launches within the TTL skip the probe.
+//! Finding nothing is only trusted briefly, so starting LM Studio is noticed
+//! on the next launch. The cache is keyed on the candidate list, so editing
+//! the config or setting the API key invalidates it.
+
+use crate::workflow::VisionConfig;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// A provider that may serve vision requests
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VisionCandidate {
+    pub provider: String,
+    pub model: String,
+    pub description: String,
+    /// Base URL to health-check; `None` for providers that need no check
+    pub check_url: Option<String>,
+}
+
+impl VisionCandidate {
+    fn into_config(self) -> VisionConfig {
+        VisionConfig {
+            enabled: true,
+            primary_has_vision: false,
+            cloud_vision_available: true,
+            cloud_vision_provider: Some(self.provider),
+            cloud_vision_model: Some(self.model),
+            description: Some(self.description),
+        }
+    }
+}
+
+/// What the last probe found
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ProbeCache {
+    checked_at: DateTime<Utc>,
+    candidates: Vec<VisionCandidate>,
+    vision: Option<VisionConfig>,
+}
+
+/// Finds a working vision provider
+pub struct VisionProbe {
+    pub config_path: PathBuf,
+    pub cache_path: PathBuf,
+    /// How long a cached result is trusted
+    pub ttl: Duration,
+    /// How long a cached "no vision provider" result is trusted
+    pub negative_ttl: Duration,
+    /// Per-endpoint health check timeout
+    pub timeout: Duration,
+    pub anthropic_key: bool,
+}
+
+impl Default for VisionProbe {
+    fn default() -> Self {
+        let dir = dirs::home_dir()
+            .unwrap_or_else(|| PathBuf::from("."))
+            .join(".ganesha");
+        Self {
+            config_path: dir.join("config.json"),
+            cache_path: dir.join("vision_cache.json"),
+            ttl: Duration::from_secs(15 * 60),
+            negative_ttl: Duration::from_secs(30),
+            timeout: Duration::from_millis(500),
+            anthropic_key: std::env::var("ANTHROPIC_API_KEY").is_ok(),
+        }
+    }
+}
+
+impl VisionProbe {
+    /// The vision provider to use, or `None` if nothing is available
+    pub async fn detect(&self) -> Option<VisionConfig> {
+        let config = std::fs::read_to_string(&self.config_path)
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or(serde_json::Value::Null);
+        let candidates = vision_candidates(&config, self.anthropic_key);
+
+        if let Some(cached) = self.cached(&candidates) {
+            return cached;
+        }
+
+        let checks = candidates.iter().map(|c| async move {
+            match &c.check_url {
+                Some(url) => is_online(url, self.timeout).await,
+                None => true,
+            }
+        });
+        let online = futures::future::join_all(checks).await;
+        let vision = choose(&candidates, &online);
+
+        self.store(candidates, vision.clone());
+        vision
+    }
+
+    /// The cached result, if it is fresh and for the same candidates
+    fn cached(&self, candidates: &[VisionCandidate]) -> Option<Option<VisionConfig>> {
+        let content = std::fs::read_to_string(&self.cache_path).ok()?;
+        let cache: ProbeCache = serde_json::from_str(&content).ok()?;
+        let age = Utc::now().signed_duration_since(cache.checked_at).to_std().ok()?;
+        let ttl = if cache.vision.is_some() { self.ttl } else { self.negative_ttl };
+        (age < ttl && cache.candidates == candidates).then_some(cache.vision)
+    }
+
+    fn store(&self, candidates: Vec<VisionCandidate>, vision: Option<VisionConfig>) {
+        let cache = ProbeCache { checked_at: Utc::now(), candidates, vision };
+        if let Ok(json) = serde_json::to_string_pretty(&cache) {
+            if let Some(dir) = self.cache_path.parent() {
+                let _ = std::fs::create_dir_all(dir);
+            }
+            let _ = std::fs::write(&self.cache_path, json);
+        }
+    }
+}
+
+/// Vision providers to try, in order of preference, given the saved
+/// `config.json` contents
+pub fn vision_candidates(config: &serde_json::Value, anthropic_key: bool) -> Vec<VisionCandidate> {
+    let mut candidates = Vec::new();
+
+    let vision = &config["tiers"]["vision"];
+    let endpoint = vision["endpoint"].as_str().unwrap_or_default();
+    let model = vision["model"].as_str().unwrap_or_default();
+    let base_url = config["endpoints"][endpoint]["base_url"].as_str().unwrap_or_default();
+    if !endpoint.is_empty() && !model.is_empty() && !base_url.is_empty() {
+        let description = vision["description"].as_str().unwrap_or("Vision");
+        candidates.push(VisionCandidate {
+            provider: endpoint.to_string(),
+            model: model.to_string(),
+            description: format!("{} ({})", description, model),
+            check_url: Some(base_url.to_string()),
+        });
+    }
+
+    candidates.push(VisionCandidate {
+        provider: "lmstudio".to_string(),
+        model: "default".to_string(),
+        description: "LM Studio (local)".to_string(),
+        check_url: Some("http://localhost:1234".to_string()),
+    });
+
+    if anthropic_key {
+        candidates.push(VisionCandidate {
+            provider: "anthropic".to_string(),
+            model: "claude-sonnet-4-5-20250514".to_string(),
+            description: "Anthropic Claude (fallback)".to_string(),
+            check_url: None,
+        });
+    }
+
+    candidates
+}
+
+/// First candidate whose health check passed
+fn choose(candidates: &[VisionCandidate], online: &[bool]) -> Option<VisionConfig> {
+    candidates
+        .iter()
+        .zip(online)
+        .find(|(_, online)| **online)
+        .map(|(candidate, _)| candidate.clone().into_config())
+}
+
+async fn is_online(url: &str, timeout: Duration) -> bool {
+    let Ok(client) = reqwest::Client::builder().timeout(timeout).build() else {
+        return false;
+    };
+    match client.get(format!("{}/v1/models", url)).send().await {
+        Ok(r) => r.status().is_success(),
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    /// A probe reading `config.json` from `dir` and caching there too
+    fn probe_in(dir: &Path) -> VisionProbe {
+        VisionProbe {
+            config_path: dir.join("config.json"),
+            cache_path: dir.join("vision_cache.json"),
+            anthropic_key: true,
+            ..VisionProbe::default()
+        }
+    }
+
+    fn saved_config() -> serde_json::Value {
+        serde_json::json!({
+            "tiers": { "vision": { "endpoint": "gpu-box", "model": "qwen2-vl-7b", "description": "Qwen VL" } },
+            "endpoints": { "gpu-box": { "base_url": "http://192.168.1.20:1234" } }
+        })
+    }
+
+    #[test]
+    fn test_candidates_follow_saved_config() {
+        let candidates = vision_candidates(&saved_config(), true);
+        let providers: Vec<&str> = candidates.iter().map(|c| c.provider.as_str()).collect();
+        assert_eq!(providers, ["gpu-box", "lmstudio", "anthropic"]);
+        assert_eq!(candidates[0].description, "Qwen VL (qwen2-vl-7b)");
+        assert_eq!(candidates[0].check_url.as_deref(), Some("http://192.168.1.20:1234"));
+
+        // Missing or partial config leaves just the fallbacks
+        let providers: Vec<String> = vision_candidates(&serde_json::Value::Null, false)
+            .into_iter()
+            .map(|c| c.provider)
+            .collect();
+        assert_eq!(providers, ["lmstudio"]);
+    }
+
+    #[test]
+    fn test_choose_skips_offline_endpoints() {
+        let candidates = vision_candidates(&saved_config(), true);
+        let pick = |online: &[bool]| choose(&candidates, online).and_then(|v| v.cloud_vision_provider);
+
+        assert_eq!(pick(&[true, true, true]).as_deref(), Some("gpu-box"));
+        assert_eq!(pick(&[false, true, true]).as_deref(), Some("lmstudio"));
+        assert_eq!(pick(&[false, false, true]).as_deref(), Some("anthropic"));
+        assert_eq!(pick(&[false, false, false]), None);
+    }
+
+    #[tokio::test]
+    async fn test_cached_result_skips_probe_until_stale() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("config.json"), saved_config().to_string()).unwrap();
+        let probe = probe_in(dir.path());
+        let candidates = vision_candidates(&saved_config(), true);
+
+        // A cached hit is returned without contacting the (unreachable) endpoint
+        let cached = choose(&candidates, &[false, true, false]);
+        probe.store(candidates.clone(), cached);
+        let vision = probe.detect().await.unwrap();
+        assert_eq!(vision.cloud_vision_provider.as_deref(), Some("lmstudio"));
+        assert!(vision.is_available());
+
+        // A different candidate list does not use it
+        assert!(probe.cached(&candidates[1..]).is_none());
+
+        // Nor does a stale entry
+        let stale = VisionProbe { ttl: Duration::ZERO, ..probe_in(dir.path()) };
+        assert!(stale.cached(&candidates).is_none());
+    }
+
+    #[test]
+    fn test_negative_result_expires_sooner() {
+        let dir = tempfile::tempdir().unwrap();
+        let candidates = vision_candidates(&saved_config(), true);
+        probe_in(dir.path()).store(candidates.clone(), None);
+
+        assert!(matches!(probe_in(dir.path()).cached(&candidates), Some(None)));
+        let expired = VisionProbe { negative_ttl: Duration::ZERO, ..probe_in(dir.path()) };
+        assert!(expired.cached(&candidates).is_none());
+
+        // A found provider is still kept for the full TTL
+        let found = choose(&candidates, &[false, true, false]);
+        expired.store(candidates.clone(), found);
+        assert!(expired.cached(&candidates).is_some_and(|v| v.is_some()));
+    }
+}
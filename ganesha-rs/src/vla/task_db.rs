@@ -16,6 +16,110 @@ use std::path::PathBuf;
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
+/// Version 1: the schema as it was before migrations were tracked
+const BASELINE_SCHEMA: &str = r#"
+    -- Top-level tasks (one per ganesha vla invocation)
+    CREATE TABLE IF NOT EXISTS tasks (
+        id TEXT PRIMARY KEY,
+        goal TEXT NOT NULL,
+        criteria TEXT NOT NULL,       -- JSON array of success criteria
+        status TEXT NOT NULL DEFAULT 'running',  -- running, success, failed, timeout, stopped
+        started_at TEXT NOT NULL,
+        ended_at TEXT,
+        total_actions INTEGER DEFAULT 0,
+        error TEXT,
+        final_screen_state TEXT
+    );
+    CREATE INDEX IF NOT EXISTS idx_tasks_status ON tasks(status);
+    CREATE INDEX IF NOT EXISTS idx_tasks_started ON tasks(started_at DESC);
+
+    -- Every action attempted within a task
+    CREATE TABLE IF NOT EXISTS actions (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        task_id TEXT NOT NULL,
+        step_num INTEGER NOT NULL,
+        intent TEXT NOT NULL,
+        action_type TEXT NOT NULL,     -- click, type, key_press, etc.
+        target_desc TEXT,              -- element description
+        target_x INTEGER,
+        target_y INTEGER,
+        text_input TEXT,               -- for type actions
+        keys_input TEXT,               -- for key_press actions
+        confidence REAL,
+        expected_result TEXT,
+        -- Outcome
+        executed INTEGER DEFAULT 0,    -- was the action actually executed
+        exec_success INTEGER DEFAULT 0,
+        exec_error TEXT,
+        exec_duration_ms INTEGER,
+        -- Before/after screen state
+        screen_before TEXT,            -- app/title/state before action
+        screen_after TEXT,             -- app/title/state after action
+        screen_changed INTEGER DEFAULT 0,  -- did the screen actually change
+        expected_achieved INTEGER DEFAULT 0,
+        -- Timestamps
+        created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+        FOREIGN KEY (task_id) REFERENCES tasks(id)
+    );
+    CREATE INDEX IF NOT EXISTS idx_actions_task ON actions(task_id, step_num);
+
+    -- Failed approaches - things that didn't work, to avoid repeating
+    CREATE TABLE IF NOT EXISTS failures (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        task_id TEXT,                  -- NULL = global failure pattern
+        context TEXT NOT NULL,         -- what screen/app state we were in
+        action_tried TEXT NOT NULL,    -- what we tried
+        what_happened TEXT NOT NULL,   -- what actually happened
+        lesson TEXT NOT NULL,          -- what to do instead
+        times_seen INTEGER DEFAULT 1,
+        created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+        FOREIGN KEY (task_id) REFERENCES tasks(id)
+    );
+    CREATE INDEX IF NOT EXISTS idx_failures_context ON failures(context);
+
+    -- Sub-steps for complex tasks (planner can decompose goals)
+    CREATE TABLE IF NOT EXISTS substeps (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        task_id TEXT NOT NULL,
+        step_order INTEGER NOT NULL,
+        description TEXT NOT NULL,
+        status TEXT NOT NULL DEFAULT 'pending',  -- pending, active, done, failed, skipped
+        completed_at TEXT,
+        FOREIGN KEY (task_id) REFERENCES tasks(id)
+    );
+    CREATE INDEX IF NOT EXISTS idx_substeps_task ON substeps(task_id, step_order);
+
+    -- Full-text search on failures for quick pattern matching
+    CREATE VIRTUAL TABLE IF NOT EXISTS failures_fts USING fts5(
+        context, action_tried, what_happened, lesson,
+        content='failures',
+        content_rowid='id'
+    );
+
+    CREATE TRIGGER IF NOT EXISTS failures_ai AFTER INSERT ON failures BEGIN
+        INSERT INTO failures_fts(rowid, context, action_tried, what_happened, lesson)
+        VALUES (NEW.id, NEW.context, NEW.action_tried, NEW.what_happened, NEW.lesson);
+    END;
+"#;
+
+/// A schema change, applied once in version order
+struct Migration {
+    version: u32,
+    description: &'static str,
+    sql: &'static str,
+}
+
+/// Every schema change, oldest first, numbered from 1 with no gaps. Append
+/// new migrations here; never edit one that has shipped.
+const MIGRATIONS: &[Migration] = &[Migration {
+    version: 1,
+    description: "baseline schema",
+    sql: BASELINE_SCHEMA,
+}];
+
+/// Newest schema version this build understands
+pub const SCHEMA_VERSION: u32 = MIGRATIONS.len() as u32;
+
 /// VLA task tracker backed by SQLite
 pub struct VlaTaskDb {
     conn: Connection,
@@ -26,17 +130,18 @@ impl VlaTaskDb {
     pub fn open() -> SqliteResult<Self> {
         let base_dir = Self::get_base_dir();
         let db_path = base_dir.join("vla_tasks.db");
-        let conn = Connection::open(&db_path)?;
-        let mut db = Self { conn };
-        db.init_schema()?;
-        Ok(db)
+        Self::from_connection(Connection::open(&db_path)?)
     }
 
     /// Open an in-memory database (for testing)
     pub fn open_memory() -> SqliteResult<Self> {
-        let conn = Connection::open_in_memory()?;
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    /// Use an existing connection, bringing its schema up to date
+    pub fn from_connection(conn: Connection) -> SqliteResult<Self> {
         let mut db = Self { conn };
-        db.init_schema()?;
+        db.migrate()?;
         Ok(db)
     }
 
@@ -47,91 +152,52 @@ impl VlaTaskDb {
         base
     }
 
-    fn init_schema(&mut self) -> SqliteResult<()> {
-        self.conn.execute_batch(r#"
-            -- Top-level tasks (one per ganesha vla invocation)
-            CREATE TABLE IF NOT EXISTS tasks (
-                id TEXT PRIMARY KEY,
-                goal TEXT NOT NULL,
-                criteria TEXT NOT NULL,       -- JSON array of success criteria
-                status TEXT NOT NULL DEFAULT 'running',  -- running, success, failed, timeout, stopped
-                started_at TEXT NOT NULL,
-                ended_at TEXT,
-                total_actions INTEGER DEFAULT 0,
-                error TEXT,
-                final_screen_state TEXT
-            );
-            CREATE INDEX IF NOT EXISTS idx_tasks_status ON tasks(status);
-            CREATE INDEX IF NOT EXISTS idx_tasks_started ON tasks(started_at DESC);
-
-            -- Every action attempted within a task
-            CREATE TABLE IF NOT EXISTS actions (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                task_id TEXT NOT NULL,
-                step_num INTEGER NOT NULL,
-                intent TEXT NOT NULL,
-                action_type TEXT NOT NULL,     -- click, type, key_press, etc.
-                target_desc TEXT,              -- element description
-                target_x INTEGER,
-                target_y INTEGER,
-                text_input TEXT,               -- for type actions
-                keys_input TEXT,               -- for key_press actions
-                confidence REAL,
-                expected_result TEXT,
-                -- Outcome
-                executed INTEGER DEFAULT 0,    -- was the action actually executed
-                exec_success INTEGER DEFAULT 0,
-                exec_error TEXT,
-                exec_duration_ms INTEGER,
-                -- Before/after screen state
-                screen_before TEXT,            -- app/title/state before action
-                screen_after TEXT,             -- app/title/state after action
-                screen_changed INTEGER DEFAULT 0,  -- did the screen actually change
-                expected_achieved INTEGER DEFAULT 0,
-                -- Timestamps
-                created_at TEXT DEFAULT CURRENT_TIMESTAMP,
-                FOREIGN KEY (task_id) REFERENCES tasks(id)
-            );
-            CREATE INDEX IF NOT EXISTS idx_actions_task ON actions(task_id, step_num);
-
-            -- Failed approaches - things that didn't work, to avoid repeating
-            CREATE TABLE IF NOT EXISTS failures (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                task_id TEXT,                  -- NULL = global failure pattern
-                context TEXT NOT NULL,         -- what screen/app state we were in
-                action_tried TEXT NOT NULL,    -- what we tried
-                what_happened TEXT NOT NULL,   -- what actually happened
-                lesson TEXT NOT NULL,          -- what to do instead
-                times_seen INTEGER DEFAULT 1,
-                created_at TEXT DEFAULT CURRENT_TIMESTAMP,
-                FOREIGN KEY (task_id) REFERENCES tasks(id)
-            );
-            CREATE INDEX IF NOT EXISTS idx_failures_context ON failures(context);
-
-            -- Sub-steps for complex tasks (planner can decompose goals)
-            CREATE TABLE IF NOT EXISTS substeps (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                task_id TEXT NOT NULL,
-                step_order INTEGER NOT NULL,
+    /// Schema version of the open database (0 if nothing has been applied)
+    pub fn current_version(&self) -> SqliteResult<u32> {
+        self.conn.query_row(
+            "SELECT COALESCE(MAX(version), 0) FROM schema_version",
+            [],
+            |r| r.get(0),
+        )
+    }
+
+    /// Apply pending migrations, each in its own transaction. A database
+    /// written by a newer build is refused rather than modified.
+    ///
+    /// Databases from before versioning have the baseline tables but no
+    /// `schema_version`; the baseline only creates what is missing, so they
+    /// are adopted as version 1 with their data intact.
+    fn migrate(&mut self) -> SqliteResult<()> {
+        self.conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS schema_version (
+                version INTEGER PRIMARY KEY,
                 description TEXT NOT NULL,
-                status TEXT NOT NULL DEFAULT 'pending',  -- pending, active, done, failed, skipped
-                completed_at TEXT,
-                FOREIGN KEY (task_id) REFERENCES tasks(id)
-            );
-            CREATE INDEX IF NOT EXISTS idx_substeps_task ON substeps(task_id, step_order);
-
-            -- Full-text search on failures for quick pattern matching
-            CREATE VIRTUAL TABLE IF NOT EXISTS failures_fts USING fts5(
-                context, action_tried, what_happened, lesson,
-                content='failures',
-                content_rowid='id'
-            );
-
-            CREATE TRIGGER IF NOT EXISTS failures_ai AFTER INSERT ON failures BEGIN
-                INSERT INTO failures_fts(rowid, context, action_tried, what_happened, lesson)
-                VALUES (NEW.id, NEW.context, NEW.action_tried, NEW.what_happened, NEW.lesson);
-            END;
-        "#)?;
+                applied_at TEXT NOT NULL
+            );",
+        )?;
+
+        let current = self.current_version()?;
+        if current > SCHEMA_VERSION {
+            return Err(rusqlite::Error::SqliteFailure(
+                rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_CANTOPEN),
+                Some(format!(
+                    "VLA task database is at schema version {}, but this build only supports up to {}; \
+                     upgrade ganesha to open it",
+                    current, SCHEMA_VERSION
+                )),
+            ));
+        }
+
+        for migration in MIGRATIONS.iter().filter(|m| m.version > current) {
+            // Dropping the transaction on error rolls the migration back
+            let tx = self.conn.transaction()?;
+            tx.execute_batch(migration.sql)?;
+            tx.execute(
+                "INSERT INTO schema_version (version, description, applied_at) VALUES (?1, ?2, ?3)",
+                params![migration.version, migration.description, Utc::now().to_rfc3339()],
+            )?;
+            tx.commit()?;
+        }
         Ok(())
     }
 
@@ -574,4 +640,48 @@ mod tests {
         assert!(ctx.contains("SCREEN DID NOT CHANGE"));
         assert!(ctx.contains("KNOWN PITFALLS"));
     }
+
+    #[test]
+    fn test_migrates_pre_versioning_db_without_data_loss() {
+        // A database written before schema_version existed
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(BASELINE_SCHEMA).unwrap();
+        conn.execute(
+            "INSERT INTO tasks (id, goal, criteria, status, started_at) VALUES ('t1', 'Open Firefox', '[]', 'success', '2025-01-01T00:00:00Z')",
+            [],
+        ).unwrap();
+        conn.execute(
+            "INSERT INTO failures (context, action_tried, what_happened, lesson) VALUES ('Firefox browser', 'key_press Super', 'Opened Activities', 'Use Ctrl+L')",
+            [],
+        ).unwrap();
+
+        let db = VlaTaskDb::from_connection(conn).unwrap();
+        assert_eq!(db.current_version().unwrap(), SCHEMA_VERSION);
+
+        let stats = db.task_stats().unwrap();
+        assert_eq!(stats.total_tasks, 1);
+        assert_eq!(stats.successful_tasks, 1);
+        assert_eq!(stats.known_failures, 1);
+        assert_eq!(db.get_relevant_failures("Firefox", 5).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_refuses_newer_schema() {
+        let db = VlaTaskDb::open_memory().unwrap();
+        assert_eq!(db.current_version().unwrap(), SCHEMA_VERSION);
+        db.conn.execute(
+            "INSERT INTO schema_version (version, description, applied_at) VALUES (?1, 'future', '2030-01-01T00:00:00Z')",
+            params![SCHEMA_VERSION + 1],
+        ).unwrap();
+
+        let err = VlaTaskDb::from_connection(db.conn).err().unwrap();
+        assert!(err.to_string().contains(&format!("only supports up to {}", SCHEMA_VERSION)));
+    }
+
+    #[test]
+    fn test_migrations_are_numbered_in_order() {
+        for (i, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.version, i as u32 + 1, "{}", migration.description);
+        }
+    }
 }
@@ -240,6 +240,9 @@ pub struct VisionConfig {
     pub cloud_vision_available: bool,
     pub cloud_vision_provider: Option<String>,
     pub cloud_vision_model: Option<String>,
+    /// Human-readable name of the vision provider, for status lines
+    #[serde(default)]
+    pub description: Option<String>,
 }
 
 
@@ -24,6 +24,60 @@ pub enum ZoneType {
     Learned,
 }
 
+impl ZoneType {
+    /// Fraction of sampled pixels that must change before `poll_for_motion`
+    /// reports the zone. `None` means never.
+    pub fn motion_sensitivity(&self) -> Option<f32> {
+        match self {
+            ZoneType::Ignore => None,
+            ZoneType::Focus => Some(0.0),   // Any visible change
+            ZoneType::Motion => Some(0.02),
+            ZoneType::Learned => Some(0.10), // Only large changes
+        }
+    }
+}
+
+/// How far a sampled pixel's brightness must move to count as changed
+const PIXEL_TOLERANCE: u8 = 16;
+
+/// Sampling stride, matching `hash_region`
+const SAMPLE_STEP: usize = 10;
+
+/// A raw RGBA frame to check for motion
+#[derive(Debug, Clone, Copy)]
+pub struct ScreenFrame<'a> {
+    pub pixels: &'a [u8],
+    pub width: u32,
+    pub height: u32,
+}
+
+impl<'a> ScreenFrame<'a> {
+    pub fn new(pixels: &'a [u8], width: u32, height: u32) -> Self {
+        Self { pixels, width, height }
+    }
+}
+
+/// A watched zone changed meaningfully
+#[derive(Debug, Clone)]
+pub struct ZoneEvent {
+    pub zone_id: String,
+    pub zone_type: ZoneType,
+    /// Fraction of sampled pixels that changed (0.0 - 1.0)
+    pub change: f32,
+    pub timestamp: Instant,
+}
+
+/// Per-zone state for `poll_for_motion`
+#[derive(Debug, Clone, Default)]
+struct MotionState {
+    /// Samples as of the last reported change
+    baseline: Vec<u8>,
+    /// Samples from the previous poll while a change is settling
+    candidate: Option<Vec<u8>>,
+    /// When the zone started differing from the baseline
+    changing_since: Option<Instant>,
+}
+
 /// A rectangular zone on screen
 #[derive(Debug, Clone)]
 pub struct Zone {
@@ -41,6 +95,8 @@ pub struct Zone {
     pub last_content: String,
     /// How many times we've seen this unchanged
     pub stable_count: u32,
+    /// Motion polling state
+    motion: MotionState,
 }
 
 impl Zone {
@@ -56,6 +112,7 @@ impl Zone {
             last_change: Instant::now(),
             last_content: String::new(),
             stable_count: 0,
+            motion: MotionState::default(),
         }
     }
 
@@ -82,6 +139,9 @@ pub struct ZoneManager {
     pub screen_height: u32,
     /// Default zones for common UI patterns
     pub presets: HashMap<String, Vec<Zone>>,
+    /// How long a zone may keep changing before `poll_for_motion` reports it
+    /// without waiting for it to settle
+    pub motion_debounce: Duration,
 }
 
 /// Zone Manager for NVR-style screen region filtering
@@ -96,6 +156,7 @@ impl ZoneManager {
             screen_width,
             screen_height,
             presets: HashMap::new(),
+            motion_debounce: Duration::from_millis(500),
         };
 
         // Define common presets
@@ -247,6 +308,66 @@ impl ZoneManager {
         )
     }
 
+    /// Check every watched zone against its last reported content and return
+    /// events for the ones that changed more than their `ZoneType` allows.
+    ///
+    /// The first poll only records a baseline. A change is reported once it
+    /// holds for two consecutive polls, or once the zone has kept changing
+    /// for `motion_debounce` (video, progress bars). Content that flips back,
+    /// like a blinking cursor, never settles and is not reported.
+    pub fn poll_for_motion(&mut self, frame: ScreenFrame<'_>) -> Vec<ZoneEvent> {
+        let now = Instant::now();
+        let mut events = Vec::new();
+
+        for zone in self.zones.values_mut() {
+            let Some(sensitivity) = zone.zone_type.motion_sensitivity() else {
+                continue;
+            };
+            let samples = sample_region(frame, zone.x, zone.y, zone.width, zone.height);
+
+            if zone.motion.baseline.is_empty() {
+                zone.last_hash = hash_region(frame.pixels, frame.width, zone.x, zone.y, zone.width, zone.height);
+                zone.motion.baseline = samples;
+                continue;
+            }
+
+            let change = changed_fraction(&zone.motion.baseline, &samples);
+            if change <= sensitivity {
+                zone.motion.candidate = None;
+                zone.motion.changing_since = None;
+                zone.stable_count += 1;
+                continue;
+            }
+
+            let settled = zone
+                .motion
+                .candidate
+                .as_ref()
+                .is_some_and(|previous| changed_fraction(previous, &samples) <= sensitivity);
+            let changing_since = *zone.motion.changing_since.get_or_insert(now);
+
+            if settled || now.duration_since(changing_since) >= self.motion_debounce {
+                zone.last_hash = hash_region(frame.pixels, frame.width, zone.x, zone.y, zone.width, zone.height);
+                zone.last_change = now;
+                zone.stable_count = 0;
+                zone.motion = MotionState {
+                    baseline: samples,
+                    ..MotionState::default()
+                };
+                events.push(ZoneEvent {
+                    zone_id: zone.id.clone(),
+                    zone_type: zone.zone_type,
+                    change,
+                    timestamp: now,
+                });
+            } else {
+                zone.motion.candidate = Some(samples);
+            }
+        }
+
+        events
+    }
+
     /// Learn to ignore a zone that hasn't changed in a while
     pub fn learn_static_zones(&mut self, stable_threshold: Duration) {
         for zone in self.zones.values_mut() {
@@ -278,6 +399,42 @@ pub fn hash_region(pixels: &[u8], width: u32, x: u32, y: u32, w: u32, h: u32) ->
     hasher.finish()
 }
 
+/// Brightness of every sampled pixel in a region, on the `hash_region` grid
+fn sample_region(frame: ScreenFrame<'_>, x: u32, y: u32, w: u32, h: u32) -> Vec<u8> {
+    let width = frame.width as usize;
+    let rows = y as usize..y.saturating_add(h).min(frame.height) as usize;
+    let cols = x as usize..x.saturating_add(w).min(frame.width) as usize;
+    let mut samples = Vec::new();
+
+    for row in rows.step_by(SAMPLE_STEP) {
+        for col in cols.clone().step_by(SAMPLE_STEP) {
+            let idx = (row * width + col) * 4; // RGBA
+            if let Some(rgb) = frame.pixels.get(idx..idx + 3) {
+                let luma = (rgb[0] as u32 * 299 + rgb[1] as u32 * 587 + rgb[2] as u32 * 114) / 1000;
+                samples.push(luma as u8);
+            }
+        }
+    }
+
+    samples
+}
+
+/// Fraction of samples whose brightness moved more than `PIXEL_TOLERANCE`
+fn changed_fraction(before: &[u8], after: &[u8]) -> f32 {
+    if before.len() != after.len() {
+        return 1.0;
+    }
+    if after.is_empty() {
+        return 0.0;
+    }
+    let changed = before
+        .iter()
+        .zip(after)
+        .filter(|(a, b)| a.abs_diff(**b) > PIXEL_TOLERANCE)
+        .count();
+    changed as f32 / after.len() as f32
+}
+
 /// Detect which zones have motion (changed since last check)
 pub fn detect_motion(
     manager: &mut ZoneManager,
@@ -329,6 +486,63 @@ mod tests {
         assert!(active.iter().any(|z| z.id == "page_content"));
     }
 
+    /// Paint a solid square into an RGBA buffer
+    fn paint(pixels: &mut [u8], width: u32, x: u32, y: u32, size: u32, value: u8) {
+        for row in y..y + size {
+            for col in x..x + size {
+                let idx = ((row * width + col) * 4) as usize;
+                pixels[idx..idx + 3].fill(value);
+            }
+        }
+    }
+
+    #[test]
+    fn test_poll_for_motion_reports_settled_changes_only() {
+        let (width, height) = (200, 100);
+        let mut manager = ZoneManager::new(width, height);
+        manager.motion_debounce = Duration::from_secs(60);
+        manager.zones.clear();
+        for zone in [
+            Zone::new("editor", ZoneType::Motion, 0, 0, 100, 100),
+            Zone::new("sidebar", ZoneType::Learned, 100, 0, 100, 100),
+        ] {
+            manager.zones.insert(zone.id.clone(), zone);
+        }
+
+        let mut pixels = vec![0u8; (width * height * 4) as usize];
+        assert!(manager.poll_for_motion(ScreenFrame::new(&pixels, width, height)).is_empty());
+
+        // A blinking cursor never settles
+        for blink in 0..6 {
+            paint(&mut pixels, width, 40, 40, 12, if blink % 2 == 0 { 255 } else { 0 });
+            assert!(manager.poll_for_motion(ScreenFrame::new(&pixels, width, height)).is_empty());
+        }
+
+        // A dialog opening is reported once it holds for a second poll
+        paint(&mut pixels, width, 10, 10, 60, 200);
+        assert!(manager.poll_for_motion(ScreenFrame::new(&pixels, width, height)).is_empty());
+        let events = manager.poll_for_motion(ScreenFrame::new(&pixels, width, height));
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].zone_id, "editor");
+        assert!(events[0].change > 0.3);
+        assert!(manager.poll_for_motion(ScreenFrame::new(&pixels, width, height)).is_empty());
+
+        // The same small change is below the learned zone's sensitivity
+        paint(&mut pixels, width, 140, 40, 20, 255);
+        manager.poll_for_motion(ScreenFrame::new(&pixels, width, height));
+        assert!(manager.poll_for_motion(ScreenFrame::new(&pixels, width, height)).is_empty());
+    }
+
+    #[test]
+    fn test_sample_region_indexes_past_u32() {
+        // 70000 * 70000 * 4 bytes is past u32::MAX; a truncated buffer must
+        // give no samples rather than overflow or wrap to the wrong pixels
+        let pixels = vec![255u8; 4 * 1000];
+        let frame = ScreenFrame::new(&pixels, 70_000, 70_000);
+        assert!(sample_region(frame, 60_000, 60_000, 100, 100).is_empty());
+        assert_eq!(sample_region(frame, 0, 0, 100, 1), vec![255; 10]);
+    }
+
     #[test]
     fn test_auto_detect() {
         let mut manager = ZoneManager::new(1920, 1080);